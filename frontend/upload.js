@@ -13,16 +13,93 @@ let lastEditedRecordId = null;
 let lastEditedFileIdentifier = null;
 
 let progressSocket = null;
+let heartbeatTimer = null;
 const selectedRecords = new Set();
+const HEARTBEAT_INTERVAL_MS = 30000;
+const WS_PROTOCOL_VERSION = 1;
+
+// Stable per-browser ID so the server can tell which client started a task
+function getClientId() {
+    let clientId = localStorage.getItem('recordroute_client_id');
+    if (!clientId) {
+        clientId = (crypto.randomUUID && crypto.randomUUID()) || `client-${Date.now()}-${Math.random().toString(16).slice(2)}`;
+        localStorage.setItem('recordroute_client_id', clientId);
+    }
+    return clientId;
+}
+
+function sendHeartbeat(type = 'heartbeat') {
+    if (progressSocket && progressSocket.readyState === WebSocket.OPEN) {
+        const message = { type, client_id: getClientId() };
+        if (type === 'hello') message.protocol_version = WS_PROTOCOL_VERSION;
+        progressSocket.send(JSON.stringify(message));
+    }
+}
+
+// With AUTH_ENABLED the server answers 401 until the browser holds the auth cookie:
+// ask for the API key once, exchange it at /auth/login and retry the request
+const baseFetch = window.fetch.bind(window);
+let pendingLogin = null;
+
+function requestLogin() {
+    if (!pendingLogin) {
+        pendingLogin = (async () => {
+            const key = window.prompt('RecordRoute API 키를 입력하세요.');
+            if (!key) return false;
+            const response = await baseFetch('/auth/login', {
+                method: 'POST',
+                headers: { 'Content-Type': 'application/json' },
+                body: JSON.stringify({ token: key.trim() })
+            });
+            if (!response.ok) {
+                alert('API 키가 올바르지 않습니다.');
+                return false;
+            }
+            initWebSocket();
+            return true;
+        })().finally(() => { pendingLogin = null; });
+    }
+    return pendingLogin;
+}
+
+window.fetch = async (resource, options) => {
+    const response = await baseFetch(resource, options);
+    const url = typeof resource === 'string' ? resource : resource.url;
+    if (response.status !== 401 || url.startsWith('/auth/')) return response;
+    return (await requestLogin()) ? baseFetch(resource, options) : response;
+};
 
 function initWebSocket() {
-    progressSocket = new WebSocket('ws://localhost:8765');
+    if (progressSocket && progressSocket.readyState <= WebSocket.OPEN) progressSocket.close();
+    const socket = new WebSocket('ws://localhost:8765');
+    progressSocket = socket;
+    progressSocket.onopen = () => {
+        sendHeartbeat('hello');
+        clearInterval(heartbeatTimer);
+        heartbeatTimer = setInterval(sendHeartbeat, HEARTBEAT_INTERVAL_MS);
+    };
+    progressSocket.onclose = () => {
+        if (progressSocket === socket) clearInterval(heartbeatTimer);
+    };
     progressSocket.onmessage = (event) => {
         try {
             const data = JSON.parse(event.data);
+            if (data.type === 'error') {
+                console.error(`WebSocket protocol error (${data.code}): ${data.message}`);
+                return;
+            }
+            if (data.type === 'watch_matched') {
+                showWatchNotification(data);
+                return;
+            }
+            if (data.type === 'notification') {
+                showDesktopNotification(data.title, data.text);
+                return;
+            }
+            if (data.type !== 'task_progress') return;
             const tasks = [currentTask, ...taskQueue];
             const task = tasks.find(t => t && t.taskId === data.task_id);
-            if (task) {
+            if (task && data.message) {
                 task.progress = data.message;
                 updateQueueDisplay();
             }
@@ -32,6 +109,18 @@ function initWebSocket() {
     };
 }
 
+function showWatchNotification(data) {
+    showDesktopNotification('RecordRoute', `감시 규칙 '${data.rule_name}'과 일치하는 녹음: ${data.title || data.record_id}`);
+}
+
+function showDesktopNotification(title, text) {
+    if ('Notification' in window && Notification.permission === 'granted') {
+        new Notification(title, { body: text });
+    } else {
+        console.info(text);
+    }
+}
+
 function isAudioFile(file) {
     if (!file) return false;
     if (file.type && file.type.startsWith('audio/')) {
@@ -149,6 +238,17 @@ function normalizeKorean(text) {
     return text.normalize('NFC');
 }
 
+function formatFileSize(bytes) {
+    const units = ['B', 'KB', 'MB', 'GB'];
+    let size = bytes;
+    let unit = 0;
+    while (size >= 1024 && unit < units.length - 1) {
+        size /= 1024;
+        unit++;
+    }
+    return `${size.toFixed(unit === 0 ? 0 : 1)}${units[unit]}`;
+}
+
 function escapeHtml(text) {
     if (typeof text !== 'string') return '';
     return text
@@ -185,6 +285,7 @@ function showTemporaryStatus(message, variant = 'success', duration = 3000) {
 }
 const summaryPopup = document.getElementById('summaryPopup');
 const summaryOnlyBtn = document.getElementById('summaryOnlyBtn');
+const summaryStreamBtn = document.getElementById('summaryStreamBtn');
 const summaryCancelBtn = document.getElementById('summaryCancelBtn');
 const sttConfirmPopup = document.getElementById('sttConfirmPopup');
 const sttConfirmOkBtn = document.getElementById('sttConfirmOkBtn');
@@ -201,6 +302,7 @@ const modelSettingsStopBtn = document.getElementById('modelSettingsStopBtn');
 const overlayEdit = document.getElementById('overlayEdit');
 const overlaySave = document.getElementById('overlaySave');
 const overlayEditor = document.getElementById('overlayEditor');
+const overlayView = document.getElementById('overlayView');
 const sttEditResetPopup = document.getElementById('sttEditResetPopup');
 const sttEditResetConfirmBtn = document.getElementById('sttEditResetConfirmBtn');
 const sttEditResetCloseBtn = document.getElementById('sttEditResetCloseBtn');
@@ -284,7 +386,8 @@ function showTextOverlay(url, fileType = null, displayName = null) {
     currentOverlayFile = {
         url: url,
         type: resolvedType,
-        identifier: identifier
+        identifier: identifier,
+        view: 'clean'
     };
 
     lastEditedRecordId = null;
@@ -307,6 +410,11 @@ function showTextOverlay(url, fileType = null, displayName = null) {
         overlaySave.textContent = '저장';
     }
 
+    if (overlayView) {
+        overlayView.style.display = resolvedType === 'stt' && identifier ? 'inline-block' : 'none';
+        overlayView.textContent = '원문 보기';
+    }
+
     if (overlayEditor) {
         overlayEditor.value = '';
     }
@@ -316,8 +424,16 @@ function showTextOverlay(url, fileType = null, displayName = null) {
     download.href = url;
     download.setAttribute('download', displayName ? normalizeKorean(displayName) : '');
 
-    fetch(url)
-        .then(resp => resp.text())
+    loadOverlayContent(url);
+}
+
+function loadOverlayContent(url) {
+    const content = document.getElementById('overlayContent');
+    return fetch(url)
+        .then(resp => {
+            if (!resp.ok) throw new Error(`HTTP ${resp.status}`);
+            return resp.text();
+        })
         .then(text => {
             content.textContent = text;
             if (overlayEditor) {
@@ -503,9 +619,40 @@ function updateDeleteButtonState() {
     deleteBtn.textContent = count > 0 ? `삭제 (${count})` : '삭제';
 }
 
-// Progress updates are pushed via WebSocket; polling functions are no-ops
-function startProgressPolling(task) {}
-function stopProgressPolling() {}
+// Progress updates are pushed via WebSocket. When the socket is blocked
+// (e.g. by a corporate proxy), fall back to long-polling /tasks/{id}/wait.
+let progressPollToken = 0;
+
+function startProgressPolling(task) {
+    if (progressSocket && progressSocket.readyState === WebSocket.OPEN) return;
+    const token = ++progressPollToken;
+    let since = 0;
+
+    const poll = async () => {
+        while (token === progressPollToken) {
+            try {
+                const resp = await fetch(`/tasks/${encodeURIComponent(task.taskId)}/wait?since=${since}&timeout=25`);
+                if (!resp.ok) throw new Error(`HTTP ${resp.status}`);
+                const data = await resp.json();
+                if (token !== progressPollToken) return;
+                since = data.seq;
+                if (data.changed && data.message) {
+                    task.progress = data.message;
+                    updateQueueDisplay();
+                }
+                if (data.done) return;
+            } catch (e) {
+                console.error('Progress long-poll error:', e);
+                await new Promise(resolve => setTimeout(resolve, 2000));
+            }
+        }
+    };
+    poll();
+}
+
+function stopProgressPolling() {
+    progressPollToken++;
+}
 
 document.getElementById('overlayClose').addEventListener('click', () => {
     const overlay = document.getElementById('textOverlay');
@@ -570,6 +717,32 @@ if (overlayEdit) {
     });
 }
 
+// Toggle between the cleaned transcript and the verbatim one (fillers and filtered segments kept)
+if (overlayView) {
+    overlayView.addEventListener('click', async () => {
+        if (!currentOverlayFile || currentOverlayFile.type !== 'stt' || overlayEditing) {
+            return;
+        }
+        const nextView = currentOverlayFile.view === 'verbatim' ? 'clean' : 'verbatim';
+        const viewUrl = `/download/${encodeURIComponent(currentOverlayFile.identifier)}?view=${nextView}`;
+        if (nextView === 'verbatim') {
+            const resp = await fetch(viewUrl);
+            if (!resp.ok) {
+                showTemporaryStatus('이 전사에는 원문 보기가 없습니다. 다시 변환하면 생성됩니다.', 'error', 4000);
+                return;
+            }
+            document.getElementById('overlayContent').textContent = await resp.text();
+        } else {
+            await loadOverlayContent(viewUrl);
+        }
+        currentOverlayFile.view = nextView;
+        overlayView.textContent = nextView === 'verbatim' ? '정리본 보기' : '원문 보기';
+        // 원문 보기는 읽기 전용 (수정은 정리본에만 적용)
+        if (overlayEdit) overlayEdit.style.display = nextView === 'verbatim' ? 'none' : 'inline-block';
+        document.getElementById('overlayDownload').href = viewUrl;
+    });
+}
+
 if (overlaySave) {
     overlaySave.addEventListener('click', async () => {
         if (!overlayEditing || !overlayEditor) {
@@ -895,6 +1068,16 @@ async function loadAvailableModels() {
             // Update summarize model dropdown
             const summarizeSelect = document.getElementById('summarizeModel');
             summarizeSelect.innerHTML = '';
+
+            // SUMMARY_MODEL_BY_LANGUAGE가 있으면 빈 값(전사 언어별 자동 선택)을 기본으로 제공
+            const languageModels = Object.entries(data.summarize_by_language || {});
+            if (languageModels.length > 0) {
+                const option = document.createElement('option');
+                option.value = '';
+                option.textContent = '언어별 자동 (' + languageModels.map(([lang, model]) => `${lang}: ${model}`).join(', ') + ')';
+                option.selected = true;
+                summarizeSelect.appendChild(option);
+            }
             
             if (data.models && data.models.length > 0) {
                 data.models.forEach(model => {
@@ -903,7 +1086,7 @@ async function loadAvailableModels() {
                     option.textContent = model;
                     if (model === data.default.summarize) {
                         option.textContent += ' (기본값)';
-                        option.selected = true;
+                        option.selected = languageModels.length === 0;
                     }
                     summarizeSelect.appendChild(option);
                 });
@@ -911,7 +1094,7 @@ async function loadAvailableModels() {
                 const option = document.createElement('option');
                 option.value = data.default.summarize;
                 option.textContent = data.default.summarize + ' (기본값)';
-                option.selected = true;
+                option.selected = languageModels.length === 0;
                 summarizeSelect.appendChild(option);
             }
             
@@ -1201,6 +1384,79 @@ function showSummaryPopup(record, span) {
         setQueuedState(span);
         hideSummaryPopup();
     };
+    summaryStreamBtn.onclick = () => {
+        hideSummaryPopup();
+        streamSummary(record);
+    };
+}
+
+// 요약을 SSE(GET /record/{id}/summary/stream)로 받아 생성되는 대로 텍스트 창에 표시
+async function streamSummary(record) {
+    const overlay = document.getElementById('textOverlay');
+    const content = document.getElementById('overlayContent');
+    const download = document.getElementById('overlayDownload');
+
+    currentOverlayFile = null;
+    exitOverlayEditMode(false);
+    [overlayEdit, overlayView, overlaySave].forEach(button => {
+        if (button) button.style.display = 'none';
+    });
+    overlay.style.display = 'flex';
+    content.textContent = '요약 준비 중...';
+    download.removeAttribute('href');
+
+    // POST로 요약을 시작하고, 받은 task_id의 스트림에만 GET으로 연결
+    let started;
+    try {
+        const response = await fetch(`/record/${encodeURIComponent(record.id)}/summary/stream`, {
+            method: 'POST',
+            headers: { 'Content-Type': 'application/json' },
+            body: JSON.stringify({})
+        });
+        started = await response.json().catch(() => ({}));
+        if (!response.ok) throw new Error(started.error || `HTTP ${response.status}`);
+    } catch (error) {
+        content.textContent = `요약을 시작하지 못했습니다: ${error.message}`;
+        return;
+    }
+
+    let text = '';
+    const source = new EventSource(started.stream);
+    source.addEventListener('progress', event => {
+        if (!text) content.textContent = JSON.parse(event.data).message;
+    });
+    source.addEventListener('token', event => {
+        text += JSON.parse(event.data).text;
+        content.textContent = text;
+    });
+    source.addEventListener('reset', () => {
+        text = '';
+        content.textContent = '요약을 다시 생성하는 중...';
+    });
+    source.addEventListener('done', event => {
+        source.close();
+        const summaryUrl = (JSON.parse(event.data).results || {}).summary;
+        if (summaryUrl) {
+            currentOverlayFile = {
+                url: summaryUrl,
+                type: 'summary',
+                identifier: summaryUrl.split('/download/')[1] || null,
+                view: 'clean'
+            };
+            download.href = summaryUrl;
+            loadOverlayContent(summaryUrl);
+        }
+        loadHistory();
+    });
+    source.addEventListener('error', event => {
+        // 스트림은 한 번만 붙을 수 있어 EventSource의 자동 재연결은 막음
+        source.close();
+        if (event.data) {
+            content.textContent = `요약 생성 실패: ${JSON.parse(event.data).error}`;
+        } else if (!text) {
+            content.textContent = '요약 스트림에 연결하지 못했습니다.';
+        }
+    });
 }
 
 function sortTaskQueue() {
@@ -1940,9 +2196,16 @@ function displayHistory(history) {
         const item = document.createElement('div');
         item.className = 'history-item';
 
-        const typeLabel = record.file_type === 'audio' ? '오디오' : record.file_type === 'pdf' ? 'PDF' : '텍스트';
+        const typeLabel = record.is_video ? '영상' : record.file_type === 'audio' ? '오디오' : record.file_type === 'pdf' ? 'PDF' : '텍스트';
         const dateTime = formatDateTime(record.timestamp);
         const duration = record.duration ? ` ${record.duration}` : '';
+        const details = [
+            record.file_size ? formatFileSize(record.file_size) : '',
+            record.audio_format && record.audio_format.codec ? record.audio_format.codec : '',
+            record.language
+                ? record.language.toUpperCase() + (record.language_confidence != null ? ` ${Math.round(record.language_confidence * 100)}%` : '')
+                : ''
+        ].filter(Boolean).join(' · ');
 
         const header = document.createElement('div');
         header.className = 'history-header';
@@ -1951,7 +2214,7 @@ function displayHistory(history) {
         info.innerHTML = `
             <strong>[${typeLabel}]</strong>
             ${dateTime}
-            <strong id="filename-${record.id}" class="filename-display" title="클릭하여 파일명 수정">${normalizeKorean(record.filename)}</strong><span class="duration">${duration}</span>
+            <strong id="filename-${record.id}" class="filename-display" title="클릭하여 파일명 수정">${normalizeKorean(record.filename)}</strong><span class="duration">${duration}</span>${details ? ` <span class="record-details">${escapeHtml(details)}</span>` : ''}
         `;
 
         // Add click event to filename for editing
@@ -1980,6 +2243,18 @@ function displayHistory(history) {
         });
 
         selectionContainer.appendChild(checkbox);
+
+        // Poster thumbnail prepared in the background for video uploads
+        if (record.thumbnail) {
+            const thumbnail = document.createElement('img');
+            thumbnail.className = 'history-thumbnail';
+            thumbnail.src = `/record/${encodeURIComponent(record.id)}/thumbnail`;
+            thumbnail.alt = '';
+            thumbnail.loading = 'lazy';
+            thumbnail.onerror = () => thumbnail.remove();
+            selectionContainer.appendChild(thumbnail);
+        }
+
         selectionContainer.appendChild(info);
 
         const resetBtn = document.createElement('button');
@@ -2022,6 +2297,45 @@ function displayHistory(history) {
         buttonContainer.className = 'button-container';
 
         buttonContainer.appendChild(batchBtn);
+
+        // Video uploads with a transcript can be exported with an embedded subtitle track
+        if (record.is_video && record.completed_tasks.stt) {
+            const subtitleBtn = document.createElement('button');
+            subtitleBtn.textContent = '자막 영상';
+            subtitleBtn.className = 'batch-btn';
+            subtitleBtn.onclick = async () => {
+                subtitleBtn.disabled = true;
+                try {
+                    const resp = await fetch(`/record/${encodeURIComponent(record.id)}/subtitled_video`, { method: 'POST' });
+                    const data = await resp.json();
+                    if (resp.ok && data.success) {
+                        window.location.href = data.download;
+                    } else {
+                        alert(data.error || '자막 영상을 만들지 못했습니다.');
+                    }
+                } catch (error) {
+                    alert('자막 영상을 만들지 못했습니다.');
+                } finally {
+                    subtitleBtn.disabled = false;
+                }
+            };
+            buttonContainer.appendChild(subtitleBtn);
+        }
+
+        // Caption files with millisecond timestamps for any transcribed record
+        if (record.completed_tasks.stt) {
+            ['srt', 'vtt'].forEach(format => {
+                const captionBtn = document.createElement('button');
+                captionBtn.textContent = format.toUpperCase();
+                captionBtn.className = 'batch-btn';
+                captionBtn.title = `자막 파일(${format.toUpperCase()}) 내려받기`;
+                captionBtn.onclick = () => {
+                    window.location.href = `/export/${encodeURIComponent(record.id)}?format=${format}`;
+                };
+                buttonContainer.appendChild(captionBtn);
+            });
+        }
+
         buttonContainer.appendChild(resetBtn);
 
         header.appendChild(selectionContainer);
@@ -2173,9 +2487,10 @@ async function processNextTask() {
         taskElement.style.cursor = 'default';
         taskElement.onclick = null;
         
-        // Initialize progress message; updates will come via WebSocket
+        // Initialize progress message; updates will come via WebSocket (or long-polling)
         currentTask.progress = '작업 준비 중...';
         updateQueueDisplay();
+        startProgressPolling(currentTask);
 
         // Create AbortController for this task
         currentTask.abortController = new AbortController();
@@ -2191,6 +2506,7 @@ async function processNextTask() {
                 steps: [currentTask.task],
                 record_id: currentTask.recordId,
                 task_id: currentTask.taskId,  // Send task_id to server
+                client_id: getClientId(),
                 model_settings: savedSettings  // Send model settings to server
             }),
             signal: currentTask.abortController.signal
@@ -2247,10 +2563,10 @@ async function processNextTask() {
                     // No need to re-add to queue
                 } else {
                     // Show error state
-                    taskElement.textContent = '오류';
+                    taskElement.textContent = result.retryable ? '오류 (재시도 가능)' : '오류';
                     taskElement.style.backgroundColor = '#dc3545';
                     taskElement.style.color = 'white';
-                    taskElement.title = `오류: ${result.error}`;
+                    taskElement.title = result.hint ? `오류: ${result.error}\n${result.hint}` : `오류: ${result.error}`;
                 }
             } else if (result[currentTask.task]) {
                 // Show success state with download link
@@ -2448,6 +2764,67 @@ document.getElementById('uploadBtn').addEventListener('click', async () => {
 });
 
 // Check for running tasks on page load
+const ORPHAN_STATUS_LABELS = {
+    interrupted: '서버 종료로 중단됨',
+    detached: '연결이 끊긴 채 실행 중',
+    finished_unattended: '연결이 끊긴 동안 완료됨'
+};
+
+// Show tasks left behind by a previous session (killed server or closed client)
+async function checkOrphanedTasks() {
+    try {
+        const response = await fetch('/tasks/orphaned');
+        if (!response.ok) return;
+        const data = await response.json();
+        if (!data.tasks || data.tasks.length === 0) return;
+
+        const items = data.tasks.map(task => {
+            const label = ORPHAN_STATUS_LABELS[task.status] || task.status;
+            const steps = (task.steps || []).join(', ');
+            return `<li>${label}: ${steps || '작업'} (시작: ${new Date(task.started_at).toLocaleString()})</li>`;
+        }).join('');
+
+        const box = document.createElement('div');
+        box.className = 'warning-box';
+        box.innerHTML = `
+            <strong>⚠️ 이전 세션에서 정상적으로 끝나지 않은 작업이 있습니다.</strong>
+            <ul style="margin: 5px 0;">${items}</ul>
+            중단된 작업은 기록에서 다시 실행하세요.
+            <button type="button" class="orphan-dismiss-btn">확인</button>
+        `;
+        box.querySelector('.orphan-dismiss-btn').addEventListener('click', async () => {
+            const dismissable = data.tasks.filter(task => task.status !== 'detached');
+            await Promise.all(dismissable.map(task =>
+                fetch(`/tasks/orphaned/${encodeURIComponent(task.task_id)}/dismiss`, { method: 'POST' })
+            ));
+            box.remove();
+        });
+        document.getElementById('status').appendChild(box);
+    } catch (error) {
+        console.error('Error checking orphaned tasks:', error);
+    }
+}
+
+async function checkMaintenanceMode() {
+    try {
+        const response = await fetch('/admin/maintenance');
+        if (!response.ok) return;
+        const data = await response.json();
+        if (!data.enabled) return;
+
+        const box = document.createElement('div');
+        box.className = 'warning-box';
+        const title = document.createElement('strong');
+        title.textContent = '🛠️ 서버 점검 중';
+        const message = document.createElement('div');
+        message.textContent = data.message;
+        box.append(title, message);
+        document.getElementById('status').appendChild(box);
+    } catch (error) {
+        console.error('Error checking maintenance mode:', error);
+    }
+}
+
 async function checkRunningTasks() {
     try {
         const response = await fetch('/tasks');
@@ -2705,7 +3082,8 @@ document.getElementById('settingsBtn').addEventListener('click', showModelSettin
 document.addEventListener('DOMContentLoaded', function() {
     initTheme();
     loadHistory();
-    checkRunningTasks();
+    // checkRunningTasks replaces the status area, so append orphan notices after it
+    checkRunningTasks().then(checkOrphanedTasks).then(checkMaintenanceMode);
     startTaskMonitoring();
     initWebSocket();
     initializeDropZone();
@@ -38,6 +38,13 @@ if not exist "%VENV_PYTHON%" (
     exit /b 1
 )
 
+REM 환경 점검만 실행: run.bat doctor [--json] [--skip-ports]
+if /i "%~1"=="doctor" (
+    cd /d "%SCRIPT_DIR%"
+    "%VENV_PYTHON%" -m sttEngine.doctor %2 %3
+    exit /b !errorlevel!
+)
+
 REM 웹서버 스크립트 존재 확인
 if not exist "%WEB_SERVER%" (
     echo 오류: 웹서버 스크립트^(server.py^)를 찾을 수 없습니다.
@@ -214,7 +221,7 @@ echo ^(웹브라우저에서 http://localhost:8080 에 접속하세요^)
 echo.
 
 cd /d "%SCRIPT_DIR%"
-"%VENV_PYTHON%" -m sttEngine.server
+"%VENV_PYTHON%" -m sttEngine.server %*
 set EXIT_CODE=!ERRORLEVEL!
 
 echo.
@@ -25,6 +25,13 @@ if [ ! -f "$VENV_PYTHON" ]; then
     exit 1
 fi
 
+# 환경 점검만 실행: ./run.sh doctor [--json] [--skip-ports]
+if [ "$1" = "doctor" ]; then
+    cd "$SCRIPT_DIR"
+    "$VENV_PYTHON" -m sttEngine.doctor "${@:2}"
+    exit $?
+fi
+
 # 웹서버 스크립트 존재 확인
 if [ ! -f "$WEB_SERVER" ]; then
     echo "오류: 웹서버 스크립트(server.py)를 찾을 수 없습니다."
@@ -177,7 +184,7 @@ echo "(웹브라우저에서 http://localhost:8080 에 접속하세요)"
 echo
 
 cd "$SCRIPT_DIR"
-"$VENV_PYTHON" -m sttEngine.server
+"$VENV_PYTHON" -m sttEngine.server "$@"
 EXIT_CODE=$?
 
 echo
@@ -0,0 +1,187 @@
+"""Follow-up tracker for the action items of summaries.
+
+Every summary lists action items in its ``action_items`` section. After a
+summary is written they are copied into ``DB/action_items.json`` as
+trackable items::
+
+    {"id": "...", "record_id": "...", "text": "견적서 재발송", "assignee": "김민수",
+     "due": "2026-10-20", "status": "open", "carried_over": false, "in_summary": true,
+     "history": [{"at": "...", "status": "assigned", "assignee": "김민수"}]}
+
+``status`` is ``open``, ``assigned``, ``done`` or ``dismissed``; setting an
+assignee on an open item makes it ``assigned``. The assignee and due date
+are read from the item text when the summary states them
+("담당: 김민수", "@김민수", "Owner: Kim", "기한: 10/20").
+
+Summarizing a record again re-syncs its items by normalized text: items
+that are still listed keep their status and assignee, open items that
+disappeared are dropped, and items someone already worked on stay with
+``in_summary: false``. Items a series summary marks "(이월)" are flagged
+``carried_over``.
+"""
+
+from __future__ import annotations
+
+import json
+import re
+import threading
+import uuid
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Dict, Iterable, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_db_base_path
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_db_base_path  # type: ignore
+
+ACTION_ITEMS_FILE = get_db_base_path() / "action_items.json"
+STATUSES = ("open", "assigned", "done", "dismissed")
+# 목록 조회의 status=open은 아직 끝나지 않은 항목 전체
+OPEN_STATUSES = ("open", "assigned")
+# 요약에서 "없음"처럼 항목이 없다는 뜻으로 쓰는 줄
+_EMPTY_ITEMS = {"없음", "해당 없음", "none", "n/a", "なし", "无"}
+_CARRIED_OVER = re.compile(r"^\s*\((?:이월|carried over)\)\s*", re.IGNORECASE)
+_CHANGED = re.compile(r"^\s*\((?:변경|changed)\)\s*", re.IGNORECASE)
+_ASSIGNEE = re.compile(r"(?:담당자?|owner|assignee)\s*[:：]\s*([^,;/)\]]+)|@([\w.\-가-힣]+)", re.IGNORECASE)
+_DUE = re.compile(r"(?:기한|마감|due)\s*[:：]\s*([^,;)\]]+)", re.IGNORECASE)
+
+_items_lock = threading.Lock()
+
+
+class ActionItemError(ValueError):
+    """Raised for invalid updates."""
+
+
+class ActionItemNotFound(ActionItemError):
+    """Raised for unknown item ids."""
+
+
+def _load(path: Path = ACTION_ITEMS_FILE) -> Dict[str, Dict[str, Any]]:
+    try:
+        with open(path, "r", encoding="utf-8") as f:
+            items = json.load(f).get("items")
+        return items if isinstance(items, dict) else {}
+    except (OSError, ValueError, AttributeError):
+        return {}
+
+
+def _save(items: Dict[str, Dict[str, Any]], path: Path = ACTION_ITEMS_FILE) -> None:
+    path.parent.mkdir(parents=True, exist_ok=True)
+    tmp_path = path.with_name(f"{path.name}.tmp")
+    with open(tmp_path, "w", encoding="utf-8") as f:
+        json.dump({"items": items}, f, ensure_ascii=False, indent=2)
+    tmp_path.replace(path)
+
+
+def _key(text: str) -> str:
+    return re.sub(r"[\W_]+", " ", text).casefold().strip()
+
+
+def parse_item(line: str) -> Optional[Dict[str, Any]]:
+    """Item fields from one action item line of a summary (``None`` for "없음")."""
+    carried_over = bool(_CARRIED_OVER.match(line))
+    text = _CHANGED.sub("", _CARRIED_OVER.sub("", line)).strip()
+    if not text or text.strip(" .").casefold() in _EMPTY_ITEMS:
+        return None
+    assignee = _ASSIGNEE.search(text)
+    due = _DUE.search(text)
+    return {
+        "text": text,
+        "assignee": (assignee.group(1) or assignee.group(2)).strip() if assignee else None,
+        "due": due.group(1).strip() if due else None,
+        "carried_over": carried_over,
+    }
+
+
+def sync_record_items(record_id: str, lines: Iterable[str]) -> Dict[str, int]:
+    """Replace the record's items with the summary's action items, keeping tracked state.
+
+    Returns counts of ``added``, ``kept`` and ``removed`` items.
+    """
+    parsed = [item for item in map(parse_item, lines) if item]
+    now = datetime.now().isoformat()
+    counts = {"added": 0, "kept": 0, "removed": 0}
+    with _items_lock:
+        items = _load()
+        existing = {_key(item["text"]): item for item in items.values() if item["record_id"] == record_id}
+        listed = set()
+        for position, entry in enumerate(parsed):
+            key = _key(entry["text"])
+            if key in listed:
+                continue
+            listed.add(key)
+            item = existing.get(key)
+            if item is not None:
+                # 사람이 지정한 담당자/기한은 요약보다 우선
+                item.update(text=entry["text"], position=position, in_summary=True,
+                            carried_over=entry["carried_over"])
+                item["assignee"] = item.get("assignee") or entry["assignee"]
+                item["due"] = item.get("due") or entry["due"]
+                counts["kept"] += 1
+                continue
+            item_id = str(uuid.uuid4())
+            items[item_id] = {
+                "id": item_id, "record_id": record_id, "position": position, **entry,
+                "status": "assigned" if entry["assignee"] else "open", "in_summary": True,
+                "note": None, "created_at": now, "updated_at": now, "history": [],
+            }
+            counts["added"] += 1
+        for key, item in existing.items():
+            if key in listed:
+                continue
+            if item["status"] == "open":
+                del items[item["id"]]
+                counts["removed"] += 1
+            else:
+                item["in_summary"] = False
+        _save(items)
+    return counts
+
+
+def list_items(status: Optional[Iterable[str]] = None, assignee: Optional[str] = None,
+               record_ids: Optional[Iterable[str]] = None) -> List[Dict[str, Any]]:
+    """Items filtered by status (default: open and assigned), assignee (case-insensitive) and records."""
+    statuses = set(status or OPEN_STATUSES)
+    record_ids = set(record_ids) if record_ids is not None else None
+    wanted = assignee.casefold().strip() if assignee else None
+    result = [
+        item for item in _load().values()
+        if item["status"] in statuses
+        and (record_ids is None or item["record_id"] in record_ids)
+        and (wanted is None or (item.get("assignee") or "").casefold() == wanted)
+    ]
+    return sorted(result, key=lambda item: (item["created_at"], item.get("position", 0)))
+
+
+def update_item(item_id: str, payload: Dict[str, Any]) -> Dict[str, Any]:
+    """Change ``status``, ``assignee``, ``due`` or ``note`` of an item (change kept in ``history``)."""
+    changes: Dict[str, Any] = {}
+    if "status" in payload:
+        if payload["status"] not in STATUSES:
+            raise ActionItemError(f"status는 {', '.join(STATUSES)} 중 하나여야 합니다.")
+        changes["status"] = payload["status"]
+    for field in ("assignee", "due", "note"):
+        if field in payload:
+            value = payload[field]
+            if value is not None and not isinstance(value, str):
+                raise ActionItemError(f"{field}는 문자열이어야 합니다.")
+            changes[field] = (value or "").strip() or None
+    if not changes:
+        raise ActionItemError("변경할 항목(status, assignee, due, note)이 없습니다.")
+    with _items_lock:
+        items = _load()
+        item = items.get(item_id)
+        if item is None:
+            raise ActionItemNotFound(f"실행 항목을 찾을 수 없습니다: {item_id}")
+        if changes.get("assignee") and "status" not in changes and item["status"] == "open":
+            changes["status"] = "assigned"
+        elif "assignee" in changes and not changes["assignee"] and item["status"] == "assigned" \
+                and "status" not in changes:
+            changes["status"] = "open"
+        now = datetime.now().isoformat()
+        item.update(changes, updated_at=now)
+        item.setdefault("history", []).append({"at": now, **changes})
+        _save(items)
+    return item
+
@@ -0,0 +1,242 @@
+"""Filter-tree evaluation for the advanced search endpoint.
+
+A query is a JSON tree of boolean groups and leaf clauses, for example::
+
+    {
+        "and": [
+            {"tag": "회의"},
+            {"date": {"from": "2025-01-01", "to": "2025-01-31"}},
+            {"or": [
+                {"text": "예산"},
+                {"semantic": {"query": "budget planning", "min_score": 0.55}}
+            ]},
+            {"not": {"duration": {"max": 60}}}
+        ]
+    }
+
+Supported groups are ``and``/``or`` (lists) and ``not`` (single node).
+Supported leaves are ``tag``, ``date``, ``speaker``, ``duration``, ``text``
+and ``semantic``. ``speaker`` matches the record's speakers and the names
+assigned to its speaker labels, not the transcript text. Dates are compared as naive local time (like the stored
+record timestamps; a bound with a UTC offset is converted), and a date-only
+``to`` includes that whole day. The tree is validated up front so malformed queries are
+rejected before any transcript is read or embedded.
+"""
+
+from __future__ import annotations
+
+import re
+from datetime import datetime, timedelta
+from typing import Any, Callable, Dict, List, Optional
+
+GROUP_KEYS = ("and", "or", "not")
+LEAF_KEYS = ("tag", "date", "speaker", "duration", "text", "semantic")
+TEXT_SOURCES = ("stt", "summary", "any")
+DEFAULT_SEMANTIC_MIN_SCORE = 0.5
+MAX_FILTER_DEPTH = 8
+_DATE_ONLY = re.compile(r"^\d{4}-?\d{2}-?\d{2}$")
+
+
+class FilterError(ValueError):
+    """Raised when a filter tree is malformed."""
+
+
+def _local_naive(moment: datetime) -> datetime:
+    """``moment`` as naive local time (offset-aware values are converted)."""
+    return moment.astimezone().replace(tzinfo=None) if moment.tzinfo else moment
+
+
+def _parse_date(value: Any, field: str) -> datetime:
+    try:
+        return _local_naive(datetime.fromisoformat(str(value).strip()))
+    except ValueError as exc:
+        raise FilterError(f"{field}: ISO 형식의 날짜가 아닙니다 ({value})") from exc
+
+
+def _date_before_end(moment: datetime, bound: Any) -> bool:
+    """Whether ``moment`` is within ``date.to`` (a date-only bound covers the whole day)."""
+    end = _parse_date(bound, "date.to")
+    if _DATE_ONLY.match(str(bound).strip()):
+        return moment < end + timedelta(days=1)
+    return moment <= end
+
+
+def parse_duration_seconds(value: Any) -> Optional[float]:
+    """Convert stored durations ("MM:SS", "HH:MM:SS" or seconds) to seconds."""
+    if value is None or value == "":
+        return None
+    if isinstance(value, (int, float)):
+        return float(value)
+    try:
+        parts = [float(part) for part in str(value).split(":")]
+    except ValueError:
+        return None
+    seconds = 0.0
+    for part in parts:
+        seconds = seconds * 60 + part
+    return seconds
+
+
+def validate_filter(node: Any, depth: int = 0) -> None:
+    """Validate a filter tree, raising :class:`FilterError` on problems."""
+    if depth > MAX_FILTER_DEPTH:
+        raise FilterError(f"필터 중첩은 최대 {MAX_FILTER_DEPTH}단계까지 허용됩니다.")
+    if not isinstance(node, dict) or len(node) != 1:
+        raise FilterError("각 필터 노드는 키가 하나인 객체여야 합니다.")
+
+    key, value = next(iter(node.items()))
+
+    if key in ("and", "or"):
+        if not isinstance(value, list) or not value:
+            raise FilterError(f"'{key}' 값은 비어 있지 않은 배열이어야 합니다.")
+        for child in value:
+            validate_filter(child, depth + 1)
+    elif key == "not":
+        validate_filter(value, depth + 1)
+    elif key in ("tag", "speaker"):
+        if not isinstance(value, str) or not value.strip():
+            raise FilterError(f"'{key}' 값은 비어 있지 않은 문자열이어야 합니다.")
+    elif key == "date":
+        if not isinstance(value, dict) or not ({"from", "to"} & value.keys()):
+            raise FilterError("'date' 값은 from/to 중 하나 이상을 포함해야 합니다.")
+        for bound in ("from", "to"):
+            if value.get(bound) is not None:
+                _parse_date(value[bound], f"date.{bound}")
+    elif key == "duration":
+        if not isinstance(value, dict) or not ({"min", "max"} & value.keys()):
+            raise FilterError("'duration' 값은 min/max(초) 중 하나 이상을 포함해야 합니다.")
+        for bound in ("min", "max"):
+            if value.get(bound) is not None and not isinstance(value[bound], (int, float)):
+                raise FilterError(f"duration.{bound} 값은 숫자(초)여야 합니다.")
+    elif key == "text":
+        query = value.get("query") if isinstance(value, dict) else value
+        if not isinstance(query, str) or not query.strip():
+            raise FilterError("'text' 값은 검색어 문자열이어야 합니다.")
+        if isinstance(value, dict) and value.get("in", "any") not in TEXT_SOURCES:
+            raise FilterError(f"text.in 값은 {', '.join(TEXT_SOURCES)} 중 하나여야 합니다.")
+    elif key == "semantic":
+        query = value.get("query") if isinstance(value, dict) else value
+        if not isinstance(query, str) or not query.strip():
+            raise FilterError("'semantic' 값은 질의 문자열이어야 합니다.")
+        if isinstance(value, dict) and value.get("min_score") is not None:
+            if not isinstance(value["min_score"], (int, float)):
+                raise FilterError("semantic.min_score 값은 숫자여야 합니다.")
+    else:
+        raise FilterError(f"알 수 없는 필터 키: {key}")
+
+
+def collect_semantic_queries(node: Dict[str, Any]) -> List[str]:
+    """Return every semantic query string used in the tree."""
+    key, value = next(iter(node.items()))
+    if key in ("and", "or"):
+        queries: List[str] = []
+        for child in value:
+            queries.extend(collect_semantic_queries(child))
+        return queries
+    if key == "not":
+        return collect_semantic_queries(value)
+    if key == "semantic":
+        return [value.get("query") if isinstance(value, dict) else value]
+    return []
+
+
+class FilterContext:
+    """Lazily provides the data leaf clauses need for one record.
+
+    Args:
+        load_text: ``(record, source) -> str`` returning STT or summary text.
+        semantic_scores: mapping of semantic query → {record_id: score}.
+    """
+
+    def __init__(self,
+                 load_text: Callable[[Dict[str, Any], str], str],
+                 semantic_scores: Optional[Dict[str, Dict[str, float]]] = None):
+        self._load_text = load_text
+        self._text_cache: Dict[tuple, str] = {}
+        self.semantic_scores = semantic_scores or {}
+
+    def text(self, record: Dict[str, Any], source: str) -> str:
+        cache_key = (record.get("id"), source)
+        if cache_key not in self._text_cache:
+            try:
+                self._text_cache[cache_key] = self._load_text(record, source) or ""
+            except Exception:
+                self._text_cache[cache_key] = ""
+        return self._text_cache[cache_key]
+
+
+def evaluate_filter(node: Dict[str, Any], record: Dict[str, Any],
+                    context: FilterContext, matches: Optional[Dict[str, Any]] = None) -> bool:
+    """Evaluate a validated filter tree against a history record.
+
+    ``matches`` collects per-clause evidence (e.g. semantic scores) for the
+    response payload.
+    """
+    key, value = next(iter(node.items()))
+
+    if key == "and":
+        return all(evaluate_filter(child, record, context, matches) for child in value)
+    if key == "or":
+        # 모든 하위 조건을 평가해 일치 근거를 빠짐없이 수집
+        results = [evaluate_filter(child, record, context, matches) for child in value]
+        return any(results)
+    if key == "not":
+        return not evaluate_filter(value, record, context, None)
+
+    if key == "tag":
+        wanted = value.strip().lower()
+        return any(wanted == str(tag).lower() for tag in record.get("tags") or [])
+
+    if key == "speaker":
+        # 기록의 화자 목록과 화자 라벨에 지정한 이름만 비교 (전사 본문은 보지 않음)
+        wanted = value.strip().lower()
+        names = list(record.get("speakers") or []) + list((record.get("speaker_names") or {}).values())
+        return any(wanted in str(name).lower() for name in names)
+
+    if key == "date":
+        timestamp = record.get("timestamp")
+        if not timestamp:
+            return False
+        try:
+            record_time = _local_naive(datetime.fromisoformat(timestamp))
+        except (TypeError, ValueError):
+            return False
+        if value.get("from") and record_time < _parse_date(value["from"], "date.from"):
+            return False
+        if value.get("to") and not _date_before_end(record_time, value["to"]):
+            return False
+        return True
+
+    if key == "duration":
+        seconds = parse_duration_seconds(record.get("duration"))
+        if seconds is None:
+            return False
+        if value.get("min") is not None and seconds < value["min"]:
+            return False
+        if value.get("max") is not None and seconds > value["max"]:
+            return False
+        return True
+
+    if key == "text":
+        query = value.get("query") if isinstance(value, dict) else value
+        source = value.get("in", "any") if isinstance(value, dict) else "any"
+        sources = ("stt", "summary") if source == "any" else (source,)
+        needle = query.strip().lower()
+        found = any(needle in context.text(record, src).lower() for src in sources)
+        if found and matches is not None:
+            matches.setdefault("text", []).append(query)
+        return found
+
+    if key == "semantic":
+        query = value.get("query") if isinstance(value, dict) else value
+        min_score = DEFAULT_SEMANTIC_MIN_SCORE
+        if isinstance(value, dict) and value.get("min_score") is not None:
+            min_score = float(value["min_score"])
+        score = context.semantic_scores.get(query, {}).get(record.get("id"))
+        if score is None or score < min_score:
+            return False
+        if matches is not None:
+            matches.setdefault("semantic", {})[query] = score
+        return True
+
+    raise FilterError(f"알 수 없는 필터 키: {key}")
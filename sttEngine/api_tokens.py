@@ -0,0 +1,236 @@
+"""Scoped API tokens for automation tools (Zapier, n8n, scripts).
+
+Tokens are created through ``/admin/tokens`` and shown once; only their
+SHA-256 hash is stored in ``DB/api_tokens.json``. Clients send them as
+``Authorization: Bearer rr_...`` (or ``X-API-Key``). Each token has one
+scope:
+
+* ``read``   — GET endpoints and read-only searches
+* ``upload`` — ``POST /upload``, ``/upload/raw``, ``/upload_url``, ``/email/inbound``, ``/process`` and task/progress polling
+* ``full``   — everything, including admin endpoints
+
+Routes are grouped by :func:`route_group`; :data:`SCOPE_GROUPS` says which
+groups a scope may call. A request without a token is the local web UI and
+keeps full access unless ``API_AUTH_REQUIRED`` is on, in which case only
+direct loopback requests (no proxy headers, i.e. not through the Cloudflare
+tunnel) may omit it.
+
+``AUTH_ENABLED`` goes further and requires a credential from every client,
+loopback included, for everything but the web UI's static files, the
+desktop shell's ``/startup_status`` probe and ``AUTH_EXEMPT_PATHS``.
+Besides the stored tokens it accepts ``AUTH_TOKEN``, a key set in the
+configuration with ``full`` scope. The browser UI asks for the key once and
+trades it for an HttpOnly cookie at ``POST /auth/login``, so downloads, audio
+and the WebSocket (port 8765 listens on every interface) are covered too.
+Every secret is compared in constant time.
+"""
+
+from __future__ import annotations
+
+import hashlib
+import json
+import re
+import secrets
+import threading
+import uuid
+from datetime import datetime, timedelta
+from http.cookies import CookieError, SimpleCookie
+from typing import Any, Dict, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value, get_db_base_path
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value, get_db_base_path  # type: ignore
+
+TOKENS_FILE = get_db_base_path() / "api_tokens.json"
+API_AUTH_REQUIRED = get_config_value("API_AUTH_REQUIRED", False, bool)
+AUTH_ENABLED = get_config_value("AUTH_ENABLED", False, bool)
+AUTH_TOKEN = get_config_value("AUTH_TOKEN", "", str).strip()
+# 쉼표로 구분한 경로 (끝이 /이면 그 아래 전체), GET/HEAD만 인증 없이 허용
+AUTH_EXEMPT_PATHS = get_config_value("AUTH_EXEMPT_PATHS", "", str)
+AUTH_COOKIE = "rr_auth"
+TOKEN_PREFIX = "rr_"
+LAST_USED_WRITE_INTERVAL = timedelta(minutes=1)
+
+SCOPES = ("read", "upload", "full")
+SCOPE_GROUPS = {
+    "read": {"read", "status"},
+    "upload": {"upload", "status"},
+    "full": {"read", "status", "upload", "write", "admin"},
+}
+
+_ADMIN_ROUTE = re.compile(r"^/(admin|webhooks|notifications|config/bundle|shutdown|reset|reset_all_tasks|cache/cleanup|index/compact)(/|$)")
+_STATUS_ROUTE = re.compile(r"^/(tasks|progress|upload_url|startup_status)(/|$)")
+# 로그인 전에 받아야 하는 웹 UI 정적 파일과 데스크톱 셸의 시작 상태 확인
+_EXEMPT_ROUTES = {"/", "/upload.css", "/upload.js", "/favicon.ico", "/startup_status"}
+_AUTH_ROUTES = {"/auth/login", "/auth/logout"}
+_UPLOAD_ROUTES = {"/upload", "/upload/raw", "/upload_url", "/email/inbound", "/process"}
+# 본문으로 조건을 받지만 아무것도 바꾸지 않는 POST
+_READ_POSTS = {"/ask", "/search/advanced", "/similar", "/check_existing_stt", "/graphql"}
+
+_tokens_lock = threading.Lock()
+
+
+class TokenError(ValueError):
+    """Raised for invalid token names/scopes and unknown token ids."""
+
+
+def route_group(method: str, path: str) -> str:
+    """Permission group of a request: ``admin``, ``status``, ``upload``, ``read`` or ``write``."""
+    path = path.split("?", 1)[0]
+    if _ADMIN_ROUTE.match(path):
+        return "admin"
+    if _STATUS_ROUTE.match(path) and method == "GET":
+        return "status"
+    if method == "POST" and path in _UPLOAD_ROUTES:
+        return "upload"
+    if method in ("GET", "HEAD") or (method == "POST" and path in _READ_POSTS):
+        return "read"
+    return "write"
+
+
+def scope_allows(scope: str, method: str, path: str) -> bool:
+    return route_group(method, path) in SCOPE_GROUPS.get(scope, set())
+
+
+def _hash(token: str) -> str:
+    return hashlib.sha256(token.encode("utf-8")).hexdigest()
+
+
+def _load() -> List[Dict[str, Any]]:
+    try:
+        with open(TOKENS_FILE, "r", encoding="utf-8") as f:
+            tokens = json.load(f)
+        return tokens if isinstance(tokens, list) else []
+    except (OSError, ValueError):
+        return []
+
+
+def _save(tokens: List[Dict[str, Any]]) -> None:
+    TOKENS_FILE.parent.mkdir(parents=True, exist_ok=True)
+    tmp_path = TOKENS_FILE.with_name(f"{TOKENS_FILE.name}.tmp")
+    with open(tmp_path, "w", encoding="utf-8") as f:
+        json.dump(tokens, f, ensure_ascii=False, indent=2)
+    tmp_path.replace(TOKENS_FILE)
+
+
+def _public(token: Dict[str, Any]) -> Dict[str, Any]:
+    return {key: value for key, value in token.items() if key != "hash"}
+
+
+def list_tokens() -> List[Dict[str, Any]]:
+    with _tokens_lock:
+        return [_public(token) for token in _load()]
+
+
+def create_token(name: Any, scope: Any) -> Dict[str, Any]:
+    """Create a token; the returned ``token`` value is not stored and cannot be shown again."""
+    if not isinstance(name, str) or not name.strip():
+        raise TokenError("토큰 이름(name)이 필요합니다.")
+    if scope not in SCOPES:
+        raise TokenError(f"scope는 {', '.join(SCOPES)} 중 하나여야 합니다.")
+    secret = TOKEN_PREFIX + secrets.token_urlsafe(32)
+    entry = {
+        "id": str(uuid.uuid4()),
+        "name": name.strip(),
+        "scope": scope,
+        "prefix": secret[:len(TOKEN_PREFIX) + 6],
+        "hash": _hash(secret),
+        "created_at": datetime.now().isoformat(),
+        "last_used_at": None,
+    }
+    with _tokens_lock:
+        tokens = _load()
+        tokens.append(entry)
+        _save(tokens)
+    return {**_public(entry), "token": secret}
+
+
+def revoke_token(token_id: str) -> bool:
+    with _tokens_lock:
+        tokens = _load()
+        remaining = [token for token in tokens if token.get("id") != token_id]
+        if len(remaining) == len(tokens):
+            return False
+        _save(remaining)
+    return True
+
+
+def is_auth_exempt(method: str, path: str) -> bool:
+    """Routes that never need a credential: static UI files, the startup probe, login/logout."""
+    path = path.split("?", 1)[0]
+    if method == "POST":
+        return path in _AUTH_ROUTES
+    if method not in ("GET", "HEAD"):
+        return False
+    if path in _EXEMPT_ROUTES:
+        return True
+    for exempt in (item.strip() for item in AUTH_EXEMPT_PATHS.split(",")):
+        if exempt and (path == exempt or (exempt.endswith("/") and path.startswith(exempt))):
+            return True
+    return False
+
+
+def _config_token() -> Dict[str, Any]:
+    return {"id": "config", "name": "AUTH_TOKEN", "scope": "full", "prefix": AUTH_TOKEN[:6]}
+
+
+def authenticate(secret: str) -> Optional[Dict[str, Any]]:
+    """Token entry for ``secret`` (without the hash), or ``None`` when unknown."""
+    if AUTH_TOKEN and secrets.compare_digest(secret.encode("utf-8"), AUTH_TOKEN.encode("utf-8")):
+        return _config_token()
+    digest = _hash(secret)
+    with _tokens_lock:
+        tokens = _load()
+        entry = next((token for token in tokens if secrets.compare_digest(token.get("hash", ""), digest)), None)
+        if entry is None:
+            return None
+        # 요청마다 파일을 쓰지 않도록 마지막 사용 시각은 1분 단위로만 갱신
+        now = datetime.now()
+        last_used = entry.get("last_used_at")
+        if not last_used or now - datetime.fromisoformat(last_used) >= LAST_USED_WRITE_INTERVAL:
+            entry["last_used_at"] = now.isoformat()
+            try:
+                _save(tokens)
+            except OSError:
+                pass
+    return _public(entry)
+
+
+def token_from_headers(headers) -> Optional[str]:
+    authorization = headers.get("Authorization") or ""
+    if authorization.lower().startswith("bearer "):
+        return authorization[7:].strip() or None
+    api_key = (headers.get("X-API-Key") or "").strip()
+    if api_key:
+        return api_key
+    # 브라우저는 /auth/login에서 받은 쿠키로 인증 (다운로드 링크, 오디오, WebSocket 포함)
+    try:
+        cookie = SimpleCookie(headers.get("Cookie") or "")
+    except CookieError:
+        return None
+    morsel = cookie.get(AUTH_COOKIE)
+    return (morsel.value.strip() or None) if morsel else None
+
+
+def auth_cookie(secret: str, secure: bool = False) -> str:
+    """``Set-Cookie`` value carrying ``secret``; an empty secret clears the cookie."""
+    attributes = "Path=/; HttpOnly; SameSite=Strict" + ("; Secure" if secure else "")
+    if not secret:
+        return f"{AUTH_COOKIE}=; Max-Age=0; {attributes}"
+    return f"{AUTH_COOKIE}={secret}; {attributes}"
+
+
+def is_direct_local_request(client_host: str, headers) -> bool:
+    """Loopback request that did not come through a reverse proxy or tunnel."""
+    if client_host not in ("127.0.0.1", "::1", "localhost"):
+        return False
+    return not any(headers.get(name) for name in ("X-Forwarded-For", "Cf-Connecting-Ip", "Forwarded"))
+
+
+def token_required(client_host: str, headers) -> bool:
+    """Whether a request without a token must be rejected (``AUTH_ENABLED``/``API_AUTH_REQUIRED``, reloadable)."""
+    if AUTH_ENABLED:
+        return True
+    return API_AUTH_REQUIRED and not is_direct_local_request(client_host, headers)
+
@@ -0,0 +1,181 @@
+"""MCP server exposing the recording archive to desktop LLM clients.
+
+Runs as a stdio MCP server that talks to the running RecordRoute API, so
+search, namespaces and API tokens behave exactly as over HTTP::
+
+    python -m sttEngine.archive_mcp
+
+Claude Desktop (``claude_desktop_config.json``)::
+
+    {"mcpServers": {"recordroute": {
+        "command": "/path/to/venv/bin/python",
+        "args": ["-m", "sttEngine.archive_mcp"],
+        "cwd": "/path/to/RecordRoute",
+        "env": {"ARCHIVE_MCP_API_TOKEN": "rr_..."}}}}
+
+Tools:
+
+* ``search_records`` — keyword and semantic search over transcripts/summaries;
+* ``get_transcript`` — a record's transcript (clean or verbatim view);
+* ``get_summary`` — a record's summary;
+* ``ask_archive`` — the summaries of the records most relevant to a question,
+  numbered for citation, for the client model to answer from.
+
+``ARCHIVE_MCP_SERVER_URL`` (default ``http://localhost:8080``) and
+``ARCHIVE_MCP_API_TOKEN`` (read scope is enough) select the API.
+"""
+
+from __future__ import annotations
+
+from typing import Any, Dict, List, Optional
+from urllib.parse import quote
+
+import requests
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+ARCHIVE_MCP_SERVER_URL = get_config_value("ARCHIVE_MCP_SERVER_URL", "http://localhost:8080", str).rstrip("/")
+ARCHIVE_MCP_API_TOKEN = get_config_value("ARCHIVE_MCP_API_TOKEN", "", str)
+ARCHIVE_MCP_TIMEOUT_SECONDS = get_config_value("ARCHIVE_MCP_TIMEOUT_SECONDS", 60, float)
+# 도구 응답이 클라이언트 컨텍스트를 다 차지하지 않도록 자르는 길이
+ARCHIVE_MCP_MAX_CHARS = max(1000, get_config_value("ARCHIVE_MCP_MAX_CHARS", 40000, int))
+ASK_ARCHIVE_RECORDS = 5
+
+
+class ArchiveError(RuntimeError):
+    """Raised when the RecordRoute API cannot answer."""
+
+
+def _get(path: str, params: Optional[Dict[str, Any]] = None, as_json: bool = True):
+    headers = {"Authorization": f"Bearer {ARCHIVE_MCP_API_TOKEN}"} if ARCHIVE_MCP_API_TOKEN else {}
+    try:
+        response = requests.get(f"{ARCHIVE_MCP_SERVER_URL}{path}", params=params, headers=headers,
+                                timeout=ARCHIVE_MCP_TIMEOUT_SECONDS)
+    except requests.RequestException as exc:
+        raise ArchiveError(f"RecordRoute 서버({ARCHIVE_MCP_SERVER_URL})에 연결할 수 없습니다: {exc}") from None
+    if response.status_code >= 400:
+        try:
+            message = response.json().get("error")
+        except ValueError:
+            message = None
+        raise ArchiveError(message or f"HTTP {response.status_code}")
+    return response.json() if as_json else response.text
+
+
+def _truncate(text: str) -> str:
+    if len(text) <= ARCHIVE_MCP_MAX_CHARS:
+        return text
+    return text[:ARCHIVE_MCP_MAX_CHARS] + f"\n\n… ({len(text) - ARCHIVE_MCP_MAX_CHARS}자 생략)"
+
+
+def _record(record_id: str) -> Dict[str, Any]:
+    record = next((r for r in _get("/history") if r.get("id") == record_id), None)
+    if record is None:
+        raise ArchiveError(f"기록을 찾을 수 없습니다: {record_id}")
+    return record
+
+
+def search_records(query: str, target: str = "both", limit: int = 10) -> List[Dict[str, Any]]:
+    """Records matching ``query``: keyword matches first, then semantically similar ones."""
+    result = _get("/search", {"q": query, "target": target})
+    hits, seen = [], set()
+    for kind, items in (("keyword", result.get("keywordMatches") or []),
+                        ("semantic", result.get("similarDocuments") or [])):
+        for item in items:
+            key = (item.get("record_id"), item.get("file"))
+            if key in seen:
+                continue
+            seen.add(key)
+            hits.append({
+                "record_id": item.get("record_id"),
+                "filename": item.get("source_filename") or item.get("display_name"),
+                "uploaded_at": item.get("uploaded_at"),
+                "match": kind,
+                "document": item.get("kind") or ("summary" if (item.get("file") or "").endswith(".summary.md")
+                                                 else "transcript"),
+                "score": item.get("score"),
+                "keyword_count": item.get("count"),
+            })
+    return hits[:max(1, limit)]
+
+
+def get_transcript(record_id: str, view: str = "clean") -> str:
+    """Transcript markdown of a record (``view``: clean or verbatim)."""
+    return _truncate(_get(f"/record/{quote(record_id)}/transcript", {"view": view})["text"])
+
+
+def get_summary(record_id: str) -> str:
+    """Summary markdown of a record."""
+    link = (_record(record_id).get("download_links") or {}).get("summary")
+    if not link:
+        raise ArchiveError("이 기록에는 요약이 없습니다.")
+    return _truncate(_get(link, as_json=False))
+
+
+def ask_archive(question: str, limit: int = ASK_ARCHIVE_RECORDS) -> str:
+    """Numbered summaries of the records most relevant to ``question``."""
+    sources = []
+    for hit in search_records(question, limit=limit * 3):
+        if not hit["record_id"] or any(source["record_id"] == hit["record_id"] for source in sources):
+            continue
+        try:
+            summary = get_summary(hit["record_id"])
+        except ArchiveError:
+            continue
+        sources.append({**hit, "summary": summary})
+        if len(sources) >= limit:
+            break
+    if not sources:
+        return f"질문과 관련된 요약을 찾지 못했습니다: {question}"
+    blocks = [
+        f"[{number}] {source['filename']} ({(source.get('uploaded_at') or '')[:10]}, record_id={source['record_id']})\n"
+        f"{source['summary']}"
+        for number, source in enumerate(sources, start=1)
+    ]
+    return _truncate(
+        f"질문: {question}\n\n아래 회의 요약만 근거로 답하고, 근거가 된 번호를 [1]처럼 표시하세요. "
+        f"요약에 없는 내용은 모른다고 답하세요.\n\n" + "\n\n---\n\n".join(blocks)
+    )
+
+
+def build_server():
+    """FastMCP server with the archive tools (needs ``mcp>=1.2``)."""
+    from mcp.server.fastmcp import FastMCP
+
+    server = FastMCP("recordroute")
+
+    @server.tool(name="search_records")
+    def search_records_tool(query: str, target: str = "both", limit: int = 10) -> List[Dict[str, Any]]:
+        """Search the user's recorded meetings (transcripts and summaries) by keyword and meaning.
+
+        target: transcript | summary | both. Returns record_id, filename, upload time and match type.
+        """
+        return search_records(query, target, limit)
+
+    @server.tool(name="get_transcript")
+    def get_transcript_tool(record_id: str, view: str = "clean") -> str:
+        """Full timestamped transcript of a recording. view: clean (default) or verbatim (fillers kept)."""
+        return get_transcript(record_id, view)
+
+    @server.tool(name="get_summary")
+    def get_summary_tool(record_id: str) -> str:
+        """Summary of a recording (topics, decisions, action items)."""
+        return get_summary(record_id)
+
+    @server.tool(name="ask_archive")
+    def ask_archive_tool(question: str, limit: int = ASK_ARCHIVE_RECORDS) -> str:
+        """Answer a question about past meetings: returns the most relevant meeting summaries, numbered for citation."""
+        return ask_archive(question, limit)
+
+    return server
+
+
+def main() -> None:
+    build_server().run()
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,216 @@
+"""Question answering over the transcript archive (``POST /ask``).
+
+Search finds documents; ``/ask`` answers a question from them and says
+where the answer came from:
+
+1. the vector index is searched with the question, and the best
+   ``ASK_CANDIDATE_RECORDS`` records become candidates;
+2. their transcripts are cut into passages — paragraphs of the stored
+   segments (:func:`paragraphs.build_paragraphs`, at most
+   ``ASK_PASSAGE_CHARS``) with start/end times, or blank-line blocks of the
+   summary when a record has no transcript;
+3. the passages are ranked by embedding similarity to the question (passage
+   embeddings go through the query embedding cache, so asking again about
+   the same records costs no new embedding calls). When there are more than
+   ``ASK_MAX_PASSAGES``, the ones sharing the fewest words with the question
+   are dropped first. The best ``ASK_TOP_K`` with a score of at least
+   ``ASK_MIN_SCORE`` are kept;
+4. the model (``ASK_MODEL``, default the summary model) answers from the
+   numbered passages only and cites them as ``[n]``. Every passage is
+   returned with its record id, file name and timestamp, and ``cited``
+   lists the numbers the answer actually used.
+
+The prompt can be overridden with ``DB/prompts/ask.txt`` (``{sources}`` and
+``{question}`` required).
+"""
+
+from __future__ import annotations
+
+import math
+import re
+from dataclasses import dataclass
+from typing import Any, Callable, Dict, List, Optional, Sequence
+
+import numpy as np
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+    from .paragraphs import build_paragraphs
+    from .summary_chunking import SUMMARY_NUM_CTX_MAX, estimate_tokens
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+    from paragraphs import build_paragraphs  # type: ignore
+    from summary_chunking import SUMMARY_NUM_CTX_MAX, estimate_tokens  # type: ignore
+
+ASK_MODEL = get_config_value("ASK_MODEL", "", str).strip()
+ASK_TOP_K = max(1, get_config_value("ASK_TOP_K", 6, int))
+ASK_CANDIDATE_RECORDS = max(1, get_config_value("ASK_CANDIDATE_RECORDS", 5, int))
+ASK_PASSAGE_CHARS = max(100, get_config_value("ASK_PASSAGE_CHARS", 800, int))
+ASK_MAX_PASSAGES = max(1, get_config_value("ASK_MAX_PASSAGES", 200, int))
+ASK_MIN_SCORE = get_config_value("ASK_MIN_SCORE", 0.3, float)
+ASK_TEMPERATURE = get_config_value("ASK_TEMPERATURE", 0.2, float)
+MAX_TOP_K = 20
+MIN_NUM_CTX = 4096
+NUM_CTX_STEP = 1024
+ANSWER_RESERVE_TOKENS = 1024
+
+# /admin/reload 시 DB/prompts/ask.txt 로 교체될 수 있음 ({sources}, {question} 필수)
+ASK_PROMPT = (
+    "당신은 회의 녹음 기록 보관소의 질문에 답하는 도우미입니다.\n"
+    "아래 [번호]가 붙은 발췌문만 근거로 질문에 답하세요.\n"
+    "- 근거로 쓴 발췌문 번호를 문장 끝에 [1], [2]처럼 표시하세요.\n"
+    "- 발췌문에 답이 없으면 추측하지 말고 기록에서 찾을 수 없다고 답하세요.\n"
+    "- 질문과 같은 언어로 간결하게 답하세요.\n\n"
+    "발췌문:\n{sources}\n\n"
+    "질문: {question}\n"
+    "답변:"
+)
+NO_SOURCES_ANSWER = "질문과 관련된 기록을 찾지 못했습니다."
+
+_CITATION = re.compile(r"\[(\d+(?:\s*,\s*\d+)*)\]")
+_WORD = re.compile(r"\w+")
+
+
+class AskError(ValueError):
+    """Raised for an invalid ``/ask`` request."""
+
+
+@dataclass
+class Passage:
+    """A piece of one record's transcript (or summary) that can back an answer."""
+
+    record_id: str
+    filename: Optional[str]
+    kind: str
+    text: str
+    start: Optional[float] = None
+    end: Optional[float] = None
+    speaker: Optional[str] = None
+    score: float = 0.0
+
+    @property
+    def timestamp(self) -> Optional[str]:
+        if self.start is None:
+            return None
+        h, rem = divmod(int(self.start), 3600)
+        m, s = divmod(rem, 60)
+        return f"{h:02d}:{m:02d}:{s:02d}"
+
+    def label(self) -> str:
+        parts = [self.filename or self.record_id]
+        if self.timestamp:
+            parts.append(self.timestamp)
+        if self.speaker:
+            parts.append(self.speaker)
+        return " · ".join(parts)
+
+    def to_dict(self, index: int) -> Dict[str, Any]:
+        return {
+            "index": index,
+            "record_id": self.record_id,
+            "filename": self.filename,
+            "kind": self.kind,
+            "start": self.start,
+            "end": self.end,
+            "timestamp": self.timestamp,
+            "speaker": self.speaker,
+            "score": round(self.score, 4),
+            "text": self.text,
+        }
+
+
+def parse_top_k(value: Any) -> int:
+    if value is None:
+        return ASK_TOP_K
+    if isinstance(value, bool) or not isinstance(value, int) or not 1 <= value <= MAX_TOP_K:
+        raise AskError(f"top_k는 1~{MAX_TOP_K} 사이의 정수여야 합니다.")
+    return value
+
+
+def transcript_passages(record_id: str, filename: Optional[str],
+                        segments: Sequence[Dict[str, Any]]) -> List[Passage]:
+    """Timed passages from a record's transcript segments."""
+    return [
+        Passage(record_id, filename, "transcript", paragraph.text, paragraph.start, paragraph.end, paragraph.speaker)
+        for paragraph in build_paragraphs(segments, max_chars=ASK_PASSAGE_CHARS)
+    ]
+
+
+def text_passages(record_id: str, filename: Optional[str], text: str, kind: str = "summary") -> List[Passage]:
+    """Untimed passages from blank-line blocks of ``text``, merged up to ``ASK_PASSAGE_CHARS``."""
+    passages: List[Passage] = []
+    current = ""
+    for block in (block.strip() for block in re.split(r"\n\s*\n", text)):
+        if not block:
+            continue
+        if current and len(current) + len(block) > ASK_PASSAGE_CHARS:
+            passages.append(Passage(record_id, filename, kind, current))
+            current = ""
+        current = f"{current}\n{block}" if current else block
+    if current:
+        passages.append(Passage(record_id, filename, kind, current))
+    return passages
+
+
+def _word_overlap(question_words: set, text: str) -> int:
+    return len(question_words & {word.casefold() for word in _WORD.findall(text)})
+
+
+def rank_passages(question: str, passages: List[Passage], embed: Callable[[str], np.ndarray],
+                  top_k: int = None, min_score: float = None) -> List[Passage]:
+    """The ``top_k`` passages most similar to ``question`` (scores set on the passages)."""
+    top_k = top_k or ASK_TOP_K
+    min_score = ASK_MIN_SCORE if min_score is None else min_score
+    if len(passages) > ASK_MAX_PASSAGES:
+        # 임베딩 호출 수를 제한: 질문과 겹치는 단어가 적은 발췌문부터 제외
+        words = {word.casefold() for word in _WORD.findall(question)}
+        ranked = sorted(range(len(passages)), key=lambda i: -_word_overlap(words, passages[i].text))
+        keep = sorted(ranked[:ASK_MAX_PASSAGES])
+        passages = [passages[i] for i in keep]
+    query = np.asarray(embed(question), dtype=np.float32)
+    query_norm = float(np.linalg.norm(query))
+    if not passages or query_norm == 0:
+        return []
+    for passage in passages:
+        vector = np.asarray(embed(passage.text), dtype=np.float32)
+        norm = float(np.linalg.norm(vector))
+        passage.score = float(vector @ query) / (norm * query_norm) if norm else 0.0
+    ranked = sorted((p for p in passages if p.score >= min_score), key=lambda p: p.score, reverse=True)
+    return ranked[:top_k]
+
+
+def build_prompt(question: str, passages: Sequence[Passage]) -> str:
+    sources = "\n\n".join(f"[{index}] ({passage.label()})\n{passage.text}"
+                          for index, passage in enumerate(passages, 1))
+    return ASK_PROMPT.replace("{sources}", sources).replace("{question}", question.strip())
+
+
+def prompt_num_ctx(prompt: str) -> int:
+    """Context just large enough for ``prompt`` and the answer."""
+    needed = estimate_tokens(prompt) + ANSWER_RESERVE_TOKENS
+    return min(SUMMARY_NUM_CTX_MAX, max(MIN_NUM_CTX, math.ceil(needed / NUM_CTX_STEP) * NUM_CTX_STEP))
+
+
+def cited_indices(answer: str, count: int) -> List[int]:
+    """Passage numbers cited in ``answer`` as ``[n]`` or ``[n, m]`` (numbers outside 1..count ignored)."""
+    cited = {int(number) for group in _CITATION.findall(answer) for number in group.split(",")}
+    return sorted(number for number in cited if 1 <= number <= count)
+
+
+def answer_question(question: str, passages: Sequence[Passage], generate: Callable[[str, int], str],
+                    model: str) -> Dict[str, Any]:
+    """Answer ``question`` from ``passages`` with ``generate(prompt, num_ctx)``; passages become citations."""
+    citations = [passage.to_dict(index) for index, passage in enumerate(passages, 1)]
+    if not passages:
+        return {"question": question, "answer": NO_SOURCES_ANSWER, "found": False,
+                "model": None, "citations": [], "cited": []}
+    prompt = build_prompt(question, passages)
+    answer = generate(prompt, prompt_num_ctx(prompt)).strip()
+    return {
+        "question": question,
+        "answer": answer,
+        "found": True,
+        "model": model,
+        "citations": citations,
+        "cited": cited_indices(answer, len(passages)),
+    }
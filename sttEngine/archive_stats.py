@@ -0,0 +1,91 @@
+"""Library-wide statistics for the archive dashboard (``GET /stats/archive``)."""
+
+from __future__ import annotations
+
+from collections import Counter, defaultdict
+from datetime import datetime
+from typing import Any, Callable, Dict, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .advanced_search import parse_duration_seconds
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from advanced_search import parse_duration_seconds  # type: ignore
+
+DEFAULT_TOP_TAGS = 10
+UNKNOWN_LANGUAGE = "unknown"
+
+
+def _hours(seconds: float) -> float:
+    return round(seconds / 3600, 2)
+
+
+def _month_of(record: Dict[str, Any]) -> Optional[str]:
+    try:
+        return datetime.fromisoformat(str(record.get("timestamp"))).strftime("%Y-%m")
+    except ValueError:
+        return None
+
+
+def build_archive_stats(records: List[Dict[str, Any]],
+                        language_of: Callable[[Dict[str, Any]], Optional[str]],
+                        summary_text_of: Callable[[Dict[str, Any]], str],
+                        top_tags: int = DEFAULT_TOP_TAGS) -> Dict[str, Any]:
+    """Summarize active records.
+
+    ``language_of`` returns the transcript language of a transcribed record and
+    ``summary_text_of`` its summary text; both are only called when needed.
+    Hours per month are grouped by upload month.
+    """
+    by_type: Counter = Counter()
+    tags: Counter = Counter()
+    monthly: Dict[str, Dict[str, float]] = defaultdict(lambda: {"seconds": 0.0, "recordings": 0})
+    languages: Dict[str, Dict[str, float]] = defaultdict(lambda: {"seconds": 0.0, "recordings": 0})
+    total_seconds = transcribed_seconds = 0.0
+    transcribed = 0
+    summary_lengths: List[int] = []
+
+    for record in records:
+        by_type[record.get("file_type") or "unknown"] += 1
+        tags.update(tag for tag in record.get("tags") or [] if tag)
+        seconds = parse_duration_seconds(record.get("duration")) or 0.0
+        total_seconds += seconds
+        completed = record.get("completed_tasks") or {}
+
+        if completed.get("stt"):
+            transcribed += 1
+            transcribed_seconds += seconds
+            month = _month_of(record)
+            if month:
+                monthly[month]["seconds"] += seconds
+                monthly[month]["recordings"] += 1
+            language = language_of(record) or UNKNOWN_LANGUAGE
+            languages[language]["seconds"] += seconds
+            languages[language]["recordings"] += 1
+
+        if completed.get("summary"):
+            text = summary_text_of(record).strip()
+            if text:
+                summary_lengths.append(len(text))
+
+    return {
+        "generated_at": datetime.now().isoformat(),
+        "total_recordings": len(records),
+        "by_type": dict(by_type),
+        "total_audio_hours": _hours(total_seconds),
+        "transcribed_recordings": transcribed,
+        "transcribed_audio_hours": _hours(transcribed_seconds),
+        "transcribed_hours_by_month": [
+            {"month": month, "hours": _hours(item["seconds"]), "recordings": item["recordings"]}
+            for month, item in sorted(monthly.items())
+        ],
+        "languages": sorted(
+            ({"language": language, "hours": _hours(item["seconds"]), "recordings": item["recordings"]}
+             for language, item in languages.items()),
+            key=lambda item: (-item["recordings"], item["language"]),
+        ),
+        "top_tags": [{"tag": tag, "count": count} for tag, count in tags.most_common(top_tags)],
+        "summaries": {
+            "count": len(summary_lengths),
+            "average_chars": round(sum(summary_lengths) / len(summary_lengths)) if summary_lengths else None,
+        },
+    }
@@ -0,0 +1,215 @@
+"""Content-addressed sharing of identical artifacts between records.
+
+The same meeting uploaded as ``.m4a`` and as ``.mp4`` passes the upload
+duplicate check (the container bytes differ) but produces the same
+transcript, summary and embedding. After an artifact is written,
+:func:`share` stores it once under its SHA-256::
+
+    DB/artifacts/
+        ab/abcdef....md          # 내용 해시 이름의 원본
+        3f/3f0912....npy
+        refs.json                # 해시 → 크기, 공유 중인 기록 파일 경로
+
+and turns the record's file into a hard link to that blob. Record paths,
+download links and the vector index stay exactly as they were; identical
+files just occupy the disk once.
+
+The reference count is the hard-link count of the blob (minus the blob
+itself), so deleting, moving to the trash or atomically replacing a record
+file needs no bookkeeping. :func:`collect_garbage` removes blobs nobody
+links to any more. Code that rewrites an artifact *in place* must call
+:func:`detach` first so the edit does not leak into the other records.
+
+File systems without hard links (or a DB folder spanning several volumes)
+simply keep separate copies.
+"""
+
+from __future__ import annotations
+
+import hashlib
+import json
+import os
+import shutil
+import threading
+from pathlib import Path
+from typing import Any, Dict, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value, get_db_base_path
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value, get_db_base_path  # type: ignore
+
+ARTIFACT_DEDUP_ENABLED = get_config_value("ARTIFACT_DEDUP_ENABLED", True, bool)
+
+STORE_DIRNAME = "artifacts"
+REFS_NAME = "refs.json"
+
+_lock = threading.RLock()
+
+
+def store_root() -> Path:
+    return get_db_base_path() / STORE_DIRNAME
+
+
+def _hash_file(path: Path) -> str:
+    digest = hashlib.sha256()
+    with open(path, "rb") as f:
+        for chunk in iter(lambda: f.read(1024 * 1024), b""):
+            digest.update(chunk)
+    return digest.hexdigest()
+
+
+def blob_path(content_hash: str, suffix: str) -> Path:
+    return store_root() / content_hash[:2] / f"{content_hash}{suffix}"
+
+
+def _load_refs() -> Dict[str, Dict[str, Any]]:
+    try:
+        with open(store_root() / REFS_NAME, "r", encoding="utf-8") as f:
+            refs = json.load(f)
+        return refs if isinstance(refs, dict) else {}
+    except (OSError, json.JSONDecodeError):
+        return {}
+
+
+def _save_refs(refs: Dict[str, Dict[str, Any]]) -> None:
+    root = store_root()
+    root.mkdir(parents=True, exist_ok=True)
+    tmp_path = root / f"{REFS_NAME}.tmp"
+    with open(tmp_path, "w", encoding="utf-8") as f:
+        json.dump(refs, f, ensure_ascii=False, indent=2)
+    tmp_path.replace(root / REFS_NAME)
+
+
+def _relative(path: Path) -> str:
+    try:
+        return path.resolve().relative_to(get_db_base_path().resolve()).as_posix()
+    except ValueError:
+        return str(path)
+
+
+def share(path: Path) -> Optional[str]:
+    """Store ``path`` content-addressed and hard-link it to an identical blob; returns the hash.
+
+    ``None`` when sharing is disabled or the file system cannot hard-link.
+    """
+    path = Path(path)
+    if not ARTIFACT_DEDUP_ENABLED or not path.is_file():
+        return None
+    with _lock:
+        content_hash = _hash_file(path)
+        blob = blob_path(content_hash, path.suffix)
+        try:
+            if not blob.exists():
+                blob.parent.mkdir(parents=True, exist_ok=True)
+                os.link(path, blob)
+            elif not os.path.samefile(blob, path):
+                tmp_path = path.with_name(f".{path.name}.link")
+                tmp_path.unlink(missing_ok=True)
+                os.link(blob, tmp_path)
+                os.replace(tmp_path, path)
+        except OSError as exc:
+            print(f"산출물 공유 건너뜀 ({path.name}): {exc}")
+            return None
+        refs = _load_refs()
+        entry = refs.setdefault(content_hash, {"suffix": path.suffix, "size": blob.stat().st_size, "paths": []})
+        relative = _relative(path)
+        if relative not in entry["paths"]:
+            entry["paths"].append(relative)
+        _save_refs(refs)
+        return content_hash
+
+
+def find_blob(content_hash: str, suffix: str) -> Optional[Path]:
+    blob = blob_path(content_hash, suffix)
+    return blob if blob.exists() else None
+
+
+def link_existing(content_hash: str, suffix: str, target: Path) -> bool:
+    """Make ``target`` a link to an existing blob (reuse instead of recomputing); ``False`` if absent."""
+    blob = find_blob(content_hash, suffix)
+    if blob is None:
+        return False
+    with _lock:
+        try:
+            target.parent.mkdir(parents=True, exist_ok=True)
+            tmp_path = target.with_name(f".{target.name}.link")
+            tmp_path.unlink(missing_ok=True)
+            os.link(blob, tmp_path)
+            os.replace(tmp_path, target)
+        except OSError:
+            return False
+        refs = _load_refs()
+        entry = refs.setdefault(content_hash, {"suffix": suffix, "size": blob.stat().st_size, "paths": []})
+        if _relative(target) not in entry["paths"]:
+            entry["paths"].append(_relative(target))
+        _save_refs(refs)
+    return True
+
+
+def detach(path: Path) -> None:
+    """Give ``path`` its own copy before an in-place write if it shares a blob."""
+    path = Path(path)
+    try:
+        if not path.is_file() or path.stat().st_nlink <= 1:
+            return
+    except OSError:
+        return
+    with _lock:
+        tmp_path = path.with_name(f".{path.name}.detach")
+        shutil.copy2(path, tmp_path)
+        os.replace(tmp_path, path)
+
+
+def reference_count(blob: Path) -> int:
+    return max(0, blob.stat().st_nlink - 1)
+
+
+def stats() -> Dict[str, Any]:
+    """Blob count, bytes stored once and bytes saved by sharing."""
+    root = store_root()
+    blobs = shared = stored_bytes = saved_bytes = unreferenced = 0
+    if root.exists():
+        for blob in root.glob("??/*"):
+            refs = reference_count(blob)
+            size = blob.stat().st_size
+            blobs += 1
+            stored_bytes += size
+            if refs > 1:
+                shared += 1
+                saved_bytes += size * (refs - 1)
+            elif refs == 0:
+                unreferenced += 1
+    return {
+        "enabled": ARTIFACT_DEDUP_ENABLED,
+        "blobs": blobs,
+        "shared_blobs": shared,
+        "unreferenced_blobs": unreferenced,
+        "stored_bytes": stored_bytes,
+        "saved_bytes": saved_bytes,
+    }
+
+
+def collect_garbage(dry_run: bool = False) -> Dict[str, Any]:
+    """Delete blobs no record file links to and prune ``refs.json``."""
+    root = store_root()
+    removed, freed = [], 0
+    with _lock:
+        refs = _load_refs()
+        if root.exists():
+            for blob in root.glob("??/*"):
+                if reference_count(blob) > 0:
+                    continue
+                freed += blob.stat().st_size
+                removed.append(blob.name)
+                if not dry_run:
+                    blob.unlink(missing_ok=True)
+                    refs.pop(blob.name[:64], None)
+        if not dry_run:
+            for content_hash, entry in list(refs.items()):
+                entry["paths"] = [p for p in entry["paths"] if (get_db_base_path() / p).exists()]
+                if not find_blob(content_hash, entry.get("suffix", "")):
+                    refs.pop(content_hash)
+            if root.exists():
+                _save_refs(refs)
+    return {"removed": removed, "freed_bytes": freed, "dry_run": dry_run}
@@ -0,0 +1,348 @@
+"""Disk cache manager for derived artifacts.
+
+Three caches live under ``DB/cache/``:
+
+    cache/*.json              query   — vector search results (search_cache.py)
+    cache/embeddings/*.npy    embedding — query embeddings per (model, text)
+    cache/waveforms/*.json    waveform — audio peak data for the player UI
+
+Each cache has a TTL and a size budget. Entries older than the TTL (by last
+write/use) are removed on cleanup, then the least recently used entries are
+evicted until the cache fits its budget. ``/cache/stats`` reports entry
+counts, bytes and hit/miss counters; ``/cache/cleanup`` runs the cleanup.
+
+Query embeddings are additionally kept in an in-memory LRU keyed by the
+normalized query (case, whitespace and trailing punctuation ignored), so a
+repeated search skips both the Ollama round-trip and the disk read.
+"""
+
+from __future__ import annotations
+
+import hashlib
+import io
+import json
+import os
+import subprocess
+import threading
+import time
+import unicodedata
+from collections import OrderedDict
+from pathlib import Path
+from typing import Any, Callable, Dict, List, Optional, Tuple
+
+import numpy as np
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+    from .search_cache import CACHE_DIR, CACHE_EXPIRY_HOURS, get_hit_counters
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+    from search_cache import CACHE_DIR, CACHE_EXPIRY_HOURS, get_hit_counters  # type: ignore
+
+MB = 1024 * 1024
+
+QUERY_CACHE_MAX_MB = get_config_value("CACHE_QUERY_MAX_MB", 100, int)
+EMBEDDING_CACHE_TTL_DAYS = get_config_value("CACHE_EMBEDDING_TTL_DAYS", 30, float)
+EMBEDDING_CACHE_MAX_MB = get_config_value("CACHE_EMBEDDING_MAX_MB", 200, int)
+WAVEFORM_CACHE_TTL_DAYS = get_config_value("CACHE_WAVEFORM_TTL_DAYS", 90, float)
+WAVEFORM_CACHE_MAX_MB = get_config_value("CACHE_WAVEFORM_MAX_MB", 100, int)
+# 메모리 검색어 임베딩 LRU 항목 수 (0이면 비활성화)
+EMBEDDING_MEMORY_CACHE_SIZE = get_config_value("CACHE_EMBEDDING_MEMORY_SIZE", 512, int)
+
+WAVEFORM_DEFAULT_POINTS = 800
+WAVEFORM_MAX_POINTS = 10_000
+# 피크 계산용 디코딩 샘플레이트 (파형 표시에는 충분)
+WAVEFORM_SAMPLE_RATE = 8000
+
+
+class FileCache:
+    """A directory of cache files with TTL, size budget and hit counters."""
+
+    def __init__(self, name: str, directory: Path, pattern: str,
+                 ttl_seconds: float, max_bytes: int, description: str = "",
+                 counters: Optional[Callable[[], Tuple[int, int]]] = None):
+        self.name = name
+        self.directory = directory
+        self.pattern = pattern
+        self.ttl_seconds = ttl_seconds
+        self.max_bytes = max_bytes
+        self.description = description
+        # 조회가 다른 모듈에서 일어나는 캐시는 그쪽 카운터를 사용
+        self.counters = counters
+        self.hits = 0
+        self.misses = 0
+        self._lock = threading.Lock()
+
+    def path_for(self, key: str) -> Path:
+        digest = hashlib.sha256(key.encode("utf-8")).hexdigest()
+        return self.directory / self.pattern.replace("*", digest)
+
+    def _expired(self, mtime: float, now: float) -> bool:
+        return self.ttl_seconds > 0 and now - mtime > self.ttl_seconds
+
+    def lookup(self, key: str) -> Optional[Path]:
+        """Return the entry path for ``key`` if present and fresh (marks it used)."""
+        path = self.path_for(key)
+        try:
+            mtime = path.stat().st_mtime
+        except FileNotFoundError:
+            mtime = None
+        if mtime is None or self._expired(mtime, time.time()):
+            with self._lock:
+                self.misses += 1
+            return None
+        try:
+            os.utime(path)  # LRU 순서와 TTL 기준을 마지막 사용 시각으로 갱신
+        except OSError:
+            pass
+        with self._lock:
+            self.hits += 1
+        return path
+
+    def write_bytes(self, key: str, data: bytes) -> Path:
+        path = self.path_for(key)
+        self.directory.mkdir(parents=True, exist_ok=True)
+        tmp_path = path.with_name(f"{path.name}.tmp")
+        tmp_path.write_bytes(data)
+        tmp_path.replace(path)
+        return path
+
+    def _entries(self) -> List[Tuple[Path, os.stat_result]]:
+        if not self.directory.exists():
+            return []
+        entries = []
+        for path in self.directory.glob(self.pattern):
+            try:
+                entries.append((path, path.stat()))
+            except FileNotFoundError:
+                continue
+        return entries
+
+    def stats(self) -> Dict[str, Any]:
+        now = time.time()
+        entries = self._entries()
+        if self.counters is not None:
+            hits, misses = self.counters()
+        else:
+            with self._lock:
+                hits, misses = self.hits, self.misses
+        total = hits + misses
+        return {
+            "description": self.description,
+            "entries": len(entries),
+            "bytes": sum(stat.st_size for _, stat in entries),
+            "expired_entries": sum(1 for _, stat in entries if self._expired(stat.st_mtime, now)),
+            "ttl_seconds": self.ttl_seconds,
+            "max_bytes": self.max_bytes,
+            "hits": hits,
+            "misses": misses,
+            "hit_rate": round(hits / total, 4) if total else None,
+        }
+
+    def cleanup(self, clear: bool = False) -> Dict[str, int]:
+        """Remove expired entries, then evict least recently used ones over budget."""
+        now = time.time()
+        removed = freed = 0
+        survivors = []
+        for path, stat in self._entries():
+            if clear or self._expired(stat.st_mtime, now):
+                if _unlink(path):
+                    removed += 1
+                    freed += stat.st_size
+            else:
+                survivors.append((path, stat))
+
+        size = sum(stat.st_size for _, stat in survivors)
+        if self.max_bytes > 0 and size > self.max_bytes:
+            for path, stat in sorted(survivors, key=lambda item: item[1].st_mtime):
+                if size <= self.max_bytes:
+                    break
+                if _unlink(path):
+                    removed += 1
+                    freed += stat.st_size
+                    size -= stat.st_size
+        return {"removed_entries": removed, "freed_bytes": freed}
+
+
+def _unlink(path: Path) -> bool:
+    try:
+        path.unlink()
+        return True
+    except FileNotFoundError:
+        return False
+    except OSError as exc:
+        print(f"캐시 파일 삭제 실패 ({path.name}): {exc}")
+        return False
+
+
+QUERY_CACHE = FileCache(
+    "query", CACHE_DIR, "*.json", CACHE_EXPIRY_HOURS * 3600, QUERY_CACHE_MAX_MB * MB,
+    "벡터 검색 결과", counters=get_hit_counters,
+)
+EMBEDDING_CACHE = FileCache(
+    "embedding", CACHE_DIR / "embeddings", "*.npy", EMBEDDING_CACHE_TTL_DAYS * 86400,
+    EMBEDDING_CACHE_MAX_MB * MB, "검색어 임베딩",
+)
+WAVEFORM_CACHE = FileCache(
+    "waveform", CACHE_DIR / "waveforms", "*.json", WAVEFORM_CACHE_TTL_DAYS * 86400,
+    WAVEFORM_CACHE_MAX_MB * MB, "오디오 파형 피크",
+)
+CACHES: Dict[str, FileCache] = {cache.name: cache for cache in (QUERY_CACHE, EMBEDDING_CACHE, WAVEFORM_CACHE)}
+
+
+class MemoryLRU:
+    """Thread-safe in-memory LRU with hit/miss counters."""
+
+    def __init__(self, max_entries: int, description: str = ""):
+        self.max_entries = max_entries
+        self.description = description
+        self.hits = 0
+        self.misses = 0
+        self._entries: "OrderedDict[str, Any]" = OrderedDict()
+        self._lock = threading.Lock()
+
+    def get(self, key: str) -> Any:
+        if self.max_entries <= 0:
+            return None
+        with self._lock:
+            value = self._entries.get(key)
+            if value is None:
+                self.misses += 1
+                return None
+            self._entries.move_to_end(key)
+            self.hits += 1
+            return value
+
+    def put(self, key: str, value: Any) -> None:
+        if self.max_entries <= 0:
+            return
+        with self._lock:
+            self._entries[key] = value
+            self._entries.move_to_end(key)
+            while len(self._entries) > self.max_entries:
+                self._entries.popitem(last=False)
+
+    def clear(self) -> int:
+        with self._lock:
+            count = len(self._entries)
+            self._entries.clear()
+            return count
+
+    def stats(self) -> Dict[str, Any]:
+        with self._lock:
+            total = self.hits + self.misses
+            return {
+                "description": self.description,
+                "entries": len(self._entries),
+                "max_entries": self.max_entries,
+                "hits": self.hits,
+                "misses": self.misses,
+                "hit_rate": round(self.hits / total, 4) if total else None,
+            }
+
+
+EMBEDDING_MEMORY_CACHE = MemoryLRU(EMBEDDING_MEMORY_CACHE_SIZE, "검색어 임베딩 (메모리)")
+
+
+def get_cache_manager_stats() -> Dict[str, Any]:
+    caches = {name: cache.stats() for name, cache in CACHES.items()}
+    return {
+        "total_bytes": sum(stats["bytes"] for stats in caches.values()),
+        "caches": caches,
+        "memory_caches": {"embedding": EMBEDDING_MEMORY_CACHE.stats()},
+    }
+
+
+def cleanup_caches(names: Optional[List[str]] = None, clear: bool = False) -> Dict[str, Any]:
+    """Run cleanup on the given caches (all when ``names`` is empty)."""
+    unknown = [name for name in names or [] if name not in CACHES]
+    if unknown:
+        raise ValueError(f"알 수 없는 캐시: {', '.join(unknown)} (사용 가능: {', '.join(CACHES)})")
+    results = {name: CACHES[name].cleanup(clear) for name in names or CACHES}
+    if clear and "embedding" in results:
+        results["embedding"]["memory_entries_cleared"] = EMBEDDING_MEMORY_CACHE.clear()
+    return {
+        "removed_entries": sum(r["removed_entries"] for r in results.values()),
+        "freed_bytes": sum(r["freed_bytes"] for r in results.values()),
+        "caches": results,
+    }
+
+
+_QUERY_TRAILING_PUNCT = ".,!?;:…~。、！？"
+
+
+def normalize_query(text: str) -> str:
+    """Cache key form of a query: NFKC, case-folded, collapsed whitespace, no trailing punctuation."""
+    text = unicodedata.normalize("NFKC", text)
+    return " ".join(text.casefold().split()).rstrip(_QUERY_TRAILING_PUNCT).strip()
+
+
+def cached_query_embedding(text: str, model_name: str,
+                           compute: Callable[[str, str], np.ndarray]) -> np.ndarray:
+    """Return the embedding of a search query, computing it only on a cache miss.
+
+    Near-identical queries share one entry; the normalized query is what gets
+    embedded, so every variant yields the same vector.
+    """
+    text = normalize_query(text) or text
+    key = f"{model_name}\0{text}"
+    vector = EMBEDDING_MEMORY_CACHE.get(key)
+    if vector is not None:
+        return vector
+    path = EMBEDDING_CACHE.lookup(key)
+    if path is not None:
+        try:
+            vector = np.load(path)
+            EMBEDDING_MEMORY_CACHE.put(key, vector)
+            return vector
+        except (OSError, ValueError):
+            pass
+    vector = compute(text, model_name)
+    EMBEDDING_MEMORY_CACHE.put(key, vector)
+    buffer = io.BytesIO()
+    np.save(buffer, vector)
+    try:
+        EMBEDDING_CACHE.write_bytes(key, buffer.getvalue())
+    except OSError as exc:
+        print(f"임베딩 캐시 저장 실패: {exc}")
+    return vector
+
+
+def compute_waveform(audio_path: Path, points: int) -> Dict[str, Any]:
+    """Decode ``audio_path`` with ffmpeg and reduce it to ``points`` peak values (0..1)."""
+    command = [
+        "ffmpeg", "-nostdin", "-v", "error", "-i", str(audio_path),
+        "-ac", "1", "-ar", str(WAVEFORM_SAMPLE_RATE), "-f", "s16le", "-",
+    ]
+    result = subprocess.run(command, capture_output=True, check=False)
+    if result.returncode != 0:
+        raise RuntimeError(f"ffmpeg 디코딩 실패: {result.stderr.decode('utf-8', 'replace').strip()}")
+
+    samples = np.frombuffer(result.stdout, dtype=np.int16)
+    duration = len(samples) / WAVEFORM_SAMPLE_RATE
+    if not len(samples):
+        return {"duration": 0.0, "points": 0, "peaks": []}
+
+    points = min(points, len(samples))
+    buckets = np.array_split(np.abs(samples.astype(np.int32)), points)
+    peaks = [round(int(bucket.max()) / 32768, 4) if len(bucket) else 0.0 for bucket in buckets]
+    return {"duration": round(duration, 3), "points": len(peaks), "peaks": peaks}
+
+
+def get_waveform(audio_path: Path, points: int = WAVEFORM_DEFAULT_POINTS) -> Dict[str, Any]:
+    """Return waveform peaks for an audio/video file, cached per file version."""
+    stat = audio_path.stat()
+    key = f"{audio_path.resolve()}:{stat.st_mtime_ns}:{stat.st_size}:{points}"
+    path = WAVEFORM_CACHE.lookup(key)
+    if path is not None:
+        try:
+            with open(path, "r", encoding="utf-8") as f:
+                return {**json.load(f), "cached": True}
+        except (OSError, json.JSONDecodeError):
+            pass
+    waveform = compute_waveform(audio_path, points)
+    try:
+        WAVEFORM_CACHE.write_bytes(key, json.dumps(waveform).encode("utf-8"))
+    except OSError as exc:
+        print(f"파형 캐시 저장 실패: {exc}")
+    return {**waveform, "cached": False}
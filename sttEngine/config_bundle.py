@@ -0,0 +1,228 @@
+"""Export/import of the summarization setup as a single JSON bundle.
+
+A bundle holds the user-editable configuration that defines how recordings
+are summarized, so a team can share one standardized setup::
+
+    {
+        "format": "recordroute-config-bundle",
+        "version": 1,
+        "exported_at": "...",
+        "prompts": {"summary_chunk.txt": "...", ...},   # 프롬프트 디렉터리 덮어쓰기 파일
+        "minutes_templates": [{"id": "...", "name": "...", "body": "..."}],
+        "glossary": {"키워드": {"weight": 3, "last_updated": "..."}}
+    }
+
+Sections missing from a bundle are left untouched on import. Everything is
+validated before anything is written. ``merge`` (default) keeps local items
+that are not in the bundle; ``replace`` makes each included section match
+the bundle exactly. Prompts take effect after ``/admin/reload`` (the HTTP
+import endpoint reloads automatically).
+
+CLI::
+
+    python sttEngine/config_bundle.py export team_setup.json
+    python sttEngine/config_bundle.py import team_setup.json [--replace] [--dry-run]
+"""
+
+from __future__ import annotations
+
+import json
+from datetime import datetime
+from typing import Any, Dict, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_db_base_path
+    from .minutes_templates import (
+        TemplateError,
+        delete_template,
+        import_template,
+        list_templates,
+    )
+    from .runtime_config import PROMPT_TEMPLATES, get_prompt_dir
+    from .vocabulary_manager import VocabularyManager
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_db_base_path  # type: ignore
+    from minutes_templates import TemplateError, delete_template, import_template, list_templates  # type: ignore
+    from runtime_config import PROMPT_TEMPLATES, get_prompt_dir  # type: ignore
+    from vocabulary_manager import VocabularyManager  # type: ignore
+
+BUNDLE_FORMAT = "recordroute-config-bundle"
+BUNDLE_VERSION = 1
+SECTIONS = ("prompts", "minutes_templates", "glossary")
+IMPORT_MODES = ("merge", "replace")
+
+
+class BundleError(ValueError):
+    """Raised when a bundle is malformed; ``errors`` lists every problem."""
+
+    def __init__(self, errors: List[str]):
+        super().__init__("; ".join(errors))
+        self.errors = errors
+
+
+def _glossary() -> VocabularyManager:
+    return VocabularyManager(vocab_path=str(get_db_base_path() / "vocab.json"))
+
+
+def export_bundle(sections: Optional[List[str]] = None) -> Dict[str, Any]:
+    """Collect the selected sections (all by default) into a bundle."""
+    sections = sections or list(SECTIONS)
+    unknown = [name for name in sections if name not in SECTIONS]
+    if unknown:
+        raise BundleError([f"알 수 없는 섹션: {', '.join(unknown)} (사용 가능: {', '.join(SECTIONS)})"])
+
+    bundle: Dict[str, Any] = {
+        "format": BUNDLE_FORMAT,
+        "version": BUNDLE_VERSION,
+        "exported_at": datetime.now().isoformat(),
+    }
+    if "prompts" in sections:
+        prompt_dir = get_prompt_dir()
+        bundle["prompts"] = {
+            filename: (prompt_dir / filename).read_text(encoding="utf-8")
+            for filename in PROMPT_TEMPLATES
+            if (prompt_dir / filename).exists()
+        }
+    if "minutes_templates" in sections:
+        bundle["minutes_templates"] = [
+            {key: template.get(key) for key in ("id", "name", "body", "created_at")}
+            for template in list_templates() if not template.get("builtin")
+        ]
+    if "glossary" in sections:
+        bundle["glossary"] = _glossary().export_entries()
+    return bundle
+
+
+def _validate(bundle: Any) -> List[str]:
+    if not isinstance(bundle, dict):
+        return ["번들은 JSON 객체여야 합니다."]
+    errors = []
+    if bundle.get("format") != BUNDLE_FORMAT:
+        errors.append(f"지원하지 않는 번들 형식입니다: {bundle.get('format')!r}")
+    if not isinstance(bundle.get("version"), int) or bundle["version"] > BUNDLE_VERSION:
+        errors.append(f"지원하지 않는 번들 버전입니다: {bundle.get('version')!r} (최대 {BUNDLE_VERSION})")
+
+    prompts = bundle.get("prompts")
+    if prompts is not None:
+        if not isinstance(prompts, dict):
+            errors.append("prompts는 {파일명: 내용} 객체여야 합니다.")
+        else:
+            for filename, text in prompts.items():
+                if filename not in PROMPT_TEMPLATES:
+                    errors.append(f"prompts.{filename}: 알 수 없는 프롬프트 파일입니다.")
+                    continue
+                if not isinstance(text, str) or not text.strip():
+                    errors.append(f"prompts.{filename}: 비어 있지 않은 문자열이어야 합니다.")
+                    continue
+                missing = [p for p in PROMPT_TEMPLATES[filename][2] if p not in text]
+                if missing:
+                    errors.append(f"prompts.{filename}: 필수 자리표시자 누락 {', '.join(missing)}")
+
+    templates = bundle.get("minutes_templates")
+    if templates is not None:
+        if not isinstance(templates, list):
+            errors.append("minutes_templates는 목록이어야 합니다.")
+        else:
+            for index, template in enumerate(templates):
+                try:
+                    import_template(template, dry_run=True)
+                except TemplateError as exc:
+                    errors.append(f"minutes_templates[{index}]: {exc}")
+
+    glossary = bundle.get("glossary")
+    if glossary is not None:
+        if not isinstance(glossary, dict):
+            errors.append("glossary는 {키워드: {weight, last_updated}} 객체여야 합니다.")
+        else:
+            for keyword, entry in glossary.items():
+                weight = entry.get("weight") if isinstance(entry, dict) else None
+                if isinstance(weight, bool) or not isinstance(weight, (int, float)) or weight < 0:
+                    errors.append(f"glossary.{keyword}: weight는 0 이상의 숫자여야 합니다.")
+    return errors
+
+
+def import_bundle(bundle: Any, mode: str = "merge", dry_run: bool = False) -> Dict[str, Any]:
+    """Validate and apply a bundle; raises :class:`BundleError` without writing anything."""
+    if mode not in IMPORT_MODES:
+        raise BundleError([f"mode는 {' 또는 '.join(IMPORT_MODES)}여야 합니다."])
+    errors = _validate(bundle)
+    if errors:
+        raise BundleError(errors)
+
+    report: Dict[str, Any] = {"mode": mode, "dry_run": dry_run, "sections": {}}
+    replace = mode == "replace"
+
+    prompts = bundle.get("prompts")
+    if prompts is not None:
+        prompt_dir = get_prompt_dir()
+        removed = [name for name in PROMPT_TEMPLATES
+                   if replace and name not in prompts and (prompt_dir / name).exists()]
+        if not dry_run:
+            prompt_dir.mkdir(parents=True, exist_ok=True)
+            for filename, text in prompts.items():
+                (prompt_dir / filename).write_text(text, encoding="utf-8")
+            for filename in removed:
+                (prompt_dir / filename).unlink()
+        report["sections"]["prompts"] = {"written": sorted(prompts), "removed": removed}
+
+    templates = bundle.get("minutes_templates")
+    if templates is not None:
+        incoming = {str(template["id"]) for template in templates}
+        existing = {t["id"] for t in list_templates() if not t.get("builtin")}
+        removed = sorted(existing - incoming) if replace else []
+        if not dry_run:
+            for template in templates:
+                import_template(template)
+            for template_id in removed:
+                delete_template(template_id)
+        report["sections"]["minutes_templates"] = {
+            "created": len(incoming - existing),
+            "updated": len(incoming & existing),
+            "removed": len(removed),
+        }
+
+    glossary = bundle.get("glossary")
+    if glossary is not None:
+        total = None if dry_run else _glossary().import_entries(glossary, replace=replace)
+        report["sections"]["glossary"] = {"imported": len(glossary), "total": total}
+
+    return report
+
+
+def main():
+    """CLI for exporting/importing configuration bundles."""
+    import argparse
+
+    parser = argparse.ArgumentParser(description="Export/import prompt, minutes template and glossary bundles")
+    subparsers = parser.add_subparsers(dest="command", required=True)
+
+    export_parser = subparsers.add_parser("export", help="Write the current setup to a bundle file")
+    export_parser.add_argument("path", help="Output JSON file")
+    export_parser.add_argument("--sections", nargs="+", choices=SECTIONS, help="Sections to export (default: all)")
+
+    import_parser = subparsers.add_parser("import", help="Apply a bundle file")
+    import_parser.add_argument("path", help="Bundle JSON file")
+    import_parser.add_argument("--replace", action="store_true", help="Replace included sections instead of merging")
+    import_parser.add_argument("--dry-run", action="store_true", help="Validate and report without writing")
+
+    args = parser.parse_args()
+    try:
+        if args.command == "export":
+            bundle = export_bundle(args.sections)
+            with open(args.path, "w", encoding="utf-8") as f:
+                json.dump(bundle, f, ensure_ascii=False, indent=2)
+            print(f"✓ 설정 번들 저장 완료: {args.path}")
+        else:
+            with open(args.path, "r", encoding="utf-8") as f:
+                bundle = json.load(f)
+            report = import_bundle(bundle, "replace" if args.replace else "merge", args.dry_run)
+            print(json.dumps(report, ensure_ascii=False, indent=2))
+            if not args.dry_run and "prompts" in report["sections"]:
+                print("프롬프트는 서버에서 POST /admin/reload 후 적용됩니다.")
+    except (OSError, json.JSONDecodeError, BundleError) as exc:
+        print(f"오류: {exc}")
+        raise SystemExit(1)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,103 @@
+"""Cross-origin (CORS) policy for the HTTP server.
+
+The web UI is served by the server itself, so by default no CORS headers
+are sent and browsers only let same-origin pages call the API. To call it
+from another origin (a separate frontend, a browser extension, a tunnel
+host), list the origins explicitly:
+
+    CORS_ALLOWED_ORIGINS=https://app.example.com,http://localhost:5173
+    CORS_ALLOWED_METHODS=GET,POST,OPTIONS
+    CORS_ALLOW_CREDENTIALS=false
+
+``*`` allows any origin and cannot be combined with credentials (the
+setting is ignored with a warning). Allowed origins get the
+``Access-Control-*`` headers on every response and on ``OPTIONS``
+preflights; preflights from other origins are answered with 403.
+"""
+
+from __future__ import annotations
+
+import logging
+from typing import Dict, Iterable, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+    from .deadline import DEADLINE_HEADER
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+    from deadline import DEADLINE_HEADER  # type: ignore
+
+
+def _split(value: str) -> List[str]:
+    return [item.strip() for item in (value or "").split(",") if item.strip()]
+
+
+CORS_ALLOWED_ORIGINS = [origin.rstrip("/") for origin in _split(get_config_value("CORS_ALLOWED_ORIGINS", "", str))]
+CORS_ALLOWED_METHODS = [m.upper() for m in _split(get_config_value("CORS_ALLOWED_METHODS", "GET,POST,OPTIONS", str))]
+CORS_ALLOW_CREDENTIALS = get_config_value("CORS_ALLOW_CREDENTIALS", False, bool)
+# 서버가 읽는 요청 헤더 (인증, 원시 업로드, 작업 제한 시간)
+CORS_ALLOWED_HEADERS = ("Content-Type", "Authorization", "X-API-Key", "X-Filename", "X-Steps", DEADLINE_HEADER)
+# 스크립트에서 읽을 수 있게 노출할 응답 헤더
+CORS_EXPOSED_HEADERS = ("Content-Disposition", "Content-Length")
+CORS_MAX_AGE_SECONDS = 600
+
+if "*" in CORS_ALLOWED_ORIGINS and CORS_ALLOW_CREDENTIALS:
+    logging.warning("CORS_ALLOWED_ORIGINS=*에는 CORS_ALLOW_CREDENTIALS를 쓸 수 없어 자격 증명 허용을 끕니다.")
+    CORS_ALLOW_CREDENTIALS = False
+
+
+def origin_allowed(origin: Optional[str], allowed: Iterable[str] = None) -> bool:
+    allowed = CORS_ALLOWED_ORIGINS if allowed is None else list(allowed)
+    if not origin or origin == "null":
+        return False
+    return "*" in allowed or origin.rstrip("/") in allowed
+
+
+def cors_headers(origin: Optional[str], preflight: bool = False) -> Dict[str, str]:
+    """``Access-Control-*`` headers for a request from ``origin`` (empty when it is not allowed)."""
+    if not origin_allowed(origin):
+        return {}
+    any_origin = "*" in CORS_ALLOWED_ORIGINS
+    headers = {"Access-Control-Allow-Origin": "*" if any_origin else origin}
+    if not any_origin:
+        headers["Vary"] = "Origin"
+    if CORS_ALLOW_CREDENTIALS:
+        headers["Access-Control-Allow-Credentials"] = "true"
+    if preflight:
+        headers["Access-Control-Allow-Methods"] = ", ".join(CORS_ALLOWED_METHODS)
+        headers["Access-Control-Allow-Headers"] = ", ".join(CORS_ALLOWED_HEADERS)
+        headers["Access-Control-Max-Age"] = str(CORS_MAX_AGE_SECONDS)
+    else:
+        headers["Access-Control-Expose-Headers"] = ", ".join(CORS_EXPOSED_HEADERS)
+    return headers
+
+
+class CorsMixin:
+    """Mixin for ``BaseHTTPRequestHandler`` adding the CORS headers and ``OPTIONS`` preflights."""
+
+    def end_headers(self):
+        if not getattr(self, "_cors_sent", False):
+            self._cors_sent = True
+            for name, value in cors_headers(self.headers.get("Origin") if self.headers else None).items():
+                self.send_header(name, value)
+        super().end_headers()
+
+    def send_response(self, code, message=None):
+        self._cors_sent = False
+        super().send_response(code, message)
+
+    def do_OPTIONS(self):
+        origin = self.headers.get("Origin")
+        method = (self.headers.get("Access-Control-Request-Method") or "").upper()
+        if not origin_allowed(origin) or (method and method not in CORS_ALLOWED_METHODS):
+            self.send_response(403)
+            self._cors_sent = True
+            self.send_header("Content-Length", "0")
+            self.end_headers()
+            return
+        self.send_response(204)
+        self._cors_sent = True
+        for name, value in cors_headers(origin, preflight=True).items():
+            self.send_header(name, value)
+        self.send_header("Content-Length", "0")
+        self.end_headers()
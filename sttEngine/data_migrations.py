@@ -0,0 +1,217 @@
+"""Versioned migrations of the on-disk data formats.
+
+The history, the vector index, segments files and the file registry have
+each changed shape over time, and every change used to be fixed up ad hoc
+wherever the data happened to be loaded. A :class:`Migration` now names the
+format it upgrades, the version it brings that format to and the files it
+may rewrite. ``DB/data_versions.json`` records the version each format is
+at and every migration that ran::
+
+    {"formats": {"history": 1, "segments": 1, ...},
+     "applied": [{"id": "segments@1", "applied_at": "...", "report": {...},
+                  "backup": "backups/migrations/20261016-093000-segments-v1"}]}
+
+At startup (``DATA_MIGRATIONS_AUTO``) the migrations newer than the recorded
+version run in list order. Before one runs, the files it may rewrite are
+copied to ``DB/backups/migrations/{time}-{format}-v{version}/`` with their
+path under the DB folder (``DATA_MIGRATION_BACKUPS``; the newest
+``DATA_MIGRATION_BACKUP_KEEP`` backups are kept). A failing migration is
+not recorded, stops the migrations after it and is reported as
+``last_error``; it runs again on the next start.
+
+A dry run calls every pending migration with ``dry_run=True`` — nothing is
+written, backed up or recorded — and returns what each would change::
+
+    ./run.sh --migrate-dry-run
+    POST /admin/migrations/run {"dry_run": true}
+
+Migrations must be idempotent: a new install starts at version 0 and runs
+every migration against whatever data is there (usually none).
+"""
+
+from __future__ import annotations
+
+import json
+import shutil
+import sqlite3
+import threading
+import time
+from contextlib import closing
+from dataclasses import dataclass
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Callable, Dict, Iterable, List, Optional, Sequence
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value, get_db_base_path
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value, get_db_base_path  # type: ignore
+
+DB_BASE_PATH = get_db_base_path()
+DATA_VERSIONS_FILE = DB_BASE_PATH / "data_versions.json"
+BACKUP_DIR = DB_BASE_PATH / "backups" / "migrations"
+DATA_MIGRATIONS_AUTO = get_config_value("DATA_MIGRATIONS_AUTO", True, bool)
+DATA_MIGRATION_BACKUPS = get_config_value("DATA_MIGRATION_BACKUPS", True, bool)
+DATA_MIGRATION_BACKUP_KEEP = max(1, get_config_value("DATA_MIGRATION_BACKUP_KEEP", 5, int))
+# data_versions.json에 남기는 실행 기록 수
+HISTORY_LIMIT = 100
+
+_migration_lock = threading.Lock()
+
+
+class MigrationError(RuntimeError):
+    """Raised for an invalid migration list."""
+
+
+@dataclass(frozen=True)
+class Migration:
+    """One step of a data format: ``apply(dry_run)`` brings ``format`` to ``version`` and returns a report."""
+
+    format: str
+    version: int
+    description: str
+    apply: Callable[[bool], Dict[str, Any]]
+    backup_paths: Callable[[], Iterable[Path]] = lambda: ()
+
+    @property
+    def id(self) -> str:
+        return f"{self.format}@{self.version}"
+
+
+def check_order(migrations: Sequence[Migration]) -> None:
+    """Raise :class:`MigrationError` unless each format's versions increase along the list."""
+    latest: Dict[str, int] = {}
+    for migration in migrations:
+        if migration.version <= latest.get(migration.format, 0):
+            raise MigrationError(
+                f"마이그레이션 {migration.id}의 버전이 앞선 {migration.format} 마이그레이션보다 높아야 합니다."
+            )
+        latest[migration.format] = migration.version
+
+
+def _load(path: Path = DATA_VERSIONS_FILE) -> Dict[str, Any]:
+    try:
+        with open(path, "r", encoding="utf-8") as f:
+            state = json.load(f)
+        return state if isinstance(state, dict) else {}
+    except (OSError, ValueError):
+        return {}
+
+
+def _save(state: Dict[str, Any], path: Path = DATA_VERSIONS_FILE) -> None:
+    path.parent.mkdir(parents=True, exist_ok=True)
+    tmp_path = path.with_name(f"{path.name}.tmp")
+    with open(tmp_path, "w", encoding="utf-8") as f:
+        json.dump(state, f, ensure_ascii=False, indent=2)
+    tmp_path.replace(path)
+
+
+def applied_versions(state: Optional[Dict[str, Any]] = None) -> Dict[str, int]:
+    formats = (state if state is not None else _load()).get("formats")
+    return dict(formats) if isinstance(formats, dict) else {}
+
+
+def pending_migrations(migrations: Sequence[Migration],
+                       state: Optional[Dict[str, Any]] = None) -> List[Migration]:
+    """Migrations newer than the recorded version of their format, in list order."""
+    versions = applied_versions(state)
+    return [m for m in migrations if m.version > int(versions.get(m.format) or 0)]
+
+
+def _copy_path(source: Path, target: Path) -> None:
+    target.parent.mkdir(parents=True, exist_ok=True)
+    if source.is_dir():
+        shutil.copytree(source, target, dirs_exist_ok=True)
+    elif source.suffix in (".sqlite3", ".sqlite", ".db"):
+        # 열린 연결이 있어도 일관된 사본이 되도록 SQLite 백업 API 사용
+        with closing(sqlite3.connect(source)) as src, closing(sqlite3.connect(target)) as dst:
+            src.backup(dst)
+    else:
+        shutil.copy2(source, target)
+
+
+def backup_files(migration: Migration, paths: Iterable[Path]) -> Optional[str]:
+    """Copy ``paths`` into a new backup folder; its path under the DB folder, or ``None`` when nothing exists."""
+    existing = [Path(p) for p in paths if Path(p).exists()]
+    if not existing:
+        return None
+    folder = BACKUP_DIR / f"{datetime.now().strftime('%Y%m%d-%H%M%S')}-{migration.format}-v{migration.version}"
+    suffix = 1
+    while folder.exists():
+        suffix += 1
+        folder = folder.with_name(f"{folder.name.rsplit('~', 1)[0]}~{suffix}")
+    for path in existing:
+        try:
+            relative = path.resolve().relative_to(DB_BASE_PATH.resolve())
+        except ValueError:
+            relative = Path(path.name)
+        _copy_path(path, folder / relative)
+    _prune_backups()
+    return folder.relative_to(DB_BASE_PATH).as_posix()
+
+
+def _prune_backups(keep: int = None) -> None:
+    keep = keep or DATA_MIGRATION_BACKUP_KEEP
+    folders = sorted((p for p in BACKUP_DIR.iterdir() if p.is_dir()), key=lambda p: p.name)
+    for folder in folders[:-keep]:
+        shutil.rmtree(folder, ignore_errors=True)
+
+
+def run_migrations(migrations: Sequence[Migration], dry_run: bool = False,
+                   backup: Optional[bool] = None) -> Dict[str, Any]:
+    """Run the pending migrations in order.
+
+    Returns ``{"dry_run", "ran": [{"id", "description", "report",
+    "backup", "duration_seconds"}], "failed": {"id", "error"} | None,
+    "formats"}``. Stops at the first migration that raises.
+    """
+    check_order(migrations)
+    backup = DATA_MIGRATION_BACKUPS if backup is None else backup
+    with _migration_lock:
+        state = _load()
+        versions = applied_versions(state)
+        ran: List[Dict[str, Any]] = []
+        failed = None
+        for migration in pending_migrations(migrations, state):
+            started = time.monotonic()
+            entry: Dict[str, Any] = {"id": migration.id, "description": migration.description, "backup": None}
+            try:
+                if backup and not dry_run:
+                    entry["backup"] = backup_files(migration, migration.backup_paths())
+                entry["report"] = migration.apply(dry_run)
+            except Exception as exc:  # noqa: BLE001 - 실패한 단계를 기록하고 멈춤
+                failed = {"id": migration.id, "error": str(exc), "backup": entry["backup"],
+                          "at": datetime.now().isoformat()}
+                break
+            entry["duration_seconds"] = round(time.monotonic() - started, 3)
+            ran.append(entry)
+            if dry_run:
+                continue
+            versions[migration.format] = migration.version
+            state["formats"] = versions
+            state["applied"] = (list(state.get("applied") or [])
+                                + [{**entry, "applied_at": datetime.now().isoformat()}])[-HISTORY_LIMIT:]
+            state.pop("last_error", None)
+            _save(state)
+        if failed and not dry_run:
+            state["last_error"] = failed
+            _save(state)
+        return {"dry_run": dry_run, "ran": ran, "failed": failed, "formats": versions}
+
+
+def migration_status(migrations: Sequence[Migration]) -> Dict[str, Any]:
+    """Recorded and latest version of every format, pending migrations and recent runs."""
+    state = _load()
+    versions = applied_versions(state)
+    latest: Dict[str, int] = {}
+    for migration in migrations:
+        latest[migration.format] = max(latest.get(migration.format, 0), migration.version)
+    return {
+        "formats": {name: {"applied": int(versions.get(name) or 0), "latest": version}
+                    for name, version in latest.items()},
+        "pending": [{"id": m.id, "description": m.description} for m in pending_migrations(migrations, state)],
+        "applied": list(state.get("applied") or [])[-20:],
+        "last_error": state.get("last_error"),
+        "auto": DATA_MIGRATIONS_AUTO,
+        "backups": DATA_MIGRATION_BACKUPS,
+    }
@@ -0,0 +1,116 @@
+"""Per-request deadlines for workflow runs.
+
+A workflow gets an overall time budget from the ``X-Request-Deadline``
+header (seconds, on ``/process`` and ``/upload/raw``), ``deadline_seconds``
+in the ``/process`` body, or ``WORKFLOW_DEADLINE_MINUTES`` (0 = no limit).
+``run_workflow`` enters :func:`deadline_scope`, and the calls that can
+block for long read the active deadline from a context variable:
+
+* Ollama summary calls and embedding requests cap their HTTP/future
+  timeouts at the remaining time (:func:`deadline_timeout`) and raise
+  :class:`DeadlineExceeded` instead of retrying once it has passed
+  (:func:`check_deadline`);
+* Whisper inference and queue waits are stopped through the task's
+  cancellation event, which :meth:`Deadline.arm` sets when time runs out.
+
+Steps that finished before the deadline keep their artifacts; the workflow
+answers with a ``deadline_exceeded`` error listing them.
+"""
+
+from __future__ import annotations
+
+import threading
+import time
+from contextlib import contextmanager
+from contextvars import ContextVar
+from typing import Callable, Iterator, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+WORKFLOW_DEADLINE_MINUTES = max(0.0, get_config_value("WORKFLOW_DEADLINE_MINUTES", 0, float))
+DEADLINE_HEADER = "X-Request-Deadline"
+# 헤더/본문으로 받을 수 있는 최대 제한 시간 (하루)
+MAX_DEADLINE_SECONDS = 24 * 3600
+
+_current: ContextVar[Optional["Deadline"]] = ContextVar("recordroute_deadline", default=None)
+
+
+class DeadlineExceeded(TimeoutError):
+    """Raised when work is attempted after the request deadline passed."""
+
+
+class Deadline:
+    """An absolute expiry time for one workflow run."""
+
+    def __init__(self, seconds: float):
+        self.seconds = float(seconds)
+        self.expires_at = time.monotonic() + self.seconds
+
+    def remaining(self) -> float:
+        return max(0.0, self.expires_at - time.monotonic())
+
+    @property
+    def expired(self) -> bool:
+        return self.remaining() <= 0
+
+    def check(self, stage: str) -> None:
+        if self.expired:
+            raise DeadlineExceeded(f"작업 제한 시간({self.seconds:g}초)이 지나 {stage} 단계를 중단했습니다.")
+
+    def arm(self, on_expire: Callable[[], None]) -> threading.Timer:
+        """Call ``on_expire`` (e.g. set the task's cancel event) when the deadline passes."""
+        timer = threading.Timer(self.remaining(), on_expire)
+        timer.daemon = True
+        timer.start()
+        return timer
+
+
+def parse_deadline_seconds(value) -> Optional[float]:
+    """Deadline in seconds from a header/body value (``None`` when absent); ``ValueError`` when invalid."""
+    if value is None or value == "":
+        return None
+    seconds = float(value)
+    if not 0 < seconds <= MAX_DEADLINE_SECONDS:
+        raise ValueError(f"제한 시간은 0초보다 크고 {MAX_DEADLINE_SECONDS}초 이하여야 합니다.")
+    return seconds
+
+
+def resolve_deadline(seconds: Optional[float] = None) -> Optional[Deadline]:
+    """Deadline for a new run: the requested ``seconds`` or ``WORKFLOW_DEADLINE_MINUTES``."""
+    if seconds:
+        return Deadline(seconds)
+    if WORKFLOW_DEADLINE_MINUTES > 0:
+        return Deadline(WORKFLOW_DEADLINE_MINUTES * 60)
+    return None
+
+
+@contextmanager
+def deadline_scope(deadline: Optional[Deadline]) -> Iterator[Optional[Deadline]]:
+    token = _current.set(deadline)
+    try:
+        yield deadline
+    finally:
+        _current.reset(token)
+
+
+def current_deadline() -> Optional[Deadline]:
+    return _current.get()
+
+
+def check_deadline(stage: str) -> None:
+    """Raise :class:`DeadlineExceeded` when the active deadline has passed (no-op without one)."""
+    deadline = _current.get()
+    if deadline is not None:
+        deadline.check(stage)
+
+
+def deadline_timeout(default: float) -> float:
+    """``default`` capped at the time left before the active deadline."""
+    deadline = _current.get()
+    if deadline is None:
+        return default
+    # 0초 타임아웃은 라이브러리마다 의미가 달라 최소값을 둠
+    return max(0.1, min(default, deadline.remaining()))
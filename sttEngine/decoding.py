@@ -0,0 +1,104 @@
+"""Whisper decoding strategies with temperature fallback.
+
+openai-whisper decodes every 30 s window at the first temperature of its
+schedule and re-decodes the window at the next one when the result fails
+the compression-ratio (looping text) or average-logprob (low confidence)
+thresholds of :class:`hallucination.HallucinationThresholds`. Strategies
+(``STT_DECODING_STRATEGY`` or ``model_settings.decoding``):
+
+    greedy    temperature 0 only; fastest, no retries
+    fallback  greedy first, then sampling at +``STT_TEMPERATURE_INCREMENT``
+              steps up to 1.0 (``STT_BEST_OF`` candidates) — Whisper's default
+    beam      beam search (``STT_BEAM_SIZE``) first, then the same fallback
+
+The strategy used and how many segments needed a fallback temperature are
+stored in the segments file as ``decoding``::
+
+    {"strategy": "fallback", "temperatures": [0.0, 0.2, ...], "beam_size": null,
+     "best_of": 5, "segments": 120, "fallback_segments": 3,
+     "by_temperature": {"0.0": 117, "0.2": 2, "0.4": 1}}
+
+Remote backends get the strategy only where their API has a matching field
+(whisper.cpp ``temperature_inc``); their statistics come from the
+per-segment ``temperature`` when the server reports it.
+"""
+
+from __future__ import annotations
+
+from typing import Any, Dict, Iterable, List, Optional, Tuple
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+STRATEGIES = ("greedy", "fallback", "beam")
+STT_DECODING_STRATEGY = get_config_value("STT_DECODING_STRATEGY", "fallback", str).strip().lower()
+STT_TEMPERATURE_INCREMENT = min(1.0, max(0.05, get_config_value("STT_TEMPERATURE_INCREMENT", 0.2, float)))
+STT_BEAM_SIZE = max(2, get_config_value("STT_BEAM_SIZE", 5, int))
+STT_BEST_OF = max(1, get_config_value("STT_BEST_OF", 5, int))
+
+
+class DecodingStrategyError(ValueError):
+    """Raised for unknown decoding strategy names."""
+
+
+def resolve_strategy(name: Optional[str] = None) -> str:
+    """Validated strategy name (``None``/empty = ``STT_DECODING_STRATEGY``)."""
+    strategy = (name or STT_DECODING_STRATEGY or "fallback").strip().lower()
+    if strategy not in STRATEGIES:
+        raise DecodingStrategyError(
+            f"알 수 없는 디코딩 전략: {strategy} (사용 가능: {', '.join(STRATEGIES)})"
+        )
+    return strategy
+
+
+def temperature_schedule(strategy: str) -> Tuple[float, ...]:
+    if strategy == "greedy":
+        return (0.0,)
+    steps = int(round(1.0 / STT_TEMPERATURE_INCREMENT))
+    return tuple(round(min(1.0, step * STT_TEMPERATURE_INCREMENT), 2) for step in range(steps + 1))
+
+
+def whisper_options(strategy: str) -> Dict[str, Any]:
+    """Decoding options in openai-whisper ``transcribe()`` keyword form."""
+    options: Dict[str, Any] = {"temperature": temperature_schedule(strategy)}
+    if strategy != "greedy":
+        # best_of는 0보다 큰 온도에서 샘플링할 후보 수
+        options["best_of"] = STT_BEST_OF
+    if strategy == "beam":
+        options["beam_size"] = STT_BEAM_SIZE
+    return options
+
+
+def describe(strategy: str) -> Dict[str, Any]:
+    """Requested decoding settings, before any segment statistics."""
+    options = whisper_options(strategy)
+    return {
+        "strategy": strategy,
+        "temperatures": list(options["temperature"]),
+        "beam_size": options.get("beam_size"),
+        "best_of": options.get("best_of"),
+    }
+
+
+def summarize_decoding(decoding: Dict[str, Any], segments: Iterable[Dict[str, Any]]) -> Dict[str, Any]:
+    """Add per-temperature segment counts to ``decoding`` (segments without ``temperature`` are skipped)."""
+    temperatures: List[float] = []
+    for segment in segments:
+        try:
+            temperatures.append(float(segment["temperature"]))
+        except (KeyError, TypeError, ValueError):
+            continue
+    if not temperatures:
+        return dict(decoding)
+    by_temperature: Dict[str, int] = {}
+    for temperature in sorted(temperatures):
+        key = f"{temperature:.1f}"
+        by_temperature[key] = by_temperature.get(key, 0) + 1
+    return {
+        **decoding,
+        "segments": len(temperatures),
+        "fallback_segments": sum(1 for temperature in temperatures if temperature > 0),
+        "by_temperature": by_temperature,
+    }
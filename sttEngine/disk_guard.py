@@ -0,0 +1,140 @@
+"""Free-space checks before writing large artifacts.
+
+A workflow that runs out of disk halfway leaves a truncated transcript or a
+half-written model file behind and fails with a bare ``ENOSPC``. Before a
+workflow starts and before Whisper downloads a model, the expected size is
+compared with the free space of the target disk, and the job fails right
+away with :class:`InsufficientDiskSpace` (error code ``disk_full``).
+
+Estimates are deliberately generous: the temporary WAV conversion
+(16 kHz mono PCM) and the transcript/segment files scale with the audio
+duration, summaries and embeddings get a fixed allowance, and
+``DISK_GUARD_RESERVE_MB`` is always kept free on top.
+"""
+
+from __future__ import annotations
+
+import os
+import shutil
+from pathlib import Path
+from typing import Iterable, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+DISK_GUARD_ENABLED = get_config_value("DISK_GUARD_ENABLED", True, bool)
+DISK_GUARD_RESERVE_MB = max(0, get_config_value("DISK_GUARD_RESERVE_MB", 500, int))
+
+MB = 1024 * 1024
+# 16kHz 모노 pcm_s16le 임시 WAV
+WAV_BYTES_PER_SECOND = 16000 * 2
+# 전사 마크다운 + 세그먼트 JSON + 체크포인트 (넉넉하게)
+TRANSCRIPT_BYTES_PER_SECOND = 512
+# 길이를 모를 때 압축 오디오 비트레이트로 가정 (128kbps)
+ASSUMED_AUDIO_BYTES_PER_SECOND = 16000
+SUMMARY_BYTES = 2 * MB  # 요약, 디버그 산출물, 회의록
+EMBEDDING_BYTES = 1 * MB
+
+# whisper.load_model이 내려받는 체크포인트 크기 (대략)
+WHISPER_MODEL_BYTES = {
+    "tiny": 75 * MB, "tiny.en": 75 * MB,
+    "base": 145 * MB, "base.en": 145 * MB,
+    "small": 484 * MB, "small.en": 484 * MB,
+    "medium": 1500 * MB, "medium.en": 1500 * MB,
+    "large": 3000 * MB, "large-v1": 3000 * MB, "large-v2": 3000 * MB, "large-v3": 3000 * MB,
+    "large-v3-turbo": 1600 * MB, "turbo": 1600 * MB,
+}
+# 별칭은 다른 이름의 파일로 저장됨
+_MODEL_FILES = {"large": "large-v3", "turbo": "large-v3-turbo"}
+
+
+class InsufficientDiskSpace(Exception):
+    """Raised when the target disk has less free space than the job needs."""
+
+    def __init__(self, path: Path, required: int, free: int, purpose: str):
+        self.path = path
+        self.required = required
+        self.free = free
+        super().__init__(
+            f"디스크 공간이 부족합니다 ({purpose}): {path}에 {format_mb(required)} 필요, "
+            f"여유 {format_mb(free)}"
+        )
+
+
+def format_mb(size: int) -> str:
+    return f"{size / 1024 ** 3:.1f} GB" if size >= 1024 ** 3 else f"{size / MB:.0f} MB"
+
+
+def _existing_parent(path: Path) -> Path:
+    path = Path(path)
+    while not path.exists() and path != path.parent:
+        path = path.parent
+    return path
+
+
+def free_bytes(path: Path) -> int:
+    return shutil.disk_usage(_existing_parent(path)).free
+
+
+def ensure_free_space(path: Path, required: int, purpose: str) -> None:
+    """Raise :class:`InsufficientDiskSpace` unless ``required`` bytes plus the reserve fit on ``path``'s disk."""
+    if not DISK_GUARD_ENABLED:
+        return
+    needed = required + DISK_GUARD_RESERVE_MB * MB
+    try:
+        free = free_bytes(path)
+    except OSError:
+        return  # 확인할 수 없으면 막지 않음
+    if free < needed:
+        raise InsufficientDiskSpace(Path(path), needed, free, purpose)
+
+
+def estimate_workflow_bytes(source: Path, file_type: str, steps: Iterable[str],
+                            duration_seconds: Optional[float] = None, has_transcript: bool = False) -> int:
+    """Expected bytes written by a workflow over ``source``.
+
+    ``has_transcript`` means summary/embedding steps reuse an existing STT
+    result instead of transcribing first.
+    """
+    steps = set(steps)
+    try:
+        source_size = Path(source).stat().st_size
+    except OSError:
+        source_size = 0
+
+    total = 0
+    if file_type == "audio":
+        if "stt" in steps or (not has_transcript and steps & {"summary", "embedding"}):
+            seconds = duration_seconds or source_size / ASSUMED_AUDIO_BYTES_PER_SECOND
+            total += int(seconds * (WAV_BYTES_PER_SECOND + TRANSCRIPT_BYTES_PER_SECOND))
+    elif file_type in ("text", "pdf"):
+        total += source_size  # 출력 폴더로 복사/추출
+    if "summary" in steps:
+        total += SUMMARY_BYTES
+    if steps & {"summary", "embedding"}:
+        total += EMBEDDING_BYTES
+    return total
+
+
+def whisper_cache_dir() -> Path:
+    """Folder ``whisper.load_model`` downloads named models into."""
+    cache_home = os.getenv("XDG_CACHE_HOME") or os.path.join(os.path.expanduser("~"), ".cache")
+    return Path(cache_home) / "whisper"
+
+
+def whisper_model_file(model_identifier: str) -> Optional[Path]:
+    """Cache file a named Whisper model is downloaded to (``None`` for local paths and unknown names)."""
+    if model_identifier not in WHISPER_MODEL_BYTES:
+        return None
+    return whisper_cache_dir() / f"{_MODEL_FILES.get(model_identifier, model_identifier)}.pt"
+
+
+def ensure_model_download_space(model_identifier: str) -> None:
+    """Check space for a named Whisper model that is not downloaded yet (local paths are skipped)."""
+    model_file = whisper_model_file(model_identifier)
+    if model_file is None or model_file.exists():
+        return
+    ensure_free_space(model_file.parent, WHISPER_MODEL_BYTES[model_identifier],
+                      f"Whisper '{model_identifier}' 모델 다운로드")
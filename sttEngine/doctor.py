@@ -0,0 +1,252 @@
+"""Environment self-test (``recordroute doctor``).
+
+Most support issues are environment problems, not bugs: ffmpeg missing from
+PATH, Ollama not running or the summary model never pulled, a full disk, a
+port taken by another process, a DB folder on a read-only mount. This module
+checks all of them without starting the server and prints one line per
+check::
+
+    python -m sttEngine.doctor          # or: ./run.sh doctor
+    python -m sttEngine.doctor --json   # machine-readable report
+
+Each check is ``ok``, ``warn`` (works, but something will be slow or degrade)
+or ``fail`` (processing will not work). The exit code is 1 when any check
+fails.
+"""
+
+from __future__ import annotations
+
+import importlib.util
+import json
+import os
+import shutil
+import socket
+import subprocess
+import sys
+import tempfile
+from dataclasses import asdict, dataclass
+from pathlib import Path
+from typing import Callable, List, Optional
+
+import requests
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value, get_db_base_path, get_default_model, get_model_for_task
+    from .record_layout import get_record_layout
+    from .text_utils import display_width
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value, get_db_base_path, get_default_model, get_model_for_task  # type: ignore
+    from record_layout import get_record_layout  # type: ignore
+    from text_utils import display_width  # type: ignore
+
+OLLAMA_HOST = get_config_value("OLLAMA_HOST", "http://localhost:11434", str).rstrip("/")
+DOCTOR_MIN_FREE_GB = get_config_value("DOCTOR_MIN_FREE_GB", 2, float)
+SERVER_PORTS = {"HTTP": 8080, "WebSocket": 8765}
+
+# Whisper 모델 이름 → ~/.cache/whisper 에 저장되는 파일명
+WHISPER_MODEL_FILES = {
+    "tiny": "tiny.pt",
+    "base": "base.pt",
+    "small": "small.pt",
+    "medium": "medium.pt",
+    "large": "large-v3.pt",
+    "large-v3": "large-v3.pt",
+    "large-v3-turbo": "large-v3-turbo.pt",
+    "turbo": "large-v3-turbo.pt",
+}
+
+STATUS_ICONS = {"ok": "✓", "warn": "!", "fail": "✗"}
+
+
+@dataclass
+class CheckResult:
+    name: str
+    status: str  # ok | warn | fail
+    message: str
+    hint: str = ""
+
+
+def check_ffmpeg() -> List[CheckResult]:
+    results = []
+    for tool in ("ffmpeg", "ffprobe"):
+        path = shutil.which(tool)
+        if not path:
+            results.append(CheckResult(tool, "fail", "PATH에서 찾을 수 없습니다.",
+                                       f"{tool}를 설치하고 PATH에 추가하세요 (예: brew install ffmpeg, apt install ffmpeg)."))
+            continue
+        try:
+            output = subprocess.run([path, "-version"], capture_output=True, text=True, timeout=10).stdout
+            version = output.splitlines()[0] if output else path
+        except (OSError, subprocess.SubprocessError) as exc:
+            results.append(CheckResult(tool, "fail", f"실행할 수 없습니다: {exc}"))
+            continue
+        results.append(CheckResult(tool, "ok", version))
+    return results
+
+
+def _whisper_cache_dir() -> Path:
+    base = os.environ.get("XDG_CACHE_HOME") or Path.home() / ".cache"
+    return Path(base) / "whisper"
+
+
+def check_stt_model() -> List[CheckResult]:
+    backend = get_config_value("STT_BACKEND", "whisper", str).strip().lower()
+    if backend != "whisper":
+        return [CheckResult("STT 모델", "ok", f"STT_BACKEND={backend} (로컬 Whisper 모델 확인 생략)")]
+
+    results = []
+    missing = [name for name in ("whisper", "torch") if importlib.util.find_spec(name) is None]
+    if missing:
+        results.append(CheckResult("Whisper 패키지", "fail", f"설치되지 않음: {', '.join(missing)}",
+                                   "pip install -r sttEngine/requirements.txt 를 실행하세요."))
+
+    model = get_model_for_task("TRANSCRIBE", get_default_model("TRANSCRIBE"))
+    if model.endswith(".pt"):
+        path = Path(model).expanduser()
+        if path.is_file():
+            results.append(CheckResult("STT 모델", "ok", f"{path} ({path.stat().st_size / 1024 ** 3:.1f} GB)"))
+        else:
+            results.append(CheckResult("STT 모델", "fail", f"모델 파일이 없습니다: {path}",
+                                       "TRANSCRIBE_MODEL_* 설정의 경로를 확인하세요."))
+        return results
+
+    path = _whisper_cache_dir() / WHISPER_MODEL_FILES.get(model, f"{model}.pt")
+    if path.is_file():
+        results.append(CheckResult("STT 모델", "ok", f"{model} → {path}"))
+    else:
+        results.append(CheckResult("STT 모델", "warn", f"{model} 모델이 아직 내려받아지지 않았습니다 ({path}).",
+                                   "첫 변환 때 자동으로 내려받습니다 (수 GB, 인터넷 연결 필요)."))
+    return results
+
+
+def check_ollama() -> List[CheckResult]:
+    try:
+        response = requests.get(f"{OLLAMA_HOST}/api/tags", timeout=5)
+        response.raise_for_status()
+        installed = [item.get("name", "") for item in response.json().get("models", [])]
+    except requests.RequestException as exc:
+        return [CheckResult("Ollama", "fail", f"{OLLAMA_HOST} 에 연결할 수 없습니다 ({type(exc).__name__})",
+                            "`ollama serve`로 시작하거나 OLLAMA_HOST 설정을 확인하세요.")]
+    except ValueError:
+        return [CheckResult("Ollama", "fail", f"{OLLAMA_HOST} 응답이 Ollama API 형식이 아닙니다.",
+                            "OLLAMA_HOST가 다른 서비스를 가리키고 있지 않은지 확인하세요.")]
+
+    results = [CheckResult("Ollama", "ok", f"{OLLAMA_HOST} (모델 {len(installed)}개)")]
+    for task, label in (("SUMMARY", "요약 모델"), ("EMBEDDING", "임베딩 모델")):
+        model = get_model_for_task(task, get_default_model(task))
+        found = any(name == model or name == f"{model}:latest" for name in installed)
+        if found:
+            results.append(CheckResult(label, "ok", model))
+        else:
+            results.append(CheckResult(label, "fail", f"{model} 모델이 설치되어 있지 않습니다.",
+                                       f"`ollama pull {model}`을 실행하거나 {task}_MODEL_* 설정을 바꾸세요."))
+    return results
+
+
+def check_disk_space() -> List[CheckResult]:
+    db_base = get_db_base_path()
+    usage = shutil.disk_usage(db_base)
+    free_gb = usage.free / 1024 ** 3
+    message = f"{db_base}: 여유 {free_gb:.1f} GB / 전체 {usage.total / 1024 ** 3:.1f} GB"
+    if free_gb < DOCTOR_MIN_FREE_GB:
+        return [CheckResult("디스크 공간", "fail", message,
+                            f"{DOCTOR_MIN_FREE_GB:g} GB 이상 확보하세요. 휴지통 비우기나 DB_FOLDER_PATH 이동을 고려하세요.")]
+    if free_gb < DOCTOR_MIN_FREE_GB * 5:
+        return [CheckResult("디스크 공간", "warn", message, "긴 녹음 몇 개로 공간이 부족해질 수 있습니다.")]
+    return [CheckResult("디스크 공간", "ok", message)]
+
+
+def check_ports() -> List[CheckResult]:
+    results = []
+    for label, port in SERVER_PORTS.items():
+        with socket.socket(socket.AF_INET, socket.SOCK_STREAM) as sock:
+            try:
+                sock.bind(("127.0.0.1", port))
+            except OSError:
+                results.append(CheckResult(f"{label} 포트", "fail", f"{port} 포트를 다른 프로세스가 사용 중입니다.",
+                                           "이미 실행 중인 서버가 있으면 종료하거나 해당 포트를 쓰는 프로그램을 종료하세요."))
+                continue
+        results.append(CheckResult(f"{label} 포트", "ok", f"{port} 사용 가능"))
+    return results
+
+
+def _writable(path: Path) -> Optional[str]:
+    """Error message if ``path`` cannot be created or written, else ``None``."""
+    try:
+        path.mkdir(parents=True, exist_ok=True)
+        with tempfile.NamedTemporaryFile(dir=path, prefix=".doctor_"):
+            pass
+    except OSError as exc:
+        return str(exc)
+    return None
+
+
+def check_db_paths() -> List[CheckResult]:
+    db_base = get_db_base_path()
+    paths = [db_base, *get_record_layout(db_base).directories(), db_base / "vector_store", db_base / "log"]
+    failures = [(path, error) for path in dict.fromkeys(paths) if (error := _writable(path))]
+    if failures:
+        return [
+            CheckResult("DB 쓰기 권한", "fail", f"{path}: {error}",
+                        "폴더 권한을 확인하거나 DB_FOLDER_PATH를 쓰기 가능한 위치로 바꾸세요.")
+            for path, error in failures
+        ]
+    return [CheckResult("DB 쓰기 권한", "ok", f"{db_base} 외 {len(paths) - 1}개 폴더")]
+
+
+CHECKS: List[Callable[[], List[CheckResult]]] = [
+    check_ffmpeg,
+    check_stt_model,
+    check_ollama,
+    check_disk_space,
+    check_ports,
+    check_db_paths,
+]
+
+
+def run_checks(skip_ports: bool = False) -> List[CheckResult]:
+    """Run every check; a check that raises is reported as a failure instead of aborting."""
+    results = []
+    for check in CHECKS:
+        if skip_ports and check is check_ports:
+            continue
+        try:
+            results.extend(check())
+        except Exception as exc:  # pragma: no cover - defensive
+            results.append(CheckResult(check.__name__.replace("check_", ""), "fail", f"확인 중 오류: {exc}"))
+    return results
+
+
+def format_report(results: List[CheckResult]) -> str:
+    width = max(display_width(result.name) for result in results)
+    lines = []
+    for result in results:
+        padding = " " * (width - display_width(result.name))
+        lines.append(f"{STATUS_ICONS[result.status]} {result.name}{padding}  {result.message}")
+        if result.hint and result.status != "ok":
+            lines.append(f"  {' ' * width}  → {result.hint}")
+    failed = sum(result.status == "fail" for result in results)
+    warned = sum(result.status == "warn" for result in results)
+    lines.append("")
+    lines.append(f"실패 {failed}개, 경고 {warned}개" if failed or warned else "모든 확인을 통과했습니다.")
+    return "\n".join(lines)
+
+
+def main() -> int:
+    import argparse
+
+    parser = argparse.ArgumentParser(description="Check ffmpeg, models, Ollama, disk space, ports and DB permissions")
+    parser.add_argument("--json", action="store_true", help="Print the report as JSON")
+    parser.add_argument("--skip-ports", action="store_true", help="Skip port checks (e.g. while the server is running)")
+    args = parser.parse_args()
+
+    results = run_checks(skip_ports=args.skip_ports)
+    if args.json:
+        print(json.dumps([asdict(result) for result in results], ensure_ascii=False, indent=2))
+    else:
+        print(format_report(results))
+    return 1 if any(result.status == "fail" for result in results) else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
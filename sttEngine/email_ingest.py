@@ -0,0 +1,238 @@
+"""Inbound email ingestion: "forward the recording to RecordRoute".
+
+Audio/video attachments of messages sent to a dedicated mailbox become
+records tagged with the sender's address and are processed with
+``EMAIL_IN_STEPS``. Messages arrive in one of two ways:
+
+* **IMAP polling** — set ``EMAIL_IN_IMAP_HOST``/``_USER``/``_PASSWORD``; the
+  server checks ``EMAIL_IN_IMAP_FOLDER`` for unread messages every
+  ``EMAIL_IN_POLL_SECONDS`` and marks them read once handled.
+* **Inbound webhook** — ``POST /email/inbound`` with the form a Mailgun
+  route sends (``sender``, ``subject``, ``attachment-1``... or the raw
+  message in ``body-mime``), or a raw ``message/rfc822`` body. With
+  ``EMAIL_IN_WEBHOOK_SIGNING_KEY`` set, Mailgun's ``timestamp``/``token``/
+  ``signature`` fields are verified instead of an API token.
+
+Only senders in ``EMAIL_IN_ALLOWED_SENDERS`` (addresses or ``@domain``) are
+accepted; with the list empty every sender is, so set it whenever the
+address is reachable from the internet.
+"""
+
+from __future__ import annotations
+
+import hashlib
+import hmac
+import imaplib
+import threading
+import time
+from dataclasses import dataclass, field
+from email import message_from_bytes, policy
+from email.message import Message
+from email.utils import parseaddr
+from pathlib import Path
+from typing import Callable, Dict, List, Optional, Tuple
+from urllib.parse import parse_qs
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+    from .url_ingest import MEDIA_EXTENSIONS
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+    from url_ingest import MEDIA_EXTENSIONS  # type: ignore
+
+EMAIL_IN_IMAP_HOST = get_config_value("EMAIL_IN_IMAP_HOST", "", str)
+EMAIL_IN_IMAP_PORT = get_config_value("EMAIL_IN_IMAP_PORT", 993, int)
+EMAIL_IN_IMAP_USER = get_config_value("EMAIL_IN_IMAP_USER", "", str)
+EMAIL_IN_IMAP_PASSWORD = get_config_value("EMAIL_IN_IMAP_PASSWORD", "", str)
+EMAIL_IN_IMAP_FOLDER = get_config_value("EMAIL_IN_IMAP_FOLDER", "INBOX", str)
+EMAIL_IN_POLL_SECONDS = get_config_value("EMAIL_IN_POLL_SECONDS", 60, float)
+EMAIL_IN_ALLOWED_SENDERS = get_config_value("EMAIL_IN_ALLOWED_SENDERS", "", str)
+EMAIL_IN_STEPS = get_config_value("EMAIL_IN_STEPS", "stt,summary", str)
+EMAIL_IN_MAX_MB = get_config_value("EMAIL_IN_MAX_MB", 200, int)
+EMAIL_IN_WEBHOOK_SIGNING_KEY = get_config_value("EMAIL_IN_WEBHOOK_SIGNING_KEY", "", str)
+
+# 재전송 공격을 막기 위해 이보다 오래된 서명은 거부
+SIGNATURE_MAX_AGE_SECONDS = 15 * 60
+
+
+class EmailIngestError(ValueError):
+    """Raised for malformed inbound messages."""
+
+
+class EmailSignatureError(EmailIngestError):
+    """Raised when the webhook signature is missing, stale or wrong."""
+
+
+@dataclass
+class InboundEmail:
+    sender: str
+    subject: str = ""
+    message_id: str = ""
+    attachments: List[Tuple[str, bytes]] = field(default_factory=list)
+
+
+def processing_steps() -> List[str]:
+    return [step.strip() for step in EMAIL_IN_STEPS.split(",") if step.strip()]
+
+
+def sender_allowed(sender: str) -> bool:
+    allowed = [entry.strip().lower() for entry in EMAIL_IN_ALLOWED_SENDERS.split(",") if entry.strip()]
+    if not allowed:
+        return True
+    sender = sender.lower()
+    return any(sender == entry or (entry.startswith("@") and sender.endswith(entry)) for entry in allowed)
+
+
+def _is_media_part(part: Message, filename: str) -> bool:
+    if part.get_content_maintype() in ("audio", "video"):
+        return True
+    return Path(filename).suffix.lower() in MEDIA_EXTENSIONS
+
+
+def media_attachments(message: Message) -> List[Tuple[str, bytes]]:
+    """``(filename, data)`` of every audio/video attachment; files over ``EMAIL_IN_MAX_MB`` are skipped."""
+    attachments = []
+    for part in message.walk():
+        if part.is_multipart():
+            continue
+        filename = part.get_filename()
+        if not filename or not _is_media_part(part, filename):
+            continue
+        data = part.get_payload(decode=True) or b""
+        if len(data) > EMAIL_IN_MAX_MB * 1024 * 1024:
+            print(f"메일 첨부 '{filename}'이 {EMAIL_IN_MAX_MB}MB를 넘어 건너뜁니다.")
+            continue
+        attachments.append((filename, data))
+    return attachments
+
+
+def parse_message(raw: bytes) -> InboundEmail:
+    """Sender, subject and media attachments of a raw RFC 822 message."""
+    message = message_from_bytes(raw, policy=policy.default)
+    sender = parseaddr(str(message.get("From") or ""))[1]
+    if not sender:
+        raise EmailIngestError("발신자(From)가 없는 메일입니다.")
+    return InboundEmail(
+        sender=sender.lower(),
+        subject=str(message.get("Subject") or ""),
+        message_id=str(message.get("Message-ID") or ""),
+        attachments=media_attachments(message),
+    )
+
+
+def parse_form(content_type: str, body: bytes) -> Tuple[Dict[str, str], List[Tuple[str, str, bytes]]]:
+    """Text fields and ``(field, filename, data)`` files of a form body (multipart or urlencoded)."""
+    if content_type.startswith("application/x-www-form-urlencoded"):
+        fields = {key: values[0] for key, values in parse_qs(body.decode("utf-8", "replace")).items()}
+        return fields, []
+    form = message_from_bytes(
+        f"Content-Type: {content_type}\r\nMIME-Version: 1.0\r\n\r\n".encode("utf-8") + body,
+        policy=policy.default,
+    )
+    if not form.is_multipart():
+        raise EmailIngestError("multipart/form-data 또는 x-www-form-urlencoded 본문이어야 합니다.")
+    fields: Dict[str, str] = {}
+    files: List[Tuple[str, str, bytes]] = []
+    for part in form.iter_parts():
+        name = part.get_param("name", header="content-disposition")
+        if not name:
+            continue
+        filename = part.get_filename()
+        data = part.get_payload(decode=True) or b""
+        if filename:
+            files.append((name, filename, data))
+        else:
+            fields[name] = data.decode(part.get_content_charset() or "utf-8", "replace")
+    return fields, files
+
+
+def verify_signature(fields: Dict[str, str], now: Optional[float] = None) -> None:
+    """Check Mailgun's ``signature`` (HMAC-SHA256 of timestamp + token)."""
+    timestamp, token, signature = (fields.get(key, "") for key in ("timestamp", "token", "signature"))
+    if not (timestamp and token and signature):
+        raise EmailSignatureError("서명 필드(timestamp, token, signature)가 없습니다.")
+    try:
+        age = abs((now or time.time()) - float(timestamp))
+    except ValueError:
+        raise EmailSignatureError("잘못된 timestamp입니다.") from None
+    if age > SIGNATURE_MAX_AGE_SECONDS:
+        raise EmailSignatureError("서명이 만료되었습니다.")
+    expected = hmac.new(EMAIL_IN_WEBHOOK_SIGNING_KEY.encode("utf-8"), f"{timestamp}{token}".encode("utf-8"),
+                        hashlib.sha256).hexdigest()
+    if not hmac.compare_digest(expected, signature):
+        raise EmailSignatureError("서명이 일치하지 않습니다.")
+
+
+def from_webhook(content_type: str, body: bytes) -> InboundEmail:
+    """Inbound message from a ``POST /email/inbound`` request body."""
+    if content_type.startswith("message/rfc822"):
+        if EMAIL_IN_WEBHOOK_SIGNING_KEY:
+            raise EmailIngestError("서명 키가 설정되어 있으면 Mailgun 형식으로만 받을 수 있습니다.")
+        return parse_message(body)
+    fields, files = parse_form(content_type, body)
+    if EMAIL_IN_WEBHOOK_SIGNING_KEY:
+        verify_signature(fields)
+    if fields.get("body-mime"):
+        return parse_message(fields["body-mime"].encode("utf-8"))
+    sender = parseaddr(fields.get("sender") or fields.get("from") or "")[1]
+    if not sender:
+        raise EmailIngestError("발신자(sender)가 없습니다.")
+    attachments = [
+        (filename, data) for name, filename, data in files
+        if name.startswith("attachment") and Path(filename).suffix.lower() in MEDIA_EXTENSIONS
+    ]
+    return InboundEmail(sender=sender.lower(), subject=fields.get("subject", ""),
+                        message_id=fields.get("Message-Id", ""), attachments=attachments)
+
+
+def imap_enabled() -> bool:
+    return bool(EMAIL_IN_IMAP_HOST and EMAIL_IN_IMAP_USER and EMAIL_IN_POLL_SECONDS > 0)
+
+
+def poll_imap(handler: Callable[[InboundEmail], None]) -> int:
+    """Hand every unread message in the mailbox to ``handler``; returns the number handled.
+
+    Messages are marked read after the handler runs, even when it fails,
+    so a broken message is not retried forever.
+    """
+    handled = 0
+    with imaplib.IMAP4_SSL(EMAIL_IN_IMAP_HOST, EMAIL_IN_IMAP_PORT) as client:
+        client.login(EMAIL_IN_IMAP_USER, EMAIL_IN_IMAP_PASSWORD)
+        client.select(EMAIL_IN_IMAP_FOLDER)
+        _, data = client.search(None, "UNSEEN")
+        for number in (data[0] or b"").split():
+            _, fetched = client.fetch(number, "(BODY.PEEK[])")
+            raw = next((item[1] for item in fetched if isinstance(item, tuple)), None)
+            try:
+                if raw:
+                    handler(parse_message(raw))
+                    handled += 1
+            except Exception as exc:
+                print(f"수신 메일 처리 실패: {exc}")
+            client.store(number, "+FLAGS", "\\Seen")
+    return handled
+
+
+def start_imap_poller(handler: Callable[[InboundEmail], None],
+                      paused: Callable[[], bool] = lambda: False) -> Optional[threading.Thread]:
+    """Poll the mailbox every ``EMAIL_IN_POLL_SECONDS`` in a daemon thread (``None`` when not configured).
+
+    While ``paused()`` is true the mailbox is left untouched, so the mail is
+    picked up once polling resumes.
+    """
+    if not imap_enabled():
+        return None
+
+    def run():
+        while True:
+            try:
+                if not paused():
+                    poll_imap(handler)
+            except (OSError, imaplib.IMAP4.error) as exc:
+                print(f"IMAP 메일 확인 실패 ({EMAIL_IN_IMAP_HOST}): {exc}")
+            time.sleep(EMAIL_IN_POLL_SECONDS)
+
+    thread = threading.Thread(target=run, daemon=True)
+    thread.start()
+    print(f"메일 수신 확인 시작: {EMAIL_IN_IMAP_USER} ({EMAIL_IN_POLL_SECONDS:g}초 간격)")
+    return thread
@@ -0,0 +1,311 @@
+"""Recall@k benchmark of embedding models on the user's own records.
+
+Whether ``nomic-embed-text`` or ``bge-m3`` finds meetings better depends on
+the language and the kind of recordings, so the comparison runs on a
+labeled set of the user's own queries::
+
+    {"pairs": [{"query": "예산 승인한 회의", "expected": ["<record id>", ...]}, ...]}
+
+For every model the transcripts (or summaries) of the active records are
+embedded from scratch — the search index is not touched — every query is
+ranked against them, and the report gives recall@k (share of expected
+records in the top k, averaged over queries) and MRR per model.
+
+Runs as a background job (``POST /embeddings/benchmark``) or from the
+command line::
+
+    python -m sttEngine.embedding_benchmark pairs.json --models bge-m3 nomic-embed-text
+"""
+
+from __future__ import annotations
+
+import threading
+import time
+import uuid
+from datetime import datetime
+from typing import Any, Callable, Dict, Iterable, List, Optional
+
+import numpy as np
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+EMBEDDING_BENCHMARK_MODELS = get_config_value("EMBEDDING_BENCHMARK_MODELS", "", str)
+EMBEDDING_BENCHMARK_MAX_RECORDS = max(1, get_config_value("EMBEDDING_BENCHMARK_MAX_RECORDS", 300, int))
+
+DEFAULT_KS = (1, 5, 10)
+DEFAULT_ALTERNATIVE = "nomic-embed-text"
+MAX_PAIRS = 500
+MAX_MODELS = 5
+TARGETS = ("transcript", "summary")
+
+
+class BenchmarkError(ValueError):
+    """Raised for invalid labeled sets, model lists or k values."""
+
+
+class BenchmarkBusy(RuntimeError):
+    """Raised when a benchmark job is already running."""
+
+
+class BenchmarkCancelled(Exception):
+    """Raised inside the job when it was cancelled."""
+
+
+def parse_pairs(pairs: Any) -> List[Dict[str, Any]]:
+    """Validated ``[{"query", "expected": [record ids]}]``; a single expected id may be a string."""
+    if not isinstance(pairs, list) or not pairs:
+        raise BenchmarkError("pairs는 {query, expected} 객체의 배열이어야 합니다.")
+    if len(pairs) > MAX_PAIRS:
+        raise BenchmarkError(f"pairs는 최대 {MAX_PAIRS}개까지 지정할 수 있습니다.")
+    parsed = []
+    for position, pair in enumerate(pairs, 1):
+        query = pair.get("query") if isinstance(pair, dict) else None
+        expected = pair.get("expected") if isinstance(pair, dict) else None
+        if isinstance(expected, str):
+            expected = [expected]
+        if not isinstance(query, str) or not query.strip():
+            raise BenchmarkError(f"{position}번째 항목에 query가 없습니다.")
+        if not isinstance(expected, list) or not expected or not all(isinstance(item, str) for item in expected):
+            raise BenchmarkError(f"{position}번째 항목의 expected는 기록 ID 배열이어야 합니다.")
+        parsed.append({"query": query.strip(), "expected": list(dict.fromkeys(expected))})
+    return parsed
+
+
+def resolve_models(models: Any, current_model: str) -> List[str]:
+    """Requested models, else ``EMBEDDING_BENCHMARK_MODELS``, else the current model and nomic-embed-text."""
+    if models is None:
+        configured = [model.strip() for model in EMBEDDING_BENCHMARK_MODELS.split(",") if model.strip()]
+        models = configured or [current_model, DEFAULT_ALTERNATIVE]
+    if not isinstance(models, list) or not all(isinstance(model, str) and model.strip() for model in models):
+        raise BenchmarkError("models는 모델 이름 문자열 배열이어야 합니다.")
+    models = list(dict.fromkeys(model.strip() for model in models))
+    if not 1 <= len(models) <= MAX_MODELS:
+        raise BenchmarkError(f"모델은 1~{MAX_MODELS}개까지 지정할 수 있습니다.")
+    return models
+
+
+def resolve_ks(ks: Any) -> List[int]:
+    if ks is None:
+        return list(DEFAULT_KS)
+    if isinstance(ks, int):
+        ks = [ks]
+    if not isinstance(ks, list) or not ks or not all(isinstance(k, int) and 1 <= k <= 100 for k in ks):
+        raise BenchmarkError("k는 1~100 사이 정수 또는 그 배열이어야 합니다.")
+    return sorted(set(ks))
+
+
+def _score(query_vec: np.ndarray, doc_vec: np.ndarray) -> float:
+    # 조각별 벡터로 저장되는 긴 문서는 가장 잘 맞는 조각의 점수 (검색과 같은 방식)
+    rows = doc_vec if doc_vec.ndim == 2 else doc_vec[np.newaxis, :]
+    norms = np.linalg.norm(rows, axis=1) * np.linalg.norm(query_vec)
+    scores = np.where(norms > 0, rows @ query_vec / np.where(norms > 0, norms, 1), -1.0)
+    return float(scores.max())
+
+
+def evaluate(rankings: List[List[str]], pairs: List[Dict[str, Any]], ks: Iterable[int]) -> Dict[str, Any]:
+    """recall@k and MRR of ranked record ids against the expected ones."""
+    ks = list(ks)
+    recall = {k: 0.0 for k in ks}
+    reciprocal_ranks = 0.0
+    for ranking, pair in zip(rankings, pairs):
+        expected = set(pair["expected"])
+        for k in ks:
+            recall[k] += len(expected & set(ranking[:k])) / len(expected)
+        first_hit = next((rank for rank, record_id in enumerate(ranking, 1) if record_id in expected), None)
+        if first_hit:
+            reciprocal_ranks += 1 / first_hit
+    count = len(pairs) or 1
+    return {
+        "recall": {f"@{k}": round(recall[k] / count, 4) for k in ks},
+        "mrr": round(reciprocal_ranks / count, 4),
+    }
+
+
+def run_benchmark(pairs: List[Dict[str, Any]], documents: Dict[str, str], models: List[str], ks: List[int],
+                  embed_document: Callable[[str, str], np.ndarray], embed_query: Callable[[str, str], np.ndarray],
+                  progress: Callable[[str], None] = print,
+                  cancel_event: Optional[threading.Event] = None) -> Dict[str, Any]:
+    """Embed ``documents`` (record id → text) and ``pairs`` with each model and score the rankings.
+
+    A model that fails (not installed, Ollama error) is reported with its
+    ``error`` and does not stop the others.
+    """
+    unknown = sorted({record_id for pair in pairs for record_id in pair["expected"]} - set(documents))
+    if unknown:
+        raise BenchmarkError(f"전사가 없거나 존재하지 않는 기록 ID입니다: {', '.join(unknown[:10])}")
+
+    results = []
+    for model in models:
+        started = time.monotonic()
+        try:
+            vectors = {}
+            for position, (record_id, text) in enumerate(documents.items(), 1):
+                if cancel_event is not None and cancel_event.is_set():
+                    raise BenchmarkCancelled()
+                if position == 1 or position % 10 == 0:
+                    progress(f"{model}: 문서 임베딩 {position}/{len(documents)}")
+                vectors[record_id] = embed_document(text, model)
+            rankings, queries = [], []
+            for pair in pairs:
+                query_vec = embed_query(pair["query"], model)
+                ranking = sorted(vectors, key=lambda record_id: _score(query_vec, vectors[record_id]), reverse=True)
+                rankings.append(ranking)
+                first_hit = next(
+                    (rank for rank, record_id in enumerate(ranking, 1) if record_id in pair["expected"]), None
+                )
+                queries.append({**pair, "top": ranking[:max(ks)], "first_hit_rank": first_hit})
+            results.append({"model": model, **evaluate(rankings, pairs, ks), "queries": queries,
+                            "seconds": round(time.monotonic() - started, 1), "error": None})
+        except BenchmarkCancelled:
+            raise
+        except Exception as exc:
+            progress(f"{model}: 실패 - {exc}")
+            results.append({"model": model, "recall": None, "mrr": None, "queries": [],
+                            "seconds": round(time.monotonic() - started, 1), "error": str(exc)})
+
+    scored = [result for result in results if result["error"] is None]
+    primary = f"@{ks[-1]}"
+    best = max(scored, key=lambda result: (result["recall"][primary], result["mrr"]))["model"] if scored else None
+    return {
+        "ks": ks,
+        "record_count": len(documents),
+        "query_count": len(pairs),
+        "best_model": best,
+        "ranked_by": f"recall{primary}",
+        "models": results,
+    }
+
+
+class BenchmarkJob:
+    """Background benchmark run; :meth:`snapshot` is what ``GET /embeddings/benchmark`` returns."""
+
+    def __init__(self, runner: Callable[[Callable[[str], None], threading.Event], Dict[str, Any]],
+                 models: List[str], query_count: int, target: str):
+        self.id = str(uuid.uuid4())
+        self.runner = runner
+        self.models = models
+        self.query_count = query_count
+        self.target = target
+        self.status = "pending"
+        self.message = ""
+        self.report: Optional[Dict[str, Any]] = None
+        self.error: Optional[str] = None
+        self.started_at: Optional[str] = None
+        self.finished_at: Optional[str] = None
+        self._cancel = threading.Event()
+
+    @property
+    def running(self) -> bool:
+        return self.status in ("pending", "running")
+
+    def cancel(self) -> None:
+        self._cancel.set()
+
+    def snapshot(self) -> Dict[str, Any]:
+        return {
+            "id": self.id,
+            "status": self.status,
+            "models": self.models,
+            "query_count": self.query_count,
+            "target": self.target,
+            "message": self.message,
+            "report": self.report,
+            "error": self.error,
+            "started_at": self.started_at,
+            "finished_at": self.finished_at,
+        }
+
+    def _progress(self, message: str) -> None:
+        self.message = message
+
+    def run(self) -> None:
+        self.status = "running"
+        self.started_at = datetime.now().isoformat()
+        try:
+            self.report = self.runner(self._progress, self._cancel)
+            self.status = "completed"
+            self.message = f"최고 모델: {self.report['best_model']}" if self.report.get("best_model") else ""
+        except BenchmarkCancelled:
+            self.status = "cancelled"
+        except Exception as exc:
+            self.status = "failed"
+            self.error = str(exc)
+        finally:
+            self.finished_at = datetime.now().isoformat()
+            print(f"임베딩 모델 벤치마크 {self.status}: {', '.join(self.models)}")
+
+
+_job_lock = threading.Lock()
+_current_job: Optional[BenchmarkJob] = None
+
+
+def get_current_job() -> Optional[BenchmarkJob]:
+    return _current_job
+
+
+def start_benchmark_job(runner: Callable[[Callable[[str], None], threading.Event], Dict[str, Any]],
+                        models: List[str], query_count: int, target: str) -> BenchmarkJob:
+    """Start a background job; raises :class:`BenchmarkBusy` if one is running."""
+    global _current_job
+    with _job_lock:
+        if _current_job is not None and _current_job.running:
+            raise BenchmarkBusy("임베딩 모델 벤치마크가 이미 진행 중입니다.")
+        job = BenchmarkJob(runner, models, query_count, target)
+        _current_job = job
+    threading.Thread(target=job.run, daemon=True).start()
+    return job
+
+
+def format_report(report: Dict[str, Any]) -> str:
+    """Plain-text table of a report for the CLI."""
+    lines = [f"기록 {report['record_count']}개, 질의 {report['query_count']}개"]
+    header = "모델".ljust(28) + "".join(f"recall@{k}".rjust(11) for k in report["ks"]) + "MRR".rjust(8)
+    lines.append(header)
+    for result in report["models"]:
+        name = result["model"].ljust(28)
+        if result["error"]:
+            lines.append(f"{name}실패: {result['error']}")
+            continue
+        scores = "".join(f"{result['recall'][f'@{k}']:>11.3f}" for k in report["ks"])
+        lines.append(f"{name}{scores}{result['mrr']:>8.3f}")
+    if report["best_model"]:
+        lines.append(f"\n가장 좋은 모델 ({report['ranked_by']}): {report['best_model']}")
+    return "\n".join(lines)
+
+
+def main() -> int:
+    """CLI: benchmark embedding models against a labeled pairs file."""
+    import argparse
+    import json
+
+    parser = argparse.ArgumentParser(description="Compare embedding models by recall@k on labeled query→record pairs")
+    parser.add_argument("pairs", help='JSON file: {"pairs": [{"query": "...", "expected": ["<record id>"]}]}')
+    parser.add_argument("--models", nargs="+", help="Ollama embedding models (default: current model + nomic-embed-text)")
+    parser.add_argument("--k", nargs="+", type=int, help="k values for recall@k (default: 1 5 10)")
+    parser.add_argument("--target", choices=TARGETS, default="transcript", help="Embed transcripts or summaries")
+    parser.add_argument("--json", action="store_true", help="Print the report as JSON")
+    args = parser.parse_args()
+
+    from . import server  # 기록 목록과 파일 경로 해석은 서버와 같은 코드를 사용
+
+    try:
+        with open(args.pairs, "r", encoding="utf-8") as f:
+            data = json.load(f)
+        pairs = parse_pairs(data.get("pairs") if isinstance(data, dict) else data)
+        models = resolve_models(args.models, server.current_embedding_model())
+        ks = resolve_ks(args.k)
+        documents = server.benchmark_corpus(args.target, [i for pair in pairs for i in pair["expected"]])
+        report = run_benchmark(pairs, documents, models, ks, server.embed_document_ollama,
+                               server.embed_text_ollama)
+    except (OSError, ValueError) as exc:
+        print(f"오류: {exc}")
+        return 1
+    print(json.dumps(report, ensure_ascii=False, indent=2) if args.json else format_report(report))
+    return 0
+
+
+if __name__ == "__main__":
+    raise SystemExit(main())
@@ -14,9 +14,14 @@ import hashlib
 import json
 import sys
 from pathlib import Path
-from typing import Dict
+from types import MappingProxyType
+from typing import Dict, Mapping, Tuple
 import os
-from datetime import datetime
+import re
+import shutil
+import threading
+import time
+from datetime import datetime, timedelta
 
 import numpy as np
 import requests
@@ -28,19 +33,57 @@ import requests
 sys.path.append(str(Path(__file__).resolve().parent.parent))
 from config import (
     DB_ALIAS,
+    get_config_value,
     get_db_base_path,
     get_default_model,
     get_model_for_task,
     resolve_db_path,
     to_db_record_path,
 )
+from artifact_store import detach as detach_artifact
+from deadline import check_deadline, deadline_timeout
 from ollama_utils import ensure_ollama_server
+from text_utils import grapheme_boundary
 from vocabulary_manager import VocabularyManager
+from vector_shards import (
+    MANIFEST_NAME,
+    SHARD_DIR_NAME,
+    VECTOR_INDEX_SHARDING,
+    describe_shard,
+    plan_shards,
+    split_index,
+)
+try:  # 서버/검색과 같은 VECTOR_CACHE에 저장해야 새 벡터가 검색 캐시에 반영됨
+    from .vector_cache import save_vector
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from vector_cache import save_vector  # type: ignore
 
 DB_BASE_PATH = get_db_base_path()
 WHISPER_OUTPUT_DIR = DB_BASE_PATH / "whisper_output"
 VECTOR_DIR = DB_BASE_PATH / "vector_store"
 INDEX_FILE = VECTOR_DIR / "index.json"
+SHARD_DIR = VECTOR_DIR / SHARD_DIR_NAME
+MANIFEST_FILE = SHARD_DIR / MANIFEST_NAME
+MIGRATED_SUFFIX = ".migrated"
+DELETED_VECTOR_DIR = DB_BASE_PATH / "deleted" / "vector_store"
+
+# 삭제 표시(tombstone)된 항목을 압축 시 영구 삭제하기 전까지 보존하는 기간
+TOMBSTONE_RETENTION_DAYS = get_config_value("VECTOR_TOMBSTONE_RETENTION_DAYS", 7, int)
+# 벡터 파일은 색인보다 먼저 저장되므로(일괄 임베딩은 끝날 때 색인을 씀) 이 시간 안에 쓰인 미참조 파일은 압축에서 제외
+ORPHAN_GRACE_SECONDS = max(0, get_config_value("VECTOR_ORPHAN_GRACE_SECONDS", 3600, int))
+
+# 색인 파일을 읽고-수정-쓰는 작업(임베딩 추가, 압축 등)을 직렬화하기 위한 잠금
+INDEX_LOCK = threading.RLock()
+
+# 검색용 읽기 전용 스냅샷. 검색은 스냅샷 참조만 잠깐 잠금 안에서 가져오고
+# 벡터 파일 IO와 점수 계산은 잠금 밖에서 수행하여 쓰기 작업을 막지 않는다.
+IndexSnapshot = Tuple[Tuple[str, Mapping[str, object]], ...]
+_snapshot_lock = threading.Lock()
+_snapshot_state: Dict[str, object] = {"mtime": None, "entries": ()}
+# 샤드별 검색 스냅샷 {샤드: (mtime, entries)}
+_shard_snapshots: Dict[str, Tuple[int, IndexSnapshot]] = {}
+# 샤드별 마지막으로 읽거나 쓴 내용의 해시 (저장 시 바뀐 샤드만 다시 씀)
+_shard_digests: Dict[str, str] = {}
 
 # Initialize vocabulary manager for STT accuracy improvement
 VOCAB_MANAGER = VocabularyManager(vocab_path=str(DB_BASE_PATH / "vocab.json"))
@@ -125,7 +168,7 @@ def resolve_index_path(key: str, meta: Dict[str, str] | None = None) -> Path:
 
     first_part = relative.parts[0] if relative.parts else ""
 
-    if first_part in {"uploads", "vector_store", "deleted", "log"}:
+    if first_part in {"uploads", "records", "vector_store", "deleted", "log"}:
         return (DB_BASE_PATH / relative).resolve()
 
     if first_part == "whisper_output":
@@ -134,75 +177,511 @@ def resolve_index_path(key: str, meta: Dict[str, str] | None = None) -> Path:
     return (WHISPER_OUTPUT_DIR / relative).resolve()
 
 
-def load_index() -> Dict[str, Dict[str, str]]:
-    """Load the JSON index mapping relative file paths to metadata."""
-    if INDEX_FILE.exists():
-        with INDEX_FILE.open("r", encoding="utf-8") as f:
-            data = json.load(f)
+# 색인 항목 종류: 전사 원문, 요약 본문 (요약 항목은 한 줄 요약 벡터를 함께 가질 수 있음)
+ENTRY_KINDS = ("transcript", "summary")
+SEARCH_TARGETS = ("transcript", "summary", "both")
+
+
+def entry_kind(key: str, meta: Dict[str, str] | None = None) -> str:
+    """Return the kind of an index entry, inferring it for legacy entries."""
+    if isinstance(meta, dict) and meta.get("kind") in ENTRY_KINDS:
+        return meta["kind"]
+    return "summary" if key.replace("\\", "/").endswith(".summary.md") else "transcript"
+
+
+# 색인 네임스페이스: 사용자/컬렉션별 파티션. 값이 없는 항목은 기본 네임스페이스에 속함
+DEFAULT_NAMESPACE = "default"
+_NAMESPACE_PATTERN = re.compile(r"^[a-z0-9][a-z0-9_.-]{0,63}$")
+
+
+class NamespaceError(ValueError):
+    """Raised for namespace names that are not lower-case slugs."""
+
+
+def normalize_namespace(value: str | None) -> str:
+    """Validate a namespace name (``None``/empty → default namespace)."""
+    namespace = (value or DEFAULT_NAMESPACE).strip().lower()
+    if not _NAMESPACE_PATTERN.match(namespace):
+        raise NamespaceError(
+            f"잘못된 네임스페이스입니다: {value!r} (영문 소문자/숫자로 시작, '_', '-', '.' 포함 최대 64자)"
+        )
+    return namespace
+
+
+def parse_namespaces(value) -> frozenset | None:
+    """Allowed namespaces from ``"a,b"`` or a list; ``None``/empty means every namespace."""
+    if value is None:
+        return None
+    if isinstance(value, str):
+        value = [part for part in value.split(",") if part.strip()]
+    if not isinstance(value, (list, tuple)) or not all(isinstance(item, str) for item in value):
+        raise NamespaceError("namespaces는 네임스페이스 이름 목록이어야 합니다.")
+    return frozenset(normalize_namespace(item) for item in value) or None
+
+
+def entry_namespace(meta: Mapping[str, object] | None) -> str:
+    if isinstance(meta, Mapping) and meta.get("namespace"):
+        return str(meta["namespace"])
+    return DEFAULT_NAMESPACE
+
+
+def namespace_stats() -> list[Dict[str, object]]:
+    """Entry/vector counts per namespace (tombstoned entries counted separately)."""
+    stats: Dict[str, Dict[str, object]] = {}
+    for key, meta in get_index_snapshot():
+        item = stats.setdefault(entry_namespace(meta), {
+            "entries": 0, "transcripts": 0, "summaries": 0, "chunks": 0, "deleted": 0,
+        })
+        if meta.get("deleted"):
+            item["deleted"] += 1
+            continue
+        item["entries"] += 1
+        item["transcripts" if entry_kind(key, dict(meta)) == "transcript" else "summaries"] += 1
+        item["chunks"] += int(meta.get("chunks") or 1)
+    return [{"namespace": name, **values} for name, values in sorted(stats.items())]
+
+
+def set_namespace_for_paths(paths, namespace: str) -> int:
+    """Move the index entries of ``paths`` to ``namespace``; returns how many changed."""
+    namespace = normalize_namespace(namespace)
+    keys = {_index_key_for_path(Path(path)) for path in paths}
+    with INDEX_LOCK:
+        index = load_index()
+        changed = 0
+        for key in keys & set(index):
+            if entry_namespace(index[key]) != namespace:
+                index[key]["namespace"] = namespace
+                changed += 1
+        if changed:
+            save_index(index)
+    return changed
+
+
+def entry_vector_names(meta: Mapping[str, str] | None) -> list[str]:
+    """Return every vector file referenced by an index entry (or snapshot entry)."""
+    if not isinstance(meta, Mapping):
+        return []
+    return [name for name in (meta.get("vector"), meta.get("title_vector")) if name]
+
+
+def index_sharded() -> bool:
+    return VECTOR_INDEX_SHARDING != "none"
+
+
+def _index_marker() -> Path:
+    """File rewritten on every index save (the shard manifest when sharded)."""
+    return MANIFEST_FILE if index_sharded() else INDEX_FILE
+
+
+def _read_json_file(path: Path) -> dict:
+    with path.open("r", encoding="utf-8") as f:
+        data = json.load(f)
+    return data if isinstance(data, dict) else {}
+
+
+def _write_json_file(path: Path, data: object) -> None:
+    tmp_file = path.with_name(f"{path.name}.tmp")
+    with tmp_file.open("w", encoding="utf-8") as f:
+        json.dump(data, f, ensure_ascii=False, indent=2)
+    os.replace(tmp_file, path)
+
 
-        normalized_index: Dict[str, Dict[str, str]] = {}
-        changed = False
-        for key, value in data.items():
-            meta: Dict[str, str] = dict(value) if isinstance(value, dict) else {}
-            new_key = _normalize_index_key(key)
+def _shard_path(name: str) -> Path:
+    return SHARD_DIR / f"{name}.json"
 
-            if "base" not in meta:
+
+def _shard_digest(entries: Dict[str, Dict[str, str]]) -> str:
+    return hashlib.sha256(json.dumps(entries, ensure_ascii=False, sort_keys=True).encode("utf-8")).hexdigest()
+
+
+def load_manifest() -> Dict[str, object]:
+    """Shard manifest ``{"period", "version", "updated_at", "shards": {name: {"entries", "first", "last"}}}``."""
+    try:
+        return _read_json_file(MANIFEST_FILE)
+    except (OSError, ValueError):
+        return {}
+
+
+def _read_shards() -> Dict[str, Dict[str, str]]:
+    index: Dict[str, Dict[str, str]] = {}
+    for name in load_manifest().get("shards") or {}:
+        try:
+            entries = _read_json_file(_shard_path(name))
+        except FileNotFoundError:
+            continue
+        _shard_digests[name] = _shard_digest(entries)
+        index.update(entries)
+    return index
+
+
+def _write_shards(index: Dict[str, Dict[str, str]]) -> None:
+    """Write the shards whose entries changed, drop emptied ones and bump the manifest."""
+    SHARD_DIR.mkdir(parents=True, exist_ok=True)
+    manifest = load_manifest()
+    shards = split_index(index, VECTOR_INDEX_SHARDING)
+    for name, entries in shards.items():
+        digest = _shard_digest(entries)
+        if _shard_digests.get(name) == digest and _shard_path(name).exists():
+            continue
+        _write_json_file(_shard_path(name), entries)
+        _shard_digests[name] = digest
+    for name in set(manifest.get("shards") or {}) - set(shards):
+        _shard_path(name).unlink(missing_ok=True)
+        _shard_digests.pop(name, None)
+    _write_json_file(MANIFEST_FILE, {
+        "period": VECTOR_INDEX_SHARDING,
+        "version": int(manifest.get("version") or 0) + 1,
+        "updated_at": datetime.now().isoformat(),
+        "shards": {name: describe_shard(shards[name]) for name in sorted(shards)},
+    })
+
+
+def _pending_layout_change() -> str | None:
+    """``split``, ``regroup`` or ``merge`` when the files on disk do not match ``VECTOR_INDEX_SHARDING``."""
+    if index_sharded():
+        manifest = load_manifest()
+        if not manifest and INDEX_FILE.exists():
+            return "split"
+        if manifest and manifest.get("period") != VECTOR_INDEX_SHARDING:
+            return "regroup"
+    elif MANIFEST_FILE.exists() and not INDEX_FILE.exists():
+        return "merge"
+    return None
+
+
+def _migrate_index_layout() -> None:
+    """Bring the files on disk in line with ``VECTOR_INDEX_SHARDING`` (lock held)."""
+    change = _pending_layout_change()
+    if change == "split":
+        index = _read_json_file(INDEX_FILE)
+        _write_shards(index)
+        INDEX_FILE.replace(INDEX_FILE.with_name(INDEX_FILE.name + MIGRATED_SUFFIX))
+        print(f"벡터 색인 {len(index)}건을 {VECTOR_INDEX_SHARDING} 단위 샤드로 나눴습니다.")
+    elif change == "regroup":
+        previous_period = load_manifest().get("period")
+        index = _read_shards()
+        _write_shards(index)
+        print(f"벡터 색인 샤드를 {previous_period} 단위에서 {VECTOR_INDEX_SHARDING} 단위로 다시 나눴습니다.")
+    elif change == "merge":
+        index = _read_shards()
+        VECTOR_DIR.mkdir(parents=True, exist_ok=True)
+        _write_json_file(INDEX_FILE, index)
+        migrated_dir = SHARD_DIR.with_name(SHARD_DIR.name + MIGRATED_SUFFIX)
+        if migrated_dir.exists():
+            shutil.rmtree(migrated_dir)
+        SHARD_DIR.replace(migrated_dir)
+        _shard_digests.clear()
+        print(f"벡터 색인 샤드 {len(index)}건을 {INDEX_FILE.name} 하나로 합쳤습니다.")
+
+
+def _read_index_data() -> Dict[str, Dict[str, str]] | None:
+    """Raw index entries from ``index.json`` or the shards (``None`` when there is no index yet)."""
+    with INDEX_LOCK:
+        _migrate_index_layout()
+        if index_sharded():
+            return _read_shards() if MANIFEST_FILE.exists() else None
+        return _read_json_file(INDEX_FILE) if INDEX_FILE.exists() else None
+
+
+def _normalize_index(data: Dict[str, Dict[str, str]]) -> Tuple[Dict[str, Dict[str, str]], int]:
+    """Index with canonical keys and a ``base`` on every entry, and the number of entries that changed."""
+    normalized_index: Dict[str, Dict[str, str]] = {}
+    changed = 0
+    for key, value in data.items():
+        meta: Dict[str, str] = dict(value) if isinstance(value, dict) else {}
+        new_key = _normalize_index_key(key)
+
+        if "base" not in meta:
+            original_path = None
+            try:
+                original_path = Path(key)
+            except Exception:
                 original_path = None
+
+            resolved_path = None
+            if original_path is not None:
                 try:
-                    original_path = Path(key)
+                    resolved_path = original_path.resolve()
                 except Exception:
-                    original_path = None
+                    resolved_path = None
 
-                resolved_path = None
-                if original_path is not None:
+            if resolved_path is not None:
+                for label, base_path in (("whisper_output", WHISPER_OUTPUT_DIR), ("db", DB_BASE_PATH)):
                     try:
-                        resolved_path = original_path.resolve()
-                    except Exception:
-                        resolved_path = None
-
-                if resolved_path is not None:
-                    for label, base_path in (("whisper_output", WHISPER_OUTPUT_DIR), ("db", DB_BASE_PATH)):
-                        try:
-                            resolved_path.relative_to(base_path)
-                            meta["base"] = label
-                            break
-                        except ValueError:
-                            continue
-                else:
-                    parts = Path(new_key.replace("\\", "/")).parts
-                    if parts:
-                        head = parts[0]
-                        if head in {"uploads", "vector_store", "deleted", "log", "whisper_output"}:
-                            meta["base"] = "db"
-                        else:
-                            meta["base"] = "whisper_output"
-
-            if new_key in normalized_index and normalized_index[new_key] != meta:
-                existing_ts = normalized_index[new_key].get("timestamp")
-                new_ts = meta.get("timestamp")
-                if existing_ts and new_ts:
-                    if new_ts > existing_ts:
-                        normalized_index[new_key] = meta
-                else:
+                        resolved_path.relative_to(base_path)
+                        meta["base"] = label
+                        break
+                    except ValueError:
+                        continue
+            else:
+                parts = Path(new_key.replace("\\", "/")).parts
+                if parts:
+                    head = parts[0]
+                    if head in {"uploads", "records", "vector_store", "deleted", "log", "whisper_output"}:
+                        meta["base"] = "db"
+                    else:
+                        meta["base"] = "whisper_output"
+
+        if new_key in normalized_index and normalized_index[new_key] != meta:
+            existing_ts = normalized_index[new_key].get("timestamp")
+            new_ts = meta.get("timestamp")
+            if existing_ts and new_ts:
+                if new_ts > existing_ts:
                     normalized_index[new_key] = meta
-                changed = True
-                continue
+            else:
+                normalized_index[new_key] = meta
+            changed += 1
+            continue
+
+        if new_key != key or meta != value:
+            changed += 1
+        normalized_index[new_key] = meta
+    return normalized_index, changed
 
-            if new_key != key or meta != value:
-                changed = True
-            normalized_index[new_key] = meta
 
+def load_index() -> Dict[str, Dict[str, str]]:
+    """Load the JSON index mapping relative file paths to metadata."""
+    data = _read_index_data()
+    if data is not None:
+        normalized_index, changed = _normalize_index(data)
         if changed:
             save_index(normalized_index)
         return normalized_index
     return {}
 
 
+def migrate_index(dry_run: bool = False) -> Dict[str, object]:
+    """Startup data migration: apply the shard layout and normalize keys (see ``data_migrations``).
+
+    With ``dry_run`` nothing is written; the report says what a real run
+    would change.
+    """
+    with INDEX_LOCK:
+        layout_change = _pending_layout_change()
+        if dry_run:
+            if load_manifest():
+                data = _read_shards()
+            else:
+                data = _read_json_file(INDEX_FILE) if INDEX_FILE.exists() else None
+            _, normalized = _normalize_index(data or {})
+            return {"entries": len(data or {}), "normalized": normalized, "layout_change": layout_change}
+        # 읽기-정규화-저장 사이에 다른 색인 갱신이 끼어들지 않도록 잠금 유지
+        data = _read_index_data()
+        normalized_index, normalized = _normalize_index(data or {})
+        if normalized:
+            save_index(normalized_index)
+    return {"entries": len(normalized_index), "normalized": normalized, "layout_change": layout_change}
+
+
 def save_index(index: Dict[str, Dict[str, str]]) -> None:
-    """Persist the JSON index to disk."""
+    """Persist the JSON index to disk and publish a fresh search snapshot.
+
+    The file (or, when sharded, every changed shard and the manifest) is
+    written to a temporary path and swapped in atomically so concurrent
+    readers never observe a partially written index.
+    """
     VECTOR_DIR.mkdir(parents=True, exist_ok=True)
-    with INDEX_FILE.open("w", encoding="utf-8") as f:
-        json.dump(index, f, ensure_ascii=False, indent=2)
+    with INDEX_LOCK:
+        if index_sharded():
+            _write_shards(index)
+        else:
+            _write_json_file(INDEX_FILE, index)
+        _publish_snapshot(index, _index_marker().stat().st_mtime_ns)
+
+
+def _freeze_entries(index: Dict[str, Dict[str, str]]) -> IndexSnapshot:
+    return tuple(
+        (key, MappingProxyType(dict(meta) if isinstance(meta, dict) else {}))
+        for key, meta in index.items()
+    )
+
+
+def _publish_snapshot(index: Dict[str, Dict[str, str]], mtime: int | None) -> None:
+    entries = _freeze_entries(index)
+    with _snapshot_lock:
+        _snapshot_state["mtime"] = mtime
+        _snapshot_state["entries"] = entries
+
+
+def _shard_snapshot(names) -> IndexSnapshot:
+    entries: list = []
+    for name in names:
+        path = _shard_path(name)
+        try:
+            mtime = path.stat().st_mtime_ns
+        except FileNotFoundError:
+            continue
+        with _snapshot_lock:
+            cached = _shard_snapshots.get(name)
+        if cached is None or cached[0] != mtime:
+            try:
+                cached = (mtime, _freeze_entries(_read_json_file(path)))
+            except (OSError, ValueError):
+                continue
+            with _snapshot_lock:
+                _shard_snapshots[name] = cached
+        entries.extend(cached[1])
+    return tuple(entries)
+
+
+def get_index_snapshot(shards=None) -> IndexSnapshot:
+    """Return an immutable snapshot of the index entries for read-only use.
+
+    The snapshot is rebuilt only when the index file changed on disk (e.g.
+    written by another process); otherwise the cached tuple is returned
+    without touching the filesystem beyond a ``stat`` call. With a sharded
+    index, ``shards`` (see :func:`plan_index_shards`) limits the snapshot to
+    those shard files.
+    """
+    if shards is not None and index_sharded():
+        return _shard_snapshot(shards)
+    try:
+        mtime = _index_marker().stat().st_mtime_ns
+    except FileNotFoundError:
+        return ()
+
+    with _snapshot_lock:
+        if _snapshot_state["mtime"] == mtime:
+            return _snapshot_state["entries"]  # type: ignore[return-value]
+
+    with INDEX_LOCK:
+        index = load_index()
+        try:
+            mtime = _index_marker().stat().st_mtime_ns
+        except FileNotFoundError:
+            mtime = None
+        _publish_snapshot(index, mtime)
+
+    with _snapshot_lock:
+        return _snapshot_state["entries"]  # type: ignore[return-value]
+
+
+def get_index_generation() -> str:
+    """Return a token that changes whenever the index file is rewritten.
+
+    Used to key search caches so results computed against an older index
+    are never served after embeddings are added, updated or removed.
+    """
+    try:
+        stat = _index_marker().stat()
+    except FileNotFoundError:
+        return "0"
+    return f"{stat.st_mtime_ns}-{stat.st_size}"
+
+
+def plan_index_shards(start_date: str | None = None, end_date: str | None = None) -> list[str] | None:
+    """Shards a search between ``start_date`` and ``end_date`` has to read (``None``: the whole index)."""
+    if not index_sharded():
+        return None
+    manifest = load_manifest()
+    if manifest.get("period") != VECTOR_INDEX_SHARDING:
+        return None  # 아직 샤드로 나누지 않음: 전체 스냅샷을 읽으며 이전
+    return plan_shards(manifest.get("shards") or {}, start_date, end_date)
+
+
+def shard_stats() -> Dict[str, object]:
+    """Sharding mode and, when sharded, entries, time range and file size per shard."""
+    if not index_sharded():
+        return {"period": VECTOR_INDEX_SHARDING, "shards": []}
+    if not MANIFEST_FILE.exists():
+        load_index()
+    manifest = load_manifest()
+    shards = []
+    for name, info in (manifest.get("shards") or {}).items():
+        try:
+            size = _shard_path(name).stat().st_size
+        except FileNotFoundError:
+            size = 0
+        shards.append({"name": name, **info, "bytes": size})
+    return {"period": VECTOR_INDEX_SHARDING, "version": manifest.get("version"),
+            "updated_at": manifest.get("updated_at"), "shards": shards}
+
+
+def _tombstone_expired(meta: Dict[str, str], cutoff: datetime) -> bool:
+    deleted_at = meta.get("deleted_at")
+    if not deleted_at:
+        return True  # 삭제 시각이 없는 오래된 tombstone은 즉시 정리 대상
+    try:
+        return datetime.fromisoformat(deleted_at) <= cutoff
+    except ValueError:
+        return True
+
+
+def compact_index(retention_days: int | None = None, dry_run: bool = False) -> Dict[str, object]:
+    """Purge tombstoned entries and orphaned vector files, then rewrite the index.
+
+    Entries marked ``deleted`` longer than ``retention_days`` ago are removed
+    together with their vector files (in ``vector_store`` or the deleted
+    area). Any ``*.npy`` file no longer referenced by a remaining entry is
+    treated as an orphan and removed, unless it was written (or hard-linked)
+    within ``VECTOR_ORPHAN_GRACE_SECONDS``: an embedding saves its vector
+    before adding the index entry, so a fresh file may still be about to be
+    referenced. With ``dry_run`` nothing is changed and the returned report
+    lists what would be removed.
+    """
+    if retention_days is None:
+        retention_days = TOMBSTONE_RETENTION_DAYS
+    cutoff = datetime.now() - timedelta(days=max(0, retention_days))
+    grace_cutoff = time.time() - ORPHAN_GRACE_SECONDS
+
+    with INDEX_LOCK:
+        index = load_index()
+
+        purged_keys = [
+            key for key, meta in index.items()
+            if isinstance(meta, dict) and meta.get("deleted") and _tombstone_expired(meta, cutoff)
+        ]
+        purged = set(purged_keys)
+        remaining = {key: meta for key, meta in index.items() if key not in purged}
+
+        referenced: set[str] = set()
+        for meta in remaining.values():
+            referenced.update(entry_vector_names(meta))
+
+        removable: list[Path] = []
+        recent_files: list[str] = []
+        for directory in (VECTOR_DIR, DELETED_VECTOR_DIR):
+            if not directory.exists():
+                continue
+            for vector_file in directory.glob("*.npy"):
+                if vector_file.name in referenced:
+                    continue
+                try:
+                    stat = vector_file.stat()
+                except OSError:
+                    continue
+                # 하드 링크로 공유된 벡터는 mtime이 오래됐을 수 있어 ctime(링크 시각)도 확인
+                if max(stat.st_mtime, stat.st_ctime) > grace_cutoff:
+                    recent_files.append(vector_file.name)
+                else:
+                    removable.append(vector_file)
+
+        freed_bytes = 0
+        removed_files: list[str] = []
+        for vector_file in removable:
+            try:
+                size = vector_file.stat().st_size
+                if not dry_run:
+                    vector_file.unlink()
+                freed_bytes += size
+                removed_files.append(vector_file.name)
+            except OSError as exc:
+                print(f"벡터 파일 삭제 실패 {vector_file}: {exc}")
+
+        if purged_keys and not dry_run:
+            save_index(remaining)
+
+    return {
+        "dry_run": dry_run,
+        "retention_days": retention_days,
+        "purged_entries": len(purged_keys),
+        "removed_vector_files": len(removed_files),
+        "freed_bytes": freed_bytes,
+        "remaining_entries": len(remaining),
+        "purged_keys": purged_keys,
+        "removed_files": removed_files,
+        "skipped_recent_files": recent_files,
+    }
 
 
 def file_hash(path: Path) -> str:
@@ -216,6 +695,68 @@ def file_hash(path: Path) -> str:
 
 DEFAULT_MAX_CHARS = int(os.environ.get("EMBEDDING_MAX_PROMPT_CHARS", "7500"))
 
+# 모델 컨텍스트 길이(토큰)를 문자 수 한도로 환산할 때 쓰는 보수적인 비율.
+# 한국어는 토큰당 1~2자 정도이므로 기본값 1.0이면 잘림 없이 안전하다.
+EMBEDDING_CHARS_PER_TOKEN = get_config_value("EMBEDDING_CHARS_PER_TOKEN", 1.0, float)
+
+# 긴 문서를 여러 조각으로 임베딩했을 때 저장 방식
+#   multi: 조각별 벡터를 모두 저장하고 검색 시 가장 높은 조각 점수를 사용
+#   mean : 조각 벡터의 평균 하나만 저장 (이전 동작)
+EMBEDDING_CHUNK_MODES = ("multi", "mean")
+EMBEDDING_CHUNK_MODE = get_config_value("EMBEDDING_CHUNK_MODE", "multi").strip().lower()
+if EMBEDDING_CHUNK_MODE not in EMBEDDING_CHUNK_MODES:
+    EMBEDDING_CHUNK_MODE = "multi"
+
+# 모델별 입력 한도 캐시: model_name -> (max_chars, num_ctx)
+_embedding_limits: Dict[str, Tuple[int, int | None]] = {}
+_embedding_limits_lock = threading.Lock()
+
+
+def _query_context_length(model_name: str) -> int | None:
+    """Ask Ollama for the model's context length in tokens."""
+    try:
+        response = requests.post(
+            "http://localhost:11434/api/show",
+            json={"model": model_name},
+            timeout=10
+        )
+        response.raise_for_status()
+        model_info = response.json().get("model_info") or {}
+    except (requests.RequestException, ValueError) as exc:
+        print(f"임베딩 모델 정보 조회 실패 ({model_name}): {exc}")
+        return None
+
+    for key, value in model_info.items():
+        if key.endswith(".context_length") and isinstance(value, int) and value > 0:
+            return value
+    return None
+
+
+def get_embedding_limit(model_name: str) -> Tuple[int, int | None]:
+    """Return ``(max_chars, num_ctx)`` for a single embedding request.
+
+    ``EMBEDDING_MAX_PROMPT_CHARS`` wins when set explicitly. Otherwise the
+    model's context length is read from Ollama and converted to characters;
+    the same value is passed as ``num_ctx`` so Ollama does not silently
+    truncate at its smaller default context.
+    """
+    if os.environ.get("EMBEDDING_MAX_PROMPT_CHARS"):
+        return DEFAULT_MAX_CHARS, None
+
+    with _embedding_limits_lock:
+        if model_name in _embedding_limits:
+            return _embedding_limits[model_name]
+
+    context_length = _query_context_length(model_name)
+    if context_length:
+        limit = (max(256, int(context_length * EMBEDDING_CHARS_PER_TOKEN)), context_length)
+    else:
+        limit = (DEFAULT_MAX_CHARS, None)
+
+    with _embedding_limits_lock:
+        _embedding_limits[model_name] = limit
+    return limit
+
 
 def _chunk_text(text: str, max_chars: int = DEFAULT_MAX_CHARS) -> list[str]:
     """Split text into reasonably sized chunks for the embedding API."""
@@ -238,7 +779,10 @@ def _chunk_text(text: str, max_chars: int = DEFAULT_MAX_CHARS) -> list[str]:
             if split_pos == -1:
                 split_pos = text.rfind(" ", start, end)
             if split_pos == -1 or split_pos <= start:
-                split_pos = end
+                # 자연스러운 경계가 없으면 분해형 한글/이모지 중간에서 자르지 않도록 글자 경계로
+                split_pos = grapheme_boundary(text, end)
+                if split_pos <= start:
+                    split_pos = end
         else:
             split_pos = end
 
@@ -253,14 +797,19 @@ def _chunk_text(text: str, max_chars: int = DEFAULT_MAX_CHARS) -> list[str]:
     return chunks or [text]
 
 
-def _request_embedding(model_name: str, prompt: str) -> np.ndarray:
+def _request_embedding(model_name: str, prompt: str, num_ctx: int | None = None) -> np.ndarray:
+    payload = {
+        "model": model_name,
+        "prompt": prompt
+    }
+    if num_ctx:
+        payload["options"] = {"num_ctx": num_ctx}
+
+    check_deadline("임베딩")
     response = requests.post(
         "http://localhost:11434/api/embeddings",
-        json={
-            "model": model_name,
-            "prompt": prompt
-        },
-        timeout=30
+        json=payload,
+        timeout=deadline_timeout(30)
     )
 
     try:
@@ -277,37 +826,70 @@ def _request_embedding(model_name: str, prompt: str) -> np.ndarray:
     return np.array(embedding, dtype=np.float32)
 
 
+def _embed_chunks(text: str, model_name: str, progress_callback=None) -> list[np.ndarray]:
+    """Embed ``text`` chunk by chunk; ``progress_callback(done, total)`` is called after each chunk."""
+    server_ok, server_msg = ensure_ollama_server()
+    if not server_ok:
+        raise Exception(f"Ollama 서버를 사용할 수 없습니다: {server_msg}")
+
+    text = text.strip()
+    if not text:
+        raise ValueError("임베딩할 텍스트가 비어 있습니다.")
+
+    max_chars, num_ctx = get_embedding_limit(model_name)
+    chunks = _chunk_text(text, max_chars)
+    vectors = []
+    for chunk in chunks:
+        vectors.append(_request_embedding(model_name, chunk, num_ctx))
+        if progress_callback:
+            progress_callback(len(vectors), len(chunks))
+    return vectors
+
+
 def embed_text_ollama(text: str, model_name: str) -> np.ndarray:
     """Ollama API를 사용하여 텍스트를 임베딩.
 
-    Ollama가 긴 입력에서 500 오류를 반환하는 문제를 피하기 위해 입력을 여러 조각으로
-    나누어 호출한 뒤 평균 임베딩을 사용한다.
+    모델 입력 한도를 넘는 입력은 여러 조각으로 나누어 호출한 뒤 평균 임베딩을
+    사용한다. 검색 질의처럼 항상 벡터 하나가 필요한 경우에 쓴다.
     """
 
     try:
-        server_ok, server_msg = ensure_ollama_server()
-        if not server_ok:
-            raise Exception(f"Ollama 서버를 사용할 수 없습니다: {server_msg}")
+        vectors = _embed_chunks(text, model_name)
+        if len(vectors) == 1:
+            return vectors[0]
+        return np.mean(np.vstack(vectors), axis=0)
+    except Exception as e:
+        print(f"Ollama 임베딩 실패: {e}")
+        raise
+
 
-        text = text.strip()
-        if not text:
-            raise ValueError("임베딩할 텍스트가 비어 있습니다.")
+def embed_document_ollama(text: str, model_name: str, progress_callback=None) -> np.ndarray:
+    """Embed a document for the index, keeping per-chunk vectors if configured.
 
-        chunks = _chunk_text(text)
-        vectors = []
-        for chunk in chunks:
-            vectors.append(_request_embedding(model_name, chunk))
+    Long transcripts (e.g. 3-hour recordings) span many chunks; averaging
+    them blurs every topic together. In ``multi`` mode the result is a
+    ``(chunks, dim)`` matrix and search scores the best matching chunk.
+    Single-chunk documents always produce a 1-D vector.
+    """
 
+    try:
+        vectors = _embed_chunks(text, model_name, progress_callback)
         if len(vectors) == 1:
             return vectors[0]
-
         stacked = np.vstack(vectors)
-        return np.mean(stacked, axis=0)
+        if EMBEDDING_CHUNK_MODE == "mean":
+            return np.mean(stacked, axis=0)
+        return stacked
     except Exception as e:
         print(f"Ollama 임베딩 실패: {e}")
         raise
 
 
+def vector_chunk_count(vector: np.ndarray) -> int:
+    """Return how many chunk vectors an embedding array holds."""
+    return int(vector.shape[0]) if vector.ndim == 2 else 1
+
+
 def process_file(model_name: str, path: Path, index: Dict[str, Dict[str, str]]) -> None:
     """Embed a single file if it is new or has changed since last run."""
     checksum = file_hash(path)
@@ -328,13 +910,16 @@ def process_file(model_name: str, path: Path, index: Dict[str, Dict[str, str]])
     if already_indexed:
         return  # already up-to-date, vocab updated above
 
-    vector = embed_text_ollama(text, model_name)
+    vector = embed_document_ollama(text, model_name)
     out_file = VECTOR_DIR / f"{path.stem}.npy"
-    np.save(out_file, vector)
+    detach_artifact(out_file)
+    save_vector(out_file, vector)
 
     entry = {
         "sha256": checksum,
         "vector": out_file.name,
+        "chunks": vector_chunk_count(vector),
+        "kind": entry_kind(key),
         "timestamp": datetime.fromtimestamp(path.stat().st_mtime).isoformat()
     }
 
@@ -0,0 +1,199 @@
+"""Error codes, retryability and user-facing hints for API error responses.
+
+Every JSON error the server sends carries, next to the human-readable
+``error`` message, a stable ``code`` the frontend can switch on, whether
+repeating the same request may succeed (``retryable``) and a Korean
+``hint`` telling the user what to do::
+
+    {"error": "STT process failed: ...", "code": "ffmpeg_missing",
+     "retryable": false, "hint": "ffmpeg를 설치하고 PATH에 추가한 뒤 다시 시도하세요."}
+
+Exceptions are mapped to a code by :func:`classify_error` — by type for the
+project's own error classes, by errno/message for library errors (Ollama
+connection failures, missing ffmpeg, full disk). Payloads that already
+have an ``error`` message but no code get one from the HTTP status.
+"""
+
+from __future__ import annotations
+
+import errno
+from dataclasses import dataclass
+from typing import Any, Dict, Optional
+
+
+@dataclass(frozen=True)
+class ErrorKind:
+    retryable: bool
+    hint: str
+
+
+ERROR_KINDS: Dict[str, ErrorKind] = {
+    "invalid_request": ErrorKind(False, "요청 값을 확인한 뒤 다시 보내세요."),
+    "unauthorized": ErrorKind(False, "Authorization: Bearer <토큰> 헤더로 유효한 API 토큰을 보내세요."),
+    "forbidden": ErrorKind(False, "이 작업을 할 권한이 없습니다. 더 넓은 scope의 토큰을 사용하세요."),
+    "not_found": ErrorKind(False, "기록이 삭제되었을 수 있습니다. 목록을 새로고침하세요."),
+    "conflict": ErrorKind(True, "같은 기록에 대한 다른 작업이 끝난 뒤 다시 시도하세요."),
+    "gone": ErrorKind(False, "처음부터 다시 요청하세요."),
+    "payload_too_large": ErrorKind(False, "파일 크기 제한을 확인하세요."),
+    "cancelled": ErrorKind(True, "작업이 취소되었습니다. 필요하면 다시 실행하세요."),
+    "busy": ErrorKind(True, "진행 중인 작업이 끝난 뒤 다시 시도하세요."),
+    "timeout": ErrorKind(True, "잠시 후 다시 시도하세요. 계속되면 파일을 나누거나 더 작은 모델을 사용하세요."),
+    "deadline_exceeded": ErrorKind(
+        False, "작업 제한 시간을 넘겼습니다. 끝난 단계의 결과는 보존되었으니 제한 시간을 늘려 남은 단계만 다시 실행하세요."
+    ),
+    "ollama_unavailable": ErrorKind(
+        True, "Ollama가 실행 중이 아닙니다. `ollama serve`로 시작하거나 OLLAMA_HOST 설정을 확인하세요."
+    ),
+    "ollama_model_missing": ErrorKind(
+        False, "모델이 설치되어 있지 않습니다. `ollama pull <모델>`로 받거나 모델 설정을 바꾸세요."
+    ),
+    "ffmpeg_missing": ErrorKind(False, "ffmpeg를 설치하고 PATH에 추가한 뒤 다시 시도하세요."),
+    "file_not_found": ErrorKind(False, "원본 파일이 이동되었거나 삭제되었습니다. 파일을 다시 업로드하세요."),
+    "disk_full": ErrorKind(True, "디스크 공간을 확보한 뒤 다시 시도하세요. 휴지통 비우기로 공간을 늘릴 수 있습니다."),
+    "out_of_memory": ErrorKind(True, "메모리가 부족합니다. 더 작은 Whisper 모델을 쓰거나 다른 작업이 끝난 뒤 다시 시도하세요."),
+    "media_unsupported": ErrorKind(False, "지원하지 않거나 손상된 미디어 파일입니다. 다른 형식으로 변환해 업로드하세요."),
+    "stt_failed": ErrorKind(True, "STT 백엔드 설정(STT_BACKEND, 모델 경로)을 확인한 뒤 다시 시도하세요."),
+    "summary_failed": ErrorKind(True, "Ollama 상태와 요약 모델 설정을 확인한 뒤 다시 시도하세요."),
+    "service_unavailable": ErrorKind(True, "서버가 준비 중입니다. 잠시 후 다시 시도하세요."),
+    "maintenance": ErrorKind(True, "서버 점검이 끝난 뒤 다시 시도하세요. 기록 조회·검색·다운로드는 계속 사용할 수 있습니다."),
+    "internal": ErrorKind(False, "서버 로그를 확인하세요. 문제가 계속되면 이슈로 알려 주세요."),
+}
+
+# 코드가 없는 오류 응답은 HTTP 상태로 분류
+STATUS_CODES = {
+    400: "invalid_request",
+    401: "unauthorized",
+    403: "forbidden",
+    404: "not_found",
+    406: "forbidden",
+    409: "conflict",
+    410: "gone",
+    413: "payload_too_large",
+    415: "media_unsupported",
+    429: "busy",
+    503: "service_unavailable",
+    504: "timeout",
+}
+
+# 예외 타입 이름 → 코드 (순환 import를 피하려고 이름으로 비교)
+_TYPE_CODES = {
+    "TranscriptionCancelled": "cancelled",
+    "DeadlineExceeded": "deadline_exceeded",
+    "RegenerationBusy": "busy",
+    "SttBackendError": "stt_failed",
+    "SummarizationError": "summary_failed",
+    "VideoProcessingError": "media_unsupported",
+    "InsufficientDiskSpace": "disk_full",
+}
+
+# 라이브러리 오류 메시지에서 원인을 추정할 때 쓰는 단서 (소문자)
+_MESSAGE_HINTS = (
+    ("ffmpeg", "ffmpeg_missing"),
+    ("connection refused", "ollama_unavailable"),
+    ("failed to connect to ollama", "ollama_unavailable"),
+    ("ollama 서버", "ollama_unavailable"),
+    ("model not found", "ollama_model_missing"),
+    ("try pulling it first", "ollama_model_missing"),
+    ("no space left", "disk_full"),
+    ("디스크 공간이 부족", "disk_full"),
+    ("out of memory", "out_of_memory"),
+    ("timed out", "timeout"),
+    ("cancelled", "cancelled"),
+    ("취소", "cancelled"),
+)
+
+
+@dataclass(frozen=True)
+class ErrorInfo:
+    """A classified error: stable ``code`` plus the message shown to the user."""
+
+    code: str
+    message: str
+
+    def is_retryable(self) -> bool:
+        return ERROR_KINDS.get(self.code, ERROR_KINDS["internal"]).retryable
+
+    def user_hint(self) -> str:
+        return ERROR_KINDS.get(self.code, ERROR_KINDS["internal"]).hint
+
+    def to_payload(self, **extra: Any) -> Dict[str, Any]:
+        return {
+            "error": self.message,
+            "code": self.code,
+            "retryable": self.is_retryable(),
+            "hint": self.user_hint(),
+            **extra,
+        }
+
+
+def _code_from_message(message: str) -> Optional[str]:
+    lowered = message.lower()
+    for needle, code in _MESSAGE_HINTS:
+        if needle in lowered:
+            return code
+    return None
+
+
+def _code_for_exception(exc: BaseException, fallback: str) -> str:
+    for cls in type(exc).__mro__:
+        if cls.__name__ in _TYPE_CODES:
+            # 백엔드 오류 안에 더 구체적인 원인(ffmpeg 등)이 있으면 그쪽을 우선
+            return _code_from_message(str(exc)) or _TYPE_CODES[cls.__name__]
+    if isinstance(exc, MemoryError):
+        return "out_of_memory"
+    if isinstance(exc, TimeoutError):
+        return "timeout"
+    if isinstance(exc, FileNotFoundError):
+        return "ffmpeg_missing" if "ffmpeg" in str(exc).lower() else "file_not_found"
+    if isinstance(exc, OSError) and exc.errno == errno.ENOSPC:
+        return "disk_full"
+    if isinstance(exc, ConnectionError):
+        return "ollama_unavailable"
+    if type(exc).__name__ in ("ConnectionError", "ConnectTimeout"):  # requests/httpx
+        return "ollama_unavailable"
+    if type(exc).__name__ in ("ReadTimeout", "Timeout", "TimeoutException"):
+        return "timeout"
+    message_code = _code_from_message(str(exc))
+    if message_code:
+        return message_code
+    if fallback == "internal" and isinstance(exc, (ValueError, KeyError)):
+        return "invalid_request"
+    return fallback
+
+
+def classify_error(exc: BaseException, message: Optional[str] = None, fallback: str = "internal") -> ErrorInfo:
+    """Map an exception to an :class:`ErrorInfo`; ``message`` overrides ``str(exc)``.
+
+    ``fallback`` is the code for exceptions with no recognizable cause, e.g.
+    ``summary_failed`` for anything raised while summarizing.
+    """
+    return ErrorInfo(_code_for_exception(exc, fallback), message or str(exc) or type(exc).__name__)
+
+
+def error_payload(error: Any, code: Optional[str] = None, message: Optional[str] = None,
+                  fallback: str = "internal", **extra: Any) -> Dict[str, Any]:
+    """JSON error body for an exception or a message string.
+
+    ``code`` forces the classification; otherwise exceptions are classified
+    by :func:`classify_error` and plain messages by their wording, with
+    ``fallback`` when nothing matches.
+    """
+    if isinstance(error, BaseException):
+        info = classify_error(error, message, fallback)
+        if code:
+            info = ErrorInfo(code, info.message)
+    else:
+        text = message or str(error)
+        info = ErrorInfo(code or _code_from_message(text) or fallback, text)
+    return info.to_payload(**extra)
+
+
+def enrich_error_payload(payload: Any, status: int) -> Any:
+    """Add ``code``/``retryable``/``hint`` to an error body that does not have them yet."""
+    if not isinstance(payload, dict) or "error" not in payload or "code" in payload:
+        return payload
+    message = payload["error"]
+    if not isinstance(message, str):
+        return payload
+    code = STATUS_CODES.get(status) or _code_from_message(message) or "internal"
+    return {**payload, **ErrorInfo(code, message).to_payload()}
@@ -0,0 +1,171 @@
+"""Append-only event log used as the audit trail for upload records.
+
+Every noteworthy change to a record (upload, STT run, transcript edit,
+summary generation, export, reset, delete) is appended as a single JSON
+line to ``events.jsonl`` under the DB folder. Higher level views such as
+the per-record timeline are built from this log.
+"""
+
+from __future__ import annotations
+
+import json
+import threading
+import uuid
+from datetime import datetime
+from typing import Any, Dict, Iterable, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_db_base_path
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_db_base_path  # type: ignore
+
+EVENT_LOG_FILE = get_db_base_path() / "events.jsonl"
+
+# 작업 유형별로 저장되는 완료 이벤트 이름
+TASK_EVENT_TYPES = {
+    "stt": "stt_completed",
+    "embedding": "embedding_completed",
+    "summary": "summary_generated",
+}
+
+_log_lock = threading.Lock()
+
+
+def record_event(record_id: Optional[str], event_type: str, **details: Any) -> Optional[Dict[str, Any]]:
+    """Append an event for ``record_id`` to the event log.
+
+    Failures are logged and swallowed so that auditing never breaks the
+    operation being audited.
+    """
+    if not record_id or not event_type:
+        return None
+
+    event = {
+        "id": str(uuid.uuid4()),
+        "record_id": record_id,
+        "type": event_type,
+        "timestamp": datetime.now().isoformat(),
+        "details": {key: value for key, value in details.items() if value is not None},
+    }
+
+    try:
+        with _log_lock:
+            EVENT_LOG_FILE.parent.mkdir(parents=True, exist_ok=True)
+            with open(EVENT_LOG_FILE, "a", encoding="utf-8") as f:
+                f.write(json.dumps(event, ensure_ascii=False) + "\n")
+    except OSError as exc:
+        print(f"이벤트 기록 실패 ({event_type}): {exc}")
+        return None
+
+    return event
+
+
+def load_events(record_id: Optional[str] = None,
+                event_types: Optional[Iterable[str]] = None) -> List[Dict[str, Any]]:
+    """Return logged events, optionally filtered by record and type."""
+    if not EVENT_LOG_FILE.exists():
+        return []
+
+    wanted_types = set(event_types) if event_types else None
+    events: List[Dict[str, Any]] = []
+
+    with _log_lock:
+        try:
+            with open(EVENT_LOG_FILE, "r", encoding="utf-8") as f:
+                lines = f.readlines()
+        except OSError:
+            return []
+
+    for line in lines:
+        line = line.strip()
+        if not line:
+            continue
+        try:
+            event = json.loads(line)
+        except json.JSONDecodeError:
+            continue  # 부분적으로 기록된 줄은 무시
+        if not isinstance(event, dict):
+            continue
+        if record_id and event.get("record_id") != record_id:
+            continue
+        if wanted_types and event.get("type") not in wanted_types:
+            continue
+        events.append(event)
+
+    return events
+
+
+def _legacy_events(record: Dict[str, Any], registry: Dict[str, Dict[str, Any]]) -> List[Dict[str, Any]]:
+    """Reconstruct best-effort events for records created before the event log."""
+    record_id = record.get("id")
+    events: List[Dict[str, Any]] = []
+
+    if record.get("timestamp"):
+        events.append({
+            "id": None,
+            "record_id": record_id,
+            "type": "uploaded",
+            "timestamp": record["timestamp"],
+            "details": {
+                key: record.get(key)
+                for key in ("filename", "file_type", "duration")
+                if record.get(key) is not None
+            },
+            "reconstructed": True,
+        })
+
+    for file_uuid, info in (registry or {}).items():
+        if not isinstance(info, dict) or info.get("record_id") != record_id:
+            continue
+        event_type = TASK_EVENT_TYPES.get(info.get("task_type"))
+        if not event_type or not info.get("created_at"):
+            continue
+        events.append({
+            "id": None,
+            "record_id": record_id,
+            "type": event_type,
+            "timestamp": info["created_at"],
+            "details": {"file_uuid": file_uuid},
+            "reconstructed": True,
+        })
+
+    if record.get("deleted") and record.get("deleted_at"):
+        events.append({
+            "id": None,
+            "record_id": record_id,
+            "type": "deleted",
+            "timestamp": record["deleted_at"],
+            "details": {},
+            "reconstructed": True,
+        })
+
+    return events
+
+
+def build_record_timeline(record: Dict[str, Any],
+                          registry: Optional[Dict[str, Dict[str, Any]]] = None) -> List[Dict[str, Any]]:
+    """Return the chronological event list for a history record.
+
+    Records that predate the event log get a reconstructed timeline from
+    the history entry and the file registry. Repeated STT/summary runs are
+    numbered with ``version`` so the UI can show "summary v2" and so on.
+    """
+    record_id = record.get("id")
+    events = load_events(record_id)
+    if not any(event.get("type") == "uploaded" for event in events):
+        logged_types = {event.get("type") for event in events}
+        events.extend(
+            event for event in _legacy_events(record, registry or {})
+            if event["type"] == "uploaded" or event["type"] not in logged_types
+        )
+
+    events.sort(key=lambda event: event.get("timestamp") or "")
+
+    versions: Dict[str, int] = {}
+    for event in events:
+        event_type = event.get("type")
+        if event_type in TASK_EVENT_TYPES.values():
+            versions[event_type] = versions.get(event_type, 0) + 1
+            event["version"] = versions[event_type]
+
+    return events
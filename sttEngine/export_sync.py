@@ -0,0 +1,298 @@
+"""Mirror transcripts and summaries to external storage.
+
+``EXPORT_SYNC_TARGET`` selects where records are copied:
+
+* a local path or ``file:///...`` — a sync folder (Dropbox, Syncthing, NAS mount);
+* ``webdav://host/path`` / ``webdavs://host/path`` — a WebDAV share
+  (Nextcloud etc.), with ``EXPORT_SYNC_USERNAME``/``EXPORT_SYNC_PASSWORD``;
+* ``s3://bucket/prefix`` — an S3 bucket (needs ``boto3``; credentials come
+  from the usual AWS environment/config).
+
+Each record becomes a folder ``{YYYY-MM-DD}_{파일명}_{id 앞 8자}/`` holding its
+artifacts in ``EXPORT_SYNC_FORMAT``: ``md`` (files as stored), ``txt``
+(transcript as readable paragraphs, summary as text) or ``json`` (one
+``record.json`` with metadata, transcript and summary).
+
+The hash of every pushed file is kept in ``DB/export_sync_state.json`` so a
+run only uploads new or changed files; deleted records are left at the
+target. Runs happen every ``EXPORT_SYNC_INTERVAL_MINUTES`` or on request
+(``POST /admin/export-sync/run``).
+"""
+
+from __future__ import annotations
+
+import hashlib
+import json
+import re
+import threading
+import time
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Callable, Dict, Iterable, List, Optional
+from urllib.parse import quote, unquote, urlparse
+
+import requests
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value, get_db_base_path
+    from .paragraphs import build_paragraphs, render_paragraphs, segments_from_markdown
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value, get_db_base_path  # type: ignore
+    from paragraphs import build_paragraphs, render_paragraphs, segments_from_markdown  # type: ignore
+
+EXPORT_SYNC_TARGET = get_config_value("EXPORT_SYNC_TARGET", "", str).strip()
+EXPORT_SYNC_FORMAT = get_config_value("EXPORT_SYNC_FORMAT", "md", str).strip().lower()
+EXPORT_SYNC_ARTIFACTS = get_config_value("EXPORT_SYNC_ARTIFACTS", "transcript,summary", str)
+EXPORT_SYNC_INTERVAL_MINUTES = get_config_value("EXPORT_SYNC_INTERVAL_MINUTES", 60, float)
+EXPORT_SYNC_USERNAME = get_config_value("EXPORT_SYNC_USERNAME", "", str)
+EXPORT_SYNC_PASSWORD = get_config_value("EXPORT_SYNC_PASSWORD", "", str)
+EXPORT_SYNC_TIMEOUT_SECONDS = get_config_value("EXPORT_SYNC_TIMEOUT_SECONDS", 30, float)
+
+EXPORT_SYNC_STATE_FILE = get_db_base_path() / "export_sync_state.json"
+FORMATS = ("md", "txt", "json")
+ARTIFACTS = ("transcript", "summary")
+MAX_RUN_ERRORS = 50
+_run_lock = threading.Lock()
+
+
+class ExportSyncError(RuntimeError):
+    """Raised for an unusable target or format."""
+
+
+class ExportSyncBusy(RuntimeError):
+    """Raised when a sync run is already in progress."""
+
+
+class LocalFolderTarget:
+    def __init__(self, root: Path):
+        self.root = root
+
+    def put(self, key: str, data: bytes, content_type: str) -> None:
+        path = self.root / key
+        path.parent.mkdir(parents=True, exist_ok=True)
+        tmp_path = path.with_name(path.name + ".tmp")
+        tmp_path.write_bytes(data)
+        tmp_path.replace(path)
+
+
+class WebDAVTarget:
+    def __init__(self, base_url: str, username: str = "", password: str = ""):
+        self.base_url = base_url.rstrip("/")
+        self.auth = (username, password) if username else None
+        self._collections = set()
+
+    def _ensure_collection(self, key: str) -> None:
+        parts = key.split("/")[:-1]
+        for depth in range(1, len(parts) + 1):
+            collection = "/".join(parts[:depth])
+            if collection in self._collections:
+                continue
+            response = requests.request("MKCOL", f"{self.base_url}/{quote(collection)}/", auth=self.auth,
+                                        timeout=EXPORT_SYNC_TIMEOUT_SECONDS)
+            # 405: 이미 존재
+            if response.status_code not in (201, 405):
+                raise ExportSyncError(f"WebDAV 폴더 생성 실패 ({collection}): HTTP {response.status_code}")
+            self._collections.add(collection)
+
+    def put(self, key: str, data: bytes, content_type: str) -> None:
+        self._ensure_collection(key)
+        response = requests.put(f"{self.base_url}/{quote(key)}", data=data, auth=self.auth,
+                                headers={"Content-Type": content_type}, timeout=EXPORT_SYNC_TIMEOUT_SECONDS)
+        if response.status_code >= 400:
+            raise ExportSyncError(f"WebDAV 업로드 실패 ({key}): HTTP {response.status_code}")
+
+
+class S3Target:
+    def __init__(self, bucket: str, prefix: str = ""):
+        try:
+            import boto3  # type: ignore
+        except ImportError:
+            raise ExportSyncError("S3 대상에는 boto3 패키지가 필요합니다 (pip install boto3).") from None
+        self.bucket = bucket
+        self.prefix = prefix.strip("/")
+        self.client = boto3.client("s3")
+
+    def put(self, key: str, data: bytes, content_type: str) -> None:
+        full_key = f"{self.prefix}/{key}" if self.prefix else key
+        self.client.put_object(Bucket=self.bucket, Key=full_key, Body=data, ContentType=content_type)
+
+
+def target_from_url(url: Optional[str] = None):
+    """Storage backend for ``url`` (default ``EXPORT_SYNC_TARGET``, see the module docstring)."""
+    url = EXPORT_SYNC_TARGET if url is None else url
+    if not url:
+        raise ExportSyncError("EXPORT_SYNC_TARGET이 설정되지 않았습니다.")
+    parsed = urlparse(url)
+    if parsed.scheme in ("webdav", "webdavs"):
+        scheme = "https" if parsed.scheme == "webdavs" else "http"
+        return WebDAVTarget(f"{scheme}://{parsed.netloc}{parsed.path}", EXPORT_SYNC_USERNAME, EXPORT_SYNC_PASSWORD)
+    if parsed.scheme == "s3":
+        if not parsed.netloc:
+            raise ExportSyncError("S3 대상은 s3://버킷/경로 형식이어야 합니다.")
+        return S3Target(parsed.netloc, parsed.path)
+    if parsed.scheme == "file":
+        return LocalFolderTarget(Path(unquote(parsed.path)))
+    if parsed.scheme and len(parsed.scheme) > 1:
+        raise ExportSyncError(f"지원하지 않는 동기화 대상입니다: {parsed.scheme}://")
+    return LocalFolderTarget(Path(url).expanduser())
+
+
+def describe_target(url: Optional[str] = None) -> Optional[str]:
+    """Target URL without credentials embedded in it."""
+    url = EXPORT_SYNC_TARGET if url is None else url
+    if not url:
+        return None
+    parsed = urlparse(url)
+    if parsed.password:
+        return url.replace(f":{parsed.password}@", ":********@", 1)
+    return url
+
+
+def selected_artifacts() -> List[str]:
+    return [name.strip() for name in EXPORT_SYNC_ARTIFACTS.split(",") if name.strip() in ARTIFACTS]
+
+
+def record_folder(record: Dict[str, Any]) -> str:
+    stem = Path(record.get("filename") or "record").stem
+    stem = re.sub(r"[\\/:*?\"<>|\s]+", "_", stem).strip("._") or "record"
+    date = (record.get("timestamp") or "")[:10] or "undated"
+    return f"{date}_{stem[:60]}_{record['id'][:8]}"
+
+
+def render_files(record: Dict[str, Any], texts: Dict[str, str], export_format: str) -> Dict[str, tuple]:
+    """``{key: (bytes, content_type)}`` of one record in ``export_format``."""
+    folder = record_folder(record)
+    if export_format == "json":
+        document = {
+            "id": record["id"],
+            "filename": record.get("filename"),
+            "created_at": record.get("timestamp"),
+            "completed_at": record.get("completed_at"),
+            "one_line_summary": record.get("title_summary"),
+            "tags": record.get("tags") or [],
+            **{name: texts.get(name) for name in ARTIFACTS if name in texts},
+        }
+        data = json.dumps(document, ensure_ascii=False, indent=2).encode("utf-8")
+        return {f"{folder}/record.json": (data, "application/json")}
+    files = {}
+    for name, text in texts.items():
+        if export_format == "txt":
+            if name == "transcript":
+                title = (text.splitlines() or [""])[0]
+                paragraphs = build_paragraphs(segments_from_markdown(text))
+                if paragraphs:
+                    text = render_paragraphs(title, paragraphs, "txt", record.get("speaker_names"))
+            files[f"{folder}/{name}.txt"] = (text.encode("utf-8"), "text/plain; charset=utf-8")
+        else:
+            files[f"{folder}/{name}.md"] = (text.encode("utf-8"), "text/markdown; charset=utf-8")
+    return files
+
+
+def load_state() -> Dict[str, Any]:
+    try:
+        with open(EXPORT_SYNC_STATE_FILE, "r", encoding="utf-8") as f:
+            return json.load(f)
+    except (OSError, json.JSONDecodeError):
+        return {"files": {}, "last_run": None}
+
+
+def _save_state(state: Dict[str, Any]) -> None:
+    EXPORT_SYNC_STATE_FILE.parent.mkdir(parents=True, exist_ok=True)
+    tmp_path = EXPORT_SYNC_STATE_FILE.with_suffix(".json.tmp")
+    with open(tmp_path, "w", encoding="utf-8") as f:
+        json.dump(state, f, ensure_ascii=False, indent=2)
+    tmp_path.replace(EXPORT_SYNC_STATE_FILE)
+
+
+def run_sync(records: Iterable[Dict[str, Any]],
+             read_texts: Callable[[Dict[str, Any], List[str]], Dict[str, str]],
+             dry_run: bool = False, full: bool = False, trigger: str = "manual") -> Dict[str, Any]:
+    """Push new/changed artifacts of ``records`` to the target.
+
+    ``read_texts(record, artifacts)`` returns the record's texts by artifact
+    name (missing artifacts left out). ``full`` ignores the saved state and
+    pushes everything; ``dry_run`` only reports what would be pushed.
+    """
+    if EXPORT_SYNC_FORMAT not in FORMATS:
+        raise ExportSyncError(f"EXPORT_SYNC_FORMAT은 {', '.join(FORMATS)} 중 하나여야 합니다.")
+    if not _run_lock.acquire(blocking=False):
+        raise ExportSyncBusy("동기화가 이미 실행 중입니다.")
+    try:
+        target = target_from_url()
+        state = load_state()
+        pushed_hashes = {} if full else dict(state.get("files") or {})
+        artifacts = selected_artifacts()
+        started = time.time()
+        report = {"trigger": trigger, "target": describe_target(), "format": EXPORT_SYNC_FORMAT,
+                  "dry_run": dry_run, "full": full, "started_at": datetime.now().isoformat(),
+                  "records": 0, "pushed": 0, "unchanged": 0, "failed": 0, "errors": [], "files": []}
+        for record in records:
+            texts = read_texts(record, artifacts)
+            if not texts:
+                continue
+            report["records"] += 1
+            for key, (data, content_type) in render_files(record, texts, EXPORT_SYNC_FORMAT).items():
+                digest = hashlib.sha256(data).hexdigest()
+                if pushed_hashes.get(key) == digest:
+                    report["unchanged"] += 1
+                    continue
+                if dry_run:
+                    report["files"].append(key)
+                    report["pushed"] += 1
+                    continue
+                try:
+                    target.put(key, data, content_type)
+                except Exception as exc:
+                    report["failed"] += 1
+                    if len(report["errors"]) < MAX_RUN_ERRORS:
+                        report["errors"].append({"key": key, "error": str(exc)})
+                    continue
+                pushed_hashes[key] = digest
+                report["pushed"] += 1
+                if len(report["files"]) < MAX_RUN_ERRORS:
+                    report["files"].append(key)
+        report["duration_seconds"] = round(time.time() - started, 2)
+        if not dry_run:
+            state["files"] = {**(state.get("files") or {}), **pushed_hashes}
+            state["last_run"] = report
+            _save_state(state)
+        return report
+    finally:
+        _run_lock.release()
+
+
+def sync_status() -> Dict[str, Any]:
+    state = load_state()
+    return {
+        "enabled": bool(EXPORT_SYNC_TARGET),
+        "target": describe_target(),
+        "format": EXPORT_SYNC_FORMAT,
+        "artifacts": selected_artifacts(),
+        "interval_minutes": EXPORT_SYNC_INTERVAL_MINUTES,
+        "tracked_files": len(state.get("files") or {}),
+        "last_run": state.get("last_run"),
+    }
+
+
+def start_sync_scheduler(list_records: Callable[[], List[Dict[str, Any]]],
+                         read_texts: Callable[[Dict[str, Any], List[str]], Dict[str, str]],
+                         interval_minutes: float = EXPORT_SYNC_INTERVAL_MINUTES):
+    """Periodically push deltas in a daemon thread (``None`` when disabled)."""
+    if not EXPORT_SYNC_TARGET or interval_minutes <= 0:
+        return None
+
+    def run():
+        while True:
+            time.sleep(interval_minutes * 60)
+            try:
+                report = run_sync(list_records(), read_texts, trigger="scheduler")
+                if report["pushed"] or report["failed"]:
+                    print(f"내보내기 동기화: {report['pushed']}개 전송, {report['failed']}개 실패")
+            except ExportSyncBusy:
+                pass
+            except Exception as exc:
+                print(f"내보내기 동기화 실패: {exc}")
+
+    thread = threading.Thread(target=run, daemon=True)
+    thread.start()
+    return thread
@@ -0,0 +1,137 @@
+"""Upload filename sanitization and export naming.
+
+Upload names come from browsers, phone shortcuts, e-mail attachments and
+remote URLs, and may carry path separators, control characters, reserved
+Windows names (``CON``, ``LPT1``), decomposed macOS Hangul or hundreds of
+bytes. :func:`sanitize_filename` turns any of them into a name that is
+safe on every platform and inside ZIP archives:
+
+* NFC normalisation, directory parts and control characters removed;
+* ``<>:"/\\|?*`` replaced with ``_``, runs of whitespace collapsed, leading
+  and trailing dots/spaces stripped;
+* reserved device names prefixed with ``_``;
+* the stem cut on a character boundary so the whole name fits in
+  ``UPLOAD_FILENAME_MAX_BYTES`` of UTF-8 (the extension is kept);
+* non-ASCII characters replaced too when ``UPLOAD_FILENAME_ASCII_ONLY``.
+
+Uploads are stored under the sanitized name; the name as sent is kept on
+the record as ``original_filename`` (:func:`clean_original_name`) and
+exports are named from it. When two entries of a ZIP bundle end up with
+the same name, ``EXPORT_NAME_COLLISION`` decides:
+
+    suffix     회의.md, 회의 (2).md, 회의 (3).md
+    record_id  회의.md, 회의-3f2c9a1b.md (first 8 characters of the record ID)
+    folder     every record's files go into a folder named after the record
+"""
+
+from __future__ import annotations
+
+import re
+import unicodedata
+from pathlib import PurePosixPath
+from typing import Optional, Set
+from urllib.parse import quote
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+    from .text_utils import truncate_bytes
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+    from text_utils import truncate_bytes  # type: ignore
+
+UPLOAD_FILENAME_MAX_BYTES = min(255, max(32, get_config_value("UPLOAD_FILENAME_MAX_BYTES", 200, int)))
+UPLOAD_FILENAME_ASCII_ONLY = get_config_value("UPLOAD_FILENAME_ASCII_ONLY", False, bool)
+COLLISION_POLICIES = ("suffix", "record_id", "folder")
+EXPORT_NAME_COLLISION = get_config_value("EXPORT_NAME_COLLISION", "suffix", str).strip().lower()
+# 기록에 남기는 원본 이름의 최대 길이 (표시용)
+ORIGINAL_NAME_MAX_CHARS = 500
+
+_UNSAFE_CHARS = re.compile(r'[<>:"/\\|?*]')
+_CONTROL_CHARS = re.compile("[\x00-\x1f\x7f-\x9f\u200b-\u200f\u202a-\u202e\u2066-\u2069]")
+_WHITESPACE = re.compile(r"\s+")
+_RESERVED_NAMES = {"CON", "PRN", "AUX", "NUL", *(f"COM{i}" for i in range(1, 10)), *(f"LPT{i}" for i in range(1, 10))}
+# 확장자로 인정할 꼬리 (".tar.gz"처럼 긴 것은 마지막 부분만)
+_EXTENSION = re.compile(r"^\.[A-Za-z0-9]{1,10}$")
+
+
+class FilenameError(ValueError):
+    """Raised for unknown collision policies."""
+
+
+def clean_original_name(name: Optional[str]) -> str:
+    """The name as uploaded, minus directories and control characters, for display and exports."""
+    name = unicodedata.normalize("NFC", str(name or ""))
+    name = re.split(r"[\\/]", name)[-1]
+    name = _CONTROL_CHARS.sub("", name).strip()
+    return name[:ORIGINAL_NAME_MAX_CHARS]
+
+
+def _split_extension(name: str):
+    suffix = PurePosixPath(name).suffix
+    if suffix and _EXTENSION.match(suffix) and len(suffix) < len(name):
+        return name[:-len(suffix)], suffix.lower()
+    return name, ""
+
+
+def sanitize_filename(name: Optional[str], default: str = "file", max_bytes: int = None,
+                      ascii_only: bool = None) -> str:
+    """File name safe on Windows/macOS/Linux and in ZIP archives (see module docstring)."""
+    max_bytes = max_bytes or UPLOAD_FILENAME_MAX_BYTES
+    ascii_only = UPLOAD_FILENAME_ASCII_ONLY if ascii_only is None else ascii_only
+    stem, extension = _split_extension(clean_original_name(name))
+    if ascii_only:
+        # 악센트는 풀어서 살리고 나머지 비ASCII 문자는 _로
+        stem = unicodedata.normalize("NFKD", stem)
+        stem = "".join(ch for ch in stem if not unicodedata.combining(ch))
+        stem = "".join(ch if ord(ch) < 128 else "_" for ch in stem)
+    stem = _UNSAFE_CHARS.sub("_", stem)
+    stem = _WHITESPACE.sub(" ", stem).strip(" .")
+    stem = re.sub(r"_{2,}", "_", stem).strip("_ .") or default
+    if stem.split(".")[0].upper() in _RESERVED_NAMES:
+        stem = f"_{stem}"
+    stem = truncate_bytes(stem, max_bytes - len(extension.encode("utf-8"))).rstrip(" .") or default
+    return stem + extension
+
+
+def export_filename(record: dict, suffix: str = "", default: str = "record") -> str:
+    """Download/export name from the record's original upload name with ``suffix`` (e.g. ``.summary.md``)."""
+    original = record.get("original_filename") or record.get("filename") or default
+    stem, _ = _split_extension(clean_original_name(original))
+    return sanitize_filename(f"{stem or default}{suffix}", default=default)
+
+
+def resolve_collision_policy(policy: Optional[str] = None) -> str:
+    policy = (policy or EXPORT_NAME_COLLISION or "suffix").strip().lower()
+    if policy not in COLLISION_POLICIES:
+        raise FilenameError(f"알 수 없는 이름 충돌 정책: {policy} (사용 가능: {', '.join(COLLISION_POLICIES)})")
+    return policy
+
+
+def unique_archive_name(name: str, taken: Set[str], policy: str, record_id: str = "") -> str:
+    """``name`` made unique among ``taken`` (case-insensitive) by ``policy``; the result is added to ``taken``.
+
+    With the ``folder`` policy callers put the record folder in ``name``
+    already; a clash left over (two records with the same folder name)
+    falls back to ``suffix``.
+    """
+    def free(candidate: str) -> bool:
+        return candidate.casefold() not in taken
+
+    candidate = name
+    if not free(candidate) and policy == "record_id" and record_id:
+        stem, extension = _split_extension(name)
+        candidate = f"{stem}-{record_id.replace('-', '')[:8]}{extension}"
+    counter = 2
+    while not free(candidate):
+        parent, _, base = name.rpartition("/")
+        stem, extension = _split_extension(base)
+        candidate = f"{parent + '/' if parent else ''}{stem} ({counter}){extension}"
+        counter += 1
+    taken.add(candidate.casefold())
+    return candidate
+
+
+def content_disposition(filename: str) -> str:
+    """``Content-Disposition: attachment`` value with an ASCII fallback and RFC 5987 ``filename*``."""
+    ascii_name = sanitize_filename(filename, default="download", max_bytes=255, ascii_only=True)
+    return f"attachment; filename=\"{ascii_name}\"; filename*=UTF-8''{quote(filename)}"
@@ -0,0 +1,207 @@
+"""Auto-ingest of recordings dropped into watched folders.
+
+Phones and sync tools (Syncthing, Dropbox, iCloud Drive, a voice recorder's
+USB folder) can put new recordings into a folder; with ``WATCH_DIRS`` set
+the server picks them up without an upload:
+
+* every ``WATCH_POLL_SECONDS`` the folders (with their subfolders when
+  ``WATCH_RECURSIVE``) are scanned for audio/video files. Scanning instead
+  of file-system events behaves the same on every platform and on network
+  or synced drives, where events are often missing;
+* a file is taken once its size and modification time have not changed
+  for ``WATCH_SETTLE_SECONDS``, because sync tools write large files in
+  pieces. Hidden and temporary files (``.part``, ``.tmp``, ``~$...``) are
+  skipped;
+* it is copied into the upload store like an upload (the original stays
+  where it is), registered in history with ``watched_path`` and processed
+  with ``WATCH_STEPS``. Content already in history is not added again.
+
+Handled files are remembered in ``DB/folder_watch_state.json`` by path,
+size and mtime, so a restart does not read them again while a file that
+changes afterwards is picked up anew. ``GET /folder_watch`` shows the
+folders and recent results; ``POST /folder_watch/scan`` scans right away.
+"""
+
+from __future__ import annotations
+
+import json
+import threading
+import time
+from collections import deque
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Callable, Deque, Dict, List, Optional, Tuple
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value, get_db_base_path
+    from .url_ingest import MEDIA_EXTENSIONS
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value, get_db_base_path  # type: ignore
+    from url_ingest import MEDIA_EXTENSIONS  # type: ignore
+
+WATCH_DIRS = get_config_value("WATCH_DIRS", "", str)
+WATCH_POLL_SECONDS = get_config_value("WATCH_POLL_SECONDS", 10, float)
+WATCH_SETTLE_SECONDS = get_config_value("WATCH_SETTLE_SECONDS", 5, float)
+WATCH_RECURSIVE = get_config_value("WATCH_RECURSIVE", True, bool)
+WATCH_STEPS = get_config_value("WATCH_STEPS", "stt,summary,embedding", str)
+STATE_FILE = get_db_base_path() / "folder_watch_state.json"
+
+# 동기화 도구가 쓰는 중인 임시 파일
+TEMP_SUFFIXES = (".part", ".partial", ".tmp", ".crdownload", ".download", ".!sync", ".syncthing")
+RECENT_LIMIT = 50
+
+_state_lock = threading.Lock()
+# 크기/수정 시각이 바뀌지 않은 채 기다리는 파일: 경로 → (크기, mtime, 처음 본 시각)
+_pending: Dict[str, Tuple[int, float, float]] = {}
+_recent: Deque[Dict[str, Any]] = deque(maxlen=RECENT_LIMIT)
+_last_scan: Dict[str, Any] = {}
+
+
+def watch_dirs() -> List[Path]:
+    return [Path(entry.strip()).expanduser() for entry in WATCH_DIRS.split(",") if entry.strip()]
+
+
+def processing_steps() -> List[str]:
+    return [step.strip() for step in WATCH_STEPS.split(",") if step.strip()]
+
+
+def folder_watch_enabled() -> bool:
+    return bool(watch_dirs()) and WATCH_POLL_SECONDS > 0
+
+
+def is_candidate(path: Path) -> bool:
+    """A media file that is not hidden or a sync tool's temporary file."""
+    name = path.name
+    if name.startswith((".", "~$")) or name.lower().endswith(TEMP_SUFFIXES):
+        return False
+    return path.suffix.lower() in MEDIA_EXTENSIONS
+
+
+def _load_state() -> Dict[str, Dict[str, Any]]:
+    try:
+        with open(STATE_FILE, "r", encoding="utf-8") as f:
+            state = json.load(f)
+        files = state.get("files") if isinstance(state, dict) else None
+        return files if isinstance(files, dict) else {}
+    except (OSError, ValueError):
+        return {}
+
+
+def _save_state(files: Dict[str, Dict[str, Any]]) -> None:
+    STATE_FILE.parent.mkdir(parents=True, exist_ok=True)
+    tmp_path = STATE_FILE.with_name(f"{STATE_FILE.name}.tmp")
+    with open(tmp_path, "w", encoding="utf-8") as f:
+        json.dump({"files": files}, f, ensure_ascii=False, indent=2)
+    tmp_path.replace(STATE_FILE)
+
+
+def _candidates(folder: Path) -> List[Path]:
+    pattern = folder.rglob("*") if WATCH_RECURSIVE else folder.glob("*")
+    try:
+        return sorted(path for path in pattern if is_candidate(path) and path.is_file())
+    except OSError as exc:
+        print(f"감시 폴더를 읽지 못했습니다 ({folder}): {exc}")
+        return []
+
+
+def scan(handler: Callable[[Path], Dict[str, Any]], now: Optional[float] = None) -> List[Dict[str, Any]]:
+    """Hand every new, settled file in the watched folders to ``handler``; returns the results.
+
+    ``handler`` registers the file and returns its ``/upload`` style entry
+    (``record_id``/``task_id`` or ``duplicate``). A file whose handler fails
+    is remembered as failed and retried only after it changes.
+    """
+    now = time.time() if now is None else now
+    results: List[Dict[str, Any]] = []
+    with _state_lock:
+        files = _load_state()
+        seen = set()
+        for folder in watch_dirs():
+            if not folder.is_dir():
+                continue
+            for path in _candidates(folder):
+                key = str(path)
+                seen.add(key)
+                try:
+                    stat = path.stat()
+                except OSError:
+                    continue
+                handled = files.get(key)
+                if handled and handled.get("size") == stat.st_size and handled.get("mtime") == stat.st_mtime:
+                    continue
+                pending = _pending.get(key)
+                if not pending or pending[:2] != (stat.st_size, stat.st_mtime):
+                    # 처음 봤거나 아직 쓰는 중: 크기가 그대로인지 다음 검사에서 확인
+                    _pending[key] = (stat.st_size, stat.st_mtime, now)
+                    continue
+                if now - pending[2] < WATCH_SETTLE_SECONDS:
+                    continue
+                del _pending[key]
+                result: Dict[str, Any] = {"path": key, "at": datetime.now().isoformat()}
+                try:
+                    entry = handler(path)
+                    result.update(status="duplicate" if entry.get("duplicate") else "ingested", **entry)
+                    print(f"감시 폴더에서 가져옴: {path} → {result.get('record_id') or result.get('original_record_id')}")
+                except Exception as exc:
+                    result.update(status="failed", error=str(exc))
+                    print(f"감시 폴더 파일 가져오기 실패 ({path}): {exc}")
+                files[key] = {
+                    "size": stat.st_size,
+                    "mtime": stat.st_mtime,
+                    "status": result["status"],
+                    "record_id": result.get("record_id") or result.get("original_record_id"),
+                    "at": result["at"],
+                }
+                results.append(result)
+                _recent.appendleft(result)
+        for key in [key for key in _pending if key not in seen]:
+            del _pending[key]
+        if results:
+            _save_state(files)
+        _last_scan.update({"at": datetime.now().isoformat(), "ingested": len(results)})
+    return results
+
+
+def folder_watch_status() -> Dict[str, Any]:
+    """Folders, settings, files waiting to settle and the latest results."""
+    with _state_lock:
+        return {
+            "enabled": folder_watch_enabled(),
+            "dirs": [{"path": str(folder), "exists": folder.is_dir()} for folder in watch_dirs()],
+            "steps": processing_steps(),
+            "poll_seconds": WATCH_POLL_SECONDS,
+            "settle_seconds": WATCH_SETTLE_SECONDS,
+            "recursive": WATCH_RECURSIVE,
+            "handled_files": len(_load_state()),
+            "pending": sorted(_pending),
+            "last_scan": dict(_last_scan) or None,
+            "recent": list(_recent),
+        }
+
+
+def start_folder_watcher(handler: Callable[[Path], Dict[str, Any]],
+                         paused: Callable[[], bool] = lambda: False) -> Optional[threading.Thread]:
+    """Scan ``WATCH_DIRS`` every ``WATCH_POLL_SECONDS`` in a daemon thread (``None`` when not configured).
+
+    While ``paused()`` is true nothing is taken, so files dropped meanwhile
+    are picked up once scanning resumes.
+    """
+    if not folder_watch_enabled():
+        return None
+    for folder in watch_dirs():
+        if not folder.is_dir():
+            print(f"감시 폴더가 없습니다 (생기면 감시 시작): {folder}")
+
+    def run():
+        while True:
+            try:
+                if not paused():
+                    scan(handler)
+            except OSError as exc:
+                print(f"감시 폴더 확인 실패: {exc}")
+            time.sleep(WATCH_POLL_SECONDS)
+
+    thread = threading.Thread(target=run, daemon=True)
+    thread.start()
+    print(f"폴더 감시 시작: {', '.join(str(folder) for folder in watch_dirs())} ({WATCH_POLL_SECONDS:g}초 간격)")
+    return thread
@@ -0,0 +1,330 @@
+"""GraphQL facade over records, tasks, search and stats.
+
+``POST /graphql`` (``{"query", "variables", "operationName"}``) answers
+queries with field-level selection, so an integrator can fetch e.g. the
+titles and summaries of the last ten records in one request instead of
+stitching ``/history``, ``/download`` and ``/tasks`` together. Transcript
+and summary texts are only read from disk when they are selected.
+
+Subscriptions (``taskProgress(taskId)``) run over the progress WebSocket
+(port 8765) with the ``graphql-transport-ws`` subprotocol used by
+graphql-ws/Apollo clients; connections without that subprotocol keep the
+regular progress messages.
+
+Enabled with ``GRAPHQL_ENABLED=true``; needs the ``graphql-core`` package.
+``GET /graphql`` returns the schema in SDL.
+"""
+
+from __future__ import annotations
+
+import asyncio
+import json
+from dataclasses import dataclass
+from typing import Any, AsyncIterator, Callable, Dict, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+try:
+    import graphql
+except ImportError:  # pragma: no cover - optional dependency
+    graphql = None
+
+GRAPHQL_ENABLED = get_config_value("GRAPHQL_ENABLED", False, bool)
+GRAPHQL_MAX_RECORDS = max(1, get_config_value("GRAPHQL_MAX_RECORDS", 100, int))
+WS_SUBPROTOCOL = "graphql-transport-ws"
+# graphql-transport-ws 종료 코드
+CLOSE_INVALID_MESSAGE = 4400
+CLOSE_UNAUTHORIZED = 4401
+CLOSE_DUPLICATE_SUBSCRIBER = 4409
+CLOSE_TOO_MANY_INIT = 4429
+
+SCHEMA_SDL = '''
+"Arbitrary JSON value"
+scalar JSON
+
+enum SearchTarget { transcript summary both }
+
+type Record {
+  id: ID!
+  filename: String
+  fileType: String
+  createdAt: String
+  updatedAt: String
+  completedAt: String
+  duration: String
+  durationSeconds: Float
+  "Size of the uploaded file in bytes"
+  fileSize: Float
+  "Container and first audio stream: container, codec, sample_rate, channels, bit_rate"
+  audioFormat: JSON
+  "Language detected by (or given to) the transcription"
+  language: String
+  "Probability of the detected language (null when the language was given)"
+  languageConfidence: Float
+  tags: [String!]!
+  oneLineSummary: String
+  "Steps that finished: stt, embedding, summary"
+  completedTasks: [String!]!
+  staleArtifacts: [String!]!
+  downloadLinks: JSON
+  "Transcript markdown (read on demand)"
+  transcript: String
+  "Summary markdown (read on demand)"
+  summary: String
+}
+
+type TaskProgressEvent {
+  taskId: ID!
+  seq: Int!
+  message: String
+  timestamp: Float!
+  done: Boolean!
+  queue: JSON
+}
+
+type Task {
+  id: ID!
+  status: String!
+  startTime: Float
+  duration: Float
+  cancelled: Boolean!
+  queuePosition: Int
+  progress: TaskProgressEvent
+}
+
+type SearchHit {
+  score: Float!
+  kind: String!
+  file: String!
+  record: Record
+}
+
+type Query {
+  records(limit: Int = 20, offset: Int = 0, tag: String): [Record!]!
+  record(id: ID!): Record
+  tasks: [Task!]!
+  search(query: String!, target: SearchTarget = both, limit: Int = 10): [SearchHit!]!
+  "Archive statistics (same shape as GET /stats/archive)"
+  stats(topTags: Int = 10): JSON!
+}
+
+type Subscription {
+  "Progress events of one task until its final done event"
+  taskProgress(taskId: ID!): TaskProgressEvent!
+}
+'''
+
+
+class GraphQLUnavailable(RuntimeError):
+    """Raised when GraphQL is disabled or graphql-core is missing."""
+
+
+@dataclass
+class GraphQLSources:
+    """Server callbacks the resolvers read from."""
+
+    records: Callable[[], List[Dict[str, Any]]]
+    read_text: Callable[[Dict[str, Any], str], str]
+    tasks: Callable[[], Dict[str, Dict[str, Any]]]
+    latest_progress: Callable[[str], Optional[Dict[str, Any]]]
+    wait_progress: Callable[[str, int, float], Optional[Dict[str, Any]]]
+    search: Callable[[str, str, int], List[Dict[str, Any]]]
+    stats: Callable[[int], Dict[str, Any]]
+
+
+def _progress(event: Optional[Dict[str, Any]]) -> Optional[Dict[str, Any]]:
+    if not event:
+        return None
+    return {"taskId": event["task_id"], "seq": event["seq"], "message": event.get("message"),
+            "timestamp": event["timestamp"], "done": bool(event.get("done")), "queue": event.get("queue")}
+
+
+def _record(record: Dict[str, Any]) -> Dict[str, Any]:
+    return {
+        "id": record["id"],
+        "filename": record.get("filename"),
+        "fileType": record.get("file_type"),
+        "createdAt": record.get("timestamp"),
+        "updatedAt": record.get("updated_at"),
+        "completedAt": record.get("completed_at"),
+        "duration": record.get("duration"),
+        "durationSeconds": record.get("duration_seconds"),
+        "fileSize": record.get("file_size"),
+        "audioFormat": record.get("audio_format"),
+        "language": record.get("language"),
+        "languageConfidence": record.get("language_confidence"),
+        "tags": record.get("tags") or [],
+        "oneLineSummary": record.get("title_summary") or None,
+        "completedTasks": [task for task, done in (record.get("completed_tasks") or {}).items() if done],
+        "staleArtifacts": record.get("stale_artifacts") or [],
+        "downloadLinks": record.get("download_links") or {},
+        "_source": record,
+    }
+
+
+def build_schema(sources: GraphQLSources):
+    """Executable schema with resolvers bound to ``sources``."""
+    if graphql is None:
+        raise GraphQLUnavailable("GraphQL을 사용하려면 graphql-core 패키지가 필요합니다 (pip install graphql-core).")
+    schema = graphql.build_schema(SCHEMA_SDL)
+
+    def records(_, info, limit=20, offset=0, tag=None):
+        items = [r for r in sources.records() if tag is None or tag in (r.get("tags") or [])]
+        limit = max(0, min(limit, GRAPHQL_MAX_RECORDS))
+        return [_record(r) for r in items[max(0, offset):max(0, offset) + limit]]
+
+    def record(_, info, id):
+        found = next((r for r in sources.records() if r.get("id") == id), None)
+        return _record(found) if found else None
+
+    def tasks(_, info):
+        return [
+            {"id": task_id, "status": task.get("status", "running"), "startTime": task.get("start_time"),
+             "duration": task.get("duration"), "cancelled": bool(task.get("cancelled")),
+             "queuePosition": task.get("queue_position"), "progress": _progress(sources.latest_progress(task_id))}
+            for task_id, task in sources.tasks().items()
+        ]
+
+    def search(_, info, query, target="both", limit=10):
+        by_id = {r.get("id"): r for r in sources.records()}
+        hits = sources.search(query, target, max(1, min(limit, GRAPHQL_MAX_RECORDS)))
+        return [{"score": hit["score"], "kind": hit["kind"], "file": hit["file"],
+                 "record": _record(by_id[hit["record_id"]]) if hit.get("record_id") in by_id else None}
+                for hit in hits]
+
+    def stats(_, info, topTags=10):
+        return sources.stats(max(1, min(topTags, 100)))
+
+    async def subscribe_progress(_, info, taskId) -> AsyncIterator[Dict[str, Any]]:
+        since = 0
+        while True:
+            event = await asyncio.to_thread(sources.wait_progress, taskId, since, 25)
+            if event is None:
+                continue
+            since = event["seq"]
+            yield {"taskProgress": _progress(event)}
+            if event.get("done"):
+                return
+
+    query_fields = schema.query_type.fields
+    query_fields["records"].resolve = records
+    query_fields["record"].resolve = record
+    query_fields["tasks"].resolve = tasks
+    query_fields["search"].resolve = search
+    query_fields["stats"].resolve = stats
+    record_fields = schema.type_map["Record"].fields
+    record_fields["transcript"].resolve = lambda r, info: sources.read_text(r["_source"], "stt") or None
+    record_fields["summary"].resolve = lambda r, info: sources.read_text(r["_source"], "summary") or None
+    progress_field = schema.subscription_type.fields["taskProgress"]
+    progress_field.subscribe = subscribe_progress
+    progress_field.resolve = lambda payload, info, **_: payload["taskProgress"]
+    return schema
+
+
+def schema_sdl() -> str:
+    return SCHEMA_SDL.strip() + "\n"
+
+
+def _result_payload(result) -> Dict[str, Any]:
+    payload: Dict[str, Any] = {"data": result.data}
+    if result.errors:
+        payload["errors"] = [error.formatted for error in result.errors]
+    return payload
+
+
+def execute(schema, payload: Dict[str, Any]) -> Dict[str, Any]:
+    """Run a query or mutation request body; subscriptions are rejected (they need the WebSocket)."""
+    query = payload.get("query")
+    if not isinstance(query, str) or not query.strip():
+        return {"errors": [{"message": "query가 필요합니다."}]}
+    variables = payload.get("variables")
+    if variables is not None and not isinstance(variables, dict):
+        return {"errors": [{"message": "variables는 객체여야 합니다."}]}
+    try:
+        document = graphql.parse(query)
+    except graphql.GraphQLError as exc:
+        return {"errors": [exc.formatted]}
+    errors = graphql.validate(schema, document)
+    if errors:
+        return {"errors": [error.formatted for error in errors]}
+    operation = graphql.get_operation_ast(document, payload.get("operationName"))
+    if operation is not None and operation.operation == graphql.OperationType.SUBSCRIPTION:
+        return {"errors": [{"message": f"구독은 WebSocket({WS_SUBPROTOCOL})으로만 사용할 수 있습니다."}]}
+    result = graphql.execute(schema, document, variable_values=variables,
+                             operation_name=payload.get("operationName"))
+    return _result_payload(result)
+
+
+async def serve_websocket(websocket, schema) -> None:
+    """Speak ``graphql-transport-ws`` on an accepted WebSocket until it closes."""
+    acknowledged = False
+    operations: Dict[str, asyncio.Task] = {}
+
+    async def send(message: Dict[str, Any]) -> None:
+        await websocket.send(json.dumps(message, ensure_ascii=False))
+
+    async def run_operation(op_id: str, payload: Dict[str, Any]) -> None:
+        try:
+            document = graphql.parse(payload.get("query") or "")
+            errors = graphql.validate(schema, document)
+            if errors:
+                await send({"id": op_id, "type": "error", "payload": [e.formatted for e in errors]})
+                return
+            operation = graphql.get_operation_ast(document, payload.get("operationName"))
+            kwargs = {"variable_values": payload.get("variables"), "operation_name": payload.get("operationName")}
+            if operation is not None and operation.operation == graphql.OperationType.SUBSCRIPTION:
+                stream = await graphql.subscribe(schema, document, **kwargs)
+                if isinstance(stream, graphql.ExecutionResult):
+                    await send({"id": op_id, "type": "error", "payload": [e.formatted for e in stream.errors or []]})
+                    return
+                async for result in stream:
+                    await send({"id": op_id, "type": "next", "payload": _result_payload(result)})
+            else:
+                result = graphql.execute(schema, document, **kwargs)
+                await send({"id": op_id, "type": "next", "payload": _result_payload(result)})
+            await send({"id": op_id, "type": "complete"})
+        except graphql.GraphQLError as exc:
+            await send({"id": op_id, "type": "error", "payload": [exc.formatted]})
+        except asyncio.CancelledError:
+            pass
+        finally:
+            operations.pop(op_id, None)
+
+    try:
+        async for raw in websocket:
+            try:
+                message = json.loads(raw)
+                message_type = message["type"]
+            except (TypeError, ValueError, KeyError):
+                await websocket.close(CLOSE_INVALID_MESSAGE, "invalid message")
+                return
+            if message_type == "connection_init":
+                if acknowledged:
+                    await websocket.close(CLOSE_TOO_MANY_INIT, "too many initialisation requests")
+                    return
+                acknowledged = True
+                await send({"type": "connection_ack"})
+            elif message_type == "ping":
+                await send({"type": "pong"})
+            elif message_type == "subscribe":
+                if not acknowledged:
+                    await websocket.close(CLOSE_UNAUTHORIZED, "unauthorized")
+                    return
+                op_id = message.get("id")
+                if not isinstance(op_id, str) or not isinstance(message.get("payload"), dict):
+                    await websocket.close(CLOSE_INVALID_MESSAGE, "invalid subscribe message")
+                    return
+                if op_id in operations:
+                    await websocket.close(CLOSE_DUPLICATE_SUBSCRIBER, f"subscriber for {op_id} already exists")
+                    return
+                operations[op_id] = asyncio.ensure_future(run_operation(op_id, message["payload"]))
+            elif message_type == "complete":
+                task = operations.pop(message.get("id"), None)
+                if task:
+                    task.cancel()
+    finally:
+        for task in list(operations.values()):
+            task.cancel()
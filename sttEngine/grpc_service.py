@@ -0,0 +1,239 @@
+"""gRPC service for embedding RecordRoute in backend systems.
+
+The service (``protos/recordroute.proto``, package ``recordroute.v1``)
+mirrors the HTTP pipeline endpoints:
+
+* ``Upload`` — client-streamed file chunks; optionally starts steps;
+* ``Process`` / ``GetStatus`` — start steps for a record, read its state;
+* ``WatchProgress`` — server-streamed progress events of a task (the
+  progress bus, like ``/tasks/{id}/wait``) until the final ``done`` event;
+* ``Search`` — vector search mapped to records;
+* ``StreamTranscript`` — server-streamed transcript segments (clean or
+  verbatim view).
+
+Enabled with ``GRPC_ENABLED=true`` and served on ``GRPC_HOST:GRPC_PORT``.
+Needs ``grpcio`` and ``grpcio-tools``: the proto is compiled at startup with
+``grpc.protos_and_services`` so no generated code is kept in the repo.
+"""
+
+from __future__ import annotations
+
+from concurrent import futures
+from dataclasses import dataclass
+from pathlib import Path
+from typing import Any, Callable, Dict, Iterator, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .api_tokens import SCOPE_GROUPS, authenticate, token_required
+    from .config import get_config_value
+    from .maintenance import BLOCKED_GROUPS as MAINTENANCE_BLOCKED_GROUPS, maintenance_active, maintenance_state
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from api_tokens import SCOPE_GROUPS, authenticate, token_required  # type: ignore
+    from config import get_config_value  # type: ignore
+    from maintenance import (  # type: ignore
+        BLOCKED_GROUPS as MAINTENANCE_BLOCKED_GROUPS,
+        maintenance_active,
+        maintenance_state,
+    )
+
+GRPC_ENABLED = get_config_value("GRPC_ENABLED", False, bool)
+GRPC_HOST = get_config_value("GRPC_HOST", "127.0.0.1", str)
+GRPC_PORT = get_config_value("GRPC_PORT", 50051, int)
+GRPC_MAX_WORKERS = max(2, get_config_value("GRPC_MAX_WORKERS", 8, int))
+GRPC_MAX_UPLOAD_MB = get_config_value("GRPC_MAX_UPLOAD_MB", 2048, int)
+PROTO_FILE = Path(__file__).parent / "protos" / "recordroute.proto"
+PROGRESS_WAIT_SECONDS = 25
+
+
+class GrpcUnavailable(RuntimeError):
+    """Raised when grpcio/grpcio-tools are not installed."""
+
+
+class PipelineError(ValueError):
+    """Raised by source callbacks for invalid requests; ``status`` names the gRPC status code."""
+
+    def __init__(self, message: str, status: str = "INVALID_ARGUMENT"):
+        self.status = status
+        super().__init__(message)
+
+
+@dataclass
+class PipelineSources:
+    """Server callbacks the service delegates to."""
+
+    store_upload: Callable[[str, bytes, List[str]], Dict[str, Any]]
+    start_steps: Callable[[str, List[str]], str]
+    record: Callable[[str], Optional[Dict[str, Any]]]
+    task_status: Callable[[str], Optional[str]]
+    latest_progress: Callable[[str], Optional[Dict[str, Any]]]
+    wait_progress: Callable[[str, int, float], Optional[Dict[str, Any]]]
+    search: Callable[[str, str, int], List[Dict[str, Any]]]
+    transcript_segments: Callable[[str, str], List[Dict[str, Any]]]
+
+
+def _load_grpc():
+    try:
+        import grpc
+        protos, services = grpc.protos_and_services(str(PROTO_FILE))
+    except (ImportError, NotImplementedError) as exc:
+        raise GrpcUnavailable(f"gRPC에는 grpcio와 grpcio-tools 패키지가 필요합니다: {exc}") from None
+    return grpc, protos, services
+
+
+def _caller_host(context) -> str:
+    # "ipv4:127.0.0.1:54321" / "ipv6:[::1]:54321"
+    peer = context.peer() or ""
+    host = peer.split(":", 1)[1] if ":" in peer else peer
+    host = host.rsplit(":", 1)[0]
+    return host.strip("[]")
+
+
+def create_server(sources: PipelineSources):
+    """gRPC server with the RecordRoute service bound to ``GRPC_HOST:GRPC_PORT`` (not started)."""
+    grpc, pb, services = _load_grpc()
+
+    def authorize(context, group: str) -> None:
+        metadata = {key.lower(): value for key, value in context.invocation_metadata()}
+        authorization = metadata.get("authorization") or ""
+        secret = authorization[7:].strip() if authorization.lower().startswith("bearer ") else None
+        if not secret:
+            if token_required(_caller_host(context), {}):
+                context.abort(grpc.StatusCode.UNAUTHENTICATED, "API 토큰이 필요합니다.")
+            return
+        token = authenticate(secret)
+        if token is None:
+            context.abort(grpc.StatusCode.UNAUTHENTICATED, "유효하지 않은 API 토큰입니다.")
+        if group not in SCOPE_GROUPS.get(token.get("scope"), set()):
+            context.abort(grpc.StatusCode.PERMISSION_DENIED, f"토큰 권한({token.get('scope')})으로 호출할 수 없습니다.")
+        if group in MAINTENANCE_BLOCKED_GROUPS and maintenance_active():
+            context.abort(grpc.StatusCode.UNAVAILABLE, maintenance_state()["message"])
+
+    def fail(context, exc: PipelineError):
+        context.abort(getattr(grpc.StatusCode, exc.status, grpc.StatusCode.INVALID_ARGUMENT), str(exc))
+
+    def progress_message(event: Dict[str, Any]):
+        return pb.ProgressEvent(
+            task_id=event["task_id"], seq=event["seq"], message=event.get("message") or "",
+            timestamp=event["timestamp"], done=bool(event.get("done")),
+            queue_position=int((event.get("queue") or {}).get("queue_position") or 0),
+        )
+
+    class RecordRouteServicer(services.RecordRouteServicer):
+        def Upload(self, request_iterator, context):
+            authorize(context, "upload")
+            first = None
+            chunks = []
+            size = 0
+            for chunk in request_iterator:
+                first = first or chunk
+                size += len(chunk.data)
+                if size > GRPC_MAX_UPLOAD_MB * 1024 * 1024:
+                    context.abort(grpc.StatusCode.RESOURCE_EXHAUSTED, f"파일이 {GRPC_MAX_UPLOAD_MB}MB를 넘습니다.")
+                chunks.append(chunk.data)
+            if first is None or not first.filename:
+                context.abort(grpc.StatusCode.INVALID_ARGUMENT, "첫 조각에 filename이 필요합니다.")
+            try:
+                entry = sources.store_upload(first.filename, b"".join(chunks), list(first.tags))
+                task_id = ""
+                if first.steps and not entry.get("duplicate"):
+                    task_id = sources.start_steps(entry["record_id"], list(first.steps))
+            except PipelineError as exc:
+                fail(context, exc)
+            return pb.UploadReply(record_id=entry["record_id"], filename=first.filename,
+                                  duplicate=bool(entry.get("duplicate")), task_id=task_id)
+
+        def Process(self, request, context):
+            authorize(context, "upload")
+            try:
+                task_id = sources.start_steps(request.record_id, list(request.steps))
+            except PipelineError as exc:
+                fail(context, exc)
+            return pb.ProcessReply(task_id=task_id, steps=list(request.steps))
+
+        def GetStatus(self, request, context):
+            authorize(context, "status")
+            record = sources.record(request.record_id) if request.record_id else None
+            if request.record_id and record is None:
+                context.abort(grpc.StatusCode.NOT_FOUND, "기록을 찾을 수 없습니다.")
+            reply = pb.StatusReply(record_id=request.record_id, task_id=request.task_id)
+            if record:
+                reply.filename = record.get("filename") or ""
+                reply.completed_steps.extend(
+                    step for step, done in (record.get("completed_tasks") or {}).items() if done)
+                reply.stale_artifacts.extend(record.get("stale_artifacts") or [])
+                reply.one_line_summary = record.get("title_summary") or ""
+            if request.task_id:
+                reply.task_status = sources.task_status(request.task_id) or ""
+                event = sources.latest_progress(request.task_id)
+                if event:
+                    reply.progress.CopyFrom(progress_message(event))
+            return reply
+
+        def WatchProgress(self, request, context) -> Iterator[Any]:
+            authorize(context, "status")
+            since = request.since
+            if not since:
+                latest = sources.latest_progress(request.task_id)
+                if latest:
+                    yield progress_message(latest)
+                    if latest.get("done"):
+                        return
+                    since = latest["seq"]
+            while context.is_active():
+                event = sources.wait_progress(request.task_id, since, PROGRESS_WAIT_SECONDS)
+                if event is None:
+                    continue
+                since = event["seq"]
+                yield progress_message(event)
+                if event.get("done"):
+                    return
+
+        def Search(self, request, context):
+            authorize(context, "read")
+            if not request.query.strip():
+                context.abort(grpc.StatusCode.INVALID_ARGUMENT, "query가 필요합니다.")
+            try:
+                hits = sources.search(request.query, request.target or "both", max(1, min(request.limit or 10, 100)))
+            except ValueError as exc:
+                context.abort(grpc.StatusCode.INVALID_ARGUMENT, str(exc))
+            return pb.SearchReply(hits=[
+                pb.SearchHit(record_id=hit.get("record_id") or "", filename=hit.get("filename") or "",
+                             kind=hit.get("kind") or "", score=hit.get("score") or 0.0, file=hit.get("file") or "")
+                for hit in hits
+            ])
+
+        def StreamTranscript(self, request, context) -> Iterator[Any]:
+            authorize(context, "read")
+            try:
+                segments = sources.transcript_segments(request.record_id, request.view or "clean")
+            except PipelineError as exc:
+                fail(context, exc)
+            for segment in segments:
+                if not context.is_active():
+                    return
+                yield pb.TranscriptSegment(
+                    start=float(segment.get("start", 0.0)), end=float(segment.get("end", 0.0)),
+                    text=segment.get("text") or "", speaker=segment.get("speaker") or "",
+                    dropped=segment.get("dropped") or "",
+                )
+
+    server = grpc.server(futures.ThreadPoolExecutor(max_workers=GRPC_MAX_WORKERS), options=[
+        ("grpc.max_receive_message_length", 8 * 1024 * 1024),
+    ])
+    services.add_RecordRouteServicer_to_server(RecordRouteServicer(), server)
+    server.add_insecure_port(f"{GRPC_HOST}:{GRPC_PORT}")
+    return server
+
+
+def start_server(sources: PipelineSources):
+    """Start the gRPC server when ``GRPC_ENABLED`` (``None`` otherwise or when grpc is missing)."""
+    if not GRPC_ENABLED:
+        return None
+    try:
+        server = create_server(sources)
+    except GrpcUnavailable as exc:
+        print(f"gRPC 서버를 시작하지 못했습니다: {exc}")
+        return None
+    server.start()
+    print(f"gRPC server running on {GRPC_HOST}:{GRPC_PORT}")
+    return server
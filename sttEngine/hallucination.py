@@ -0,0 +1,124 @@
+"""Per-segment hallucination checks on STT output.
+
+openai-whisper only uses its thresholds to decide on temperature fallback
+and to skip silent windows, and remote backends may not apply them at all.
+These checks run on every segment after transcription, the same way
+Whisper judges a decoding result:
+
+* ``compression_ratio`` — gzip ratio of the text above the threshold means
+  repetitive, looping output ("감사합니다 감사합니다 감사합니다 …").
+* ``no_speech`` — high no-speech probability together with a low average
+  log probability means the model transcribed silence.
+* ``low_logprob`` — average log probability below the threshold.
+
+Segments that fail a check are dropped (``STT_HALLUCINATION_ACTION=drop``)
+or kept with a ``hallucination`` list of reasons (``flag``). Missing
+statistics (remote backends) are skipped, except the compression ratio,
+which is computed from the text.
+"""
+
+from __future__ import annotations
+
+import zlib
+from dataclasses import dataclass
+from typing import Any, Dict, List
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+ACTIONS = ("drop", "flag")
+REASONS = ("compression_ratio", "no_speech", "low_logprob")
+
+
+def compression_ratio(text: str) -> float:
+    """Same measure as openai-whisper: raw bytes / zlib-compressed bytes."""
+    data = text.encode("utf-8")
+    if not data:
+        return 0.0
+    return len(data) / len(zlib.compress(data))
+
+
+@dataclass(frozen=True)
+class HallucinationThresholds:
+    compression_ratio: float = 2.4
+    logprob: float = -1.0
+    no_speech: float = 0.6
+    action: str = "drop"
+
+    @classmethod
+    def from_config(cls) -> "HallucinationThresholds":
+        action = get_config_value("STT_HALLUCINATION_ACTION", "drop", str).lower()
+        return cls(
+            compression_ratio=get_config_value("STT_COMPRESSION_RATIO_THRESHOLD", 2.4, float),
+            logprob=get_config_value("STT_LOGPROB_THRESHOLD", -1.0, float),
+            no_speech=get_config_value("STT_NO_SPEECH_THRESHOLD", 0.6, float),
+            action=action if action in ACTIONS else "drop",
+        )
+
+    def whisper_options(self) -> Dict[str, float]:
+        """Thresholds in openai-whisper ``transcribe()`` keyword form."""
+        return {
+            "compression_ratio_threshold": self.compression_ratio,
+            "logprob_threshold": self.logprob,
+            "no_speech_threshold": self.no_speech,
+        }
+
+
+def check_segment(segment: Dict[str, Any], thresholds: HallucinationThresholds) -> List[str]:
+    """Return the reasons a segment looks hallucinated (empty list if it looks fine)."""
+    reasons = []
+    text = str(segment.get("text", "")).strip()
+
+    ratio = segment.get("compression_ratio")
+    if ratio is None:
+        ratio = compression_ratio(text)
+    if ratio > thresholds.compression_ratio:
+        reasons.append("compression_ratio")
+
+    avg_logprob = segment.get("avg_logprob")
+    no_speech_prob = segment.get("no_speech_prob")
+    if avg_logprob is not None and avg_logprob < thresholds.logprob:
+        # Whisper과 같은 규칙: 무음 확률이 높고 확신도도 낮으면 무음을 받아쓴 것으로 본다
+        if no_speech_prob is not None and no_speech_prob > thresholds.no_speech:
+            reasons.append("no_speech")
+        else:
+            reasons.append("low_logprob")
+    return reasons
+
+
+class HallucinationFilter:
+    """Applies the checks to a stream of segments and counts the results."""
+
+    def __init__(self, thresholds: HallucinationThresholds):
+        self.thresholds = thresholds
+        self.counts = {reason: 0 for reason in REASONS}
+        self.dropped = 0
+        self.flagged = 0
+
+    def apply(self, segment: Dict[str, Any]) -> List[str] | None:
+        """Return ``None`` to drop the segment, else the reasons to flag it with."""
+        reasons = check_segment(segment, self.thresholds)
+        for reason in reasons:
+            self.counts[reason] += 1
+        if not reasons:
+            return []
+        if self.thresholds.action == "drop":
+            self.dropped += 1
+            return None
+        self.flagged += 1
+        return reasons
+
+    def summary(self) -> Dict[str, Any]:
+        return {
+            "action": self.thresholds.action,
+            "thresholds": {
+                "compression_ratio": self.thresholds.compression_ratio,
+                "logprob": self.thresholds.logprob,
+                "no_speech": self.thresholds.no_speech,
+            },
+            "reasons": dict(self.counts),
+            "dropped": self.dropped,
+            "flagged": self.flagged,
+        }
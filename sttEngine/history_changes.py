@@ -0,0 +1,161 @@
+"""Change feed over the upload history for incremental client sync.
+
+Every save of the upload history goes through :func:`stamp_changes`,
+which compares each record with the previously saved version and gives
+new or modified records the next ``change_seq`` and a fresh ``updated_at``. Records that disappear
+from the history entirely (trash purge, layout migration) leave a
+tombstone with their own sequence number. Like ``GET /history``, the feed
+only carries active records: a record moved to the trash is reported in
+``removed``, and restoring it reports it as changed again.
+
+Clients keep the ``cursor`` from the last response and ask for::
+
+    GET /history/changes?cursor=<seq>
+
+Only the newest ``HISTORY_TOMBSTONE_LIMIT`` tombstones are kept; a cursor
+older than that gets ``reset: true`` and a full snapshot instead.
+"""
+
+from __future__ import annotations
+
+import json
+import threading
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value, get_db_base_path
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value, get_db_base_path  # type: ignore
+
+CHANGES_FILE = get_db_base_path() / "history_changes.json"
+HISTORY_TOMBSTONE_LIMIT = max(1, get_config_value("HISTORY_TOMBSTONE_LIMIT", 1000, int))
+
+_state_lock = threading.Lock()
+
+
+class ChangeCursorError(ValueError):
+    """Raised for cursors that are not a known sequence number."""
+
+
+def _load_state(path: Path = CHANGES_FILE) -> Dict[str, Any]:
+    try:
+        with open(path, "r", encoding="utf-8") as f:
+            state = json.load(f)
+        if isinstance(state, dict) and isinstance(state.get("seq"), int):
+            state.setdefault("removed", [])
+            state.setdefault("trimmed_seq", 0)
+            return state
+    except (OSError, ValueError):
+        pass
+    return {"seq": 0, "removed": [], "trimmed_seq": 0}
+
+
+def _save_state(state: Dict[str, Any], path: Path = CHANGES_FILE) -> None:
+    path.parent.mkdir(parents=True, exist_ok=True)
+    tmp_path = path.with_name(f"{path.name}.tmp")
+    with open(tmp_path, "w", encoding="utf-8") as f:
+        json.dump(state, f, ensure_ascii=False)
+    tmp_path.replace(path)
+
+
+# 변경 여부 판단에서 제외하는 필드 (이 모듈이 직접 채우는 값)
+_STAMP_FIELDS = ("change_seq", "updated_at")
+
+
+def _fingerprint(record: Dict[str, Any]) -> str:
+    return json.dumps({k: v for k, v in record.items() if k not in _STAMP_FIELDS}, sort_keys=True, ensure_ascii=False)
+
+
+def stamp_changes(previous: List[Dict[str, Any]], history: List[Dict[str, Any]]) -> int:
+    """Assign ``change_seq``/``updated_at`` to changed records in ``history`` (in place).
+
+    ``previous`` is the history as last saved. Returns the latest sequence.
+    """
+    with _state_lock:
+        state = _load_state()
+        seq = max([state["seq"]] + [r.get("change_seq") or 0 for r in previous if isinstance(r, dict)])
+        before = {r.get("id"): r for r in previous if isinstance(r, dict)}
+        now = datetime.now().isoformat()
+
+        for record in history:
+            old = before.get(record.get("id"))
+            if old is not None and _fingerprint(old) == _fingerprint(record):
+                # 변경 없음: 이전 값 유지 (호출자가 필드를 빠뜨린 경우도 복구)
+                for field in _STAMP_FIELDS:
+                    if old.get(field) is not None and record.get(field) is None:
+                        record[field] = old[field]
+                continue
+            seq += 1
+            record["change_seq"] = seq
+            record["updated_at"] = now
+
+        current_ids = {record.get("id") for record in history}
+        for record_id in before:
+            if record_id and record_id not in current_ids:
+                seq += 1
+                state["removed"].append({"id": record_id, "seq": seq})
+
+        overflow = len(state["removed"]) - HISTORY_TOMBSTONE_LIMIT
+        if overflow > 0:
+            state["trimmed_seq"] = state["removed"][overflow - 1]["seq"]
+            state["removed"] = state["removed"][overflow:]
+
+        if seq != state["seq"] or overflow > 0:
+            state["seq"] = seq
+            try:
+                _save_state(state)
+            except OSError as exc:
+                print(f"기록 변경 순번 저장 실패: {exc}")
+        return seq
+
+
+def parse_cursor(value: Optional[str]) -> int:
+    if value in (None, ""):
+        return 0
+    try:
+        cursor = int(value)
+    except (TypeError, ValueError):
+        raise ChangeCursorError("cursor는 이전 응답의 cursor 값이어야 합니다.") from None
+    if cursor < 0:
+        raise ChangeCursorError("cursor는 이전 응답의 cursor 값이어야 합니다.")
+    return cursor
+
+
+def changes_since(cursor: int, history: List[Dict[str, Any]], limit: Optional[int] = None) -> Dict[str, Any]:
+    """Records and removed IDs changed after ``cursor``.
+
+    With ``limit``, at most that many changes are returned in sequence order
+    and ``has_more`` tells the client to ask again with the new cursor. A
+    reset snapshot is never split.
+    """
+    with _state_lock:
+        state = _load_state()
+    latest = max([state["seq"]] + [r.get("change_seq") or 0 for r in history])
+    if cursor > latest:
+        raise ChangeCursorError("알 수 없는 cursor입니다. cursor 없이 전체를 다시 받으세요.")
+
+    # 오래된 cursor는 삭제 기록이 잘려 나갔을 수 있으므로 전체 스냅샷으로 대체
+    reset = cursor == 0 or cursor < state["trimmed_seq"]
+    since = 0 if reset else cursor
+    entries = [
+        ("removed" if r.get("deleted") else "record", r.get("change_seq") or 0, r.get("id") if r.get("deleted") else r)
+        for r in history
+        if (not r.get("deleted") if reset else (r.get("change_seq") or 0) > since)
+    ]
+    if not reset:
+        entries += [("removed", item["seq"], item["id"]) for item in state["removed"] if item["seq"] > since]
+    entries.sort(key=lambda entry: entry[1])
+
+    has_more = not reset and bool(limit) and len(entries) > limit
+    if has_more:
+        entries = entries[:limit]
+    next_cursor = entries[-1][1] if has_more else latest
+    return {
+        "cursor": str(next_cursor),
+        "reset": reset,
+        "has_more": has_more,
+        "changes": [value for kind, _, value in entries if kind == "record"],
+        "removed": [value for kind, _, value in entries if kind == "removed"],
+    }
@@ -0,0 +1,250 @@
+"""Storage backends for the upload history.
+
+The history used to live only in ``upload_history.json``, rewritten as a
+whole on every change; a crash mid-write or two writers at once could
+leave it truncated. ``HISTORY_BACKEND`` selects the store:
+
+    sqlite  ``upload_history.sqlite3`` (default) — one row per record,
+            written in a single transaction, indexed on ``timestamp``,
+            ``updated_at`` and ``deleted`` (``id`` is the primary key)
+    json    the previous ``upload_history.json`` file (written atomically)
+
+Both keep the list order of the history (newest first), so
+``load_upload_history``/``save_upload_history`` behave the same either way.
+A SQLite save is merged row by row against the rows the saved list was
+loaded from (the list returned by ``load`` carries them as its baseline):
+only records it changed are written and only records it removed are
+deleted, so two requests updating different records at the same time no
+longer overwrite each other, and a load/save nested between another
+caller's load and save does not move that caller's baseline.
+
+On first use the SQLite store imports an existing ``upload_history.json``
+and renames it to ``upload_history.json.migrated`` (kept as a backup).
+:meth:`SqliteHistoryStore.page` serves ``GET /history?limit=&offset=``
+straight from the indexes.
+"""
+
+from __future__ import annotations
+
+import json
+import sqlite3
+import threading
+from contextlib import closing
+from pathlib import Path
+from typing import Any, Dict, List, Optional, Tuple
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value, get_db_base_path
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value, get_db_base_path  # type: ignore
+
+HISTORY_BACKEND = get_config_value("HISTORY_BACKEND", "sqlite", str).strip().lower()
+BACKENDS = ("sqlite", "json")
+JSON_FILENAME = "upload_history.json"
+SQLITE_FILENAME = "upload_history.sqlite3"
+MIGRATED_SUFFIX = ".migrated"
+# 저장소 읽기/쓰기에서 날 수 있는 오류
+STORE_ERRORS = (OSError, sqlite3.Error)
+# 페이지 조회 정렬에 쓸 수 있는 필드 (인덱스가 있는 열)
+PAGE_SORT_FIELDS = ("timestamp", "updated_at", "completed_at")
+
+_SCHEMA = """
+CREATE TABLE IF NOT EXISTS records (
+    id TEXT PRIMARY KEY,
+    position INTEGER NOT NULL,
+    timestamp TEXT,
+    updated_at TEXT,
+    completed_at TEXT,
+    deleted INTEGER NOT NULL DEFAULT 0,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_records_position ON records(position);
+CREATE INDEX IF NOT EXISTS idx_records_timestamp ON records(timestamp);
+CREATE INDEX IF NOT EXISTS idx_records_updated_at ON records(updated_at);
+CREATE INDEX IF NOT EXISTS idx_records_deleted ON records(deleted, position);
+CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+"""
+
+
+class HistoryList(list):
+    """History loaded from :class:`SqliteHistoryStore`; ``baseline`` is ``{id: data}`` of the rows it was read from."""
+
+    baseline: Optional[Dict[str, str]] = None
+
+
+class JsonHistoryStore:
+    """History as a single JSON list (the original format)."""
+
+    backend = "json"
+
+    def __init__(self, db_base: Path):
+        self.path = db_base / JSON_FILENAME
+        self._lock = threading.Lock()
+
+    def load(self, remember: bool = True) -> List[Dict[str, Any]]:
+        if not self.path.exists():
+            return []
+        try:
+            with open(self.path, "r", encoding="utf-8") as f:
+                history = json.load(f)
+        except (json.JSONDecodeError, OSError):
+            return []
+        return history if isinstance(history, list) else []
+
+    def save(self, history: List[Dict[str, Any]]) -> None:
+        with self._lock:
+            tmp_path = self.path.with_name(f"{self.path.name}.tmp")
+            with open(tmp_path, "w", encoding="utf-8") as f:
+                json.dump(history, f, ensure_ascii=False, indent=2)
+            tmp_path.replace(self.path)
+
+    def page(self, offset: int, limit: int, sort: Optional[str] = None,
+             descending: bool = True) -> Tuple[List[Dict[str, Any]], int]:
+        return _page_in_memory([r for r in self.load() if not r.get("deleted")], offset, limit, sort, descending)
+
+    def generation(self) -> str:
+        try:
+            stat = self.path.stat()
+        except FileNotFoundError:
+            return "0"
+        return f"{stat.st_mtime_ns}-{stat.st_size}"
+
+
+class SqliteHistoryStore:
+    """History rows in SQLite; see the module docstring."""
+
+    backend = "sqlite"
+
+    def __init__(self, db_base: Path):
+        self.path = db_base / SQLITE_FILENAME
+        self.json_path = db_base / JSON_FILENAME
+        self._lock = threading.Lock()
+        self._ready = False
+
+    def _connect(self) -> sqlite3.Connection:
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        connection = sqlite3.connect(str(self.path), timeout=30)
+        if not self._ready:
+            with self._lock:
+                if not self._ready:
+                    connection.executescript(_SCHEMA)
+                    self._migrate_json(connection)
+                    self._ready = True
+        return connection
+
+    def _migrate_json(self, connection: sqlite3.Connection) -> None:
+        """Import ``upload_history.json`` once, then keep it as ``.migrated``."""
+        if not self.json_path.exists():
+            return
+        if connection.execute("SELECT COUNT(*) FROM records").fetchone()[0]:
+            print(f"히스토리 DB가 이미 있어 {self.json_path.name}은 가져오지 않습니다.")
+            return
+        history = JsonHistoryStore(self.json_path.parent).load()
+        with connection:
+            self._write(connection, history, {}, None)
+        self.json_path.replace(self.json_path.with_name(self.json_path.name + MIGRATED_SUFFIX))
+        print(f"업로드 기록 {len(history)}건을 {self.json_path.name}에서 {self.path.name}로 옮겼습니다.")
+
+    @staticmethod
+    def _row(position: int, record: Dict[str, Any], data: str) -> tuple:
+        return (record.get("id"), position, record.get("timestamp"), record.get("updated_at"),
+                record.get("completed_at"), 1 if record.get("deleted") else 0, data)
+
+    def _write(self, connection: sqlite3.Connection, history: List[Dict[str, Any]],
+               stored: Dict[str, Tuple[int, str]], snapshot: Optional[Dict[str, str]]) -> Dict[str, str]:
+        """Write ``history`` over ``stored`` rows; with ``snapshot`` (its baseline) only the differences from it.
+
+        Returns ``{id: data}`` of the written history (its next baseline).
+        """
+        base = snapshot if snapshot is not None else {record_id: data for record_id, (_, data) in stored.items()}
+        rows, moved, written = [], [], {}
+        for position, record in enumerate(history):
+            record_id = record.get("id") if isinstance(record, dict) else None
+            if not record_id or record_id in written:
+                continue
+            data = json.dumps(record, ensure_ascii=False)
+            written[record_id] = data
+            if record_id not in stored or data != base.get(record_id):
+                rows.append(self._row(position, record, data))
+            elif stored[record_id][0] != position:
+                moved.append((position, record_id))
+        # 이 목록을 읽은 뒤 다른 곳에서 추가된 기록은 지우지 않음
+        removed = [(record_id,) for record_id in stored
+                   if record_id not in written and (snapshot is None or record_id in snapshot)]
+        connection.executemany("INSERT OR REPLACE INTO records VALUES (?, ?, ?, ?, ?, ?, ?)", rows)
+        connection.executemany("UPDATE records SET position = ? WHERE id = ?", moved)
+        connection.executemany("DELETE FROM records WHERE id = ?", removed)
+        if rows or moved or removed:
+            connection.execute(
+                "INSERT INTO meta(key, value) VALUES ('version', '1') "
+                "ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1")
+        return written
+
+    def load(self, remember: bool = True) -> List[Dict[str, Any]]:
+        """All records in history order; with ``remember`` a :class:`HistoryList` that :meth:`save` merges against."""
+        with closing(self._connect()) as connection:
+            rows = connection.execute("SELECT id, data FROM records ORDER BY position, rowid DESC").fetchall()
+        records = [json.loads(data) for _, data in rows]
+        if not remember:
+            return records
+        history = HistoryList(records)
+        history.baseline = dict(rows)
+        return history
+
+    def save(self, history: List[Dict[str, Any]]) -> None:
+        with closing(self._connect()) as connection:
+            # BEGIN IMMEDIATE: 다른 쓰기와 겹치면 기다렸다가 최신 상태 기준으로 비교
+            connection.isolation_level = None
+            connection.execute("BEGIN IMMEDIATE")
+            try:
+                stored = {record_id: (position, data) for record_id, position, data in
+                          connection.execute("SELECT id, position, data FROM records")}
+                written = self._write(connection, history, stored, getattr(history, "baseline", None))
+                connection.execute("COMMIT")
+            except BaseException:
+                connection.execute("ROLLBACK")
+                raise
+        if isinstance(history, HistoryList):
+            # 같은 목록을 다시 저장하면 방금 쓴 내용과 비교
+            history.baseline = written
+
+    def page(self, offset: int, limit: int, sort: Optional[str] = None,
+             descending: bool = True) -> Tuple[List[Dict[str, Any]], int]:
+        """Active records ``offset..offset+limit`` and the active total.
+
+        Without ``sort`` the history order is kept; with it, records missing
+        the field come last whatever the direction (like ``GET /history``).
+        """
+        if sort and sort not in PAGE_SORT_FIELDS:
+            raise ValueError(f"정렬할 수 없는 필드입니다: {sort}")
+        order = (f"{sort} IS NULL OR {sort} = '', {sort} {'DESC' if descending else 'ASC'}, position"
+                 if sort else "position")
+        with closing(self._connect()) as connection:
+            total = connection.execute("SELECT COUNT(*) FROM records WHERE deleted = 0").fetchone()[0]
+            rows = connection.execute(
+                f"SELECT data FROM records WHERE deleted = 0 ORDER BY {order}, rowid DESC LIMIT ? OFFSET ?",
+                (limit, offset)).fetchall()
+        return [json.loads(data) for (data,) in rows], total
+
+    def generation(self) -> str:
+        with closing(self._connect()) as connection:
+            row = connection.execute("SELECT value FROM meta WHERE key = 'version'").fetchone()
+        return f"sqlite-{row[0] if row else 0}"
+
+
+def _page_in_memory(records: List[Dict[str, Any]], offset: int, limit: int, sort: Optional[str],
+                    descending: bool) -> Tuple[List[Dict[str, Any]], int]:
+    if sort:
+        dated = sorted((r for r in records if r.get(sort)), key=lambda r: r[sort], reverse=descending)
+        records = dated + [r for r in records if not r.get(sort)]
+    return records[offset:offset + limit], len(records)
+
+
+def open_history_store(db_base: Optional[Path] = None, backend: Optional[str] = None):
+    """History store for ``db_base`` (default DB folder) and ``backend`` (default ``HISTORY_BACKEND``)."""
+    db_base = db_base or get_db_base_path()
+    backend = (backend or HISTORY_BACKEND or "sqlite").strip().lower()
+    if backend not in BACKENDS:
+        print(f"알 수 없는 HISTORY_BACKEND '{backend}', sqlite를 사용합니다.")
+        backend = "sqlite"
+    return SqliteHistoryStore(db_base) if backend == "sqlite" else JsonHistoryStore(db_base)
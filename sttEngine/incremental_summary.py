@@ -0,0 +1,122 @@
+"""Incremental re-summarization after transcript edits.
+
+A full summary of a long transcript takes minutes, and fixing one
+misheard name used to pay that cost again. With ``SUMMARY_INCREMENTAL``
+the summary step instead:
+
+1. splits the transcript into content-defined chunks
+   (:func:`split_stable_chunks`) — a chunk ends after a line whose hash
+   hits a boundary pattern, so an edit only changes the chunk it falls in
+   and the boundaries after it stay where they were;
+2. keeps every chunk summary in ``{stem}.summary_chunks.json`` next to the
+   transcript, keyed by a hash of the model, options and full chunk prompt;
+3. on the next run summarizes only chunks whose key is not cached and runs
+   the reduce phase over cached and new chunk summaries together.
+
+Changing the model, prompt templates, summary language or people context
+changes the key, so a stale chunk summary is never reused. Entries not
+used by the latest run are dropped when the cache is saved.
+"""
+
+from __future__ import annotations
+
+import hashlib
+import json
+import threading
+import zlib
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+SUMMARY_INCREMENTAL = get_config_value("SUMMARY_INCREMENTAL", False, bool)
+SUMMARY_INCREMENTAL_CHUNK_BYTES = max(2000, get_config_value("SUMMARY_INCREMENTAL_CHUNK_BYTES", 8000, int))
+CACHE_SUFFIX = ".summary_chunks.json"
+# 줄 해시가 이 값으로 나누어떨어지면 청크 경계 후보 (평균 8줄마다)
+BOUNDARY_DIVISOR = 8
+
+
+def split_stable_chunks(text: str, max_bytes: int = None) -> List[str]:
+    """Line-aligned chunks of at most ``max_bytes`` whose boundaries depend on content, not position.
+
+    A chunk may end once it holds half of ``max_bytes`` and must end before
+    exceeding it; in between it ends after a line whose CRC32 is a multiple
+    of :data:`BOUNDARY_DIVISOR`. A single line longer than ``max_bytes``
+    becomes a chunk on its own.
+    """
+    max_bytes = max_bytes or SUMMARY_INCREMENTAL_CHUNK_BYTES
+    min_bytes = max_bytes // 2
+    chunks: List[str] = []
+    current: List[str] = []
+    size = 0
+    for line in text.splitlines():
+        line_bytes = len(line.encode("utf-8")) + 1
+        if current and size + line_bytes > max_bytes:
+            chunks.append("\n".join(current))
+            current, size = [], 0
+        current.append(line)
+        size += line_bytes
+        if size >= min_bytes and zlib.crc32(line.strip().encode("utf-8")) % BOUNDARY_DIVISOR == 0:
+            chunks.append("\n".join(current))
+            current, size = [], 0
+    if current:
+        chunks.append("\n".join(current))
+    return [chunk.strip() for chunk in chunks if chunk.strip()]
+
+
+def cache_path_for(transcript_path: Path) -> Path:
+    return transcript_path.with_name(f"{transcript_path.stem}{CACHE_SUFFIX}")
+
+
+class ChunkSummaryCache:
+    """Chunk summaries of one transcript, persisted as JSON; counts hits and misses of the current run."""
+
+    def __init__(self, path: Path):
+        self.path = path
+        self.hits = 0
+        self.misses = 0
+        self._used: Dict[str, Dict[str, Any]] = {}
+        self._lock = threading.Lock()
+        try:
+            with open(path, "r", encoding="utf-8") as f:
+                entries = json.load(f).get("chunks")
+            self._entries: Dict[str, Dict[str, Any]] = entries if isinstance(entries, dict) else {}
+        except (OSError, ValueError, AttributeError):
+            self._entries = {}
+
+    @staticmethod
+    def key(model: str, prompt: str, temperature: float, options: Optional[dict] = None) -> str:
+        material = json.dumps([model, temperature, options or {}, prompt], ensure_ascii=False, sort_keys=True)
+        return hashlib.sha256(material.encode("utf-8")).hexdigest()
+
+    def get(self, key: str) -> Optional[str]:
+        with self._lock:
+            entry = self._entries.get(key)
+            if entry is None:
+                self.misses += 1
+                return None
+            self.hits += 1
+            self._used[key] = entry
+            return entry["summary"]
+
+    def put(self, key: str, summary: str) -> None:
+        with self._lock:
+            entry = {"summary": summary, "created_at": datetime.now().isoformat()}
+            self._entries[key] = entry
+            self._used[key] = entry
+
+    def save(self) -> None:
+        """Write the entries used by this run (older ones are dropped)."""
+        with self._lock:
+            self.path.parent.mkdir(parents=True, exist_ok=True)
+            tmp_path = self.path.with_name(f"{self.path.name}.tmp")
+            with open(tmp_path, "w", encoding="utf-8") as f:
+                json.dump({"chunks": self._used}, f, ensure_ascii=False, indent=2)
+            tmp_path.replace(self.path)
+
+    def stats(self) -> Dict[str, int]:
+        return {"chunks_reused": self.hits, "chunks_summarized": self.misses}
@@ -0,0 +1,168 @@
+"""Lower priority and CPU share of in-process Whisper inference.
+
+Whisper on the CPU uses every core at normal priority, so a desktop where
+the server runs becomes sluggish while a recording is transcribed. Each
+transcription now runs inside :func:`background_inference`:
+
+* ``INFERENCE_PRIORITY`` — ``normal`` (default), ``low`` or ``idle``. The
+  thread running the inference is lowered: ``nice`` 10/19 of that thread on
+  Linux (PyTorch's worker threads are started from it and inherit it),
+  ``SetThreadPriority`` below normal/idle on Windows. Other systems (macOS)
+  cannot lower a single thread, so the whole process is lowered the first
+  time. The HTTP and WebSocket threads keep normal priority on Linux and
+  Windows, so the UI stays responsive.
+* ``INFERENCE_CPU_THREADS`` — PyTorch CPU threads used while inference runs
+  (``0``: PyTorch's default, every core).
+* ``INFERENCE_CPU_ADAPTIVE`` — choose the thread count before each
+  transcription from the load average: the cores not busy with other
+  programs, minus ``INFERENCE_CPU_RESERVE`` kept free (at least one, at most
+  ``INFERENCE_CPU_THREADS`` when set).
+
+A lowered Unix thread cannot raise its priority again without privileges,
+so it stays lowered; inference runs on task threads that end with the task.
+The Ollama steps run in the Ollama process; cap them with
+``OLLAMA_OPTIONS={"num_thread": 4}``.
+"""
+
+from __future__ import annotations
+
+import logging
+import os
+import sys
+import threading
+from contextlib import contextmanager
+from typing import Any, Dict, Iterator, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+PRIORITY_LEVELS = ("normal", "low", "idle")
+INFERENCE_PRIORITY = get_config_value("INFERENCE_PRIORITY", "normal", str).strip().lower()
+INFERENCE_CPU_THREADS = max(0, get_config_value("INFERENCE_CPU_THREADS", 0, int))
+INFERENCE_CPU_ADAPTIVE = get_config_value("INFERENCE_CPU_ADAPTIVE", False, bool)
+INFERENCE_CPU_RESERVE = max(0, get_config_value("INFERENCE_CPU_RESERVE", 1, int))
+
+# 우선순위 단계별 nice 값과 Windows 스레드 우선순위 (THREAD_PRIORITY_BELOW_NORMAL / IDLE)
+NICE_VALUES = {"normal": 0, "low": 10, "idle": 19}
+WINDOWS_THREAD_PRIORITIES = {"normal": 0, "low": -1, "idle": -15}
+
+_state_lock = threading.Lock()
+_thread_state = threading.local()
+_process_lowered = False
+_active = 0
+_default_threads: Optional[int] = None
+_last: Dict[str, Any] = {}
+
+
+def priority_level() -> str:
+    if INFERENCE_PRIORITY not in PRIORITY_LEVELS:
+        logging.warning("알 수 없는 INFERENCE_PRIORITY '%s' — normal로 실행합니다.", INFERENCE_PRIORITY)
+        return "normal"
+    return INFERENCE_PRIORITY
+
+
+def lower_current_thread(level: Optional[str] = None) -> str:
+    """Lower the calling thread to ``level``; returns what was lowered (``thread``, ``process`` or ``none``)."""
+    global _process_lowered
+    level = level or priority_level()
+    if level == "normal":
+        return "none"
+    if getattr(_thread_state, "level", None) == level:
+        return _thread_state.scope
+    scope = "none"
+    try:
+        if sys.platform == "win32":
+            import ctypes
+
+            kernel32 = ctypes.windll.kernel32
+            if kernel32.SetThreadPriority(kernel32.GetCurrentThread(), WINDOWS_THREAD_PRIORITIES[level]):
+                scope = "thread"
+        elif sys.platform.startswith("linux"):
+            # Linux의 nice는 스레드 단위라 스레드 ID로 지정하면 이 스레드만 낮아짐
+            tid = threading.get_native_id()
+            os.setpriority(os.PRIO_PROCESS, tid, max(os.getpriority(os.PRIO_PROCESS, tid), NICE_VALUES[level]))
+            scope = "thread"
+        else:
+            with _state_lock:
+                if not _process_lowered:
+                    os.setpriority(os.PRIO_PROCESS, 0, max(os.getpriority(os.PRIO_PROCESS, 0), NICE_VALUES[level]))
+                    _process_lowered = True
+            scope = "process"
+    except (OSError, AttributeError) as exc:
+        logging.warning("추론 우선순위를 낮추지 못했습니다: %s", exc)
+    _thread_state.level, _thread_state.scope = level, scope
+    return scope
+
+
+def _load_average() -> Optional[float]:
+    try:
+        return os.getloadavg()[0]
+    except (OSError, AttributeError):  # Windows
+        return None
+
+
+def inference_threads(active: int = 1) -> Optional[int]:
+    """CPU threads for the next inference (``None``: leave PyTorch's setting alone)."""
+    cores = os.cpu_count() or 1
+    cap = INFERENCE_CPU_THREADS or None
+    if not INFERENCE_CPU_ADAPTIVE:
+        return cap
+    available = cores - INFERENCE_CPU_RESERVE
+    load = _load_average()
+    if load is not None:
+        # 이미 돌고 있는 추론이 만든 부하는 빼고 다른 프로그램의 부하만 반영
+        own = max(0, active - 1) * (_last.get("threads") or 0)
+        available -= max(0.0, load - own)
+    threads = max(1, int(available))
+    return min(threads, cap) if cap else threads
+
+
+def _torch():
+    try:
+        import torch
+    except ImportError:
+        return None
+    return torch if hasattr(torch, "set_num_threads") else None
+
+
+@contextmanager
+def background_inference() -> Iterator[Dict[str, Any]]:
+    """Run the body at the configured inference priority and CPU thread count."""
+    global _active, _default_threads
+    scope = lower_current_thread()
+    torch = _torch()
+    with _state_lock:
+        _active += 1
+        threads = inference_threads(_active)
+        if torch is not None and threads:
+            if _default_threads is None:
+                _default_threads = torch.get_num_threads()
+            torch.set_num_threads(threads)
+        _last.update({"priority": priority_level(), "scope": scope, "threads": threads, "load": _load_average()})
+        applied = dict(_last)
+    try:
+        yield applied
+    finally:
+        with _state_lock:
+            _active -= 1
+            # 마지막 추론이 끝나면 PyTorch 스레드 수를 원래대로 되돌림
+            if _active == 0 and torch is not None and _default_threads is not None:
+                torch.set_num_threads(_default_threads)
+                _default_threads = None
+
+
+def inference_priority_status() -> Dict[str, Any]:
+    """Settings and what the latest inference ran with."""
+    with _state_lock:
+        return {
+            "priority": priority_level(),
+            "cpu_threads": INFERENCE_CPU_THREADS,
+            "adaptive": INFERENCE_CPU_ADAPTIVE,
+            "reserve": INFERENCE_CPU_RESERVE,
+            "cpu_count": os.cpu_count(),
+            "load_average": _load_average(),
+            "active": _active,
+            "last": dict(_last) or None,
+        }
@@ -0,0 +1,139 @@
+"""Parent/child lineage between upload records.
+
+A record derived from others — a merge of several meetings, one part of a
+split recording, a digest summarizing a series — stores its parents on the
+history record::
+
+    "lineage": [{"parent_id": "...", "relation": "merged_from", "linked_at": "..."}]
+
+Children are not stored; :func:`build_lineage_graph` finds them by scanning
+the history, so deleting a record never leaves dangling back-references.
+Re-summaries and partial re-transcriptions keep the same record and show up
+as ``revisions`` (from the event log) instead of graph edges.
+"""
+
+from __future__ import annotations
+
+from datetime import datetime
+from typing import Any, Dict, List, Optional
+
+RELATIONS = ("merged_from", "split_from", "digest_of", "derived_from")
+REVISION_EVENT_TYPES = ("stt_completed", "stt_retranscribed", "summary_generated")
+MAX_LINEAGE_DEPTH = 10
+
+
+class LineageError(ValueError):
+    """Raised for unknown records or relations and links that would form a cycle."""
+
+
+def parent_links(record: Dict[str, Any]) -> List[Dict[str, Any]]:
+    return [link for link in record.get("lineage") or [] if isinstance(link, dict) and link.get("parent_id")]
+
+
+def _ancestor_ids(record_id: str, by_id: Dict[str, Dict[str, Any]]) -> set:
+    seen, stack = set(), [record_id]
+    while stack:
+        current = by_id.get(stack.pop())
+        for link in parent_links(current or {}):
+            if link["parent_id"] not in seen:
+                seen.add(link["parent_id"])
+                stack.append(link["parent_id"])
+    return seen
+
+
+def add_parent_links(record: Dict[str, Any], parent_ids: List[str], relation: str,
+                     history: List[Dict[str, Any]]) -> List[str]:
+    """Validate and append parent links to ``record`` in place; returns the new parent IDs."""
+    if relation not in RELATIONS:
+        raise LineageError(f"relation은 {', '.join(RELATIONS)} 중 하나여야 합니다.")
+    if not isinstance(parent_ids, list) or not parent_ids or not all(isinstance(p, str) for p in parent_ids):
+        raise LineageError("parents는 기록 ID 문자열 배열이어야 합니다.")
+
+    by_id = {item.get("id"): item for item in history}
+    existing = {link["parent_id"] for link in parent_links(record)}
+    added = []
+    for parent_id in dict.fromkeys(parent_ids):
+        if parent_id == record["id"]:
+            raise LineageError("기록을 자기 자신과 연결할 수 없습니다.")
+        if parent_id not in by_id:
+            raise LineageError(f"기록을 찾을 수 없습니다: {parent_id}")
+        if record["id"] in _ancestor_ids(parent_id, by_id):
+            raise LineageError(f"순환 관계가 됩니다: {parent_id}")
+        if parent_id in existing:
+            continue
+        added.append(parent_id)
+
+    now = datetime.now().isoformat()
+    record["lineage"] = parent_links(record) + [
+        {"parent_id": parent_id, "relation": relation, "linked_at": now} for parent_id in added
+    ]
+    return added
+
+
+def remove_parent_links(record: Dict[str, Any], parent_ids: List[str]) -> List[str]:
+    """Drop links to ``parent_ids`` from ``record`` in place; returns the removed IDs."""
+    removed = [link["parent_id"] for link in parent_links(record) if link["parent_id"] in parent_ids]
+    record["lineage"] = [link for link in parent_links(record) if link["parent_id"] not in parent_ids]
+    if not record["lineage"]:
+        record.pop("lineage")
+    return removed
+
+
+def _node(record: Dict[str, Any]) -> Dict[str, Any]:
+    return {
+        "id": record.get("id"),
+        "filename": record.get("filename"),
+        "title_summary": record.get("title_summary") or "",
+        "timestamp": record.get("timestamp"),
+        "deleted": bool(record.get("deleted")),
+    }
+
+
+def build_lineage_graph(record_id: str, history: List[Dict[str, Any]],
+                        revisions: Optional[List[Dict[str, Any]]] = None,
+                        max_depth: int = MAX_LINEAGE_DEPTH) -> Dict[str, Any]:
+    """Ancestors and descendants of a record as ``nodes``/``edges`` (edges point parent → child)."""
+    by_id = {item.get("id"): item for item in history}
+    children: Dict[str, List[Dict[str, Any]]] = {}
+    for item in history:
+        for link in parent_links(item):
+            children.setdefault(link["parent_id"], []).append({**link, "child_id": item.get("id")})
+
+    nodes: Dict[str, Dict[str, Any]] = {record_id: {**_node(by_id[record_id]), "depth": 0}}
+    edges: Dict[tuple, Dict[str, Any]] = {}
+
+    def walk(start: str, upward: bool) -> None:
+        frontier = [start]
+        for depth in range(1, max_depth + 1):
+            next_frontier = []
+            for current in frontier:
+                if upward:
+                    links = [(link["parent_id"], current, link) for link in parent_links(by_id.get(current, {}))]
+                else:
+                    links = [(current, link["child_id"], link) for link in children.get(current, [])]
+                for parent_id, child_id, link in links:
+                    edges[(parent_id, child_id)] = {
+                        "parent": parent_id, "child": child_id,
+                        "relation": link.get("relation"), "linked_at": link.get("linked_at"),
+                    }
+                    neighbor = parent_id if upward else child_id
+                    if neighbor in nodes:
+                        continue
+                    if neighbor in by_id:
+                        nodes[neighbor] = {**_node(by_id[neighbor]), "depth": -depth if upward else depth}
+                    else:
+                        # 기록 목록에서 밀려난 부모도 연결은 보여줌
+                        nodes[neighbor] = {"id": neighbor, "missing": True, "depth": -depth if upward else depth}
+                    next_frontier.append(neighbor)
+            frontier = next_frontier
+            if not frontier:
+                break
+
+    walk(record_id, upward=True)
+    walk(record_id, upward=False)
+    return {
+        "record_id": record_id,
+        "nodes": sorted(nodes.values(), key=lambda node: (node["depth"], node.get("timestamp") or "")),
+        "edges": list(edges.values()),
+        "revisions": revisions or [],
+    }
@@ -0,0 +1,117 @@
+"""Read-only maintenance mode for backups and migrations.
+
+While maintenance mode is on, requests that would change the archive —
+uploads, ``/process`` and every other write or admin ``POST`` (see
+:func:`api_tokens.route_group`) — are answered with ``503`` and
+``code: "maintenance"``; history, search, downloads and other reads keep
+working. The periodic jobs that write on their own (summary regeneration,
+index compaction, mailbox polling) skip their runs. Tasks that were
+already running or queued are not stopped, so wait for
+``GET /admin/maintenance`` to report no active tasks before copying files.
+
+Turn it on with ``MAINTENANCE_MODE=true`` (applied at startup) or
+``POST /admin/maintenance`` ``{"enabled": true, "message": "..."}``. The
+state set through the endpoint is kept in ``DB/maintenance.json`` so a
+restart during a migration stays read-only.
+"""
+
+from __future__ import annotations
+
+import json
+import threading
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Dict, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .api_tokens import route_group
+    from .config import get_config_value, get_db_base_path
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from api_tokens import route_group  # type: ignore
+    from config import get_config_value, get_db_base_path  # type: ignore
+
+MAINTENANCE_FILE = get_db_base_path() / "maintenance.json"
+MAINTENANCE_MODE = get_config_value("MAINTENANCE_MODE", False, bool)
+DEFAULT_MESSAGE = get_config_value(
+    "MAINTENANCE_MESSAGE", "점검 중이라 업로드와 처리, 변경 작업을 잠시 멈췄습니다. 조회·검색·다운로드는 사용할 수 있습니다.", str
+)
+MAX_MESSAGE_LENGTH = 500
+# 점검 중에도 받는 쓰기 요청 (점검 해제, 설정 재로드, 외부 백업 동기화, 데이터 마이그레이션, 종료)
+ALLOWED_ROUTES = {"/admin/maintenance", "/admin/reload", "/admin/export-sync/run", "/admin/migrations/run", "/shutdown"}
+# 점검 중에는 받지 않는 권한 그룹
+BLOCKED_GROUPS = ("upload", "write", "admin")
+
+_maintenance_lock = threading.Lock()
+
+
+class MaintenanceError(ValueError):
+    """Raised for invalid maintenance mode changes."""
+
+
+def _load(path: Path = MAINTENANCE_FILE) -> Dict[str, Any]:
+    try:
+        with open(path, "r", encoding="utf-8") as f:
+            state = json.load(f)
+        return state if isinstance(state, dict) else {}
+    except (OSError, ValueError):
+        return {}
+
+
+def _save(state: Dict[str, Any], path: Path = MAINTENANCE_FILE) -> None:
+    path.parent.mkdir(parents=True, exist_ok=True)
+    tmp_path = path.with_name(f"{path.name}.tmp")
+    with open(tmp_path, "w", encoding="utf-8") as f:
+        json.dump(state, f, ensure_ascii=False, indent=2)
+    tmp_path.replace(path)
+
+
+def _initial_state() -> Dict[str, Any]:
+    if MAINTENANCE_MODE:
+        return {"enabled": True, "message": DEFAULT_MESSAGE, "since": datetime.now().isoformat(), "source": "config"}
+    state = _load()
+    if state.get("enabled"):
+        return {"enabled": True, "message": state.get("message") or DEFAULT_MESSAGE,
+                "since": state.get("since"), "source": "admin"}
+    return {"enabled": False, "message": None, "since": None, "source": None}
+
+
+_state: Dict[str, Any] = _initial_state()
+
+
+def maintenance_active() -> bool:
+    return bool(_state["enabled"])
+
+
+def maintenance_state() -> Dict[str, Any]:
+    """``{"enabled", "message", "since", "source": "config" | "admin" | None}``."""
+    with _maintenance_lock:
+        return dict(_state)
+
+
+def set_maintenance(enabled: Any, message: Any = None) -> Dict[str, Any]:
+    """Turn maintenance mode on or off and persist it; returns the new state."""
+    if not isinstance(enabled, bool):
+        raise MaintenanceError("enabled는 true 또는 false여야 합니다.")
+    if message is not None and not isinstance(message, str):
+        raise MaintenanceError("message는 문자열이어야 합니다.")
+    message = (message or "").strip()
+    if len(message) > MAX_MESSAGE_LENGTH:
+        raise MaintenanceError(f"message는 {MAX_MESSAGE_LENGTH}자 이하여야 합니다.")
+    with _maintenance_lock:
+        if enabled:
+            _state.update(enabled=True, message=message or _state.get("message") or DEFAULT_MESSAGE,
+                          since=_state.get("since") if _state["enabled"] else datetime.now().isoformat(),
+                          source="admin")
+        else:
+            _state.update(enabled=False, message=None, since=None, source=None)
+        _save({"enabled": _state["enabled"], "message": _state["message"], "since": _state["since"]})
+        return dict(_state)
+
+
+def blocks_request(method: str, path: str) -> bool:
+    """Whether maintenance mode rejects this request (reads and :data:`ALLOWED_ROUTES` pass)."""
+    if not maintenance_active() or method in ("GET", "HEAD", "OPTIONS"):
+        return False
+    if path.split("?", 1)[0] in ALLOWED_ROUTES:
+        return False
+    return route_group(method, path) in BLOCKED_GROUPS
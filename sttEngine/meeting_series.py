@@ -0,0 +1,198 @@
+"""Meeting series: records of a recurring meeting linked together.
+
+A series is stored as ``DB/meeting_series/{id}.json``::
+
+    {"id": "...", "name": "주간 스탠드업", "description": "",
+     "record_ids": ["...", "..."], "rolling_context": true, "context_count": 3}
+
+A record belongs to at most one series; adding it to another series moves
+it. Entries are ordered by the record's upload time, not by the order they
+were linked.
+
+With ``rolling_context`` the summary of a new entry is written with the
+summaries of up to ``context_count`` earlier entries of the series in the
+final prompt (:func:`previous_entries` picks them, the server reads the
+files). The model is asked to mark what changed since the previous meetings
+and which action items carried over. ``/process`` can turn this off or on
+per run with ``series_context``.
+"""
+
+from __future__ import annotations
+
+import json
+import re
+import threading
+import uuid
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Dict, Iterable, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value, get_db_base_path
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value, get_db_base_path  # type: ignore
+
+SERIES_DIR = get_db_base_path() / "meeting_series"
+SERIES_CONTEXT_COUNT = min(10, max(1, get_config_value("SERIES_CONTEXT_COUNT", 3, int)))
+# 이전 요약 하나당 프롬프트에 넣을 최대 글자 수
+SERIES_CONTEXT_MAX_CHARS = max(500, get_config_value("SERIES_CONTEXT_MAX_CHARS", 4000, int))
+_series_lock = threading.Lock()
+
+
+class SeriesError(ValueError):
+    """Raised for invalid series definitions or unknown series ids."""
+
+
+def _series_path(series_id: str) -> Path:
+    if not re.fullmatch(r"[\w-]+", series_id or ""):
+        raise SeriesError("잘못된 시리즈 ID입니다.")
+    return SERIES_DIR / f"{series_id}.json"
+
+
+def _apply_fields(series: Dict[str, Any], payload: Dict[str, Any]) -> None:
+    if "name" in payload:
+        if not isinstance(payload["name"], str) or not payload["name"].strip():
+            raise SeriesError("시리즈 이름(name)이 필요합니다.")
+        series["name"] = payload["name"].strip()
+    if "description" in payload:
+        if payload["description"] is not None and not isinstance(payload["description"], str):
+            raise SeriesError("description은 문자열이어야 합니다.")
+        series["description"] = (payload["description"] or "").strip()
+    if "rolling_context" in payload:
+        series["rolling_context"] = bool(payload["rolling_context"])
+    if "context_count" in payload:
+        count = payload["context_count"]
+        if count is not None and (isinstance(count, bool) or not isinstance(count, int) or not 1 <= count <= 10):
+            raise SeriesError("context_count는 1~10 사이의 정수여야 합니다.")
+        series["context_count"] = count
+
+
+def _validate_record_ids(record_ids) -> List[str]:
+    if not isinstance(record_ids, list) or not all(isinstance(r, str) for r in record_ids):
+        raise SeriesError("record_ids는 문자열 배열이어야 합니다.")
+    return list(dict.fromkeys(record_ids))
+
+
+def _write_series(series: Dict[str, Any]) -> None:
+    SERIES_DIR.mkdir(parents=True, exist_ok=True)
+    path = _series_path(series["id"])
+    tmp_path = path.with_suffix(".json.tmp")
+    with open(tmp_path, "w", encoding="utf-8") as f:
+        json.dump(series, f, ensure_ascii=False, indent=2)
+    tmp_path.replace(path)
+
+
+def list_series() -> List[Dict[str, Any]]:
+    series_list = []
+    if SERIES_DIR.exists():
+        for path in sorted(SERIES_DIR.glob("*.json")):
+            try:
+                with open(path, "r", encoding="utf-8") as f:
+                    series_list.append(json.load(f))
+            except (OSError, json.JSONDecodeError):
+                continue
+    return sorted(series_list, key=lambda series: series.get("name", "").casefold())
+
+
+def get_series(series_id: str) -> Dict[str, Any]:
+    path = _series_path(series_id)
+    if not path.exists():
+        raise SeriesError(f"시리즈를 찾을 수 없습니다: {series_id}")
+    with open(path, "r", encoding="utf-8") as f:
+        return json.load(f)
+
+
+def series_of(record_id: str) -> Optional[Dict[str, Any]]:
+    """The series ``record_id`` belongs to, or ``None``."""
+    return next((series for series in list_series() if record_id in series.get("record_ids", [])), None)
+
+
+def _detach(record_ids: Iterable[str], keep_series_id: str) -> None:
+    """Remove ``record_ids`` from every series but ``keep_series_id`` (lock held)."""
+    record_ids = set(record_ids)
+    for series in list_series():
+        if series["id"] == keep_series_id:
+            continue
+        remaining = [r for r in series.get("record_ids", []) if r not in record_ids]
+        if len(remaining) != len(series.get("record_ids", [])):
+            series["record_ids"] = remaining
+            series["updated_at"] = datetime.now().isoformat()
+            _write_series(series)
+
+
+def create_series(payload: Dict[str, Any]) -> Dict[str, Any]:
+    if "name" not in payload:
+        raise SeriesError("시리즈 이름(name)이 필요합니다.")
+    now = datetime.now().isoformat()
+    series = {"id": str(uuid.uuid4()), "name": None, "description": "", "record_ids": [],
+              "rolling_context": True, "context_count": None, "created_at": now, "updated_at": now}
+    _apply_fields(series, payload)
+    record_ids = _validate_record_ids(payload.get("record_ids", []))
+    with _series_lock:
+        _detach(record_ids, series["id"])
+        series["record_ids"] = record_ids
+        _write_series(series)
+    return series
+
+
+def update_series(series_id: str, payload: Dict[str, Any]) -> Dict[str, Any]:
+    with _series_lock:
+        series = get_series(series_id)
+        _apply_fields(series, payload)
+        series["updated_at"] = datetime.now().isoformat()
+        _write_series(series)
+    return series
+
+
+def link_records(series_id: str, add: Iterable[str] = (), remove: Iterable[str] = ()) -> Dict[str, Any]:
+    """Add records to (moving them out of any other series) and remove records from a series."""
+    add = _validate_record_ids(list(add))
+    remove = set(_validate_record_ids(list(remove)))
+    with _series_lock:
+        series = get_series(series_id)
+        _detach(add, series_id)
+        record_ids = [r for r in series.get("record_ids", []) if r not in remove]
+        series["record_ids"] = record_ids + [r for r in add if r not in record_ids and r not in remove]
+        series["updated_at"] = datetime.now().isoformat()
+        _write_series(series)
+    return series
+
+
+def delete_series(series_id: str) -> bool:
+    """Delete a series; its records stay, only the link is removed."""
+    path = _series_path(series_id)
+    with _series_lock:
+        if not path.exists():
+            return False
+        path.unlink()
+    return True
+
+
+def ordered_entries(series: Dict[str, Any], records: Dict[str, Dict[str, Any]]) -> List[Dict[str, Any]]:
+    """Active records of ``series`` (``records`` by id), oldest first."""
+    entries = [records[r] for r in series.get("record_ids", []) if r in records]
+    return sorted(entries, key=lambda record: record.get("timestamp", ""))
+
+
+def previous_entries(series: Dict[str, Any], record_id: str, records: Dict[str, Dict[str, Any]],
+                     count: Optional[int] = None) -> List[Dict[str, Any]]:
+    """Up to ``count`` summarized entries before ``record_id``, oldest first."""
+    count = count or series.get("context_count") or SERIES_CONTEXT_COUNT
+    entries = ordered_entries(series, records)
+    current = records.get(record_id)
+    if current is not None:
+        entries = [r for r in entries
+                   if r["id"] != record_id and r.get("timestamp", "") < current.get("timestamp", "")]
+    summarized = [r for r in entries if (r.get("completed_tasks") or {}).get("summary")]
+    return summarized[-count:]
+
+
+def render_context(series: Dict[str, Any], summaries: List[Dict[str, str]]) -> str:
+    """Context block for the summary prompt from ``[{"label", "summary"}]`` (oldest first)."""
+    parts = []
+    for entry in summaries:
+        summary = entry["summary"].strip()
+        if len(summary) > SERIES_CONTEXT_MAX_CHARS:
+            summary = summary[:SERIES_CONTEXT_MAX_CHARS].rstrip() + "\n…"
+        parts.append(f"[{entry['label']}]\n{summary}")
+    return f"시리즈: {series.get('name', '')}\n\n" + "\n\n---이전 회의 구분선---\n\n".join(parts)
@@ -0,0 +1,313 @@
+"""Meeting minutes templates rendered from workflow outputs.
+
+Templates use a small Mustache/Handlebars subset so no extra dependency is
+needed:
+
+    {{title}}                  값 치환 (없으면 빈 문자열)
+    {{#decisions}}- {{.}}\n{{/decisions}}   목록 반복 ({{.}} = 현재 항목)
+    {{#quotes}}> {{text}} ({{time}}){{/quotes}}  객체 목록이면 필드 참조
+    {{^action_items}}없음{{/action_items}}     비어 있을 때만 출력
+
+Available placeholders are listed in :data:`PLACEHOLDERS`. Templates are
+stored as JSON files under ``DB/minutes_templates/`` and managed through
+the ``/minutes/templates`` endpoints; the built-in ``default`` template is
+always available and cannot be modified.
+"""
+
+from __future__ import annotations
+
+import json
+import re
+import threading
+import uuid
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_db_base_path
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_db_base_path  # type: ignore
+
+TEMPLATES_DIR = get_db_base_path() / "minutes_templates"
+DEFAULT_TEMPLATE_ID = "default"
+MAX_TEMPLATE_LENGTH = 50_000
+MAX_QUOTES = 5
+
+PLACEHOLDERS = {
+    "title": "기록 제목 (파일명)",
+    "headline": "한 줄 요약",
+    "date": "녹음/업로드 날짜 (YYYY-MM-DD)",
+    "duration": "녹음 길이",
+    "attendees": "참석자 목록 (지정된 화자 이름)",
+    "topics": "주요 주제 목록",
+    "key_points": "핵심 내용 목록",
+    "decisions": "결정 사항 목록",
+    "action_items": "실행 항목 목록",
+    "risks": "리스크/이슈 목록",
+    "next_steps": "차기 일정 목록",
+    "quotes": "주요 발언 목록 ({{text}}, {{time}}, {{speaker}})",
+    "summary": "요약 전문",
+    "transcript": "전사 전문",
+}
+
+DEFAULT_TEMPLATE = """# {{title}} 회의록
+
+- 일시: {{date}}
+- 길이: {{duration}}
+- 참석자: {{#attendees}}{{.}} {{/attendees}}{{^attendees}}미지정{{/attendees}}
+
+> {{headline}}
+
+## 주요 주제
+{{#topics}}- {{.}}
+{{/topics}}
+## 결정 사항
+{{#decisions}}- {{.}}
+{{/decisions}}{{^decisions}}- 없음
+{{/decisions}}
+## 실행 항목
+{{#action_items}}- [ ] {{.}}
+{{/action_items}}{{^action_items}}- 없음
+{{/action_items}}
+## 리스크/이슈
+{{#risks}}- {{.}}
+{{/risks}}
+## 차기 일정
+{{#next_steps}}- {{.}}
+{{/next_steps}}
+## 주요 발언
+{{#quotes}}> [{{time}}] {{speaker}} {{text}}
+{{/quotes}}"""
+
+# 요약 섹션 템플릿 변수 (parse_summary_to_sections의 언어 무관 키와 같음)
+SUMMARY_SECTION_KEYS = ("topics", "key_points", "decisions", "action_items", "risks", "next_steps")
+
+_TAG_PATTERN = re.compile(r"{{\s*([#^/]?)\s*([\w.]+)\s*}}")
+_templates_lock = threading.Lock()
+
+
+class TemplateError(ValueError):
+    """Raised for malformed templates or unknown template ids."""
+
+
+def _parse(template: str) -> List[Any]:
+    """Parse a template into a tree of text, variable and section nodes."""
+    root: List[Any] = []
+    stack = [(None, root)]
+    position = 0
+    for match in _TAG_PATTERN.finditer(template):
+        if match.start() > position:
+            stack[-1][1].append(("text", template[position:match.start()]))
+        kind, name = match.group(1), match.group(2)
+        if kind in ("#", "^"):
+            children: List[Any] = []
+            stack[-1][1].append(("section" if kind == "#" else "inverted", name, children))
+            stack.append((name, children))
+        elif kind == "/":
+            if stack[-1][0] != name:
+                raise TemplateError(f"닫는 태그가 맞지 않습니다: {{{{/{name}}}}}")
+            stack.pop()
+        else:
+            stack[-1][1].append(("var", name))
+        position = match.end()
+    if len(stack) > 1:
+        raise TemplateError(f"닫히지 않은 섹션: {{{{#{stack[-1][0]}}}}}")
+    if position < len(template):
+        root.append(("text", template[position:]))
+    return root
+
+
+def _lookup(name: str, scopes: List[Any]) -> Any:
+    if name == ".":
+        return scopes[-1]
+    for scope in reversed(scopes):
+        if isinstance(scope, dict) and name in scope:
+            return scope[name]
+    return None
+
+
+def _format_value(value: Any) -> str:
+    if value is None:
+        return ""
+    if isinstance(value, list):
+        return ", ".join(_format_value(item) for item in value)
+    return str(value)
+
+
+def _render_nodes(nodes: List[Any], scopes: List[Any]) -> str:
+    output = []
+    for node in nodes:
+        if node[0] == "text":
+            output.append(node[1])
+        elif node[0] == "var":
+            output.append(_format_value(_lookup(node[1], scopes)))
+        elif node[0] == "section":
+            value = _lookup(node[1], scopes)
+            if isinstance(value, list):
+                for item in value:
+                    output.append(_render_nodes(node[2], scopes + [item]))
+            elif value:
+                output.append(_render_nodes(node[2], scopes + [value]))
+        elif node[0] == "inverted":
+            if not _lookup(node[1], scopes):
+                output.append(_render_nodes(node[2], scopes))
+    return "".join(output)
+
+
+def validate_template(body: Any) -> str:
+    if not isinstance(body, str) or not body.strip():
+        raise TemplateError("템플릿 본문(body)은 비어 있지 않은 문자열이어야 합니다.")
+    if len(body) > MAX_TEMPLATE_LENGTH:
+        raise TemplateError(f"템플릿은 {MAX_TEMPLATE_LENGTH:,}자 이하여야 합니다.")
+    _parse(body)
+    return body
+
+
+def render_template(body: str, context: Dict[str, Any]) -> str:
+    return _render_nodes(_parse(body), [context])
+
+
+def _template_path(template_id: str) -> Path:
+    if not re.fullmatch(r"[\w-]+", template_id or ""):
+        raise TemplateError("잘못된 템플릿 ID입니다.")
+    return TEMPLATES_DIR / f"{template_id}.json"
+
+
+def _builtin_template() -> Dict[str, Any]:
+    return {"id": DEFAULT_TEMPLATE_ID, "name": "기본 회의록", "body": DEFAULT_TEMPLATE,
+            "builtin": True, "created_at": None, "updated_at": None}
+
+
+def list_templates() -> List[Dict[str, Any]]:
+    templates = [_builtin_template()]
+    if TEMPLATES_DIR.exists():
+        for path in sorted(TEMPLATES_DIR.glob("*.json")):
+            try:
+                with open(path, "r", encoding="utf-8") as f:
+                    templates.append(json.load(f))
+            except (OSError, json.JSONDecodeError):
+                continue
+    return templates
+
+
+def get_template(template_id: Optional[str]) -> Dict[str, Any]:
+    if not template_id or template_id == DEFAULT_TEMPLATE_ID:
+        return _builtin_template()
+    path = _template_path(template_id)
+    if not path.exists():
+        raise TemplateError(f"템플릿을 찾을 수 없습니다: {template_id}")
+    with open(path, "r", encoding="utf-8") as f:
+        return json.load(f)
+
+
+def _write_template(template: Dict[str, Any]) -> None:
+    TEMPLATES_DIR.mkdir(parents=True, exist_ok=True)
+    path = _template_path(template["id"])
+    tmp_path = path.with_suffix(".json.tmp")
+    with open(tmp_path, "w", encoding="utf-8") as f:
+        json.dump(template, f, ensure_ascii=False, indent=2)
+    tmp_path.replace(path)
+
+
+def create_template(name: Any, body: Any) -> Dict[str, Any]:
+    if not isinstance(name, str) or not name.strip():
+        raise TemplateError("템플릿 이름(name)이 필요합니다.")
+    now = datetime.now().isoformat()
+    template = {"id": str(uuid.uuid4()), "name": name.strip(), "body": validate_template(body),
+                "builtin": False, "created_at": now, "updated_at": now}
+    with _templates_lock:
+        _write_template(template)
+    return template
+
+
+def update_template(template_id: str, name: Any = None, body: Any = None) -> Dict[str, Any]:
+    if template_id == DEFAULT_TEMPLATE_ID:
+        raise TemplateError("기본 템플릿은 수정할 수 없습니다. 새 템플릿을 만들어 사용하세요.")
+    with _templates_lock:
+        template = get_template(template_id)
+        if name is not None:
+            if not isinstance(name, str) or not name.strip():
+                raise TemplateError("템플릿 이름(name)은 비어 있을 수 없습니다.")
+            template["name"] = name.strip()
+        if body is not None:
+            template["body"] = validate_template(body)
+        template["updated_at"] = datetime.now().isoformat()
+        _write_template(template)
+    return template
+
+
+def delete_template(template_id: str) -> bool:
+    if template_id == DEFAULT_TEMPLATE_ID:
+        raise TemplateError("기본 템플릿은 삭제할 수 없습니다.")
+    path = _template_path(template_id)
+    with _templates_lock:
+        if not path.exists():
+            return False
+        path.unlink()
+    return True
+
+
+def import_template(template: Any, dry_run: bool = False) -> Dict[str, Any]:
+    """Store a template exported from another installation, keeping its ID.
+
+    With ``dry_run`` the template is only validated.
+    """
+    if not isinstance(template, dict):
+        raise TemplateError("템플릿은 객체여야 합니다.")
+    template_id = str(template.get("id") or "")
+    if template_id == DEFAULT_TEMPLATE_ID:
+        raise TemplateError("기본 템플릿은 가져올 수 없습니다.")
+    _template_path(template_id)  # ID 형식 검증
+    name = template.get("name")
+    if not isinstance(name, str) or not name.strip():
+        raise TemplateError(f"템플릿 이름(name)이 필요합니다: {template_id}")
+    now = datetime.now().isoformat()
+    imported = {"id": template_id, "name": name.strip(), "body": validate_template(template.get("body")),
+                "builtin": False, "created_at": template.get("created_at") or now, "updated_at": now}
+    if dry_run:
+        return imported
+    with _templates_lock:
+        _write_template(imported)
+    return imported
+
+
+def _format_time(seconds: Any) -> str:
+    seconds = int(float(seconds or 0))
+    return f"{seconds // 3600:02d}:{seconds % 3600 // 60:02d}:{seconds % 60:02d}"
+
+
+def select_quotes(segments: List[Dict[str, Any]], speaker_names: Dict[str, str],
+                  limit: int = MAX_QUOTES) -> List[Dict[str, Any]]:
+    """Pick the longest transcript segments as notable quotes, in time order."""
+    longest = sorted(segments, key=lambda seg: len(seg.get("text", "")), reverse=True)[:limit]
+    quotes = []
+    for segment in sorted(longest, key=lambda seg: seg.get("start", 0.0)):
+        label = segment.get("speaker")
+        quotes.append({
+            "text": segment.get("text", "").strip(),
+            "time": _format_time(segment.get("start")),
+            "speaker": f"{speaker_names.get(label, label)}:" if label else "",
+        })
+    return quotes
+
+
+def build_minutes_context(record: Dict[str, Any], summary_sections: Dict[str, List[str]],
+                          summary_text: str = "", transcript_text: str = "",
+                          segments: Optional[List[Dict[str, Any]]] = None) -> Dict[str, Any]:
+    """Collect template variables from a history record and its outputs."""
+    speaker_names = record.get("speaker_names") or {}
+    timestamp = record.get("timestamp") or ""
+    context: Dict[str, Any] = {
+        "title": Path(record.get("filename") or "").stem or record.get("filename") or "",
+        "headline": record.get("title_summary") or "",
+        "date": timestamp[:10],
+        "duration": record.get("duration") or "",
+        "attendees": sorted(set(speaker_names.values())),
+        "summary": summary_text,
+        "transcript": transcript_text,
+        "quotes": select_quotes(segments or [], speaker_names),
+    }
+    for key in SUMMARY_SECTION_KEYS:
+        context[key] = list(summary_sections.get(key) or [])
+    return context
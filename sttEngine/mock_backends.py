@@ -0,0 +1,220 @@
+"""Deterministic fake STT/LLM backends for frontend and Electron development.
+
+Started with ``python -m sttEngine.server --mock`` or ``MOCK_BACKENDS=true``,
+the server runs the full workflow — upload, STT, summary, one-line summary,
+embedding, search — without Whisper models, a GPU or Ollama:
+
+* STT uses :class:`MockSttEngine` (``STT_BACKEND=mock``), which reports
+  progress in a few steps with ``MOCK_DELAY_SECONDS`` pauses, honours
+  cancellation and writes a canned transcript through the normal output
+  path (segments file, postprocessing).
+* ``ollama.chat`` / ``ollama.generate`` return a canned summary with the
+  section headings of the requested summary language (JSON for
+  ``format="json"``, the structured summary mode), a canned one-line
+  summary and, for ``/ask`` prompts, an answer citing the first passage.
+  ``stream=True`` yields the answer a few words at a time. The Ollama
+  server check always succeeds.
+* Embeddings are bag-of-words hash vectors (``MOCK_EMBEDDING_DIM``), so
+  similar texts still find each other in search.
+
+The vectors are not compatible with a real embedding model: point
+``DB_FOLDER_PATH`` at a separate folder while mocking.
+"""
+
+from __future__ import annotations
+
+import hashlib
+import json
+import re
+import sys
+import time
+from pathlib import Path
+from typing import Any, Dict, Iterator, List
+
+import numpy as np
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+    from .decoding import describe as describe_decoding, resolve_strategy
+    from .stt_backends import STT_ENGINES, SttEngine, Transcription, TranscriptionOptions
+    from .workflow.transcribe import write_transcription_outputs
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+    from decoding import describe as describe_decoding, resolve_strategy  # type: ignore
+    from stt_backends import STT_ENGINES, SttEngine, Transcription, TranscriptionOptions  # type: ignore
+    from workflow.transcribe import write_transcription_outputs  # type: ignore
+
+MOCK_BACKENDS = get_config_value("MOCK_BACKENDS", False, bool)
+MOCK_DELAY_SECONDS = max(0.0, get_config_value("MOCK_DELAY_SECONDS", 0.5, float))
+MOCK_EMBEDDING_DIM = max(8, get_config_value("MOCK_EMBEDDING_DIM", 384, int))
+MOCK_MODEL = "mock-llm"
+
+MOCK_SEGMENTS = [
+    (0.0, 4.2, "안녕하세요, 오늘 회의를 시작하겠습니다."),
+    (4.2, 9.8, "지난주에 논의한 일정은 다음 달 초로 확정되었습니다."),
+    (9.8, 15.5, "예산은 기존 안대로 승인하기로 했습니다."),
+    (15.5, 21.0, "보고서 초안은 금요일까지 공유해 주세요."),
+    (21.0, 26.4, "다음 회의는 월요일 오전 열 시입니다."),
+]
+MOCK_SECTION_ITEMS = [
+    ["일정 확정", "예산 승인"],
+    ["일정은 다음 달 초로 확정", "예산은 기존 안대로 승인"],
+    ["예산 기존 안 승인"],
+    ["보고서 초안 금요일까지 공유"],
+    ["일정 지연 가능성"],
+    ["다음 회의: 월요일 오전 10시"],
+]
+
+_installed = False
+
+
+def is_enabled() -> bool:
+    return _installed
+
+
+def _pause(options: TranscriptionOptions = None) -> None:
+    deadline = time.monotonic() + MOCK_DELAY_SECONDS
+    while time.monotonic() < deadline:
+        if options is not None:
+            options.check_cancelled()
+        time.sleep(min(0.05, MOCK_DELAY_SECONDS))
+
+
+class MockSttEngine(SttEngine):
+    """Canned transcript after simulated progress; no audio is decoded."""
+
+    name = "mock"
+
+    def transcribe(self, path: Path, output_dir: Path, options: TranscriptionOptions) -> Transcription:
+        output_dir.mkdir(parents=True, exist_ok=True)
+        for percent in (0, 25, 50, 75, 100):
+            options.check_cancelled()
+            options.report(f"'{path.name}' 모의 변환 중... {percent}%")
+            if percent < 100:
+                _pause(options)
+        result = {
+            "text": " ".join(text for _, _, text in MOCK_SEGMENTS),
+            "language": "ko",
+            "segments": [{"start": start, "end": end, "text": text, "temperature": 0.0}
+                         for start, end, text in MOCK_SEGMENTS],
+            "decoding": describe_decoding(resolve_strategy(options.decoding_strategy)),
+        }
+        output_path = write_transcription_outputs(
+            path, output_dir, result, options.language, options.filter_fillers,
+            options.min_seg_length, options.normalize_punct, MOCK_MODEL, options.progress_callback,
+            export_to_obsidian=False, prompt_provenance=options.resolve_prompt(scope="request")[1],
+        )
+        return Transcription.from_output(output_path, self.name, MOCK_MODEL, "ko")
+
+
+def _loaded_modules(name: str) -> List[Any]:
+    """Both copies of a module (``x`` and ``sttEngine.x``) that are currently imported."""
+    return [sys.modules[candidate] for candidate in (name, f"sttEngine.{name}") if candidate in sys.modules]
+
+
+def _patch(name: str, attr: str, value: Any) -> None:
+    for module in _loaded_modules(name):
+        if hasattr(module, attr):
+            setattr(module, attr, value)
+
+
+def _summary_language(prompt: str) -> str:
+    for module in _loaded_modules("workflow.summarize"):
+        positions = {code: prompt.find(label) for code, label in module.LANGUAGE_NAMES.items() if label in prompt}
+        if positions:
+            return min(positions, key=positions.get)
+    return "ko"
+
+
+def mock_summary(prompt: str) -> str:
+    headings = next(
+        (module.SECTION_HEADINGS for module in _loaded_modules("workflow.summarize")), {"ko": []}
+    )
+    language = _summary_language(prompt)
+    lines = []
+    for heading, items in zip(headings.get(language) or headings["ko"], MOCK_SECTION_ITEMS):
+        lines.append(f"## {heading}")
+        lines.extend(f"- {item}" for item in items)
+        lines.append("")
+    lines.append(f"(모의 요약 · 입력 {len(prompt):,}자)")
+    return "\n".join(lines)
+
+
+def mock_structured_summary() -> str:
+    topics, key_points, decisions, actions, risks, schedule = MOCK_SECTION_ITEMS
+    return json.dumps({
+        "topics": topics,
+        "key_points": key_points,
+        "decisions": decisions,
+        "action_items": [{"task": task, "owner": "김민수", "due": "금요일"} for task in actions],
+        "risks": risks,
+        "next_steps": [dict(zip(("item", "date"), entry.split(": ", 1))) for entry in schedule],
+    }, ensure_ascii=False)
+
+
+def _stream_chat(model: str, content: str) -> Iterator[Dict[str, Any]]:
+    pieces = re.findall(r"\S+\s*|\s+", content)
+    for index in range(0, len(pieces), 3):
+        time.sleep(MOCK_DELAY_SECONDS / 10)
+        yield {"model": model, "message": {"role": "assistant", "content": "".join(pieces[index:index + 3])},
+               "done": False}
+    yield {"model": model, "message": {"role": "assistant", "content": ""}, "done": True}
+
+
+def fake_chat(model: str = MOCK_MODEL, messages: List[Dict[str, str]] = None, format: str = None,
+              stream: bool = False, **_: Any) -> Any:
+    prompt = "\n".join(message.get("content", "") for message in messages or [])
+    _pause()
+    if format == "json":
+        content = mock_structured_summary()
+    elif "\n[1] (" in prompt:
+        content = f"모의 답변: 첫 번째 발췌문에 근거한 답입니다 [1]. (프롬프트 {len(prompt):,}자)"
+    else:
+        content = mock_summary(prompt)
+    if stream:
+        return _stream_chat(model, content)
+    return {"model": model, "message": {"role": "assistant", "content": content}, "done": True}
+
+
+def fake_generate(model: str = MOCK_MODEL, prompt: str = "", **_: Any) -> Dict[str, Any]:
+    _pause()
+    return {"model": model, "response": "일정 확정과 예산 승인, 보고서 공유 일정을 논의한 회의", "done": True}
+
+
+def fake_embedding(model_name: str, prompt: str, num_ctx: int = None) -> np.ndarray:
+    """Hashed bag-of-words vector: texts sharing words get similar vectors."""
+    vector = np.zeros(MOCK_EMBEDDING_DIM, dtype=np.float32)
+    for token in re.findall(r"\w+", prompt.lower()):
+        index = int.from_bytes(hashlib.md5(token.encode("utf-8")).digest()[:4], "little") % MOCK_EMBEDDING_DIM
+        vector[index] += 1.0
+    norm = float(np.linalg.norm(vector))
+    return vector / norm if norm else vector
+
+
+def _server_ok(*_: Any, **__: Any):
+    return True, "모의 모드: Ollama 서버 확인을 건너뜁니다."
+
+
+def install() -> None:
+    """Wire the fake engines into the already imported server modules."""
+    global _installed
+    STT_ENGINES[MockSttEngine.name] = MockSttEngine
+    _patch("stt_backends", "STT_BACKEND", MockSttEngine.name)
+
+    try:
+        import ollama
+    except ImportError:  # pragma: no cover - ollama 패키지 없이도 모의 모드는 동작
+        ollama = None
+    if ollama is not None:
+        ollama.chat = fake_chat
+        ollama.generate = fake_generate
+        ollama.list = lambda: {"models": [{"name": MOCK_MODEL, "model": MOCK_MODEL}]}
+
+    for name in ("ollama_utils", "embedding_pipeline", "workflow.summarize"):
+        _patch(name, "ensure_ollama_server", _server_ok)
+    _patch("workflow.summarize", "check_ollama_model_available", _server_ok)
+    _patch("ollama_utils", "list_ollama_models", lambda: None)
+    _patch("embedding_pipeline", "_request_embedding", fake_embedding)
+    _patch("embedding_pipeline", "_query_context_length", lambda model_name: None)
+    _installed = True
+    print(f"모의 모드: 가짜 STT/LLM/임베딩 사용 (지연 {MOCK_DELAY_SECONDS:g}초)")
@@ -0,0 +1,124 @@
+"""Validated pass-through of advanced Ollama generation options.
+
+Requests may send a ``model_options`` map (``POST /process``) that is
+forwarded to Ollama's ``options`` for the summary step. Every key must be
+in :data:`OPTION_SCHEMA` and every value must have the right type and lie
+within its bounds — otherwise the request is rejected with all problems
+listed, so a typo like ``num_cxt`` fails loudly instead of being ignored
+by Ollama. ``GET /model/options`` returns the schema for the frontend.
+
+Server-wide defaults can be set with ``OLLAMA_OPTIONS`` (a JSON object);
+request values override them.
+"""
+
+from __future__ import annotations
+
+import difflib
+import json
+from typing import Any, Dict, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+MAX_STOP_SEQUENCES = 8
+MAX_STOP_LENGTH = 100
+
+# 옵션별 타입과 허용 범위 (GET /model/options로 그대로 노출)
+OPTION_SCHEMA: Dict[str, Dict[str, Any]] = {
+    "temperature": {"type": "float", "min": 0.0, "max": 2.0, "description": "샘플링 온도"},
+    "num_ctx": {"type": "int", "min": 256, "max": 262144, "description": "컨텍스트 길이 (토큰)"},
+    "num_predict": {"type": "int", "min": -2, "max": 131072, "description": "최대 생성 토큰 (-1: 무제한)"},
+    "num_gpu": {"type": "int", "min": 0, "max": 1000, "description": "GPU에 올릴 레이어 수"},
+    "num_thread": {"type": "int", "min": 1, "max": 512, "description": "CPU 스레드 수"},
+    "repeat_penalty": {"type": "float", "min": 0.0, "max": 2.0, "description": "반복 억제 강도"},
+    "repeat_last_n": {"type": "int", "min": -1, "max": 32768, "description": "반복 검사 범위 (-1: num_ctx)"},
+    "top_k": {"type": "int", "min": 1, "max": 1000, "description": "Top-K 샘플링"},
+    "top_p": {"type": "float", "min": 0.0, "max": 1.0, "description": "Top-P 샘플링"},
+    "min_p": {"type": "float", "min": 0.0, "max": 1.0, "description": "Min-P 샘플링"},
+    "seed": {"type": "int", "min": 0, "max": 2 ** 31 - 1, "description": "난수 시드"},
+    "stop": {"type": "list[str]", "max_items": MAX_STOP_SEQUENCES, "description": "생성 중단 문자열"},
+}
+
+
+class ModelOptionsError(ValueError):
+    """Raised when a model options map fails validation; ``errors`` lists every problem."""
+
+    def __init__(self, errors: List[str]):
+        super().__init__("; ".join(errors))
+        self.errors = errors
+
+
+def _check_number(name: str, value: Any, spec: Dict[str, Any]) -> Any:
+    # bool은 int의 하위 타입이므로 명시적으로 거부
+    if isinstance(value, bool) or not isinstance(value, (int, float)):
+        raise ValueError(f"{name}: 숫자여야 합니다 (받은 값: {value!r})")
+    if spec["type"] == "int":
+        if isinstance(value, float) and not value.is_integer():
+            raise ValueError(f"{name}: 정수여야 합니다 (받은 값: {value!r})")
+        value = int(value)
+    else:
+        value = float(value)
+    if not spec["min"] <= value <= spec["max"]:
+        raise ValueError(f"{name}: {spec['min']}~{spec['max']} 범위여야 합니다 (받은 값: {value})")
+    return value
+
+
+def _check_stop(value: Any) -> List[str]:
+    if isinstance(value, str):
+        value = [value]
+    if not isinstance(value, list) or not all(isinstance(item, str) for item in value):
+        raise ValueError("stop: 문자열 또는 문자열 목록이어야 합니다")
+    if len(value) > MAX_STOP_SEQUENCES:
+        raise ValueError(f"stop: 최대 {MAX_STOP_SEQUENCES}개까지 지정할 수 있습니다")
+    for item in value:
+        if not item or len(item) > MAX_STOP_LENGTH:
+            raise ValueError(f"stop: 각 항목은 1~{MAX_STOP_LENGTH}자여야 합니다")
+    return value
+
+
+def validate_model_options(options: Any) -> Dict[str, Any]:
+    """Return a normalized copy of ``options`` or raise :class:`ModelOptionsError`."""
+    if options is None:
+        return {}
+    if not isinstance(options, dict):
+        raise ModelOptionsError(["model_options는 객체여야 합니다"])
+
+    errors = []
+    normalized: Dict[str, Any] = {}
+    for name, value in options.items():
+        spec = OPTION_SCHEMA.get(name)
+        if spec is None:
+            message = f"{name}: 지원하지 않는 옵션입니다"
+            suggestion = difflib.get_close_matches(name, OPTION_SCHEMA, n=1)
+            if suggestion:
+                message += f" ('{suggestion[0]}'을(를) 의도하셨나요?)"
+            errors.append(message)
+            continue
+        try:
+            normalized[name] = _check_stop(value) if name == "stop" else _check_number(name, value, spec)
+        except ValueError as exc:
+            errors.append(str(exc))
+    if errors:
+        raise ModelOptionsError(errors)
+    return normalized
+
+
+def _load_default_options() -> Dict[str, Any]:
+    raw = get_config_value("OLLAMA_OPTIONS", "", str).strip()
+    if not raw:
+        return {}
+    try:
+        return validate_model_options(json.loads(raw))
+    except (json.JSONDecodeError, ModelOptionsError) as exc:
+        print(f"경고: OLLAMA_OPTIONS 설정을 무시합니다 ({exc})")
+        return {}
+
+
+DEFAULT_MODEL_OPTIONS = _load_default_options()
+
+
+def resolve_model_options(options: Optional[Dict[str, Any]] = None) -> Dict[str, Any]:
+    """Merge validated request options over the ``OLLAMA_OPTIONS`` defaults."""
+    return {**DEFAULT_MODEL_OPTIONS, **(options or {})}
@@ -0,0 +1,488 @@
+"""Notification channels and per-person delivery preferences.
+
+Every notification about a record goes out through a :class:`Notifier`
+registered in :data:`NOTIFIERS`:
+
+    desktop  browser notification through the progress WebSocket (no target)
+    webhook  an outgoing webhook of ``DB/webhooks`` (target: webhook id)
+    email    mail through ``NOTIFY_SMTP_*`` (target: address)
+    slack    Slack incoming webhook (target: ``https://hooks.slack.com/...``)
+    discord  Discord webhook (target: ``https://discord.com/api/webhooks/...``)
+
+When an event happens (:data:`EVENTS`, the same as the webhook events),
+:func:`notify` sends it to every enabled webhook subscribed to it, as
+before, and to the channels each person of the people directory chose for
+it. The choices are kept in ``DB/notification_settings.json``::
+
+    {"users": {"<person id>": {
+        "enabled": true,
+        "channels": {"email": "minsu@example.com", "slack": "https://hooks.slack.com/..."},
+        "events": {"summary_completed": ["email", "slack"], "*": ["desktop"]}}}}
+
+``events["*"]`` applies to the events not listed; an empty list mutes an
+event. Without an ``email`` target the person's directory email is used.
+The same channel and target is notified once per event even if several
+people chose it.
+
+Each send is a delivery in ``DB/notification_deliveries.json`` (the newest
+``NOTIFY_DELIVERY_LOG_LIMIT``) with its status (``pending`` → ``sent`` |
+``failed``), attempts and error. Failed sends are retried
+``NOTIFY_MAX_ATTEMPTS`` times with backoff (webhooks use their own retry
+settings).
+"""
+
+from __future__ import annotations
+
+import json
+import re
+import smtplib
+import threading
+import time
+import uuid
+from abc import ABC, abstractmethod
+from datetime import datetime
+from email.message import EmailMessage
+from typing import Any, Callable, Dict, List, Optional, Tuple
+from urllib.parse import urlparse
+
+import requests
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value, get_db_base_path
+    from .people_directory import PersonNotFound, get_person
+    from .webhooks import EVENTS, WebhookError, deliver as deliver_webhook, get_webhook, list_webhooks
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value, get_db_base_path  # type: ignore
+    from people_directory import PersonNotFound, get_person  # type: ignore
+    from webhooks import EVENTS, WebhookError, deliver as deliver_webhook, get_webhook, list_webhooks  # type: ignore
+
+SETTINGS_FILE = get_db_base_path() / "notification_settings.json"
+DELIVERIES_FILE = get_db_base_path() / "notification_deliveries.json"
+NOTIFY_TIMEOUT_SECONDS = get_config_value("NOTIFY_TIMEOUT_SECONDS", 10, float)
+NOTIFY_MAX_ATTEMPTS = max(1, get_config_value("NOTIFY_MAX_ATTEMPTS", 3, int))
+NOTIFY_DELIVERY_LOG_LIMIT = max(10, get_config_value("NOTIFY_DELIVERY_LOG_LIMIT", 500, int))
+NOTIFY_SMTP_HOST = get_config_value("NOTIFY_SMTP_HOST", "", str).strip()
+NOTIFY_SMTP_PORT = get_config_value("NOTIFY_SMTP_PORT", 587, int)
+NOTIFY_SMTP_USER = get_config_value("NOTIFY_SMTP_USER", "", str)
+NOTIFY_SMTP_PASSWORD = get_config_value("NOTIFY_SMTP_PASSWORD", "", str)
+NOTIFY_SMTP_FROM = get_config_value("NOTIFY_SMTP_FROM", "", str).strip()
+NOTIFY_SMTP_STARTTLS = get_config_value("NOTIFY_SMTP_STARTTLS", True, bool)
+
+DEFAULT_EVENT = "*"
+STATUSES = ("pending", "sent", "failed")
+_EMAIL = re.compile(r"^[^@\s]+@[^@\s]+\.[^@\s]+$")
+_settings_lock = threading.Lock()
+_deliveries_lock = threading.Lock()
+
+
+class NotificationError(ValueError):
+    """Raised for invalid preferences, unknown channels and unusable targets."""
+
+
+class Notifier(ABC):
+    """One notification channel: checks targets and sends a message to one of them."""
+
+    name: str = ""
+    label: str = ""
+    # False: 대상 없이 보내는 채널 (desktop)
+    needs_target: bool = True
+    # False: send()가 스스로 재시도하는 채널 (webhook)
+    retries: bool = True
+
+    def configured(self) -> bool:
+        """Whether the server has what the channel needs (e.g. SMTP settings)."""
+        return True
+
+    @abstractmethod
+    def validate_target(self, target: Any) -> str:
+        """Normalized ``target``; raises :class:`NotificationError` when it cannot be used."""
+
+    @abstractmethod
+    def send(self, target: str, message: Dict[str, Any]) -> None:
+        """Deliver ``message`` (see :func:`build_message`); raises on failure."""
+
+    def mask(self, target: str) -> str:
+        """``target`` as shown in listings and the delivery log."""
+        return target
+
+
+class DesktopNotifier(Notifier):
+    name = "desktop"
+    label = "데스크톱 알림"
+    needs_target = False
+
+    def __init__(self):
+        self.sender: Optional[Callable[[Dict[str, Any]], None]] = None
+
+    def configured(self) -> bool:
+        return self.sender is not None
+
+    def validate_target(self, target: Any) -> str:
+        return ""
+
+    def send(self, target: str, message: Dict[str, Any]) -> None:
+        if self.sender is None:
+            raise RuntimeError("데스크톱 알림을 보낼 WebSocket 서버가 없습니다.")
+        self.sender(message)
+
+
+class WebhookNotifier(Notifier):
+    name = "webhook"
+    label = "웹훅"
+    retries = False
+
+    def validate_target(self, target: Any) -> str:
+        if not isinstance(target, str) or not target:
+            raise NotificationError("webhook 대상은 웹훅 ID여야 합니다.")
+        try:
+            get_webhook(target)
+        except (WebhookError, OSError, ValueError) as exc:
+            raise NotificationError(str(exc)) from None
+        return target
+
+    def send(self, target: str, message: Dict[str, Any]) -> None:
+        result = deliver_webhook(get_webhook(target), {**message["context"], "event": message["event"]})
+        if not result["ok"]:
+            raise RuntimeError(result["error"])
+
+
+class EmailNotifier(Notifier):
+    name = "email"
+    label = "이메일"
+
+    def configured(self) -> bool:
+        return bool(NOTIFY_SMTP_HOST and NOTIFY_SMTP_FROM)
+
+    def validate_target(self, target: Any) -> str:
+        if not isinstance(target, str) or not _EMAIL.match(target.strip()):
+            raise NotificationError("email 대상은 이메일 주소여야 합니다.")
+        return target.strip()
+
+    def send(self, target: str, message: Dict[str, Any]) -> None:
+        if not self.configured():
+            raise RuntimeError("NOTIFY_SMTP_HOST와 NOTIFY_SMTP_FROM을 설정해야 합니다.")
+        mail = EmailMessage()
+        mail["Subject"] = message["title"]
+        mail["From"] = NOTIFY_SMTP_FROM
+        mail["To"] = target
+        mail.set_content(message["text"] + (f"\n\n{message['url']}" if message.get("url") else ""))
+        with smtplib.SMTP(NOTIFY_SMTP_HOST, NOTIFY_SMTP_PORT, timeout=NOTIFY_TIMEOUT_SECONDS) as smtp:
+            if NOTIFY_SMTP_STARTTLS:
+                smtp.starttls()
+            if NOTIFY_SMTP_USER:
+                smtp.login(NOTIFY_SMTP_USER, NOTIFY_SMTP_PASSWORD)
+            smtp.send_message(mail)
+
+
+class ChatWebhookNotifier(Notifier):
+    """Slack/Discord style incoming webhook that takes ``{text_field: "..."}``."""
+
+    hosts: Tuple[str, ...] = ()
+    text_field = "text"
+
+    def validate_target(self, target: Any) -> str:
+        parsed = urlparse(target if isinstance(target, str) else "")
+        if parsed.scheme != "https" or not parsed.netloc:
+            raise NotificationError(f"{self.name} 대상은 https 웹훅 주소여야 합니다.")
+        if self.hosts and not any(parsed.netloc == host or parsed.netloc.endswith(f".{host}") for host in self.hosts):
+            raise NotificationError(f"{self.name} 웹훅 주소는 {', '.join(self.hosts)} 주소여야 합니다.")
+        return target
+
+    def send(self, target: str, message: Dict[str, Any]) -> None:
+        text = f"*{message['title']}*\n{message['text']}"
+        if message.get("url"):
+            text += f"\n{message['url']}"
+        response = requests.post(target, json={self.text_field: text}, timeout=NOTIFY_TIMEOUT_SECONDS)
+        if response.status_code >= 400:
+            raise RuntimeError(f"HTTP {response.status_code}: {response.text[:200]}")
+
+    def mask(self, target: str) -> str:
+        # 주소의 경로가 곧 비밀 토큰이므로 호스트만 표시
+        parsed = urlparse(target)
+        return f"{parsed.scheme}://{parsed.netloc}/********" if parsed.netloc else "********"
+
+
+class SlackNotifier(ChatWebhookNotifier):
+    name = "slack"
+    label = "Slack"
+    hosts = ("hooks.slack.com",)
+
+
+class DiscordNotifier(ChatWebhookNotifier):
+    name = "discord"
+    label = "Discord"
+    hosts = ("discord.com", "discordapp.com")
+    text_field = "content"
+
+
+NOTIFIERS: Dict[str, Notifier] = {
+    notifier.name: notifier
+    for notifier in (DesktopNotifier(), WebhookNotifier(), EmailNotifier(), SlackNotifier(), DiscordNotifier())
+}
+
+
+def set_desktop_sender(sender: Callable[[Dict[str, Any]], None]) -> None:
+    """Route ``desktop`` notifications to ``sender`` (the server's WebSocket broadcast)."""
+    NOTIFIERS["desktop"].sender = sender
+
+
+def channel_info() -> List[Dict[str, Any]]:
+    return [{"name": n.name, "label": n.label, "needs_target": n.needs_target, "configured": n.configured()}
+            for n in NOTIFIERS.values()]
+
+
+def build_message(event: str, context: Dict[str, Any]) -> Dict[str, Any]:
+    """Title and text of a notification about ``event`` from its webhook ``context``."""
+    title = context.get("title") or context.get("record_id") or "RecordRoute"
+    if event == "stt_completed":
+        heading, text = "전사 완료", f"'{title}' 전사가 완료되었습니다."
+    elif event == "summary_completed":
+        heading, text = "요약 완료", f"'{title}' 요약이 완료되었습니다."
+        if context.get("one_line_summary"):
+            text += f"\n{context['one_line_summary']}"
+    elif event == "watch_matched":
+        watch = context.get("watch") or {}
+        heading = "감시 규칙 일치"
+        text = f"감시 규칙 '{watch.get('rule_name', '')}'과 일치하는 녹음: {title}"
+    else:
+        heading, text = event, title
+    return {
+        "event": event,
+        "record_id": context.get("record_id"),
+        "title": f"RecordRoute {heading}",
+        "text": text,
+        "url": context.get("summary_url") or context.get("transcript_url"),
+        "context": context,
+    }
+
+
+# --- 사용자별 설정 ---
+
+def _load_json(path, default):
+    try:
+        with open(path, "r", encoding="utf-8") as f:
+            data = json.load(f)
+        return data if isinstance(data, type(default)) else default
+    except (OSError, ValueError):
+        return default
+
+
+def _save_json(path, data) -> None:
+    path.parent.mkdir(parents=True, exist_ok=True)
+    tmp_path = path.with_name(f"{path.name}.tmp")
+    with open(tmp_path, "w", encoding="utf-8") as f:
+        json.dump(data, f, ensure_ascii=False, indent=2)
+    tmp_path.replace(path)
+
+
+def _load_users() -> Dict[str, Dict[str, Any]]:
+    users = _load_json(SETTINGS_FILE, {}).get("users")
+    return users if isinstance(users, dict) else {}
+
+
+def _apply_preferences(preferences: Dict[str, Any], payload: Dict[str, Any]) -> None:
+    if "channels" in payload:
+        channels = payload["channels"] or {}
+        if not isinstance(channels, dict):
+            raise NotificationError("channels는 채널 이름을 대상으로 매핑한 객체여야 합니다.")
+        previous = preferences.get("channels") or {}
+        cleaned = {}
+        for name, target in channels.items():
+            if name not in NOTIFIERS:
+                raise NotificationError(f"알 수 없는 채널입니다: {name} ({', '.join(NOTIFIERS)} 중 하나)")
+            notifier = NOTIFIERS[name]
+            # 목록 응답에서 가려진 주소가 그대로 돌아오면 기존 값 유지
+            if name in previous and target == notifier.mask(previous[name]):
+                cleaned[name] = previous[name]
+            elif target not in (None, ""):
+                cleaned[name] = notifier.validate_target(target)
+        preferences["channels"] = cleaned
+    if "events" in payload:
+        events = payload["events"] or {}
+        if not isinstance(events, dict):
+            raise NotificationError("events는 이벤트 이름을 채널 목록으로 매핑한 객체여야 합니다.")
+        for event, names in events.items():
+            if event != DEFAULT_EVENT and event not in EVENTS:
+                raise NotificationError(f"events의 키는 {DEFAULT_EVENT} 또는 {', '.join(EVENTS)} 중 하나여야 합니다.")
+            if not isinstance(names, list) or any(name not in NOTIFIERS for name in names):
+                raise NotificationError(f"{event}의 채널은 {', '.join(NOTIFIERS)} 중에서 골라야 합니다.")
+        preferences["events"] = {event: list(dict.fromkeys(names)) for event, names in events.items()}
+    if "enabled" in payload:
+        preferences["enabled"] = bool(payload["enabled"])
+
+
+def _public_preferences(user_id: str, preferences: Dict[str, Any]) -> Dict[str, Any]:
+    try:
+        name = get_person(user_id)["name"]
+    except PersonNotFound:
+        name = None
+    return {
+        "user_id": user_id,
+        "name": name,
+        "enabled": preferences.get("enabled", True),
+        "channels": {channel: NOTIFIERS[channel].mask(target)
+                     for channel, target in (preferences.get("channels") or {}).items() if channel in NOTIFIERS},
+        "events": preferences.get("events") or {},
+        "updated_at": preferences.get("updated_at"),
+    }
+
+
+def list_preferences() -> List[Dict[str, Any]]:
+    """Preferences of every person, targets masked."""
+    return [_public_preferences(user_id, prefs) for user_id, prefs in sorted(_load_users().items())]
+
+
+def get_preferences(user_id: str) -> Dict[str, Any]:
+    """Stored preferences of a person (targets not masked)."""
+    preferences = _load_users().get(user_id)
+    if preferences is None:
+        raise NotificationError(f"알림 설정을 찾을 수 없습니다: {user_id}")
+    return preferences
+
+
+def update_preferences(user_id: str, payload: Dict[str, Any]) -> Dict[str, Any]:
+    """Create or change the preferences of a person of the people directory."""
+    try:
+        get_person(user_id)
+    except PersonNotFound as exc:
+        raise NotificationError(str(exc)) from None
+    with _settings_lock:
+        users = _load_users()
+        preferences = dict(users.get(user_id) or {"enabled": True, "channels": {}, "events": {}})
+        _apply_preferences(preferences, payload)
+        preferences["updated_at"] = datetime.now().isoformat()
+        users[user_id] = preferences
+        _save_json(SETTINGS_FILE, {"users": users})
+    return _public_preferences(user_id, preferences)
+
+
+def delete_preferences(user_id: str) -> bool:
+    with _settings_lock:
+        users = _load_users()
+        if users.pop(user_id, None) is None:
+            return False
+        _save_json(SETTINGS_FILE, {"users": users})
+    return True
+
+
+def user_targets(user_id: str, preferences: Dict[str, Any], event: Optional[str] = None) -> List[Tuple[str, str]]:
+    """``(channel, target)`` pairs a person receives ``event`` on (every configured channel when ``None``)."""
+    if not preferences.get("enabled", True):
+        return []
+    channels = dict(preferences.get("channels") or {})
+    if "email" not in channels:
+        try:
+            email = (get_person(user_id).get("email") or "").strip()
+        except PersonNotFound:
+            email = ""
+        if _EMAIL.match(email):
+            channels["email"] = email
+    if event is None:
+        names = [name for name in NOTIFIERS if name in channels or not NOTIFIERS[name].needs_target]
+    else:
+        events = preferences.get("events") or {}
+        names = events.get(event, events.get(DEFAULT_EVENT, []))
+    targets = []
+    for name in names:
+        if name not in NOTIFIERS:
+            continue
+        if not NOTIFIERS[name].needs_target:
+            targets.append((name, ""))
+        elif channels.get(name):
+            targets.append((name, channels[name]))
+    return targets
+
+
+# --- 전송 기록 ---
+
+def _store_delivery(delivery: Dict[str, Any]) -> None:
+    with _deliveries_lock:
+        deliveries = [item for item in _load_json(DELIVERIES_FILE, []) if item.get("id") != delivery["id"]]
+        deliveries.append(delivery)
+        _save_json(DELIVERIES_FILE, deliveries[-NOTIFY_DELIVERY_LOG_LIMIT:])
+
+
+def list_deliveries(status: Optional[str] = None, channel: Optional[str] = None,
+                    record_id: Optional[str] = None, limit: int = 100) -> List[Dict[str, Any]]:
+    """Recorded deliveries, newest first."""
+    deliveries = _load_json(DELIVERIES_FILE, [])
+    matching = [item for item in reversed(deliveries)
+                if (not status or item.get("status") == status)
+                and (not channel or item.get("channel") == channel)
+                and (not record_id or item.get("record_id") == record_id)]
+    return matching[:limit]
+
+
+def send(channel: str, target: str, message: Dict[str, Any], user_id: Optional[str] = None,
+         test: bool = False) -> Dict[str, Any]:
+    """Send one notification now and record it; returns the finished delivery."""
+    notifier = NOTIFIERS[channel]
+    delivery = {
+        "id": str(uuid.uuid4()),
+        "event": message["event"],
+        "channel": channel,
+        "target": notifier.mask(target),
+        "user_id": user_id,
+        "record_id": message.get("record_id"),
+        "test": test,
+        "status": "pending",
+        "attempts": 0,
+        "error": None,
+        "created_at": datetime.now().isoformat(),
+        "finished_at": None,
+    }
+    _store_delivery(delivery)
+    max_attempts = NOTIFY_MAX_ATTEMPTS if notifier.retries and notifier.configured() else 1
+    for attempt in range(1, max_attempts + 1):
+        delivery["attempts"] = attempt
+        try:
+            notifier.send(target, message)
+            delivery["status"], delivery["error"] = "sent", None
+            break
+        except Exception as exc:  # noqa: BLE001 - 채널마다 예외 종류가 다름
+            delivery["status"], delivery["error"] = "failed", str(exc) or type(exc).__name__
+        if attempt < max_attempts:
+            time.sleep(2 ** (attempt - 1))
+    delivery["finished_at"] = datetime.now().isoformat()
+    _store_delivery(delivery)
+    if delivery["status"] == "failed":
+        print(f"알림 전송 실패 ({channel}, {delivery['target']}): {delivery['error']}")
+    return delivery
+
+
+def event_targets(event: str, include_webhooks: bool = True) -> List[Tuple[str, str, Optional[str]]]:
+    """``(channel, target, user_id)`` of everyone subscribed to ``event``, each channel/target once."""
+    targets: List[Tuple[str, str, Optional[str]]] = []
+    if include_webhooks:
+        targets += [("webhook", hook["id"], None) for hook in list_webhooks()
+                    if hook.get("enabled", True) and event in hook.get("events", [])]
+    for user_id, preferences in sorted(_load_users().items()):
+        targets += [(channel, target, user_id) for channel, target in user_targets(user_id, preferences, event)]
+    seen = set()
+    unique = []
+    for channel, target, user_id in targets:
+        if (channel, target) not in seen:
+            seen.add((channel, target))
+            unique.append((channel, target, user_id))
+    return unique
+
+
+def notify(event: str, context: Dict[str, Any], include_webhooks: bool = True,
+           on_result: Optional[Callable[[Dict[str, Any]], None]] = None) -> int:
+    """Send ``event`` to its subscribers in the background.
+
+    ``include_webhooks=False`` leaves out the subscribed webhooks (a watch
+    rule without the ``webhook`` channel). Returns the number of deliveries
+    started; ``on_result`` receives each finished delivery.
+    """
+    message = build_message(event, {**context, "event": event})
+
+    def run(channel: str, target: str, user_id: Optional[str]) -> None:
+        delivery = send(channel, target, message, user_id)
+        if on_result:
+            on_result(delivery)
+
+    targets = event_targets(event, include_webhooks)
+    for channel, target, user_id in targets:
+        threading.Thread(target=run, args=(channel, target, user_id), daemon=True).start()
+    return len(targets)
@@ -3,7 +3,7 @@ import logging
 import subprocess
 import sys
 import time
-from typing import Optional, Tuple
+from typing import List, Optional, Tuple
 import requests
 import platform
 
@@ -141,6 +141,72 @@ def check_ollama_model_available(model_name: str) -> Tuple[bool, str]:
     except Exception as e:
         return False, f"모델 목록 확인 중 오류 발생: {str(e)}"
 
+# /api/tags 결과를 잠시 재사용 (요청마다 모델 목록을 다시 받지 않도록)
+MODEL_LIST_TTL_SECONDS = 30
+_model_list_cache = {"at": 0.0, "models": None}
+
+
+class UnknownModelError(ValueError):
+    """Raised when a requested model is not installed in Ollama."""
+
+    def __init__(self, model_name: str, available: List[str]):
+        self.model_name = model_name
+        self.available = available
+        super().__init__(
+            f"Ollama에 '{model_name}' 모델이 없습니다. "
+            f"사용 가능한 모델: {', '.join(available) if available else '없음'} (ollama pull {model_name}로 설치)"
+        )
+
+
+def list_ollama_models() -> Optional[List[str]]:
+    """
+    Ollama에 설치된 모델 이름(``/api/tags``)을 반환합니다.
+
+    서버를 자동으로 시작하지 않으며, 서버에 연결할 수 없으면 None을 반환합니다.
+    """
+    now = time.time()
+    if _model_list_cache["models"] is not None and now - _model_list_cache["at"] < MODEL_LIST_TTL_SECONDS:
+        return _model_list_cache["models"]
+    if ollama is None:
+        return None
+    try:
+        response = ollama.list()
+    except Exception as e:
+        logging.warning(f"Ollama 모델 목록 조회 실패: {e}")
+        return None
+    entries = response.get("models", []) if isinstance(response, dict) else getattr(response, "models", [])
+    models = []
+    for entry in entries or []:
+        name = (entry.get("model") or entry.get("name")) if isinstance(entry, dict) else getattr(entry, "model", None)
+        if name:
+            models.append(name)
+    _model_list_cache.update({"at": now, "models": models})
+    return models
+
+
+def require_ollama_model(model_name: str) -> str:
+    """
+    요청에서 고른 모델이 Ollama에 설치되어 있는지 확인하고 모델 이름을 그대로 반환합니다.
+
+    태그 없는 이름은 ``:latest``와 같게 봅니다. 모델 목록을 받을 수 없으면
+    (서버 중지 등) 확인을 건너뛰고 실행 시점의 오류에 맡깁니다.
+
+    Raises:
+        UnknownModelError: 설치되지 않은 모델
+    """
+    models = list_ollama_models()
+    if models is None:
+        return model_name
+    if model_name in models or f"{model_name}:latest" in models:
+        return model_name
+    # 방금 설치한 모델일 수 있으므로 캐시를 비우고 한 번 더 확인
+    _model_list_cache["models"] = None
+    models = list_ollama_models() or []
+    if model_name in models or f"{model_name}:latest" in models:
+        return model_name
+    raise UnknownModelError(model_name, models)
+
+
 def safe_ollama_call(func, *args, **kwargs):
     """
     Ollama API 호출을 안전하게 실행합니다.
@@ -174,4 +240,32 @@ def safe_ollama_call(func, *args, **kwargs):
             else:
                 raise Exception(f"Ollama 서버 재시작 실패: {start_msg}")
         else:
-            raise e
\ No newline at end of file
+            raise e
+_context_lengths = {}
+
+def get_model_context_length(model_name: str) -> Optional[int]:
+    """
+    모델이 지원하는 최대 컨텍스트 길이(토큰)를 ``ollama show``로 조회합니다.
+
+    모델 정보의 ``{arch}.context_length`` 값을 모델별로 한 번만 조회해 캐시하며,
+    조회할 수 없으면 None을 반환합니다.
+    """
+    if model_name in _context_lengths:
+        return _context_lengths[model_name]
+    if ollama is None:
+        return None
+    length = None
+    try:
+        response = safe_ollama_call(ollama.show, model_name)
+        info = getattr(response, "modelinfo", None)
+        if info is None and isinstance(response, dict):
+            info = response.get("modelinfo") or response.get("model_info")
+        for key, value in (info or {}).items():
+            if key.endswith(".context_length") and isinstance(value, int) and value > 0:
+                length = value
+                break
+    except Exception as e:
+        logging.warning(f"모델 컨텍스트 길이 조회 실패 ({model_name}): {e}")
+        return None
+    _context_lengths[model_name] = length
+    return length
@@ -1,28 +1,122 @@
-"""Utility for generating one-line summaries using Ollama."""
+"""Utility for generating one-line summaries using Ollama.
+
+Length, tone and language come from ``ONE_LINE_MAX_CHARS``,
+``ONE_LINE_TONE`` and ``ONE_LINE_LANGUAGE`` and can be overridden per request
+(``/process`` ``one_line`` option). The model's answer is cleaned up and cut
+to the maximum length on the server so history list rows stay uniform.
+"""
 
 from pathlib import Path
+from typing import Any, Dict, List, Optional
+
 import ollama
+from config import get_config_value
 from workflow.summarize import read_text_with_fallback, DEFAULT_MODEL
 from ollama_utils import safe_ollama_call
+from text_utils import grapheme_len, graphemes, truncate_graphemes
+
+TONES = {
+    "neutral": "사실만 중립적으로 서술하세요.",
+    "action": "결정 사항과 해야 할 일을 중심으로 서술하세요.",
+}
+LANGUAGES = {
+    "ko": "한국어",
+    "en": "영어(English)",
+    "ja": "일본어(日本語)",
+    "zh": "중국어(中文)",
+}
+MIN_CHARS, MAX_CHARS = 10, 300
+
+# /admin/reload 시 다시 읽힘
+ONE_LINE_MAX_CHARS = get_config_value("ONE_LINE_MAX_CHARS", 80, int)
+ONE_LINE_TONE = get_config_value("ONE_LINE_TONE", "neutral", str)
+ONE_LINE_LANGUAGE = get_config_value("ONE_LINE_LANGUAGE", "ko", str)
+
+# /admin/reload 시 DB/prompts/one_line.txt 로 교체될 수 있음
+# ({text} 필수, {max_chars}/{language}/{tone} 선택)
+ONE_LINE_PROMPT = (
+    "다음 텍스트를 {language}로 {max_chars}자 이내의 한 문장으로 요약해 주세요. {tone}\n"
+    "요약 문장만 출력하세요.\n{text}"
+)
+
+_STRIP_PREFIXES = ("한 줄 요약:", "한줄 요약:", "요약:", "Summary:", "summary:")
+
+
+class OneLineOptionsError(ValueError):
+    """Raised for invalid one-line summary options; ``errors`` lists every problem."""
 
+    def __init__(self, errors: List[str]):
+        super().__init__("; ".join(errors))
+        self.errors = errors
 
-def generate_one_line_summary(file_path: Path, model: str = None) -> str:
-    """Generate a single-line Korean summary for the given text file.
+
+def resolve_one_line_options(overrides: Optional[Dict[str, Any]] = None) -> Dict[str, Any]:
+    """Merge per-request overrides into the configured defaults and validate them."""
+    if overrides is not None and not isinstance(overrides, dict):
+        raise OneLineOptionsError(["one_line은 객체여야 합니다."])
+    options = {"max_chars": ONE_LINE_MAX_CHARS, "tone": ONE_LINE_TONE, "language": ONE_LINE_LANGUAGE}
+    options.update({key: value for key, value in (overrides or {}).items() if value is not None})
+
+    errors = []
+    unknown = sorted(set(options) - {"max_chars", "tone", "language"})
+    if unknown:
+        errors.append(f"알 수 없는 옵션: {', '.join(unknown)}")
+    max_chars = options["max_chars"]
+    if isinstance(max_chars, bool) or not isinstance(max_chars, int) or not MIN_CHARS <= max_chars <= MAX_CHARS:
+        errors.append(f"max_chars는 {MIN_CHARS}~{MAX_CHARS} 사이의 정수여야 합니다.")
+    if options["tone"] not in TONES:
+        errors.append(f"tone은 {', '.join(TONES)} 중 하나여야 합니다.")
+    if options["language"] not in LANGUAGES:
+        errors.append(f"language는 {', '.join(LANGUAGES)} 중 하나여야 합니다.")
+    if errors:
+        raise OneLineOptionsError(errors)
+    return options
+
+
+def clean_one_line(response: str, max_chars: int) -> str:
+    """First non-empty line without labels/quotes, cut to ``max_chars`` user-perceived characters."""
+    line = next((line.strip() for line in response.splitlines() if line.strip()), "")
+    line = line.lstrip("-*•# ").strip()
+    for prefix in _STRIP_PREFIXES:
+        if line.startswith(prefix):
+            line = line[len(prefix):].strip()
+    line = " ".join(line.strip("\"'“”‘’「」").split())
+    if grapheme_len(line) <= max_chars:
+        return line
+    cut = "".join(graphemes(line)[:max_chars - 1])
+    # 단어 중간에서 자르지 않도록 마지막 공백까지 (너무 짧아지면 그대로)
+    if " " in cut and cut.rindex(" ") >= max_chars // 2:
+        cut = cut[:cut.rindex(" ")]
+    return cut.rstrip(" ,.;:") + "…"
+
+
+def generate_one_line_summary(file_path: Path, model: str = None,
+                              options: Optional[Dict[str, Any]] = None) -> str:
+    """Generate a single-line summary for the given text file.
 
     Args:
         file_path: Path to the text file to summarize.
         model: Optional Ollama model name to use. Defaults to the
             structured summary model when not provided.
+        options: Validated options from :func:`resolve_one_line_options`
+            (configured defaults when omitted).
 
     Returns:
-        A one-line summary string.
+        A one-line summary string of at most ``max_chars`` characters.
     """
+    options = options or resolve_one_line_options()
     text = read_text_with_fallback(file_path)
-    prompt = "다음 텍스트를 한 줄로 한국어로 요약해 주세요:\n" + text[:4000]
+    prompt = (
+        ONE_LINE_PROMPT
+        .replace("{max_chars}", str(options["max_chars"]))
+        .replace("{language}", LANGUAGES[options["language"]])
+        .replace("{tone}", TONES[options["tone"]])
+        .replace("{text}", truncate_graphemes(text, 4000, ellipsis=""))
+    )
     response = safe_ollama_call(
         ollama.generate,
         model=model or DEFAULT_MODEL,
         prompt=prompt,
         options={"temperature": 0},
     )
-    return response.get("response", "").strip().splitlines()[0]
+    return clean_one_line(response.get("response", ""), options["max_chars"])
@@ -0,0 +1,139 @@
+"""Find and remove artifact folders that no record points to any more.
+
+Output folders (``DB/whisper_output/{folder}`` or ``DB/records/{folder}``
+and their ``DB/deleted/`` mirrors) are left behind when a record is purged
+while a file is still open, when a delete is interrupted halfway, or when a
+record drops out of the history. Nothing lists them, so they only eat disk.
+
+A folder is an orphan when:
+
+* it is in the live tree and its record is missing (``no_record``) or in
+  the trash with a trash copy of the folder (``deleted_record``);
+* it is in the trash and its record is missing (``no_record``) or live
+  again with a live copy of the folder (``restored_record``).
+
+Folders still referenced from the file registry are never reported, and
+folders modified within ``ORPHAN_MIN_AGE_HOURS`` are skipped so a workflow
+that is just creating its output is not mistaken for an orphan.
+
+At startup the server runs :func:`find_orphans` according to
+``ORPHAN_CLEANUP_ON_STARTUP`` (``report`` by default, ``delete`` or
+``off``); ``GET /admin/orphans`` and ``POST /admin/orphans/cleanup`` do the
+same on demand.
+"""
+
+from __future__ import annotations
+
+import shutil
+import time
+from dataclasses import asdict, dataclass
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Dict, Iterable, List, Optional, Tuple
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+    from .record_layout import RecordLayout
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+    from record_layout import RecordLayout  # type: ignore
+
+ORPHAN_CLEANUP_ON_STARTUP = get_config_value("ORPHAN_CLEANUP_ON_STARTUP", "report", str).strip().lower()
+ORPHAN_MIN_AGE_HOURS = get_config_value("ORPHAN_MIN_AGE_HOURS", 1, float)
+STARTUP_MODES = ("off", "report", "delete")
+
+
+@dataclass
+class Orphan:
+    path: str  # DB 기준 상대 경로
+    folder: str
+    location: str  # live | deleted
+    reason: str  # no_record | deleted_record | restored_record
+    size_bytes: int
+    modified_at: str
+
+
+def _size(path: Path) -> int:
+    if path.is_file():
+        return path.stat().st_size
+    total = 0
+    for child in path.rglob("*"):
+        try:
+            if child.is_file():
+                total += child.stat().st_size
+        except OSError:
+            continue
+    return total
+
+
+def _reason(layout: RecordLayout, folder: str, location: str, live: set, trashed: set) -> Optional[str]:
+    # 기록이 있는 폴더는 다른 쪽에 사본이 실제로 있을 때만 남은 찌꺼기로 본다
+    if location == "live":
+        if folder in live:
+            return None
+        if folder in trashed:
+            return "deleted_record" if layout.deleted_output_dir(folder).exists() else None
+        return "no_record"
+    if folder in trashed:
+        return None
+    if folder in live:
+        return "restored_record" if layout.output_dir(folder).exists() else None
+    return "no_record"
+
+
+def find_orphans(layout: RecordLayout, history: List[Dict[str, Any]], registry_folders: Iterable[str] = (),
+                 min_age_hours: float = ORPHAN_MIN_AGE_HOURS, now: Optional[float] = None) -> List[Orphan]:
+    """Orphaned output folders of ``layout``, oldest first."""
+    live = {r["folder_name"] for r in history if r.get("folder_name") and not r.get("deleted")}
+    trashed = {r["folder_name"] for r in history if r.get("folder_name") and r.get("deleted")}
+    referenced = set(registry_folders)
+    cutoff = (now or time.time()) - min_age_hours * 3600
+
+    orphans = []
+    for location, root in (("live", layout.output_root), ("deleted", layout.deleted_output_root)):
+        if not root.exists():
+            continue
+        for path in root.iterdir():
+            folder = path.name
+            reason = _reason(layout, folder, location, live, trashed)
+            if reason is None or (reason == "no_record" and folder in referenced):
+                continue
+            try:
+                modified = path.stat().st_mtime
+            except OSError:
+                continue
+            if modified > cutoff:
+                continue
+            orphans.append(Orphan(
+                path=path.relative_to(layout.db_base).as_posix(),
+                folder=folder,
+                location=location,
+                reason=reason,
+                size_bytes=_size(path),
+                modified_at=datetime.fromtimestamp(modified).isoformat(),
+            ))
+    return sorted(orphans, key=lambda orphan: orphan.modified_at)
+
+
+def remove_orphans(layout: RecordLayout, orphans: Iterable[Orphan]) -> Tuple[List[str], Dict[str, str]]:
+    """Delete the given orphans; returns removed paths and ``{path: error}`` for failures."""
+    removed, errors = [], {}
+    for orphan in orphans:
+        path = layout.db_base / orphan.path
+        try:
+            if path.is_dir():
+                shutil.rmtree(path)
+            elif path.exists():
+                path.unlink()
+            removed.append(orphan.path)
+        except OSError as exc:
+            errors[orphan.path] = str(exc)
+    return removed, errors
+
+
+def report(orphans: List[Orphan]) -> Dict[str, Any]:
+    return {
+        "count": len(orphans),
+        "total_bytes": sum(orphan.size_bytes for orphan in orphans),
+        "orphans": [asdict(orphan) for orphan in orphans],
+    }
@@ -0,0 +1,145 @@
+"""Sentence-level deduplication where consecutive chunks or windows overlap.
+
+Two stages produce text twice around a boundary:
+
+* map-reduce summaries — neighbouring chunk summaries restate the same
+  sentences, so the reduce prompt reads them twice;
+* windowed (checkpointed) transcription — consecutive windows share
+  ``STT_CHECKPOINT_OVERLAP_SECONDS`` of audio, so the speech in that
+  stretch comes back from both windows.
+
+Both align the sentences on either side of the boundary with
+:class:`difflib.SequenceMatcher`. Sentences are compared after
+normalisation (case, punctuation, whitespace) and count as equal when their
+similarity reaches ``OVERLAP_DEDUP_SIMILARITY``, so the slightly different
+wording two Whisper windows produce for the same speech still aligns.
+Sentences shorter than ``OVERLAP_DEDUP_MIN_CHARS`` ("네.", "OK.") are never
+treated as duplicates because they legitimately repeat.
+"""
+
+from __future__ import annotations
+
+import re
+from difflib import SequenceMatcher
+from typing import Any, Dict, List, Optional, Sequence, Tuple
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+OVERLAP_DEDUP_ENABLED = get_config_value("OVERLAP_DEDUP_ENABLED", True, bool)
+OVERLAP_DEDUP_SIMILARITY = min(1.0, max(0.5, get_config_value("OVERLAP_DEDUP_SIMILARITY", 0.85, float)))
+OVERLAP_DEDUP_MIN_CHARS = max(1, get_config_value("OVERLAP_DEDUP_MIN_CHARS", 8, int))
+
+# 문장 끝(마침표/물음표/느낌표 뒤 공백, 전각 문장부호)에서 분리
+_SENTENCE_END = re.compile(r"(?<=[.!?。？！])\s+|(?<=[。？！])")
+_NON_WORD = re.compile(r"[\W_]+", re.UNICODE)
+# 요약의 제목/구분선은 중복이어도 구조이므로 유지
+_STRUCTURE_LINE = re.compile(r"^\s*(#|---|\*\*[^*]+\*\*:?\s*$)")
+_BULLET = re.compile(r"^(\s*(?:[-*+•]|\d+[.)])\s+)")
+
+
+def normalize_sentence(text: str) -> str:
+    """Comparison key of a sentence: case-folded word characters only."""
+    return _NON_WORD.sub(" ", text or "").casefold().strip()
+
+
+def split_sentences(text: str) -> List[str]:
+    return [part for part in (p.strip() for p in _SENTENCE_END.split(text or "")) if part]
+
+
+def _similar(a: str, b: str) -> bool:
+    if len(a) < OVERLAP_DEDUP_MIN_CHARS or len(b) < OVERLAP_DEDUP_MIN_CHARS:
+        return False
+    if a == b:
+        return True
+    return SequenceMatcher(None, a, b, autojunk=False).ratio() >= OVERLAP_DEDUP_SIMILARITY
+
+
+def align_sentences(previous: Sequence[str], following: Sequence[str]) -> List[Tuple[int, int, int]]:
+    """Matching runs ``(i, j, size)`` between two sentence lists (``SequenceMatcher.get_matching_blocks`` form).
+
+    Each sentence of ``following`` that is similar to a sentence of
+    ``previous`` takes that sentence's key before the exact alignment, so
+    near-duplicates align like identical sentences.
+    """
+    keys_a = [normalize_sentence(sentence) for sentence in previous]
+    keys_b = []
+    for index, sentence in enumerate(following):
+        key = normalize_sentence(sentence)
+        match = next((candidate for candidate in keys_a if _similar(candidate, key)), None)
+        # 매칭되지 않은 문장은 서로 겹치지 않는 고유 키로 둔다
+        keys_b.append(match if match is not None else f"\0{index}")
+    keys_a = [key if len(key) >= OVERLAP_DEDUP_MIN_CHARS else f"\1{i}" for i, key in enumerate(keys_a)]
+    matcher = SequenceMatcher(None, keys_a, keys_b, autojunk=False)
+    return [tuple(block) for block in matcher.get_matching_blocks() if block.size]
+
+
+def dedup_chunk_texts(texts: Sequence[str]) -> Tuple[List[str], int]:
+    """Drop sentences of each text that repeat the previous text; ``(texts, removed sentence count)``.
+
+    Used for chunk summaries before the reduce step. Line structure (bullets,
+    headings) is kept; a bullet whose sentences were all duplicates is dropped.
+    """
+    if not OVERLAP_DEDUP_ENABLED or len(texts) < 2:
+        return list(texts), 0
+    result = [texts[0]]
+    removed = 0
+    for previous, text in zip(texts, texts[1:]):
+        previous_sentences = [
+            sentence for line in previous.splitlines() if not _STRUCTURE_LINE.match(line)
+            for sentence in split_sentences(_BULLET.sub("", line))
+        ]
+        lines = []
+        for line in text.splitlines():
+            if not line.strip() or _STRUCTURE_LINE.match(line):
+                lines.append(line)
+                continue
+            bullet = _BULLET.match(line)
+            prefix = bullet.group(1) if bullet else ""
+            sentences = split_sentences(line[len(prefix):])
+            duplicated = set()
+            for _, j, size in align_sentences(previous_sentences, sentences):
+                duplicated.update(range(j, j + size))
+            removed += len(duplicated)
+            kept = [sentence for index, sentence in enumerate(sentences) if index not in duplicated]
+            if kept:
+                lines.append(prefix + " ".join(kept))
+        result.append("\n".join(lines).strip())
+    return result, removed
+
+
+def splice_window_segments(kept: List[Dict[str, Any]], incoming: List[Dict[str, Any]],
+                           overlap_start: float, overlap_end: float) -> Tuple[List[Dict[str, Any]], int]:
+    """Join the segments of a window that starts ``overlap_end - overlap_start`` seconds early.
+
+    The sentences the previous window produced after ``overlap_start`` are
+    aligned with the ones the new window produced before ``overlap_end``;
+    the transcript continues from the new window right after the last
+    aligned sentence. Without an alignment (silence, or the windows heard
+    it differently) it is cut at the middle of the overlap. Returns the
+    joined list and the number of segments dropped.
+    """
+    if overlap_end <= overlap_start:
+        return kept + incoming, 0
+    tail_index = next((i for i, segment in enumerate(kept) if segment.get("end", 0.0) > overlap_start), len(kept))
+    tail = kept[tail_index:]
+    head_count = next((i for i, segment in enumerate(incoming) if segment.get("start", 0.0) >= overlap_end),
+                      len(incoming))
+    head = incoming[:head_count]
+
+    splice: Optional[Tuple[int, int]] = None
+    if OVERLAP_DEDUP_ENABLED and tail and head:
+        blocks = align_sentences([s.get("text", "") for s in tail], [s.get("text", "") for s in head])
+        if blocks:
+            i, j, size = blocks[-1]
+            splice = (i + size, j + size)
+    if splice is None:
+        middle = (overlap_start + overlap_end) / 2
+        splice = (
+            sum(1 for segment in tail if segment.get("start", 0.0) < middle),
+            sum(1 for segment in head if segment.get("start", 0.0) < middle),
+        )
+    joined = kept[:tail_index] + tail[:splice[0]] + incoming[splice[1]:]
+    return joined, len(kept) + len(incoming) - len(joined)
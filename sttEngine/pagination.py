@@ -0,0 +1,92 @@
+"""Cursor pagination shared by the list endpoints.
+
+A list endpoint called with ``?cursor=`` (empty for the first page) and an
+optional ``?limit=`` returns one page plus the cursor of the next one::
+
+    GET /history?cursor=&limit=50
+    {"records": [...], "next_cursor": "eyJsIjoiaGlzdG9yeSIs...", "has_more": true, "limit": 50}
+
+    GET /history?cursor=eyJsIjoiaGlzdG9yeSIs...&limit=50
+
+The cursor is opaque to clients: URL-safe base64 of the list name, the sort
+and the sort key of the last item served. The next page starts strictly
+after that key instead of at an offset, so records added or removed while
+a client pages through a list never shift items into or out of later
+pages. Every key ends with the item id, so items that tie on the sort
+field keep a fixed order. Without ``cursor`` the endpoints answer as
+before (full list, or ``limit``/``offset`` for ``GET /history``).
+"""
+
+from __future__ import annotations
+
+import base64
+import binascii
+import json
+from typing import Any, Callable, Dict, Iterable, List, Optional, Tuple
+
+PAGE_LIMIT_DEFAULT = 50
+PAGE_LIMIT_MAX = 500
+
+
+class CursorError(ValueError):
+    """Raised for malformed cursors, cursors of another list/sort and invalid limits."""
+
+
+def sort_key(value: Any, *tiebreak: Any, descending: bool = False) -> Tuple[Any, ...]:
+    """Sort key that puts items without ``value`` last in either direction, then ``tiebreak`` (ending with the id)."""
+    present = value not in (None, "")
+    rank = (1 if present else 0) if descending else (0 if present else 1)
+    return (rank, value if present else "", *("" if part is None else part for part in tiebreak))
+
+
+def encode_cursor(list_name: str, sort: str, key: Tuple[Any, ...]) -> str:
+    raw = json.dumps({"l": list_name, "s": sort, "k": list(key)}, ensure_ascii=False, separators=(",", ":"))
+    return base64.urlsafe_b64encode(raw.encode("utf-8")).decode("ascii").rstrip("=")
+
+
+def decode_cursor(cursor: str, list_name: str, sort: str) -> Optional[Tuple[Any, ...]]:
+    """Sort key stored in ``cursor`` (``None`` for the empty first-page cursor)."""
+    if not cursor:
+        return None
+    try:
+        data = json.loads(base64.urlsafe_b64decode(cursor + "=" * (-len(cursor) % 4)))
+        key = tuple(data["k"])
+    except (ValueError, KeyError, TypeError, binascii.Error):
+        raise CursorError("잘못된 cursor입니다. 첫 페이지는 cursor를 비워 요청하세요.") from None
+    if data.get("l") != list_name or data.get("s") != sort:
+        raise CursorError("다른 목록이나 정렬에서 받은 cursor입니다.")
+    return key
+
+
+def parse_page_params(params: Dict[str, List[str]], default_limit: int = PAGE_LIMIT_DEFAULT,
+                      max_limit: int = PAGE_LIMIT_MAX) -> Optional[Tuple[str, int]]:
+    """``(cursor, limit)`` from the query, or ``None`` when no ``cursor`` was sent (unpaginated request).
+
+    ``params`` must be parsed with ``keep_blank_values=True`` so the empty
+    first-page cursor is seen.
+    """
+    if "cursor" not in params:
+        return None
+    try:
+        limit = int(params.get("limit", [default_limit])[0])
+    except ValueError:
+        raise CursorError("limit은 정수여야 합니다.") from None
+    if not 1 <= limit <= max_limit:
+        raise CursorError(f"limit은 1~{max_limit}이어야 합니다.")
+    return params["cursor"][0], limit
+
+
+def cursor_page(items: Iterable[Any], key: Callable[[Any], Tuple[Any, ...]], cursor: str, limit: int,
+                list_name: str, sort: str = "default",
+                descending: bool = False) -> Tuple[List[Any], Optional[str]]:
+    """Items after ``cursor`` in ``key`` order, at most ``limit``, and the next cursor (``None`` on the last page)."""
+    after = decode_cursor(cursor, list_name, sort)
+    keyed = sorted(((key(item), item) for item in items), key=lambda pair: pair[0], reverse=descending)
+    if after is not None:
+        try:
+            keyed = [pair for pair in keyed if (pair[0] < after if descending else pair[0] > after)]
+        except TypeError:
+            raise CursorError("cursor가 이 목록의 정렬 값과 맞지 않습니다.") from None
+    page = keyed[:limit]
+    next_cursor = encode_cursor(list_name, sort, page[-1][0]) if len(keyed) > limit else None
+    return [item for _, item in page], next_cursor
@@ -0,0 +1,133 @@
+"""Readable paragraphs from choppy Whisper segments.
+
+Whisper emits one short line every few seconds, which is what subtitles
+need but reads badly as a document. :func:`build_paragraphs` merges
+consecutive segments into paragraphs and starts a new one when
+
+* the speaker changes;
+* the pause before a segment is at least ``TRANSCRIPT_PARAGRAPH_PAUSE_SECONDS``
+  and the previous segment completed a sentence (or the pause is three
+  times that long, sentence or not);
+* the paragraph reached ``TRANSCRIPT_PARAGRAPH_MAX_CHARS`` and the previous
+  segment completed a sentence (hard limit at 1.5× regardless).
+
+Sentence completion is punctuation, or a Korean final ending (``-다``,
+``-요``, ``-죠``, ``-까``) since Whisper often leaves Korean unpunctuated.
+
+Only the text/markdown transcript export (``GET /record/{id}/transcript/export``)
+uses paragraphs; stored segments, subtitles and the STT markdown keep the
+raw segment lines.
+"""
+
+from __future__ import annotations
+
+import re
+from dataclasses import asdict, dataclass
+from typing import Any, Dict, Iterable, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+TRANSCRIPT_PARAGRAPH_PAUSE_SECONDS = get_config_value("TRANSCRIPT_PARAGRAPH_PAUSE_SECONDS", 1.5, float)
+TRANSCRIPT_PARAGRAPH_MAX_CHARS = max(50, get_config_value("TRANSCRIPT_PARAGRAPH_MAX_CHARS", 600, int))
+
+LONG_PAUSE_FACTOR = 3
+HARD_LIMIT_FACTOR = 1.5
+EXPORT_FORMATS = ("md", "txt")
+
+_SENTENCE_END = re.compile(r"([.?!…。？！]|(다|요|죠|까|니다|세요))[\"'”’)\]]*$")
+_MARKDOWN_LINE = re.compile(r"^\[(\d+):(\d{2}):(\d{2}) - (\d+):(\d{2}):(\d{2})\]\s?(.*)$")
+
+
+@dataclass
+class Paragraph:
+    start: float
+    end: float
+    text: str
+    speaker: Optional[str] = None
+    segments: int = 1
+
+    def to_dict(self) -> Dict[str, Any]:
+        return asdict(self)
+
+
+def ends_sentence(text: str) -> bool:
+    return bool(_SENTENCE_END.search(text.strip()))
+
+
+def build_paragraphs(segments: Iterable[Dict[str, Any]],
+                     pause_seconds: float = TRANSCRIPT_PARAGRAPH_PAUSE_SECONDS,
+                     max_chars: int = TRANSCRIPT_PARAGRAPH_MAX_CHARS) -> List[Paragraph]:
+    """Merge ``{"start", "end", "text", "speaker"?}`` segments into paragraphs."""
+    paragraphs: List[Paragraph] = []
+    current: Optional[Paragraph] = None
+    previous_text = ""
+    for segment in segments:
+        text = str(segment.get("text", "")).strip()
+        if not text:
+            continue
+        start, end = float(segment.get("start", 0.0)), float(segment.get("end", 0.0))
+        speaker = segment.get("speaker")
+        if current is not None:
+            pause = start - current.end
+            complete = ends_sentence(previous_text)
+            length = len(current.text)
+            if (speaker != current.speaker
+                    or pause >= pause_seconds * LONG_PAUSE_FACTOR
+                    or (complete and (pause >= pause_seconds or length >= max_chars))
+                    or length >= max_chars * HARD_LIMIT_FACTOR):
+                current = None
+        if current is None:
+            current = Paragraph(start=start, end=end, text=text, speaker=speaker)
+            paragraphs.append(current)
+        else:
+            current.text += " " + text
+            current.end = max(current.end, end)
+            current.segments += 1
+        previous_text = text
+    return paragraphs
+
+
+def segments_from_markdown(markdown: str) -> List[Dict[str, Any]]:
+    """Segments parsed back from ``[HH:MM:SS - HH:MM:SS] 텍스트`` lines (manual edits included).
+
+    Lines without a timestamp continue the previous segment; the title and
+    blank lines are skipped.
+    """
+    segments: List[Dict[str, Any]] = []
+    for line in markdown.splitlines():
+        match = _MARKDOWN_LINE.match(line.strip())
+        if match:
+            h1, m1, s1, h2, m2, s2, text = match.groups()
+            segments.append({
+                "start": int(h1) * 3600 + int(m1) * 60 + int(s1),
+                "end": int(h2) * 3600 + int(m2) * 60 + int(s2),
+                "text": text,
+            })
+        elif line.strip() and not line.startswith("# ") and segments:
+            segments[-1]["text"] += " " + line.strip()
+    return segments
+
+
+def _timestamp(seconds: float) -> str:
+    h, rem = divmod(int(seconds), 3600)
+    m, s = divmod(rem, 60)
+    return f"{h:02d}:{m:02d}:{s:02d}"
+
+
+def render_paragraphs(title: str, paragraphs: List[Paragraph], export_format: str = "md",
+                      speaker_names: Optional[Dict[str, str]] = None) -> str:
+    """Markdown (timestamp and speaker per paragraph) or plain text."""
+    speaker_names = speaker_names or {}
+    blocks = []
+    for paragraph in paragraphs:
+        speaker = speaker_names.get(paragraph.speaker, paragraph.speaker) if paragraph.speaker else None
+        if export_format == "txt":
+            blocks.append(f"{speaker}: {paragraph.text}" if speaker else paragraph.text)
+        else:
+            header = f"**{speaker}** " if speaker else ""
+            blocks.append(f"{header}[{_timestamp(paragraph.start)}] {paragraph.text}")
+    heading = title if export_format == "md" else title.lstrip("# ")
+    return f"{heading}\n\n" + "\n\n".join(blocks) + "\n"
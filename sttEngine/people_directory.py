@@ -0,0 +1,265 @@
+"""Directory of known people and where they appear in the archive.
+
+People are kept in ``DB/people.json``::
+
+    {"people": {"...": {"id": "...", "name": "김민수", "aliases": ["민수", "Minsu Kim"],
+                        "email": "", "role": "PM", "note": "", "speaker_profile_id": null}}}
+
+Names and aliases are unique across the directory (case-insensitive), so
+any of them resolves to one person. The directory is used in three places:
+
+* speaker labelling — a name given for a speaker label (or a voice profile
+  linked with ``speaker_profile_id``) is stored as the person's canonical
+  name, so "민수" and "김민수" do not become two speakers;
+* the STT initial prompt — names and aliases of the record's attendees,
+  then the other names of the directory (source ``people``);
+* the summary prompt — the people who attend or are named in a transcript
+  are listed with their aliases and roles so the summary spells them one way.
+
+:func:`find_mentions` scans a record's segments for the segments a person
+spoke (speaker label named after them) and the segments that name them.
+"""
+
+from __future__ import annotations
+
+import json
+import re
+import threading
+import uuid
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Dict, Iterable, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_db_base_path
+    from .speaker_profiles import MAX_SPEAKER_NAME_LENGTH, load_profiles
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_db_base_path  # type: ignore
+    from speaker_profiles import MAX_SPEAKER_NAME_LENGTH, load_profiles  # type: ignore
+
+PEOPLE_FILE = get_db_base_path() / "people.json"
+MAX_ALIASES = 20
+TEXT_FIELDS = ("email", "role", "note")
+# 언급 위치에 함께 돌려줄 세그먼트 텍스트 길이
+MENTION_TEXT_MAX_CHARS = 200
+
+_people_lock = threading.Lock()
+
+
+class PeopleError(ValueError):
+    """Raised for invalid person definitions."""
+
+
+class PersonNotFound(PeopleError):
+    """Raised for unknown person ids."""
+
+
+def _load(path: Path = PEOPLE_FILE) -> Dict[str, Dict[str, Any]]:
+    try:
+        with open(path, "r", encoding="utf-8") as f:
+            people = json.load(f).get("people")
+        return people if isinstance(people, dict) else {}
+    except (OSError, ValueError, AttributeError):
+        return {}
+
+
+def _save(people: Dict[str, Dict[str, Any]], path: Path = PEOPLE_FILE) -> None:
+    path.parent.mkdir(parents=True, exist_ok=True)
+    tmp_path = path.with_name(f"{path.name}.tmp")
+    with open(tmp_path, "w", encoding="utf-8") as f:
+        json.dump({"people": people}, f, ensure_ascii=False, indent=2)
+    tmp_path.replace(path)
+
+
+def person_names(person: Dict[str, Any]) -> List[str]:
+    """The person's name followed by their aliases."""
+    return [person["name"], *person.get("aliases", [])]
+
+
+def _clean_name(value: Any, field: str) -> str:
+    if not isinstance(value, str) or not value.strip():
+        raise PeopleError(f"{field}은(는) 비어 있지 않은 문자열이어야 합니다.")
+    value = re.sub(r"\s+", " ", value).strip()
+    if len(value) > MAX_SPEAKER_NAME_LENGTH:
+        raise PeopleError(f"{field}은(는) {MAX_SPEAKER_NAME_LENGTH}자 이하여야 합니다.")
+    return value
+
+
+def _apply_fields(person: Dict[str, Any], payload: Dict[str, Any]) -> None:
+    if "name" in payload:
+        person["name"] = _clean_name(payload["name"], "이름(name)")
+    if "aliases" in payload:
+        aliases = payload["aliases"] or []
+        if not isinstance(aliases, list):
+            raise PeopleError("aliases는 문자열 배열이어야 합니다.")
+        if len(aliases) > MAX_ALIASES:
+            raise PeopleError(f"별칭은 최대 {MAX_ALIASES}개까지 지정할 수 있습니다.")
+        cleaned = [_clean_name(alias, "별칭(aliases)") for alias in aliases]
+        person["aliases"] = list({alias.casefold(): alias for alias in cleaned}.values())
+    for field in TEXT_FIELDS:
+        if field in payload:
+            value = payload[field]
+            if value is not None and not isinstance(value, str):
+                raise PeopleError(f"{field}는 문자열이어야 합니다.")
+            person[field] = (value or "").strip()
+    if "speaker_profile_id" in payload:
+        profile_id = payload["speaker_profile_id"] or None
+        if profile_id is not None and profile_id not in load_profiles():
+            raise PeopleError(f"화자 프로필을 찾을 수 없습니다: {profile_id}")
+        person["speaker_profile_id"] = profile_id
+    # 본명과 같은 별칭은 저장하지 않음
+    person["aliases"] = [a for a in person.get("aliases", []) if a.casefold() != person["name"].casefold()]
+
+
+def _check_unique(person: Dict[str, Any], people: Dict[str, Dict[str, Any]]) -> None:
+    """Names/aliases and the voice profile may belong to one person only (lock held)."""
+    taken = {}
+    for other in people.values():
+        if other["id"] == person["id"]:
+            continue
+        for name in person_names(other):
+            taken[name.casefold()] = other["name"]
+        if person.get("speaker_profile_id") and other.get("speaker_profile_id") == person["speaker_profile_id"]:
+            raise PeopleError(f"이 화자 프로필은 이미 {other['name']}에게 연결되어 있습니다.")
+    for name in person_names(person):
+        if name.casefold() in taken:
+            raise PeopleError(f"'{name}'은(는) 이미 {taken[name.casefold()]}의 이름 또는 별칭입니다.")
+
+
+def list_people() -> List[Dict[str, Any]]:
+    return sorted(_load().values(), key=lambda person: person["name"].casefold())
+
+
+def get_person(person_id: str) -> Dict[str, Any]:
+    person = _load().get(person_id)
+    if person is None:
+        raise PersonNotFound(f"인물을 찾을 수 없습니다: {person_id}")
+    return person
+
+
+def create_person(payload: Dict[str, Any]) -> Dict[str, Any]:
+    if "name" not in payload:
+        raise PeopleError("이름(name)이 필요합니다.")
+    now = datetime.now().isoformat()
+    person = {"id": str(uuid.uuid4()), "name": None, "aliases": [], "email": "", "role": "", "note": "",
+              "speaker_profile_id": None, "created_at": now, "updated_at": now}
+    _apply_fields(person, payload)
+    with _people_lock:
+        people = _load()
+        _check_unique(person, people)
+        people[person["id"]] = person
+        _save(people)
+    return person
+
+
+def update_person(person_id: str, payload: Dict[str, Any]) -> Dict[str, Any]:
+    with _people_lock:
+        people = _load()
+        person = people.get(person_id)
+        if person is None:
+            raise PersonNotFound(f"인물을 찾을 수 없습니다: {person_id}")
+        _apply_fields(person, payload)
+        _check_unique(person, people)
+        person["updated_at"] = datetime.now().isoformat()
+        _save(people)
+    return person
+
+
+def delete_person(person_id: str) -> bool:
+    """Remove a person from the directory; names already stored on records stay."""
+    with _people_lock:
+        people = _load()
+        if people.pop(person_id, None) is None:
+            return False
+        _save(people)
+    return True
+
+
+def find_person(name: Optional[str], people: Optional[Iterable[Dict[str, Any]]] = None) -> Optional[Dict[str, Any]]:
+    """The person whose name or alias is ``name`` (case-insensitive)."""
+    wanted = re.sub(r"\s+", " ", name or "").strip().casefold()
+    if not wanted:
+        return None
+    people = list_people() if people is None else people
+    return next((p for p in people if wanted in (n.casefold() for n in person_names(p))), None)
+
+
+def canonical_speaker_names(names: Dict[str, str]) -> Dict[str, str]:
+    """``{label: name}`` with aliases replaced by the person's name (unknown names and "" kept)."""
+    people = list_people()
+    canonical = {}
+    for label, name in names.items():
+        person = find_person(name, people) if name else None
+        canonical[label] = person["name"] if person else name
+    return canonical
+
+
+def person_for_profile(profile_id: str) -> Optional[Dict[str, Any]]:
+    return next((p for p in list_people() if profile_id and p.get("speaker_profile_id") == profile_id), None)
+
+
+def people_prompt_terms(attendees: Iterable[str] = ()) -> List[str]:
+    """STT prompt terms: names and aliases of the given attendees first, then every other name."""
+    people = list_people()
+    attending = [p for p in (find_person(name, people) for name in attendees) if p]
+    terms = [name for person in attending for name in person_names(person)]
+    return terms + [p["name"] for p in people if p not in attending]
+
+
+def people_in_text(text: str, names: Iterable[str] = ()) -> List[Dict[str, Any]]:
+    """People attending (``names``) or named in ``text``, in directory order."""
+    people = list_people()
+    present = {p["id"] for p in (find_person(name, people) for name in names) if p}
+    return [p for p in people
+            if p["id"] in present or any(_mention_pattern(n).search(text or "") for n in person_names(p))]
+
+
+def render_people_context(people: List[Dict[str, Any]]) -> str:
+    """One line per person for the summary prompt: name, aliases and role."""
+    lines = []
+    for person in people:
+        details = []
+        if person.get("aliases"):
+            details.append(f"별칭: {', '.join(person['aliases'])}")
+        if person.get("role"):
+            details.append(f"역할: {person['role']}")
+        lines.append(f"- {person['name']}" + (f" ({'; '.join(details)})" if details else ""))
+    return "\n".join(lines)
+
+
+def _mention_pattern(name: str) -> "re.Pattern[str]":
+    # 한국어는 조사가 붙으므로("민수가") 앞쪽 경계만 보고, 영문 이름은 뒤쪽 경계도 확인
+    tail = r"(?![A-Za-z0-9])" if name[-1:].isascii() else ""
+    return re.compile(rf"(?<!\w){re.escape(name)}{tail}", re.IGNORECASE)
+
+
+def find_mentions(person: Dict[str, Any], document: Optional[Dict[str, Any]],
+                  speaker_names: Optional[Dict[str, str]] = None) -> Dict[str, Any]:
+    """Segments of a segments document in which ``person`` spoke or was named.
+
+    Returns ``{"spoke": [{"start", "end"}], "spoke_seconds", "mentions":
+    [{"start", "end", "speaker", "name", "text"}]}``; a speaker label counts
+    as the person when its assigned name is one of the person's names.
+    """
+    names = {name.casefold() for name in person_names(person)}
+    labels = {label for label, name in (speaker_names or {}).items() if (name or "").casefold() in names}
+    patterns = [(name, _mention_pattern(name)) for name in person_names(person)]
+    spoke, mentions = [], []
+    for segment in (document or {}).get("segments") or []:
+        start, end = segment.get("start"), segment.get("end")
+        if segment.get("speaker") in labels:
+            spoke.append({"start": start, "end": end})
+        text = segment.get("text") or ""
+        matched = next((name for name, pattern in patterns if pattern.search(text)), None)
+        if matched:
+            speaker = segment.get("speaker")
+            mentions.append({
+                "start": start,
+                "end": end,
+                "speaker": (speaker_names or {}).get(speaker, speaker) if speaker else None,
+                "name": matched,
+                "text": text.strip()[:MENTION_TEXT_MAX_CHARS],
+            })
+    spoke_seconds = sum(max(0.0, (s["end"] or 0) - (s["start"] or 0)) for s in spoke
+                        if isinstance(s["start"], (int, float)) and isinstance(s["end"], (int, float)))
+    return {"spoke": spoke, "spoke_seconds": round(spoke_seconds, 2), "mentions": mentions}
@@ -0,0 +1,241 @@
+"""Per-language STT postprocessing rule packs.
+
+A rule pack lists phrases Whisper tends to hallucinate (``discard_phrases``,
+segments equal to one are dropped) and ``filler_words`` (segments consisting
+of only a filler are dropped when filler filtering is on)::
+
+    {"language": "ko", "discard_phrases": ["자막을 사용하였습니다."], "filler_words": ["음", "어"]}
+
+Built-in packs ship in ``sttEngine/rule_packs/{lang}.json``. Users can add
+packs or extend the built-in ones with ``{lang}.json``/``{lang}.toml`` files
+in ``POSTPROCESS_RULES_DIR`` (default ``DB/postprocess_rules``); both are
+merged. The pack is chosen by the detected language, falling back to
+``POSTPROCESS_DEFAULT_LANGUAGE`` when none is known. Comparisons ignore case
+and trailing punctuation.
+"""
+
+from __future__ import annotations
+
+import json
+import os
+import re
+import threading
+from dataclasses import dataclass, field
+from pathlib import Path
+from typing import Any, Dict, FrozenSet, List, Optional, Tuple
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value, get_db_base_path
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value, get_db_base_path  # type: ignore
+
+try:
+    import tomllib
+except ImportError:  # pragma: no cover - Python < 3.11
+    tomllib = None
+
+BUILTIN_RULES_DIR = Path(__file__).parent / "rule_packs"
+DEFAULT_LANGUAGE = get_config_value("POSTPROCESS_DEFAULT_LANGUAGE", "ko", str)
+RULE_LISTS = ("discard_phrases", "filler_words")
+MAX_PHRASE_LENGTH = 200
+
+_LANGUAGE_PATTERN = re.compile(r"^[a-z]{2,3}(-[a-z0-9]+)?$")
+_TRAILING_PUNCT = ".,!?…~。、！？ "
+
+
+class RulePackError(ValueError):
+    """Raised for invalid languages, rule files or phrases."""
+
+
+def get_user_rules_dir() -> Path:
+    configured = os.environ.get("POSTPROCESS_RULES_DIR")
+    return Path(configured) if configured else get_db_base_path() / "postprocess_rules"
+
+
+def normalize_phrase(text: str) -> str:
+    """Comparison key: collapsed whitespace, lower case, no trailing punctuation."""
+    return " ".join(str(text).split()).lower().rstrip(_TRAILING_PUNCT)
+
+
+def normalize_language(language: Optional[str]) -> str:
+    language = (language or DEFAULT_LANGUAGE or "").strip().lower().replace("_", "-")
+    if not _LANGUAGE_PATTERN.match(language):
+        raise RulePackError(f"잘못된 언어 코드입니다: {language!r}")
+    return language
+
+
+@dataclass(frozen=True)
+class RulePack:
+    language: str
+    discard_phrases: FrozenSet[str] = frozenset()
+    filler_words: FrozenSet[str] = frozenset()
+    sources: Tuple[str, ...] = field(default_factory=tuple)
+
+    def is_discard_phrase(self, text: str) -> bool:
+        return normalize_phrase(text) in self.discard_phrases
+
+    def is_filler(self, text: str) -> bool:
+        return normalize_phrase(text) in self.filler_words
+
+
+def _read_rule_file(path: Path) -> Dict[str, Any]:
+    try:
+        if path.suffix == ".toml":
+            if tomllib is None:
+                raise RulePackError(f"{path.name}: TOML 규칙 파일은 Python 3.11 이상에서 지원됩니다.")
+            with open(path, "rb") as f:
+                data = tomllib.load(f)
+        else:
+            with open(path, "r", encoding="utf-8") as f:
+                data = json.load(f)
+    except (OSError, ValueError) as exc:
+        raise RulePackError(f"{path.name}: 규칙 파일을 읽을 수 없습니다 ({exc})") from exc
+    if not isinstance(data, dict):
+        raise RulePackError(f"{path.name}: 규칙 파일은 객체여야 합니다.")
+    for key in RULE_LISTS:
+        values = data.get(key, [])
+        if not isinstance(values, list) or not all(isinstance(v, str) for v in values):
+            raise RulePackError(f"{path.name}: {key}는 문자열 목록이어야 합니다.")
+    return data
+
+
+def _rule_files(language: str) -> List[Path]:
+    user_dir = get_user_rules_dir()
+    candidates = [
+        BUILTIN_RULES_DIR / f"{language}.json",
+        user_dir / f"{language}.json",
+        user_dir / f"{language}.toml",
+    ]
+    return [path for path in candidates if path.exists()]
+
+
+_cache_lock = threading.Lock()
+_cache: Dict[str, Tuple[Tuple, RulePack, Dict[str, List[str]]]] = {}
+
+
+def _load(language: str) -> Tuple[RulePack, Dict[str, List[str]]]:
+    files = _rule_files(language)
+    signature = tuple((str(path), path.stat().st_mtime_ns) for path in files)
+    with _cache_lock:
+        cached = _cache.get(language)
+        if cached and cached[0] == signature:
+            return cached[1], cached[2]
+
+    originals: Dict[str, List[str]] = {key: [] for key in RULE_LISTS}
+    for path in files:
+        try:
+            data = _read_rule_file(path)
+        except RulePackError as exc:
+            print(f"경고: 후처리 규칙 파일을 건너뜁니다 - {exc}")
+            continue
+        for key in RULE_LISTS:
+            for value in data.get(key, []):
+                if value.strip() and value not in originals[key]:
+                    originals[key].append(value)
+
+    pack = RulePack(
+        language=language,
+        discard_phrases=frozenset(normalize_phrase(p) for p in originals["discard_phrases"]),
+        filler_words=frozenset(normalize_phrase(w) for w in originals["filler_words"]),
+        sources=tuple(str(path) for path in files),
+    )
+    with _cache_lock:
+        _cache[language] = (signature, pack, originals)
+    return pack, originals
+
+
+def get_rule_pack(language: Optional[str] = None) -> RulePack:
+    """Rule pack for a detected language (default language when unknown)."""
+    try:
+        language = normalize_language(language)
+    except RulePackError:
+        language = normalize_language(None)
+    # "en-us" 같은 지역 코드는 기본 언어 규칙을 사용
+    base = language.split("-")[0]
+    return _load(base)[0]
+
+
+def discard_phrase_list(language: Optional[str] = None) -> List[str]:
+    """Original (unnormalized) discard phrases, for removing them from raw text."""
+    return _load(get_rule_pack(language).language)[1]["discard_phrases"]
+
+
+def available_languages() -> List[str]:
+    languages = set()
+    for directory in (BUILTIN_RULES_DIR, get_user_rules_dir()):
+        if directory.exists():
+            languages.update(p.stem for p in directory.iterdir() if p.suffix in (".json", ".toml"))
+    return sorted(languages)
+
+
+def describe_rule_pack(language: str) -> Dict[str, Any]:
+    language = normalize_language(language).split("-")[0]
+    pack, originals = _load(language)
+    custom = _read_custom(language)
+    return {
+        "language": language,
+        "default_language": DEFAULT_LANGUAGE,
+        "sources": list(pack.sources),
+        "discard_phrases": originals["discard_phrases"],
+        "filler_words": originals["filler_words"],
+        "custom": {key: custom.get(key, []) for key in RULE_LISTS},
+    }
+
+
+def _custom_path(language: str) -> Path:
+    return get_user_rules_dir() / f"{language}.json"
+
+
+def _read_custom(language: str) -> Dict[str, Any]:
+    path = _custom_path(language)
+    if not path.exists():
+        return {"language": language}
+    return _read_rule_file(path)
+
+
+def _clean_phrases(values: Any, key: str) -> List[str]:
+    if values is None:
+        return []
+    if not isinstance(values, list) or not all(isinstance(v, str) for v in values):
+        raise RulePackError(f"{key}는 문자열 목록이어야 합니다.")
+    cleaned = [" ".join(v.split()) for v in values if v.strip()]
+    too_long = [v for v in cleaned if len(v) > MAX_PHRASE_LENGTH]
+    if too_long:
+        raise RulePackError(f"{key}: 각 문구는 {MAX_PHRASE_LENGTH}자 이하여야 합니다.")
+    return cleaned
+
+
+def update_custom_rules(language: str, add: Optional[Dict[str, Any]] = None,
+                        remove: Optional[Dict[str, Any]] = None) -> Dict[str, Any]:
+    """Add/remove user phrases in ``{POSTPROCESS_RULES_DIR}/{lang}.json``.
+
+    ``add``/``remove`` map ``discard_phrases``/``filler_words`` to lists.
+    Built-in phrases cannot be removed this way.
+    """
+    language = normalize_language(language).split("-")[0]
+    add, remove = add or {}, remove or {}
+    unknown = [key for key in list(add) + list(remove) if key not in RULE_LISTS]
+    if unknown:
+        raise RulePackError(f"알 수 없는 규칙 종류: {', '.join(unknown)} (사용 가능: {', '.join(RULE_LISTS)})")
+
+    with _cache_lock:
+        custom = _read_custom(language)
+        for key in RULE_LISTS:
+            current = list(custom.get(key, []))
+            removals = {normalize_phrase(v) for v in _clean_phrases(remove.get(key), key)}
+            current = [v for v in current if normalize_phrase(v) not in removals]
+            known = {normalize_phrase(v) for v in current}
+            for value in _clean_phrases(add.get(key), key):
+                if normalize_phrase(value) not in known:
+                    current.append(value)
+                    known.add(normalize_phrase(value))
+            custom[key] = current
+        custom["language"] = language
+
+        path = _custom_path(language)
+        path.parent.mkdir(parents=True, exist_ok=True)
+        tmp_path = path.with_name(f"{path.name}.tmp")
+        with open(tmp_path, "w", encoding="utf-8") as f:
+            json.dump(custom, f, ensure_ascii=False, indent=2)
+        tmp_path.replace(path)
+    return describe_rule_pack(language)
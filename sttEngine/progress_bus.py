@@ -0,0 +1,86 @@
+"""In-process event bus for task progress.
+
+``update_task_progress`` publishes every progress message here. Subscribers
+(the WebSocket broadcaster) are called for each event, and HTTP clients
+that cannot use WebSockets long-poll with :meth:`ProgressBus.wait`::
+
+    GET /tasks/{id}/wait?since=<seq>&timeout=25
+
+Each event carries a global, monotonically increasing ``seq``; a client
+passes the last ``seq`` it saw and gets the next change (or the final
+``done`` event) as soon as it is published. Finished tasks are remembered
+for ``retention_seconds`` so late pollers still learn the outcome.
+
+Tasks waiting for a Whisper state carry ``queue`` (``queue_position`` and
+``estimated_start_time``) on their events until they start.
+"""
+
+from __future__ import annotations
+
+import threading
+import time
+from typing import Any, Callable, Dict, List, Optional
+
+Event = Dict[str, Any]
+
+
+class ProgressBus:
+    def __init__(self, retention_seconds: float = 300):
+        self.retention_seconds = retention_seconds
+        self._condition = threading.Condition()
+        self._seq = 0
+        self._latest: Dict[str, Event] = {}
+        self._subscribers: List[Callable[[Event], None]] = []
+
+    def subscribe(self, callback: Callable[[Event], None]) -> None:
+        self._subscribers.append(callback)
+
+    def publish(self, task_id: str, message: Optional[str], done: bool = False,
+                queue: Optional[Dict[str, Any]] = None) -> Event:
+        with self._condition:
+            self._seq += 1
+            event = {
+                "task_id": task_id,
+                "seq": self._seq,
+                "message": message,
+                "timestamp": time.time(),
+                "done": done,
+                "queue": queue,
+            }
+            self._latest[task_id] = event
+            self._prune()
+            self._condition.notify_all()
+        for callback in list(self._subscribers):
+            try:
+                callback(event)
+            except Exception as exc:  # pragma: no cover - a broken subscriber must not stop tasks
+                print(f"진행 상황 구독자 오류: {exc}")
+        return event
+
+    def finish(self, task_id: str) -> Event:
+        """Mark a task as finished, keeping its last message."""
+        with self._condition:
+            last = self._latest.get(task_id) or {}
+        return self.publish(task_id, last.get("message"), done=True)
+
+    def latest(self, task_id: str) -> Optional[Event]:
+        with self._condition:
+            return self._latest.get(task_id)
+
+    def wait(self, task_id: str, since: int = 0, timeout: float = 25) -> Optional[Event]:
+        """Block until ``task_id`` has an event newer than ``since`` (or timeout)."""
+        def changed():
+            event = self._latest.get(task_id)
+            return event is not None and event["seq"] > since
+
+        with self._condition:
+            if self._condition.wait_for(changed, timeout=timeout):
+                return self._latest[task_id]
+        return None
+
+    def _prune(self) -> None:
+        cutoff = time.time() - self.retention_seconds
+        expired = [task_id for task_id, event in self._latest.items()
+                   if event["done"] and event["timestamp"] < cutoff]
+        for task_id in expired:
+            del self._latest[task_id]
@@ -0,0 +1,138 @@
+"""Public read-only gallery of explicitly published records.
+
+Podcasters and teachers want to share transcripts from the same tool they
+record with. With ``PUBLIC_GALLERY_ENABLED=true`` the server exposes
+records marked ``published`` (``POST /record/{id}/publish``) under
+``/public`` without an API token, even when ``API_AUTH_REQUIRED`` is on:
+
+* ``GET /public`` — HTML page listing the published records
+* ``GET /public/records`` — the same list as JSON
+* ``GET /public/records/{id}`` — summary, transcript and timed segments
+  (an HTML page when the browser asks for ``text/html``)
+* ``GET /public/records/{id}/audio`` — the recording, with HTTP range
+  requests so players can seek (``PUBLIC_GALLERY_AUDIO=false`` disables it)
+
+Only the fields in :func:`public_view` leave the server; file paths, tags
+(which may hold email senders), hashes and processing metadata do not.
+"""
+
+from __future__ import annotations
+
+import html
+import re
+from typing import Any, Dict, List, Optional, Tuple
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+PUBLIC_GALLERY_ENABLED = get_config_value("PUBLIC_GALLERY_ENABLED", False, bool)
+PUBLIC_GALLERY_TITLE = get_config_value("PUBLIC_GALLERY_TITLE", "RecordRoute", str)
+PUBLIC_GALLERY_AUDIO = get_config_value("PUBLIC_GALLERY_AUDIO", True, bool)
+
+_PUBLIC_ROUTE = re.compile(r"^/public(/|$)")
+_RANGE = re.compile(r"^bytes=(\d*)-(\d*)$")
+
+
+class RangeNotSatisfiable(ValueError):
+    """Raised for a ``Range`` header outside the file."""
+
+
+def is_public_request(method: str, path: str) -> bool:
+    """Whether the request may skip API token checks (gallery on, read-only ``/public`` route)."""
+    return PUBLIC_GALLERY_ENABLED and method == "GET" and bool(_PUBLIC_ROUTE.match(path.split("?", 1)[0]))
+
+
+def is_published(record: Dict[str, Any]) -> bool:
+    return bool(record.get("published")) and not record.get("deleted")
+
+
+def published_records(history: List[Dict[str, Any]]) -> List[Dict[str, Any]]:
+    """Published records, most recently published first."""
+    records = [record for record in history if is_published(record)]
+    return sorted(records, key=lambda record: record.get("published_at") or "", reverse=True)
+
+
+def public_view(record: Dict[str, Any]) -> Dict[str, Any]:
+    """Fields of a record that may be shown to anyone."""
+    return {
+        "id": record["id"],
+        "title": record.get("public_title") or record.get("filename"),
+        "one_line_summary": record.get("title_summary") or "",
+        "duration": record.get("duration"),
+        "created_at": record.get("timestamp"),
+        "published_at": record.get("published_at"),
+        "has_audio": PUBLIC_GALLERY_AUDIO and record.get("file_type") == "audio",
+        "has_summary": bool((record.get("completed_tasks") or {}).get("summary")),
+    }
+
+
+def public_segments(document: Optional[Dict[str, Any]], speaker_names: Dict[str, str]) -> List[Dict[str, Any]]:
+    """Timed transcript segments with speaker labels replaced by assigned names."""
+    segments = []
+    for segment in (document or {}).get("segments") or []:
+        entry = {"start": segment.get("start"), "end": segment.get("end"), "text": segment.get("text", "")}
+        if segment.get("speaker"):
+            entry["speaker"] = speaker_names.get(segment["speaker"], segment["speaker"])
+        segments.append(entry)
+    return segments
+
+
+def parse_byte_range(header: Optional[str], size: int) -> Optional[Tuple[int, int]]:
+    """Inclusive ``(start, end)`` of a single-range ``Range`` header, ``None`` for the whole file."""
+    match = _RANGE.match((header or "").strip())
+    if not match or not (match.group(1) or match.group(2)):
+        return None
+    if match.group(1):
+        start = int(match.group(1))
+        end = min(int(match.group(2)), size - 1) if match.group(2) else size - 1
+    else:  # bytes=-500: 마지막 500바이트
+        start = max(0, size - int(match.group(2)))
+        end = size - 1
+    if start >= size or start > end:
+        raise RangeNotSatisfiable(f"bytes */{size}")
+    return start, end
+
+
+def _page(title: str, body: str) -> str:
+    return (
+        '<!DOCTYPE html><html lang="ko"><head><meta charset="utf-8">'
+        '<meta name="viewport" content="width=device-width, initial-scale=1">'
+        f"<title>{html.escape(title)}</title>"
+        "<style>body{font-family:system-ui,sans-serif;max-width:48rem;margin:2rem auto;padding:0 1rem}"
+        "ul{list-style:none;padding:0}li{border-bottom:1px solid #ddd;padding:1rem 0}"
+        ".meta{color:#666;font-size:.9rem}audio{width:100%}pre{white-space:pre-wrap;font-family:inherit}</style>"
+        f"</head><body>{body}</body></html>"
+    )
+
+
+def _record_header(record: Dict[str, Any], link: bool) -> str:
+    record_id = html.escape(record["id"])
+    title = html.escape(record["title"] or "")
+    parts = [f'<h2><a href="/public/records/{record_id}">{title}</a></h2>' if link else f"<h1>{title}</h1>"]
+    meta = " · ".join(html.escape(value) for value in (record.get("duration"), (record.get("published_at") or "")[:10]) if value)
+    if meta:
+        parts.append(f'<p class="meta">{meta}</p>')
+    if record["one_line_summary"]:
+        parts.append(f"<p>{html.escape(record['one_line_summary'])}</p>")
+    if record["has_audio"]:
+        parts.append(f'<audio controls preload="none" src="/public/records/{record_id}/audio"></audio>')
+    return "".join(parts)
+
+
+def render_page(records: List[Dict[str, Any]]) -> str:
+    """Minimal HTML gallery: title, one-line summary and player per record."""
+    items = "".join(f"<li>{_record_header(record, link=True)}</li>" for record in records)
+    body = f"<ul>{items}</ul>" if items else "<p>공개된 기록이 없습니다.</p>"
+    return _page(PUBLIC_GALLERY_TITLE, f"<h1>{html.escape(PUBLIC_GALLERY_TITLE)}</h1>{body}")
+
+
+def render_record_page(record: Dict[str, Any]) -> str:
+    """HTML page of one published record (player, summary, transcript)."""
+    parts = [f'<p><a href="/public">← {html.escape(PUBLIC_GALLERY_TITLE)}</a></p>', _record_header(record, link=False)]
+    if record.get("summary"):
+        parts.append(f"<h2>요약</h2><pre>{html.escape(record['summary'])}</pre>")
+    if record.get("transcript"):
+        parts.append(f"<h2>전사</h2><pre>{html.escape(record['transcript'])}</pre>")
+    return _page(record["title"] or PUBLIC_GALLERY_TITLE, "".join(parts))
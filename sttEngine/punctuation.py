@@ -0,0 +1,112 @@
+"""Punctuation and casing restoration for under-punctuated transcripts.
+
+Whisper often returns Korean dictation and fast English speech as long
+runs without sentence marks, which is hard to read and splits badly into
+summary chunks. With ``PUNCTUATION_RESTORE_ENABLED`` the STT step sends
+the transcript segments for languages in ``PUNCTUATION_LANGUAGES`` to a
+local Ollama model (``PUNCTUATION_MODEL``, the summary model by default) in
+numbered batches and asks it to fix punctuation and casing only.
+
+Every corrected line is checked against the original: if anything but
+punctuation, casing or spacing changed, the original line is kept (and
+counted as ``rejected``), so the model cannot rewrite what was said. The
+restored text replaces the transcript markdown and the clean ``segments``;
+the ``verbatim`` view keeps Whisper's raw text. ``POST /record/{id}/punctuate``
+runs the same pass on an existing transcript.
+"""
+
+from __future__ import annotations
+
+import re
+from typing import Any, Callable, Dict, List, Optional, Tuple
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+PUNCTUATION_RESTORE_ENABLED = get_config_value("PUNCTUATION_RESTORE_ENABLED", False, bool)
+PUNCTUATION_MODEL = get_config_value("PUNCTUATION_MODEL", "", str).strip()
+PUNCTUATION_LANGUAGES = get_config_value("PUNCTUATION_LANGUAGES", "ko,en", str)
+PUNCTUATION_BATCH_CHARS = max(200, get_config_value("PUNCTUATION_BATCH_CHARS", 1500, int))
+
+PUNCTUATION_PROMPT = (
+    "다음은 음성 인식 결과를 줄마다 번호를 붙인 목록입니다. 각 줄의 문장 부호(마침표, 쉼표, 물음표, 느낌표)와 "
+    "영어 대소문자만 바로잡아 주세요.\n"
+    "단어를 추가, 삭제, 변경하거나 줄을 합치거나 나누지 마세요. 설명 없이 같은 번호 형식(\"번호: 내용\")으로 "
+    "모든 줄을 출력하세요.\n\n{lines}"
+)
+
+_NUMBERED_LINE = re.compile(r"^\s*(\d+)\s*[:.)\t]\s?(.*)$")
+
+
+class PunctuationError(RuntimeError):
+    """Raised when no batch could be restored."""
+
+
+def should_restore(language: Optional[str]) -> bool:
+    languages = {code.strip().lower() for code in PUNCTUATION_LANGUAGES.split(",") if code.strip()}
+    return bool(language) and language.lower() in languages
+
+
+def _skeleton(text: str) -> str:
+    """Text without punctuation, spacing and case — what restoration must not change."""
+    return re.sub(r"[\W_]+", "", text).lower()
+
+
+def _batches(texts: List[str], max_chars: int) -> List[List[int]]:
+    batches, current, size = [], [], 0
+    for index, text in enumerate(texts):
+        if current and size + len(text) > max_chars:
+            batches.append(current)
+            current, size = [], 0
+        current.append(index)
+        size += len(text) + 8
+    if current:
+        batches.append(current)
+    return batches
+
+
+def parse_numbered_lines(response: str) -> Dict[int, str]:
+    lines = {}
+    for line in response.splitlines():
+        match = _NUMBERED_LINE.match(line)
+        if match:
+            lines.setdefault(int(match.group(1)), match.group(2).strip())
+    return lines
+
+
+def restore_texts(texts: List[str], generate: Callable[[str], str],
+                  batch_chars: int = PUNCTUATION_BATCH_CHARS,
+                  progress: Optional[Callable[[int, int], None]] = None) -> Tuple[List[str], Dict[str, Any]]:
+    """Restored copy of ``texts`` plus ``{"changed", "rejected", "batches", "failed_batches"}``.
+
+    ``generate(prompt)`` returns the model's answer.
+    """
+    restored = list(texts)
+    stats = {"changed": 0, "rejected": 0, "batches": 0, "failed_batches": 0}
+    batches = _batches(texts, batch_chars)
+    last_error = None
+    for number, batch in enumerate(batches, start=1):
+        if progress:
+            progress(number, len(batches))
+        stats["batches"] += 1
+        lines = "\n".join(f"{position}: {texts[index]}" for position, index in enumerate(batch, start=1))
+        try:
+            answer = parse_numbered_lines(generate(PUNCTUATION_PROMPT.replace("{lines}", lines)))
+        except Exception as exc:
+            stats["failed_batches"] += 1
+            last_error = exc
+            continue
+        for position, index in enumerate(batch, start=1):
+            candidate = answer.get(position)
+            if not candidate or candidate == texts[index]:
+                continue
+            if _skeleton(candidate) != _skeleton(texts[index]):
+                stats["rejected"] += 1
+                continue
+            restored[index] = candidate
+            stats["changed"] += 1
+    if batches and stats["failed_batches"] == len(batches):
+        raise PunctuationError(f"문장 부호 복원 실패: {last_error}")
+    return restored, stats
@@ -0,0 +1,344 @@
+"""Directory layout for uploaded sources and generated artifacts.
+
+Two layouts are supported, selected with ``RECORD_LAYOUT`` in ``.env``:
+
+``legacy`` (default)::
+
+    DB/uploads/{folder}/원본파일
+    DB/whisper_output/{folder}/{stem}.md, {stem}.summary.md, ...
+
+``per_record``::
+
+    DB/records/{folder}/source/원본파일
+    DB/records/{folder}/{stem}.md, {stem}.segments.json, {stem}.summary.md, ...
+
+``{folder}`` is the upload folder name stored as ``folder_name`` in the
+history record. Deleted records mirror the same structure under
+``DB/deleted/``.
+
+Switching an existing database to ``per_record`` requires moving the files
+once; run ``python record_layout.py migrate`` (add ``--dry-run`` to preview).
+The migration also rewrites paths stored in the upload history, the file
+registry and the vector index.
+"""
+
+from __future__ import annotations
+
+import argparse
+import json
+import shutil
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import DB_ALIAS, get_config_value, get_db_base_path
+    from .history_store import open_history_store
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import DB_ALIAS, get_config_value, get_db_base_path  # type: ignore
+    from history_store import open_history_store  # type: ignore
+
+RECORD_LAYOUTS = ("legacy", "per_record")
+SOURCE_SUBDIR = "source"
+
+
+class RecordLayout:
+    """Resolve per-record directories for a given layout and DB folder."""
+
+    def __init__(self, db_base: Path, layout: str = "legacy"):
+        if layout not in RECORD_LAYOUTS:
+            raise ValueError(f"알 수 없는 RECORD_LAYOUT: {layout} (지원: {', '.join(RECORD_LAYOUTS)})")
+        self.db_base = db_base
+        self.layout = layout
+        deleted = db_base / "deleted"
+        if layout == "per_record":
+            self.upload_root = db_base / "records"
+            self.output_root = db_base / "records"
+            self.deleted_upload_root = deleted / "records"
+            self.deleted_output_root = deleted / "records"
+        else:
+            self.upload_root = db_base / "uploads"
+            self.output_root = db_base / "whisper_output"
+            self.deleted_upload_root = deleted / "uploads"
+            self.deleted_output_root = deleted / "whisper_output"
+
+    @property
+    def is_per_record(self) -> bool:
+        return self.layout == "per_record"
+
+    def upload_dir(self, folder: str) -> Path:
+        if self.is_per_record:
+            return self.upload_root / folder / SOURCE_SUBDIR
+        return self.upload_root / folder
+
+    def output_dir(self, folder: str) -> Path:
+        return self.output_root / folder
+
+    def deleted_upload_dir(self, folder: str) -> Path:
+        if self.is_per_record:
+            return self.deleted_upload_root / folder / SOURCE_SUBDIR
+        return self.deleted_upload_root / folder
+
+    def deleted_output_dir(self, folder: str) -> Path:
+        return self.deleted_output_root / folder
+
+    def remove_outputs(self, folder: str) -> None:
+        """Delete generated artifacts of a record, keeping the uploaded source."""
+        output_dir = self.output_dir(folder)
+        if not output_dir.exists():
+            return
+        if not self.is_per_record:
+            shutil.rmtree(output_dir)
+            return
+        for child in output_dir.iterdir():
+            if child.name == SOURCE_SUBDIR:
+                continue
+            if child.is_dir():
+                shutil.rmtree(child)
+            else:
+                child.unlink()
+
+    def folder_for_path(self, path: Path) -> str:
+        """Return the record folder a source or artifact path belongs to."""
+        resolved = path.resolve()
+        for root in (self.upload_root, self.output_root):
+            try:
+                relative = resolved.relative_to(root.resolve())
+            except ValueError:
+                continue
+            if relative.parts:
+                return relative.parts[0]
+        return path.parent.name
+
+    def directories(self) -> List[Path]:
+        """Return the root directories that must exist at startup."""
+        return sorted({
+            self.upload_root,
+            self.output_root,
+            self.deleted_upload_root,
+            self.deleted_output_root,
+        })
+
+
+def get_record_layout(db_base: Optional[Path] = None) -> RecordLayout:
+    """Return the layout configured by ``RECORD_LAYOUT`` (default ``legacy``)."""
+    layout = str(get_config_value("RECORD_LAYOUT", "legacy")).strip().lower() or "legacy"
+    if layout not in RECORD_LAYOUTS:
+        print(f"알 수 없는 RECORD_LAYOUT '{layout}', legacy 레이아웃을 사용합니다.")
+        layout = "legacy"
+    return RecordLayout(db_base or get_db_base_path(), layout)
+
+
+# ---------------------------------------------------------------------------
+# legacy → per_record 마이그레이션
+# ---------------------------------------------------------------------------
+
+# (기존 DB 상대 경로 접두사, 새 접두사). 폴더명 뒤에 붙는 하위 경로는 유지된다.
+_PREFIX_MAP = (
+    ("deleted/uploads/", "deleted/records/", SOURCE_SUBDIR),
+    ("deleted/whisper_output/", "deleted/records/", None),
+    ("uploads/", "records/", SOURCE_SUBDIR),
+    ("whisper_output/", "records/", None),
+)
+
+
+def _remap_relative(relative: str) -> Optional[str]:
+    """Map a DB-relative legacy path to its per-record location."""
+    for old_prefix, new_prefix, subdir in _PREFIX_MAP:
+        if not relative.startswith(old_prefix):
+            continue
+        rest = relative[len(old_prefix):]
+        folder, _, tail = rest.partition("/")
+        if not folder:
+            return None
+        parts = [new_prefix + folder]
+        if subdir:
+            parts.append(subdir)
+        if tail:
+            parts.append(tail)
+        return "/".join(parts)
+    return None
+
+
+def remap_legacy_path(path_str: str, db_base: Path) -> Optional[str]:
+    """Remap a stored path (``DB/...`` alias or absolute) to the per-record layout.
+
+    Returns ``None`` when the path is not part of the legacy upload/output tree.
+    The result keeps the input style: alias paths stay alias paths and
+    absolute paths stay absolute.
+    """
+    if not isinstance(path_str, str) or not path_str:
+        return None
+    normalized = path_str.replace("\\", "/")
+    if normalized.startswith(f"{DB_ALIAS}/"):
+        remapped = _remap_relative(normalized[len(DB_ALIAS) + 1:])
+        return f"{DB_ALIAS}/{remapped}" if remapped else None
+
+    path_obj = Path(path_str)
+    if not path_obj.is_absolute():
+        return None
+    try:
+        relative = path_obj.resolve().relative_to(db_base.resolve()).as_posix()
+    except ValueError:
+        return None
+    remapped = _remap_relative(relative)
+    return str(db_base.resolve() / remapped) if remapped else None
+
+
+def _remap_nested(value: Any, db_base: Path) -> Any:
+    if isinstance(value, str):
+        return remap_legacy_path(value, db_base) or value
+    if isinstance(value, list):
+        return [_remap_nested(item, db_base) for item in value]
+    if isinstance(value, dict):
+        return {key: _remap_nested(item, db_base) for key, item in value.items()}
+    return value
+
+
+def _move_tree(source: Path, target: Path, dry_run: bool, moved: List[Dict[str, str]]) -> None:
+    """Move ``source`` to ``target``, merging into an existing directory."""
+    if not source.exists():
+        return
+    moved.append({"from": str(source), "to": str(target)})
+    if dry_run:
+        return
+    if not target.exists():
+        target.parent.mkdir(parents=True, exist_ok=True)
+        shutil.move(str(source), str(target))
+        return
+    for child in list(source.iterdir()):
+        destination = target / child.name
+        if destination.exists():
+            raise FileExistsError(f"대상 경로가 이미 존재합니다: {destination}")
+        shutil.move(str(child), str(destination))
+    source.rmdir()
+
+
+def _load_json(path: Path, default: Any) -> Any:
+    if not path.exists():
+        return default
+    with open(path, "r", encoding="utf-8") as f:
+        return json.load(f)
+
+
+def _save_json(path: Path, data: Any) -> None:
+    tmp_path = path.with_suffix(path.suffix + ".tmp")
+    with open(tmp_path, "w", encoding="utf-8") as f:
+        json.dump(data, f, ensure_ascii=False, indent=2)
+    tmp_path.replace(path)
+
+
+def migrate_to_per_record(db_base: Optional[Path] = None, dry_run: bool = False) -> Dict[str, Any]:
+    """Move legacy uploads/whisper_output folders into ``records/{folder}``.
+
+    Paths in the upload history, ``file_registry.json`` and the vector
+    index are rewritten to match. Running it again is a no-op.
+    """
+    db_base = (db_base or get_db_base_path()).resolve()
+    legacy = RecordLayout(db_base, "legacy")
+    target = RecordLayout(db_base, "per_record")
+    moved: List[Dict[str, str]] = []
+
+    # 1) 디렉터리 이동
+    for root, make_target in (
+        (legacy.upload_root, target.upload_dir),
+        (legacy.output_root, target.output_dir),
+        (legacy.deleted_upload_root, target.deleted_upload_dir),
+        (legacy.deleted_output_root, target.deleted_output_dir),
+    ):
+        if not root.exists():
+            continue
+        for folder in sorted(p for p in root.iterdir() if p.is_dir()):
+            _move_tree(folder, make_target(folder.name), dry_run, moved)
+
+    # 2) 히스토리 경로 갱신
+    history_store = open_history_store(db_base)
+    history = history_store.load()
+    history_changed = 0
+    if isinstance(history, list):
+        for record in history:
+            if not isinstance(record, dict):
+                continue
+            for field in ("file_path", "deleted_assets"):
+                if field in record:
+                    updated = _remap_nested(record[field], db_base)
+                    if updated != record[field]:
+                        record[field] = updated
+                        history_changed += 1
+
+    # 3) 파일 레지스트리 경로 갱신
+    registry_file = db_base / "file_registry.json"
+    registry = _load_json(registry_file, {})
+    registry_changed = 0
+    if isinstance(registry, dict):
+        for info in registry.values():
+            if not isinstance(info, dict):
+                continue
+            new_path = remap_legacy_path(info.get("file_path", ""), db_base)
+            if new_path:
+                info["file_path"] = new_path
+                registry_changed += 1
+
+    # 4) 벡터 색인 키 갱신 (whisper_output 기준 상대 키 → DB 기준 records/ 키)
+    index_file = db_base / "vector_store" / "index.json"
+    index = _load_json(index_file, {})
+    new_index: Dict[str, Any] = {}
+    index_changed = 0
+    if isinstance(index, dict):
+        for key, meta in index.items():
+            meta = dict(meta) if isinstance(meta, dict) else {}
+            normalized_key = key.replace("\\", "/")
+            if meta.get("base") == "whisper_output" and not Path(key).is_absolute():
+                relative = f"whisper_output/{normalized_key}"
+            elif meta.get("base") == "db" and not Path(key).is_absolute():
+                relative = normalized_key
+            else:
+                relative = None
+            remapped = _remap_relative(relative) if relative else None
+            if remapped is None and Path(key).is_absolute():
+                absolute = remap_legacy_path(key, db_base)
+                remapped = Path(absolute).relative_to(db_base).as_posix() if absolute else None
+            if meta.get("deleted_path"):
+                meta["deleted_path"] = remap_legacy_path(meta["deleted_path"], db_base) or meta["deleted_path"]
+            if remapped:
+                meta["base"] = "db"
+                new_index[remapped] = meta
+                index_changed += 1
+            else:
+                new_index[key] = meta
+
+    if not dry_run:
+        if history_changed:
+            history_store.save(history)
+        if registry_changed:
+            _save_json(registry_file, registry)
+        if index_changed:
+            _save_json(index_file, new_index)
+
+    return {
+        "dry_run": dry_run,
+        "moved_directories": moved,
+        "history_records_updated": history_changed,
+        "registry_entries_updated": registry_changed,
+        "index_entries_updated": index_changed,
+    }
+
+
+if __name__ == "__main__":
+    parser = argparse.ArgumentParser(description="Record directory layout tools")
+    subparsers = parser.add_subparsers(dest="command", required=True)
+    migrate_parser = subparsers.add_parser("migrate", help="Move legacy folders into the per-record layout")
+    migrate_parser.add_argument("--db", default=None, help="DB folder (default: DB_FOLDER_PATH or project DB)")
+    migrate_parser.add_argument("--dry-run", action="store_true", help="Only report what would be moved")
+    args = parser.parse_args()
+
+    report = migrate_to_per_record(Path(args.db) if args.db else None, dry_run=args.dry_run)
+    for item in report["moved_directories"]:
+        print(f"{'(dry-run) ' if report['dry_run'] else ''}{item['from']} → {item['to']}")
+    print(
+        f"디렉터리 {len(report['moved_directories'])}개 이동, "
+        f"히스토리 {report['history_records_updated']}건, "
+        f"레지스트리 {report['registry_entries_updated']}건, "
+        f"색인 {report['index_entries_updated']}건 경로 갱신"
+    )
+    if not report["dry_run"]:
+        print("서버 설정에서 RECORD_LAYOUT=per_record 로 변경한 뒤 재시작하세요.")
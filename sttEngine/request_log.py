@@ -0,0 +1,121 @@
+"""Structured per-request logging for the HTTP server.
+
+Each handled request emits one ``request`` line with method, path, status,
+latency, request/response sizes and the task id when the handler knows
+it. Requests slower than ``SLOW_REQUEST_THRESHOLD_MS`` are logged as
+warnings so sluggish search/history calls stand out in the log files.
+
+Example line::
+
+    [request] {"method": "GET", "path": "/search", "query": "q=...", "status": 200,
+               "latency_ms": 1532.4, "bytes_in": 0, "bytes_out": 18234, "slow": true}
+"""
+
+from __future__ import annotations
+
+import json
+import logging
+import sys
+import time
+from datetime import datetime
+from typing import Any, Dict, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+REQUEST_LOG_ENABLED = get_config_value("REQUEST_LOG_ENABLED", True, bool)
+SLOW_REQUEST_THRESHOLD_MS = get_config_value("SLOW_REQUEST_THRESHOLD_MS", 1000, int)
+
+logger = logging.getLogger("recordroute.request")
+if not logger.handlers:
+    _handler = logging.StreamHandler(sys.stdout)
+    _handler.setFormatter(logging.Formatter("[%(name)s] %(levelname)s %(message)s"))
+    logger.addHandler(_handler)
+    logger.setLevel(logging.INFO)
+    logger.propagate = False
+
+
+class _CountingWriter:
+    """Wrap the response stream and count the bytes written to it."""
+
+    def __init__(self, stream):
+        self._stream = stream
+        self.bytes_written = 0
+
+    def write(self, data) -> int:
+        written = self._stream.write(data)
+        self.bytes_written += written if isinstance(written, int) else len(data)
+        return written
+
+    def __getattr__(self, name):
+        return getattr(self._stream, name)
+
+
+class RequestLoggingMixin:
+    """Mixin for ``BaseHTTPRequestHandler`` replacing the default access log.
+
+    Handlers can attach extra fields to the current request's log line via
+    :meth:`annotate_request` (e.g. ``task_id``).
+    """
+
+    def setup(self):
+        super().setup()
+        self.wfile = _CountingWriter(self.wfile)
+
+    def handle_one_request(self):
+        self.command = None
+        self._response_status: Optional[int] = None
+        self._request_log_fields: Dict[str, Any] = {}
+        self.wfile.bytes_written = 0
+        started = time.perf_counter()
+        try:
+            super().handle_one_request()
+        finally:
+            if self.command:
+                self._emit_request_log((time.perf_counter() - started) * 1000)
+
+    def send_response(self, code, message=None):
+        self._response_status = code
+        super().send_response(code, message)
+
+    def log_request(self, code="-", size="-"):
+        # 기본 접근 로그는 구조화 로그로 대체
+        pass
+
+    def annotate_request(self, **fields: Any) -> None:
+        """Attach extra fields (skipping ``None``) to this request's log line."""
+        self._request_log_fields.update({k: v for k, v in fields.items() if v is not None})
+
+    def _emit_request_log(self, latency_ms: float) -> None:
+        if not REQUEST_LOG_ENABLED:
+            return
+
+        path, _, query = (self.path or "").partition("?")
+        try:
+            bytes_in = int(self.headers.get("Content-Length", 0)) if self.headers else 0
+        except (TypeError, ValueError):
+            bytes_in = 0
+
+        slow = SLOW_REQUEST_THRESHOLD_MS > 0 and latency_ms >= SLOW_REQUEST_THRESHOLD_MS
+        event = {
+            "ts": datetime.now().isoformat(timespec="milliseconds"),
+            "method": self.command,
+            "path": path,
+            "query": query or None,
+            "status": self._response_status,
+            "latency_ms": round(latency_ms, 1),
+            "bytes_in": bytes_in,
+            "bytes_out": self.wfile.bytes_written,
+            "client": self.client_address[0] if self.client_address else None,
+            **self._request_log_fields,
+        }
+        if slow:
+            event["slow"] = True
+        message = json.dumps({k: v for k, v in event.items() if v is not None}, ensure_ascii=False)
+
+        if slow:
+            logger.warning(message)
+        else:
+            logger.info(message)
@@ -0,0 +1,128 @@
+"""Re-transcription of one time range of an existing transcript.
+
+Runs the configured STT backend on ``[start, end)`` of a record's audio only
+— optionally with a larger model and a domain prompt — and splices the new
+segments into ``{stem}.segments.json`` and the transcript markdown, which is
+much faster than redoing a two-hour recording for one garbled minute.
+
+The slice is read with ``RETRANSCRIBE_PADDING_SECONDS`` of extra audio on
+both sides so words cut at the boundaries are still recognized; only new
+segments whose midpoint falls inside the range are kept, matching how
+:func:`segment_store.splice_segments` picks the segments it replaces.
+"""
+
+from __future__ import annotations
+
+import math
+import tempfile
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Dict, Optional, Tuple
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+    from .segment_store import load_segments, segments_path_for, splice_segments
+    from .stt_backends import SttEngine, TranscriptionOptions
+    from .workflow.transcribe import extract_audio_slice, format_transcript_lines, write_atomic
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+    from segment_store import load_segments, segments_path_for, splice_segments  # type: ignore
+    from stt_backends import SttEngine, TranscriptionOptions  # type: ignore
+    from workflow.transcribe import extract_audio_slice, format_transcript_lines, write_atomic  # type: ignore
+
+RETRANSCRIBE_MAX_SECONDS = get_config_value("RETRANSCRIBE_MAX_SECONDS", 900, float)
+RETRANSCRIBE_PADDING_SECONDS = max(0.0, get_config_value("RETRANSCRIBE_PADDING_SECONDS", 1.0, float))
+
+
+class RetranscribeError(ValueError):
+    """Raised for invalid ranges or transcripts that cannot be spliced."""
+
+
+def parse_time_range(start: Any, end: Any, duration: Optional[float] = None) -> Tuple[float, float]:
+    """Validate a ``[start, end)`` range in seconds; ``end`` is clipped to ``duration``."""
+    values = []
+    for name, value in (("start", start), ("end", end)):
+        if isinstance(value, bool) or not isinstance(value, (int, float)) or not math.isfinite(value):
+            raise RetranscribeError(f"{name}는 초 단위 숫자여야 합니다.")
+        values.append(float(value))
+    start, end = values
+    if start < 0:
+        raise RetranscribeError("start는 0 이상이어야 합니다.")
+    if duration:
+        if start >= duration:
+            raise RetranscribeError(f"start가 오디오 길이({duration:.1f}초)를 넘습니다.")
+        end = min(end, duration)
+    if end <= start:
+        raise RetranscribeError("end는 start보다 커야 합니다.")
+    if end - start > RETRANSCRIBE_MAX_SECONDS:
+        raise RetranscribeError(
+            f"한 번에 재변환할 수 있는 구간은 최대 {RETRANSCRIBE_MAX_SECONDS:.0f}초입니다. 전체를 다시 변환하세요."
+        )
+    return start, end
+
+
+def retranscribe_range(audio_path: Path, transcript_path: Path, start: float, end: float,
+                       engine: SttEngine, options: TranscriptionOptions) -> Dict[str, Any]:
+    """Transcribe ``[start, end)`` of ``audio_path`` and splice it into the transcript.
+
+    Rewrites the transcript markdown from the spliced segments and returns a
+    report with the replaced/inserted counts and the new segments.
+    """
+    segments_path = segments_path_for(transcript_path)
+    document = load_segments(segments_path)
+    if not document or not document.get("segments"):
+        raise RetranscribeError("타임스탬프 세그먼트가 없는 전사는 구간 재변환을 할 수 없습니다.")
+
+    slice_start = max(0.0, start - RETRANSCRIBE_PADDING_SECONDS)
+    slice_duration = end + RETRANSCRIBE_PADDING_SECONDS - slice_start
+    options.export_to_obsidian = False
+    options.report(f"구간 {start:.1f}~{end:.1f}초 재변환 중...")
+
+    with tempfile.TemporaryDirectory(prefix="recordroute_retranscribe_") as tmp_dir:
+        slice_path = extract_audio_slice(
+            audio_path, Path(tmp_dir) / f"{transcript_path.stem}.slice.wav", slice_start, slice_duration
+        )
+        transcription = engine.transcribe(slice_path, Path(tmp_dir) / "output", options)
+
+    def shift_into_range(segments):
+        shifted_segments = []
+        for segment in segments:
+            shifted = dict(segment, start=segment["start"] + slice_start, end=segment["end"] + slice_start)
+            if start <= (shifted["start"] + shifted["end"]) / 2 < end:
+                shifted_segments.append(shifted)
+        return shifted_segments
+
+    replacement = shift_into_range(transcription.segments)
+
+    revision = {
+        "start": start,
+        "end": end,
+        "backend": transcription.backend,
+        "model": transcription.model,
+        "prompt": options.initial_prompt or None,
+        "decoding": (transcription.decoding or {}).get("strategy"),
+        "at": datetime.now().isoformat(),
+    }
+    document = splice_segments(segments_path, replacement, start, end, revision,
+                               shift_into_range(transcription.verbatim) if transcription.verbatim else None)
+
+    # 마크다운 전사도 세그먼트 기준으로 다시 생성 (제목 줄은 유지)
+    lines = transcript_path.read_text(encoding="utf-8").splitlines()
+    title = lines[0] if lines and lines[0].startswith("# ") else f"# {transcript_path.stem}"
+    body = format_transcript_lines((s["start"], s["end"], s["text"]) for s in document["segments"])
+    write_atomic(transcript_path, f"{title}\n\n{body}")
+
+    revision = document["retranscriptions"][-1]
+    return {
+        "start": start,
+        "end": end,
+        "model": transcription.model,
+        "backend": transcription.backend,
+        "decoding": transcription.decoding,
+        "replaced": revision["replaced"],
+        "inserted": revision["inserted"],
+        "segments": [
+            s for s in document["segments"]
+            if s.get("retranscribed") and start <= (s["start"] + s["end"]) / 2 < end
+        ],
+    }
@@ -0,0 +1,204 @@
+"""Hot reload of prompt templates and selected settings (POST /admin/reload).
+
+Prompt templates can be overridden by dropping text files into the prompt
+directory (``PROMPT_TEMPLATE_DIR``, default ``DB/prompts``):
+
+    summary_chunk.txt    청크 요약 프롬프트 ({chunk} 필수)
+    summary_reduce.txt   요약 통합 프롬프트 ({summaries} 필수)
+    one_line.txt         한 줄 요약 프롬프트 ({text} 필수, {max_chars}/{language}/{tone} 선택)
+    ask.txt              질문 답변(POST /ask) 프롬프트 ({sources}, {question} 필수)
+
+Removing a file restores the built-in prompt on the next reload. A reload
+also re-reads ``.env`` and applies the settings in :data:`RELOADABLE_SETTINGS`.
+Everything is validated first and then applied together, so a broken
+template never leaves the server half reloaded.
+"""
+
+from __future__ import annotations
+
+import os
+import sys
+import threading
+from pathlib import Path
+from typing import Any, Dict, List, Tuple
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value, get_db_base_path, load_env_file
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value, get_db_base_path, load_env_file  # type: ignore
+
+
+def get_prompt_dir() -> Path:
+    configured = os.environ.get("PROMPT_TEMPLATE_DIR")
+    return Path(configured) if configured else get_db_base_path() / "prompts"
+
+
+# 파일명 → (모듈, 속성, 필수 자리표시자)
+PROMPT_TEMPLATES: Dict[str, Tuple[str, str, Tuple[str, ...]]] = {
+    "summary_chunk.txt": ("workflow.summarize", "CHUNK_PROMPT", ("{chunk}",)),
+    "summary_reduce.txt": ("workflow.summarize", "REDUCE_PROMPT", ("{summaries}",)),
+    "one_line.txt": ("one_line_summary", "ONE_LINE_PROMPT", ("{text}",)),
+    "ask.txt": ("archive_qa", "ASK_PROMPT", ("{sources}", "{question}")),
+}
+
+# 환경변수 → (모듈, 속성 경로, 타입). 다음 작업부터 적용되는 값들이다.
+RELOADABLE_SETTINGS: Dict[str, Tuple[str, str, type]] = {
+    "OLLAMA_TIMEOUT": ("workflow.summarize", "OLLAMA_TIMEOUT", int),
+    "MAX_RETRIES": ("workflow.summarize", "MAX_RETRIES", int),
+    "RETRY_DELAY": ("workflow.summarize", "RETRY_DELAY", int),
+    "DEFAULT_CHUNK_SIZE": ("workflow.summarize", "DEFAULT_CHUNK_SIZE", int),
+    "DEFAULT_TEMPERATURE_SUMMARY": ("workflow.summarize", "DEFAULT_TEMPERATURE", float),
+    "DEFAULT_NUM_CTX": ("workflow.summarize", "DEFAULT_NUM_CTX", int),
+    "STT_BACKEND": ("stt_backends", "STT_BACKEND", str),
+    "STT_HTTP_URL": ("stt_backends", "STT_HTTP_URL", str),
+    "STT_VOCABULARY_FILE": ("stt_prompt", "STT_VOCABULARY_FILE", str),
+    "STT_DEFAULT_LANGUAGE": ("stt_backends", "STT_DEFAULT_LANGUAGE", str),
+    "STT_LANGUAGE_DETECT_WINDOWS": ("workflow.transcribe", "STT_LANGUAGE_DETECT_WINDOWS", int),
+    "STT_LANGUAGE_MIN_CONFIDENCE": ("workflow.transcribe", "STT_LANGUAGE_MIN_CONFIDENCE", float),
+    "STT_LANGUAGE_FALLBACK": ("workflow.transcribe", "STT_LANGUAGE_FALLBACK", str),
+    "WHISPER_POOL_SIZE": ("workflow.transcribe", "engine_manager.pool_size", int),
+    "WHISPER_MAX_LOADED_MODELS": ("workflow.transcribe", "engine_manager.max_models", int),
+    "INFERENCE_PRIORITY": ("inference_priority", "INFERENCE_PRIORITY", str),
+    "INFERENCE_CPU_THREADS": ("inference_priority", "INFERENCE_CPU_THREADS", int),
+    "INFERENCE_CPU_ADAPTIVE": ("inference_priority", "INFERENCE_CPU_ADAPTIVE", bool),
+    "INFERENCE_CPU_RESERVE": ("inference_priority", "INFERENCE_CPU_RESERVE", int),
+    "REQUEST_LOG_ENABLED": ("request_log", "REQUEST_LOG_ENABLED", bool),
+    "SLOW_REQUEST_THRESHOLD_MS": ("request_log", "SLOW_REQUEST_THRESHOLD_MS", int),
+    "SUMMARY_DEBUG_ENABLED": ("summary_debug", "SUMMARY_DEBUG_ENABLED", bool),
+    "ONE_LINE_MAX_CHARS": ("one_line_summary", "ONE_LINE_MAX_CHARS", int),
+    "ONE_LINE_TONE": ("one_line_summary", "ONE_LINE_TONE", str),
+    "ONE_LINE_LANGUAGE": ("one_line_summary", "ONE_LINE_LANGUAGE", str),
+    "SUMMARY_LANGUAGE": ("workflow.summarize", "SUMMARY_LANGUAGE", str),
+    "SUMMARY_MODEL_BY_LANGUAGE": ("workflow.summarize", "SUMMARY_MODEL_BY_LANGUAGE", str),
+    "SUMMARY_INCREMENTAL": ("incremental_summary", "SUMMARY_INCREMENTAL", bool),
+    "SUMMARY_FORMAT": ("structured_summary", "SUMMARY_FORMAT", str),
+    "ASK_MODEL": ("archive_qa", "ASK_MODEL", str),
+    "ASK_TOP_K": ("archive_qa", "ASK_TOP_K", int),
+    "ASK_MIN_SCORE": ("archive_qa", "ASK_MIN_SCORE", float),
+    "SUMMARY_CHUNKING": ("summary_chunking", "SUMMARY_CHUNKING", str),
+    "SUMMARY_CHUNK_TOKENS": ("summary_chunking", "SUMMARY_CHUNK_TOKENS", int),
+    "SUMMARY_CHUNK_OVERLAP_TOKENS": ("summary_chunking", "SUMMARY_CHUNK_OVERLAP_TOKENS", int),
+    "SUMMARY_NUM_CTX_MAX": ("summary_chunking", "SUMMARY_NUM_CTX_MAX", int),
+    "SUMMARY_OUTPUT_RESERVE_TOKENS": ("summary_chunking", "SUMMARY_OUTPUT_RESERVE_TOKENS", int),
+    "VECTOR_TOMBSTONE_RETENTION_DAYS": ("embedding_pipeline", "TOMBSTONE_RETENTION_DAYS", int),
+    "API_AUTH_REQUIRED": ("api_tokens", "API_AUTH_REQUIRED", bool),
+    "WATCH_STEPS": ("folder_watch", "WATCH_STEPS", str),
+    "WATCH_SETTLE_SECONDS": ("folder_watch", "WATCH_SETTLE_SECONDS", float),
+    "WATCH_RECURSIVE": ("folder_watch", "WATCH_RECURSIVE", bool),
+    "AUTH_ENABLED": ("api_tokens", "AUTH_ENABLED", bool),
+    "AUTH_TOKEN": ("api_tokens", "AUTH_TOKEN", str),
+    "AUTH_EXEMPT_PATHS": ("api_tokens", "AUTH_EXEMPT_PATHS", str),
+}
+
+# /admin/reload 응답에 값을 그대로 보여 주지 않는 설정
+SECRET_SETTINGS = {"AUTH_TOKEN"}
+
+RELOAD_LOCK = threading.Lock()
+
+# 처음 재로드할 때 기본 프롬프트를 기억해 두고 파일이 사라지면 되돌린다
+_builtin_prompts: Dict[Tuple[int, str], str] = {}
+
+
+class ReloadError(ValueError):
+    """Raised when the new prompts or settings are invalid; nothing is applied."""
+
+
+def _loaded_modules(name: str) -> List[Any]:
+    """Return every loaded copy of a module (script and package imports)."""
+    modules = []
+    for candidate in (name, f"sttEngine.{name}"):
+        module = sys.modules.get(candidate)
+        if module is not None and module not in modules:
+            modules.append(module)
+    return modules
+
+
+def _resolve_target(module: Any, attr_path: str) -> Tuple[Any, str]:
+    owner = module
+    *parents, attr = attr_path.split(".")
+    for parent in parents:
+        owner = getattr(owner, parent)
+    return owner, attr
+
+
+def _collect_prompt_changes(errors: List[str]) -> Tuple[List[Tuple[Any, str, Any]], Dict[str, str]]:
+    prompt_dir = get_prompt_dir()
+    changes: List[Tuple[Any, str, Any]] = []
+    sources: Dict[str, str] = {}
+
+    for filename, (module_name, attr, placeholders) in PROMPT_TEMPLATES.items():
+        path = prompt_dir / filename
+        text = None
+        if path.exists():
+            try:
+                text = path.read_text(encoding="utf-8")
+            except (OSError, UnicodeDecodeError) as exc:
+                errors.append(f"{filename}: 읽기 실패 ({exc})")
+                continue
+            missing = [p for p in placeholders if p not in text]
+            if missing:
+                errors.append(f"{filename}: 필수 자리표시자 누락 {', '.join(missing)}")
+                continue
+            sources[filename] = str(path)
+        else:
+            sources[filename] = "builtin"
+
+        for module in _loaded_modules(module_name):
+            key = (id(module), attr)
+            _builtin_prompts.setdefault(key, getattr(module, attr))
+            changes.append((module, attr, text if text is not None else _builtin_prompts[key]))
+
+    return changes, sources
+
+
+def _collect_setting_changes(errors: List[str]) -> Tuple[List[Tuple[Any, str, Any]], Dict[str, Any]]:
+    changes: List[Tuple[Any, str, Any]] = []
+    values: Dict[str, Any] = {}
+
+    for key, (module_name, attr_path, value_type) in RELOADABLE_SETTINGS.items():
+        modules = _loaded_modules(module_name)
+        if not modules or os.environ.get(key) is None:
+            continue
+        try:
+            value = get_config_value(key, None, value_type)
+        except ValueError:
+            errors.append(f"{key}: {value_type.__name__} 값이 아닙니다 ({os.environ.get(key)})")
+            continue
+        values[key] = "********" if key in SECRET_SETTINGS and value else value
+        for module in modules:
+            try:
+                owner, attr = _resolve_target(module, attr_path)
+            except AttributeError:
+                continue
+            changes.append((owner, attr, value))
+
+    return changes, values
+
+
+def reload_runtime_config() -> Dict[str, Any]:
+    """Re-read ``.env`` and prompt files, then apply them all at once.
+
+    Raises :class:`ReloadError` (without applying anything) when a prompt
+    file or setting is invalid.
+    """
+    with RELOAD_LOCK:
+        load_env_file()
+
+        errors: List[str] = []
+        prompt_changes, prompt_sources = _collect_prompt_changes(errors)
+        setting_changes, setting_values = _collect_setting_changes(errors)
+        if errors:
+            raise ReloadError("; ".join(errors))
+
+        changed: List[str] = []
+        for owner, attr, value in prompt_changes + setting_changes:
+            if getattr(owner, attr, None) != value:
+                setattr(owner, attr, value)
+                changed.append(attr)
+
+        return {
+            "prompt_dir": str(get_prompt_dir()),
+            "prompts": prompt_sources,
+            "settings": setting_values,
+            "changed": sorted(set(changed)),
+        }
@@ -1,21 +1,27 @@
 from __future__ import annotations
 
 import json
+import threading
+import time
 import uuid
+from collections import OrderedDict
 from datetime import datetime, timedelta
 from pathlib import Path
-from typing import Dict, List, Any, Optional
+from typing import Dict, Iterable, List, Any, Optional
 import hashlib
 
 try:  # pragma: no cover - import resolution for both package/script execution
-    from .config import DB_ALIAS, get_db_base_path
+    from .config import DB_ALIAS, get_config_value, get_db_base_path
 except Exception:  # pragma: no cover - fallback when imported as a script
     try:
-        from config import DB_ALIAS, get_db_base_path  # type: ignore
+        from config import DB_ALIAS, get_config_value, get_db_base_path  # type: ignore
     except Exception:  # pragma: no cover - keep module usable without config
         DB_ALIAS = "DB"  # type: ignore
         get_db_base_path = None  # type: ignore
 
+        def get_config_value(key, default=None, value_type=str):  # type: ignore
+            return default
+
 
 def _resolve_cache_directory() -> Path:
     """Return the cache directory derived from the configured DB base path."""
@@ -41,12 +47,105 @@ CACHE_DIR = _resolve_cache_directory()
 # 캐시 만료 시간 (24시간)
 CACHE_EXPIRY_HOURS = 24
 
+# /search 응답 메모리 캐시 (같은 검색을 다시 보낼 때 키워드/벡터 검색을 생략)
+SEARCH_RESPONSE_CACHE_SIZE = get_config_value("SEARCH_RESPONSE_CACHE_SIZE", 256, int)
+SEARCH_RESPONSE_CACHE_TTL_SECONDS = get_config_value("SEARCH_RESPONSE_CACHE_TTL_SECONDS", 600, int)
+
+_stats_lock = threading.Lock()
+_stats = {
+    "hits": 0,
+    "misses": 0,
+    "stale": 0,
+    "response_hits": 0,
+    "response_misses": 0,
+    "response_invalidations": 0,
+}
+
+_response_lock = threading.Lock()
+_response_cache: "OrderedDict[str, tuple[float, Dict[str, Any]]]" = OrderedDict()
+_response_generation: Optional[str] = None
+
+
+def _count(name: str) -> None:
+    with _stats_lock:
+        _stats[name] += 1
+
+
+def _hit_rate(hits: int, misses: int) -> Optional[float]:
+    total = hits + misses
+    return round(hits / total, 4) if total else None
+
+
+def get_hit_counters() -> tuple[int, int]:
+    """Return ``(hits, misses)`` of the on-disk vector result cache."""
+    with _stats_lock:
+        return _stats["hits"], _stats["misses"]
+
+
+def search_response_key(query: str, filters: Dict[str, Any]) -> str:
+    """Cache key for a /search response (query + all filter parameters)."""
+    payload = json.dumps({"q": query, **filters}, ensure_ascii=False, sort_keys=True)
+    return hashlib.sha256(payload.encode("utf-8")).hexdigest()
+
+
+def _sync_generation(generation: str) -> None:
+    """Drop every cached response once the index generation moves on."""
+    global _response_generation
+    if _response_generation != generation:
+        if _response_cache:
+            _count("response_invalidations")
+        _response_cache.clear()
+        _response_generation = generation
+
+
+def get_search_response(key: str, generation: str) -> Optional[Dict[str, Any]]:
+    """Return a cached /search response built for ``generation`` or ``None``."""
+    if SEARCH_RESPONSE_CACHE_SIZE <= 0:
+        return None
+    with _response_lock:
+        _sync_generation(generation)
+        entry = _response_cache.get(key)
+        if entry and time.monotonic() - entry[0] <= SEARCH_RESPONSE_CACHE_TTL_SECONDS:
+            _response_cache.move_to_end(key)
+            _count("response_hits")
+            return entry[1]
+        if entry:
+            del _response_cache[key]
+    _count("response_misses")
+    return None
+
+
+def store_search_response(key: str, generation: str, response: Dict[str, Any]) -> None:
+    if SEARCH_RESPONSE_CACHE_SIZE <= 0:
+        return
+    with _response_lock:
+        _sync_generation(generation)
+        _response_cache[key] = (time.monotonic(), response)
+        _response_cache.move_to_end(key)
+        while len(_response_cache) > SEARCH_RESPONSE_CACHE_SIZE:
+            _response_cache.popitem(last=False)
+
+
+def invalidate_search_responses() -> None:
+    """Forget all cached /search responses (e.g. after a transcript edit)."""
+    with _response_lock:
+        if _response_cache:
+            _count("response_invalidations")
+        _response_cache.clear()
+
 
 def get_query_hash(query: str, top_k: int,
                    start_date: Optional[str] = None,
-                   end_date: Optional[str] = None) -> str:
+                   end_date: Optional[str] = None,
+                   target: Optional[str] = None,
+                   namespaces: Optional[Iterable[str]] = None) -> str:
     """검색 쿼리와 파라미터에 대한 해시값 생성"""
     query_data = f"{query}:{top_k}:{start_date or ''}:{end_date or ''}"
+    # 기본 대상(both)과 전체 네임스페이스는 기존 캐시 키를 그대로 사용
+    if target and target != "both":
+        query_data += f":{target}"
+    if namespaces:
+        query_data += f":ns={','.join(sorted(namespaces))}"
     return hashlib.md5(query_data.encode('utf-8')).hexdigest()
 
 
@@ -89,29 +188,43 @@ def is_cache_expired(timestamp_str: str) -> bool:
 
 def get_cached_search_result(query: str, top_k: int,
                              start_date: Optional[str] = None,
-                             end_date: Optional[str] = None) -> Optional[List[Dict[str, Any]]]:
-    """캐시된 검색 결과 조회"""
+                             end_date: Optional[str] = None,
+                             target: Optional[str] = None,
+                             generation: Optional[str] = None,
+                             namespaces: Optional[Iterable[str]] = None) -> Optional[List[Dict[str, Any]]]:
+    """캐시된 검색 결과 조회
+
+    ``generation``이 주어지면 같은 색인 세대에서 저장된 결과만 사용한다.
+    """
     # 캐시 사용 전 만료된 항목을 정리하여 디스크 사용량을 관리
     cleanup_expired_cache()
 
-    query_hash = get_query_hash(query, top_k, start_date, end_date)
+    query_hash = get_query_hash(query, top_k, start_date, end_date, target, namespaces)
     record = load_cache_record(query_hash)
     
-    if not record:
+    if not record or is_cache_expired(record.get('timestamp', '')):
+        _count("misses")
         return None
-    
-    if is_cache_expired(record.get('timestamp', '')):
+
+    if generation is not None and record.get('generation') != generation:
+        # 색인이 갱신된 뒤의 오래된 결과
+        _count("stale")
+        _count("misses")
         return None
-    
+
+    _count("hits")
     return record.get('results', [])
 
 
 def cache_search_result(query: str, top_k: int, results: List[Dict[str, Any]],
                        existing_uuid: Optional[str] = None,
                        start_date: Optional[str] = None,
-                       end_date: Optional[str] = None) -> str:
+                       end_date: Optional[str] = None,
+                       target: Optional[str] = None,
+                       generation: Optional[str] = None,
+                       namespaces: Optional[Iterable[str]] = None) -> str:
     """검색 결과를 캐시에 저장"""
-    query_hash = get_query_hash(query, top_k, start_date, end_date)
+    query_hash = get_query_hash(query, top_k, start_date, end_date, target, namespaces)
     
     # 기존 UUID 유지하거나 새로 생성
     if existing_uuid:
@@ -132,7 +245,10 @@ def cache_search_result(query: str, top_k: int, results: List[Dict[str, Any]],
         "query_hash": query_hash,
         "results": results,
         "start_date": start_date,
-        "end_date": end_date
+        "end_date": end_date,
+        "target": target or "both",
+        "namespaces": sorted(namespaces) if namespaces else None,
+        "generation": generation
     }
     
     save_cache_record(query_hash, record)
@@ -179,13 +295,10 @@ def cleanup_expired_cache() -> int:
 
 def get_cache_stats() -> Dict[str, Any]:
     """캐시 통계 정보 반환"""
-    if not CACHE_DIR.exists():
-        return {"total_entries": 0, "expired_entries": 0, "valid_entries": 0}
-    
     total = 0
     expired = 0
     
-    for cache_file in CACHE_DIR.glob("*.json"):
+    for cache_file in CACHE_DIR.glob("*.json") if CACHE_DIR.exists() else []:
         try:
             with open(cache_file, 'r', encoding='utf-8') as f:
                 record = json.load(f)
@@ -197,8 +310,26 @@ def get_cache_stats() -> Dict[str, Any]:
             total += 1
             expired += 1  # 잘못된 파일도 만료된 것으로 간주
     
+    with _stats_lock:
+        counters = dict(_stats)
+    with _response_lock:
+        response_entries = len(_response_cache)
+
     return {
         "total_entries": total,
         "expired_entries": expired,
-        "valid_entries": total - expired
+        "valid_entries": total - expired,
+        "hits": counters["hits"],
+        "misses": counters["misses"],
+        "stale": counters["stale"],
+        "hit_rate": _hit_rate(counters["hits"], counters["misses"]),
+        "response_cache": {
+            "entries": response_entries,
+            "max_entries": SEARCH_RESPONSE_CACHE_SIZE,
+            "ttl_seconds": SEARCH_RESPONSE_CACHE_TTL_SECONDS,
+            "hits": counters["response_hits"],
+            "misses": counters["response_misses"],
+            "hit_rate": _hit_rate(counters["response_hits"], counters["response_misses"]),
+            "invalidations": counters["response_invalidations"],
+        },
     }
\ No newline at end of file
@@ -0,0 +1,307 @@
+"""Versioned storage for structured transcript segments.
+
+Each STT result ``{stem}.md`` gets a sibling ``{stem}.segments.json`` with
+the timestamped segments in a versioned envelope::
+
+    {
+        "version": 1,
+        "language": "ko",
+        "model": "large-v3-turbo",
+        "created_at": "2025-01-01T12:00:00",
+        "segments": [{"start": 0.0, "end": 2.5, "text": "..."}]
+    }
+
+Diarizing backends may add a ``speaker`` label to each segment and an
+optional top-level ``speaker_embeddings`` map (label → voice embedding)
+used to match known speaker profiles; both are optional and need no
+version bump. Likewise, an optional ``filtering`` object records how many
+segments postprocessing removed or flagged (see ``hallucination.py``), and
+flagged segments carry a ``hallucination`` list of reasons. Time ranges
+re-transcribed after the fact are listed in ``retranscriptions`` and their
+segments carry ``"retranscribed": true``. ``prompt`` records the Whisper
+initial prompt the transcript was biased with and where its terms came
+from (see ``stt_prompt.py``); ``decoding`` the decoding strategy and how
+many segments needed temperature fallback (see ``decoding.py``).
+``language_detection`` is present when no language was given and Whisper
+detected it (``{"language", "confidence", "candidates", "windows"}``).
+``verbatim`` keeps every raw
+segment before postprocessing; segments missing from the clean
+``segments`` list carry a ``dropped`` reason (see ``transcript_views.py``).
+
+Older files that are a bare segment array are treated as version 0 and
+migrated to the current envelope when read. Readers should always go
+through :func:`load_segments` so new fields (speaker labels, confidence)
+can be added with a migration step instead of breaking old files.
+
+Run ``python segment_store.py`` to migrate every segments file under the
+DB folder in one pass.
+"""
+
+from __future__ import annotations
+
+import argparse
+import json
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Callable, Dict, List, Optional, Tuple
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_db_base_path
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_db_base_path  # type: ignore
+
+SEGMENTS_SCHEMA_VERSION = 1
+SEGMENTS_SUFFIX = ".segments.json"
+# 데이터 마이그레이션 백업 폴더 (DB 폴더 아래, 스캔에서 제외)
+BACKUPS_DIRNAME = "backups"
+
+
+class SegmentSchemaError(ValueError):
+    """Raised when a segments file cannot be understood or migrated."""
+
+
+def segments_path_for(transcript_path: Path) -> Path:
+    """Return the segments file that belongs to an STT markdown file."""
+    return transcript_path.with_name(f"{transcript_path.stem}{SEGMENTS_SUFFIX}")
+
+
+def _normalize_segment(segment: Any) -> Dict[str, Any]:
+    if isinstance(segment, (list, tuple)) and len(segment) >= 3:
+        start, end, text = segment[0], segment[1], segment[2]
+        return {"start": float(start), "end": float(end), "text": str(text)}
+    if isinstance(segment, dict):
+        normalized = dict(segment)
+        normalized["start"] = float(segment.get("start", 0.0))
+        normalized["end"] = float(segment.get("end", 0.0))
+        normalized["text"] = str(segment.get("text", ""))
+        return normalized
+    raise SegmentSchemaError(f"알 수 없는 세그먼트 형식: {segment!r}")
+
+
+def build_segments_document(segments: List[Any], language: Optional[str] = None,
+                            model: Optional[str] = None,
+                            speaker_embeddings: Optional[Dict[str, List[float]]] = None,
+                            filtering: Optional[Dict[str, Any]] = None,
+                            verbatim: Optional[List[Any]] = None,
+                            prompt: Optional[Dict[str, Any]] = None,
+                            decoding: Optional[Dict[str, Any]] = None,
+                            language_detection: Optional[Dict[str, Any]] = None) -> Dict[str, Any]:
+    """Wrap segments in the current versioned envelope."""
+    document = {
+        "version": SEGMENTS_SCHEMA_VERSION,
+        "language": language,
+        "model": model,
+        "created_at": datetime.now().isoformat(),
+        "segments": [_normalize_segment(segment) for segment in segments],
+    }
+    if speaker_embeddings:
+        document["speaker_embeddings"] = speaker_embeddings
+    if filtering:
+        document["filtering"] = filtering
+    if verbatim is not None:
+        document["verbatim"] = [_normalize_segment(segment) for segment in verbatim]
+    if prompt:
+        document["prompt"] = prompt
+    if decoding:
+        document["decoding"] = decoding
+    if language_detection:
+        document["language_detection"] = language_detection
+    return document
+
+
+def _migrate_v0(data: Any) -> Dict[str, Any]:
+    """v0: bare array of segments without metadata."""
+    if not isinstance(data, list):
+        raise SegmentSchemaError("버전 정보가 없는 세그먼트 파일은 배열이어야 합니다.")
+    document = build_segments_document(data)
+    document["created_at"] = None
+    return document
+
+
+# 버전 N 문서를 N+1로 올리는 함수 목록. 스키마를 바꿀 때 여기에 단계를 추가한다.
+MIGRATIONS: Dict[int, Callable[[Any], Dict[str, Any]]] = {
+    0: _migrate_v0,
+}
+
+
+def migrate_segments_document(data: Any) -> Tuple[Dict[str, Any], bool]:
+    """Bring a parsed segments file up to the current schema version.
+
+    Returns ``(document, migrated)``.
+    """
+    version = data.get("version", 0) if isinstance(data, dict) else 0
+    if not isinstance(version, int) or version < 0:
+        raise SegmentSchemaError(f"잘못된 세그먼트 스키마 버전: {version!r}")
+    if version > SEGMENTS_SCHEMA_VERSION:
+        raise SegmentSchemaError(
+            f"세그먼트 스키마 버전 {version}은(는) 이 버전에서 지원하지 않습니다 "
+            f"(최대 {SEGMENTS_SCHEMA_VERSION})."
+        )
+
+    migrated = False
+    while version < SEGMENTS_SCHEMA_VERSION:
+        data = MIGRATIONS[version](data)
+        version = data["version"]
+        migrated = True
+    return data, migrated
+
+
+def write_segments(path: Path, segments: List[Any], language: Optional[str] = None,
+                   model: Optional[str] = None,
+                   speaker_embeddings: Optional[Dict[str, List[float]]] = None,
+                   filtering: Optional[Dict[str, Any]] = None,
+                   verbatim: Optional[List[Any]] = None,
+                   prompt: Optional[Dict[str, Any]] = None,
+                   decoding: Optional[Dict[str, Any]] = None,
+                   language_detection: Optional[Dict[str, Any]] = None) -> Dict[str, Any]:
+    """Write segments to ``path`` atomically in the current envelope."""
+    document = build_segments_document(segments, language, model, speaker_embeddings, filtering, verbatim,
+                                       prompt, decoding, language_detection)
+    _write_document(path, document)
+    return document
+
+
+def save_segments(path: Path, document: Dict[str, Any]) -> None:
+    """Write back a document loaded with :func:`load_segments` after editing it in place."""
+    _write_document(path, document)
+
+
+def _write_document(path: Path, document: Dict[str, Any]) -> None:
+    tmp_path = path.with_suffix(path.suffix + ".tmp")
+    with open(tmp_path, "w", encoding="utf-8") as f:
+        json.dump(document, f, ensure_ascii=False, indent=2)
+    tmp_path.replace(path)
+
+
+def splice_segments(path: Path, replacement: List[Any], start: float, end: float,
+                    revision: Optional[Dict[str, Any]] = None,
+                    verbatim_replacement: Optional[List[Any]] = None) -> Dict[str, Any]:
+    """Replace the segments of ``[start, end)`` with ``replacement`` and save.
+
+    A segment belongs to the range when its midpoint falls inside it, so
+    segments straddling a boundary are replaced only once. ``revision`` is
+    appended to the document's ``retranscriptions`` list. The ``verbatim``
+    list, when present, gets ``verbatim_replacement`` (or ``replacement``)
+    for the same range. Returns the updated document.
+    """
+    document = load_segments(path)
+    if document is None:
+        raise SegmentSchemaError(f"세그먼트 파일이 없습니다: {path.name}")
+
+    def in_range(segment: Dict[str, Any]) -> bool:
+        return start <= (segment["start"] + segment["end"]) / 2 < end
+
+    existing = document.get("segments") or []
+    kept = [segment for segment in existing if not in_range(segment)]
+    inserted = [dict(_normalize_segment(segment), retranscribed=True) for segment in replacement]
+    document["segments"] = sorted(kept + inserted, key=lambda segment: segment["start"])
+    if "verbatim" in document:
+        raw = replacement if verbatim_replacement is None else verbatim_replacement
+        document["verbatim"] = sorted(
+            [segment for segment in document["verbatim"] if not in_range(segment)]
+            + [dict(_normalize_segment(segment), retranscribed=True) for segment in raw],
+            key=lambda segment: segment["start"],
+        )
+    if revision is not None:
+        document.setdefault("retranscriptions", []).append(
+            dict(revision, replaced=len(existing) - len(kept), inserted=len(inserted))
+        )
+    _write_document(path, document)
+    return document
+
+
+def load_segments(path: Path, persist_migration: bool = True) -> Optional[Dict[str, Any]]:
+    """Read a segments file, migrating older schemas on the fly.
+
+    Returns ``None`` when the file does not exist. When the file was written
+    with an older schema and ``persist_migration`` is set, the migrated
+    document is written back so later reads are cheap.
+    """
+    if not path.exists():
+        return None
+    try:
+        with open(path, "r", encoding="utf-8") as f:
+            data = json.load(f)
+    except json.JSONDecodeError as exc:
+        raise SegmentSchemaError(f"세그먼트 파일을 읽을 수 없습니다: {path.name}") from exc
+
+    document, migrated = migrate_segments_document(data)
+    if migrated and persist_migration:
+        try:
+            _write_document(path, document)
+        except OSError as exc:
+            print(f"세그먼트 파일 마이그레이션 저장 실패 ({path.name}): {exc}")
+    return document
+
+
+def describe_segments(path: Path) -> Optional[Dict[str, Any]]:
+    """Return segment metadata (without the segments) for record detail views."""
+    document = load_segments(path)
+    if document is None:
+        return None
+    return {
+        "version": document.get("version"),
+        "language": document.get("language"),
+        "model": document.get("model"),
+        "created_at": document.get("created_at"),
+        "count": len(document.get("segments") or []),
+        "prompt": document.get("prompt"),
+        "decoding": document.get("decoding"),
+    }
+
+
+def _segment_files(base_dir: Optional[Path] = None) -> List[Path]:
+    """Segments files under ``base_dir`` (defaults to the DB folder), leaving out migration backups."""
+    base_dir = base_dir or get_db_base_path()
+    backups = (get_db_base_path() / BACKUPS_DIRNAME).resolve()
+    return [path for path in base_dir.rglob(f"*{SEGMENTS_SUFFIX}")
+            if backups not in path.resolve().parents]
+
+
+def pending_segment_files(base_dir: Optional[Path] = None) -> List[Path]:
+    """Segments files under ``base_dir`` stored with an older schema version."""
+    pending = []
+    for path in _segment_files(base_dir):
+        try:
+            with open(path, "r", encoding="utf-8") as f:
+                data = json.load(f)
+        except (OSError, json.JSONDecodeError):
+            continue
+        version = data.get("version", 0) if isinstance(data, dict) else 0
+        if isinstance(version, int) and version < SEGMENTS_SCHEMA_VERSION:
+            pending.append(path)
+    return pending
+
+
+def migrate_all_segments(base_dir: Optional[Path] = None, dry_run: bool = False) -> Dict[str, int]:
+    """Migrate every segments file under ``base_dir`` (defaults to the DB folder).
+
+    With ``dry_run`` files are only checked; ``migrated`` counts the files
+    that would be rewritten.
+    """
+    report = {"checked": 0, "migrated": 0, "failed": 0}
+    for path in _segment_files(base_dir):
+        report["checked"] += 1
+        try:
+            with open(path, "r", encoding="utf-8") as f:
+                data = json.load(f)
+            document, migrated = migrate_segments_document(data)
+            if migrated:
+                if not dry_run:
+                    _write_document(path, document)
+                report["migrated"] += 1
+        except (OSError, json.JSONDecodeError, SegmentSchemaError) as exc:
+            report["failed"] += 1
+            print(f"세그먼트 파일 마이그레이션 실패 ({path}): {exc}")
+    return report
+
+
+if __name__ == "__main__":
+    parser = argparse.ArgumentParser(description="Migrate segments files to the current schema version")
+    parser.add_argument("base_dir", nargs="?", default=None, help="Directory to scan (default: DB folder)")
+    args = parser.parse_args()
+    result = migrate_all_segments(Path(args.base_dir) if args.base_dir else None)
+    print(
+        f"세그먼트 파일 {result['checked']}개 확인, "
+        f"{result['migrated']}개 마이그레이션, {result['failed']}개 실패"
+    )
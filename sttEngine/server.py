@@ -3,6 +3,9 @@
 The server exposes:
   * ``GET /`` – serve the upload HTML page.
   * ``POST /upload`` – accept an audio file and store it under ``DB/uploads/``.
+  * ``POST /upload_url`` – download a remote recording (or yt-dlp link) in the background.
+  * ``POST /email/inbound`` – create records from audio attachments of forwarded emails.
+  * ``POST /upload/raw`` – single raw-body upload for phone shortcuts (API token required).
   * ``POST /process`` – run selected workflow steps for the uploaded file.
   * ``GET /download/<file>`` – return processed files for download.
 
@@ -10,15 +13,18 @@ Only selected workflow steps return download links.
 """
 
 from http.server import ThreadingHTTPServer, BaseHTTPRequestHandler
+import io
 import json
+import mimetypes
 import os
 import subprocess
 import sys
 import uuid
+import zipfile
 from pathlib import Path
 from typing import Any
 import re
-from urllib.parse import unquote
+from urllib.parse import parse_qs, quote, unquote, urlparse
 
 try:
     from .logger import setup_logging
@@ -26,65 +32,559 @@ except ImportError:  # pragma: no cover - fallback for script execution
     from logger import setup_logging
 
 setup_logging()
+from collections import deque
 from datetime import datetime
 import threading
 import time
 import shutil
 import hashlib
+import socket
 import asyncio
 import websockets
 
-from .workflow.transcribe import transcribe_audio_files
+from .workflow.transcribe import TranscriptionCancelled, available_whisper_models, engine_manager
+from .workflow import summarize as summarize_workflow
 from .workflow.summarize import (
     summarize_text_mapreduce,
+    summary_model_for_language,
+    parse_language_models,
+    detect_summary_language,
+    parse_summary_to_sections,
     read_text_with_fallback,
     save_output,
+    structured_summary,
     DEFAULT_MODEL,
-    DEFAULT_CHUNK_SIZE,
-    DEFAULT_TEMPERATURE,
 )
 from .obsidian_mcp import send_summary_to_obsidian_sync
 from .config import (
     DB_ALIAS,
+    get_config_value,
     get_db_base_path,
     get_default_model,
+    get_model_for_task,
     normalize_db_record_path,
     resolve_db_path,
     to_db_record_path,
 )
-from .one_line_summary import generate_one_line_summary
+from .one_line_summary import OneLineOptionsError, generate_one_line_summary, resolve_one_line_options
 from .vector_search import search as search_vectors
-from .search_cache import cleanup_expired_cache, get_cache_stats, delete_cache_record
-from .embedding_pipeline import embed_text_ollama, load_index, save_index
-from ollama_utils import ensure_ollama_server, check_ollama_model_available
+from .inference_priority import inference_priority_status
+from .vector_cache import VECTOR_CACHE, VECTOR_CACHE_PRELOAD, preload as preload_vectors, save_vector
+from .cache_manager import (
+    WAVEFORM_DEFAULT_POINTS,
+    WAVEFORM_MAX_POINTS,
+    cached_query_embedding,
+    cleanup_caches,
+    get_cache_manager_stats,
+    get_waveform,
+)
+from .search_cache import (
+    cleanup_expired_cache,
+    delete_cache_record,
+    get_cache_stats,
+    get_search_response,
+    invalidate_search_responses,
+    search_response_key,
+    store_search_response,
+)
+from .embedding_pipeline import (
+    INDEX_LOCK,
+    SEARCH_TARGETS,
+    NamespaceError,
+    compact_index,
+    embed_document_ollama,
+    embed_text_ollama,
+    entry_namespace,
+    entry_vector_names,
+    get_index_generation,
+    get_index_snapshot,
+    namespace_stats,
+    normalize_namespace,
+    parse_namespaces,
+    set_namespace_for_paths,
+    shard_stats,
+    vector_chunk_count,
+    INDEX_FILE,
+    SHARD_DIR,
+    load_index,
+    migrate_index,
+    resolve_index_path,
+    save_index,
+)
+from .progress_bus import ProgressBus
+from .task_journal import (
+    JOURNAL_FILENAME as TASK_JOURNAL_FILENAME,
+    UPLOAD_IDLE_TIMEOUT_SECONDS,
+    TaskJournal,
+    start_client_watchdog,
+)
+from .model_options import (
+    DEFAULT_MODEL_OPTIONS,
+    OPTION_SCHEMA as MODEL_OPTION_SCHEMA,
+    ModelOptionsError,
+    resolve_model_options,
+    validate_model_options,
+)
+from .request_log import RequestLoggingMixin
+from .cors import CorsMixin
+from .runtime_config import ReloadError, reload_runtime_config
+from .config_bundle import SECTIONS as CONFIG_BUNDLE_SECTIONS, BundleError, export_bundle, import_bundle
+from .record_layout import SOURCE_SUBDIR, get_record_layout
+from .segment_store import (
+    SegmentSchemaError,
+    describe_segments,
+    load_segments,
+    migrate_all_segments,
+    pending_segment_files,
+    save_segments,
+    segments_path_for,
+)
+from . import summary_debug
+from . import incremental_summary
+from .incremental_summary import ChunkSummaryCache, cache_path_for as summary_cache_path_for
+from .stt_backends import TranscriptionOptions, default_language, get_stt_engine
+from .stt_prompt import custom_vocabulary, prompt_for_record, update_custom_vocabulary, vocabulary_path
+from .workflow_queue import WORKFLOW_QUEUE
+from .decoding import DecodingStrategyError, resolve_strategy as resolve_decoding_strategy
+from .deadline import DEADLINE_HEADER, DeadlineExceeded, deadline_scope, parse_deadline_seconds, resolve_deadline
+from .history_store import STORE_ERRORS as HISTORY_STORE_ERRORS, open_history_store
+from .filenames import (
+    FilenameError,
+    clean_original_name,
+    content_disposition,
+    export_filename,
+    resolve_collision_policy,
+    sanitize_filename,
+    unique_archive_name,
+)
+from .minutes_templates import (
+    PLACEHOLDERS as MINUTES_PLACEHOLDERS,
+    TemplateError,
+    build_minutes_context,
+    create_template,
+    delete_template,
+    get_template,
+    list_templates,
+    render_template,
+    update_template,
+)
+from .speaker_profiles import (
+    SpeakerProfileError,
+    create_profile,
+    delete_profile,
+    enroll_speakers,
+    list_profiles,
+    match_speakers,
+    public_profile,
+    speaker_embeddings,
+    speaker_labels,
+    update_profile,
+    validate_speaker_names,
+)
+from .embedding_benchmark import (
+    BenchmarkBusy,
+    BenchmarkError,
+    get_current_job as get_embedding_benchmark_job,
+    parse_pairs as parse_benchmark_pairs,
+    resolve_ks as resolve_benchmark_ks,
+    resolve_models as resolve_benchmark_models,
+    run_benchmark as run_embedding_benchmark,
+    start_benchmark_job as start_embedding_benchmark_job,
+    EMBEDDING_BENCHMARK_MAX_RECORDS,
+    TARGETS as BENCHMARK_TARGETS,
+)
+from .summary_bakeoff import (
+    BakeoffError,
+    compare as compare_bakeoff,
+    list_bakeoffs,
+    load_manifest as load_bakeoff,
+    resolve_models as resolve_bakeoff_models,
+    start_bakeoff,
+)
+from .structured_summary import (
+    StructuredSummaryError,
+    discard_structured_summary,
+    from_sections as structured_summary_from_sections,
+    load_structured_summary,
+    save_structured_summary,
+    structured_path_for,
+    summary_format,
+)
+from .summary_stream import (
+    HEARTBEAT as SSE_HEARTBEAT,
+    close_stream as close_summary_stream,
+    format_sse,
+    forward_progress as forward_summary_stream_progress,
+    attach_stream as attach_summary_stream,
+    open_stream as open_summary_stream,
+    token_sink as summary_token_sink,
+)
+from .summary_debug import (
+    SummaryTrace, list_summary_debug_runs, load_summary_debug, summary_debug_dir, write_summary_debug,
+)
+from .event_log import record_event, build_record_timeline, load_events
+from .history_changes import ChangeCursorError, changes_since, parse_cursor, stamp_changes
+from .errors import enrich_error_payload, error_payload
+from .api_tokens import (
+    SCOPES as API_TOKEN_SCOPES,
+    TokenError,
+    auth_cookie,
+    authenticate as authenticate_token,
+    create_token,
+    is_auth_exempt,
+    list_tokens,
+    revoke_token,
+    route_group,
+    scope_allows,
+    token_from_headers,
+    token_required,
+)
+from . import api_tokens
+from . import mock_backends
+from .email_ingest import (
+    EMAIL_IN_WEBHOOK_SIGNING_KEY,
+    EmailIngestError,
+    EmailSignatureError,
+    InboundEmail,
+    from_webhook as parse_inbound_email,
+    processing_steps as email_processing_steps,
+    sender_allowed as email_sender_allowed,
+    start_imap_poller,
+)
+from .folder_watch import (
+    folder_watch_enabled,
+    folder_watch_status,
+    processing_steps as folder_watch_steps,
+    scan as scan_watch_folders,
+    start_folder_watcher,
+)
+from .public_gallery import (
+    PUBLIC_GALLERY_AUDIO,
+    PUBLIC_GALLERY_ENABLED,
+    RangeNotSatisfiable,
+    is_public_request,
+    is_published,
+    parse_byte_range,
+    public_segments,
+    public_view,
+    published_records,
+    render_page as render_gallery_page,
+    render_record_page as render_gallery_record_page,
+)
+from .artifact_store import (
+    collect_garbage as collect_artifact_garbage,
+    detach as detach_artifact,
+    link_existing as link_artifact,
+    share as share_artifact_file,
+    stats as artifact_stats,
+)
+from .disk_guard import ensure_free_space, estimate_workflow_bytes
+from .punctuation import (
+    PUNCTUATION_MODEL,
+    PUNCTUATION_RESTORE_ENABLED,
+    PunctuationError,
+    restore_texts as restore_punctuation,
+    should_restore as should_restore_punctuation,
+)
+from .pagination import CursorError, cursor_page, parse_page_params, sort_key
+from .paragraphs import (
+    EXPORT_FORMATS as PARAGRAPH_EXPORT_FORMATS,
+    TRANSCRIPT_PARAGRAPH_MAX_CHARS,
+    TRANSCRIPT_PARAGRAPH_PAUSE_SECONDS,
+    build_paragraphs,
+    render_paragraphs,
+    segments_from_markdown,
+)
+from .transcript_views import (
+    TranscriptViewError,
+    available_views as transcript_views_of,
+    dropped_counts,
+    parse_view as parse_transcript_view,
+    render_markdown as render_transcript_markdown,
+    title_line as transcript_title_line,
+    verbatim_segments,
+)
+from .ws_outbox import ClientOutbox
+from .ws_protocol import (
+    CLOSE_UNAUTHORIZED,
+    CLOSE_UNSUPPORTED_VERSION,
+    WEBSOCKET_PORT,
+    SUPPORTED_VERSIONS as WS_PROTOCOL_VERSIONS,
+    ClientMessageType,
+    Hello as WsHello,
+    ProtocolError,
+    QueueChanged,
+    RecordUpdated,
+    Notification as WsNotification,
+    TaskProgress,
+    WatchMatched,
+    asyncapi_document,
+    coalesce_key as ws_coalesce_key,
+    encode as encode_ws_message,
+    error_from as ws_error,
+    negotiate_version,
+    parse_client_message,
+)
+from .orphan_cleanup import (
+    ORPHAN_CLEANUP_ON_STARTUP,
+    ORPHAN_MIN_AGE_HOURS,
+    STARTUP_MODES as ORPHAN_STARTUP_MODES,
+    find_orphans,
+    remove_orphans,
+    report as orphan_report,
+)
+from .url_ingest import (
+    UrlIngestCancelled,
+    UrlIngestError,
+    create_job as create_url_job,
+    download_media,
+    get_job as get_url_job,
+    update_job as update_url_job,
+    validate_url,
+)
+from .webhooks import (
+    CONTEXT_FIELDS as WEBHOOK_CONTEXT_FIELDS,
+    EVENTS as WEBHOOK_EVENTS,
+    WebhookError,
+    create_webhook,
+    delete_webhook,
+    deliver as deliver_webhook,
+    get_webhook,
+    list_webhooks,
+    masked as masked_webhook,
+    public_url,
+    update_webhook,
+)
+from .notifications import (
+    NOTIFIERS,
+    STATUSES as NOTIFICATION_STATUSES,
+    NotificationError,
+    build_message as build_notification,
+    channel_info as notification_channels,
+    delete_preferences as delete_notification_preferences,
+    get_preferences as get_notification_preferences,
+    list_deliveries as list_notification_deliveries,
+    list_preferences as list_notification_preferences,
+    notify,
+    send as send_notification,
+    set_desktop_sender,
+    update_preferences as update_notification_preferences,
+    user_targets as notification_user_targets,
+)
+from .grpc_service import (
+    PipelineError,
+    PipelineSources,
+    start_server as start_grpc_service,
+)
+from .graphql_api import (
+    GRAPHQL_ENABLED,
+    WS_SUBPROTOCOL as GRAPHQL_WS_SUBPROTOCOL,
+    GraphQLSources,
+    GraphQLUnavailable,
+    build_schema as build_graphql_schema,
+    execute as execute_graphql,
+    schema_sdl as graphql_schema_sdl,
+    serve_websocket as serve_graphql_websocket,
+)
+from .export_sync import (
+    ExportSyncBusy,
+    ExportSyncError,
+    run_sync as run_export_sync,
+    start_sync_scheduler as start_export_sync_poller,
+    sync_status as export_sync_status,
+)
+from .watch_rules import (
+    CHANNELS as WATCH_CHANNELS,
+    WATCH_SEMANTIC_THRESHOLD,
+    WatchRuleError,
+    create_rule as create_watch_rule,
+    delete_rule as delete_watch_rule,
+    evaluate_rule as evaluate_watch_rule,
+    get_rule as get_watch_rule,
+    list_rules as list_watch_rules,
+    record_match as record_watch_match,
+    update_rule as update_watch_rule,
+)
+from .action_items import (
+    STATUSES as ACTION_ITEM_STATUSES,
+    OPEN_STATUSES as OPEN_ACTION_ITEM_STATUSES,
+    ActionItemError,
+    ActionItemNotFound,
+    list_items as list_action_items,
+    sync_record_items as sync_record_action_items,
+    update_item as update_action_item,
+)
+from .meeting_series import (
+    SeriesError,
+    create_series,
+    delete_series,
+    get_series,
+    link_records as link_series_records,
+    list_series,
+    ordered_entries as series_entries,
+    previous_entries as previous_series_entries,
+    render_context as render_series_context,
+    series_of,
+    update_series,
+)
+from .people_directory import (
+    PeopleError,
+    PersonNotFound,
+    canonical_speaker_names,
+    create_person,
+    delete_person,
+    find_mentions as find_person_mentions,
+    get_person,
+    list_people,
+    people_in_text,
+    person_for_profile,
+    render_people_context,
+    update_person,
+)
+from .lineage import (
+    REVISION_EVENT_TYPES,
+    LineageError,
+    add_parent_links,
+    build_lineage_graph,
+    remove_parent_links,
+)
+from .video_media import (
+    VIDEO_PREP_ENABLED,
+    VIDEO_PREP_QUEUE,
+    VideoProcessingError,
+    extract_audio_track,
+    generate_thumbnail,
+    is_video_candidate,
+    media_paths,
+    mux_subtitles,
+    probe_media,
+    subtitled_video_path,
+)
+from .data_migrations import (
+    DATA_MIGRATIONS_AUTO,
+    Migration,
+    migration_status,
+    pending_migrations,
+    run_migrations,
+)
+from .startup_status import (
+    STARTUP_PRELOAD_STT_MODEL,
+    enable_stream as enable_startup_stream,
+    report as report_startup,
+    startup_status,
+    watch_model_download,
+)
+from .maintenance import (
+    MaintenanceError,
+    blocks_request as blocked_by_maintenance,
+    maintenance_active,
+    maintenance_state,
+    set_maintenance,
+)
+from .subtitles import SUBTITLE_FORMATS, render_subtitles, segments_to_srt, segments_to_text
+from .summary_regen import (
+    RegenerationBusy,
+    find_stale_summaries,
+    get_current_job as get_summary_regen_job,
+    start_regeneration as start_summary_regeneration,
+    start_regeneration_scheduler,
+)
+from .advanced_search import (
+    FilterContext,
+    FilterError,
+    collect_semantic_queries,
+    evaluate_filter,
+    parse_duration_seconds,
+    validate_filter,
+)
+from .archive_stats import DEFAULT_TOP_TAGS, build_archive_stats
+from . import archive_qa
+from .archive_qa import AskError, answer_question, parse_top_k, rank_passages, text_passages, transcript_passages
+from .retranscribe import RetranscribeError, parse_time_range, retranscribe_range
+from .postprocess_rules import (
+    DEFAULT_LANGUAGE as POSTPROCESS_DEFAULT_LANGUAGE,
+    RulePackError,
+    available_languages as postprocess_rule_languages,
+    describe_rule_pack,
+    update_custom_rules,
+)
+from ollama_utils import UnknownModelError, check_ollama_model_available, ensure_ollama_server, require_ollama_model
 import numpy as np
 import os
 
 
 BASE_DIR = Path(getattr(sys, "_MEIPASS", Path(__file__).parent.parent)).resolve()
 DB_BASE_PATH = get_db_base_path(BASE_DIR)
-UPLOAD_DIR = DB_BASE_PATH / "uploads"
-OUTPUT_DIR = DB_BASE_PATH / "whisper_output"
+# 업로드 원본/산출물 디렉터리 구조 (RECORD_LAYOUT: legacy | per_record)
+LAYOUT = get_record_layout(DB_BASE_PATH)
+UPLOAD_DIR = LAYOUT.upload_root
+OUTPUT_DIR = LAYOUT.output_root
 VECTOR_DIR = DB_BASE_PATH / "vector_store"
-HISTORY_FILE = DB_BASE_PATH / "upload_history.json"
+# 업로드 기록 저장소 (HISTORY_BACKEND: sqlite | json, history_store.py)
+HISTORY_STORE = open_history_store(DB_BASE_PATH)
 FILE_REGISTRY_FILE = DB_BASE_PATH / "file_registry.json"
 DELETED_DIR = DB_BASE_PATH / "deleted"
-DELETED_UPLOAD_DIR = DELETED_DIR / "uploads"
-DELETED_OUTPUT_DIR = DELETED_DIR / "whisper_output"
+DELETED_UPLOAD_DIR = LAYOUT.deleted_upload_root
+DELETED_OUTPUT_DIR = LAYOUT.deleted_output_root
 DELETED_VECTOR_DIR = DELETED_DIR / "vector_store"
 SEARCHABLE_SUFFIXES = {".md", ".txt", ".text", ".markdown"}
 TASK_TYPES = ("stt", "embedding", "summary")
+# 벡터 색인 자동 압축 주기 (0이면 비활성화)
+VECTOR_COMPACTION_INTERVAL_HOURS = get_config_value("VECTOR_COMPACTION_INTERVAL_HOURS", 24, float)
 
 # Global dictionary to track running processes
 running_processes = {}
 process_lock = threading.Lock()
 
+# 프로세스 없이 서버 안에서 실행되는 작업(Whisper 추론 등)의 취소 신호와 시작 시각
+task_cancel_events = {}
+task_started_at = {}
+
+# Whisper 상태를 기다리는 작업의 대기 순번과 예상 시작 시각
+task_queue_state = {}
+
+# 최근 끝난 작업 (GET /tasks/recent), 오래된 것부터 버림
+finished_tasks = {}
+FINISHED_TASK_RETENTION = 100
+
+# 작업별 진행 로그 (GET /tasks/{id}/log), 작업 수는 FINISHED_TASK_RETENTION까지만 유지
+task_logs = {}
+TASK_LOG_MAX_EVENTS = 200
+
 # Global dictionary to track task progress
 task_progress = {}
 progress_lock = threading.Lock()
 
+# 진행 상황 이벤트 버스 (WebSocket 브로드캐스트와 롱 폴링이 공유)
+progress_bus = ProgressBus()
+TASK_WAIT_MAX_SECONDS = 60
+
+# 진행 중 작업 저널 (서버 재시작/클라이언트 이탈로 고아가 된 작업 추적)
+task_journal = TaskJournal(DB_BASE_PATH / TASK_JOURNAL_FILENAME)
+UPLOAD_READ_CHUNK_SIZE = 1024 * 1024
+PARTIAL_UPLOAD_SUFFIX = ".part"
+
+# POST /upload/raw (iOS 단축어, Tasker): steps를 지정하지 않았을 때 자동 실행할 단계
+RAW_UPLOAD_STEPS = get_config_value("RAW_UPLOAD_STEPS", "stt,summary", str)
+# 파일 이름 없이 올라온 본문의 Content-Type → 확장자
+RAW_UPLOAD_EXTENSIONS = {
+    "audio/mp4": ".m4a",
+    "audio/m4a": ".m4a",
+    "audio/x-m4a": ".m4a",
+    "audio/aac": ".m4a",
+    "audio/mpeg": ".mp3",
+    "audio/wav": ".wav",
+    "audio/x-wav": ".wav",
+    "audio/webm": ".webm",
+    "audio/ogg": ".ogg",
+    "audio/flac": ".flac",
+    "video/mp4": ".mp4",
+    "video/webm": ".webm",
+}
+
 # WebSocket server setup for real-time progress updates
-connected_clients = set()
+# 연결별 송신 큐 (느린 클라이언트는 진행 상황을 최신 값으로 합쳐 받음)
+connected_clients = {}
 websocket_loop = asyncio.new_event_loop()
 
 
@@ -103,26 +603,180 @@ def resolve_record_path(path_str: str) -> Path:
     return resolve_db_path(path_str, BASE_DIR)
 
 
-async def _send_progress(task_id, message):
-    data = json.dumps({"task_id": task_id, "message": message})
-    if connected_clients:
-        await asyncio.gather(
-            *[client.send(data) for client in list(connected_clients) if not client.closed]
-        )
+def _enqueue_for_clients(data: str, key):
+    for outbox in list(connected_clients.values()):
+        outbox.put(data, key)
 
 
-def broadcast_progress(task_id, message):
+def broadcast_ws_message(message):
+    """Queue a typed protocol message for every connected WebSocket client (never blocks)."""
     if websocket_loop.is_running():
-        asyncio.run_coroutine_threadsafe(_send_progress(task_id, message), websocket_loop)
+        websocket_loop.call_soon_threadsafe(_enqueue_for_clients, encode_ws_message(message), ws_coalesce_key(message))
+
+
+def _send_desktop_notification(message: dict) -> None:
+    broadcast_ws_message(WsNotification(
+        event=message["event"],
+        title=message["title"],
+        text=message["text"],
+        record_id=message.get("record_id"),
+        url=message.get("url"),
+    ))
+
+
+set_desktop_sender(_send_desktop_notification)
+
+
+def websocket_client_stats():
+    """Outbound queue state of each connected WebSocket client."""
+    return [outbox.stats() for outbox in list(connected_clients.values())]
+
+
+def _broadcast_progress_event(event):
+    broadcast_ws_message(TaskProgress(
+        task_id=event["task_id"],
+        message=event["message"],
+        seq=event["seq"],
+        timestamp=event["timestamp"],
+        done=event["done"],
+        queue=event.get("queue"),
+    ))
+
+
+progress_bus.subscribe(_broadcast_progress_event)
+progress_bus.subscribe(forward_summary_stream_progress)
+
+
+def websocket_authorized(websocket) -> bool:
+    """Credential check for a WebSocket handshake (the port listens on every interface)."""
+    request = getattr(websocket, "request", None)
+    headers = getattr(request, "headers", None) or getattr(websocket, "request_headers", None) or {}
+    remote = getattr(websocket, "remote_address", None) or ("",)
+    secret = token_from_headers(headers)
+    if not secret:
+        return not token_required(remote[0], headers)
+    return authenticate_token(secret) is not None
 
 
 async def websocket_handler(websocket):
-    connected_clients.add(websocket)
+    if not websocket_authorized(websocket):
+        await websocket.close(CLOSE_UNAUTHORIZED, "authentication required")
+        return
+    if GRAPHQL_ENABLED and getattr(websocket, "subprotocol", None) == GRAPHQL_WS_SUBPROTOCOL:
+        await serve_graphql_websocket(websocket, graphql_schema())
+        return
+    outbox = ClientOutbox(websocket)
+    connected_clients[websocket] = outbox
+    sender = asyncio.ensure_future(outbox.run())
     try:
-        async for _ in websocket:
-            pass
+        async for raw in websocket:
+            # 클라이언트 메시지: {"type": "hello", "protocol_version": 1, "client_id": "..."}, {"type": "heartbeat", ...}
+            try:
+                message_type, message = parse_client_message(raw)
+                if message_type == ClientMessageType.HELLO:
+                    version = negotiate_version(message.get("protocol_version"))
+                    await websocket.send(encode_ws_message(WsHello(version, list(WS_PROTOCOL_VERSIONS))))
+            except ProtocolError as exc:
+                await websocket.send(encode_ws_message(ws_error(exc)))
+                if exc.code == "unsupported_protocol_version":
+                    await websocket.close(CLOSE_UNSUPPORTED_VERSION, "unsupported protocol version")
+                    return
+                continue
+            client_id = message.get("client_id")
+            if isinstance(client_id, str) and client_id:
+                task_journal.heartbeat(client_id[:100])
     finally:
-        connected_clients.discard(websocket)
+        connected_clients.pop(websocket, None)
+        sender.cancel()
+
+
+def flag_interrupted_tasks():
+    """Flag tasks that were still running when the previous server process died."""
+    for entry in task_journal.recover_interrupted():
+        record_event(entry.get("record_id"), "task_interrupted",
+                     task_id=entry["task_id"], steps=entry.get("steps"))
+        print(f"중단된 작업 발견: {entry['task_id']} (단계: {', '.join(entry.get('steps') or [])})")
+
+
+def _record_detached_tasks(entries):
+    for entry in entries:
+        record_event(entry.get("record_id"), "task_detached", task_id=entry["task_id"])
+
+
+def start_client_heartbeat_watchdog():
+    """Flag running tasks whose frontend client stopped sending heartbeats."""
+    return start_client_watchdog(task_journal, _record_detached_tasks)
+
+
+def remove_partial_uploads() -> int:
+    """Delete ``*.part`` files left by uploads that never finished writing."""
+    removed = 0
+    if not UPLOAD_DIR.exists():
+        return removed
+    for partial in list(UPLOAD_DIR.rglob(f"*{PARTIAL_UPLOAD_SUFFIX}")):
+        try:
+            partial.unlink()
+            removed += 1
+        except OSError as exc:
+            print(f"미완료 업로드 삭제 실패 ({partial.name}): {exc}")
+            continue
+        # 업로드용으로 만든 폴더가 비었으면 함께 삭제 (비어 있지 않으면 rmdir이 실패)
+        folder = partial.parent
+        while folder != UPLOAD_DIR:
+            try:
+                folder.rmdir()
+            except OSError:
+                break
+            folder = folder.parent
+    if removed:
+        print(f"미완료 업로드 파일 {removed}개 정리")
+    return removed
+
+
+def find_orphan_artifacts(min_age_hours: float = ORPHAN_MIN_AGE_HOURS):
+    """Output folders no history record points to (folders in the file registry are kept)."""
+    registry_folders = set()
+    for info in load_file_registry().values():
+        if isinstance(info, dict) and info.get("file_path"):
+            path = resolve_record_path(normalize_record_path(info["file_path"]))
+            registry_folders.add(LAYOUT.folder_for_path(path))
+    return find_orphans(LAYOUT, load_upload_history(), registry_folders, min_age_hours)
+
+
+def reconcile_orphans_on_startup():
+    """Report (or delete, ``ORPHAN_CLEANUP_ON_STARTUP=delete``) orphaned output folders."""
+    mode = ORPHAN_CLEANUP_ON_STARTUP
+    if mode not in ORPHAN_STARTUP_MODES:
+        print(f"알 수 없는 ORPHAN_CLEANUP_ON_STARTUP 값: {mode} (지원: {', '.join(ORPHAN_STARTUP_MODES)})")
+        return
+    if mode == "off":
+        return
+    try:
+        orphans = find_orphan_artifacts()
+    except OSError as exc:
+        print(f"고아 산출물 확인 실패: {exc}")
+        return
+    if not orphans:
+        return
+    size_mb = sum(orphan.size_bytes for orphan in orphans) / 1024 ** 2
+    if mode == "delete":
+        removed, errors = remove_orphans(LAYOUT, orphans)
+        print(f"고아 산출물 폴더 {len(removed)}개 삭제 ({size_mb:.1f} MB)"
+              + (f", 실패 {len(errors)}개" if errors else ""))
+    else:
+        print(f"기록이 없는 산출물 폴더 {len(orphans)}개 ({size_mb:.1f} MB) — "
+              "GET /admin/orphans로 확인, POST /admin/orphans/cleanup으로 정리")
+
+
+def collect_unreferenced_artifacts():
+    """Drop content-addressed blobs that no record file links to any more."""
+    try:
+        result = collect_artifact_garbage()
+    except OSError as exc:
+        print(f"공유 산출물 정리 실패: {exc}")
+        return
+    if result["removed"]:
+        print(f"참조 없는 공유 산출물 {len(result['removed'])}개 정리 ({result['freed_bytes'] / 1024 ** 2:.1f} MB)")
 
 
 def start_websocket_server():
@@ -130,13 +784,57 @@ def start_websocket_server():
     asyncio.set_event_loop(websocket_loop)
 
     async def run_server():
-        async with websockets.serve(websocket_handler, "0.0.0.0", 8765):
-            print("WebSocket server running on ws://localhost:8765")
+        # GraphQL 구독 클라이언트는 graphql-transport-ws 서브프로토콜로 같은 포트에 연결
+        subprotocols = [GRAPHQL_WS_SUBPROTOCOL] if GRAPHQL_ENABLED else None
+        async with websockets.serve(websocket_handler, "0.0.0.0", WEBSOCKET_PORT, subprotocols=subprotocols):
+            print(f"WebSocket server running on ws://localhost:{WEBSOCKET_PORT}")
             await asyncio.Future()  # run forever
 
     websocket_loop.run_until_complete(run_server())
 
 
+def start_vector_cache_preload() -> None:
+    """Load the vectors of the index into memory in the background (VECTOR_CACHE_PRELOAD)."""
+    if not VECTOR_CACHE_PRELOAD or VECTOR_CACHE.limit_bytes == 0:
+        return
+
+    def run():
+        generation = get_index_generation()
+        paths = [VECTOR_DIR / name for _, meta in get_index_snapshot()
+                 if not meta.get("deleted") for name in entry_vector_names(meta)]
+        loaded = preload_vectors(paths, generation)
+        stats = VECTOR_CACHE.stats()
+        print(f"검색용 벡터 {loaded}/{len(paths)}개를 메모리에 불러왔습니다 ({stats['bytes'] / 1024 ** 2:.1f} MB)")
+
+    threading.Thread(target=run, daemon=True).start()
+
+
+def start_index_compaction_scheduler(interval_hours: float = VECTOR_COMPACTION_INTERVAL_HOURS):
+    """Periodically compact the vector index in a daemon thread."""
+    if interval_hours <= 0:
+        print("벡터 색인 자동 압축이 비활성화되어 있습니다.")
+        return None
+
+    def run():
+        while True:
+            time.sleep(interval_hours * 3600)
+            if maintenance_active():
+                print("점검 모드라 벡터 색인 자동 압축을 건너뜁니다.")
+                continue
+            try:
+                report = compact_index()
+                print(
+                    f"벡터 색인 압축 완료: 항목 {report['purged_entries']}개, "
+                    f"파일 {report['removed_vector_files']}개 정리"
+                )
+            except Exception as exc:
+                print(f"벡터 색인 자동 압축 실패: {exc}")
+
+    thread = threading.Thread(target=run, daemon=True)
+    thread.start()
+    return thread
+
+
 def register_process(task_id: str, process):
     """Register a running process for a task."""
     with process_lock:
@@ -148,16 +846,64 @@ def register_process(task_id: str, process):
         print(f"Registered process for task {task_id}, PID: {process.pid}")
 
 
+def _remember_finished_task(task_id: str, start_time: float, cancelled: bool):
+    """Keep finish time and duration of a task; caller holds ``process_lock``."""
+    finished_at = time.time()
+    finished_tasks.pop(task_id, None)
+    finished_tasks[task_id] = {
+        'start_time': start_time,
+        'finished_at': finished_at,
+        'duration': finished_at - start_time,
+        'cancelled': cancelled,
+    }
+    while len(finished_tasks) > FINISHED_TASK_RETENTION:
+        finished_tasks.pop(next(iter(finished_tasks)))
+
+
 def unregister_process(task_id: str):
     """Unregister a process when it completes."""
     with process_lock:
         if task_id in running_processes:
-            del running_processes[task_id]
+            info = running_processes.pop(task_id)
+            _remember_finished_task(task_id, info['start_time'], info['cancelled'])
             print(f"Unregistered process for task {task_id}")
 
+def register_task(task_id: str) -> threading.Event:
+    """Register an in-process task and return its cancellation event."""
+    with process_lock:
+        task_started_at.setdefault(task_id, time.time())
+        return task_cancel_events.setdefault(task_id, threading.Event())
+
+
+def unregister_task(task_id: str):
+    """Forget the cancellation event of a finished in-process task and record its finish time."""
+    with process_lock:
+        cancel_event = task_cancel_events.pop(task_id, None)
+        start_time = task_started_at.pop(task_id, None)
+        was_queued = task_queue_state.pop(task_id, None) is not None
+        if start_time is not None:
+            _remember_finished_task(task_id, start_time, bool(cancel_event and cancel_event.is_set()))
+    if was_queued:
+        broadcast_queue_changed()
+
+
+def get_cancel_event(task_id: str):
+    """Return the cancellation event for a task, if it is registered."""
+    if not task_id:
+        return None
+    with process_lock:
+        return task_cancel_events.get(task_id)
+
+
 def cancel_task(task_id: str):
-    """Cancel a running task by terminating its process."""
+    """Cancel a running task by signalling its inference or terminating its process."""
     with process_lock:
+        cancel_event = task_cancel_events.get(task_id)
+        if cancel_event is not None:
+            # Whisper 추론 훅이 이 신호를 확인하여 수 초 내에 중단한다
+            cancel_event.set()
+            print(f"Cancellation requested for task {task_id}")
+            return True
         if task_id in running_processes:
             task_info = running_processes[task_id]
             task_info['cancelled'] = True
@@ -189,20 +935,105 @@ def cancel_task(task_id: str):
 def is_task_cancelled(task_id: str):
     """Check if a task has been cancelled."""
     with process_lock:
+        cancel_event = task_cancel_events.get(task_id)
+        if cancel_event is not None:
+            return cancel_event.is_set()
         if task_id in running_processes:
             return running_processes[task_id]['cancelled']
         return False
 
 
-def update_task_progress(task_id: str, message: str):
-    """Update progress message for a task."""
+def update_task_progress(task_id: str, message: str, queue: dict = None):
+    """Update progress message for a task (``queue`` while it waits for a Whisper state)."""
     with progress_lock:
         task_progress[task_id] = {
             'message': message,
             'timestamp': time.time()
         }
+        if queue:
+            task_progress[task_id]['queue'] = queue
+        log = task_logs.setdefault(task_id, {})
+        log.setdefault('events', deque(maxlen=TASK_LOG_MAX_EVENTS)).append({
+            'message': message,
+            'timestamp': task_progress[task_id]['timestamp'],
+        })
+        while len(task_logs) > FINISHED_TASK_RETENTION:
+            task_logs.pop(next(iter(task_logs)))
         print(f"Task {task_id}: {message}")
-    broadcast_progress(task_id, message)
+    progress_bus.publish(task_id, message, queue=queue)
+
+
+def note_task_log(task_id: str, **fields):
+    """Attach ``record_id``/``summary_debug_dir`` to a task's log."""
+    if not task_id:
+        return
+    with progress_lock:
+        task_logs.setdefault(task_id, {}).update(fields)
+
+
+def get_task_log(task_id: str):
+    """Progress log of a task with its status, or ``None`` when the task is unknown."""
+    with progress_lock:
+        log = dict(task_logs.get(task_id) or {})
+    with process_lock:
+        if task_id in task_queue_state:
+            status = 'queued'
+        elif task_id in task_started_at or task_id in running_processes:
+            status = 'running'
+        elif task_id in finished_tasks:
+            status = 'cancelled' if finished_tasks[task_id]['cancelled'] else 'finished'
+        elif log:
+            status = 'unknown'
+        else:
+            return None
+    return {
+        'task_id': task_id,
+        'record_id': log.get('record_id'),
+        'status': status,
+        'events': list(log.get('events') or []),
+        'summary_debug_dir': log.get('summary_debug_dir'),
+    }
+
+
+QUEUE_STEP_LABELS = {"stt": "변환", "summary": "요약", "embedding": "색인"}
+
+
+def update_task_queue(task_id: str, position: int, estimated_start: float = None, category: str = "stt"):
+    """Record a task's place in the workflow/Whisper queue and push it as a progress event."""
+    if not task_id:
+        return
+    with process_lock:
+        if position:
+            task_queue_state[task_id] = {
+                'queue_position': position,
+                'estimated_start_time': estimated_start,
+                'queue_category': category,
+            }
+        else:
+            task_queue_state.pop(task_id, None)
+    broadcast_queue_changed()
+    label = QUEUE_STEP_LABELS.get(category, category)
+    if not position:
+        update_task_progress(task_id, f"{label} 대기 끝, {label} 시작")
+        return
+    eta = f", 예상 시작 {datetime.fromtimestamp(estimated_start).strftime('%H:%M:%S')}" if estimated_start else ""
+    update_task_progress(task_id, f"{label} 대기 중 ({position}번째{eta})", queue=task_queue_state.get(task_id))
+
+
+def wait_for_workflow_slot(queue_key: str, category: str, task_id: str = None) -> bool:
+    """Queue a workflow step (see ``workflow_queue.py``); ``False`` when the task was cancelled while waiting."""
+    def on_queued(position, estimated_start):
+        update_task_queue(task_id, position, estimated_start, category)
+
+    return WORKFLOW_QUEUE.acquire(queue_key, category, on_queued=on_queued if task_id else None,
+                                  cancel_event=get_cancel_event(task_id))
+
+
+def broadcast_queue_changed():
+    """Push the queue (waiting tasks by position) to WebSocket clients."""
+    with process_lock:
+        tasks = [{"task_id": task_id, **state} for task_id, state in task_queue_state.items()]
+    broadcast_ws_message(QueueChanged(tasks=sorted(tasks, key=lambda task: task["queue_position"])))
 
 
 def get_task_progress(task_id: str):
@@ -216,19 +1047,40 @@ def clear_task_progress(task_id: str):
     with progress_lock:
         if task_id in task_progress:
             del task_progress[task_id]
+    progress_bus.finish(task_id)
 
 def get_running_tasks():
     """Get information about currently running tasks."""
+    now = time.time()
     with process_lock:
-        return {
+        tasks = {
+            task_id: {
+                'pid': None,
+                'start_time': start_time,
+                'cancelled': task_cancel_events[task_id].is_set() if task_id in task_cancel_events else False,
+                'duration': now - start_time,
+                'status': 'queued' if task_id in task_queue_state else 'running',
+                **task_queue_state.get(task_id, {}),
+            }
+            for task_id, start_time in task_started_at.items()
+        }
+        tasks.update({
             task_id: {
                 'pid': info['process'].pid,
                 'start_time': info['start_time'],
                 'cancelled': info['cancelled'],
-                'duration': time.time() - info['start_time']
+                'duration': now - info['start_time'],
+                'status': 'running',
             }
             for task_id, info in running_processes.items()
-        }
+        })
+        return tasks
+
+
+def get_finished_tasks():
+    """Recently finished tasks, newest first, with ``finished_at`` and ``duration``."""
+    with process_lock:
+        return [{'task_id': task_id, **info} for task_id, info in reversed(finished_tasks.items())]
 
 
 def get_file_type(file_path: Path):
@@ -252,19 +1104,24 @@ def get_file_type(file_path: Path):
         return 'unknown'
 
 
-def get_audio_duration(file_path: Path):
-    """Get audio file duration using ffprobe."""
-    try:
-        result = subprocess.run([
-            'ffprobe', '-v', 'quiet', '-show_entries', 'format=duration',
-            '-of', 'csv=p=0', str(file_path)
-        ], capture_output=True, text=True, check=True)
-        duration = float(result.stdout.strip())
-        minutes = int(duration // 60)
-        seconds = int(duration % 60)
-        return f"{minutes:02d}:{seconds:02d}"
-    except (subprocess.CalledProcessError, ValueError, FileNotFoundError):
+def format_duration(seconds):
+    """``MM:SS`` display form of a duration (the record's ``duration``)."""
+    if seconds is None:
         return None
+    return f"{int(seconds // 60):02d}:{int(seconds % 60):02d}"
+
+
+def media_metadata(file_path: Path) -> dict:
+    """``duration_seconds``, ``file_size`` and ``audio_format`` of a media file (ffprobe)."""
+    metadata = {"file_size": file_path.stat().st_size, "duration_seconds": None, "audio_format": None}
+    try:
+        info = probe_media(file_path)
+    except VideoProcessingError:
+        return metadata
+    if info["duration"] is not None:
+        metadata["duration_seconds"] = round(info["duration"], 3)
+    metadata["audio_format"] = info["audio_format"]
+    return metadata
 
 
 def compute_file_hash(data: bytes) -> str:
@@ -272,6 +1129,9 @@ def compute_file_hash(data: bytes) -> str:
     return hashlib.sha256(data).hexdigest()
 
 
+RECORD_METADATA_FIELDS = ("duration_seconds", "file_size", "audio_format", "language", "language_confidence")
+
+
 def _ensure_record_schema(record: dict) -> bool:
     """Ensure an upload history record has the expected structure."""
     updated = False
@@ -304,31 +1164,48 @@ def _ensure_record_schema(record: dict) -> bool:
         record["deleted_assets"] = {}
         updated = True
 
+    # 이전 기록은 생성 시각을 마지막 수정 시각으로 간주 (이후 저장 시 자동 갱신)
+    if not record.get("updated_at"):
+        record["updated_at"] = record.get("timestamp")
+        updated = True
+
+    # 길이/크기/형식은 업로드 때, 언어는 전사 후 채워짐 (모르면 null)
+    for field in RECORD_METADATA_FIELDS:
+        if field not in record:
+            record[field] = None
+            updated = True
+
     return updated
 
 
 def load_upload_history():
-    """Load upload history from JSON file and normalize record schema."""
-    if HISTORY_FILE.exists():
-        try:
-            with open(HISTORY_FILE, 'r', encoding='utf-8') as f:
-                history = json.load(f)
+    """Load upload history from the history store and normalize record schema."""
+    try:
+        history = HISTORY_STORE.load()
+    except HISTORY_STORE_ERRORS as e:
+        print(f"업로드 기록을 읽지 못했습니다: {e}")
+        return []
+
+    updated = False
+    for record in history:
+        if _ensure_record_schema(record):
+            updated = True
+
+    if updated:
+        save_upload_history(history)
 
-            if not isinstance(history, list):
-                return []
+    return history
 
-            updated = False
-            for record in history:
-                if _ensure_record_schema(record):
-                    updated = True
 
-            if updated:
-                save_upload_history(history)
+# GET /history?sort= 값 → 기록 필드
+HISTORY_SORT_FIELDS = {"created": "timestamp", "updated": "updated_at", "completed": "completed_at"}
+# GET /history?limit= 최댓값
+HISTORY_PAGE_MAX = 500
 
-            return history
-        except (json.JSONDecodeError, IOError):
-            return []
-    return []
+
+def record_namespace(record: dict) -> str:
+    """Vector index namespace of a record (default namespace when unset)."""
+    return record.get("namespace") or entry_namespace(None)
 
 
 def get_active_history(history: list[dict] | None = None) -> list[dict]:
@@ -339,15 +1216,32 @@ def get_active_history(history: list[dict] | None = None) -> list[dict]:
 
 
 def save_upload_history(history):
-    """Save upload history to JSON file."""
+    """Save upload history to the history store, stamping changed records for the change feed."""
     try:
-        with open(HISTORY_FILE, 'w', encoding='utf-8') as f:
-            json.dump(history, f, ensure_ascii=False, indent=2)
-    except IOError:
-        pass
-
-def add_upload_record(file_path: Path, file_type: str, duration: str = None, file_hash: str = None):
-    """Add a new upload record to history."""
+        # 비교용으로만 읽음 (저장할 목록이 읽혀 온 기록을 기준으로 병합 저장)
+        previous = HISTORY_STORE.load(remember=False)
+        last_seq = max([r.get("change_seq") or 0 for r in previous if isinstance(r, dict)], default=0)
+        stamp_changes(previous, history)
+        HISTORY_STORE.save(history)
+    except HISTORY_STORE_ERRORS as e:
+        print(f"업로드 기록을 저장하지 못했습니다: {e}")
+        return
+    current_ids = {record.get("id") for record in history}
+    for record in history:
+        if (record.get("change_seq") or 0) > last_seq:
+            broadcast_ws_message(RecordUpdated(record["id"], "removed" if record.get("deleted") else "updated",
+                                               record["change_seq"]))
+    for record in previous:
+        if isinstance(record, dict) and record.get("id") and record["id"] not in current_ids:
+            broadcast_ws_message(RecordUpdated(record["id"], "removed"))
+
+def add_upload_record(file_path: Path, file_type: str, duration: str = None, file_hash: str = None,
+                      tags: list = None, **metadata):
+    """Add a new upload record to history.
+
+    ``metadata`` (``source_url``, ``email_sender``, ...) is stored on the
+    record and in its ``uploaded`` event.
+    """
     history = load_upload_history()
 
     record = {
@@ -357,26 +1251,32 @@ def add_upload_record(file_path: Path, file_type: str, duration: str = None, fil
         "file_type": file_type,
         "duration": duration,
         "file_path": to_record_path(file_path),
-        "folder_name": file_path.parent.name,  # UUID folder name
+        "folder_name": LAYOUT.folder_for_path(file_path),  # UUID folder name
         "completed_tasks": {task: False for task in TASK_TYPES},
         "download_links": {},
         "title_summary": "",
-        "tags": [],
+        "tags": list(tags or []),
         "file_hash": file_hash,
         "deleted": False,
         "deleted_at": None,
         "deleted_assets": {}
     }
+    record["updated_at"] = record["timestamp"]
+    record.update({key: value for key, value in metadata.items() if value is not None})
 
     _ensure_record_schema(record)
 
     history.insert(0, record)  # Add to beginning (most recent first)
 
-    # Keep only last 100 records
-    if len(history) > 100:
-        history = history[:100]
-
     save_upload_history(history)
+    record_event(
+        record["id"],
+        "uploaded",
+        filename=record["filename"],
+        file_type=file_type,
+        duration=duration,
+        **metadata,
+    )
     return record
 
 def load_file_registry():
@@ -482,6 +1382,17 @@ def _timestamp_to_sort_key(timestamp_str: str) -> float:
         return float('-inf')
 
 
+def search_generation() -> str:
+    """Token for /search response caching: vector index plus history/registry state."""
+    parts = [get_index_generation(), HISTORY_STORE.generation()]
+    try:
+        stat = FILE_REGISTRY_FILE.stat()
+        parts.append(f"{stat.st_mtime_ns}-{stat.st_size}")
+    except FileNotFoundError:
+        parts.append("0")
+    return ":".join(parts)
+
+
 def _collect_searchable_documents():
     """Return documents eligible for keyword search and similarity mapping."""
     documents = []
@@ -539,6 +1450,7 @@ def _collect_keyword_matches(query: str, documents, history_map, limit: int = 5)
 
         matches.append({
             "file_uuid": doc["uuid"],
+            "record_id": doc["info"].get("record_id"),
             "file": doc["relative_path"],
             "display_name": doc["info"].get("original_filename") or Path(doc["relative_path"]).name,
             "count": count,
@@ -550,6 +1462,91 @@ def _collect_keyword_matches(query: str, documents, history_map, limit: int = 5)
     matches.sort(key=lambda item: (-item["count"], -_timestamp_to_sort_key(item.get("uploaded_at"))))
     return matches[:limit]
 
+def _read_record_text(record: dict, source: str) -> str:
+    """Return the STT or summary text linked from a history record."""
+    link = (record.get("download_links") or {}).get(source)
+    if not link:
+        return ""
+    file_path, _, _, _ = resolve_file_identifier(link)
+    if not file_path or not file_path.exists():
+        return ""
+    return read_text_with_fallback(file_path)
+
+
+def _record_language(record: dict) -> str | None:
+    """Transcript language stored in a record's segments document."""
+    document = load_record_segments(record)
+    return (document or {}).get("language") or record.get("language")
+
+
+def _record_id_for_output_path(rel_path: str, folder_map: dict[str, str]) -> str | None:
+    """Map a stored output path (DB/whisper_output/<folder>/...) to its record ID."""
+    try:
+        relative = resolve_record_path(rel_path).relative_to(OUTPUT_DIR.resolve())
+    except (ValueError, OSError):
+        return None
+    if not relative.parts:
+        return None
+    return folder_map.get(relative.parts[0])
+
+
+def _semantic_scores_by_record(query: str, history: list[dict], top_k: int = 200) -> dict[str, float]:
+    """Run a vector search and keep the best score per record."""
+    folder_map = {
+        record.get("folder_name"): record.get("id")
+        for record in history
+        if record.get("folder_name")
+    }
+    scores: dict[str, float] = {}
+    for hit in search_vectors(query, BASE_DIR, top_k=top_k):
+        record_id = _record_id_for_output_path(hit.get("file", ""), folder_map)
+        if not record_id:
+            continue
+        score = float(hit.get("score", 0.0))
+        if score > scores.get(record_id, float("-inf")):
+            scores[record_id] = score
+    return scores
+
+
+def search_records_advanced(filter_tree: dict, limit: int = 20) -> dict:
+    """Evaluate a filter tree against active history records."""
+    validate_filter(filter_tree)
+
+    history = get_active_history()
+    semantic_scores = {
+        query: _semantic_scores_by_record(query, history)
+        for query in set(collect_semantic_queries(filter_tree))
+    }
+    context = FilterContext(_read_record_text, semantic_scores)
+
+    results = []
+    for record in history:
+        matches: dict = {}
+        if not evaluate_filter(filter_tree, record, context, matches):
+            continue
+        best_score = max(matches.get("semantic", {}).values(), default=None)
+        results.append({
+            "id": record.get("id"),
+            "filename": record.get("filename"),
+            "timestamp": record.get("timestamp"),
+            "duration": record.get("duration"),
+            "tags": record.get("tags", []),
+            "title_summary": record.get("title_summary", ""),
+            "download_links": record.get("download_links", {}),
+            "score": best_score,
+            "matches": matches,
+        })
+
+    results.sort(
+        key=lambda item: (
+            item["score"] if item["score"] is not None else float("-inf"),
+            _timestamp_to_sort_key(item.get("timestamp")),
+        ),
+        reverse=True,
+    )
+    return {"total": len(results), "results": results[:limit]}
+
+
 def register_file(file_path: str, record_id: str, task_type: str, original_filename: str = None):
     """Register a file with UUID and return the file UUID."""
     registry = load_file_registry()
@@ -577,11 +1574,12 @@ def get_file_by_uuid(file_uuid: str):
     registry = load_file_registry()
     return registry.get(file_uuid)
 
-def migrate_existing_files():
+def migrate_existing_files(dry_run: bool = False) -> dict:
     """Migrate existing files from upload history to file registry."""
-    history = load_upload_history()
+    history = HISTORY_STORE.load(remember=False) if dry_run else load_upload_history()
     registry = load_file_registry()
     updated = False
+    registered = 0
     
     for record in history:
         if record.get("deleted"):
@@ -605,6 +1603,9 @@ def migrate_existing_files():
                     # Register the file and update download link
                     full_path = resolve_record_path(file_path)
                     if full_path.exists():
+                        registered += 1
+                        if dry_run:
+                            continue
                         file_uuid = register_file(file_path, record_id, task_type, os.path.basename(full_path))
                         # Update the download link to use UUID
                         record["download_links"][task_type] = f"/download/{file_uuid}"
@@ -613,6 +1614,95 @@ def migrate_existing_files():
     if updated:
         save_upload_history(history)
         print("기존 파일들이 레지스트리에 등록되었습니다.")
+    return {"registered": registered}
+
+
+def _migrate_record_metadata(dry_run: bool = False) -> dict:
+    """Fill duration/size/audio format from the uploaded files and the language from the segments."""
+    history = HISTORY_STORE.load(remember=not dry_run)
+    filled = 0
+    for record in history:
+        _ensure_record_schema(record)
+        if record.get("deleted"):
+            continue
+        fields = {}
+        file_path = resolve_record_path(normalize_record_path(record.get("file_path") or ""))
+        if record.get("file_size") is None and file_path.is_file():
+            if record.get("file_type") == "audio":
+                fields.update({key: value for key, value in media_metadata(file_path).items() if value is not None})
+            else:
+                fields["file_size"] = file_path.stat().st_size
+        if record.get("language") is None and (record.get("completed_tasks") or {}).get("stt"):
+            language = (load_record_segments(record) or {}).get("language")
+            if language:
+                fields["language"] = language
+        if fields:
+            record.update(fields)
+            filled += 1
+    if filled and not dry_run:
+        save_upload_history(history)
+    return {"records": len(history), "filled": filled}
+
+
+def _migrate_record_schema(dry_run: bool = False) -> dict:
+    """Fill fields added to history records since they were written (see ``_ensure_record_schema``)."""
+    history = HISTORY_STORE.load(remember=not dry_run)
+    updated = sum(1 for record in history if _ensure_record_schema(record))
+    if updated and not dry_run:
+        save_upload_history(history)
+    return {"records": len(history), "updated": updated}
+
+
+# 디스크 데이터 형식 마이그레이션 (형식별로 버전 순서대로, data_migrations 참고)
+DATA_MIGRATIONS = [
+    Migration("history", 1, "기록에 빠진 작업 상태/시각 필드 채우기", _migrate_record_schema,
+              backup_paths=lambda: [HISTORY_STORE.path]),
+    Migration("file_registry", 1, "경로 다운로드 링크를 파일 레지스트리 UUID 링크로 바꾸기", migrate_existing_files,
+              backup_paths=lambda: [HISTORY_STORE.path, FILE_REGISTRY_FILE]),
+    Migration("segments", 1, "세그먼트 파일을 현재 스키마 버전으로 올리기",
+              lambda dry_run: migrate_all_segments(DB_BASE_PATH, dry_run=dry_run),
+              backup_paths=lambda: pending_segment_files(DB_BASE_PATH)),
+    Migration("vector_index", 1, "색인 키 정규화와 VECTOR_INDEX_SHARDING 배치 적용", migrate_index,
+              backup_paths=lambda: [INDEX_FILE, SHARD_DIR]),
+    Migration("history", 2, "기록에 길이/파일 크기/오디오 형식/언어 채우기", _migrate_record_metadata,
+              backup_paths=lambda: [HISTORY_STORE.path]),
+]
+
+
+def finish_startup() -> None:
+    """Preload STARTUP_PRELOAD_STT_MODEL (if set) once the port is open, then report ready."""
+    model = STARTUP_PRELOAD_STT_MODEL
+    if model:
+        try:
+            engine = get_stt_engine()
+            # 원격/모의 백엔드는 내려받거나 불러올 모델이 없음
+            if engine.name == "whisper":
+                with watch_model_download(model):
+                    engine.preload(model)
+        except Exception as e:
+            # 미리 불러오지 못해도 서버는 쓸 수 있음 (첫 전사 때 다시 로드)
+            print(f"STT 모델 미리 불러오기 실패 ({model}): {e}")
+            report_startup("ready", model_error=str(e))
+            return
+    report_startup("ready")
+
+
+def run_startup_migrations() -> None:
+    """Run pending data migrations at startup (only report them when DATA_MIGRATIONS_AUTO is off)."""
+    pending = pending_migrations(DATA_MIGRATIONS)
+    if not pending:
+        return
+    if not DATA_MIGRATIONS_AUTO:
+        print(f"대기 중인 데이터 마이그레이션 {len(pending)}개: {', '.join(m.id for m in pending)} "
+              "(POST /admin/migrations/run으로 실행)")
+        return
+    result = run_migrations(DATA_MIGRATIONS)
+    for entry in result["ran"]:
+        print(f"데이터 마이그레이션 {entry['id']} 완료: {entry['report']}"
+              + (f" (백업: {entry['backup']})" if entry["backup"] else ""))
+    if result["failed"]:
+        print(f"데이터 마이그레이션 {result['failed']['id']} 실패: {result['failed']['error']} "
+              "(이후 단계는 다음 시작 때 다시 시도)")
 
 def update_task_completion(record_id: str, task: str, file_path: str):
     """Update task completion status and register file with UUID."""
@@ -628,6 +1718,19 @@ def update_task_completion(record_id: str, task: str, file_path: str):
                 return file_uuid
             record["completed_tasks"][task] = True
             record["download_links"][task] = download_url
+            record["completed_at"] = datetime.now().isoformat()
+            if task == "stt":
+                # Whisper가 감지(또는 지정받은) 언어를 기록에도 저장, 지정받은 언어는 확률 없음
+                document = load_record_segments(record) or {}
+                if document.get("language"):
+                    record["language"] = document["language"]
+                record["language_confidence"] = (document.get("language_detection") or {}).get("confidence")
+            # 다시 만든 산출물은 더 이상 오래된 상태가 아님
+            stale = [item for item in record.get("stale_artifacts") or [] if item != task]
+            if stale:
+                record["stale_artifacts"] = stale
+            else:
+                record.pop("stale_artifacts", None)
             break
     
     save_upload_history(history)
@@ -644,6 +1747,155 @@ def update_title_summary(record_id: str, summary: str):
             break
     save_upload_history(history)
 
+def mark_artifacts_stale(record_id: str, tasks) -> list[str]:
+    """Flag completed artifacts built from a transcript that has since changed."""
+    history = load_upload_history()
+    for record in history:
+        if record["id"] == record_id:
+            completed = record.get("completed_tasks") or {}
+            stale = set(record.get("stale_artifacts") or [])
+            stale.update(task for task in tasks if completed.get(task))
+            if stale:
+                record["stale_artifacts"] = sorted(stale)
+            save_upload_history(history)
+            return sorted(stale)
+    return []
+
+def update_summary_prompt_version(record_id: str, prompt_version: str):
+    """Remember which prompt version produced the record's summary."""
+    history = load_upload_history()
+    for record in history:
+        if record["id"] == record_id:
+            if record.get("deleted"):
+                return
+            record["summary_prompt_version"] = prompt_version
+            break
+    save_upload_history(history)
+
+def update_video_metadata(record_id: str, **fields):
+    """Store video preparation results (thumbnail, audio track, status) on a record."""
+    history = load_upload_history()
+    for record in history:
+        if record["id"] == record_id:
+            if record.get("deleted"):
+                return
+            record.update(fields)
+            break
+    save_upload_history(history)
+
+def prepare_video_record(record_id: str, file_path: Path):
+    """Background job: poster thumbnail and audio track for a video upload."""
+    output_dir = LAYOUT.output_dir(LAYOUT.folder_for_path(file_path))
+    thumbnail_path, audio_path = media_paths(output_dir, file_path.stem)
+    try:
+        info = probe_media(file_path)
+        if not info["has_video"]:
+            update_video_metadata(record_id, is_video=False, video_prep="skipped")
+            return
+        generate_thumbnail(file_path, thumbnail_path, info["duration"])
+        fields = {"is_video": True, "thumbnail": to_record_path(thumbnail_path)}
+        if info["has_audio"]:
+            extract_audio_track(file_path, audio_path)
+            fields["audio_track"] = to_record_path(audio_path)
+        update_video_metadata(record_id, video_prep="ready", video_prep_error=None, **fields)
+        record_event(record_id, "video_prepared", thumbnail=thumbnail_path.name,
+                     audio_track=audio_path.name if info["has_audio"] else None)
+    except VideoProcessingError as e:
+        print(f"영상 전처리 실패 ({file_path.name}): {e}")
+        update_video_metadata(record_id, video_prep="failed", video_prep_error=str(e))
+
+def queue_video_preparation(record: dict, file_path: Path) -> bool:
+    """Queue thumbnail/audio extraction for a video upload (rate-limited worker)."""
+    if not VIDEO_PREP_ENABLED or not is_video_candidate(file_path):
+        return False
+    update_video_metadata(record["id"], video_prep="pending")
+    VIDEO_PREP_QUEUE.submit(prepare_video_record, record["id"], file_path)
+    return True
+
+def register_uploaded_file(file_path: Path, file_hash: str, tags: list = None, **metadata) -> dict:
+    """Create the history record for a file saved in its upload folder."""
+    file_type = get_file_type(file_path)
+
+    # 오디오/영상이면 길이와 형식을 ffprobe로 읽어 기록에 저장
+    if file_type == 'audio':
+        media = media_metadata(file_path)
+    else:
+        media = {"file_size": file_path.stat().st_size}
+    duration = format_duration(media.get("duration_seconds"))
+
+    record = add_upload_record(file_path, file_type, duration, file_hash, tags, **media, **metadata)
+
+    # 영상이면 썸네일/오디오 트랙을 백그라운드에서 준비
+    if file_type == 'audio':
+        queue_video_preparation(record, file_path)
+    return record
+
+def store_uploaded_bytes(filename: str, data: bytes, history: list, tags: list = None, **metadata) -> dict:
+    """Save uploaded file content and create its record (``/upload`` response entry).
+
+    Content already in ``history`` (same hash) is not stored again; new
+    records are inserted into ``history`` so later files of the same request
+    are checked against them too.
+    """
+    file_hash = compute_file_hash(data)
+    existing = next((r for r in history if r.get('file_hash') == file_hash), None)
+    if existing:
+        return {"duplicate": True, "original_record_id": existing["id"], "filename": filename}
+
+    uid = uuid.uuid4().hex
+    save_dir = LAYOUT.upload_dir(uid)
+    save_dir.mkdir(parents=True, exist_ok=True)
+    # 디스크에는 정리한 이름으로 저장하고, 보낸 그대로의 이름은 기록에 보관 (내보내기 파일명)
+    file_path = save_dir / sanitize_filename(filename, default="upload")
+    metadata.setdefault("original_filename", clean_original_name(filename) or None)
+
+    # 쓰는 도중 종료되면 .part만 남아 다음 시작 시 정리됨
+    partial_path = file_path.with_name(file_path.name + PARTIAL_UPLOAD_SUFFIX)
+    with open(partial_path, "wb") as output_file:
+        output_file.write(data)
+    partial_path.replace(file_path)
+
+    print(f"File saved successfully: {file_path}")
+
+    record = register_uploaded_file(file_path, file_hash, tags, **metadata)
+    history.insert(0, record)
+    return {"file_path": to_record_path(file_path), "file_type": record["file_type"], "record_id": record["id"]}
+
+def resume_video_preparation():
+    """Re-queue video uploads whose preparation was interrupted by a restart."""
+    for record in get_active_history():
+        if record.get("video_prep") == "pending":
+            file_path = resolve_record_path(normalize_record_path(record.get("file_path", "")))
+            if file_path.exists():
+                queue_video_preparation(record, file_path)
+
+def stt_input_path(record_id: str, file_path: Path) -> Path:
+    """Use the pre-extracted audio track of a video upload when available."""
+    if not record_id:
+        return file_path
+    record = next((r for r in load_upload_history() if r.get("id") == record_id), None)
+    audio_track = (record or {}).get("audio_track")
+    if audio_track:
+        audio_path = resolve_record_path(normalize_record_path(audio_track))
+        if audio_path.exists():
+            return audio_path
+    return file_path
+
+def set_record_published(record_id: str, published: bool, public_title: str = None):
+    """Show (or hide) a record in the public gallery; returns the record or ``None``."""
+    history = load_upload_history()
+    record = next((r for r in history if r.get("id") == record_id and not r.get("deleted")), None)
+    if record is None:
+        return None
+    if published and not record.get("published"):
+        record["published_at"] = datetime.now().isoformat()
+    record["published"] = published
+    if public_title is not None:
+        record["public_title"] = public_title.strip() or None
+    save_upload_history(history)
+    record_event(record_id, "published" if published else "unpublished", public_title=record.get("public_title"))
+    return record
+
 def update_filename(record_id: str, new_filename: str):
     """Update filename for a record."""
     history = load_upload_history()
@@ -651,17 +1903,566 @@ def update_filename(record_id: str, new_filename: str):
         if record["id"] == record_id:
             if record.get("deleted"):
                 return
+            old_filename = record.get("filename")
             record["filename"] = new_filename
+            record_event(record_id, "renamed", old_filename=old_filename, filename=new_filename)
             break
     save_upload_history(history)
 
-def generate_and_store_title_summary(record_id: str, file_path: Path, model: str = None):
-    """Generate one-line summary and store it."""
+def load_record_segments(record: dict):
+    """Load the segments document of a record's STT result (``None`` if absent)."""
+    stt_link = (record.get("download_links") or {}).get("stt")
+    if not stt_link:
+        return None
+    stt_path, _, _, _ = resolve_file_identifier(stt_link)
+    if not stt_path:
+        return None
     try:
-        summary = generate_one_line_summary(file_path, model=model)
-        update_title_summary(record_id, summary)
-    except Exception as e:
-        print(f"One-line summary generation failed: {e}")
+        return load_segments(segments_path_for(stt_path), persist_migration=False)
+    except SegmentSchemaError:
+        return None
+
+
+def update_speaker_names(record_id: str, names: dict, source: str = "manual"):
+    """Merge ``{label: name}`` into a record's speaker names (empty name = unset)."""
+    history = load_upload_history()
+    for record in history:
+        if record["id"] == record_id:
+            if record.get("deleted"):
+                return None
+            speaker_names = dict(record.get("speaker_names") or {})
+            for label, name in names.items():
+                if name:
+                    speaker_names[label] = name
+                else:
+                    speaker_names.pop(label, None)
+            record["speaker_names"] = speaker_names
+            # 고급 검색의 speaker 필터가 이름으로 찾을 수 있도록 목록도 유지
+            record["speakers"] = sorted(set(speaker_names.values()))
+            record_event(record_id, "speakers_renamed", names=names, source=source)
+            save_upload_history(history)
+            return record
+    return None
+
+
+def auto_label_speakers(record_id: str, stt_file: Path):
+    """Label speakers of a new transcript that match known voice profiles."""
+    try:
+        document = load_segments(segments_path_for(stt_file), persist_migration=False)
+        matches = match_speakers(speaker_embeddings(document))
+    except Exception as e:
+        print(f"화자 자동 인식 실패: {e}")
+        return {}
+    if matches:
+        # 인물 디렉터리에 연결된 프로필은 그 사람의 이름으로 지정
+        names = {label: (person_for_profile(m["profile_id"]) or m)["name"] for label, m in matches.items()}
+        update_speaker_names(record_id, canonical_speaker_names(names), source="profile_match")
+    return matches
+
+
+def render_minutes(record_id: str, template_id: str = None):
+    """Render meeting minutes for a record with a template.
+
+    Writes ``{stem}.minutes.md`` next to the summary and returns
+    ``(minutes_path, download_url)``. Raises :class:`TemplateError` when the
+    template is unknown or the record has no summary yet.
+    """
+    template = get_template(template_id)
+    history = load_upload_history()
+    record = next((item for item in history if item.get("id") == record_id and not item.get("deleted")), None)
+    if not record:
+        raise TemplateError("기록을 찾을 수 없습니다.")
+
+    links = record.get("download_links") or {}
+    summary_path = resolve_file_identifier(links["summary"])[0] if links.get("summary") else None
+    if not summary_path or not summary_path.exists():
+        raise TemplateError("요약이 없는 기록입니다. 먼저 요약을 생성하세요.")
+    summary_text = read_text_with_fallback(summary_path)
+
+    transcript_text = ""
+    stt_path = resolve_file_identifier(links["stt"])[0] if links.get("stt") else None
+    if stt_path and stt_path.exists():
+        transcript_text = read_text_with_fallback(stt_path)
+    segments = (load_record_segments(record) or {}).get("segments") or []
+
+    context = build_minutes_context(
+        record,
+        parse_summary_to_sections(summary_text),
+        summary_text=summary_text,
+        transcript_text=transcript_text,
+        segments=segments,
+    )
+    minutes_path = summary_path.with_name(summary_path.name.replace(".summary.md", ".minutes.md"))
+    if minutes_path == summary_path:
+        minutes_path = summary_path.with_name(f"{summary_path.stem}.minutes.md")
+    save_output(render_template(template["body"], context), minutes_path, as_json=False)
+
+    file_uuid = register_file(to_record_path(minutes_path), record_id, "minutes")
+    download_url = f"/download/{file_uuid}"
+    for item in history:
+        if item.get("id") == record_id:
+            item.setdefault("download_links", {})["minutes"] = download_url
+            item["minutes_template"] = template["id"]
+            break
+    save_upload_history(history)
+    record_event(record_id, "minutes_rendered", template_id=template["id"], template_name=template.get("name"))
+    return minutes_path, download_url
+
+
+def build_webhook_context(record_id: str, event: str = "summary_completed") -> dict:
+    """Artifacts of a record as the webhook template context (see ``webhooks.CONTEXT_FIELDS``)."""
+    record = next((item for item in load_upload_history() if item.get("id") == record_id), None)
+    if not record:
+        raise WebhookError("기록을 찾을 수 없습니다.")
+    links = record.get("download_links") or {}
+    summary_text = ""
+    summary_path = resolve_file_identifier(links["summary"])[0] if links.get("summary") else None
+    if summary_path and summary_path.exists():
+        summary_text = read_text_with_fallback(summary_path)
+    structured = structured_summary(summary_text) if summary_text else {"language": None, "sections": []}
+    context = {
+        "event": event,
+        "record_id": record_id,
+        "title": Path(record.get("filename") or "").stem,
+        "filename": record.get("filename"),
+        "created_at": record.get("timestamp"),
+        "completed_at": record.get("completed_at"),
+        "one_line_summary": record.get("title_summary") or "",
+        "summary": summary_text,
+        "summary_url": public_url(links.get("summary")),
+        "transcript_url": public_url(links.get("stt")),
+        "structured": structured,
+    }
+    for section in structured["sections"]:
+        context[section["key"]] = section["items"]
+    return context
+
+
+def record_notification_result(delivery: dict) -> None:
+    """Log a finished notification delivery on its record."""
+    if delivery.get("record_id"):
+        record_event(delivery["record_id"],
+                     "notification_sent" if delivery["status"] == "sent" else "notification_failed",
+                     **{key: delivery[key] for key in ("channel", "target", "user_id", "event", "attempts", "error")})
+
+
+def notify_record_event(record_id: str, event: str, extra: dict = None, include_webhooks: bool = True) -> int:
+    """Notify the subscribers of ``event`` about a record; returns the number of deliveries started."""
+    try:
+        context = {**build_webhook_context(record_id, event), **(extra or {})}
+    except (WebhookError, OSError) as e:
+        print(f"알림 전송 준비 실패: {e}")
+        return 0
+    return notify(event, context, include_webhooks, record_notification_result)
+
+
+def notify_completed_steps(record_id: str, results: dict) -> None:
+    """Send notifications for the steps that completed in ``results``; outcomes go to the event log."""
+    for step, event in (("stt", "stt_completed"), ("summary", "summary_completed")):
+        if results.get(step):
+            notify_record_event(record_id, event)
+
+
+def record_watch_inputs(record: dict):
+    """``(texts, vectors)`` of a record for watch rules: texts by source, index vectors by embedding model."""
+    links = record.get("download_links") or {}
+    texts = {"title": record.get("title_summary") or ""}
+    vectors: dict = {}
+    index = load_index()
+    for source in ("stt", "summary"):
+        path = resolve_file_identifier(links[source])[0] if links.get(source) else None
+        if not path or not Path(path).exists():
+            continue
+        texts["transcript" if source == "stt" else "summary"] = read_text_with_fallback(Path(path))
+        entry = index.get(str(Path(path).resolve()))
+        if not entry or entry.get("deleted"):
+            continue
+        for name in (entry.get("vector"), entry.get("title_vector")):
+            vector_file = VECTOR_DIR / name if name else None
+            if vector_file and vector_file.exists():
+                vectors.setdefault(entry.get("model"), []).append(np.load(vector_file))
+    return texts, vectors
+
+
+def watch_rule_matches(record_id: str, rules) -> list:
+    """Match reports of ``rules`` against a record (nothing is sent)."""
+    record = next((r for r in load_upload_history() if r.get("id") == record_id and not r.get("deleted")), None)
+    if not record:
+        raise WatchRuleError("기록을 찾을 수 없습니다.")
+    texts, vectors = record_watch_inputs(record)
+    matches = []
+    for rule in rules:
+        try:
+            match = evaluate_watch_rule(rule, texts, vectors,
+                                        lambda text, model: cached_query_embedding(text, model, embed_text_ollama))
+        except Exception as e:
+            print(f"감시 규칙 평가 실패 ({rule.get('name')}): {e}")
+            continue
+        if match:
+            matches.append(match)
+    return matches
+
+
+def evaluate_watch_rules(record_id: str) -> list:
+    """Check a processed record against the enabled watch rules and notify their channels.
+
+    Rules that already matched the record are skipped, so re-running a step
+    does not notify twice.
+    """
+    record = next((r for r in load_upload_history() if r.get("id") == record_id), None)
+    if not record or record.get("deleted"):
+        return []
+    notified = set(record.get("watch_matches") or [])
+    rules = [rule for rule in list_watch_rules() if rule.get("enabled", True) and rule["id"] not in notified]
+    if not rules:
+        return []
+    try:
+        matches = watch_rule_matches(record_id, rules)
+    except WatchRuleError:
+        return []
+    if not matches:
+        return []
+
+    history = load_upload_history()
+    for item in history:
+        if item.get("id") == record_id:
+            item["watch_matches"] = sorted(notified | {match["rule_id"] for match in matches})
+            break
+    save_upload_history(history)
+
+    channels = {rule["id"]: rule.get("channels") or list(WATCH_CHANNELS) for rule in rules}
+    title = Path(record.get("filename") or "").stem
+    for match in matches:
+        record_watch_match(match["rule_id"], record_id)
+        record_event(record_id, "watch_matched", **match)
+        if "websocket" in channels[match["rule_id"]]:
+            broadcast_ws_message(WatchMatched(
+                rule_id=match["rule_id"],
+                rule_name=match["rule_name"],
+                record_id=record_id,
+                title=title,
+                keywords=[hit["keyword"] for hit in match["keywords"]],
+                score=match["score"],
+            ))
+        # 웹훅은 규칙의 webhook 채널로, 사람별 알림은 각자의 설정으로 전송
+        notify_record_event(record_id, "watch_matched", {"watch": match},
+                            include_webhooks="webhook" in channels[match["rule_id"]])
+    return matches
+
+
+def export_subtitled_video(record_id: str) -> str:
+    """Mux the record's transcript as a subtitle track into its video upload.
+
+    Writes ``media/{stem}.subtitled.{mp4|webm}`` and returns the download URL.
+    Raises :class:`VideoProcessingError` when the record is not a video or
+    has no timestamped transcript.
+    """
+    history = load_upload_history()
+    record = next((item for item in history if item.get("id") == record_id and not item.get("deleted")), None)
+    if not record:
+        raise VideoProcessingError("기록을 찾을 수 없습니다.")
+    video_path = resolve_record_path(normalize_record_path(record.get("file_path", "")))
+    if record.get("is_video") is False or not is_video_candidate(video_path):
+        raise VideoProcessingError("영상(mp4/webm) 기록에서만 자막 영상을 만들 수 있습니다.")
+    if not video_path.exists():
+        raise VideoProcessingError("원본 영상을 찾을 수 없습니다.")
+    document = load_record_segments(record)
+    segments = (document or {}).get("segments") or []
+    if not segments:
+        raise VideoProcessingError("타임스탬프가 있는 전사 결과가 없습니다. 먼저 STT를 실행하세요.")
+
+    output_dir = LAYOUT.output_dir(LAYOUT.folder_for_path(video_path))
+    output_path = subtitled_video_path(output_dir, video_path)
+    srt_path = output_path.with_name(f"{video_path.stem}.srt")
+    srt_path.parent.mkdir(parents=True, exist_ok=True)
+    srt_path.write_text(segments_to_srt(segments, record.get("speaker_names")), encoding="utf-8")
+    mux_subtitles(video_path, srt_path, output_path, document.get("language"))
+
+    original_filename = export_filename(record, f".subtitled{video_path.suffix.lower()}")
+    file_uuid = register_file(to_record_path(output_path), record_id, "subtitled_video", original_filename)
+    download_url = f"/download/{file_uuid}"
+    for item in history:
+        if item.get("id") == record_id:
+            item.setdefault("download_links", {})["subtitled_video"] = download_url
+            break
+    save_upload_history(history)
+    record_event(record_id, "subtitled_video_exported", cues=len(segments), filename=original_filename)
+    return download_url
+
+
+# ZIP 묶음에 넣을 수 있는 산출물과 파일명 꼬리 (audio는 원본 업로드 파일)
+EXPORT_BUNDLE_SUFFIXES = {"stt": ".md", "summary": ".summary.md", "audio": ""}
+# GET /export/{id}로 내려받을 수 있는 전사 형식과 Content-Type
+TRANSCRIPT_EXPORT_TYPES = {
+    "srt": "application/x-subrip; charset=utf-8",
+    "vtt": "text/vtt; charset=utf-8",
+    "md": "text/markdown; charset=utf-8",
+    "txt": "text/plain; charset=utf-8",
+    "json": "application/json; charset=utf-8",
+}
+
+
+def build_export_bundle(record_ids: list, include: list, collision: str = None):
+    """ZIP of the records' ``include`` artifacts named after their original upload names.
+
+    Returns ``(zip bytes, entries, missing)``; ``entries`` lists
+    ``{"record_id", "task", "name"}`` for every file written and is also
+    stored in the archive as ``manifest.json``. Raises
+    :class:`FilenameError` for an unknown ``collision`` policy.
+    """
+    policy = resolve_collision_policy(collision)
+    records = {record.get("id"): record for record in get_active_history()}
+    taken, folders = {"manifest.json"}, set()
+    entries, missing = [], []
+    buffer = io.BytesIO()
+    with zipfile.ZipFile(buffer, "w", zipfile.ZIP_DEFLATED) as archive:
+        for record_id in record_ids:
+            record = records.get(record_id)
+            if record is None:
+                missing.append(record_id)
+                continue
+            folder = unique_archive_name(export_filename(record), folders, "suffix") if policy == "folder" else ""
+            for task in include:
+                if task == "audio":
+                    path = resolve_record_path(normalize_record_path(record.get("file_path", "")))
+                    name = export_filename(record, path.suffix.lower())
+                else:
+                    link = (record.get("download_links") or {}).get(task)
+                    path = resolve_file_identifier(link)[0] if link else None
+                    name = export_filename(record, EXPORT_BUNDLE_SUFFIXES[task])
+                if path is None or not path.exists():
+                    continue
+                name = unique_archive_name(f"{folder}/{name}" if folder else name, taken, policy, record_id)
+                archive.write(path, name)
+                entries.append({"record_id": record_id, "task": task, "name": name})
+        archive.writestr("manifest.json", json.dumps({
+            "created_at": datetime.now().isoformat(),
+            "collision": policy,
+            "entries": entries,
+            "missing": missing,
+        }, ensure_ascii=False, indent=2))
+    return buffer.getvalue(), entries, missing
+
+
+def generate_and_store_title_summary(record_id: str, file_path: Path, model: str = None,
+                                     options: dict = None):
+    """Generate one-line summary, store it, and return it (``None`` on failure)."""
+    try:
+        summary = generate_one_line_summary(file_path, model=model, options=options)
+        update_title_summary(record_id, summary)
+        return summary
+    except Exception as e:
+        print(f"One-line summary generation failed: {e}")
+        return None
+
+def record_transcript_path(record: dict):
+    """Existing STT result of a record, or ``None``."""
+    stt_link = (record.get("download_links") or {}).get("stt")
+    source_path = resolve_file_identifier(stt_link)[0] if stt_link else None
+    return Path(source_path) if source_path and Path(source_path).exists() else None
+
+
+def transcript_view(stt_path: Path, view: str):
+    """``(text, segments, document)`` of an STT file in the given view; ``None`` if the view is unavailable."""
+    try:
+        document = load_segments(segments_path_for(stt_path), persist_migration=False)
+    except SegmentSchemaError:
+        document = None
+    markdown = read_text_with_fallback(stt_path)
+    if view == "clean":
+        return markdown, (document or {}).get("segments"), document
+    segments = verbatim_segments(document)
+    if segments is None:
+        return None
+    return render_transcript_markdown(transcript_title_line(markdown, stt_path.stem), segments), segments, document
+
+
+def paragraph_source_segments(stt_path: Path, view: str):
+    """Segments to paragraph for the transcript export (``None`` if the view is unavailable)."""
+    rendered = transcript_view(stt_path, view)
+    if rendered is None:
+        return None
+    text, segments, _ = rendered
+    if view != "clean":
+        return segments
+    # 수동 수정을 반영하도록 마크다운에서 다시 읽고, 화자 라벨은 원본 세그먼트에서 가져옴
+    speakers = {int(seg["start"]): seg["speaker"] for seg in segments or [] if seg.get("speaker")}
+    return [
+        dict(seg, speaker=speakers[seg["start"]]) if seg["start"] in speakers else seg
+        for seg in segments_from_markdown(text)
+    ]
+
+
+def export_source_segments(stt_path: Path, view: str):
+    """Segments for the subtitle/text export with the stored millisecond timings (``None`` if the view is unavailable)."""
+    rendered = transcript_view(stt_path, view)
+    if rendered is None:
+        return None
+    text, segments, _ = rendered
+    if view != "clean":
+        return segments or []
+    # 수동 수정은 마크다운(초 단위)에서 읽고, 같은 초에 시작하는 원본 세그먼트의 밀리초 시간과 화자를 사용
+    stored = {}
+    for seg in segments or []:
+        stored.setdefault(int(seg["start"]), seg)
+    exported = []
+    for seg in segments_from_markdown(text):
+        original = stored.get(seg["start"])
+        if original:
+            seg = dict(seg, start=original["start"], end=original["end"])
+            if original.get("speaker"):
+                seg["speaker"] = original["speaker"]
+        exported.append(seg)
+    return exported
+
+
+def series_summary_context(record_id: str, enabled: bool = None):
+    """Previous summaries of the record's series for the summary prompt: ``(context, record ids)``.
+
+    ``(None, [])`` when the record is in no series, rolling context is off
+    (``enabled``, default: the series' ``rolling_context``) or no earlier
+    entry has a summary.
+    """
+    series = series_of(record_id) if record_id else None
+    if series is None or not (series.get("rolling_context", True) if enabled is None else enabled):
+        return None, []
+    records = {record["id"]: record for record in get_active_history()}
+    summaries = []
+    for entry in previous_series_entries(series, record_id, records):
+        summary_path = resolve_file_identifier((entry.get("download_links") or {}).get("summary"))[0]
+        if summary_path is None or not summary_path.exists():
+            continue
+        label = entry.get("title_summary") or entry.get("original_filename") or entry.get("filename", "")
+        summaries.append({
+            "record_id": entry["id"],
+            "label": f"{entry.get('timestamp', '')[:10]} {label}".strip(),
+            "summary": read_text_with_fallback(summary_path),
+        })
+    if not summaries:
+        return None, []
+    return render_series_context(series, summaries), [entry["record_id"] for entry in summaries]
+
+
+def routed_summary_model(record_id: str, text: str):
+    """Summary model for the transcript language (``SUMMARY_MODEL_BY_LANGUAGE``): ``(model, language)``.
+
+    The language is the one Whisper detected for the record, or guessed from
+    the text's script; ``model`` is ``None`` when no model is mapped to it.
+    """
+    record = next((r for r in get_active_history() if r.get("id") == record_id), None) if record_id else None
+    language = (_record_language(record) if record else None) or detect_summary_language(text)
+    return summary_model_for_language(language), language
+
+
+def validate_summary_model_setting(model_settings) -> None:
+    """Reject a per-request summary model (``model_settings.summarize``) that Ollama does not have.
+
+    Checked when the request is accepted, so a typo fails with 400 before
+    the transcription runs instead of after it. Raises :class:`UnknownModelError`
+    (or :class:`StructuredSummaryError` for an unknown ``summary_format``).
+    """
+    model_settings = model_settings if isinstance(model_settings, dict) else {}
+    summary_format(model_settings.get("summary_format"))
+    if model_settings.get("summarize"):
+        require_ollama_model(model_settings["summarize"])
+
+
+def people_summary_context(record_id: str, text: str):
+    """Directory people attending or named in ``text`` for the summary prompt: ``(context, names)``."""
+    record = next((r for r in get_active_history() if r.get("id") == record_id), None) if record_id else None
+    record = record or {}
+    names = list(record.get("attendees") or []) + list((record.get("speaker_names") or {}).values())
+    people = people_in_text(text, names)
+    if not people:
+        return None, []
+    return render_people_context(people), [person["name"] for person in people]
+
+
+def person_mentions(person: dict, record_ids=None) -> list:
+    """Records where ``person`` spoke or was named, newest first, with segment timestamps."""
+    entries = []
+    for record in get_active_history():
+        if record_ids is not None and record["id"] not in record_ids:
+            continue
+        found = find_person_mentions(person, load_record_segments(record), record.get("speaker_names") or {})
+        if not found["spoke"] and not found["mentions"]:
+            continue
+        entries.append({
+            "record_id": record["id"],
+            "filename": record.get("original_filename") or record.get("filename"),
+            "timestamp": record.get("timestamp"),
+            "title_summary": record.get("title_summary", ""),
+            **found,
+        })
+    return sorted(entries, key=lambda entry: entry.get("timestamp") or "", reverse=True)
+
+
+def sync_action_items(record_id: str, summary_text: str) -> dict:
+    """Copy the summary's action items into the tracker (action_items.py); returns the sync counts."""
+    counts = sync_record_action_items(record_id, parse_summary_to_sections(summary_text)["action_items"])
+    if counts["added"] or counts["removed"]:
+        record_event(record_id, "action_items_synced", **counts)
+    return counts
+
+
+def describe_action_items(items: list, records: dict = None) -> list:
+    """Items with their record's name/timestamp and series for the tracker endpoints."""
+    records = records if records is not None else {r["id"]: r for r in get_active_history()}
+    series_names = {record_id: series["name"] for series in list_series() for record_id in series["record_ids"]}
+    described = []
+    for item in items:
+        record = records.get(item["record_id"]) or {}
+        described.append({
+            **item,
+            "record_filename": record.get("original_filename") or record.get("filename"),
+            "record_timestamp": record.get("timestamp"),
+            "record_title": record.get("title_summary", ""),
+            "series_name": series_names.get(item["record_id"]),
+        })
+    return described
+
+
+def describe_series(series: dict) -> dict:
+    """Series with its active entries (oldest first) for the ``/series`` endpoints."""
+    records = {record["id"]: record for record in get_active_history()}
+    return {
+        **series,
+        "entries": [
+            {
+                "id": record["id"],
+                "filename": record.get("original_filename") or record.get("filename"),
+                "timestamp": record.get("timestamp"),
+                "title_summary": record.get("title_summary", ""),
+                "has_summary": bool((record.get("completed_tasks") or {}).get("summary")),
+            }
+            for record in series_entries(series, records)
+        ],
+    }
+
+
+def start_summary_bakeoff(record: dict, models: list, model_options: dict = None) -> dict:
+    """Summarize a record's transcript with several models in parallel; returns the initial manifest."""
+    transcript_path = record_transcript_path(record)
+    text = read_text_with_fallback(transcript_path)
+    options = resolve_model_options(model_options)
+    chunk_size = summarize_workflow.DEFAULT_CHUNK_SIZE
+    temperature = summarize_workflow.DEFAULT_TEMPERATURE
+
+    def summarize(source_text: str, model: str) -> str:
+        return summarize_text_mapreduce(text=source_text, model=model, chunk_size=chunk_size, max_tokens=None,
+                                        temperature=temperature, model_options=options)
+
+    manifest = start_bakeoff(transcript_path.parent, text, models, summarize, {
+        "record_id": record["id"],
+        "source_file": transcript_path.name,
+        "prompt_version": summarize_workflow.get_prompt_version(),
+        "chunk_size": chunk_size,
+        "temperature": temperature,
+        "model_options": options,
+    })
+    record_event(record["id"], "summary_bakeoff_started", bakeoff_id=manifest["id"], models=models)
+    return manifest
+
 
 def find_existing_stt_file(original_file_path: Path):
     """Find existing STT result file for the given original file."""
@@ -672,7 +2473,7 @@ def find_existing_stt_file(original_file_path: Path):
     print(f"[DEBUG] 업로드 UUID: {upload_uuid}")
     
     # Look for STT file in whisper_output/UUID/filename.md
-    stt_output_dir = OUTPUT_DIR / upload_uuid
+    stt_output_dir = LAYOUT.output_dir(upload_uuid)
     potential_files = [
         stt_output_dir / f"{stem}.md",
         stt_output_dir / f"{stem}.corrected.md"
@@ -699,16 +2500,18 @@ def run_incremental_embedding(base_dir: Path = None):
         except:
             model_name = os.environ.get("EMBEDDING_MODEL", "bge-m3:latest")
         
-        # Load existing index
+        # Load existing index (스냅샷으로 비교만 하고 갱신분은 마지막에 병합)
         index = load_index()
+        updates = {}
         processed_count = 0
         
-        # Find all STT result files
+        # Find all STT result and summary files
         for md_file in base_dir.glob("**/*.md"):
-            # Skip summary files
-            if md_file.name.endswith('.summary.md'):
+            # per_record 레이아웃의 source/ 폴더는 업로드 원본이므로 제외
+            if LAYOUT.is_per_record and SOURCE_SUBDIR in md_file.relative_to(base_dir).parts[:-1]:
                 continue
-                
+            kind = "summary" if md_file.name.endswith('.summary.md') else "transcript"
+
             # Check if already processed and up-to-date
             checksum = file_hash(md_file)
             key = str(md_file.resolve())
@@ -720,19 +2523,24 @@ def run_incremental_embedding(base_dir: Path = None):
                 text = md_file.read_text(encoding="utf-8")
                 
                 # Generate embedding
-                vector = embed_text_ollama(text, model_name)
+                vector = embed_document_ollama(text, model_name)
                 
                 # Create vector directory if not exists
                 VECTOR_DIR.mkdir(parents=True, exist_ok=True)
                 
                 # Save embedding vector with unique name
                 vector_file = VECTOR_DIR / f"{md_file.parent.name}_{md_file.stem}.npy"
-                np.save(vector_file, vector)
+                detach_artifact(vector_file)
+                save_vector(vector_file, vector)
                 
                 # Update index
-                index[key] = {
+                updates[key] = {
                     "sha256": checksum,
                     "vector": vector_file.name,
+                    "model": model_name,
+                    "chunks": vector_chunk_count(vector),
+                    "kind": kind,
+                    "namespace": entry_namespace(index.get(key)),
                     "deleted": False,
                     "deleted_path": None,
                     "vector_deleted_path": None,
@@ -746,7 +2554,11 @@ def run_incremental_embedding(base_dir: Path = None):
                 continue
         
         # Save updated index
-        save_index(index)
+        if updates:
+            with INDEX_LOCK:
+                index = load_index()
+                index.update(updates)
+                save_index(index)
         print(f"증분 임베딩 완료: {processed_count}개 파일 처리됨")
         return processed_count
         
@@ -763,54 +2575,157 @@ def file_hash(path: Path) -> str:
             h.update(chunk)
     return h.hexdigest()
 
-def generate_embedding(file_path: Path, record_id: str = None):
-    """Generate embedding for a text file and store it."""
+def share_artifact(path: Path):
+    """Share an identical artifact of another record through the content-addressed store (best effort)."""
     try:
-        # Get embedding model name
-        try:
-            from sttEngine.config import get_model_for_task, get_default_model
-            model_name = get_model_for_task("EMBEDDING", get_default_model("EMBEDDING"))
-        except:
-            model_name = os.environ.get("EMBEDDING_MODEL", "bge-m3:latest")
-        
-        # Read text content
-        text = file_path.read_text(encoding="utf-8")
-        
-        # Generate embedding
-        vector = embed_text_ollama(text, model_name)
-        
-        # Create vector directory if not exists
-        VECTOR_DIR.mkdir(parents=True, exist_ok=True)
-        
-        # Save embedding vector
-        vector_file = VECTOR_DIR / f"{file_path.stem}.npy"
-        np.save(vector_file, vector)
-        
-        # Update index
-        index = load_index()
-        checksum = file_hash(file_path)
-        
-        index[str(file_path.resolve())] = {
-            "sha256": checksum,
-            "vector": vector_file.name,
-            "deleted": False,
-            "deleted_path": None,
-            "vector_deleted_path": None,
-        }
-        save_index(index)
-        
-        # Update task completion
-        if record_id:
-            file_path_str = to_record_path(file_path)
-            update_task_completion(record_id, "embedding", file_path_str)
-        
-        print(f"Embedding generated for {file_path.name}")
+        return share_artifact_file(path)
+    except OSError as e:
+        print(f"산출물 공유 실패 ({path.name}): {e}")
+        return None
+
+
+def reuse_embedding(checksum: str, model_name: str, vector_file: Path):
+    """Link the vector of an index entry with the same text and model to ``vector_file``.
+
+    Returns the reused entry or ``None`` when nothing matches.
+    """
+    for key, meta in get_index_snapshot():
+        if meta.get("deleted") or meta.get("sha256") != checksum or meta.get("model") != model_name:
+            continue
+        source = VECTOR_DIR / (meta.get("vector") or "")
+        if not meta.get("vector") or not source.exists() or source == vector_file:
+            continue
+        content_hash = share_artifact(source)
+        if content_hash and link_artifact(content_hash, source.suffix, vector_file):
+            return meta
+    return None
+
+
+def current_embedding_model() -> str:
+    try:
+        return get_model_for_task("EMBEDDING", get_default_model("EMBEDDING"))
+    except Exception:
+        return os.environ.get("EMBEDDING_MODEL", "bge-m3:latest")
+
+
+def benchmark_corpus(target: str = "transcript", required_ids=()) -> dict:
+    """Record id → transcript (or summary) text of active records for the embedding benchmark.
+
+    Records in ``required_ids`` come first; the rest are the most recent ones
+    up to ``EMBEDDING_BENCHMARK_MAX_RECORDS``.
+    """
+    required = set(required_ids)
+    history = get_active_history()
+    others = sorted((r for r in history if r.get("id") not in required),
+                    key=lambda r: r.get("timestamp") or "", reverse=True)
+    records = [r for r in history if r.get("id") in required] + others
+    documents = {}
+    for record in records:
+        if len(documents) >= max(EMBEDDING_BENCHMARK_MAX_RECORDS, len(required)):
+            break
+        link = (record.get("download_links") or {}).get("stt" if target == "transcript" else "summary")
+        path = resolve_file_identifier(link)[0] if link else None
+        if not path or not Path(path).exists():
+            continue
+        text = read_text_with_fallback(Path(path)).strip()
+        if text:
+            documents[record["id"]] = text
+    return documents
+
+
+def generate_embedding(file_path: Path, record_id: str = None, kind: str = "transcript",
+                       title: str | None = None) -> bool:
+    """Generate embedding for a text file and store it (``False`` when it failed, see :func:`embed_record_file`)."""
+    try:
+        embed_record_file(file_path, record_id, kind, title)
         return True
-        
     except Exception as e:
         print(f"Embedding generation failed for {file_path.name}: {e}")
         return False
 
+
+def embed_record_file(file_path: Path, record_id: str = None, kind: str = "transcript",
+                      title: str | None = None, progress_callback=None) -> dict:
+    """Embed a text file, add it to the vector index and return its index entry.
+
+    ``kind`` marks the index entry as a transcript or a summary so searches
+    can target either. Summary entries may also carry a separate vector for
+    the one-line ``title`` summary. With ``record_id`` the entry carries the
+    record's id, namespace, name and tags, and a transcript embedding
+    completes the record's "embedding" task. ``progress_callback(done,
+    total)`` follows the embedding requests of long texts. Errors propagate.
+    """
+    # Get embedding model name
+    try:
+        from sttEngine.config import get_model_for_task, get_default_model
+        model_name = get_model_for_task("EMBEDDING", get_default_model("EMBEDDING"))
+    except:
+        model_name = os.environ.get("EMBEDDING_MODEL", "bge-m3:latest")
+    
+    # Read text content
+    text = file_path.read_text(encoding="utf-8")
+    checksum = file_hash(file_path)
+
+    # Create vector directory if not exists
+    VECTOR_DIR.mkdir(parents=True, exist_ok=True)
+
+    # Save embedding vector (폴더명 접두사로 기록 간 파일명 충돌 방지)
+    vector_file = VECTOR_DIR / f"{file_path.parent.name}_{file_path.stem}.npy"
+
+    # 같은 내용을 같은 모델로 임베딩한 다른 기록이 있으면 벡터를 공유 (Ollama 호출 생략)
+    reused = reuse_embedding(checksum, model_name, vector_file)
+    if reused:
+        chunks = reused.get("chunks", 1)
+    else:
+        vector = embed_document_ollama(text, model_name, progress_callback)
+        detach_artifact(vector_file)
+        save_vector(vector_file, vector)
+        chunks = vector_chunk_count(vector)
+        share_artifact(vector_file)
+
+    entry = {
+        "sha256": checksum,
+        "vector": vector_file.name,
+        "model": model_name,
+        "chunks": chunks,
+        "kind": kind,
+        "timestamp": datetime.fromtimestamp(file_path.stat().st_mtime).isoformat(),
+        "deleted": False,
+        "deleted_path": None,
+        "vector_deleted_path": None,
+    }
+
+    if title and title.strip():
+        title_vector_file = VECTOR_DIR / f"{file_path.parent.name}_{file_path.stem}.title.npy"
+        detach_artifact(title_vector_file)
+        save_vector(title_vector_file, embed_text_ollama(title, model_name))
+        entry["title_vector"] = title_vector_file.name
+
+    if record_id:
+        record = next((r for r in load_upload_history() if r.get("id") == record_id), None) or {}
+        entry["namespace"] = record_namespace(record)
+        # 검색 결과를 기록과 바로 잇도록 기록 정보를 색인 항목에 남김
+        entry["record_id"] = record_id
+        entry["record_filename"] = record.get("original_filename") or record.get("filename")
+        entry["tags"] = list(record.get("tags") or [])
+
+    # Update index (임베딩 계산은 잠금 밖에서 끝내고 색인 갱신만 직렬화)
+    with INDEX_LOCK:
+        index = load_index()
+        index[str(file_path.resolve())] = entry
+        save_index(index)
+
+    # Update task completion
+    if record_id:
+        if kind == "transcript":
+            file_path_str = to_record_path(file_path)
+            update_task_completion(record_id, "embedding", file_path_str)
+        record_event(record_id, "embedding_completed", model=model_name, kind=kind)
+    
+    print(f"Embedding generated for {file_path.name}")
+    return entry
+
+
 def reset_upload_record(record_id: str) -> bool:
     """Remove processed files and reset completion status for a record."""
     history = load_upload_history()
@@ -820,50 +2735,52 @@ def reset_upload_record(record_id: str) -> bool:
             if record.get("deleted"):
                 return False
             folder = record.get("folder_name")
-            output_dir = OUTPUT_DIR / folder if folder else None
+            output_dir = LAYOUT.output_dir(folder) if folder else None
             try:
                 if output_dir and output_dir.exists():
-                    shutil.rmtree(output_dir)
+                    LAYOUT.remove_outputs(folder)
             except Exception:
                 pass
 
             # Remove embedding vectors and index entries related to this record
             if output_dir:
-                index = load_index()
-                keys_to_remove = []
-                for key, meta in index.items():
-                    try:
-                        Path(key).resolve().relative_to(output_dir.resolve())
-                        keys_to_remove.append((key, meta))
-                    except ValueError:
-                        continue
-
-                for key, meta in keys_to_remove:
-                    vector_name = meta.get("vector")
-                    if vector_name:
-                        vector_path = VECTOR_DIR / vector_name
-                        if vector_path.exists():
-                            # Check if this vector is referenced elsewhere
-                            if not any(
-                                v.get("vector") == vector_name and k != key
-                                for k, v in index.items()
-                            ):
-                                try:
-                                    vector_path.unlink()
-                                except Exception:
-                                    pass
-                    del index[key]
+                with INDEX_LOCK:
+                    index = load_index()
+                    keys_to_remove = []
+                    for key, meta in index.items():
+                        try:
+                            resolve_index_path(key, meta).relative_to(output_dir.resolve())
+                            keys_to_remove.append((key, meta))
+                        except ValueError:
+                            continue
 
-                if keys_to_remove:
-                    save_index(index)
+                    for key, meta in keys_to_remove:
+                        for vector_name in entry_vector_names(meta):
+                            vector_path = VECTOR_DIR / vector_name
+                            if vector_path.exists():
+                                # Check if this vector is referenced elsewhere
+                                if not any(
+                                    vector_name in entry_vector_names(v) and k != key
+                                    for k, v in index.items()
+                                ):
+                                    try:
+                                        vector_path.unlink()
+                                    except Exception:
+                                        pass
+                        del index[key]
+
+                    if keys_to_remove:
+                        save_index(index)
 
             record["completed_tasks"] = {
                 task: False for task in TASK_TYPES
             }
             record["download_links"] = {}
             record["title_summary"] = ""
+            record.pop("summary_prompt_version", None)
 
             save_upload_history(history)
+            record_event(record_id, "reset", tasks=list(TASK_TYPES))
             return True
 
     return False
@@ -921,6 +2838,7 @@ def delete_file(file_identifier: str, file_type: str) -> tuple[bool, str]:
                 # If deleting summary, also clear title_summary
                 if file_type == 'summary':
                     record["title_summary"] = ""
+                    record.pop("summary_prompt_version", None)
                 
                 break
         
@@ -932,7 +2850,8 @@ def delete_file(file_identifier: str, file_type: str) -> tuple[bool, str]:
         
         # Save updated history
         save_upload_history(history)
-        
+        record_event(record_id, "file_deleted", task_type=file_type, file_uuid=file_identifier)
+
         return True, ""
         
     except Exception as e:
@@ -952,10 +2871,10 @@ def _delete_single_record_assets(
     folder_name = record.get("folder_name")
     deleted_at = datetime.now().isoformat()
 
-    upload_dir = (UPLOAD_DIR / folder_name).resolve() if folder_name else None
-    deleted_upload_dir = (DELETED_UPLOAD_DIR / folder_name).resolve() if folder_name else None
-    output_dir = (OUTPUT_DIR / folder_name).resolve() if folder_name else None
-    deleted_output_dir = (DELETED_OUTPUT_DIR / folder_name).resolve() if folder_name else None
+    upload_dir = LAYOUT.upload_dir(folder_name).resolve() if folder_name else None
+    deleted_upload_dir = LAYOUT.deleted_upload_dir(folder_name).resolve() if folder_name else None
+    output_dir = LAYOUT.output_dir(folder_name).resolve() if folder_name else None
+    deleted_output_dir = LAYOUT.deleted_output_dir(folder_name).resolve() if folder_name else None
     vector_dir = VECTOR_DIR.resolve()
     deleted_vector_dir = DELETED_VECTOR_DIR.resolve()
 
@@ -1014,7 +2933,7 @@ def _delete_single_record_assets(
             if not isinstance(meta, dict):
                 continue
             try:
-                rel = Path(key).resolve().relative_to(output_dir)
+                rel = resolve_index_path(key, meta).relative_to(output_dir)
             except (ValueError, FileNotFoundError):
                 continue
 
@@ -1024,11 +2943,10 @@ def _delete_single_record_assets(
 
             index_entries.append((key, meta, deleted_path))
 
-            vector_name = meta.get("vector")
-            if vector_name:
-                vector_names.add(vector_name)
+            vector_names.update(entry_vector_names(meta))
 
-    if upload_dir and upload_dir.exists():
+    # per_record 레이아웃에서는 원본이 산출물 폴더 안에 있으므로 함께 이동된다
+    if upload_dir and upload_dir.exists() and not (output_dir and upload_dir.is_relative_to(output_dir)):
         deleted_upload_dir.parent.mkdir(parents=True, exist_ok=True)
         shutil.move(str(upload_dir), str(deleted_upload_dir))
         record_assets["uploads"] = to_record_path(deleted_upload_dir)
@@ -1037,6 +2955,8 @@ def _delete_single_record_assets(
         deleted_output_dir.parent.mkdir(parents=True, exist_ok=True)
         shutil.move(str(output_dir), str(deleted_output_dir))
         record_assets["outputs"] = to_record_path(deleted_output_dir)
+        if upload_dir and upload_dir.is_relative_to(output_dir):
+            record_assets["uploads"] = to_record_path(deleted_upload_dir)
 
     for file_uuid, info, new_path in registry_updates:
         info["file_path"] = to_record_path(new_path)
@@ -1092,50 +3012,52 @@ def delete_records(record_ids: list[str]) -> tuple[bool, dict[str, dict]]:
 
     history = load_upload_history()
     registry = load_file_registry()
-    index = load_index()
+    with INDEX_LOCK:
+        index = load_index()
 
-    history_by_id = {record.get("id"): record for record in history}
-    results: dict[str, dict] = {}
+        history_by_id = {record.get("id"): record for record in history}
+        results: dict[str, dict] = {}
 
-    history_changed = False
-    registry_changed = False
-    index_changed = False
-    moved_vector_names: set[str] = set()
+        history_changed = False
+        registry_changed = False
+        index_changed = False
+        moved_vector_names: set[str] = set()
 
-    for record_id in record_ids:
-        record = history_by_id.get(record_id)
-        if not record:
-            results[record_id] = {
-                "success": False,
-                "error": "기록을 찾을 수 없습니다.",
-            }
-            continue
+        for record_id in record_ids:
+            record = history_by_id.get(record_id)
+            if not record:
+                results[record_id] = {
+                    "success": False,
+                    "error": "기록을 찾을 수 없습니다.",
+                }
+                continue
 
-        if record.get("deleted"):
-            results[record_id] = {
-                "success": False,
-                "error": "이미 삭제된 항목입니다.",
-            }
-            continue
+            if record.get("deleted"):
+                results[record_id] = {
+                    "success": False,
+                    "error": "이미 삭제된 항목입니다.",
+                }
+                continue
 
-        try:
-            summary = _delete_single_record_assets(record, registry, index, moved_vector_names)
-            history_changed = True
-            registry_changed = registry_changed or summary.get("registry_changed", False)
-            index_changed = index_changed or summary.get("index_changed", False)
-            results[record_id] = {"success": True}
-        except Exception as exc:
-            results[record_id] = {
-                "success": False,
-                "error": str(exc),
-            }
+            try:
+                summary = _delete_single_record_assets(record, registry, index, moved_vector_names)
+                history_changed = True
+                record_event(record_id, "deleted")
+                registry_changed = registry_changed or summary.get("registry_changed", False)
+                index_changed = index_changed or summary.get("index_changed", False)
+                results[record_id] = {"success": True}
+            except Exception as exc:
+                results[record_id] = {
+                    "success": False,
+                    "error": str(exc),
+                }
 
-    if history_changed:
-        save_upload_history(history)
-    if registry_changed:
-        save_file_registry(registry)
-    if index_changed:
-        save_index(index)
+        if history_changed:
+            save_upload_history(history)
+        if registry_changed:
+            save_file_registry(registry)
+        if index_changed:
+            save_index(index)
 
     overall_success = (
         bool(results)
@@ -1165,11 +3087,15 @@ def update_stt_text(file_identifier: str, new_text: str) -> tuple[bool, str, str
         return False, "지원하지 않는 파일 형식입니다.", record_id
 
     try:
+        detach_artifact(file_path)
         file_path.write_text(new_text, encoding='utf-8')
     except Exception as exc:
         print(f"Failed to write updated STT text: {exc}")
         return False, "텍스트를 저장하지 못했습니다.", record_id
 
+    # 키워드 검색 결과가 바뀌므로 캐시된 /search 응답을 버림
+    invalidate_search_responses()
+
     if not record_id:
         history = load_upload_history()
         resolved_path = file_path.resolve()
@@ -1177,7 +3103,7 @@ def update_stt_text(file_identifier: str, new_text: str) -> tuple[bool, str, str
             folder = record.get("folder_name")
             if not folder:
                 continue
-            output_dir = (OUTPUT_DIR / folder).resolve()
+            output_dir = LAYOUT.output_dir(folder).resolve()
             try:
                 resolved_path.relative_to(output_dir)
                 record_id = record["id"]
@@ -1185,6 +3111,7 @@ def update_stt_text(file_identifier: str, new_text: str) -> tuple[bool, str, str
             except ValueError:
                 continue
 
+    record_event(record_id, "transcript_edited", file=to_record_path(file_path), characters=len(new_text))
     return True, "", record_id
 
 
@@ -1249,6 +3176,10 @@ def reset_tasks_for_record(
 
         if task_name == "summary":
             record["title_summary"] = ""
+            record.pop("summary_prompt_version", None)
+            if delete_file and file_path:
+                shutil.rmtree(summary_debug_dir(file_path.parent), ignore_errors=True)
+                discard_structured_summary(file_path)
 
         return True
 
@@ -1268,25 +3199,24 @@ def reset_tasks_for_record(
     if embedding_removed:
         try:
             folder_name = record.get("folder_name", "")
-            output_dir = OUTPUT_DIR / folder_name
+            output_dir = LAYOUT.output_dir(folder_name)
             output_resolved = output_dir.resolve()
             keys_to_remove = []
 
             for key, meta in list(index.items()):
                 try:
-                    Path(key).resolve().relative_to(output_resolved)
+                    resolve_index_path(key, meta).relative_to(output_resolved)
                     keys_to_remove.append((key, meta))
                 except (ValueError, FileNotFoundError):
                     continue
 
             if keys_to_remove:
                 for key, meta in keys_to_remove:
-                    vector_name = meta.get("vector")
-                    if vector_name:
+                    for vector_name in entry_vector_names(meta):
                         vector_path = VECTOR_DIR / vector_name
                         if vector_path.exists():
                             if not any(
-                                v.get("vector") == vector_name and k != key
+                                vector_name in entry_vector_names(v) and k != key
                                 for k, v in index.items()
                             ):
                                 try:
@@ -1305,7 +3235,7 @@ def reset_tasks_for_record(
             folder_name = record.get("folder_name")
             if original_path and folder_name:
                 source_path = resolve_record_path(original_path)
-                output_dir = OUTPUT_DIR / folder_name
+                output_dir = LAYOUT.output_dir(folder_name)
                 if source_path and output_dir.exists():
                     stem = Path(source_path).stem
                     corrected_file = output_dir / f"{stem}.corrected.md"
@@ -1317,6 +3247,10 @@ def reset_tasks_for_record(
         except Exception as exc:
             print(f"Failed to clean STT artifacts: {exc}")
 
+    reset_names = [task for task in TASK_TYPES if results.get(task)]
+    if reset_names:
+        record_event(record.get("id"), "reset", tasks=reset_names)
+
     return results, registry_changed, index_changed
 
 
@@ -1333,20 +3267,21 @@ def reset_summary_and_embedding(record_id: str) -> tuple[bool, str]:
         return False, "기록을 찾을 수 없습니다."
 
     registry = load_file_registry()
-    index = load_index()
+    with INDEX_LOCK:
+        index = load_index()
 
-    results, registry_changed, index_changed = reset_tasks_for_record(
-        record,
-        {"summary", "embedding"},
-        registry,
-        index,
-    )
+        results, registry_changed, index_changed = reset_tasks_for_record(
+            record,
+            {"summary", "embedding"},
+            registry,
+            index,
+        )
 
-    if registry_changed:
-        save_file_registry(registry)
+        if registry_changed:
+            save_file_registry(registry)
 
-    if index_changed:
-        save_index(index)
+        if index_changed:
+            save_index(index)
 
     save_upload_history(history)
 
@@ -1379,36 +3314,37 @@ def reset_tasks_for_all_records(tasks: set[str]) -> tuple[bool, dict[str, int],
         return True, {task: 0 for task in valid_tasks}, "초기화할 기록이 없습니다."
 
     registry = load_file_registry()
-    index = load_index()
+    with INDEX_LOCK:
+        index = load_index()
 
-    registry_changed = False
-    index_changed = False
-    reset_counts = {task: 0 for task in valid_tasks}
+        registry_changed = False
+        index_changed = False
+        reset_counts = {task: 0 for task in valid_tasks}
 
-    for record in history:
-        if record.get("deleted"):
-            continue
-        results, reg_changed, idx_changed = reset_tasks_for_record(
-            record,
-            requested_tasks,
-            registry,
-            index,
-        )
-
-        if reg_changed:
-            registry_changed = True
-        if idx_changed:
-            index_changed = True
+        for record in history:
+            if record.get("deleted"):
+                continue
+            results, reg_changed, idx_changed = reset_tasks_for_record(
+                record,
+                requested_tasks,
+                registry,
+                index,
+            )
+
+            if reg_changed:
+                registry_changed = True
+            if idx_changed:
+                index_changed = True
 
-        for task in requested_tasks:
-            if results.get(task):
-                reset_counts[task] += 1
+            for task in requested_tasks:
+                if results.get(task):
+                    reset_counts[task] += 1
 
-    if registry_changed:
-        save_file_registry(registry)
+        if registry_changed:
+            save_file_registry(registry)
 
-    if index_changed:
-        save_index(index)
+        if index_changed:
+            save_index(index)
 
     save_upload_history(history)
 
@@ -1427,7 +3363,158 @@ def reset_tasks_for_all_records(tasks: set[str]) -> tuple[bool, dict[str, int],
     return True, reset_counts, message
 
 
-def run_workflow(file_path: Path, steps, record_id: str = None, task_id: str = None, model_settings: dict = None):
+def run_stt_step(file_path: Path, output_dir: Path, model_settings: dict = None,
+                 task_id: str = None, record_id: str = None):
+    """Transcribe an audio file with the configured STT backend.
+
+    Model, language and device come from ``model_settings``; the Whisper
+    initial prompt combines its ``initial_prompt``/``attendees`` with the
+    record's metadata and the glossary (see ``stt_prompt.py``), and
+    ``decoding`` picks the decoding strategy (see ``decoding.py``). On success the
+    history and event log are updated and ``(stt_file, None)`` is returned;
+    otherwise ``(None, error_dict)`` suitable as the run_workflow result.
+    """
+
+    def progress_callback(message):
+        if task_id:
+            update_task_progress(task_id, message)
+
+    model_settings = model_settings or {}
+
+    # Get language from settings, default to STT_DEFAULT_LANGUAGE ("" / "auto" = detect)
+    language = default_language()
+    if model_settings.get("language") is not None:
+        lang = model_settings.get("language")
+        language = None if lang in ("", "auto") else lang
+    try:
+        decoding_strategy = resolve_decoding_strategy(model_settings.get("decoding"))
+    except DecodingStrategyError as e:
+        return None, error_payload(str(e), code="invalid_request")
+
+    record = next((r for r in load_upload_history() if r.get("id") == record_id), None) if record_id else None
+    attendees = model_settings.get("attendees")
+    prompt, prompt_provenance = prompt_for_record(
+        str(model_settings.get("initial_prompt") or ""),
+        record,
+        [str(name) for name in attendees] if isinstance(attendees, list) else (),
+    )
+
+    options = TranscriptionOptions(
+        model=model_settings.get("whisper") or "large-v3-turbo",
+        language=language,
+        initial_prompt=prompt,
+        prompt_provenance=prompt_provenance,
+        decoding_strategy=decoding_strategy,
+        device=model_settings.get("device") or "auto",
+        filter_fillers=False,
+        min_seg_length=2,
+        normalize_punct=False,
+        progress_callback=progress_callback,
+        queue_callback=lambda position, estimated_start: update_task_queue(task_id, position, estimated_start),
+        cancel_event=get_cancel_event(task_id),
+    )
+
+    try:
+        engine = get_stt_engine()
+        if task_id:
+            update_task_progress(task_id, f"STT 백엔드: {engine.name}")
+        # 영상은 업로드 직후 추출한 오디오 트랙을 사용 (같은 파일명 기준으로 출력)
+        transcription = engine.transcribe(stt_input_path(record_id, file_path), output_dir, options)
+    except TranscriptionCancelled:
+        print(f"STT cancelled for task {task_id}")
+        return None, error_payload("Task was cancelled", code="cancelled")
+    except Exception as e:
+        print(f"STT process failed: {e}")
+        if task_id:
+            update_task_progress(task_id, f"STT 실패: {e}")
+        return None, error_payload(e, message=f"STT process failed: {e}", fallback="stt_failed")
+
+    stt_file = transcription.output_path
+    apply_punctuation_step(stt_file, transcription.language or options.language, record_id, task_id)
+    share_artifact(stt_file)
+    if record_id:
+        update_task_completion(record_id, "stt", to_record_path(stt_file))
+        record_event(
+            record_id,
+            "stt_completed",
+            source="audio",
+            model=options.model,
+            language=transcription.language or options.language or "auto",
+            language_detection=transcription.language_detection,
+            device=options.device,
+            prompt=transcription.prompt,
+            decoding=transcription.decoding,
+            **engine.describe(),
+        )
+        if auto_label_speakers(record_id, stt_file) and task_id:
+            update_task_progress(task_id, "등록된 화자 프로필로 화자 이름 지정")
+    return stt_file, None
+
+
+def restore_transcript_punctuation(stt_file: Path, model: str = None, task_id: str = None) -> dict:
+    """Restore punctuation/casing of a transcript's segments with an LLM and rewrite its markdown.
+
+    Raises :class:`PunctuationError` when the transcript has no segments or every batch failed.
+    """
+    segments_path = segments_path_for(stt_file)
+    document = load_segments(segments_path)
+    segments = (document or {}).get("segments") or []
+    if not segments:
+        raise PunctuationError("타임스탬프 세그먼트가 없는 전사는 문장 부호를 복원할 수 없습니다.")
+    model = model or PUNCTUATION_MODEL or DEFAULT_MODEL
+
+    def generate(prompt: str) -> str:
+        return summarize_workflow.call_ollama_with_retry(model, prompt, temperature=0)
+
+    def progress(batch: int, total: int):
+        if task_id:
+            update_task_progress(task_id, f"문장 부호 복원 중... ({batch}/{total})")
+
+    texts, stats = restore_punctuation([segment["text"] for segment in segments], generate, progress=progress)
+    for segment, text in zip(segments, texts):
+        segment["text"] = text
+    document["punctuation"] = {"model": model, "restored_at": datetime.now().isoformat(), **stats}
+    save_segments(segments_path, document)
+
+    markdown = read_text_with_fallback(stt_file)
+    detach_artifact(stt_file)
+    stt_file.write_text(
+        render_transcript_markdown(transcript_title_line(markdown, stt_file.stem), segments), encoding="utf-8"
+    )
+    invalidate_search_responses()
+    return {"model": model, **stats}
+
+
+def apply_punctuation_step(stt_file: Path, language: str, record_id: str = None, task_id: str = None):
+    """Run punctuation restoration after STT when enabled for ``language`` (failures keep the raw text)."""
+    if not PUNCTUATION_RESTORE_ENABLED or not should_restore_punctuation(language):
+        return
+    if task_id:
+        update_task_progress(task_id, "문장 부호 복원 중...")
+    try:
+        report = restore_transcript_punctuation(stt_file, task_id=task_id)
+    except Exception as e:
+        print(f"문장 부호 복원 실패 (원문 유지): {e}")
+        if task_id:
+            update_task_progress(task_id, "문장 부호 복원 실패, 원문 유지")
+        return
+    if record_id:
+        record_event(record_id, "punctuation_restored", **report)
+
+
+def check_workflow_disk_space(file_path: Path, steps, record_id: str = None) -> None:
+    """Raise :class:`InsufficientDiskSpace` when the workflow's artifacts would not fit on the DB disk."""
+    record = next((r for r in load_upload_history() if r.get("id") == record_id), None) if record_id else None
+    file_type = get_file_type(file_path)
+    has_transcript = file_type == "audio" and find_existing_stt_file(file_path) is not None
+    required = estimate_workflow_bytes(file_path, file_type, steps,
+                                       parse_duration_seconds((record or {}).get("duration")), has_transcript)
+    ensure_free_space(LAYOUT.output_root, required, "작업 산출물 저장")
+
+
+def run_workflow(file_path: Path, steps, record_id: str = None, task_id: str = None, model_settings: dict = None,
+                 minutes_template: str = None, model_options: dict = None, client_id: str = None,
+                 one_line_options: dict = None, deadline_seconds: float = None, series_context: bool = None):
     """Run the requested workflow steps sequentially.
 
     Args:
@@ -1435,27 +3522,77 @@ def run_workflow(file_path: Path, steps, record_id: str = None, task_id: str = N
         steps: list of step names, e.g. ["stt", "correct", "summary"].
         record_id: Upload record ID for updating history.
         task_id: Unique task ID for tracking and cancellation.
+        minutes_template: Template ID; when set, minutes are rendered after the summary.
+        model_options: Validated Ollama options passed through to the summary model.
+        client_id: Frontend client that started the task (for orphan detection).
+        one_line_options: Validated one-line summary length/tone/language overrides.
+        deadline_seconds: Overall time budget (default ``WORKFLOW_DEADLINE_MINUTES``, see deadline.py).
+        series_context: Summarize with the previous summaries of the record's series
+            (default: the series' ``rolling_context``, see meeting_series.py).
 
     Returns:
-        Dict mapping step name to download URL.
+        Dict mapping step name to download URL. When the deadline passes, a
+        ``deadline_exceeded`` error with the steps finished before it
+        (``partial_results``; their artifacts are kept).
     """
+    deadline = resolve_deadline(deadline_seconds)
+    if deadline is None:
+        return _run_workflow_steps(file_path, steps, record_id, task_id, model_settings, minutes_template,
+                                   model_options, client_id, one_line_options, series_context=series_context)
 
     results = {}
+    # 제한 시간이 되면 취소 이벤트로 Whisper 추론과 대기열 대기를 멈춤
+    timer = deadline.arm(register_task(task_id).set) if task_id else None
+    try:
+        with deadline_scope(deadline):
+            outcome = _run_workflow_steps(file_path, steps, record_id, task_id, model_settings, minutes_template,
+                                          model_options, client_id, one_line_options, results,
+                                          series_context=series_context)
+    finally:
+        if timer:
+            timer.cancel()
+    if deadline.expired and "error" in outcome:
+        message = f"작업 제한 시간({deadline.seconds:g}초)을 넘겨 중단했습니다."
+        if task_id:
+            update_task_progress(task_id, message)
+        if record_id:
+            record_event(record_id, "workflow_deadline_exceeded", deadline_seconds=deadline.seconds,
+                         completed_steps=sorted(results))
+        return error_payload(DeadlineExceeded(message), partial_results=results)
+    return outcome
+
+
+def _run_workflow_steps(file_path: Path, steps, record_id: str = None, task_id: str = None,
+                        model_settings: dict = None, minutes_template: str = None, model_options: dict = None,
+                        client_id: str = None, one_line_options: dict = None, results: dict = None,
+                        series_context: bool = None):
+    """Steps of :func:`run_workflow`; finished steps are added to ``results`` as they complete."""
+
+    results = {} if results is None else results
     current_file = file_path
     file_type = get_file_type(file_path)
+    # 작업 ID 없이 호출돼도 단계 대기열에는 들어감
+    queue_key = task_id or f"workflow-{uuid.uuid4()}"
+    if task_id:
+        register_task(task_id)
+        task_journal.start(task_id, record_id, steps, client_id)
+        note_task_log(task_id, record_id=record_id)
     
     # Create individual output directory based on upload folder structure
-    upload_folder_name = current_file.parent.name  # Get UUID folder name
-    individual_output_dir = OUTPUT_DIR / upload_folder_name
-    individual_output_dir.mkdir(exist_ok=True)
+    upload_folder_name = LAYOUT.folder_for_path(current_file)  # Get UUID folder name
+    individual_output_dir = LAYOUT.output_dir(upload_folder_name)
 
     try:
+        # 중간에 공간이 모자라 반쯤 쓴 산출물이 남지 않도록 시작 전에 확인
+        check_workflow_disk_space(file_path, steps, record_id)
+        individual_output_dir.mkdir(parents=True, exist_ok=True)
+
         # For text files, skip STT step and copy to output directory
         if file_type == 'text':
             if "stt" in steps:
                 # Check if task was cancelled
                 if task_id and is_task_cancelled(task_id):
-                    return {"error": "Task was cancelled"}
+                    return error_payload("Task was cancelled", code="cancelled")
                     
                 # For text files, we already have the text content, so just copy it to output
                 text_file = individual_output_dir / f"{file_path.stem}.md"
@@ -1470,19 +3607,22 @@ def run_workflow(file_path: Path, steps, record_id: str = None, task_id: str = N
                 if record_id:
                     file_path_str = to_record_path(text_file)
                     update_task_completion(record_id, "stt", file_path_str)
+                    record_event(record_id, "stt_completed", source="text")
             else:
                 # If no STT step for text file, use the original file as starting point
                 # Copy to output directory for consistency
                 text_file = individual_output_dir / f"{file_path.stem}.md"
-                import shutil
-                shutil.copy2(file_path, text_file)
+                # 이미 있는 전사는 사용자가 고쳤을 수 있으므로 원본으로 덮어쓰지 않음
+                if not text_file.exists():
+                    import shutil
+                    shutil.copy2(file_path, text_file)
                 current_file = text_file
             
 
         # For PDF files, extract text and treat as markdown
         elif file_type == 'pdf':
             if task_id and is_task_cancelled(task_id):
-                return {"error": "Task was cancelled"}
+                return error_payload("Task was cancelled", code="cancelled")
 
             try:
                 from pypdf import PdfReader
@@ -1490,7 +3630,7 @@ def run_workflow(file_path: Path, steps, record_id: str = None, task_id: str = N
                 pdf_text = "\n".join(page.extract_text() or "" for page in reader.pages)
             except Exception as e:
                 print(f"PDF text extraction failed: {e}")
-                return {"error": f"PDF text extraction failed: {e}"}
+                return error_payload(e, code="media_unsupported", message=f"PDF text extraction failed: {e}")
 
             text_file = individual_output_dir / f"{file_path.stem}.md"
             text_file.write_text(pdf_text, encoding='utf-8')
@@ -1501,6 +3641,7 @@ def run_workflow(file_path: Path, steps, record_id: str = None, task_id: str = N
                 if record_id:
                     file_path_str = to_record_path(text_file)
                     update_task_completion(record_id, "stt", file_path_str)
+                    record_event(record_id, "stt_completed", source="pdf")
 
             current_file = text_file
             
@@ -1509,68 +3650,23 @@ def run_workflow(file_path: Path, steps, record_id: str = None, task_id: str = N
         elif file_type == 'audio' and "stt" in steps:
             # Check if task was cancelled before starting STT
             if task_id and is_task_cancelled(task_id):
-                return {"error": "Task was cancelled"}
+                return error_payload("Task was cancelled", code="cancelled")
                 
             print(f"Starting STT for task {task_id}")
-            
-            # Create progress callback function
-            def progress_callback(message):
-                if task_id:
-                    update_task_progress(task_id, message)
-                    
-            # Get Whisper model from settings, default to large-v3-turbo
-            whisper_model = "large-v3-turbo"
-            if model_settings and model_settings.get("whisper"):
-                whisper_model = model_settings["whisper"]
-
-            # Get Whisper language from settings, default to Korean
-            language = "ko"
-            if model_settings and model_settings.get("language") is not None:
-                lang = model_settings.get("language")
-                if lang in ("", "auto"):
-                    language = None
-                else:
-                    language = lang
-
-            device_choice = "auto"
-            if model_settings and model_settings.get("device"):
-                device_choice = model_settings.get("device")
-
-            try:
-                transcribe_audio_files(
-                    input_dir=str(current_file.parent),
-                    output_dir=str(individual_output_dir),
-                    model_identifier=whisper_model,
-                    language=language,
-                    initial_prompt="",
-                    workers=1,
-                    recursive=False,
-                    filter_fillers=False,
-                    min_seg_length=2,
-                    normalize_punct=False,
-                    requested_device=device_choice,
-                    progress_callback=progress_callback
-                )
-            except Exception as e:
-                print(f"STT process failed: {e}")
-                if task_id:
-                    update_task_progress(task_id, f"STT 실패: {e}")
-                return {"error": f"STT process failed: {e}"}
 
-            stt_file = individual_output_dir / f"{file_path.stem}.md"
-            download_url = f"/download/{upload_folder_name}/{stt_file.name}"
-            results["stt"] = download_url
-            current_file = stt_file
+            if not wait_for_workflow_slot(queue_key, "stt", task_id):
+                return error_payload("Task was cancelled", code="cancelled")
+            stt_file, error = run_stt_step(file_path, individual_output_dir, model_settings, task_id, record_id)
+            if error:
+                return error
 
-            # Update history
-            if record_id:
-                file_path_str = to_record_path(stt_file)
-                update_task_completion(record_id, "stt", file_path_str)
+            results["stt"] = f"/download/{upload_folder_name}/{stt_file.name}"
+            current_file = stt_file
 
         if "embedding" in steps and current_file:
             # Check if task was cancelled
             if task_id and is_task_cancelled(task_id):
-                return {"error": "Task was cancelled"}
+                return error_payload("Task was cancelled", code="cancelled")
             
             # For audio files, check if we have the text file (STT completed)
             if file_type == 'audio' and current_file == file_path:
@@ -1595,73 +3691,40 @@ def run_workflow(file_path: Path, steps, record_id: str = None, task_id: str = N
                     # No existing STT result, run STT first
                     if task_id:
                         update_task_progress(task_id, "STT 자동 실행 시작")
-                    try:
-                        def progress_callback(message):
-                            if task_id:
-                                update_task_progress(task_id, message)
-
-                        # Get Whisper model from settings, default to large-v3-turbo
-                        whisper_model = "large-v3-turbo"
-                        if model_settings and model_settings.get("whisper"):
-                            whisper_model = model_settings["whisper"]
-
-                        # Get Whisper language from settings, default to Korean
-                        language = "ko"
-                        if model_settings and model_settings.get("language") is not None:
-                            lang = model_settings.get("language")
-                            if lang in ("", "auto"):
-                                language = None
-                            else:
-                                language = lang
-
-                        device_choice = "auto"
-                        if model_settings and model_settings.get("device"):
-                            device_choice = model_settings.get("device")
-
-                        transcribe_audio_files(
-                            input_dir=str(current_file.parent),
-                            output_dir=str(individual_output_dir),
-                            model_identifier=whisper_model,
-                            language=language,
-                            initial_prompt="",
-                            workers=1,
-                            recursive=False,
-                            filter_fillers=False,
-                            min_seg_length=2,
-                            normalize_punct=False,
-                            requested_device=device_choice,
-                            progress_callback=progress_callback
-                        )
-                    except Exception as e:
-                        print(f"STT process failed: {e}")
-                        if task_id:
-                            update_task_progress(task_id, f"STT 실패: {e}")
-                        return {"error": f"STT process failed: {e}"}
+                    if not wait_for_workflow_slot(queue_key, "stt", task_id):
+                        return error_payload("Task was cancelled", code="cancelled")
+                    stt_file, error = run_stt_step(file_path, individual_output_dir, model_settings, task_id, record_id)
+                    if error:
+                        return error
 
-                    stt_file = individual_output_dir / f"{file_path.stem}.md"
-                    download_url = f"/download/{upload_folder_name}/{stt_file.name}"
-                    results["stt"] = download_url
+                    results["stt"] = f"/download/{upload_folder_name}/{stt_file.name}"
                     current_file = stt_file
 
-                    # Update history
-                    if record_id:
-                        file_path_str = to_record_path(current_file)
-                        update_task_completion(record_id, "stt", file_path_str)
-
+            if not wait_for_workflow_slot(queue_key, "embedding", task_id):
+                return error_payload("Task was cancelled", code="cancelled")
             if task_id:
                 update_task_progress(task_id, "임베딩 생성 시작")
 
-            if generate_embedding(current_file, record_id):
+            def embedding_progress(done: int, total: int):
+                if task_id and total > 1:
+                    update_task_progress(task_id, f"임베딩 생성 중 ({done}/{total})")
+
+            try:
+                entry = embed_record_file(current_file, record_id, progress_callback=embedding_progress)
+                results["embedding"] = f"/download/{upload_folder_name}/{current_file.name}"
                 if task_id:
-                    update_task_progress(task_id, "임베딩 생성 완료")
-            else:
+                    update_task_progress(task_id, f"임베딩 생성 완료 (청크 {entry['chunks']}개)")
+            except Exception as e:
+                # 임베딩 실패는 요약 등 다음 단계를 막지 않음
+                print(f"Embedding generation failed for {current_file.name}: {e}")
+                results["embedding_error"] = str(e)
                 if task_id:
                     update_task_progress(task_id, "임베딩 생성 실패")
 
         if "summary" in steps:
             # Check if task was cancelled before starting summary
             if task_id and is_task_cancelled(task_id):
-                return {"error": "Task was cancelled"}
+                return error_payload("Task was cancelled", code="cancelled")
 
             # For audio files, check if we have the text file (STT completed)
             if file_type == 'audio' and current_file == file_path:
@@ -1686,61 +3749,19 @@ def run_workflow(file_path: Path, steps, record_id: str = None, task_id: str = N
                     # No existing STT result, run STT first
                     if task_id:
                         update_task_progress(task_id, "STT 자동 실행 시작")
-                    try:
-                        def progress_callback(message):
-                            if task_id:
-                                update_task_progress(task_id, message)
-
-                        # Get Whisper model from settings, default to large-v3-turbo
-                        whisper_model = "large-v3-turbo"
-                        if model_settings and model_settings.get("whisper"):
-                            whisper_model = model_settings["whisper"]
-
-                        # Get Whisper language from settings, default to Korean
-                        language = "ko"
-                        if model_settings and model_settings.get("language") is not None:
-                            lang = model_settings.get("language")
-                            if lang in ("", "auto"):
-                                language = None
-                            else:
-                                language = lang
-
-                        device_choice = "auto"
-                        if model_settings and model_settings.get("device"):
-                            device_choice = model_settings.get("device")
-
-                        transcribe_audio_files(
-                            input_dir=str(current_file.parent),
-                            output_dir=str(individual_output_dir),
-                            model_identifier=whisper_model,
-                            language=language,
-                            initial_prompt="",
-                            workers=1,
-                            recursive=False,
-                            filter_fillers=False,
-                            min_seg_length=2,
-                            normalize_punct=False,
-                            requested_device=device_choice,
-                            progress_callback=progress_callback
-                        )
-                    except Exception as e:
-                        print(f"STT process failed: {e}")
-                        if task_id:
-                            update_task_progress(task_id, f"STT 실패: {e}")
-                        return {"error": f"STT process failed: {e}"}
+                    if not wait_for_workflow_slot(queue_key, "stt", task_id):
+                        return error_payload("Task was cancelled", code="cancelled")
+                    stt_file, error = run_stt_step(file_path, individual_output_dir, model_settings, task_id, record_id)
+                    if error:
+                        return error
 
-                    stt_file = individual_output_dir / f"{file_path.stem}.md"
-                    download_url = f"/download/{upload_folder_name}/{stt_file.name}"
-                    results["stt"] = download_url
+                    results["stt"] = f"/download/{upload_folder_name}/{stt_file.name}"
                     current_file = stt_file
 
-                    # Update history
-                    if record_id:
-                        file_path_str = to_record_path(current_file)
-                        update_task_completion(record_id, "stt", file_path_str)
-                
             source_text_path = Path(current_file) if current_file else None
 
+            if not wait_for_workflow_slot(queue_key, "summary", task_id):
+                return error_payload("Task was cancelled", code="cancelled")
             print(f"Starting summary for task {task_id}")
             if task_id:
                 update_task_progress(task_id, "요약 생성 시작")
@@ -1754,26 +3775,101 @@ def run_workflow(file_path: Path, steps, record_id: str = None, task_id: str = N
                 text = read_text_with_fallback(Path(current_file))
                 if task_id:
                     update_task_progress(task_id, "텍스트 분석 중...")
+                # 요청에 요약 모델이 없으면 전사 언어에 지정된 모델 사용
+                transcript_language = None
+                if not (model_settings and model_settings.get("summarize")):
+                    routed_model, transcript_language = routed_summary_model(record_id, text)
+                    if routed_model:
+                        summarize_model = routed_model
+                        print(f"전사 언어 {transcript_language} → 요약 모델 {summarize_model}")
                     
                 # Create progress callback function for summary
                 def summary_progress_callback(message):
                     if task_id:
                         update_task_progress(task_id, message)
+
+                # 요약이 끝난 뒤 프롬프트가 재로드돼도 실제로 사용한 버전을 기록
+                prompt_version = summarize_workflow.get_prompt_version()
+                summary_model_options = resolve_model_options(model_options)
+
+                # SUMMARY_DEBUG_ENABLED일 때 청크 요약/리듀스 중간 결과와 프롬프트를 작업별로 보존
+                summary_trace = None
+                if summary_debug.SUMMARY_DEBUG_ENABLED:
+                    summary_trace = SummaryTrace(
+                        task_id=queue_key,
+                        record_id=record_id,
+                        source_file=Path(current_file).name,
+                        model=summarize_model,
+                        prompt_version=prompt_version,
+                        chunk_size=summarize_workflow.DEFAULT_CHUNK_SIZE,
+                        temperature=summarize_workflow.DEFAULT_TEMPERATURE,
+                        model_options=summary_model_options,
+                    )
                 
+                # 시리즈에 속한 기록이면 이전 회의 요약을 넣어 변경 사항/이월 실행 항목 표시
+                series_text, series_record_ids = series_summary_context(record_id, series_context)
+                if series_record_ids and task_id:
+                    update_task_progress(task_id, f"이전 회의 요약 {len(series_record_ids)}개 참고 중...")
+                people_text, people_names = people_summary_context(record_id, text)
+                # 전사를 고친 뒤에는 바뀐 청크만 다시 요약
+                chunk_cache = (ChunkSummaryCache(summary_cache_path_for(Path(current_file)))
+                               if incremental_summary.SUMMARY_INCREMENTAL else None)
+                # markdown(기본) 또는 structured(JSON으로 받아 summary.json도 저장)
+                output_format = summary_format((model_settings or {}).get("summary_format"))
+                # 모델 컨텍스트와 전사 밀도로 청크 크기/num_ctx 결정 (summary_chunking)
+                chunk_plan = summarize_workflow.plan_summary(
+                    text, summarize_model, summarize_workflow.DEFAULT_CHUNK_SIZE,
+                    model_options=summary_model_options, series_context=series_text, people_context=people_text,
+                )
+
                 summary = summarize_text_mapreduce(
                     text=text,
                     model=summarize_model,
-                    chunk_size=DEFAULT_CHUNK_SIZE,
+                    # /admin/reload로 바뀐 값을 쓰도록 모듈에서 매번 읽음
+                    chunk_size=summarize_workflow.DEFAULT_CHUNK_SIZE,
                     max_tokens=None,
-                    temperature=DEFAULT_TEMPERATURE,
-                    progress_callback=summary_progress_callback
+                    temperature=summarize_workflow.DEFAULT_TEMPERATURE,
+                    progress_callback=summary_progress_callback,
+                    trace=summary_trace,
+                    model_options=summary_model_options,
+                    series_context=series_text,
+                    people_context=people_text,
+                    chunk_cache=chunk_cache,
+                    chunk_plan=chunk_plan,
+                    structured=output_format == "structured",
+                    on_token=None if output_format == "structured" else summary_token_sink(task_id),
                 )
+                structured = None
+                if output_format == "structured":
+                    # 기존 소비자(실행 항목, 회의록, 검색, Obsidian)는 JSON에서 만든 마크다운을 그대로 읽음
+                    structured = summary
+                    summary = structured.to_markdown(summarize_workflow.SECTION_HEADINGS[structured.language])
+                chunk_stats = {}
+                if chunk_cache is not None:
+                    chunk_cache.save()
+                    chunk_stats = chunk_cache.stats()
+                    if chunk_stats["chunks_reused"]:
+                        print(f"청크 요약 재사용: {chunk_stats['chunks_reused']}개, 새로 요약: {chunk_stats['chunks_summarized']}개")
                 
                 if task_id:
                     update_task_progress(task_id, "요약 파일 저장 중...")
                     
                 output_file = Path(current_file).with_name(f"{Path(current_file).stem}.summary.md")
                 save_output(summary, output_file, as_json=False)
+                share_artifact(output_file)
+                if structured is not None:
+                    save_structured_summary(structured, output_file)
+                else:
+                    discard_structured_summary(output_file)
+                if record_id:
+                    sync_action_items(record_id, summary)
+
+                if summary_trace is not None:
+                    try:
+                        write_summary_debug(output_file.parent, summary_trace)
+                        note_task_log(task_id, summary_debug_dir=str(output_file.parent))
+                    except OSError as debug_error:
+                        print(f"요약 디버그 산출물 저장 실패: {debug_error}")
 
                 # Obsidian MCP 자동 전송
                 try:
@@ -1824,36 +3920,403 @@ def run_workflow(file_path: Path, steps, record_id: str = None, task_id: str = N
                 print(f"Summary process failed: {e}")
                 if task_id:
                     update_task_progress(task_id, f"요약 생성 실패: {e}")
-                return {"error": f"Summary process failed: {e}"}
+                return error_payload(e, message=f"Summary process failed: {e}", fallback="summary_failed")
 
             summary_file = current_file.with_name(f"{current_file.stem}.summary.md")
             download_url = f"/download/{upload_folder_name}/{summary_file.name}"
             results["summary"] = download_url
+            if output_format == "structured":
+                results["summary_structured"] = f"/download/{upload_folder_name}/{structured_path_for(summary_file).name}"
             current_file = summary_file
 
             # Update history
+            title_summary = None
             if record_id:
                 file_path_str = to_record_path(summary_file)
                 update_task_completion(record_id, "summary", file_path_str)
+                update_summary_prompt_version(record_id, prompt_version)
+                record_event(record_id, "summary_generated", model=summarize_model,
+                             prompt_version=prompt_version, series_context_records=series_record_ids,
+                             people=people_names, transcript_language=transcript_language,
+                             chunk_plan=chunk_plan.to_dict(), summary_format=output_format, **chunk_stats)
                 if source_text_path:
-                    generate_and_store_title_summary(record_id, source_text_path, summarize_model)
+                    title_summary = generate_and_store_title_summary(
+                        record_id, source_text_path, summarize_model, one_line_options
+                    )
+
+            # 요약 본문과 한 줄 요약을 전사와 별도로 색인 (실패해도 요약 결과는 유지)
+            if task_id:
+                update_task_progress(task_id, "요약 색인 생성 중...")
+            generate_embedding(summary_file, record_id, kind="summary", title=title_summary)
+
+            if minutes_template and record_id:
+                if task_id:
+                    update_task_progress(task_id, "회의록 생성 중...")
+                try:
+                    _, results["minutes"] = render_minutes(record_id, minutes_template)
+                except (TemplateError, OSError) as e:
+                    print(f"회의록 생성 실패: {e}")
+                    results["minutes_error"] = str(e)
 
     except Exception as exc:  # pragma: no cover - best effort error handling
         # Clean up process registration if something goes wrong
         if task_id:
             unregister_process(task_id)
             update_task_progress(task_id, f"작업 실패: {exc}")
-        return {"error": str(exc)}
+        return error_payload(exc)
     
     finally:
+        WORKFLOW_QUEUE.release(queue_key)
         # Clear progress when task completes
         if task_id:
             clear_task_progress(task_id)
+            unregister_task(task_id)
+            task_journal.finish(task_id)
+
+    if record_id:
+        notify_completed_steps(record_id, results)
+        if "embedding" in steps or "summary" in steps:
+            # 색인이 끝난 기록을 감시 규칙과 비교 (질의 임베딩 때문에 응답을 늦추지 않도록 백그라운드)
+            threading.Thread(target=evaluate_watch_rules, args=(record_id,), daemon=True).start()
+    return results
+
+
+def stale_summary_record_ids() -> list[str]:
+    """IDs of records whose summary was made with an older prompt version."""
+    current_version = summarize_workflow.get_prompt_version()
+    return [record["id"] for record in find_stale_summaries(load_upload_history(), current_version)]
+
+
+def regenerate_record_summary(record_id: str) -> dict:
+    """Re-run only the summary step of a record with the current prompts."""
+    record = next((r for r in load_upload_history() if r.get("id") == record_id), None)
+    if not record or record.get("deleted"):
+        return {"error": "기록을 찾을 수 없습니다."}
+    file_path = resolve_record_path(normalize_record_path(record.get("file_path", "")))
+    if not file_path.exists():
+        return {"error": f"원본 파일을 찾을 수 없습니다: {file_path.name}"}
+    return run_workflow(file_path, ["summary"], record_id, str(uuid.uuid4()))
+
+
+def retranscribe_record_range(record_id: str, start, end, model: str = None, prompt: str = None,
+                              language: str = None, task_id: str = None, decoding: str = None) -> dict:
+    """Re-transcribe ``[start, end)`` of a record and splice it into its transcript.
+
+    The embedding and summary built from the old transcript are flagged in
+    ``stale_artifacts`` until they are regenerated. Raises
+    :class:`RetranscribeError` for invalid ranges or records without audio.
+    """
+    record = next((r for r in load_upload_history() if r.get("id") == record_id), None)
+    if not record or record.get("deleted"):
+        raise RetranscribeError("기록을 찾을 수 없습니다.")
+    stt_link = (record.get("download_links") or {}).get("stt")
+    stt_path = resolve_file_identifier(stt_link)[0] if stt_link else None
+    if not stt_path or not Path(stt_path).exists():
+        raise RetranscribeError("STT 결과가 없는 기록입니다.")
+    file_path = resolve_record_path(normalize_record_path(record.get("file_path", "")))
+    if get_file_type(file_path) != "audio" or not file_path.exists():
+        raise RetranscribeError("원본 오디오가 없는 기록은 구간 재변환을 할 수 없습니다.")
+
+    start, end = parse_time_range(start, end, parse_duration_seconds(record.get("duration")))
+    try:
+        decoding = resolve_decoding_strategy(decoding)
+    except DecodingStrategyError as e:
+        raise RetranscribeError(str(e)) from None
+    document = load_record_segments(record) or {}
+    if task_id:
+        register_task(task_id)
+
+    def progress_callback(message):
+        if task_id:
+            update_task_progress(task_id, message)
+
+    options = TranscriptionOptions(
+        model=model or document.get("model") or "large-v3-turbo",
+        language=language or document.get("language"),
+        initial_prompt=prompt or "",
+        decoding_strategy=decoding,
+        min_seg_length=2,
+        progress_callback=progress_callback,
+        queue_callback=lambda position, estimated_start: update_task_queue(task_id, position, estimated_start),
+        cancel_event=get_cancel_event(task_id),
+    )
+    try:
+        report = retranscribe_range(
+            stt_input_path(record_id, file_path), Path(stt_path), start, end, get_stt_engine(), options
+        )
+    finally:
+        if task_id:
+            clear_task_progress(task_id)
+            unregister_task(task_id)
+
+    report["stale_artifacts"] = mark_artifacts_stale(record_id, ["embedding", "summary"])
+    record_event(
+        record_id,
+        "stt_retranscribed",
+        start=start,
+        end=end,
+        model=report["model"],
+        backend=report["backend"],
+        replaced=report["replaced"],
+        inserted=report["inserted"],
+    )
+    return report
+
+
+def run_url_upload(task_id: str, url: str, steps: list, use_ytdlp: bool = None, **workflow_options):
+    """Background job of ``POST /upload_url``: download, create the record, then run ``steps``."""
+    cancel_event = register_task(task_id)
+    save_dir = LAYOUT.upload_dir(uuid.uuid4().hex)
+    record = None
+    try:
+        downloaded = download_media(url, save_dir, lambda message: update_task_progress(task_id, message),
+                                    cancel_event, use_ytdlp)
+        downloaded_hash = file_hash(downloaded)
+        existing = next((r for r in load_upload_history() if r.get('file_hash') == downloaded_hash), None)
+        if existing:
+            shutil.rmtree(save_dir, ignore_errors=True)
+            update_url_job(task_id, status="duplicate", duplicate_of=existing["id"], filename=downloaded.name)
+            return
+        record = register_uploaded_file(downloaded, downloaded_hash, source_url=url)
+    except UrlIngestCancelled as e:
+        shutil.rmtree(save_dir, ignore_errors=True)
+        update_url_job(task_id, status="cancelled", error=error_payload(e, code="cancelled"))
+        return
+    except Exception as e:
+        print(f"URL 업로드 실패 ({url}): {e}")
+        shutil.rmtree(save_dir, ignore_errors=True)
+        update_url_job(task_id, status="failed", error=error_payload(e))
+        return
+    finally:
+        # 이어서 처리할 단계가 있으면 run_workflow가 같은 task_id를 이어받아 정리
+        if record is None or not steps:
+            clear_task_progress(task_id)
+            unregister_task(task_id)
+
+    update_url_job(task_id, record_id=record["id"], file_path=record["file_path"], filename=record["filename"],
+                   status="processing" if steps else "completed")
+    if not steps:
+        return
+    results = run_workflow(downloaded, steps, record["id"], task_id, **workflow_options)
+    if "error" in results:
+        update_url_job(task_id, status="cancelled" if results.get("code") == "cancelled" else "failed",
+                       error=results)
+    else:
+        update_url_job(task_id, status="completed", results=results)
 
+
+def validate_steps(steps) -> list:
+    """``steps`` when it lists known workflow steps (``TASK_TYPES``) without repeats; ``ValueError`` otherwise."""
+    if not isinstance(steps, list) or not all(isinstance(step, str) for step in steps):
+        raise ValueError("steps는 문자열 배열이어야 합니다.")
+    unknown = [step for step in steps if step not in TASK_TYPES]
+    if unknown:
+        raise ValueError(f"알 수 없는 단계입니다: {', '.join(unknown)} (가능: {', '.join(TASK_TYPES)})")
+    repeated = sorted({step for step in steps if steps.count(step) > 1})
+    if repeated:
+        raise ValueError(f"중복된 단계입니다: {', '.join(repeated)}")
+    return steps
+
+
+def start_workflow_thread(file_path: Path, steps: list, record_id: str, deadline_seconds: float = None) -> str:
+    """Run ``steps`` for a record in a background thread; returns the task id to poll."""
+    task_id = str(uuid.uuid4())
+    threading.Thread(target=run_workflow, args=(file_path, steps, record_id, task_id),
+                     kwargs={"deadline_seconds": deadline_seconds}, daemon=True).start()
+    return task_id
+
+
+def ingest_inbound_email(inbound: InboundEmail) -> dict:
+    """Create records for the media attachments of an inbound email and process them.
+
+    Records are tagged with the sender's address. Mail from senders outside
+    ``EMAIL_IN_ALLOWED_SENDERS`` is ignored (``accepted: false``).
+    """
+    if not email_sender_allowed(inbound.sender):
+        print(f"허용되지 않은 발신자의 메일을 무시합니다: {inbound.sender}")
+        return {"accepted": False, "sender": inbound.sender, "records": []}
+    history = load_upload_history()
+    steps = email_processing_steps()
+    entries = []
+    for filename, data in inbound.attachments:
+        entry = store_uploaded_bytes(
+            filename, data, history, tags=[inbound.sender],
+            email_sender=inbound.sender, email_subject=inbound.subject or None,
+            email_message_id=inbound.message_id or None,
+        )
+        if steps and not entry.get("duplicate"):
+            entry["task_id"] = start_workflow_thread(resolve_record_path(entry["file_path"]), steps, entry["record_id"])
+        entries.append(entry)
+    print(f"메일 수신: {inbound.sender} '{inbound.subject}' → 첨부 {len(entries)}개")
+    return {"accepted": True, "sender": inbound.sender, "subject": inbound.subject, "records": entries}
+
+
+def ingest_watched_file(path: Path) -> dict:
+    """Create a record for a recording found in a ``WATCH_DIRS`` folder and process it with ``WATCH_STEPS``."""
+    history = load_upload_history()
+    entry = store_uploaded_bytes(path.name, path.read_bytes(), history, watched_path=str(path))
+    steps = folder_watch_steps()
+    if steps and not entry.get("duplicate"):
+        entry["task_id"] = start_workflow_thread(resolve_record_path(entry["file_path"]), steps, entry["record_id"])
+    return entry
+
+
+def export_sync_texts(record: dict, artifacts: list) -> dict:
+    """Transcript/summary texts of a record for the export sync (missing ones left out)."""
+    links = record.get("download_links") or {}
+    texts = {}
+    for artifact, link_key in (("transcript", "stt"), ("summary", "summary")):
+        if artifact not in artifacts or not links.get(link_key):
+            continue
+        path = resolve_file_identifier(links[link_key])[0]
+        if path and Path(path).exists():
+            texts[artifact] = read_text_with_fallback(Path(path))
+    return texts
+
+
+def start_export_sync_scheduler():
+    """Mirror new/changed artifacts to EXPORT_SYNC_TARGET periodically."""
+    thread = start_export_sync_poller(get_active_history, export_sync_texts)
+    if thread is None:
+        print("내보내기 동기화가 비활성화되어 있습니다.")
+    return thread
+
+
+def search_record_hits(query: str, target: str, limit: int) -> list:
+    """Vector search hits with the record they belong to (GraphQL/gRPC ``search``)."""
+    records = {record.get("folder_name"): record for record in get_active_history() if record.get("folder_name")}
+    folder_map = {folder: record.get("id") for folder, record in records.items()}
+    filenames = {record.get("id"): record.get("filename") for record in records.values()}
+    hits = search_vectors(query, BASE_DIR, top_k=limit, target=target)
+    results = []
+    for hit in hits:
+        record_id = _record_id_for_output_path(hit.get("file", ""), folder_map)
+        results.append({**hit, "record_id": record_id, "filename": filenames.get(record_id)})
     return results
 
 
-class UploadHandler(BaseHTTPRequestHandler):
+def ask_archive(question: str, top_k: int, namespaces=None, model: str = None) -> dict:
+    """Answer a question from the passages of the records the vector search finds (``POST /ask``)."""
+    records = {record.get("folder_name"): record for record in get_active_history() if record.get("folder_name")}
+    folder_map = {folder: record.get("id") for folder, record in records.items()}
+    by_id = {record.get("id"): record for record in records.values()}
+    candidates = []
+    hits = search_vectors(question, BASE_DIR, top_k=archive_qa.ASK_CANDIDATE_RECORDS * 2, namespaces=namespaces)
+    for hit in hits:
+        record_id = _record_id_for_output_path(hit.get("file", ""), folder_map)
+        if record_id in by_id and record_id not in candidates:
+            candidates.append(record_id)
+    passages = []
+    for record_id in candidates[:archive_qa.ASK_CANDIDATE_RECORDS]:
+        record = by_id[record_id]
+        stt_path = record_transcript_path(record)
+        segments = paragraph_source_segments(stt_path, "clean") if stt_path else None
+        if segments:
+            passages.extend(transcript_passages(record_id, record.get("filename"), segments))
+        elif stt_path:
+            passages.extend(text_passages(record_id, record.get("filename"), read_text_with_fallback(stt_path),
+                                          "transcript"))
+        else:
+            passages.extend(text_passages(record_id, record.get("filename"), _read_record_text(record, "summary")))
+    embedding_model = current_embedding_model()
+    ranked = rank_passages(question, passages,
+                           lambda text: cached_query_embedding(text, embedding_model, embed_text_ollama), top_k)
+    model = model or archive_qa.ASK_MODEL or summarize_workflow.DEFAULT_MODEL
+    result = answer_question(question, ranked, lambda prompt, num_ctx: summarize_workflow.call_ollama_with_retry(
+        model, prompt, temperature=archive_qa.ASK_TEMPERATURE, num_ctx=num_ctx,
+    ), model)
+    for citation in result["citations"]:
+        citation["link"] = f"/record/{citation['record_id']}"
+    return result
+
+
+_graphql_schema = None
+
+
+def graphql_schema():
+    """GraphQL schema bound to the server state (built on first use)."""
+    global _graphql_schema
+    if _graphql_schema is None:
+        _graphql_schema = build_graphql_schema(GraphQLSources(
+            records=get_active_history,
+            read_text=_read_record_text,
+            tasks=get_running_tasks,
+            latest_progress=progress_bus.latest,
+            wait_progress=progress_bus.wait,
+            search=search_record_hits,
+            stats=lambda top_tags: build_archive_stats(
+                get_active_history(), _record_language,
+                lambda record: _read_record_text(record, "summary"), top_tags,
+            ),
+        ))
+    return _graphql_schema
+
+
+def _grpc_store_upload(filename: str, data: bytes, tags: list) -> dict:
+    return store_uploaded_bytes(os.path.basename(filename), data, load_upload_history(), tags or None)
+
+
+def _grpc_start_steps(record_id: str, steps: list) -> str:
+    if not steps or any(step not in TASK_TYPES for step in steps):
+        raise PipelineError(f"steps는 {', '.join(TASK_TYPES)} 중 하나 이상이어야 합니다.")
+    record = next((r for r in get_active_history() if r.get("id") == record_id), None)
+    if record is None:
+        raise PipelineError("기록을 찾을 수 없습니다.", "NOT_FOUND")
+    file_path = resolve_record_path(normalize_record_path(record.get("file_path", "")))
+    if not file_path.exists():
+        raise PipelineError("원본 파일이 없습니다.", "FAILED_PRECONDITION")
+    return start_workflow_thread(file_path, steps, record_id)
+
+
+def _grpc_task_status(task_id: str):
+    running = get_running_tasks().get(task_id)
+    if running:
+        return running["status"]
+    return "finished" if any(task["task_id"] == task_id for task in get_finished_tasks()) else None
+
+
+def _grpc_transcript_segments(record_id: str, view: str) -> list:
+    try:
+        view = parse_transcript_view(view)
+    except TranscriptViewError as e:
+        raise PipelineError(str(e))
+    record = next((r for r in get_active_history() if r.get("id") == record_id), None)
+    stt_path = record_transcript_path(record) if record else None
+    if stt_path is None:
+        raise PipelineError("전사 결과가 있는 기록을 찾을 수 없습니다.", "NOT_FOUND")
+    segments = paragraph_source_segments(stt_path, view)
+    if segments is None:
+        raise PipelineError("이 전사에는 원문(verbatim) 보기가 없습니다.", "FAILED_PRECONDITION")
+    return segments
+
+
+def start_grpc_server():
+    """Serve the gRPC pipeline service (GRPC_ENABLED)."""
+    return start_grpc_service(PipelineSources(
+        store_upload=_grpc_store_upload,
+        start_steps=_grpc_start_steps,
+        record=lambda record_id: next((r for r in get_active_history() if r.get("id") == record_id), None),
+        task_status=_grpc_task_status,
+        latest_progress=progress_bus.latest,
+        wait_progress=progress_bus.wait,
+        search=search_record_hits,
+        transcript_segments=_grpc_transcript_segments,
+    ))
+
+
+def start_summary_regen_scheduler():
+    """Re-summarize stale records periodically (SUMMARY_REGEN_INTERVAL_HOURS)."""
+    thread = start_regeneration_scheduler(
+        # 점검 모드에서는 재생성 대상이 없는 것으로 보고 건너뜀
+        lambda: [] if maintenance_active() else stale_summary_record_ids(),
+        regenerate_record_summary, summarize_workflow.get_prompt_version
+    )
+    if thread is None:
+        print("요약 자동 재생성이 비활성화되어 있습니다.")
+    return thread
+
+
+class UploadHandler(CorsMixin, RequestLoggingMixin, BaseHTTPRequestHandler):
     def log_message(self, format, *args):
         """Override to filter out successful HTTP requests (200)."""
         # Only log non-200 status codes
@@ -1885,7 +4348,9 @@ class UploadHandler(BaseHTTPRequestHandler):
             self.send_response(404)
             self.end_headers()
 
-    def _serve_download(self, file_identifier: str):
+    def _serve_download(self, file_identifier: str, view: str = None):
+        record_id = None
+        task_type = None
         # Check if it's a UUID (new system) or file path (legacy system)
         if self._is_uuid(file_identifier):
             # New UUID-based system
@@ -1898,6 +4363,8 @@ class UploadHandler(BaseHTTPRequestHandler):
                 file_path = normalize_record_path(file_info["file_path"])
                 filename = file_info["original_filename"]
                 full_path = resolve_record_path(file_path)
+                record_id = file_info.get("record_id")
+                task_type = file_info.get("task_type")
             else:
                 self.send_response(404)
                 self.end_headers()
@@ -1908,24 +4375,31 @@ class UploadHandler(BaseHTTPRequestHandler):
             full_path = resolve_record_path(normalized_path)
             filename = os.path.basename(file_identifier) or full_path.name
 
+        body = None
+        if view is not None and full_path.exists():
+            # STT 파일은 ?view=verbatim|clean으로 보기를 고를 수 있음
+            try:
+                view = parse_transcript_view(view)
+            except TranscriptViewError as e:
+                self._send_json(400, {"error": str(e)})
+                return
+            if view != "clean":
+                rendered = transcript_view(full_path, view) if task_type == "stt" else None
+                if rendered is None:
+                    self._send_json(404, {"error": f"이 파일에는 '{view}' 보기가 없습니다."})
+                    return
+                body = rendered[0].encode("utf-8")
+
         if full_path.exists():
             self.send_response(200)
             self.send_header("Content-Type", "application/octet-stream")
-            
-            # RFC 6266: Use UTF-8 encoding for non-ASCII filenames
-            try:
-                # Try ASCII encoding first
-                filename.encode('ascii')
-                self.send_header("Content-Disposition", f"attachment; filename={filename}")
-            except UnicodeEncodeError:
-                # Use UTF-8 encoding for non-ASCII filenames
-                from urllib.parse import quote
-                encoded_filename = quote(filename.encode('utf-8'))
-                self.send_header("Content-Disposition", f"attachment; filename*=UTF-8''{encoded_filename}")
-                
+            self.send_header("Content-Disposition", content_disposition(sanitize_filename(filename, default="download")))
             self.end_headers()
-            with open(full_path, "rb") as f:
-                self.wfile.write(f.read())
+            if body is None:
+                with open(full_path, "rb") as f:
+                    body = f.read()
+            self.wfile.write(body)
+            record_event(record_id, "exported", task_type=task_type, filename=filename)
         else:
             self.send_response(404)
             self.end_headers()
@@ -1938,24 +4412,307 @@ class UploadHandler(BaseHTTPRequestHandler):
         except ValueError:
             return False
 
+    def _authorize(self) -> bool:
+        """Enforce API token scopes; sends 401/403 and returns ``False`` when denied."""
+        if is_public_request(self.command, self.path) or is_auth_exempt(self.command, self.path):
+            return True
+        # Mailgun은 토큰 헤더를 보낼 수 없으므로 서명 키가 있으면 본문 서명으로 인증
+        if self.command == "POST" and self.path == "/email/inbound" and EMAIL_IN_WEBHOOK_SIGNING_KEY:
+            return True
+        secret = token_from_headers(self.headers)
+        if not secret:
+            if not token_required(self.client_address[0], self.headers):
+                return True
+            self._send_json(401, {"error": "API 토큰이 필요합니다."}, {"WWW-Authenticate": "Bearer"})
+            return False
+        token = authenticate_token(secret)
+        if not token:
+            self._send_json(401, {"error": "유효하지 않은 API 토큰입니다."}, {"WWW-Authenticate": "Bearer"})
+            return False
+        self.annotate_request(token_id=token["id"])
+        if not scope_allows(token["scope"], self.command, self.path):
+            self._send_json(403, {
+                "error": f"'{token['scope']}' 토큰으로는 이 요청을 할 수 없습니다.",
+                "route_group": route_group(self.command, self.path),
+            })
+            return False
+        return True
+
     def do_GET(self):
+        if not self._authorize():
+            return
         if self.path == "/":
             self._serve_upload_page()
         elif self.path in ("/upload.css", "/upload.js"):
             content_type = "text/css" if self.path.endswith(".css") else "application/javascript"
             self._serve_static(self.path.lstrip("/"), content_type)
         elif self.path.startswith("/download/"):
-            file_identifier = unquote(self.path[len("/download/"):])
-            self._serve_download(file_identifier)
-        elif self.path == "/history":
-            self._serve_history()
-        elif self.path == "/tasks":
-            self._serve_running_tasks()
+            parsed = urlparse(self.path)
+            file_identifier = unquote(parsed.path[len("/download/"):])
+            self._serve_download(file_identifier, parse_qs(parsed.query).get("view", [None])[0])
+        elif urlparse(self.path).path == "/history":
+            self._serve_history(parse_qs(urlparse(self.path).query, keep_blank_values=True))
+        elif urlparse(self.path).path == "/history/changes":
+            params = parse_qs(urlparse(self.path).query)
+            try:
+                cursor = parse_cursor(params.get("cursor", [""])[0])
+                limit = max(0, int(params.get("limit", ["0"])[0])) or None
+            except ChangeCursorError as e:
+                self._send_json(400, {"error": str(e)})
+                return
+            except ValueError:
+                self._send_json(400, {"error": "limit은 정수여야 합니다."})
+                return
+            try:
+                self._send_json(200, changes_since(cursor, load_upload_history(), limit))
+            except ChangeCursorError as e:
+                self._send_json(410, {"error": str(e)})
+        elif self.path == "/postprocess/rules":
+            self._send_json(200, {
+                "default_language": POSTPROCESS_DEFAULT_LANGUAGE,
+                "languages": postprocess_rule_languages(),
+            })
+        elif re.match(r"^/postprocess/rules/[^/]+$", self.path):
+            try:
+                self._send_json(200, describe_rule_pack(unquote(self.path.split("/")[3])))
+            except RulePackError as e:
+                self._send_json(400, {"error": str(e)})
+        elif self.path == "/stt/vocabulary":
+            terms = custom_vocabulary()
+            self._send_json(200, {"path": str(vocabulary_path()), "count": len(terms), "terms": terms})
+        elif urlparse(self.path).path == "/config/bundle":
+            params = parse_qs(urlparse(self.path).query)
+            sections = [name for value in params.get("sections", []) for name in value.split(",") if name]
+            try:
+                bundle = export_bundle(sections or None)
+            except BundleError as e:
+                self._send_json(400, {"error": str(e), "available": list(CONFIG_BUNDLE_SECTIONS)})
+                return
+            filename = f"recordroute_config_{datetime.now().strftime('%Y%m%d')}.json"
+            body = json.dumps(bundle, ensure_ascii=False, indent=2).encode("utf-8")
+            self.send_response(200)
+            self.send_header("Content-Type", "application/json; charset=utf-8")
+            self.send_header("Content-Disposition", f'attachment; filename="{filename}"')
+            self.send_header("Content-Length", str(len(body)))
+            self.end_headers()
+            self.wfile.write(body)
+        elif urlparse(self.path).path == "/stats/archive":
+            params = parse_qs(urlparse(self.path).query)
+            try:
+                top_tags = max(1, min(int(params.get("top_tags", [DEFAULT_TOP_TAGS])[0]), 100))
+            except ValueError:
+                top_tags = DEFAULT_TOP_TAGS
+            stats = build_archive_stats(
+                get_active_history(), _record_language,
+                lambda record: _read_record_text(record, "summary"), top_tags,
+            )
+            self._send_json(200, stats)
+        elif self.path == "/tasks/orphaned":
+            tasks = task_journal.orphaned()
+            self._send_json(200, {"count": len(tasks), "tasks": tasks})
+        elif urlparse(self.path).path == "/tasks":
+            self._serve_running_tasks(parse_qs(urlparse(self.path).query, keep_blank_values=True))
+        elif urlparse(self.path).path == "/tasks/recent":
+            self._serve_paginated(parse_qs(urlparse(self.path).query, keep_blank_values=True), "tasks",
+                                  get_finished_tasks(), "tasks_recent",
+                                  lambda task: sort_key(task["finished_at"], task["task_id"], descending=True),
+                                  descending=True)
+        elif self.path == "/tasks/queue":
+            self._send_json(200, {**WORKFLOW_QUEUE.snapshot(), "inference": inference_priority_status()})
+        elif re.match(r"^/tasks/[^/]+/log$", self.path):
+            task_id = unquote(self.path.split("/")[2])
+            self.annotate_request(task_id=task_id)
+            self._serve_task_log(task_id)
+        elif re.match(r"^/tasks/[^/]+/wait(\?.*)?$", self.path):
+            parsed = urlparse(self.path)
+            task_id = unquote(parsed.path.split("/")[2])
+            self.annotate_request(task_id=task_id)
+            self._serve_task_wait(task_id, parse_qs(parsed.query))
+        elif re.match(r"^/public(/|$)", self.path):
+            self._serve_public(urlparse(self.path).path)
+        elif re.match(r"^/upload_url/[^/]+$", self.path):
+            task_id = unquote(self.path.split("/")[2])
+            self.annotate_request(task_id=task_id)
+            job = get_url_job(task_id)
+            if job is None:
+                self._send_json(404, {"error": "해당 URL 업로드 작업을 찾을 수 없습니다."})
+            else:
+                self._send_json(200, {"job": job, "progress": get_task_progress(task_id)})
         elif self.path.startswith("/progress/"):
             task_id = self.path[len("/progress/"):]
+            self.annotate_request(task_id=task_id)
             self._serve_task_progress(task_id)
+        elif re.match(r"^/record/[^/]+/summary/(bakeoffs|bakeoff/[^/]+)$", self.path):
+            parts = self.path.split("/")
+            self._serve_summary_bakeoff(unquote(parts[2]), unquote(parts[5]) if len(parts) > 5 else None)
+        elif re.match(r"^/export/[^/?]+(\?.*)?$", self.path):
+            parsed = urlparse(self.path)
+            self._serve_record_export(unquote(parsed.path.split("/")[2]), parse_qs(parsed.query))
+        elif re.match(r"^/record/[^/]+/transcript/export(\?.*)?$", self.path):
+            parsed = urlparse(self.path)
+            self._serve_transcript_export(unquote(parsed.path.split("/")[2]), parse_qs(parsed.query))
+        elif re.match(r"^/record/[^/]+/transcript(\?.*)?$", self.path):
+            parsed = urlparse(self.path)
+            self._serve_record_transcript(unquote(parsed.path.split("/")[2]), parse_qs(parsed.query))
+        elif re.match(r"^/record/[^/]+/summary/stream(\?.*)?$", self.path):
+            parsed = urlparse(self.path)
+            self._stream_record_summary(unquote(parsed.path.split("/")[2]), parse_qs(parsed.query))
+        elif re.match(r"^/record/[^/]+/summary$", self.path):
+            self._serve_record_summary(unquote(self.path.split("/")[2]))
+        elif re.match(r"^/record/[^/]+/summary_debug(\?.*)?$", self.path):
+            parsed = urlparse(self.path)
+            record_id = unquote(parsed.path.split("/")[2])
+            self._serve_summary_debug(record_id, parse_qs(parsed.query).get("task_id", [None])[0])
+        elif re.match(r"^/record/[^/]+/waveform(\?.*)?$", self.path):
+            parsed = urlparse(self.path)
+            record_id = unquote(parsed.path.split("/")[2])
+            self._serve_record_waveform(record_id, parse_qs(parsed.query))
+        elif re.match(r"^/record/[^/]+/speakers$", self.path):
+            record_id = unquote(self.path.split("/")[2])
+            self._serve_record_speakers(record_id)
+        elif re.match(r"^/record/[^/]+/thumbnail$", self.path):
+            record_id = unquote(self.path.split("/")[2])
+            self._serve_record_thumbnail(record_id)
+        elif urlparse(self.path).path == "/speakers/profiles":
+            self._serve_paginated(parse_qs(urlparse(self.path).query, keep_blank_values=True), "profiles",
+                                  list_profiles(), "speaker_profiles",
+                                  lambda profile: sort_key(profile.get("name"), profile.get("id")))
+        elif urlparse(self.path).path == "/minutes/templates":
+            # 기본 템플릿이 항상 맨 앞
+            self._serve_paginated(parse_qs(urlparse(self.path).query, keep_blank_values=True), "templates",
+                                  list_templates(), "minutes_templates",
+                                  lambda template: sort_key(0 if template.get("builtin") else 1, template.get("id")),
+                                  extra={"placeholders": MINUTES_PLACEHOLDERS})
+        elif self.path == "/ws/asyncapi.json":
+            self._send_json(200, asyncapi_document())
+        elif re.match(r"^/admin/orphans(\?.*)?$", self.path):
+            params = parse_qs(urlparse(self.path).query)
+            try:
+                min_age_hours = max(0.0, float(params.get("min_age_hours", [ORPHAN_MIN_AGE_HOURS])[0]))
+            except ValueError:
+                self._send_json(400, {"error": "min_age_hours는 숫자여야 합니다."})
+                return
+            self._send_json(200, orphan_report(find_orphan_artifacts(min_age_hours)))
+        elif self.path == "/admin/websocket/clients":
+            self._send_json(200, {"clients": websocket_client_stats()})
+        elif self.path == "/admin/export-sync":
+            self._send_json(200, export_sync_status())
+        elif self.path == "/admin/artifacts":
+            self._send_json(200, artifact_stats())
+        elif urlparse(self.path).path == "/admin/tokens":
+            self._serve_paginated(parse_qs(urlparse(self.path).query, keep_blank_values=True), "tokens",
+                                  list_tokens(), "api_tokens",
+                                  lambda token: sort_key(token.get("created_at"), token.get("id")),
+                                  extra={"scopes": list(API_TOKEN_SCOPES)})
+        elif urlparse(self.path).path == "/webhooks":
+            self._serve_paginated(parse_qs(urlparse(self.path).query, keep_blank_values=True), "webhooks",
+                                  list_webhooks(), "webhooks", lambda hook: sort_key(hook.get("id")),
+                                  extra={"events": list(WEBHOOK_EVENTS), "fields": WEBHOOK_CONTEXT_FIELDS},
+                                  render=lambda hooks: [masked_webhook(hook) for hook in hooks])
+        elif self.path == "/notifications":
+            self._send_json(200, {
+                "channels": notification_channels(),
+                "events": list(WEBHOOK_EVENTS),
+                "users": list_notification_preferences(),
+            })
+        elif urlparse(self.path).path == "/notifications/deliveries":
+            self._serve_notification_deliveries(parse_qs(urlparse(self.path).query))
+        elif self.path == "/graphql":
+            if not GRAPHQL_ENABLED:
+                self._send_json(404, {"error": "GraphQL이 비활성화되어 있습니다 (GRAPHQL_ENABLED=true로 설정)."})
+                return
+            body = graphql_schema_sdl().encode("utf-8")
+            self.send_response(200)
+            self.send_header("Content-Type", "text/plain; charset=utf-8")
+            self.send_header("Content-Length", str(len(body)))
+            self.end_headers()
+            self.wfile.write(body)
+        elif urlparse(self.path).path == "/series":
+            self._serve_paginated(parse_qs(urlparse(self.path).query, keep_blank_values=True), "series",
+                                  list_series(), "series",
+                                  lambda series: sort_key(series.get("name", "").casefold(), series.get("id")),
+                                  render=lambda page: [{**series, "record_count": len(series.get("record_ids", []))}
+                                                       for series in page])
+        elif re.match(r"^/series/[^/]+$", self.path):
+            try:
+                self._send_json(200, describe_series(get_series(unquote(self.path.split("/")[2]))))
+            except SeriesError as e:
+                self._send_json(404, {"error": str(e)})
+        elif urlparse(self.path).path == "/people":
+            self._serve_paginated(parse_qs(urlparse(self.path).query, keep_blank_values=True), "people",
+                                  list_people(), "people",
+                                  lambda person: sort_key(person["name"].casefold(), person["id"]))
+        elif re.match(r"^/people/[^/]+$", self.path):
+            try:
+                self._send_json(200, get_person(unquote(self.path.split("/")[2])))
+            except PersonNotFound as e:
+                self._send_json(404, {"error": str(e)})
+        elif re.match(r"^/people/[^/]+/mentions$", urlparse(self.path).path):
+            parsed = urlparse(self.path)
+            self._serve_person_mentions(unquote(parsed.path.split("/")[2]), parse_qs(parsed.query))
+        elif self.path == "/folder_watch":
+            self._send_json(200, folder_watch_status())
+        elif urlparse(self.path).path == "/watch/rules":
+            self._serve_paginated(parse_qs(urlparse(self.path).query, keep_blank_values=True), "rules",
+                                  list_watch_rules(), "watch_rules", lambda rule: sort_key(rule.get("id")),
+                                  extra={"channels": list(WATCH_CHANNELS),
+                                         "default_threshold": WATCH_SEMANTIC_THRESHOLD})
+        elif self.path == "/summaries/stale":
+            current_version = summarize_workflow.get_prompt_version()
+            stale = [
+                {
+                    "id": record["id"],
+                    "filename": record.get("filename"),
+                    "summary_prompt_version": record.get("summary_prompt_version"),
+                }
+                for record in find_stale_summaries(load_upload_history(), current_version)
+            ]
+            self._send_json(200, {"prompt_version": current_version, "count": len(stale), "records": stale})
+        elif self.path == "/summaries/regenerate":
+            job = get_summary_regen_job()
+            self._send_json(200, {"job": job.snapshot() if job else None})
+        elif self.path == "/embeddings/benchmark":
+            job = get_embedding_benchmark_job()
+            self._send_json(200, {"job": job.snapshot() if job else None})
+        elif urlparse(self.path).path == "/action_items":
+            self._serve_action_items(parse_qs(urlparse(self.path).query, keep_blank_values=True))
+        elif re.match(r"^/record/[^/]+/action_items$", self.path):
+            record_id = unquote(self.path.split("/")[2])
+            records = {r["id"]: r for r in get_active_history()}
+            if record_id not in records:
+                self._send_json(404, {"error": "기록을 찾을 수 없습니다."})
+                return
+            items = list_action_items(ACTION_ITEM_STATUSES, record_ids=[record_id])
+            self._send_json(200, {"record_id": record_id, "items": describe_action_items(items, records)})
+        elif re.match(r"^/record/[^/]+/timeline(\?.*)?$", self.path):
+            parsed = urlparse(self.path)
+            record_id = unquote(parsed.path.split("/")[2])
+            self._serve_record_timeline(record_id, parse_qs(parsed.query, keep_blank_values=True))
+        elif self.path == "/admin/maintenance":
+            self._send_json(200, self._maintenance_report(maintenance_state()))
+        elif self.path == "/startup_status":
+            self._send_json(200, startup_status())
+        elif self.path == "/admin/migrations":
+            self._send_json(200, migration_status(DATA_MIGRATIONS))
+        elif self.path == "/index/namespaces":
+            self._send_json(200, {"namespaces": namespace_stats()})
+        elif self.path == "/index/shards":
+            self._send_json(200, shard_stats())
+        elif re.match(r"^/record/[^/]+/lineage$", self.path):
+            record_id = unquote(self.path.split("/")[2])
+            history = load_upload_history()
+            if not any(item.get("id") == record_id for item in history):
+                self._send_json(404, {"error": "기록을 찾을 수 없습니다."})
+                return
+            revisions = [
+                {"type": event["type"], "timestamp": event.get("timestamp"), "details": event.get("details", {})}
+                for event in load_events(record_id, REVISION_EVENT_TYPES)
+            ]
+            self._send_json(200, build_lineage_graph(record_id, history, revisions))
+        elif re.match(r"^/record/[^/]+$", self.path):
+            record_id = unquote(self.path.split("/")[2])
+            self._serve_record_detail(record_id)
         elif self.path.startswith("/file_search"):
-            from urllib.parse import urlparse, parse_qs
             parsed = urlparse(self.path)
             params = parse_qs(parsed.query)
             query = params.get("q", [""])[0].lower()
@@ -1978,13 +4735,26 @@ class UploadHandler(BaseHTTPRequestHandler):
             self.end_headers()
             self.wfile.write(json.dumps(results, ensure_ascii=False).encode())
         elif self.path.startswith("/search"):
-            from urllib.parse import urlparse, parse_qs
-
             parsed = urlparse(self.path)
             params = parse_qs(parsed.query)
             query = params.get("q", [""])[0].strip()
             start_date = params.get("start", [None])[0]
             end_date = params.get("end", [None])[0]
+            target = params.get("target", ["both"])[0]
+            try:
+                namespaces = parse_namespaces(params.get("namespaces", [None])[0])
+            except NamespaceError as e:
+                self._send_json(400, {"error": str(e)})
+                return
+
+            if target not in SEARCH_TARGETS:
+                self.send_response(400)
+                self.send_header("Content-Type", "application/json")
+                self.end_headers()
+                self.wfile.write(json.dumps({
+                    "error": f"target 값은 {', '.join(SEARCH_TARGETS)} 중 하나여야 합니다."
+                }, ensure_ascii=False).encode())
+                return
 
             try:
                 response_data = {
@@ -1992,10 +4762,28 @@ class UploadHandler(BaseHTTPRequestHandler):
                     "similarDocuments": []
                 }
 
-                if query:
+                cache_key = search_response_key(query, {
+                    "start": start_date, "end": end_date, "target": target,
+                    "namespaces": sorted(namespaces) if namespaces else None,
+                })
+                generation = search_generation()
+                cached_response = get_search_response(cache_key, generation) if query else None
+                if cached_response is not None:
+                    response_data = cached_response
+                elif query:
                     documents, path_index = _collect_searchable_documents()
+                    if target != "both":
+                        documents = [
+                            doc for doc in documents
+                            if ("summary" if doc["info"].get("task_type") == "summary" else "transcript") == target
+                        ]
                     history = get_active_history()
                     history_map = {record.get("id"): record for record in history}
+                    if namespaces:
+                        documents = [
+                            doc for doc in documents
+                            if record_namespace(history_map.get(doc["info"].get("record_id"), {})) in namespaces
+                        ]
 
                     keyword_matches = _collect_keyword_matches(query, documents, history_map)
                     response_data["keywordMatches"] = keyword_matches
@@ -2008,7 +4796,9 @@ class UploadHandler(BaseHTTPRequestHandler):
                         BASE_DIR,
                         top_k=10,
                         start_date=start_date,
-                        end_date=end_date
+                        end_date=end_date,
+                        target=target,
+                        namespaces=namespaces
                     )
 
                     similar_documents = []
@@ -2028,6 +4818,7 @@ class UploadHandler(BaseHTTPRequestHandler):
                         uploaded_at = None
                         source_filename = None
                         file_uuid = None
+                        record_id = None
 
                         if doc:
                             record = history_map.get(doc["info"].get("record_id"), {})
@@ -2036,12 +4827,15 @@ class UploadHandler(BaseHTTPRequestHandler):
                             display_name = doc["info"].get("original_filename") or display_name
                             link = f"/download/{doc['uuid']}"
                             file_uuid = doc["uuid"]
+                            record_id = doc["info"].get("record_id")
 
                         similar_documents.append({
                             "file_uuid": file_uuid,
+                            "record_id": record_id,
                             "file": rel_path,
                             "display_name": display_name,
                             "score": hit.get("score"),
+                            "kind": hit.get("kind", "transcript"),
                             "uploaded_at": uploaded_at,
                             "source_filename": source_filename,
                             "link": link,
@@ -2051,6 +4845,9 @@ class UploadHandler(BaseHTTPRequestHandler):
                             break
 
                     response_data["similarDocuments"] = similar_documents
+                    # 벡터 검색 실패(Ollama 미응답 등)는 빈 목록으로 오므로 캐시하지 않음
+                    if hits:
+                        store_search_response(cache_key, generation, response_data)
 
                 self.send_response(200)
                 self.send_header("Content-Type", "application/json")
@@ -2073,18 +4870,90 @@ class UploadHandler(BaseHTTPRequestHandler):
             self._serve_similar_documents(file_identifier)
         elif self.path == "/models":
             self._serve_available_models()
+        elif self.path == "/stt/models":
+            self._send_json(200, {
+                "backend": get_stt_engine().name,
+                "available": available_whisper_models(),
+                "loaded": engine_manager.loaded_models(),
+                "max_loaded": engine_manager.max_models,
+                "states_per_model": engine_manager.pool_size,
+            })
+        elif self.path == "/model/options":
+            self._send_json(200, {"schema": MODEL_OPTION_SCHEMA, "defaults": DEFAULT_MODEL_OPTIONS})
         elif self.path == "/cache/stats":
             self._serve_cache_stats()
-        elif self.path == "/cache/cleanup":
+        elif urlparse(self.path).path == "/cache/cleanup":
             self._serve_cache_cleanup()
         else:
             self.send_response(404)
             self.end_headers()
     
-    def _serve_history(self):
-        """Serve upload history as JSON."""
+    def _serve_paginated(self, params: dict, field: str, items: list, list_name: str, key,
+                         sort: str = "default", descending: bool = False, extra: dict = None, render=None):
+        """Send ``{field: items}``; with ``?cursor=`` only one page plus ``next_cursor``/``has_more``/``limit``."""
+        payload = dict(extra or {})
+        try:
+            page_params = parse_page_params(params)
+            if page_params is None:
+                payload[field] = render(items) if render else items
+            else:
+                cursor, limit = page_params
+                page, next_cursor = cursor_page(items, key, cursor, limit, list_name, sort, descending)
+                payload.update({field: render(page) if render else page, "next_cursor": next_cursor,
+                                "has_more": next_cursor is not None, "limit": limit})
+        except CursorError as e:
+            self._send_json(400, {"error": str(e)})
+            return
+        self._send_json(200, payload)
+
+    def _serve_history(self, params: dict = None):
+        """Serve upload history as JSON, optionally sorted (``?sort=created|updated|completed&order=desc``).
+
+        With ``?limit=`` (and ``offset``) one page is served from the history
+        store as ``{"records", "total", "offset", "limit", "has_more"}``.
+        """
+        params = params or {}
+        sort = params.get("sort", [""])[0]
+        order = params.get("order", [""])[0] or "desc"
+        if sort and sort not in HISTORY_SORT_FIELDS or order not in ("asc", "desc"):
+            self._send_json(400, {"error": f"sort는 {', '.join(HISTORY_SORT_FIELDS)} 중 하나, order는 asc 또는 desc여야 합니다."})
+            return
+        if "cursor" in params:
+            # 정렬을 지정하지 않으면 생성 시각 기준 (같은 시각은 id 순)
+            field = HISTORY_SORT_FIELDS[sort or "created"]
+            descending = order == "desc"
+            history = get_active_history()
+            self._serve_paginated(params, "records", history, "history",
+                                  lambda r: sort_key(r.get(field), r.get("id"), descending=descending),
+                                  sort=f"{field}:{order}", descending=descending, extra={"total": len(history)})
+            return
+        if "limit" in params or "offset" in params:
+            try:
+                limit = int(params.get("limit", [HISTORY_PAGE_MAX])[0])
+                offset = int(params.get("offset", ["0"])[0])
+            except ValueError:
+                self._send_json(400, {"error": "limit과 offset은 정수여야 합니다."})
+                return
+            if not 1 <= limit <= HISTORY_PAGE_MAX or offset < 0:
+                self._send_json(400, {"error": f"limit은 1~{HISTORY_PAGE_MAX}, offset은 0 이상이어야 합니다."})
+                return
+            try:
+                records, total = HISTORY_STORE.page(offset, limit, HISTORY_SORT_FIELDS.get(sort), order == "desc")
+            except HISTORY_STORE_ERRORS as e:
+                self._send_json(500, {"error": f"업로드 기록을 읽지 못했습니다: {e}"})
+                return
+            for record in records:
+                _ensure_record_schema(record)
+            self._send_json(200, {"records": records, "total": total, "offset": offset, "limit": limit,
+                                  "has_more": offset + len(records) < total})
+            return
         try:
             history = get_active_history()
+            if sort:
+                field = HISTORY_SORT_FIELDS[sort]
+                # 값이 없는 기록(아직 처리되지 않음 등)은 정렬 방향과 관계없이 뒤로
+                dated = sorted((r for r in history if r.get(field)), key=lambda r: r[field], reverse=order == "desc")
+                history = dated + [r for r in history if not r.get(field)]
             self.send_response(200)
             self.send_header("Content-Type", "application/json")
             self.end_headers()
@@ -2094,18 +4963,1032 @@ class UploadHandler(BaseHTTPRequestHandler):
             self.end_headers()
             self.wfile.write(f"Error loading history: {str(e)}".encode())
 
-    def _serve_running_tasks(self):
-        """Serve information about currently running tasks."""
+    def _serve_record_transcript(self, record_id: str, params: dict):
+        """Serve a record's transcript in the clean or verbatim view."""
         try:
-            tasks = get_running_tasks()
+            view = parse_transcript_view(params.get("view", [None])[0])
+        except TranscriptViewError as e:
+            self._send_json(400, {"error": str(e)})
+            return
+        record = next((item for item in get_active_history() if item.get("id") == record_id), None)
+        stt_path = record_transcript_path(record) if record else None
+        if stt_path is None:
+            self._send_json(404, {"error": "전사 결과가 있는 기록을 찾을 수 없습니다."})
+            return
+        rendered = transcript_view(stt_path, view)
+        if rendered is None:
+            self._send_json(404, {
+                "error": "이 전사에는 원문(verbatim) 보기가 없습니다. 다시 변환하면 생성됩니다.",
+                "available_views": ["clean"],
+            })
+            return
+        text, segments, document = rendered
+        self._send_json(200, {
+            "record_id": record_id,
+            "view": view,
+            "available_views": transcript_views_of(document),
+            "text": text,
+            "segments": segments,
+            "dropped": dropped_counts(verbatim_segments(document) or []),
+        })
+
+    def _serve_transcript_export(self, record_id: str, params: dict):
+        """Download a record's transcript merged into readable paragraphs (``format=md|txt``)."""
+        export_format = params.get("format", ["md"])[0]
+        if export_format not in PARAGRAPH_EXPORT_FORMATS:
+            self._send_json(400, {"error": f"format은 {', '.join(PARAGRAPH_EXPORT_FORMATS)} 중 하나여야 합니다."})
+            return
+        try:
+            view = parse_transcript_view(params.get("view", [None])[0])
+            pause = float(params.get("pause", [TRANSCRIPT_PARAGRAPH_PAUSE_SECONDS])[0])
+            max_chars = int(params.get("max_chars", [TRANSCRIPT_PARAGRAPH_MAX_CHARS])[0])
+        except TranscriptViewError as e:
+            self._send_json(400, {"error": str(e)})
+            return
+        except ValueError:
+            self._send_json(400, {"error": "pause는 숫자, max_chars는 정수여야 합니다."})
+            return
+        if pause < 0 or max_chars < 50:
+            self._send_json(400, {"error": "pause는 0 이상, max_chars는 50 이상이어야 합니다."})
+            return
+        record = next((item for item in get_active_history() if item.get("id") == record_id), None)
+        stt_path = record_transcript_path(record) if record else None
+        if stt_path is None:
+            self._send_json(404, {"error": "전사 결과가 있는 기록을 찾을 수 없습니다."})
+            return
+        segments = paragraph_source_segments(stt_path, view)
+        if segments is None:
+            self._send_json(404, {"error": "이 전사에는 원문(verbatim) 보기가 없습니다."})
+            return
+        paragraphs = build_paragraphs(segments, pause, max_chars)
+        title = transcript_title_line(read_text_with_fallback(stt_path), stt_path.stem)
+        body = render_paragraphs(title, paragraphs, export_format, record.get("speaker_names")).encode("utf-8")
+
+        filename = export_filename(record, f".paragraphs.{export_format}")
+        self.send_response(200)
+        self.send_header("Content-Type", "text/markdown; charset=utf-8" if export_format == "md"
+                         else "text/plain; charset=utf-8")
+        self.send_header("Content-Disposition", content_disposition(filename))
+        self.send_header("Content-Length", str(len(body)))
+        self.end_headers()
+        self.wfile.write(body)
+        record_event(record_id, "exported", task_type="transcript_paragraphs", filename=filename,
+                     view=view, paragraphs=len(paragraphs))
+
+    def _maintenance_report(self, state: dict) -> dict:
+        """Maintenance state plus the tasks still running or queued (wait for zero before a backup)."""
+        queue = WORKFLOW_QUEUE.snapshot()
+        return {**state, "active_tasks": len(get_running_tasks()),
+                "queue": {"running": len(queue["running"]), "waiting": len(queue["waiting"])}}
+
+    def _serve_record_export(self, record_id: str, params: dict):
+        """Download a record's transcript as subtitles (``srt``/``vtt``), markdown, plain text or segments JSON."""
+        export_format = params.get("format", ["srt"])[0]
+        if export_format not in TRANSCRIPT_EXPORT_TYPES:
+            self._send_json(400, {"error": f"format은 {', '.join(TRANSCRIPT_EXPORT_TYPES)} 중 하나여야 합니다."})
+            return
+        try:
+            view = parse_transcript_view(params.get("view", [None])[0])
+        except TranscriptViewError as e:
+            self._send_json(400, {"error": str(e)})
+            return
+        record = next((item for item in get_active_history() if item.get("id") == record_id), None)
+        stt_path = record_transcript_path(record) if record else None
+        if stt_path is None:
+            self._send_json(404, {"error": "전사 결과가 있는 기록을 찾을 수 없습니다."})
+            return
+        rendered = transcript_view(stt_path, view)
+        segments = export_source_segments(stt_path, view) if rendered else None
+        if segments is None:
+            self._send_json(404, {"error": "이 전사에는 원문(verbatim) 보기가 없습니다."})
+            return
+        if export_format in SUBTITLE_FORMATS and not segments:
+            self._send_json(404, {"error": "타임스탬프가 있는 전사 결과가 없습니다. 먼저 STT를 실행하세요."})
+            return
+
+        speaker_names = record.get("speaker_names")
+        if export_format in SUBTITLE_FORMATS:
+            body = render_subtitles(segments, export_format, speaker_names)
+        elif export_format == "md":
+            body = rendered[0]
+        elif export_format == "txt":
+            body = segments_to_text(segments, speaker_names) if segments else rendered[0]
+        else:
+            document = rendered[2] or {}
+            body = json.dumps({
+                "record_id": record_id,
+                "view": view,
+                "language": document.get("language"),
+                "speaker_names": speaker_names or {},
+                "segments": segments,
+            }, ensure_ascii=False, indent=2)
+        body = body.encode("utf-8")
+
+        filename = export_filename(record, f".{export_format}" if view == "clean" else f".{view}.{export_format}")
+        self.send_response(200)
+        self.send_header("Content-Type", TRANSCRIPT_EXPORT_TYPES[export_format])
+        self.send_header("Content-Disposition", content_disposition(filename))
+        self.send_header("Content-Length", str(len(body)))
+        self.end_headers()
+        self.wfile.write(body)
+        record_event(record_id, "exported", task_type=f"transcript_{export_format}", filename=filename,
+                     view=view, segments=len(segments))
+
+    def _serve_record_detail(self, record_id: str):
+        """Serve a single history record with its segment schema metadata."""
+        try:
+            history = load_upload_history()
+            record = next((item for item in history if item.get("id") == record_id), None)
+            if not record:
+                self.send_response(404)
+                self.send_header("Content-Type", "application/json")
+                self.end_headers()
+                self.wfile.write(json.dumps({"error": "기록을 찾을 수 없습니다."}, ensure_ascii=False).encode())
+                return
+
+            segments = None
+            stt_link = (record.get("download_links") or {}).get("stt")
+            if stt_link:
+                stt_path, _, _, _ = resolve_file_identifier(stt_link)
+                if stt_path:
+                    try:
+                        segments = describe_segments(segments_path_for(stt_path))
+                    except SegmentSchemaError as exc:
+                        segments = {"error": str(exc)}
+
             self.send_response(200)
             self.send_header("Content-Type", "application/json")
             self.end_headers()
-            self.wfile.write(json.dumps(tasks, ensure_ascii=False).encode())
+            self.wfile.write(json.dumps({
+                **record,
+                "segments": segments,
+            }, ensure_ascii=False).encode())
         except Exception as e:
             self.send_response(500)
             self.end_headers()
-            self.wfile.write(f"Error getting running tasks: {str(e)}".encode())
+            self.wfile.write(f"Error loading record: {str(e)}".encode())
+
+    def _send_json(self, status: int, payload, headers: dict = None):
+        payload = enrich_error_payload(payload, status)
+        self.send_response(status)
+        self.send_header("Content-Type", "application/json")
+        for name, value in (headers or {}).items():
+            self.send_header(name, value)
+        self.end_headers()
+        self.wfile.write(json.dumps(payload, ensure_ascii=False).encode())
+
+    def _serve_public(self, path: str):
+        """Read-only gallery of published records (``PUBLIC_GALLERY_ENABLED``)."""
+        if not PUBLIC_GALLERY_ENABLED:
+            self._send_json(404, {"error": "공개 갤러리가 꺼져 있습니다."})
+            return
+        history = get_active_history()
+        if path in ("/public", "/public/"):
+            page = render_gallery_page([public_view(r) for r in published_records(history)]).encode("utf-8")
+            self.send_response(200)
+            self.send_header("Content-Type", "text/html; charset=utf-8")
+            self.send_header("Content-Length", str(len(page)))
+            self.end_headers()
+            self.wfile.write(page)
+            return
+        if path == "/public/records":
+            self._send_json(200, {"records": [public_view(r) for r in published_records(history)]})
+            return
+        match = re.match(r"^/public/records/([^/]+)(/audio)?$", path)
+        record_id = unquote(match.group(1)) if match else None
+        record = next((r for r in history if r.get("id") == record_id and is_published(r)), None)
+        if record is None:
+            self._send_json(404, {"error": "공개된 기록을 찾을 수 없습니다."})
+            return
+        if match.group(2):
+            self._serve_public_audio(record)
+            return
+
+        view = {
+            **public_view(record),
+            "summary": _read_record_text(record, "summary"),
+            "transcript": _read_record_text(record, "stt"),
+            "segments": public_segments(load_record_segments(record), record.get("speaker_names") or {}),
+        }
+        if "text/html" in (self.headers.get("Accept") or ""):
+            page = render_gallery_record_page(view).encode("utf-8")
+            self.send_response(200)
+            self.send_header("Content-Type", "text/html; charset=utf-8")
+            self.send_header("Content-Length", str(len(page)))
+            self.end_headers()
+            self.wfile.write(page)
+            return
+        self._send_json(200, view)
+
+    def _serve_public_audio(self, record: dict):
+        """Stream a published recording with single-range support so players can seek."""
+        file_path = resolve_record_path(normalize_record_path(record.get("file_path", "")))
+        audio_path = stt_input_path(record["id"], file_path)
+        if not PUBLIC_GALLERY_AUDIO or record.get("file_type") != "audio" or not audio_path.exists():
+            self._send_json(404, {"error": "공개된 오디오가 없습니다."})
+            return
+        size = audio_path.stat().st_size
+        try:
+            byte_range = parse_byte_range(self.headers.get("Range"), size)
+        except RangeNotSatisfiable as e:
+            self.send_response(416)
+            self.send_header("Content-Range", str(e))
+            self.end_headers()
+            return
+        start, end = byte_range or (0, size - 1)
+        self.send_response(206 if byte_range else 200)
+        self.send_header("Content-Type", mimetypes.guess_type(audio_path.name)[0] or "application/octet-stream")
+        self.send_header("Accept-Ranges", "bytes")
+        self.send_header("Content-Length", str(end - start + 1))
+        if byte_range:
+            self.send_header("Content-Range", f"bytes {start}-{end}/{size}")
+        self.end_headers()
+        with open(audio_path, "rb") as f:
+            f.seek(start)
+            remaining = end - start + 1
+            while remaining > 0:
+                chunk = f.read(min(UPLOAD_READ_CHUNK_SIZE, remaining))
+                if not chunk:
+                    break
+                self.wfile.write(chunk)
+                remaining -= len(chunk)
+
+    def _handle_raw_upload(self, params: dict):
+        """``POST /upload/raw``: one recording as the raw body, for phone automations that cannot build multipart."""
+        if not token_from_headers(self.headers):
+            self._send_json(401, {"error": "API 토큰이 필요합니다."}, {"WWW-Authenticate": "Bearer"})
+            return
+        content_type = (self.headers.get("Content-Type") or "").split(";")[0].strip().lower()
+        filename = os.path.basename(unquote(self.headers.get("X-Filename") or params.get("filename", [""])[0])).strip()
+        if not filename:
+            filename = f"recording-{datetime.now():%Y%m%d-%H%M%S}"
+        if not Path(filename).suffix and content_type in RAW_UPLOAD_EXTENSIONS:
+            filename += RAW_UPLOAD_EXTENSIONS[content_type]
+        if get_file_type(Path(filename)) != 'audio':
+            self._send_json(415, {"error": "오디오/영상 파일만 받을 수 있습니다. X-Filename 헤더에 확장자를 포함하세요."})
+            return
+        steps_value = self.headers.get("X-Steps", params.get("steps", [RAW_UPLOAD_STEPS])[0])
+        try:
+            steps = validate_steps([step.strip() for step in steps_value.split(",") if step.strip()])
+        except ValueError as e:
+            self._send_json(400, {"error": str(e)})
+            return
+        try:
+            deadline_seconds = parse_deadline_seconds(self.headers.get(DEADLINE_HEADER))
+        except ValueError as e:
+            self._send_json(400, {"error": f"잘못된 제한 시간입니다: {e}"})
+            return
+
+        content_length = int(self.headers.get("Content-Length", 0))
+        if content_length <= 0:
+            self._send_json(400, {"error": "요청 본문이 비어 있습니다."})
+            return
+        data = self._read_upload_body(content_length)
+        if data is None:
+            return
+
+        entry = store_uploaded_bytes(filename, data, load_upload_history())
+        if entry.get("duplicate"):
+            self._send_json(200, {"success": True, "duplicate": True,
+                                  "record_id": entry["original_record_id"], "filename": filename})
+            return
+        task_id = None
+        if steps:
+            task_id = start_workflow_thread(resolve_record_path(entry["file_path"]), steps, entry["record_id"],
+                                            deadline_seconds)
+        self.annotate_request(task_id=task_id, record_id=entry["record_id"])
+        self._send_json(201, {"success": True, "record_id": entry["record_id"], "filename": filename,
+                              "file_path": entry["file_path"], "task_id": task_id, "steps": steps})
+
+    def _handle_webhook_request(self, webhook_id, action):
+        """Create, update, delete or test-send a webhook."""
+        webhook_id = unquote(webhook_id) if webhook_id else None
+        payload = {} if action == "/delete" else self._read_json_payload()
+        if payload is None:
+            return
+        try:
+            if action == "/delete":
+                if not delete_webhook(webhook_id):
+                    self._send_json(404, {"success": False, "error": "웹훅을 찾을 수 없습니다."})
+                    return
+                self._send_json(200, {"success": True})
+                return
+            if action == "/test":
+                # 지정한 기록(없으면 가장 최근 요약 기록)의 산출물로 즉시 한 번 전송
+                webhook = get_webhook(webhook_id)
+                record_id = payload.get("record_id") or next(
+                    (r["id"] for r in reversed(load_upload_history())
+                     if not r.get("deleted") and (r.get("download_links") or {}).get("summary")),
+                    None,
+                )
+                if not record_id:
+                    self._send_json(400, {"success": False, "error": "테스트에 사용할 요약 기록이 없습니다."})
+                    return
+                context = build_webhook_context(record_id, webhook["events"][0])
+                result = deliver_webhook(webhook, context)
+                self._send_json(200, {"success": result["ok"], "record_id": record_id, "result": result})
+                return
+            webhook = update_webhook(webhook_id, payload) if webhook_id else create_webhook(payload)
+        except WebhookError as e:
+            self._send_json(400, {"success": False, "error": str(e)})
+            return
+        self._send_json(200, {"success": True, "webhook": masked_webhook(webhook)})
+
+    def _serve_notification_deliveries(self, params: dict):
+        """Recorded notification deliveries (``?status=&channel=&record_id=&limit=``), newest first."""
+        status = params.get("status", [""])[0]
+        if status and status not in NOTIFICATION_STATUSES:
+            self._send_json(400, {"error": f"status는 {', '.join(NOTIFICATION_STATUSES)} 중 하나여야 합니다."})
+            return
+        try:
+            limit = int(params.get("limit", ["100"])[0])
+        except ValueError:
+            self._send_json(400, {"error": "limit은 정수여야 합니다."})
+            return
+        self._send_json(200, {"deliveries": list_notification_deliveries(
+            status or None,
+            params.get("channel", [""])[0] or None,
+            params.get("record_id", [""])[0] or None,
+            max(1, min(limit, 500)),
+        )})
+
+    def _handle_notification_preferences(self, user_id, action):
+        """Set or remove the notification preferences of a person."""
+        user_id = unquote(user_id)
+        if action == "/delete":
+            if not delete_notification_preferences(user_id):
+                self._send_json(404, {"success": False, "error": "알림 설정을 찾을 수 없습니다."})
+                return
+            self._send_json(200, {"success": True})
+            return
+        payload = self._read_json_payload()
+        if payload is None:
+            return
+        try:
+            preferences = update_notification_preferences(user_id, payload)
+        except NotificationError as e:
+            self._send_json(400, {"success": False, "error": str(e)})
+            return
+        self._send_json(200, {"success": True, "user": preferences})
+
+    def _handle_notification_test(self):
+        """Send a test notification to one channel/target or to every channel of a person."""
+        payload = self._read_json_payload()
+        if payload is None:
+            return
+        event = payload.get("event") or "summary_completed"
+        if event not in WEBHOOK_EVENTS:
+            self._send_json(400, {"success": False, "error": f"event는 {', '.join(WEBHOOK_EVENTS)} 중 하나여야 합니다."})
+            return
+        try:
+            if payload.get("user_id"):
+                user_id = payload["user_id"]
+                targets = [(channel, target, user_id) for channel, target
+                           in notification_user_targets(user_id, get_notification_preferences(user_id))]
+                if not targets:
+                    raise NotificationError("대상이 설정된 채널이 없습니다.")
+            else:
+                channel = payload.get("channel")
+                if channel not in NOTIFIERS:
+                    raise NotificationError(f"channel은 {', '.join(NOTIFIERS)} 중 하나여야 합니다.")
+                targets = [(channel, NOTIFIERS[channel].validate_target(payload.get("target")), None)]
+        except NotificationError as e:
+            self._send_json(400, {"success": False, "error": str(e)})
+            return
+        # 지정한 기록(없으면 가장 최근 요약 기록)의 내용으로 보내고, 기록이 없으면 예시 문구 사용
+        record_id = payload.get("record_id") or next(
+            (r["id"] for r in reversed(load_upload_history())
+             if not r.get("deleted") and (r.get("download_links") or {}).get("summary")),
+            None,
+        )
+        try:
+            context = build_webhook_context(record_id, event) if record_id else {"title": "테스트 알림"}
+        except (WebhookError, OSError):
+            context = {"title": "테스트 알림"}
+        message = build_notification(event, {**context, "event": event})
+        deliveries = [send_notification(channel, target, message, user_id, test=True)
+                      for channel, target, user_id in targets]
+        self._send_json(200, {
+            "success": all(delivery["status"] == "sent" for delivery in deliveries),
+            "record_id": record_id,
+            "deliveries": deliveries,
+        })
+
+    def _serve_action_items(self, params: dict):
+        """Open action items across the archive (``?status=&assignee=&series_id=&record_id=``)."""
+        status = params.get("status", [""])[0] or "open"
+        if status == "open":
+            statuses = OPEN_ACTION_ITEM_STATUSES
+        elif status == "all":
+            statuses = ACTION_ITEM_STATUSES
+        else:
+            statuses = [value for value in status.split(",") if value]
+            if not statuses or any(value not in ACTION_ITEM_STATUSES for value in statuses):
+                self._send_json(400, {"error": f"status는 open, all 또는 {', '.join(ACTION_ITEM_STATUSES)}여야 합니다."})
+                return
+        records = {record["id"]: record for record in get_active_history()}
+        record_ids = set(records)
+        if params.get("series_id", [""])[0]:
+            try:
+                record_ids &= set(get_series(params["series_id"][0])["record_ids"])
+            except SeriesError as e:
+                self._send_json(404, {"error": str(e)})
+                return
+        if params.get("record_id", [""])[0]:
+            record_ids &= {params["record_id"][0]}
+        items = list_action_items(statuses, params.get("assignee", [None])[0] or None, record_ids)
+        self._serve_paginated(params, "items", items, "action_items",
+                              lambda item: sort_key(item["created_at"], item.get("position", 0), item["id"]),
+                              extra={"total": len(items), "statuses": list(ACTION_ITEM_STATUSES)},
+                              render=lambda page: describe_action_items(page, records))
+
+    def _serve_person_mentions(self, person_id: str, params: dict):
+        """Records and segment timestamps where a person spoke or was named (``?series_id=&record_id=``)."""
+        try:
+            person = get_person(person_id)
+        except PersonNotFound as e:
+            self._send_json(404, {"error": str(e)})
+            return
+        record_ids = None
+        if params.get("series_id"):
+            try:
+                record_ids = set(get_series(params["series_id"][0])["record_ids"])
+            except SeriesError as e:
+                self._send_json(404, {"error": str(e)})
+                return
+        if params.get("record_id"):
+            wanted = {params["record_id"][0]}
+            record_ids = wanted if record_ids is None else record_ids & wanted
+        records = person_mentions(person, record_ids)
+        self._send_json(200, {
+            "person": person,
+            "records": records,
+            "totals": {
+                "records": len(records),
+                "spoke_records": sum(1 for entry in records if entry["spoke"]),
+                "spoke_segments": sum(len(entry["spoke"]) for entry in records),
+                "spoke_seconds": round(sum(entry["spoke_seconds"] for entry in records), 2),
+                "mentions": sum(len(entry["mentions"]) for entry in records),
+            },
+        })
+
+    def _handle_people_request(self, person_id, action):
+        """Create, update or delete a person of the people directory."""
+        person_id = unquote(person_id) if person_id else None
+        payload = {} if action == "/delete" else self._read_json_payload()
+        if payload is None:
+            return
+        try:
+            if action == "/delete":
+                if not delete_person(person_id):
+                    raise PersonNotFound(f"인물을 찾을 수 없습니다: {person_id}")
+                self._send_json(200, {"success": True})
+                return
+            person = update_person(person_id, payload) if person_id else create_person(payload)
+        except PersonNotFound as e:
+            self._send_json(404, {"success": False, "error": str(e)})
+            return
+        except PeopleError as e:
+            self._send_json(400, {"success": False, "error": str(e)})
+            return
+        self._send_json(200, {"success": True, "person": person})
+
+    def _handle_series_request(self, series_id, action):
+        """Create, update or delete a meeting series, or link/unlink its records."""
+        series_id = unquote(series_id) if series_id else None
+        if series_id:
+            try:
+                get_series(series_id)
+            except SeriesError as e:
+                self._send_json(404, {"success": False, "error": str(e)})
+                return
+        payload = {} if action == "/delete" else self._read_json_payload()
+        if payload is None:
+            return
+        try:
+            if action == "/delete":
+                delete_series(series_id)
+                self._send_json(200, {"success": True})
+                return
+            if action == "/records":
+                active = {record["id"] for record in get_active_history()}
+                unknown = [r for r in payload.get("add") or [] if isinstance(r, str) and r not in active]
+                if unknown:
+                    self._send_json(400, {"success": False, "error": f"기록을 찾을 수 없습니다: {', '.join(unknown)}"})
+                    return
+                series = link_series_records(series_id, payload.get("add") or [], payload.get("remove") or [])
+                for record_id in payload.get("add") or []:
+                    record_event(record_id, "series_linked", series_id=series_id, series_name=series["name"])
+            elif series_id:
+                series = update_series(series_id, payload)
+            else:
+                series = create_series(payload)
+                for record_id in series["record_ids"]:
+                    record_event(record_id, "series_linked", series_id=series["id"], series_name=series["name"])
+        except SeriesError as e:
+            self._send_json(400, {"success": False, "error": str(e)})
+            return
+        self._send_json(200, {"success": True, "series": describe_series(series)})
+
+    def _handle_watch_rule_request(self, rule_id, action):
+        """Create, update, delete or dry-run a watch rule."""
+        rule_id = unquote(rule_id) if rule_id else None
+        payload = {} if action == "/delete" else self._read_json_payload()
+        if payload is None:
+            return
+        try:
+            if action == "/delete":
+                if not delete_watch_rule(rule_id):
+                    self._send_json(404, {"success": False, "error": "감시 규칙을 찾을 수 없습니다."})
+                    return
+                self._send_json(200, {"success": True})
+                return
+            if action == "/test":
+                # 알림 없이 지정한 기록에 규칙을 적용한 결과만 반환
+                rule = get_watch_rule(rule_id)
+                if not isinstance(payload.get("record_id"), str):
+                    self._send_json(400, {"success": False, "error": "record_id가 필요합니다."})
+                    return
+                matches = watch_rule_matches(payload["record_id"], [rule])
+                self._send_json(200, {"success": True, "matched": bool(matches),
+                                      "match": matches[0] if matches else None})
+                return
+            rule = update_watch_rule(rule_id, payload) if rule_id else create_watch_rule(payload)
+        except WatchRuleError as e:
+            self._send_json(400, {"success": False, "error": str(e)})
+            return
+        self._send_json(200, {"success": True, "rule": rule})
+
+    def _read_json_payload(self):
+        """Read a JSON request body; sends 400 and returns ``None`` when invalid."""
+        length = int(self.headers.get("Content-Length", 0))
+        try:
+            payload = json.loads(self.rfile.read(length)) if length else {}
+        except json.JSONDecodeError:
+            self._send_json(400, {"error": "잘못된 JSON 형식입니다."})
+            return None
+        if not isinstance(payload, dict):
+            self._send_json(400, {"error": "요청 본문은 JSON 객체여야 합니다."})
+            return None
+        return payload
+
+    def _serve_record_speakers(self, record_id: str):
+        """Serve speaker labels, assigned names and profile suggestions for a record."""
+        record = next((item for item in load_upload_history() if item.get("id") == record_id), None)
+        if not record:
+            self._send_json(404, {"error": "기록을 찾을 수 없습니다."})
+            return
+
+        document = load_record_segments(record)
+        names = record.get("speaker_names") or {}
+        suggestions = match_speakers(speaker_embeddings(document))
+        self._send_json(200, {
+            "record_id": record_id,
+            "labels": speaker_labels(document),
+            "names": names,
+            "suggestions": {label: m for label, m in suggestions.items() if label not in names},
+            "has_voice_embeddings": bool(speaker_embeddings(document)),
+            "people": [{"id": p["id"], "name": p["name"], "aliases": p["aliases"]} for p in list_people()],
+        })
+
+    def _serve_record_waveform(self, record_id: str, params: dict):
+        """Serve cached waveform peaks of a record's source audio."""
+        try:
+            points = int(params.get("points", [WAVEFORM_DEFAULT_POINTS])[0])
+        except ValueError:
+            points = 0
+        if not 1 <= points <= WAVEFORM_MAX_POINTS:
+            self._send_json(400, {"error": f"points는 1~{WAVEFORM_MAX_POINTS} 사이의 정수여야 합니다."})
+            return
+
+        record = next((item for item in get_active_history() if item.get("id") == record_id), None)
+        if not record:
+            self._send_json(404, {"error": "기록을 찾을 수 없습니다."})
+            return
+        if record.get("file_type") != "audio":
+            self._send_json(400, {"error": "오디오 기록에서만 파형을 제공합니다."})
+            return
+        file_path = resolve_record_path(normalize_record_path(record.get("file_path", "")))
+        if not file_path.exists():
+            self._send_json(404, {"error": "원본 파일을 찾을 수 없습니다."})
+            return
+
+        try:
+            waveform = get_waveform(file_path, points)
+        except RuntimeError as e:
+            self._send_json(500, {"error": str(e)})
+            return
+        self._send_json(200, {"record_id": record_id, **waveform})
+
+    def _serve_record_thumbnail(self, record_id: str):
+        """Serve the poster image generated for a video upload."""
+        record = next((item for item in get_active_history() if item.get("id") == record_id), None)
+        thumbnail = (record or {}).get("thumbnail")
+        thumbnail_path = resolve_record_path(normalize_record_path(thumbnail)) if thumbnail else None
+        if not thumbnail_path or not thumbnail_path.exists():
+            self.send_response(404)
+            self.end_headers()
+            return
+        data = thumbnail_path.read_bytes()
+        self.send_response(200)
+        self.send_header("Content-Type", "image/jpeg")
+        self.send_header("Content-Length", str(len(data)))
+        self.send_header("Cache-Control", "max-age=86400")
+        self.end_headers()
+        self.wfile.write(data)
+
+    def _handle_speaker_rename(self, record_id: str):
+        payload = self._read_json_payload()
+        if payload is None:
+            return
+        record = next((item for item in load_upload_history() if item.get("id") == record_id), None)
+        if not record or record.get("deleted"):
+            self._send_json(404, {"error": "기록을 찾을 수 없습니다."})
+            return
+
+        document = load_record_segments(record)
+        try:
+            names = canonical_speaker_names(validate_speaker_names(payload.get("names"), speaker_labels(document)))
+            record = update_speaker_names(record_id, names)
+            enrolled = []
+            if payload.get("save_profiles"):
+                enrolled = enroll_speakers(names, speaker_embeddings(document))
+        except SpeakerProfileError as e:
+            self._send_json(400, {"success": False, "error": str(e)})
+            return
+
+        self._send_json(200, {
+            "success": True,
+            "names": record.get("speaker_names", {}) if record else {},
+            "profiles_updated": enrolled,
+        })
+
+    def _handle_title_summary_regenerate(self, record_id: str):
+        """Regenerate a record's one-line summary with optional length/tone/language overrides."""
+        payload = self._read_json_payload()
+        if payload is None:
+            return
+        record = next((r for r in get_active_history() if r.get("id") == record_id), None)
+        if not record:
+            self._send_json(404, {"success": False, "error": "기록을 찾을 수 없습니다."})
+            return
+        try:
+            options = resolve_one_line_options(payload.get("one_line"))
+        except OneLineOptionsError as e:
+            self._send_json(400, {"success": False, "error": "잘못된 one_line 옵션입니다.", "details": e.errors})
+            return
+        stt_link = (record.get("download_links") or {}).get("stt")
+        source_path = resolve_file_identifier(stt_link)[0] if stt_link else None
+        if not source_path or not Path(source_path).exists():
+            self._send_json(400, {"success": False, "error": "전사 결과가 없는 기록입니다."})
+            return
+        model = payload.get("model") if isinstance(payload.get("model"), str) else None
+        if model:
+            try:
+                require_ollama_model(model)
+            except UnknownModelError as e:
+                self._send_json(400, {"success": False, "error": str(e), "available": e.available})
+                return
+        summary = generate_and_store_title_summary(record_id, Path(source_path), model, options)
+        if summary is None:
+            self._send_json(500, {"success": False, "error": "한 줄 요약 생성에 실패했습니다."})
+            return
+        self._send_json(200, {"success": True, "title_summary": summary, "options": options})
+
+    def _handle_summary_bakeoff(self, record_id: str):
+        """Start summarizing a record's transcript with 2-3 models for comparison."""
+        payload = self._read_json_payload()
+        if payload is None:
+            return
+        record = next((r for r in get_active_history() if r.get("id") == record_id), None)
+        if not record:
+            self._send_json(404, {"success": False, "error": "기록을 찾을 수 없습니다."})
+            return
+        try:
+            models = [require_ollama_model(model) for model in resolve_bakeoff_models(payload.get("models"))]
+            model_options = validate_model_options(payload.get("model_options"))
+        except (BakeoffError, UnknownModelError) as e:
+            self._send_json(400, {"success": False, "error": str(e)})
+            return
+        except ModelOptionsError as e:
+            self._send_json(400, {"success": False, "error": "잘못된 model_options입니다.", "details": e.errors})
+            return
+        if record_transcript_path(record) is None:
+            self._send_json(400, {"success": False, "error": "전사 결과가 없는 기록입니다."})
+            return
+        self._send_json(202, {"success": True, "bakeoff": start_summary_bakeoff(record, models, model_options)})
+
+    def _start_summary_stream(self, record_id: str):
+        """Start a record's summary step whose progress and tokens can be streamed as SSE (summary_stream.py)."""
+        payload = self._read_json_payload()
+        if payload is None:
+            return
+        record = next((r for r in get_active_history() if r.get("id") == record_id), None)
+        file_path = resolve_record_path(normalize_record_path(record.get("file_path", ""))) if record else None
+        if record is None or record_transcript_path(record) is None or not file_path.exists():
+            self._send_json(404, {"error": "전사 결과가 있는 기록을 찾을 수 없습니다."})
+            return
+        model = payload.get("model")
+        if model is not None and not isinstance(model, str):
+            self._send_json(400, {"error": "model은 문자열이어야 합니다."})
+            return
+        try:
+            model_settings = {"summarize": require_ollama_model(model)} if model else None
+        except UnknownModelError as e:
+            self._send_json(400, {"error": str(e), "available": e.available})
+            return
+
+        task_id = str(uuid.uuid4())
+        self.annotate_request(record_id=record_id, task_id=task_id)
+        stream = open_summary_stream(task_id, record_id)
+
+        def run():
+            try:
+                result = run_workflow(file_path, ["summary"], record_id, task_id, model_settings=model_settings)
+            except Exception as e:  # pragma: no cover - run_workflow reports its own errors
+                result = error_payload(e)
+            if result.get("error"):
+                stream.send("error", result)
+            else:
+                stream.send("done", {"results": result})
+
+        threading.Thread(target=run, daemon=True).start()
+        self._send_json(202, {
+            "success": True,
+            "task_id": task_id,
+            "record_id": record_id,
+            "stream": f"/record/{quote(record_id)}/summary/stream?task_id={task_id}",
+        })
+
+    def _stream_record_summary(self, record_id: str, params: dict):
+        """Attach to a summary run started with POST and stream its events as SSE (never starts a run)."""
+        task_id = params.get("task_id", [None])[0]
+        stream = attach_summary_stream(task_id, record_id)
+        if stream is None:
+            self._send_json(404, {"error": "진행 중인 요약 스트림을 찾을 수 없습니다. "
+                                           "POST /record/{id}/summary/stream으로 먼저 시작하세요."})
+            return
+        self.send_response(200)
+        self.send_header("Content-Type", "text/event-stream; charset=utf-8")
+        self.send_header("Cache-Control", "no-cache")
+        self.send_header("X-Accel-Buffering", "no")
+        self.end_headers()
+        try:
+            self.wfile.write(format_sse("started", {"task_id": task_id, "record_id": record_id}))
+            self.wfile.flush()
+            for event in stream.events():
+                self.wfile.write(SSE_HEARTBEAT if event is None else format_sse(*event))
+                self.wfile.flush()
+        except (BrokenPipeError, ConnectionResetError):
+            print(f"요약 스트림 연결이 끊겼습니다 (요약은 계속 진행): {task_id}")
+        finally:
+            close_summary_stream(task_id)
+
+    def _serve_record_summary(self, record_id: str):
+        """Serve a record's summary as structured JSON (parsed from the markdown when it was not stored)."""
+        record = next((r for r in get_active_history() if r.get("id") == record_id), None)
+        summary_link = ((record or {}).get("download_links") or {}).get("summary")
+        summary_path = resolve_file_identifier(summary_link)[0] if summary_link else None
+        if not summary_path or not Path(summary_path).exists():
+            self._send_json(404, {"error": "기록 또는 요약을 찾을 수 없습니다."})
+            return
+        summary_path = Path(summary_path)
+        structured = load_structured_summary(summary_path)
+        if structured is None:
+            text = read_text_with_fallback(summary_path)
+            structured = structured_summary_from_sections(
+                parse_summary_to_sections(text), detect_summary_language(text)
+            ).to_dict()
+        self._send_json(200, {
+            "record_id": record_id,
+            "format": "structured" if structured.get("source") == "model" else "markdown",
+            "summary": structured,
+        })
+
+    def _serve_summary_bakeoff(self, record_id: str, bakeoff_id: str = None):
+        """List a record's bake-offs, or compare the outputs of one."""
+        record = next((r for r in get_active_history() if r.get("id") == record_id), None)
+        transcript_path = record_transcript_path(record) if record else None
+        if transcript_path is None:
+            self._send_json(404, {"error": "기록 또는 전사 결과를 찾을 수 없습니다."})
+            return
+        if bakeoff_id is None:
+            self._send_json(200, {"record_id": record_id, "bakeoffs": list_bakeoffs(transcript_path.parent)})
+            return
+        manifest = load_bakeoff(transcript_path.parent, bakeoff_id)
+        if manifest is None:
+            self._send_json(404, {"error": "요약 비교 결과를 찾을 수 없습니다."})
+            return
+        self._send_json(200, compare_bakeoff(transcript_path.parent, manifest, parse_summary_to_sections))
+
+    def _handle_embedding_benchmark(self):
+        """Start a recall@k comparison of embedding models on labeled query→record pairs."""
+        payload = self._read_json_payload()
+        if payload is None:
+            return
+        target = payload.get("target", "transcript")
+        try:
+            pairs = parse_benchmark_pairs(payload.get("pairs"))
+            models = resolve_benchmark_models(payload.get("models"), current_embedding_model())
+            ks = resolve_benchmark_ks(payload.get("k"))
+            if target not in BENCHMARK_TARGETS:
+                raise BenchmarkError(f"target은 {', '.join(BENCHMARK_TARGETS)} 중 하나여야 합니다.")
+        except BenchmarkError as e:
+            self._send_json(400, {"success": False, "error": str(e)})
+            return
+        expected_ids = [record_id for pair in pairs for record_id in pair["expected"]]
+        documents = benchmark_corpus(target, expected_ids)
+        missing = sorted(set(expected_ids) - set(documents))
+        if missing:
+            self._send_json(400, {"success": False, "error": "전사가 없거나 존재하지 않는 기록이 있습니다.",
+                                  "missing": missing})
+            return
+
+        def runner(progress, cancel_event):
+            return run_embedding_benchmark(pairs, documents, models, ks, embed_document_ollama,
+                                           embed_text_ollama, progress, cancel_event)
+
+        try:
+            job = start_embedding_benchmark_job(runner, models, len(pairs), target)
+        except BenchmarkBusy as e:
+            self._send_json(409, {"success": False, "error": str(e)})
+            return
+        self._send_json(202, {"success": True, "job": job.snapshot()})
+
+    def _handle_lineage_update(self, record_id: str):
+        """Link a record to the records it was merged/split/digested from (or unlink)."""
+        payload = self._read_json_payload()
+        if payload is None:
+            return
+        history = load_upload_history()
+        record = next((item for item in history if item.get("id") == record_id and not item.get("deleted")), None)
+        if not record:
+            self._send_json(404, {"success": False, "error": "기록을 찾을 수 없습니다."})
+            return
+        remove = payload.get("remove") or []
+        if not isinstance(remove, list) or not all(isinstance(p, str) for p in remove):
+            self._send_json(400, {"success": False, "error": "remove는 기록 ID 문자열 배열이어야 합니다."})
+            return
+        try:
+            removed = remove_parent_links(record, remove) if remove else []
+            added = []
+            if payload.get("parents"):
+                added = add_parent_links(record, payload["parents"], payload.get("relation", "derived_from"), history)
+        except LineageError as e:
+            self._send_json(400, {"success": False, "error": str(e)})
+            return
+        save_upload_history(history)
+        if added or removed:
+            record_event(record_id, "lineage_updated", relation=payload.get("relation", "derived_from") if added else None,
+                         added=added or None, removed=removed or None)
+        self._send_json(200, {"success": True, "added": added, "removed": removed,
+                              "lineage": record.get("lineage", [])})
+
+    def _handle_namespace_update(self, record_id: str):
+        """Move a record and its index entries to another vector namespace."""
+        payload = self._read_json_payload()
+        if payload is None:
+            return
+        try:
+            namespace = normalize_namespace(payload.get("namespace"))
+        except NamespaceError as e:
+            self._send_json(400, {"success": False, "error": str(e)})
+            return
+        history = load_upload_history()
+        record = next((item for item in history if item.get("id") == record_id and not item.get("deleted")), None)
+        if not record:
+            self._send_json(404, {"success": False, "error": "기록을 찾을 수 없습니다."})
+            return
+        previous = record_namespace(record)
+        record["namespace"] = namespace
+        save_upload_history(history)
+
+        paths = [
+            resolve_file_identifier(link)[0]
+            for link in (record.get("download_links") or {}).values()
+            if isinstance(link, str)
+        ]
+        moved = set_namespace_for_paths([path for path in paths if path], namespace)
+        if previous != namespace:
+            record_event(record_id, "namespace_changed", previous=previous, namespace=namespace)
+        self._send_json(200, {"success": True, "namespace": namespace, "previous": previous, "index_entries": moved})
+
+    def _handle_speaker_profile(self, profile_id: str = None, delete: bool = False):
+        payload = {} if delete else self._read_json_payload()
+        if payload is None:
+            return
+        try:
+            if delete:
+                if not delete_profile(profile_id):
+                    self._send_json(404, {"success": False, "error": "화자 프로필을 찾을 수 없습니다."})
+                    return
+                self._send_json(200, {"success": True})
+                return
+
+            embedding = payload.get("embedding")
+            if embedding is None and payload.get("record_id") and payload.get("label"):
+                # 기록의 화자 라벨에서 음성 임베딩을 가져와 등록
+                record = next((item for item in load_upload_history()
+                               if item.get("id") == payload["record_id"]), None)
+                embedding = speaker_embeddings(load_record_segments(record) if record else None).get(payload["label"])
+                if embedding is None:
+                    raise SpeakerProfileError("해당 기록/화자 라벨의 음성 임베딩을 찾을 수 없습니다.")
+
+            if profile_id:
+                profile = update_profile(profile_id, payload.get("name"), embedding,
+                                         replace_embedding=bool(payload.get("replace_embedding")))
+            else:
+                profile = create_profile(payload.get("name"), embedding)
+        except SpeakerProfileError as e:
+            self._send_json(400, {"success": False, "error": str(e)})
+            return
+        self._send_json(200, {"success": True, "profile": public_profile(profile)})
+
+    def _serve_summary_debug(self, record_id: str, task_id: str = None):
+        """Serve the retained summary intermediates (chunk/reduce prompts and outputs) of the latest or ``task_id``'s run."""
+        try:
+            history = load_upload_history()
+            record = next((item for item in history if item.get("id") == record_id), None)
+            if not record:
+                self.send_response(404)
+                self.send_header("Content-Type", "application/json")
+                self.end_headers()
+                self.wfile.write(json.dumps({"error": "기록을 찾을 수 없습니다."}, ensure_ascii=False).encode())
+                return
+
+            manifest = None
+            runs = []
+            links = record.get("download_links") or {}
+            for task in ("summary", "stt"):
+                if not links.get(task):
+                    continue
+                file_path, _, _, _ = resolve_file_identifier(links[task])
+                if file_path:
+                    manifest = load_summary_debug(file_path.parent, task_id)
+                    runs = list_summary_debug_runs(file_path.parent)
+                    break
+
+            if manifest is None:
+                self.send_response(404)
+                self.send_header("Content-Type", "application/json")
+                self.end_headers()
+                message = "저장된 요약 디버그 정보가 없습니다."
+                if not summary_debug.SUMMARY_DEBUG_ENABLED:
+                    message += " SUMMARY_DEBUG_ENABLED=true로 설정한 뒤 요약을 다시 생성하세요."
+                self.wfile.write(json.dumps({"error": message}, ensure_ascii=False).encode())
+                return
+
+            self.send_response(200)
+            self.send_header("Content-Type", "application/json")
+            self.end_headers()
+            self.wfile.write(json.dumps({"record_id": record_id, **manifest, "runs": runs}, ensure_ascii=False).encode())
+        except Exception as e:
+            self.send_response(500)
+            self.end_headers()
+            self.wfile.write(f"Error loading summary debug: {str(e)}".encode())
+
+    def _serve_record_timeline(self, record_id: str, params: dict = None):
+        """Serve the chronological event list for a single record (cursor-paginated with ``?cursor=``)."""
+        try:
+            history = load_upload_history()
+            record = next((item for item in history if item.get("id") == record_id), None)
+            if not record:
+                self.send_response(404)
+                self.send_header("Content-Type", "application/json")
+                self.end_headers()
+                self.wfile.write(json.dumps({"error": "기록을 찾을 수 없습니다."}, ensure_ascii=False).encode())
+                return
+
+            events = build_record_timeline(record, load_file_registry())
+            self._serve_paginated(params or {}, "events", events, "timeline",
+                                  lambda event: sort_key(event.get("timestamp"), event.get("id") or event.get("type")),
+                                  extra={"record_id": record_id, "filename": record.get("filename")})
+        except Exception as e:
+            self.send_response(500)
+            self.end_headers()
+            self.wfile.write(f"Error building timeline: {str(e)}".encode())
+
+    def _serve_running_tasks(self, params: dict = None):
+        """Serve information about currently running tasks.
+
+        The full answer maps task ids to task info; with ``?cursor=`` one page
+        of ``{"task_id", ...}`` entries (oldest first) is served as ``{"tasks": [...]}``.
+        """
+        if params and "cursor" in params:
+            tasks = [{"task_id": task_id, **info} for task_id, info in get_running_tasks().items()]
+            self._serve_paginated(params, "tasks", tasks, "tasks",
+                                  lambda task: sort_key(task["start_time"], task["task_id"]))
+            return
+        try:
+            tasks = get_running_tasks()
+            self.send_response(200)
+            self.send_header("Content-Type", "application/json")
+            self.end_headers()
+            self.wfile.write(json.dumps(tasks, ensure_ascii=False).encode())
+        except Exception as e:
+            self.send_response(500)
+            self.end_headers()
+            self.wfile.write(f"Error getting running tasks: {str(e)}".encode())
 
     def _serve_task_progress(self, task_id: str):
         """Serve progress information for a specific task."""
@@ -2120,6 +6003,44 @@ class UploadHandler(BaseHTTPRequestHandler):
             self.end_headers()
             self.wfile.write(f"Error getting task progress: {str(e)}".encode())
 
+    def _serve_task_log(self, task_id: str):
+        """Serve a task's progress log and, when captured, its summary prompts/responses."""
+        log = get_task_log(task_id)
+        if log is None:
+            self._send_json(404, {"error": "작업을 찾을 수 없습니다."})
+            return
+        debug_dir = log.pop("summary_debug_dir")
+        try:
+            log["summary_debug"] = load_summary_debug(Path(debug_dir), task_id) if debug_dir else None
+        except (OSError, ValueError) as e:
+            self._send_json(500, {"error": f"요약 디버그 정보를 읽지 못했습니다: {e}"})
+            return
+        log["summary_debug_enabled"] = summary_debug.SUMMARY_DEBUG_ENABLED
+        self._send_json(200, log)
+
+    def _serve_task_wait(self, task_id: str, params: dict):
+        """Long-poll until the task's progress changes after ``since`` or the timeout passes."""
+        try:
+            since = int(params.get("since", ["0"])[0])
+            timeout = float(params.get("timeout", ["25"])[0])
+        except ValueError:
+            self._send_json(400, {"error": "since는 정수, timeout은 초 단위 숫자여야 합니다."})
+            return
+        timeout = max(0.0, min(timeout, TASK_WAIT_MAX_SECONDS))
+
+        event = progress_bus.wait(task_id, since, timeout)
+        if event is None:
+            latest = progress_bus.latest(task_id)
+            self._send_json(200, {
+                "task_id": task_id,
+                "changed": False,
+                "seq": latest["seq"] if latest else since,
+                "message": latest["message"] if latest else None,
+                "done": latest["done"] if latest else False,
+            })
+            return
+        self._send_json(200, {"changed": True, **event})
+
     def _serve_similar_documents(self, file_identifier: str):
         """Find similar documents based on the provided file's content."""
         try:
@@ -2340,6 +6261,12 @@ class UploadHandler(BaseHTTPRequestHandler):
 
     def _serve_available_models(self):
         """Serve available Ollama models as JSON."""
+        if mock_backends.is_enabled():
+            self._send_json(200, {"models": [mock_backends.MOCK_MODEL], "default": {
+                "whisper": "large-v3-turbo", "summarize": mock_backends.MOCK_MODEL,
+                "embedding": mock_backends.MOCK_MODEL,
+            }})
+            return
         try:
             # Ollama 서버 상태 확인 및 필요시 시작
             server_ok, server_msg = ensure_ollama_server()
@@ -2373,7 +6300,8 @@ class UploadHandler(BaseHTTPRequestHandler):
                     "whisper": "large-v3-turbo",
                     "summarize": DEFAULT_MODEL,
                     "embedding": get_default_model("EMBEDDING")
-                }
+                },
+                "summarize_by_language": parse_language_models(summarize_workflow.SUMMARY_MODEL_BY_LANGUAGE),
             }
             
             self.send_response(200)
@@ -2402,6 +6330,39 @@ class UploadHandler(BaseHTTPRequestHandler):
 
         threading.Thread(target=shutdown_server, daemon=True).start()
 
+    def _read_upload_body(self, content_length):
+        """Read an upload body, aborting when the client stalls or disconnects.
+
+        Returns ``None`` after sending an error response.
+        """
+        chunks = []
+        received = 0
+        previous_timeout = self.connection.gettimeout()
+        self.connection.settimeout(UPLOAD_IDLE_TIMEOUT_SECONDS)
+        try:
+            while received < content_length:
+                chunk = self.rfile.read(min(UPLOAD_READ_CHUNK_SIZE, content_length - received))
+                if not chunk:
+                    break
+                chunks.append(chunk)
+                received += len(chunk)
+        except (socket.timeout, ConnectionError) as e:
+            print(f"Upload aborted after {received}/{content_length} bytes: {e}")
+            self.close_connection = True
+            try:
+                self._send_json(408, {"error": "업로드가 중단되었습니다. 다시 시도하세요."})
+            except OSError:
+                pass
+            return None
+        finally:
+            self.connection.settimeout(previous_timeout)
+
+        if received < content_length:
+            print(f"Upload aborted: client disconnected after {received}/{content_length} bytes")
+            self.close_connection = True
+            return None
+        return b"".join(chunks)
+
     def _parse_multipart(self, data, boundary):
         """Simple multipart/form-data parser"""
         parts = data.split(f'--{boundary}'.encode())
@@ -2431,7 +6392,33 @@ class UploadHandler(BaseHTTPRequestHandler):
 
         return files
 
+    def _handle_auth_login(self):
+        """Check a key and store it in an HttpOnly cookie for the browser UI."""
+        payload = self._read_json_payload()
+        if payload is None:
+            return
+        secret = payload.get("token")
+        token = authenticate_token(secret) if isinstance(secret, str) and secret.strip() else None
+        if not token:
+            self._send_json(401, {"error": "유효하지 않은 API 키입니다."}, {"WWW-Authenticate": "Bearer"})
+            return
+        secure = (self.headers.get("X-Forwarded-Proto") or "").lower() == "https"
+        self._send_json(200, {"success": True, "scope": token["scope"], "name": token["name"]},
+                        {"Set-Cookie": auth_cookie(secret.strip(), secure)})
+
     def do_POST(self):
+        if not self._authorize():
+            return
+        if self.path == "/auth/login":
+            self._handle_auth_login()
+            return
+        if self.path == "/auth/logout":
+            self._send_json(200, {"success": True}, {"Set-Cookie": auth_cookie("")})
+            return
+        if blocked_by_maintenance(self.command, self.path):
+            state = maintenance_state()
+            self._send_json(503, {"error": state["message"], "code": "maintenance", "maintenance": state})
+            return
         if self.path == "/upload":
             try:
                 print(f"Upload request received - Content-Length: {self.headers.get('Content-Length')}")
@@ -2456,7 +6443,9 @@ class UploadHandler(BaseHTTPRequestHandler):
                 
                 boundary = boundary_match.group(1).strip()
                 content_length = int(self.headers.get('Content-Length', 0))
-                data = self.rfile.read(content_length)
+                data = self._read_upload_body(content_length)
+                if data is None:
+                    return
                 
                 files = self._parse_multipart(data, boundary)
                 print(f"Parsed fields: {list(files.keys())}")
@@ -2475,42 +6464,7 @@ class UploadHandler(BaseHTTPRequestHandler):
                     if not file_info.get('filename'):
                         continue
 
-                    file_hash = compute_file_hash(file_info['data'])
-                    existing = next((r for r in history if r.get('file_hash') == file_hash), None)
-                    if existing:
-                        uploaded_files.append({
-                            "duplicate": True,
-                            "original_record_id": existing["id"],
-                            "filename": file_info['filename']
-                        })
-                        continue
-
-                    uid = uuid.uuid4().hex
-                    save_dir = UPLOAD_DIR / uid
-                    save_dir.mkdir(parents=True, exist_ok=True)
-                    file_path = save_dir / os.path.basename(file_info['filename'])
-
-                    with open(file_path, "wb") as output_file:
-                        output_file.write(file_info['data'])
-
-                    print(f"File saved successfully: {file_path}")
-
-                    file_type = get_file_type(file_path)
-
-                    # Get audio duration if it's an audio file
-                    duration = None
-                    if file_type == 'audio':
-                        duration = get_audio_duration(file_path)
-
-                    # Add to upload history
-                    record = add_upload_record(file_path, file_type, duration, file_hash)
-                    history.insert(0, record)
-
-                    uploaded_files.append({
-                        "file_path": to_record_path(file_path),
-                        "file_type": file_type,
-                        "record_id": record["id"]
-                    })
+                    uploaded_files.append(store_uploaded_bytes(file_info['filename'], file_info['data'], history))
 
                 self.send_response(200)
                 self.send_header("Content-Type", "application/json")
@@ -2526,6 +6480,80 @@ class UploadHandler(BaseHTTPRequestHandler):
                 self.end_headers()
                 self.wfile.write(f"Upload error: {str(e)}".encode())
 
+        if urlparse(self.path).path == "/upload/raw":
+            self._handle_raw_upload(parse_qs(urlparse(self.path).query, keep_blank_values=True))
+            return
+
+        if self.path == "/folder_watch/scan":
+            if not folder_watch_enabled():
+                self._send_json(404, {"error": "폴더 감시가 꺼져 있습니다 (WATCH_DIRS 미설정)."})
+                return
+            results = scan_watch_folders(ingest_watched_file)
+            self._send_json(200, {"success": True, "results": results, "status": folder_watch_status()})
+            return
+
+        if self.path == "/email/inbound":
+            content_length = int(self.headers.get("Content-Length", 0))
+            body = self._read_upload_body(content_length)
+            if body is None:
+                return
+            try:
+                inbound = parse_inbound_email(self.headers.get("Content-Type", ""), body)
+            except EmailSignatureError as e:
+                self._send_json(401, {"error": str(e)})
+                return
+            except EmailIngestError as e:
+                self._send_json(400, {"error": str(e)})
+                return
+            result = ingest_inbound_email(inbound)
+            if not result["accepted"]:
+                # 406이면 Mailgun이 재전송하지 않음
+                self._send_json(406, {"error": f"허용되지 않은 발신자입니다: {inbound.sender}"})
+                return
+            self._send_json(200, {"success": True, **result})
+            return
+
+        if self.path == "/upload_url":
+            payload = self._read_json_payload()
+            if payload is None:
+                return
+            steps = payload.get("steps") or []
+            use_ytdlp = payload.get("ytdlp")
+            try:
+                url = validate_url(payload.get("url"))
+                if not isinstance(steps, list) or not all(isinstance(step, str) for step in steps):
+                    raise UrlIngestError("steps는 문자열 배열이어야 합니다.")
+                if use_ytdlp is not None and not isinstance(use_ytdlp, bool):
+                    raise UrlIngestError("ytdlp는 true/false여야 합니다.")
+                model_options = validate_model_options(payload.get("model_options"))
+                one_line_options = resolve_one_line_options(payload.get("one_line"))
+                if "summary" in steps:
+                    validate_summary_model_setting(payload.get("model_settings"))
+            except (UrlIngestError, UnknownModelError, StructuredSummaryError) as e:
+                self._send_json(400, {"error": str(e)})
+                return
+            except (ModelOptionsError, OneLineOptionsError) as e:
+                self._send_json(400, {"error": "잘못된 처리 옵션입니다.", "details": e.errors})
+                return
+
+            task_id = payload.get("task_id") or str(uuid.uuid4())
+            self.annotate_request(task_id=task_id)
+            job = create_url_job(task_id, url, steps)
+            threading.Thread(
+                target=run_url_upload,
+                args=(task_id, url, steps, use_ytdlp),
+                kwargs={
+                    "model_settings": payload.get("model_settings", {}),
+                    "minutes_template": payload.get("minutes_template"),
+                    "model_options": model_options,
+                    "client_id": payload.get("client_id"),
+                    "one_line_options": one_line_options,
+                },
+                daemon=True,
+            ).start()
+            self._send_json(202, {"success": True, "task_id": task_id, "job": job})
+            return
+
         if self.path == "/process":
             length = int(self.headers.get("Content-Length", 0))
             try:
@@ -2535,11 +6563,44 @@ class UploadHandler(BaseHTTPRequestHandler):
                 self.end_headers()
                 self.wfile.write(b"Invalid JSON payload")
                 return
-            file_path = payload.get("file_path")
-            steps = payload.get("steps", [])
+            file_path = payload.get("file_path")
+            try:
+                steps = validate_steps(payload.get("steps", []))
+            except ValueError as e:
+                self._send_json(400, {"error": str(e)})
+                return
             record_id = payload.get("record_id")
             task_id = payload.get("task_id")  # Get task_id from frontend
             model_settings = payload.get("model_settings", {})  # Get model settings from frontend
+            try:
+                model_options = validate_model_options(payload.get("model_options"))
+            except ModelOptionsError as e:
+                self._send_json(400, {"error": "잘못된 model_options입니다.", "details": e.errors})
+                return
+            try:
+                one_line_options = resolve_one_line_options(payload.get("one_line"))
+            except OneLineOptionsError as e:
+                self._send_json(400, {"error": "잘못된 one_line 옵션입니다.", "details": e.errors})
+                return
+            series_context = payload.get("series_context")
+            if series_context is not None and not isinstance(series_context, bool):
+                self._send_json(400, {"error": "series_context는 true/false여야 합니다."})
+                return
+            if "summary" in steps:
+                try:
+                    validate_summary_model_setting(model_settings)
+                except UnknownModelError as e:
+                    self._send_json(400, {"error": str(e), "available": e.available})
+                    return
+                except StructuredSummaryError as e:
+                    self._send_json(400, {"error": str(e)})
+                    return
+            try:
+                deadline_seconds = parse_deadline_seconds(
+                    self.headers.get(DEADLINE_HEADER) or payload.get("deadline_seconds"))
+            except (TypeError, ValueError) as e:
+                self._send_json(400, error_payload(f"잘못된 제한 시간입니다: {e}", code="invalid_request"))
+                return
             
             if not file_path:
                 self.send_response(400)
@@ -2550,16 +6611,82 @@ class UploadHandler(BaseHTTPRequestHandler):
             # Generate task_id if not provided
             if not task_id:
                 task_id = str(uuid.uuid4())
+            self.annotate_request(task_id=task_id, record_id=record_id)
 
             print(f"Processing task {task_id} with steps {steps} and model settings {model_settings}")
             normalized_path = normalize_record_path(file_path)
             absolute_path = resolve_record_path(normalized_path)
 
-            results = run_workflow(absolute_path, steps, record_id, task_id, model_settings)
-            self.send_response(200)
+            results = run_workflow(absolute_path, steps, record_id, task_id, model_settings,
+                                   minutes_template=payload.get("minutes_template"),
+                                   model_options=model_options,
+                                   client_id=payload.get("client_id"),
+                                   one_line_options=one_line_options,
+                                   deadline_seconds=deadline_seconds,
+                                   series_context=series_context)
+            self._send_json(200, results)
+            return
+
+        if self.path == "/ask":
+            payload = self._read_json_payload()
+            if payload is None:
+                return
+            question = payload.get("question")
+            if not isinstance(question, str) or not question.strip():
+                self._send_json(400, {"error": "question에 질문을 입력하세요."})
+                return
+            try:
+                top_k = parse_top_k(payload.get("top_k"))
+                namespaces = parse_namespaces(payload.get("namespaces"))
+                model = payload.get("model")
+                if model is not None and not isinstance(model, str):
+                    raise AskError("model은 모델 이름이어야 합니다.")
+                if model:
+                    model = require_ollama_model(model)
+            except (AskError, NamespaceError) as e:
+                self._send_json(400, {"error": str(e)})
+                return
+            except UnknownModelError as e:
+                self._send_json(400, {"error": str(e), "available": e.available})
+                return
+            try:
+                result = ask_archive(question.strip(), top_k, namespaces, model)
+            except Exception as e:
+                print(f"질문 답변 중 오류: {e}")
+                self._send_json(500, {"error": f"답변을 만들지 못했습니다: {e}"})
+                return
+            self._send_json(200, result)
+            return
+
+        if self.path == "/search/advanced":
+            payload = self._read_json_payload()
+            if payload is None:
+                return
+
+            filter_tree = payload.get("filter")
+            try:
+                limit = max(1, min(int(payload.get("limit", 20)), 200))
+            except (TypeError, ValueError):
+                limit = 20
+
+            try:
+                response_data = search_records_advanced(filter_tree, limit)
+                status_code = 200
+            except FilterError as e:
+                response_data = {"error": f"잘못된 필터입니다: {e}"}
+                status_code = 400
+            except Exception as e:
+                print(f"고급 검색 처리 중 오류: {e}")
+                response_data = {
+                    "error": "검색 중 오류가 발생했습니다. Ollama 서버가 실행 중인지 확인하고, 임베딩 모델이 설치되어 있는지 확인해주세요.",
+                    "details": str(e),
+                }
+                status_code = 500
+
+            self.send_response(status_code)
             self.send_header("Content-Type", "application/json")
             self.end_headers()
-            self.wfile.write(json.dumps(results).encode())
+            self.wfile.write(json.dumps(response_data, ensure_ascii=False).encode())
             return
 
         if self.path == "/cancel":
@@ -2572,6 +6699,7 @@ class UploadHandler(BaseHTTPRequestHandler):
                 self.wfile.write(b"Invalid JSON payload")
                 return
             task_id = payload.get("task_id")
+            self.annotate_request(task_id=task_id)
             
             if not task_id:
                 self.send_response(400)
@@ -2670,6 +6798,584 @@ class UploadHandler(BaseHTTPRequestHandler):
                 }).encode())
             return
 
+        if re.match(r"^/record/[^/]+/speakers$", self.path):
+            self._handle_speaker_rename(unquote(self.path.split("/")[2]))
+            return
+
+        if re.match(r"^/record/[^/]+/publish$", self.path):
+            record_id = unquote(self.path.split("/")[2])
+            payload = self._read_json_payload()
+            if payload is None:
+                return
+            published = payload.get("published", True)
+            public_title = payload.get("title")
+            if not isinstance(published, bool) or (public_title is not None and not isinstance(public_title, str)):
+                self._send_json(400, {"error": "published는 true/false, title은 문자열이어야 합니다."})
+                return
+            record = set_record_published(record_id, published, public_title)
+            if record is None:
+                self._send_json(404, {"error": "기록을 찾을 수 없습니다."})
+                return
+            self._send_json(200, {
+                "success": True,
+                "public": public_view(record) if published else None,
+                "gallery_enabled": PUBLIC_GALLERY_ENABLED,
+            })
+            return
+
+        if self.path == "/speakers/profiles":
+            self._handle_speaker_profile()
+            return
+
+        profile_match = re.match(r"^/speakers/profiles/([^/]+)(/delete)?$", self.path)
+        if profile_match:
+            self._handle_speaker_profile(unquote(profile_match.group(1)), delete=bool(profile_match.group(2)))
+            return
+
+        if self.path == "/minutes/render":
+            payload = self._read_json_payload()
+            if payload is None:
+                return
+            if not payload.get("record_id"):
+                self._send_json(400, {"success": False, "error": "record_id가 필요합니다."})
+                return
+            try:
+                _, download_url = render_minutes(payload["record_id"], payload.get("template_id"))
+            except TemplateError as e:
+                self._send_json(400, {"success": False, "error": str(e)})
+                return
+            self._send_json(200, {"success": True, "minutes": download_url})
+            return
+
+        if re.match(r"^/record/[^/]+/title_summary$", self.path):
+            self._handle_title_summary_regenerate(unquote(self.path.split("/")[2]))
+            return
+
+        if re.match(r"^/record/[^/]+/summary/bakeoff$", self.path):
+            self._handle_summary_bakeoff(unquote(self.path.split("/")[2]))
+            return
+
+        if re.match(r"^/record/[^/]+/summary/stream$", self.path):
+            self._start_summary_stream(unquote(self.path.split("/")[2]))
+            return
+
+        lineage_match = re.match(r"^/record/([^/]+)/lineage$", self.path)
+        if lineage_match:
+            self._handle_lineage_update(unquote(lineage_match.group(1)))
+            return
+
+        namespace_match = re.match(r"^/record/([^/]+)/namespace$", self.path)
+        if namespace_match:
+            self._handle_namespace_update(unquote(namespace_match.group(1)))
+            return
+
+        retranscribe_match = re.match(r"^/record/([^/]+)/retranscribe$", self.path)
+        if retranscribe_match:
+            record_id = unquote(retranscribe_match.group(1))
+            payload = self._read_json_payload()
+            if payload is None:
+                return
+            task_id = payload.get("task_id")
+            self.annotate_request(record_id=record_id, task_id=task_id)
+            if not any(r.get("id") == record_id and not r.get("deleted") for r in load_upload_history()):
+                self._send_json(404, {"success": False, "error": "기록을 찾을 수 없습니다."})
+                return
+            for key in ("model", "prompt", "language", "decoding"):
+                if payload.get(key) is not None and not isinstance(payload[key], str):
+                    self._send_json(400, {"success": False, "error": f"{key}는 문자열이어야 합니다."})
+                    return
+            try:
+                report = retranscribe_record_range(
+                    record_id, payload.get("start"), payload.get("end"),
+                    model=payload.get("model"), prompt=payload.get("prompt"),
+                    language=payload.get("language"), task_id=task_id,
+                    decoding=payload.get("decoding"),
+                )
+            except RetranscribeError as e:
+                self._send_json(400, {"success": False, "error": str(e)})
+                return
+            except TranscriptionCancelled:
+                self._send_json(409, {"success": False, "error": "작업이 취소되었습니다."})
+                return
+            except Exception as e:
+                print(f"구간 재변환 실패 ({record_id}): {e}")
+                self._send_json(500, {"success": False, "error": f"구간 재변환 실패: {e}"})
+                return
+            self._send_json(200, {"success": True, **report})
+            return
+
+        punctuate_match = re.match(r"^/record/([^/]+)/punctuate$", self.path)
+        if punctuate_match:
+            record_id = unquote(punctuate_match.group(1))
+            payload = self._read_json_payload()
+            if payload is None:
+                return
+            task_id = payload.get("task_id")
+            self.annotate_request(record_id=record_id, task_id=task_id)
+            if payload.get("model") is not None and not isinstance(payload["model"], str):
+                self._send_json(400, {"success": False, "error": "model은 문자열이어야 합니다."})
+                return
+            record = next((r for r in get_active_history() if r.get("id") == record_id), None)
+            stt_path = record_transcript_path(record) if record else None
+            if stt_path is None:
+                self._send_json(404, {"success": False, "error": "전사 결과가 있는 기록을 찾을 수 없습니다."})
+                return
+            if task_id:
+                register_task(task_id)
+            try:
+                report = restore_transcript_punctuation(stt_path, payload.get("model"), task_id)
+            except PunctuationError as e:
+                self._send_json(400, {"success": False, "error": str(e)})
+                return
+            finally:
+                if task_id:
+                    clear_task_progress(task_id)
+                    unregister_task(task_id)
+            record_event(record_id, "punctuation_restored", **report)
+            report["stale_artifacts"] = mark_artifacts_stale(record_id, ["embedding", "summary"])
+            self._send_json(200, {"success": True, **report})
+            return
+
+        action_item_match = re.match(r"^/action_items/([^/]+)$", self.path)
+        if action_item_match:
+            payload = self._read_json_payload()
+            if payload is None:
+                return
+            try:
+                item = update_action_item(unquote(action_item_match.group(1)), payload)
+            except ActionItemNotFound as e:
+                self._send_json(404, {"success": False, "error": str(e)})
+                return
+            except ActionItemError as e:
+                self._send_json(400, {"success": False, "error": str(e)})
+                return
+            record_event(item["record_id"], "action_item_updated", item_id=item["id"], text=item["text"],
+                         **{field: payload[field] for field in ("status", "assignee", "due") if field in payload})
+            self._send_json(200, {"success": True, "item": describe_action_items([item])[0]})
+            return
+
+        extract_match = re.match(r"^/record/([^/]+)/action_items/extract$", self.path)
+        if extract_match:
+            record_id = unquote(extract_match.group(1))
+            record = next((r for r in get_active_history() if r.get("id") == record_id), None)
+            summary_link = ((record or {}).get("download_links") or {}).get("summary")
+            summary_path = resolve_file_identifier(summary_link)[0] if summary_link else None
+            if summary_path is None or not summary_path.exists():
+                self._send_json(404, {"success": False, "error": "요약이 있는 기록을 찾을 수 없습니다."})
+                return
+            counts = sync_action_items(record_id, read_text_with_fallback(summary_path))
+            items = list_action_items(ACTION_ITEM_STATUSES, record_ids=[record_id])
+            self._send_json(200, {"success": True, **counts, "items": describe_action_items(items)})
+            return
+
+        subtitle_match = re.match(r"^/record/([^/]+)/subtitled_video$", self.path)
+        if subtitle_match:
+            record_id = unquote(subtitle_match.group(1))
+            self.annotate_request(record_id=record_id)
+            try:
+                download_url = export_subtitled_video(record_id)
+            except VideoProcessingError as e:
+                self._send_json(400, {"success": False, "error": str(e)})
+                return
+            self._send_json(200, {"success": True, "download": download_url})
+            return
+
+        template_match = re.match(r"^/minutes/templates(?:/([^/]+)(/delete)?)?$", self.path)
+        if template_match:
+            template_id, delete = template_match.group(1), bool(template_match.group(2))
+            payload = {} if delete else self._read_json_payload()
+            if payload is None:
+                return
+            try:
+                if delete:
+                    if not delete_template(unquote(template_id)):
+                        self._send_json(404, {"success": False, "error": "템플릿을 찾을 수 없습니다."})
+                        return
+                    self._send_json(200, {"success": True})
+                    return
+                if template_id:
+                    template = update_template(unquote(template_id), payload.get("name"), payload.get("body"))
+                else:
+                    template = create_template(payload.get("name"), payload.get("body"))
+            except TemplateError as e:
+                self._send_json(400, {"success": False, "error": str(e)})
+                return
+            self._send_json(200, {"success": True, "template": template})
+            return
+
+        if self.path == "/graphql":
+            if not GRAPHQL_ENABLED:
+                self._send_json(404, {"error": "GraphQL이 비활성화되어 있습니다 (GRAPHQL_ENABLED=true로 설정)."})
+                return
+            payload = self._read_json_payload()
+            if payload is None:
+                return
+            try:
+                result = execute_graphql(graphql_schema(), payload)
+            except GraphQLUnavailable as e:
+                self._send_json(501, {"error": str(e)})
+                return
+            # GraphQL over HTTP: 실행 오류도 200과 errors로 응답, 요청 자체가 잘못되면 400
+            self._send_json(200 if "data" in result else 400, result)
+            return
+
+        series_match = re.match(r"^/series(?:/([^/]+)(/delete|/records)?)?$", self.path)
+        if series_match:
+            self._handle_series_request(series_match.group(1), series_match.group(2))
+            return
+
+        people_match = re.match(r"^/people(?:/([^/]+)(/delete)?)?$", self.path)
+        if people_match:
+            self._handle_people_request(people_match.group(1), people_match.group(2))
+            return
+
+        watch_rule_match = re.match(r"^/watch/rules(?:/([^/]+)(/delete|/test)?)?$", self.path)
+        if watch_rule_match:
+            self._handle_watch_rule_request(watch_rule_match.group(1), watch_rule_match.group(2))
+            return
+
+        webhook_match = re.match(r"^/webhooks(?:/([^/]+)(/delete|/test)?)?$", self.path)
+        if webhook_match:
+            self._handle_webhook_request(webhook_match.group(1), webhook_match.group(2))
+            return
+
+        notification_match = re.match(r"^/notifications/users/([^/]+)(/delete)?$", self.path)
+        if notification_match:
+            self._handle_notification_preferences(notification_match.group(1), notification_match.group(2))
+            return
+
+        if self.path == "/notifications/test":
+            self._handle_notification_test()
+            return
+
+        if self.path == "/summaries/regenerate":
+            payload = self._read_json_payload()
+            if payload is None:
+                return
+            record_ids = payload.get("record_ids")
+            batch_size = payload.get("batch_size")
+            if record_ids is not None and (
+                not isinstance(record_ids, list) or not all(isinstance(r, str) for r in record_ids)
+            ):
+                self._send_json(400, {"success": False, "error": "record_ids는 문자열 배열이어야 합니다."})
+                return
+            if batch_size is not None and (not isinstance(batch_size, int) or batch_size < 1):
+                self._send_json(400, {"success": False, "error": "batch_size는 1 이상의 정수여야 합니다."})
+                return
+            stale_ids = stale_summary_record_ids()
+            if record_ids is not None:
+                # 지정된 기록은 버전과 무관하게 다시 요약 (요약이 있는 활성 기록만)
+                summarized = {
+                    r["id"] for r in get_active_history()
+                    if (r.get("completed_tasks") or {}).get("summary")
+                }
+                missing = [r for r in record_ids if r not in summarized]
+                if missing:
+                    self._send_json(400, {
+                        "success": False,
+                        "error": f"요약이 없는 기록입니다: {', '.join(missing)}",
+                    })
+                    return
+                stale_ids = record_ids
+            if not stale_ids:
+                self._send_json(200, {"success": True, "job": None, "message": "재생성할 요약이 없습니다."})
+                return
+            try:
+                job = start_summary_regeneration(
+                    stale_ids, regenerate_record_summary, summarize_workflow.get_prompt_version(), batch_size
+                )
+            except RegenerationBusy as e:
+                current = get_summary_regen_job()
+                self._send_json(409, {"success": False, "error": str(e),
+                                      "job": current.snapshot() if current else None})
+                return
+            self._send_json(202, {"success": True, "job": job.snapshot()})
+            return
+
+        if re.match(r"^/tasks/orphaned/[^/]+/dismiss$", self.path):
+            task_id = unquote(self.path.split("/")[3])
+            if not task_journal.dismiss(task_id):
+                self._send_json(404, {"success": False, "error": "해당 작업을 찾을 수 없습니다."})
+                return
+            self._send_json(200, {"success": True, "task_id": task_id})
+            return
+
+        if self.path == "/embeddings/benchmark":
+            self._handle_embedding_benchmark()
+            return
+
+        if self.path == "/embeddings/benchmark/cancel":
+            job = get_embedding_benchmark_job()
+            if job is None or not job.running:
+                self._send_json(404, {"success": False, "error": "진행 중인 임베딩 모델 벤치마크가 없습니다."})
+                return
+            job.cancel()
+            self._send_json(200, {"success": True, "job": job.snapshot()})
+            return
+
+        if self.path == "/summaries/regenerate/cancel":
+            job = get_summary_regen_job()
+            if job is None or not job.running:
+                self._send_json(404, {"success": False, "error": "진행 중인 요약 재생성 작업이 없습니다."})
+                return
+            job.cancel()
+            self._send_json(200, {"success": True, "job": job.snapshot()})
+            return
+
+        if re.match(r"^/postprocess/rules/[^/]+$", self.path):
+            payload = self._read_json_payload()
+            if payload is None:
+                return
+            add, remove = payload.get("add"), payload.get("remove")
+            if not isinstance(add or {}, dict) or not isinstance(remove or {}, dict):
+                self._send_json(400, {"error": "add/remove는 {규칙 종류: [문구]} 객체여야 합니다."})
+                return
+            try:
+                pack = update_custom_rules(unquote(self.path.split("/")[3]), add, remove)
+            except RulePackError as e:
+                self._send_json(400, {"error": str(e)})
+                return
+            self._send_json(200, {"success": True, **pack})
+            return
+
+        if self.path == "/stt/vocabulary":
+            payload = self._read_json_payload()
+            if payload is None:
+                return
+            add, remove, replace = payload.get("add") or [], payload.get("remove") or [], payload.get("terms")
+            if not all(isinstance(value, list) for value in (add, remove, replace or [])):
+                self._send_json(400, {"error": "add/remove/terms는 용어 목록이어야 합니다."})
+                return
+            try:
+                terms = update_custom_vocabulary(add, remove, replace)
+            except ValueError as e:
+                self._send_json(400, {"error": str(e)})
+                return
+            except OSError as e:
+                self._send_json(500, {"error": f"사용자 용어 파일을 저장하지 못했습니다: {e}"})
+                return
+            self._send_json(200, {"success": True, "path": str(vocabulary_path()), "count": len(terms), "terms": terms})
+            return
+
+        if urlparse(self.path).path == "/config/bundle/import":
+            params = parse_qs(urlparse(self.path).query)
+            bundle = self._read_json_payload()
+            if bundle is None:
+                return
+            mode = params.get("mode", ["merge"])[0]
+            dry_run = params.get("dry_run", ["false"])[0].lower() in ("1", "true", "yes")
+            try:
+                report = import_bundle(bundle, mode, dry_run)
+            except BundleError as e:
+                self._send_json(400, {"success": False, "error": "설정 번들을 가져올 수 없습니다.", "details": e.errors})
+                return
+            except (OSError, TemplateError) as e:
+                self._send_json(500, {"success": False, "error": f"설정 번들 적용 중 오류: {e}"})
+                return
+            if not dry_run and "prompts" in report["sections"]:
+                # 가져온 프롬프트를 바로 적용
+                try:
+                    report["reload"] = reload_runtime_config()
+                    report["prompt_version"] = summarize_workflow.get_prompt_version()
+                except ReloadError as e:
+                    report["reload_error"] = str(e)
+            self._send_json(200, {"success": True, **report})
+            return
+
+        token_match = re.match(r"^/admin/tokens(?:/([^/]+)/delete)?$", self.path)
+        if token_match:
+            if token_match.group(1):
+                if not revoke_token(unquote(token_match.group(1))):
+                    self._send_json(404, {"success": False, "error": "토큰을 찾을 수 없습니다."})
+                    return
+                self._send_json(200, {"success": True})
+                return
+            payload = self._read_json_payload()
+            if payload is None:
+                return
+            try:
+                token = create_token(payload.get("name"), payload.get("scope"))
+            except TokenError as e:
+                self._send_json(400, {"success": False, "error": str(e)})
+                return
+            self._send_json(201, {"success": True, **token})
+            return
+
+        if self.path == "/export/bundle":
+            payload = self._read_json_payload()
+            if payload is None:
+                return
+            record_ids = payload.get("record_ids")
+            include = payload.get("include", ["stt", "summary"])
+            if not isinstance(record_ids, list) or not record_ids:
+                self._send_json(400, {"error": "record_ids 필드는 비어 있지 않은 배열이어야 합니다."})
+                return
+            if not isinstance(include, list) or not include or any(task not in EXPORT_BUNDLE_SUFFIXES for task in include):
+                self._send_json(400, {"error": f"include는 {', '.join(EXPORT_BUNDLE_SUFFIXES)} 중에서 골라야 합니다."})
+                return
+            try:
+                body, entries, missing = build_export_bundle([str(r) for r in record_ids],
+                                                             list(dict.fromkeys(include)), payload.get("collision"))
+            except FilenameError as e:
+                self._send_json(400, {"error": str(e)})
+                return
+            if not entries:
+                self._send_json(404, {"error": "내보낼 파일이 없습니다.", "missing": missing})
+                return
+            filename = f"recordroute_export_{datetime.now().strftime('%Y%m%d_%H%M%S')}.zip"
+            self.send_response(200)
+            self.send_header("Content-Type", "application/zip")
+            self.send_header("Content-Disposition", content_disposition(filename))
+            self.send_header("Content-Length", str(len(body)))
+            self.end_headers()
+            self.wfile.write(body)
+            for record_id in dict.fromkeys(entry["record_id"] for entry in entries):
+                record_event(record_id, "exported", task_type="bundle", filename=filename,
+                             files=[entry["name"] for entry in entries if entry["record_id"] == record_id])
+            return
+
+        if self.path == "/admin/orphans/cleanup":
+            payload = self._read_json_payload()
+            if payload is None:
+                return
+            paths = payload.get("paths")
+            min_age_hours = payload.get("min_age_hours", ORPHAN_MIN_AGE_HOURS)
+            if paths is not None and (not isinstance(paths, list) or not all(isinstance(p, str) for p in paths)):
+                self._send_json(400, {"success": False, "error": "paths는 문자열 배열이어야 합니다."})
+                return
+            if isinstance(min_age_hours, bool) or not isinstance(min_age_hours, (int, float)) or min_age_hours < 0:
+                self._send_json(400, {"success": False, "error": "min_age_hours는 0 이상의 숫자여야 합니다."})
+                return
+            # 지금도 고아인 폴더만 삭제 (paths는 그중 일부를 고르는 용도)
+            orphans = find_orphan_artifacts(min_age_hours)
+            if paths is not None:
+                orphans = [orphan for orphan in orphans if orphan.path in set(paths)]
+            if payload.get("dry_run"):
+                self._send_json(200, {"success": True, "dry_run": True, **orphan_report(orphans)})
+                return
+            removed, errors = remove_orphans(LAYOUT, orphans)
+            freed = sum(orphan.size_bytes for orphan in orphans if orphan.path in removed)
+            print(f"고아 산출물 폴더 {len(removed)}개 삭제 ({freed / 1024 ** 2:.1f} MB)")
+            self._send_json(200, {"success": not errors, "removed": removed, "errors": errors, "freed_bytes": freed})
+            return
+
+        if self.path == "/admin/export-sync/run":
+            payload = self._read_json_payload()
+            if payload is None:
+                return
+            try:
+                report = run_export_sync(get_active_history(), export_sync_texts,
+                                         dry_run=bool(payload.get("dry_run")), full=bool(payload.get("full")))
+            except ExportSyncBusy as e:
+                self._send_json(409, {"success": False, "error": str(e)})
+                return
+            except ExportSyncError as e:
+                self._send_json(400, {"success": False, "error": str(e)})
+                return
+            self._send_json(200, {"success": report["failed"] == 0, **report})
+            return
+
+        if self.path == "/admin/artifacts/gc":
+            payload = self._read_json_payload()
+            if payload is None:
+                return
+            result = collect_artifact_garbage(dry_run=bool(payload.get("dry_run")))
+            if result["removed"] and not result["dry_run"]:
+                print(f"참조 없는 공유 산출물 {len(result['removed'])}개 정리 "
+                      f"({result['freed_bytes'] / 1024 ** 2:.1f} MB)")
+            self._send_json(200, {"success": True, **result})
+            return
+
+        if self.path == "/admin/maintenance":
+            payload = self._read_json_payload()
+            if payload is None:
+                return
+            try:
+                state = set_maintenance(payload.get("enabled"), payload.get("message"))
+            except MaintenanceError as e:
+                self._send_json(400, {"error": str(e)})
+                return
+            print(f"점검 모드 {'시작' if state['enabled'] else '해제'}")
+            self._send_json(200, self._maintenance_report(state))
+            return
+
+        if self.path == "/admin/migrations/run":
+            payload = self._read_json_payload()
+            if payload is None:
+                return
+            dry_run = payload.get("dry_run", False)
+            if not isinstance(dry_run, bool):
+                self._send_json(400, {"error": "dry_run은 true 또는 false여야 합니다."})
+                return
+            # 실행 중 쓰기와 겹치지 않도록 실제 마이그레이션은 점검 모드에서만
+            if not dry_run and not maintenance_active():
+                self._send_json(409, {"error": "데이터 마이그레이션은 점검 모드에서만 실행할 수 있습니다.",
+                                      "code": "conflict", "retryable": False,
+                                      "hint": "dry_run으로 미리 확인하거나 POST /admin/maintenance로 점검 모드를 켜세요."})
+                return
+            result = run_migrations(DATA_MIGRATIONS, dry_run=dry_run)
+            if result["ran"] and not dry_run:
+                print(f"데이터 마이그레이션 실행: {', '.join(entry['id'] for entry in result['ran'])}")
+            self._send_json(500 if result["failed"] else 200, {"success": not result["failed"], **result})
+            return
+
+        if self.path == "/admin/reload":
+            try:
+                report = reload_runtime_config()
+                print(f"런타임 설정 재로드 완료: {', '.join(report['changed']) or '변경 없음'}")
+                # 프롬프트가 바뀌었다면 재생성 대상 요약 수를 함께 알려줌
+                report["prompt_version"] = summarize_workflow.get_prompt_version()
+                report["stale_summaries"] = len(stale_summary_record_ids())
+                self.send_response(200)
+                self.send_header("Content-Type", "application/json")
+                self.end_headers()
+                self.wfile.write(json.dumps({"success": True, **report}, ensure_ascii=False).encode())
+            except ReloadError as e:
+                self.send_response(400)
+                self.send_header("Content-Type", "application/json")
+                self.end_headers()
+                self.wfile.write(json.dumps({"success": False, "error": str(e)}, ensure_ascii=False).encode())
+            except Exception as e:
+                self.send_response(500)
+                self.send_header("Content-Type", "application/json")
+                self.end_headers()
+                self.wfile.write(json.dumps({"success": False, "error": str(e)}, ensure_ascii=False).encode())
+            return
+
+        if self.path == "/index/compact":
+            length = int(self.headers.get("Content-Length", 0))
+            try:
+                payload = json.loads(self.rfile.read(length)) if length else {}
+            except json.JSONDecodeError:
+                self.send_response(400)
+                self.end_headers()
+                self.wfile.write(b"Invalid JSON payload")
+                return
+
+            retention_days = payload.get("retention_days")
+            if retention_days is not None and not isinstance(retention_days, int):
+                self.send_response(400)
+                self.send_header("Content-Type", "application/json")
+                self.end_headers()
+                self.wfile.write(json.dumps({
+                    "success": False,
+                    "error": "retention_days 값은 정수여야 합니다.",
+                }, ensure_ascii=False).encode())
+                return
+
+            try:
+                report = compact_index(retention_days, dry_run=bool(payload.get("dry_run", False)))
+                self.send_response(200)
+                self.send_header("Content-Type", "application/json")
+                self.end_headers()
+                self.wfile.write(json.dumps({"success": True, **report}, ensure_ascii=False).encode())
+            except Exception as e:
+                self.send_response(500)
+                self.send_header("Content-Type", "application/json")
+                self.end_headers()
+                self.wfile.write(json.dumps({"success": False, "error": str(e)}, ensure_ascii=False).encode())
+            return
+
         if self.path == "/check_existing_stt":
             length = int(self.headers.get("Content-Length", 0))
             try:
@@ -2729,13 +7435,27 @@ class UploadHandler(BaseHTTPRequestHandler):
             success, message, record_id = update_stt_text(file_identifier, content)
 
             if success:
+                # 요약/임베딩은 고친 전사로 다시 만들어야 함 (resummarize면 바로 요약 재생성)
+                stale = mark_artifacts_stale(record_id, ["embedding", "summary"]) if record_id else []
+                job = None
+                if payload.get("resummarize") and "summary" in stale:
+                    try:
+                        job = start_summary_regeneration(
+                            [record_id], regenerate_record_summary, summarize_workflow.get_prompt_version(),
+                            trigger="transcript_edit",
+                        ).snapshot()
+                    except RegenerationBusy as e:
+                        message = str(e)
                 self.send_response(200)
                 self.send_header("Content-Type", "application/json")
                 self.end_headers()
                 self.wfile.write(json.dumps({
                     "success": True,
-                    "record_id": record_id
-                }).encode())
+                    "record_id": record_id,
+                    "stale_artifacts": stale,
+                    "summary_job": job,
+                    "warning": message or None,
+                }, ensure_ascii=False).encode())
             else:
                 self.send_response(400)
                 self.send_header("Content-Type", "application/json")
@@ -2898,6 +7618,8 @@ class UploadHandler(BaseHTTPRequestHandler):
         """Serve cache statistics as JSON."""
         try:
             stats = get_cache_stats()
+            stats.update(get_cache_manager_stats())
+            stats["vector_cache"] = VECTOR_CACHE.stats()
             self.send_response(200)
             self.send_header("Content-Type", "application/json")
             self.end_headers()
@@ -2908,18 +7630,32 @@ class UploadHandler(BaseHTTPRequestHandler):
             self.wfile.write(f"Error getting cache stats: {str(e)}".encode())
 
     def _serve_cache_cleanup(self):
-        """Clean up expired cache entries and return cleanup stats."""
+        """Clean up expired cache entries and return cleanup stats.
+
+        ``?cache=query,embedding,waveform`` limits the caches, ``?clear=true``
+        removes every entry instead of only expired/over-budget ones.
+        """
+        params = parse_qs(urlparse(self.path).query)
+        names = [name for value in params.get("cache", []) for name in value.split(",") if name]
+        clear = params.get("clear", ["false"])[0].lower() in ("1", "true", "yes")
         try:
-            cleaned_count = cleanup_expired_cache()
+            # 손상된 검색 캐시 파일은 기존 정리 로직이 처리
+            cleaned_count = cleanup_expired_cache() if not names or "query" in names else 0
+            report = cleanup_caches(names, clear=clear)
+            cleaned_count += report["removed_entries"]
             response = {
                 "success": True,
                 "cleaned_entries": cleaned_count,
-                "message": f"정리된 만료된 캐시 항목: {cleaned_count}개"
+                "freed_bytes": report["freed_bytes"],
+                "caches": report["caches"],
+                "message": f"정리된 캐시 항목: {cleaned_count}개"
             }
             self.send_response(200)
             self.send_header("Content-Type", "application/json")
             self.end_headers()
             self.wfile.write(json.dumps(response, ensure_ascii=False).encode())
+        except ValueError as e:
+            self._send_json(400, {"success": False, "error": str(e)})
         except Exception as e:
             self.send_response(500)
             self.end_headers()
@@ -2929,26 +7665,78 @@ class UploadHandler(BaseHTTPRequestHandler):
             }
             self.wfile.write(json.dumps(error_response, ensure_ascii=False).encode())
 
-
 if __name__ == "__main__":
-    UPLOAD_DIR.mkdir(parents=True, exist_ok=True)
-    OUTPUT_DIR.mkdir(parents=True, exist_ok=True)
-    DELETED_UPLOAD_DIR.mkdir(parents=True, exist_ok=True)
-    DELETED_OUTPUT_DIR.mkdir(parents=True, exist_ok=True)
-    DELETED_VECTOR_DIR.mkdir(parents=True, exist_ok=True)
-
-    # Migrate existing files to UUID system
-    migrate_existing_files()
-
-    # Start WebSocket server for progress updates
-    ws_thread = threading.Thread(target=start_websocket_server, daemon=True)
-    ws_thread.start()
-
-    # Use ThreadingHTTPServer to allow concurrent request handling.
-    # This lets the server respond to cancellation requests while
-    # long-running tasks are processing in separate threads.
-    server = ThreadingHTTPServer(("127.0.0.1", 8080), UploadHandler)
+    # 데스크톱 셸이 부팅 진행을 읽는 JSON 줄 출력 (startup_status 참고)
+    if "--startup-status" in sys.argv[1:]:
+        enable_startup_stream()
+    report_startup("starting")
+    try:
+        # 모델/GPU/Ollama 없이 프론트엔드를 개발할 때: --mock 또는 MOCK_BACKENDS=true
+        if "--mock" in sys.argv[1:] or mock_backends.MOCK_BACKENDS:
+            mock_backends.install()
+
+        for layout_dir in LAYOUT.directories():
+            layout_dir.mkdir(parents=True, exist_ok=True)
+        DELETED_VECTOR_DIR.mkdir(parents=True, exist_ok=True)
+        if api_tokens.AUTH_ENABLED and not api_tokens.AUTH_TOKEN and not list_tokens():
+            print("경고: AUTH_ENABLED=true인데 AUTH_TOKEN도 발급된 API 토큰도 없어 모든 요청이 거부됩니다.")
+
+        # Bring on-disk data formats up to date (data_versions.json)
+        if "--migrate-dry-run" in sys.argv[1:]:
+            print(json.dumps(run_migrations(DATA_MIGRATIONS, dry_run=True), ensure_ascii=False, indent=2))
+            sys.exit(0)
+        report_startup("migrating")
+        run_startup_migrations()
+        # Migrate existing files to UUID system (links added since the file_registry migration, too)
+        migrate_existing_files()
+
+        report_startup("recovering")
+        # Finish video thumbnail/audio extraction interrupted by a restart
+        resume_video_preparation()
+
+        # Flag tasks and uploads left behind when the previous process was killed
+        flag_interrupted_tasks()
+        remove_partial_uploads()
+        reconcile_orphans_on_startup()
+        collect_unreferenced_artifacts()
+        start_client_heartbeat_watchdog()
+
+        # Start WebSocket server for progress updates
+        ws_thread = threading.Thread(target=start_websocket_server, daemon=True)
+        ws_thread.start()
+
+        # Keep search vectors in memory instead of reading every file per query
+        start_vector_cache_preload()
+
+        # Periodically purge vector index tombstones and orphaned vectors
+        start_index_compaction_scheduler()
+
+        # Re-generate summaries made with outdated prompts
+        start_summary_regen_scheduler()
+
+        # gRPC pipeline service for backend integrations
+        start_grpc_server()
+
+        # Mirror transcripts/summaries to EXPORT_SYNC_TARGET
+        start_export_sync_scheduler()
+
+        # Records from audio attachments mailed to the EMAIL_IN_IMAP_* mailbox
+        start_imap_poller(ingest_inbound_email, paused=maintenance_active)
+
+        # Records from recordings synced into the WATCH_DIRS folders
+        start_folder_watcher(ingest_watched_file, paused=maintenance_active)
+
+        # Use ThreadingHTTPServer to allow concurrent request handling.
+        # This lets the server respond to cancellation requests while
+        # long-running tasks are processing in separate threads.
+        server = ThreadingHTTPServer(("127.0.0.1", 8080), UploadHandler)
+    except Exception as e:
+        report_startup("fatal", error=str(e))
+        raise
+    report_startup("binding", host="127.0.0.1", port=server.server_address[1])
     print("Serving on http://localhost:8080")
+    # 모델을 미리 불러오는 동안에도 /startup_status에 응답
+    threading.Thread(target=finish_startup, daemon=True).start()
     try:
         server.serve_forever()
     except KeyboardInterrupt:
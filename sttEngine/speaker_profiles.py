@@ -0,0 +1,253 @@
+"""Speaker names per record and persistent voice profiles.
+
+Diarized transcripts carry a ``speaker`` label per segment ("SPEAKER_00",
+"Speaker 1", ...) and optionally one voice embedding per label in the
+segments file (``speaker_embeddings``). Records map those labels to real
+names (``record["speaker_names"]``), and named voice embeddings can be kept
+as profiles in ``DB/speaker_profiles.json``::
+
+    {
+        "3f2c...": {
+            "id": "3f2c...",
+            "name": "김철수",
+            "embedding": [0.12, ...],
+            "sample_count": 3,
+            "created_at": "...",
+            "updated_at": "..."
+        }
+    }
+
+When a new recording has speaker embeddings, each label is compared with
+the profiles (cosine similarity) and labelled automatically when the score
+reaches ``SPEAKER_MATCH_THRESHOLD``.
+"""
+
+from __future__ import annotations
+
+import json
+import math
+import threading
+import uuid
+from datetime import datetime
+from typing import Any, Dict, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value, get_db_base_path
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value, get_db_base_path  # type: ignore
+
+PROFILES_FILE = get_db_base_path() / "speaker_profiles.json"
+SPEAKER_MATCH_THRESHOLD = get_config_value("SPEAKER_MATCH_THRESHOLD", 0.75, float)
+MAX_SPEAKER_NAME_LENGTH = 100
+
+_profiles_lock = threading.Lock()
+
+
+class SpeakerProfileError(ValueError):
+    """Raised for invalid speaker names, embeddings or unknown profiles."""
+
+
+def speaker_labels(document: Optional[Dict[str, Any]]) -> List[str]:
+    """Return the speaker labels of a segments document in order of appearance."""
+    labels: List[str] = []
+    for segment in (document or {}).get("segments") or []:
+        label = segment.get("speaker")
+        if label and label not in labels:
+            labels.append(label)
+    return labels
+
+
+def speaker_embeddings(document: Optional[Dict[str, Any]]) -> Dict[str, List[float]]:
+    embeddings = (document or {}).get("speaker_embeddings") or {}
+    return {label: vector for label, vector in embeddings.items() if _is_vector(vector)}
+
+
+def _is_vector(value: Any) -> bool:
+    return isinstance(value, list) and bool(value) and all(isinstance(v, (int, float)) for v in value)
+
+
+def _validate_name(name: Any) -> str:
+    if not isinstance(name, str) or not name.strip():
+        raise SpeakerProfileError("화자 이름은 비어 있지 않은 문자열이어야 합니다.")
+    name = name.strip()
+    if len(name) > MAX_SPEAKER_NAME_LENGTH:
+        raise SpeakerProfileError(f"화자 이름은 {MAX_SPEAKER_NAME_LENGTH}자 이하여야 합니다.")
+    return name
+
+
+def _cosine(a: List[float], b: List[float]) -> float:
+    if len(a) != len(b):
+        return 0.0
+    dot = sum(x * y for x, y in zip(a, b))
+    norm = math.sqrt(sum(x * x for x in a)) * math.sqrt(sum(y * y for y in b))
+    return dot / norm if norm else 0.0
+
+
+def load_profiles() -> Dict[str, Dict[str, Any]]:
+    if not PROFILES_FILE.exists():
+        return {}
+    try:
+        with open(PROFILES_FILE, "r", encoding="utf-8") as f:
+            data = json.load(f)
+        return data if isinstance(data, dict) else {}
+    except (OSError, json.JSONDecodeError):
+        return {}
+
+
+def _save_profiles(profiles: Dict[str, Dict[str, Any]]) -> None:
+    PROFILES_FILE.parent.mkdir(parents=True, exist_ok=True)
+    tmp_path = PROFILES_FILE.with_suffix(".json.tmp")
+    with open(tmp_path, "w", encoding="utf-8") as f:
+        json.dump(profiles, f, ensure_ascii=False, indent=2)
+    tmp_path.replace(PROFILES_FILE)
+
+
+def public_profile(profile: Dict[str, Any]) -> Dict[str, Any]:
+    """Profile fields for API responses (the embedding itself is omitted)."""
+    embedding = profile.get("embedding")
+    return {
+        "id": profile.get("id"),
+        "name": profile.get("name"),
+        "has_voice": _is_vector(embedding),
+        "dimensions": len(embedding) if _is_vector(embedding) else 0,
+        "sample_count": profile.get("sample_count", 0),
+        "created_at": profile.get("created_at"),
+        "updated_at": profile.get("updated_at"),
+    }
+
+
+def list_profiles() -> List[Dict[str, Any]]:
+    profiles = sorted(load_profiles().values(), key=lambda p: p.get("name") or "")
+    return [public_profile(profile) for profile in profiles]
+
+
+def _merge_embedding(profile: Dict[str, Any], embedding: List[float]) -> None:
+    """Fold a new voice sample into the profile as a running average."""
+    current = profile.get("embedding")
+    count = profile.get("sample_count", 0)
+    if _is_vector(current) and len(current) == len(embedding) and count:
+        profile["embedding"] = [(c * count + e) / (count + 1) for c, e in zip(current, embedding)]
+        profile["sample_count"] = count + 1
+    else:
+        profile["embedding"] = [float(v) for v in embedding]
+        profile["sample_count"] = 1
+
+
+def create_profile(name: Any, embedding: Optional[List[float]] = None) -> Dict[str, Any]:
+    name = _validate_name(name)
+    if embedding is not None and not _is_vector(embedding):
+        raise SpeakerProfileError("embedding은 숫자 배열이어야 합니다.")
+
+    now = datetime.now().isoformat()
+    profile = {"id": str(uuid.uuid4()), "name": name, "embedding": None,
+               "sample_count": 0, "created_at": now, "updated_at": now}
+    if embedding is not None:
+        _merge_embedding(profile, embedding)
+
+    with _profiles_lock:
+        profiles = load_profiles()
+        if any(p.get("name") == name for p in profiles.values()):
+            raise SpeakerProfileError(f"같은 이름의 화자 프로필이 이미 있습니다: {name}")
+        profiles[profile["id"]] = profile
+        _save_profiles(profiles)
+    return profile
+
+
+def update_profile(profile_id: str, name: Any = None, embedding: Optional[List[float]] = None,
+                   replace_embedding: bool = False) -> Dict[str, Any]:
+    """Rename a profile and/or add a voice sample (``replace_embedding`` resets it)."""
+    if embedding is not None and not _is_vector(embedding):
+        raise SpeakerProfileError("embedding은 숫자 배열이어야 합니다.")
+
+    with _profiles_lock:
+        profiles = load_profiles()
+        profile = profiles.get(profile_id)
+        if not profile:
+            raise SpeakerProfileError("화자 프로필을 찾을 수 없습니다.")
+        if name is not None:
+            name = _validate_name(name)
+            if any(p.get("name") == name and pid != profile_id for pid, p in profiles.items()):
+                raise SpeakerProfileError(f"같은 이름의 화자 프로필이 이미 있습니다: {name}")
+            profile["name"] = name
+        if embedding is not None:
+            if replace_embedding:
+                profile["sample_count"] = 0
+            _merge_embedding(profile, embedding)
+        profile["updated_at"] = datetime.now().isoformat()
+        _save_profiles(profiles)
+    return profile
+
+
+def delete_profile(profile_id: str) -> bool:
+    with _profiles_lock:
+        profiles = load_profiles()
+        if profiles.pop(profile_id, None) is None:
+            return False
+        _save_profiles(profiles)
+    return True
+
+
+def enroll_speakers(names: Dict[str, str], embeddings: Dict[str, List[float]]) -> List[Dict[str, Any]]:
+    """Store the voice of each named label, adding to a same-named profile if present."""
+    enrolled = []
+    for label, name in names.items():
+        embedding = embeddings.get(label)
+        if not name or embedding is None:
+            continue
+        existing = next((p for p in load_profiles().values() if p.get("name") == name), None)
+        if existing:
+            profile = update_profile(existing["id"], embedding=embedding)
+        else:
+            profile = create_profile(name, embedding)
+        enrolled.append(public_profile(profile))
+    return enrolled
+
+
+def match_speakers(embeddings: Dict[str, List[float]],
+                   threshold: Optional[float] = None) -> Dict[str, Dict[str, Any]]:
+    """Match each label's embedding to the most similar voice profile.
+
+    Returns ``{label: {"profile_id", "name", "score"}}`` for labels whose
+    best score reaches the threshold. A profile is assigned to at most one
+    label (the best scoring one).
+    """
+    threshold = SPEAKER_MATCH_THRESHOLD if threshold is None else threshold
+    profiles = [p for p in load_profiles().values() if _is_vector(p.get("embedding"))]
+    if not embeddings or not profiles:
+        return {}
+
+    candidates = []
+    for label, vector in embeddings.items():
+        for profile in profiles:
+            score = _cosine(vector, profile["embedding"])
+            if score >= threshold:
+                candidates.append((score, label, profile))
+
+    matches: Dict[str, Dict[str, Any]] = {}
+    used_profiles = set()
+    for score, label, profile in sorted(candidates, key=lambda item: item[0], reverse=True):
+        if label in matches or profile["id"] in used_profiles:
+            continue
+        matches[label] = {"profile_id": profile["id"], "name": profile["name"], "score": round(score, 4)}
+        used_profiles.add(profile["id"])
+    return matches
+
+
+def validate_speaker_names(names: Any, labels: List[str]) -> Dict[str, str]:
+    """Validate a ``{label: name}`` rename request against the record's labels.
+
+    An empty name removes the mapping for that label.
+    """
+    if not isinstance(names, dict) or not names:
+        raise SpeakerProfileError("names는 {화자 라벨: 이름} 형식의 객체여야 합니다.")
+    if not labels:
+        raise SpeakerProfileError("이 기록에는 화자 라벨이 없습니다. 화자 분리가 된 전사에서만 이름을 지정할 수 있습니다.")
+
+    unknown = [label for label in names if label not in labels]
+    if unknown:
+        raise SpeakerProfileError(f"알 수 없는 화자 라벨: {', '.join(unknown)}")
+
+    validated = {}
+    for label, name in names.items():
+        validated[label] = "" if name in (None, "") else _validate_name(name)
+    return validated
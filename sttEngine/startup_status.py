@@ -0,0 +1,121 @@
+"""Machine-readable startup progress for the desktop shell.
+
+"Serving on http://localhost:8080" is printed as soon as the port is open,
+which is before a preloaded Whisper model is downloaded and loaded, so a
+shell that waits for that line shows a UI whose first job then stalls. The
+server now reports its boot phases:
+
+    starting           process started, settings loaded
+    migrating          data migrations (``data_migrations``)
+    recovering         interrupted tasks, partial uploads, orphaned files
+    binding            HTTP port open — ``/startup_status`` answers, not ready yet
+    model-downloading  ``STARTUP_PRELOAD_STT_MODEL`` downloading, ``{"model", "pct"}``
+    model-loading      model loading into memory, ``{"model"}``
+    ready              accepting work
+    fatal              startup failed, ``{"error"}`` (the process exits)
+
+With ``--startup-status`` (or ``STARTUP_STATUS_STDOUT=true``) every change
+is also written to stdout as one JSON line starting with ``{"startup":``,
+e.g. ``{"startup": "model-downloading", "model": "large-v3-turbo", "pct": 42,
+"at": "..."}``; other output lines never start that way. ``GET
+/startup_status`` returns the current phase with the same fields.
+"""
+
+from __future__ import annotations
+
+import json
+import sys
+import threading
+from contextlib import contextmanager
+from datetime import datetime
+from typing import Any, Dict, Iterator, List
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+    from .disk_guard import WHISPER_MODEL_BYTES, whisper_model_file
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+    from disk_guard import WHISPER_MODEL_BYTES, whisper_model_file  # type: ignore
+
+STARTUP_STATUS_STDOUT = get_config_value("STARTUP_STATUS_STDOUT", False, bool)
+# 빈 값이면 미리 불러오지 않고 첫 전사 때 로드
+STARTUP_PRELOAD_STT_MODEL = get_config_value("STARTUP_PRELOAD_STT_MODEL", "", str).strip()
+PHASES = ("starting", "migrating", "recovering", "binding", "model-downloading", "model-loading", "ready", "fatal")
+# 모델 다운로드 진행률 확인 간격 (초)
+DOWNLOAD_POLL_SECONDS = 0.5
+
+_status_lock = threading.Lock()
+_stream = STARTUP_STATUS_STDOUT
+_started_at = datetime.now().isoformat()
+_state: Dict[str, Any] = {"phase": "starting", "details": {}, "since": _started_at}
+_history: List[Dict[str, Any]] = []
+
+
+def enable_stream() -> None:
+    """Write status changes to stdout as JSON lines (``--startup-status``)."""
+    global _stream
+    _stream = True
+
+
+def report(phase: str, **details: Any) -> None:
+    """Move to ``phase`` (or update its details, e.g. ``pct``) and emit a status line."""
+    if phase not in PHASES:
+        raise ValueError(f"알 수 없는 시작 단계: {phase}")
+    now = datetime.now().isoformat()
+    with _status_lock:
+        if phase != _state["phase"] or not _history:
+            _history.append({"phase": phase, "at": now})
+            _state["since"] = now
+        _state["phase"] = phase
+        _state["details"] = details
+        if _stream:
+            line = json.dumps({"startup": phase, **details, "at": now}, ensure_ascii=False)
+            sys.stdout.write(line + "\n")
+            sys.stdout.flush()
+
+
+def startup_status() -> Dict[str, Any]:
+    """``{"phase", "ready", "since", "started_at", "phases": [{"phase", "at"}], ...details}``."""
+    with _status_lock:
+        return {
+            "phase": _state["phase"],
+            "ready": _state["phase"] == "ready",
+            **_state["details"],
+            "since": _state["since"],
+            "started_at": _started_at,
+            "phases": list(_history),
+        }
+
+
+@contextmanager
+def watch_model_download(model: str) -> Iterator[None]:
+    """Report ``model-downloading`` with the cache file size while the body runs, if the model is not cached yet."""
+    model_file = whisper_model_file(model)
+    if model_file is None or model_file.exists():
+        report("model-loading", model=model)
+        yield
+        return
+    expected = WHISPER_MODEL_BYTES[model]
+    done = threading.Event()
+
+    def poll() -> None:
+        last_pct = -1
+        while not done.wait(DOWNLOAD_POLL_SECONDS):
+            try:
+                size = model_file.stat().st_size
+            except OSError:
+                size = 0
+            # 크기는 대략값이라 로드가 끝나기 전에는 99%를 넘기지 않음
+            pct = min(99, size * 100 // expected)
+            if pct != last_pct:
+                report("model-downloading", model=model, pct=pct)
+                last_pct = pct
+
+    report("model-downloading", model=model, pct=0)
+    poller = threading.Thread(target=poll, daemon=True)
+    poller.start()
+    try:
+        yield
+    finally:
+        done.set()
+        poller.join()
@@ -0,0 +1,269 @@
+"""Structured (JSON) meeting summaries.
+
+The summary is normally free-form markdown with six fixed sections that
+``parse_summary_to_sections`` reads back by their headings. With
+``SUMMARY_FORMAT=structured`` (or ``model_settings.summary_format`` for one
+run) the final summary call asks Ollama for JSON instead (``format: json``)
+and the answer is parsed and validated into a :class:`StructuredSummary`::
+
+    {
+        "version": 1,
+        "language": "ko",
+        "topics": ["..."],
+        "key_points": ["..."],
+        "decisions": ["..."],
+        "action_items": [{"task": "견적서 재발송", "owner": "김민수", "due": "2026-10-20"}],
+        "risks": ["..."],
+        "next_steps": [{"item": "다음 회의", "date": "월요일 오전 10시"}]
+    }
+
+It is stored as ``{stem}.summary.json`` next to ``{stem}.summary.md``; the
+markdown is rendered from it with the usual headings (owners and dates as
+``(담당: ..., 기한: ...)``), so the action item tracker, minutes templates,
+search and Obsidian export keep reading the markdown. Only the final call
+changes; chunk summaries stay markdown. ``GET /record/{id}/summary`` serves
+the JSON, or parses it from the markdown for summaries made before.
+"""
+
+from __future__ import annotations
+
+import json
+import re
+from dataclasses import asdict, dataclass, field
+from pathlib import Path
+from typing import Any, Dict, List, Optional, Sequence
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .action_items import parse_item as parse_action_item
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from action_items import parse_item as parse_action_item  # type: ignore
+    from config import get_config_value  # type: ignore
+
+SUMMARY_FORMATS = ("markdown", "structured")
+SUMMARY_FORMAT = get_config_value("SUMMARY_FORMAT", "markdown", str).strip().lower()
+STRUCTURED_SUMMARY_VERSION = 1
+TEXT_SECTIONS = ("topics", "key_points", "decisions", "risks")
+# 모델이 다른 이름으로 답한 키 → 고정 키
+KEY_ALIASES = {
+    "main_topics": "topics",
+    "keypoints": "key_points",
+    "key_content": "key_points",
+    "action_item": "action_items",
+    "actions": "action_items",
+    "risks_issues": "risks",
+    "issues": "risks",
+    "schedule": "next_steps",
+    "next_schedule": "next_steps",
+}
+OWNER_KEYS = ("owner", "assignee", "담당", "담당자")
+DUE_KEYS = ("due", "deadline", "due_date", "기한", "마감")
+DATE_KEYS = ("date", "when", "time", "일시", "날짜")
+# 빈 섹션에 쓰는 말 (action_items.py가 항목으로 보지 않는 표현)
+EMPTY_ITEM = {"ko": "없음", "en": "None", "ja": "なし", "zh": "无"}
+OWNER_LABELS = {"ko": ("담당", "기한"), "en": ("Owner", "Due"), "ja": ("Owner", "Due"), "zh": ("Owner", "Due")}
+
+
+class StructuredSummaryError(ValueError):
+    """Raised when a model answer is not a valid structured summary."""
+
+
+def summary_format(requested: Optional[str] = None) -> str:
+    """``markdown`` or ``structured`` for this run (``SUMMARY_FORMAT`` unless requested)."""
+    value = (requested or SUMMARY_FORMAT or "markdown").strip().lower()
+    if value not in SUMMARY_FORMATS:
+        raise StructuredSummaryError(
+            f"summary_format은 {', '.join(SUMMARY_FORMATS)} 중 하나여야 합니다: {requested!r}"
+        )
+    return value
+
+
+@dataclass
+class ActionItem:
+    task: str
+    owner: Optional[str] = None
+    due: Optional[str] = None
+
+
+@dataclass
+class ScheduleItem:
+    item: str
+    date: Optional[str] = None
+
+
+@dataclass
+class StructuredSummary:
+    """A summary split into the six fixed sections, with owners/dates on action items and schedule."""
+
+    language: str
+    topics: List[str] = field(default_factory=list)
+    key_points: List[str] = field(default_factory=list)
+    decisions: List[str] = field(default_factory=list)
+    action_items: List[ActionItem] = field(default_factory=list)
+    risks: List[str] = field(default_factory=list)
+    next_steps: List[ScheduleItem] = field(default_factory=list)
+    source: str = "model"
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"version": STRUCTURED_SUMMARY_VERSION, **asdict(self)}
+
+    def to_markdown(self, headings: Sequence[str]) -> str:
+        """Markdown in the usual layout (``## heading`` + bullets) for ``headings`` in section order."""
+        owner_label, due_label = OWNER_LABELS.get(self.language, OWNER_LABELS["en"])
+        empty = EMPTY_ITEM.get(self.language, EMPTY_ITEM["en"])
+        sections: List[List[str]] = [self.topics, self.key_points, self.decisions]
+        actions = []
+        for action in self.action_items:
+            details = [f"{label}: {value}" for label, value in ((owner_label, action.owner), (due_label, action.due))
+                       if value]
+            actions.append(f"{action.task} ({', '.join(details)})" if details else action.task)
+        sections += [actions, self.risks, [f"{entry.item} ({entry.date})" if entry.date else entry.item
+                                           for entry in self.next_steps]]
+        lines: List[str] = []
+        for index, (heading, items) in enumerate(zip(headings, sections), 1):
+            lines.append(f"## {index}) {heading}")
+            lines.extend(f"- {item}" for item in items or [empty])
+            lines.append("")
+        return "\n".join(lines).strip() + "\n"
+
+
+def _text(value: Any, where: str) -> Optional[str]:
+    if value is None:
+        return None
+    if isinstance(value, (int, float)) and not isinstance(value, bool):
+        value = str(value)
+    if not isinstance(value, str):
+        raise StructuredSummaryError(f"{where}: 문자열이어야 합니다.")
+    value = value.strip()
+    if not value or value.casefold() in ("null", "none", "없음", "n/a", "-"):
+        return None
+    return value
+
+
+def _entries(value: Any, where: str) -> List[Any]:
+    if value is None:
+        return []
+    if isinstance(value, (str, dict)):
+        return [value]
+    if not isinstance(value, list):
+        raise StructuredSummaryError(f"{where}: 배열이어야 합니다.")
+    return value
+
+
+def _pick(entry: Dict[str, Any], keys: Sequence[str]) -> Any:
+    return next((entry[key] for key in keys if entry.get(key) not in (None, "")), None)
+
+
+def _normalize_key(key: str) -> str:
+    key = re.sub(r"[\s/-]+", "_", str(key).strip()).casefold()
+    return KEY_ALIASES.get(key, key)
+
+
+def extract_json_object(text: str) -> Dict[str, Any]:
+    """The JSON object in a model answer (code fences and text around it are ignored)."""
+    text = re.sub(r"^\s*```(?:json)?\s*|\s*```\s*$", "", text.strip())
+    start, end = text.find("{"), text.rfind("}")
+    if start < 0 or end <= start:
+        raise StructuredSummaryError("응답에 JSON 객체가 없습니다.")
+    try:
+        data = json.loads(text[start:end + 1])
+    except ValueError as e:
+        raise StructuredSummaryError(f"JSON을 해석할 수 없습니다: {e}") from None
+    if not isinstance(data, dict):
+        raise StructuredSummaryError("최상위 값이 JSON 객체가 아닙니다.")
+    return data
+
+
+def parse_structured_summary(answer: str, language: str) -> StructuredSummary:
+    """Validate a model's JSON answer into a :class:`StructuredSummary`.
+
+    Missing sections become empty, a bare string becomes a one-item list and
+    action items/schedule entries given as plain strings keep no owner/date.
+    Raises :class:`StructuredSummaryError` when the answer is not JSON, a
+    section has the wrong type or no known section is present.
+    """
+    data = {_normalize_key(key): value for key, value in extract_json_object(answer).items()}
+    known = [key for key in (*TEXT_SECTIONS, "action_items", "next_steps") if key in data]
+    if not known:
+        raise StructuredSummaryError(
+            "요약 섹션 키(topics, key_points, decisions, action_items, risks, next_steps)가 하나도 없습니다."
+        )
+    summary = StructuredSummary(language=language)
+    for key in TEXT_SECTIONS:
+        items = [_text(entry, f"{key}[{index}]") for index, entry in enumerate(_entries(data.get(key), key))]
+        setattr(summary, key, [item for item in items if item])
+    for index, entry in enumerate(_entries(data.get("action_items"), "action_items")):
+        where = f"action_items[{index}]"
+        if isinstance(entry, dict):
+            task = _text(_pick(entry, ("task", "item", "text", "action", "내용")), f"{where}.task")
+            if task:
+                owner = _text(_pick(entry, OWNER_KEYS), f"{where}.owner")
+                summary.action_items.append(ActionItem(task, owner, _text(_pick(entry, DUE_KEYS), f"{where}.due")))
+        else:
+            task = _text(entry, where)
+            if task:
+                summary.action_items.append(ActionItem(task))
+    for index, entry in enumerate(_entries(data.get("next_steps"), "next_steps")):
+        where = f"next_steps[{index}]"
+        if isinstance(entry, dict):
+            item = _text(_pick(entry, ("item", "event", "task", "text", "내용")), f"{where}.item")
+            if item:
+                summary.next_steps.append(ScheduleItem(item, _text(_pick(entry, DATE_KEYS), f"{where}.date")))
+        else:
+            item = _text(entry, where)
+            if item:
+                summary.next_steps.append(ScheduleItem(item))
+    return summary
+
+
+def from_sections(sections: Dict[str, List[str]], language: str) -> StructuredSummary:
+    """Best-effort structure of a markdown summary (``parse_summary_to_sections`` output)."""
+    empty = {word.casefold() for word in EMPTY_ITEM.values()} | {"해당 없음", "n/a"}
+
+    def keep(items: List[str]) -> List[str]:
+        return [item for item in items if item.strip(" .").casefold() not in empty]
+
+    actions = []
+    for line in sections.get("action_items") or []:
+        parsed = parse_action_item(line)
+        if parsed:
+            actions.append(ActionItem(parsed["text"], parsed["assignee"], parsed["due"]))
+    return StructuredSummary(
+        language=language,
+        topics=keep(sections.get("topics") or []),
+        key_points=keep(sections.get("key_points") or []),
+        decisions=keep(sections.get("decisions") or []),
+        action_items=actions,
+        risks=keep(sections.get("risks") or []),
+        next_steps=[ScheduleItem(item) for item in keep(sections.get("next_steps") or [])],
+        source="markdown",
+    )
+
+
+def structured_path_for(summary_path: Path) -> Path:
+    """``{stem}.summary.json`` next to ``{stem}.summary.md``."""
+    return summary_path.with_suffix(".json")
+
+
+def save_structured_summary(summary: StructuredSummary, summary_path: Path) -> Path:
+    path = structured_path_for(summary_path)
+    tmp_path = path.with_name(f"{path.name}.tmp")
+    with open(tmp_path, "w", encoding="utf-8") as f:
+        json.dump(summary.to_dict(), f, ensure_ascii=False, indent=2)
+    tmp_path.replace(path)
+    return path
+
+
+def discard_structured_summary(summary_path: Path) -> None:
+    """Remove the JSON of an earlier structured run when the summary is rewritten as markdown."""
+    structured_path_for(summary_path).unlink(missing_ok=True)
+
+
+def load_structured_summary(summary_path: Path) -> Optional[Dict[str, Any]]:
+    """The stored JSON of a summary, or ``None`` when it was written as markdown only."""
+    try:
+        with open(structured_path_for(summary_path), "r", encoding="utf-8") as f:
+            data = json.load(f)
+    except (OSError, ValueError):
+        return None
+    return data if isinstance(data, dict) else None
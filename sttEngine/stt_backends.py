@@ -0,0 +1,492 @@
+"""Pluggable speech-to-text backends.
+
+Every backend implements :class:`SttEngine` — ``transcribe(path, output_dir,
+options) -> Transcription`` — and writes the same outputs as the local
+Whisper engine (``{stem}.md`` plus ``{stem}.segments.json``), so the rest of
+the workflow does not care where the audio was transcribed.
+
+Backends (``STT_BACKEND``):
+
+    whisper  로컬 OpenAI Whisper (기본값)
+    http     whisper.cpp server / faster-whisper server 등 원격 HTTP 서버
+    openai   OpenAI / Azure OpenAI 음성 변환 API (``OPENAI_STT_ENABLED=true`` 필요)
+
+The ``http`` backend posts the audio as multipart form data to
+``STT_HTTP_URL`` and expects a ``verbose_json`` style response
+(``{"text": ..., "language": ..., "segments": [{"start", "end", "text"}]}``).
+Servers that diarize may add ``speaker`` to each segment and a top-level
+``speaker_embeddings`` map; both are kept for speaker profile matching.
+That covers whisper.cpp's ``/inference`` endpoint and OpenAI-compatible
+``/v1/audio/transcriptions`` endpoints such as faster-whisper-server.
+
+The decoding strategy (``decoding.py``) reaches the ``http`` backend as
+whisper.cpp's ``temperature_inc`` (0 for greedy); the OpenAI API applies its
+own temperature fallback and is recorded as strategy ``api``.
+
+The ``openai`` backend sends audio to the cloud, so it stays unavailable
+unless explicitly enabled. Audio is re-encoded to compact mono MP3 and
+uploaded in ``OPENAI_STT_CHUNK_MINUTES`` windows to stay under the API's
+upload limit; segment timestamps are shifted back by each window's offset.
+"""
+
+from __future__ import annotations
+
+import logging
+import os
+import subprocess
+import tempfile
+import time
+from abc import ABC, abstractmethod
+from dataclasses import dataclass, field
+from pathlib import Path
+from typing import Any, Callable, Dict, List, Optional, Tuple, Type
+
+import requests
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+    from .deadline import deadline_timeout
+    from .decoding import STT_TEMPERATURE_INCREMENT, describe as describe_decoding, resolve_strategy
+    from .resampler import needs_resample, resample_args
+    from .segment_store import load_segments, segments_path_for
+    from .stt_prompt import build_initial_prompt, custom_vocabulary, glossary_terms
+    from .text_utils import truncate_graphemes
+    from .workflow.transcribe import (
+        TranscriptionCancelled,
+        convert_to_wav,
+        engine_manager,
+        get_audio_duration,
+        resolve_inference_device,
+        transcribe_file,
+        write_transcription_outputs,
+    )
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+    from deadline import deadline_timeout  # type: ignore
+    from decoding import STT_TEMPERATURE_INCREMENT, describe as describe_decoding, resolve_strategy  # type: ignore
+    from resampler import needs_resample, resample_args  # type: ignore
+    from segment_store import load_segments, segments_path_for  # type: ignore
+    from stt_prompt import build_initial_prompt, custom_vocabulary, glossary_terms  # type: ignore
+    from text_utils import truncate_graphemes  # type: ignore
+    from workflow.transcribe import (  # type: ignore
+        TranscriptionCancelled,
+        convert_to_wav,
+        engine_manager,
+        get_audio_duration,
+        resolve_inference_device,
+        transcribe_file,
+        write_transcription_outputs,
+    )
+
+STT_BACKEND = get_config_value("STT_BACKEND", "whisper", str).strip().lower()
+STT_HTTP_URL = get_config_value("STT_HTTP_URL", "http://localhost:8080/inference", str)
+STT_HTTP_MODEL = get_config_value("STT_HTTP_MODEL", "", str)
+STT_HTTP_API_KEY = get_config_value("STT_HTTP_API_KEY", "", str)
+STT_HTTP_TIMEOUT = get_config_value("STT_HTTP_TIMEOUT", 3600, int)
+
+OPENAI_STT_ENABLED = get_config_value("OPENAI_STT_ENABLED", False, bool)
+OPENAI_STT_API_KEY = get_config_value("OPENAI_STT_API_KEY", "", str) or os.environ.get("OPENAI_API_KEY", "")
+OPENAI_STT_BASE_URL = get_config_value("OPENAI_STT_BASE_URL", "https://api.openai.com/v1", str)
+OPENAI_STT_MODEL = get_config_value("OPENAI_STT_MODEL", "whisper-1", str)
+# Azure OpenAI: OPENAI_STT_BASE_URL=https://{resource}.openai.azure.com/openai/deployments/{deployment}
+OPENAI_STT_AZURE_API_VERSION = get_config_value("OPENAI_STT_AZURE_API_VERSION", "", str)
+OPENAI_STT_CHUNK_MINUTES = max(1.0, get_config_value("OPENAI_STT_CHUNK_MINUTES", 10, float))
+OPENAI_STT_TIMEOUT = get_config_value("OPENAI_STT_TIMEOUT", 600, int)
+OPENAI_STT_MAX_RETRIES = max(1, get_config_value("OPENAI_STT_MAX_RETRIES", 3, int))
+# 다음 청크에 이어 붙이는 이전 청크 끝부분 (문맥 유지용)
+OPENAI_STT_CONTEXT_CHARS = 200
+# 요청에 언어가 없을 때 쓸 언어 ("auto" 또는 빈 값이면 로컬 Whisper가 언어를 감지)
+STT_DEFAULT_LANGUAGE = get_config_value("STT_DEFAULT_LANGUAGE", "ko", str)
+
+
+def default_language() -> Optional[str]:
+    """``STT_DEFAULT_LANGUAGE`` as a Whisper language code (``None``: detect)."""
+    language = STT_DEFAULT_LANGUAGE.strip().lower()
+    return None if language in ("", "auto") else language
+
+
+class SttBackendError(RuntimeError):
+    """Raised when a backend is unknown, misconfigured or its server fails."""
+
+
+@dataclass
+class TranscriptionOptions:
+    """Options shared by every backend; backends ignore what they cannot use."""
+
+    model: str = "large-v3-turbo"
+    # None이면 로컬 Whisper가 변환 전에 언어를 감지
+    language: Optional[str] = field(default_factory=default_language)
+    initial_prompt: str = ""
+    device: str = "auto"
+    filter_fillers: bool = False
+    min_seg_length: int = 2
+    normalize_punct: bool = False
+    # greedy | fallback | beam (None = STT_DECODING_STRATEGY)
+    decoding_strategy: Optional[str] = None
+    progress_callback: Optional[Callable[[str], None]] = None
+    # 로컬 Whisper 상태 풀을 기다리는 동안 (대기 순번, 예상 시작 epoch 초)로 호출, 대기가 끝나면 (0, None)
+    queue_callback: Optional[Callable[[int, Optional[float]], None]] = None
+    cancel_event: Any = None
+    # 구간 재변환 같은 중간 결과는 Obsidian으로 보내지 않음
+    export_to_obsidian: bool = True
+    # stt_prompt.build_initial_prompt 결과. 있으면 initial_prompt가 용어집까지 합친 최종 프롬프트
+    prompt_provenance: Optional[Dict[str, Any]] = None
+
+    def resolve_prompt(self, scope: Optional[str] = None) -> Tuple[str, Dict[str, Any]]:
+        """Final prompt and its provenance; without provenance the vocabulary and glossary are merged in here."""
+        if self.prompt_provenance is not None:
+            prompt, provenance = self.initial_prompt, dict(self.prompt_provenance)
+        else:
+            prompt, provenance = build_initial_prompt(
+                self.initial_prompt, {"vocabulary": custom_vocabulary(), "glossary": glossary_terms()}
+            )
+        if provenance["applied"]:
+            self.report(f"초기 프롬프트 적용: {provenance['tokens']}토큰 ({', '.join(provenance['sources'])})")
+        if scope is not None:
+            provenance["scope"] = scope if prompt else None
+        return prompt, provenance
+
+    def report(self, message: str) -> None:
+        if self.progress_callback:
+            self.progress_callback(message)
+
+    def check_cancelled(self) -> None:
+        if self.cancel_event is not None and self.cancel_event.is_set():
+            raise TranscriptionCancelled("작업이 취소되었습니다.")
+
+
+@dataclass
+class Transcription:
+    """Result of one transcription: the written markdown and its segments."""
+
+    output_path: Path
+    backend: str
+    model: Optional[str] = None
+    language: Optional[str] = None
+    segments: List[Dict[str, Any]] = field(default_factory=list)
+    verbatim: List[Dict[str, Any]] = field(default_factory=list)
+    prompt: Optional[Dict[str, Any]] = None
+    decoding: Optional[Dict[str, Any]] = None
+    # 언어를 감지했을 때만: {"language", "confidence", "candidates", "windows"}
+    language_detection: Optional[Dict[str, Any]] = None
+
+    @property
+    def language_confidence(self) -> Optional[float]:
+        """Probability of the detected language (``None`` when the language was given)."""
+        return (self.language_detection or {}).get("confidence")
+
+    @classmethod
+    def from_output(cls, output_path: Path, backend: str, model: Optional[str],
+                    language: Optional[str]) -> "Transcription":
+        document = load_segments(segments_path_for(output_path), persist_migration=False) or {}
+        return cls(
+            output_path=output_path,
+            backend=backend,
+            model=document.get("model") or model,
+            language=document.get("language") or language,
+            segments=document.get("segments") or [],
+            verbatim=document.get("verbatim") or [],
+            prompt=document.get("prompt"),
+            decoding=document.get("decoding"),
+            language_detection=document.get("language_detection"),
+        )
+
+
+class SttEngine(ABC):
+    """Interface every STT backend implements."""
+
+    name: str = ""
+
+    @abstractmethod
+    def transcribe(self, path: Path, output_dir: Path, options: TranscriptionOptions) -> Transcription:
+        """Transcribe ``path`` into ``output_dir`` and return the result."""
+
+    def describe(self) -> Dict[str, Any]:
+        """Backend details recorded with the stt_completed event."""
+        return {"backend": self.name}
+
+    def preload(self, model: str, device: str = "auto") -> None:
+        """Load ``model`` ahead of the first job (``STARTUP_PRELOAD_STT_MODEL``); only local Whisper loads anything."""
+
+
+class WhisperEngine(SttEngine):
+    """Local OpenAI Whisper using the shared model/state pool."""
+
+    name = "whisper"
+
+    def preload(self, model: str, device: str = "auto") -> None:
+        engine_manager.get_pool(model, resolve_inference_device(device)[0])
+
+    def transcribe(self, path: Path, output_dir: Path, options: TranscriptionOptions) -> Transcription:
+        # 적용 범위(scope)는 설치된 Whisper 버전에 따라 transcribe_file이 채움
+        prompt, provenance = options.resolve_prompt()
+        output_path = transcribe_file(
+            path,
+            output_dir,
+            model_identifier=options.model,
+            language=options.language,
+            initial_prompt=prompt,
+            filter_fillers=options.filter_fillers,
+            min_seg_length=options.min_seg_length,
+            normalize_punct=options.normalize_punct,
+            requested_device=options.device,
+            progress_callback=options.progress_callback,
+            cancel_event=options.cancel_event,
+            export_to_obsidian=options.export_to_obsidian,
+            queue_callback=options.queue_callback,
+            prompt_provenance=provenance,
+            decoding_strategy=options.decoding_strategy,
+        )
+        return Transcription.from_output(output_path, self.name, os.path.basename(options.model), options.language)
+
+
+class HttpSttEngine(SttEngine):
+    """Remote whisper.cpp / faster-whisper server reached over HTTP."""
+
+    name = "http"
+
+    def __init__(self, url: str = None, model: str = None, api_key: str = None, timeout: int = None):
+        self.url = url or STT_HTTP_URL
+        self.model = model if model is not None else STT_HTTP_MODEL
+        self.api_key = api_key if api_key is not None else STT_HTTP_API_KEY
+        self.timeout = timeout or STT_HTTP_TIMEOUT
+
+    def describe(self) -> Dict[str, Any]:
+        return {"backend": self.name, "url": self.url}
+
+    def _form_fields(self, options: TranscriptionOptions, prompt: str) -> Dict[str, str]:
+        strategy = resolve_strategy(options.decoding_strategy)
+        fields = {
+            "response_format": "verbose_json",
+            "temperature": "0.0",
+            "temperature_inc": "0.0" if strategy == "greedy" else str(STT_TEMPERATURE_INCREMENT),
+        }
+        if self.model:
+            fields["model"] = self.model
+        if options.language:
+            fields["language"] = options.language
+        if prompt:
+            fields["prompt"] = prompt
+        return fields
+
+    def _request(self, audio_path: Path, fields: Dict[str, str]) -> Dict[str, Any]:
+        headers = {"Authorization": f"Bearer {self.api_key}"} if self.api_key else {}
+        try:
+            with open(audio_path, "rb") as audio:
+                response = requests.post(
+                    self.url,
+                    data=fields,
+                    files={"file": (audio_path.name, audio)},
+                    headers=headers,
+                    timeout=deadline_timeout(self.timeout),
+                )
+            response.raise_for_status()
+            return response.json()
+        except requests.RequestException as exc:
+            raise SttBackendError(f"원격 STT 서버 요청 실패 ({self.url}): {exc}") from exc
+        except ValueError as exc:
+            raise SttBackendError(f"원격 STT 서버 응답이 JSON이 아닙니다 ({self.url})") from exc
+
+    def transcribe(self, path: Path, output_dir: Path, options: TranscriptionOptions) -> Transcription:
+        options.check_cancelled()
+        output_dir.mkdir(parents=True, exist_ok=True)
+        prompt, provenance = options.resolve_prompt(scope="request")
+
+        with tempfile.TemporaryDirectory(prefix="recordroute_stt_") as tmp_dir:
+            # whisper.cpp 서버는 기본적으로 16kHz WAV만 받으므로 업로드 전에 16kHz 모노로 변환
+            audio_path = path
+            if needs_resample(path):
+                options.report(f"'{path.name}' wav 변환 중...")
+                audio_path = convert_to_wav(path, Path(tmp_dir) / f"{path.stem}.wav")
+
+            options.report(f"'{path.name}' 원격 STT 서버로 전송 중...")
+            logging.info("원격 STT 요청: %s → %s", path.name, self.url)
+            payload = self._request(audio_path, self._form_fields(options, prompt))
+
+        # HTTP 요청 중에는 중단할 수 없으므로 응답 후 취소 여부를 확인
+        options.check_cancelled()
+        result = normalize_remote_result(payload)
+        result["decoding"] = describe_decoding(resolve_strategy(options.decoding_strategy))
+        model_name = self.model or "remote"
+        output_path = write_transcription_outputs(
+            path, output_dir, result, options.language, options.filter_fillers,
+            options.min_seg_length, options.normalize_punct, model_name, options.progress_callback,
+            export_to_obsidian=options.export_to_obsidian, prompt_provenance=provenance,
+        )
+        return Transcription.from_output(output_path, self.name, model_name, result.get("language"))
+
+
+def normalize_remote_result(payload: Any) -> Dict[str, Any]:
+    """Convert a verbose_json style response into Whisper's result shape."""
+    if not isinstance(payload, dict):
+        raise SttBackendError("원격 STT 응답 형식이 올바르지 않습니다.")
+    if payload.get("error"):
+        raise SttBackendError(f"원격 STT 서버 오류: {payload['error']}")
+
+    segments = []
+    for segment in payload.get("segments") or []:
+        try:
+            normalized = {
+                "start": float(segment.get("start", 0.0)),
+                "end": float(segment.get("end", 0.0)),
+                "text": str(segment.get("text", "")),
+            }
+        except (AttributeError, TypeError, ValueError):
+            continue
+        if segment.get("speaker"):
+            normalized["speaker"] = str(segment["speaker"])
+        if isinstance(segment.get("temperature"), (int, float)):
+            normalized["temperature"] = float(segment["temperature"])
+        segments.append(normalized)
+
+    result = {
+        "text": str(payload.get("text") or ""),
+        "language": payload.get("language"),
+        "segments": segments,
+    }
+    if isinstance(payload.get("speaker_embeddings"), dict):
+        result["speaker_embeddings"] = payload["speaker_embeddings"]
+    return result
+
+
+class OpenAISttEngine(SttEngine):
+    """OpenAI (or Azure OpenAI) hosted transcription API with chunked uploads."""
+
+    name = "openai"
+
+    def __init__(self):
+        if not OPENAI_STT_API_KEY:
+            raise SttBackendError("OpenAI STT 백엔드를 쓰려면 OPENAI_STT_API_KEY를 설정하세요.")
+        self.base_url = OPENAI_STT_BASE_URL.rstrip("/")
+        self.model = OPENAI_STT_MODEL
+        self.azure = bool(OPENAI_STT_AZURE_API_VERSION)
+
+    def describe(self) -> Dict[str, Any]:
+        return {"backend": self.name, "api_model": self.model, "azure": self.azure}
+
+    def _endpoint(self) -> str:
+        url = f"{self.base_url}/audio/transcriptions"
+        if self.azure:
+            url += f"?api-version={OPENAI_STT_AZURE_API_VERSION}"
+        return url
+
+    def _headers(self) -> Dict[str, str]:
+        if self.azure:
+            return {"api-key": OPENAI_STT_API_KEY}
+        return {"Authorization": f"Bearer {OPENAI_STT_API_KEY}"}
+
+    def _encode_chunk(self, source: Path, target: Path, start: float, duration: Optional[float]) -> Path:
+        command = ["ffmpeg", "-nostdin", "-y", "-ss", f"{start:.3f}"]
+        if duration is not None:
+            command += ["-t", f"{duration:.3f}"]
+        command += ["-i", str(source), "-vn", *resample_args(), "-b:a", "48k", str(target)]
+        result = subprocess.run(command, capture_output=True, text=True, encoding="utf-8", check=False)
+        if result.returncode != 0:
+            raise RuntimeError(f"ffmpeg 청크 인코딩 실패: {result.stderr}")
+        return target
+
+    def _transcribe_chunk(self, chunk_path: Path, options: TranscriptionOptions, prompt: str) -> Dict[str, Any]:
+        fields = {
+            "model": self.model,
+            "response_format": "verbose_json",
+            "temperature": "0",
+            "timestamp_granularities[]": "segment",
+        }
+        if options.language:
+            fields["language"] = options.language
+        if prompt:
+            fields["prompt"] = prompt
+
+        last_error = None
+        for attempt in range(1, OPENAI_STT_MAX_RETRIES + 1):
+            options.check_cancelled()
+            try:
+                with open(chunk_path, "rb") as audio:
+                    response = requests.post(
+                        self._endpoint(),
+                        data=fields,
+                        files={"file": (chunk_path.name, audio, "audio/mpeg")},
+                        headers=self._headers(),
+                        timeout=deadline_timeout(OPENAI_STT_TIMEOUT),
+                    )
+                if response.status_code == 429 or response.status_code >= 500:
+                    raise requests.HTTPError(f"{response.status_code} {truncate_graphemes(response.text, 200)}")
+                if response.status_code >= 400:
+                    # 인증/요청 오류는 재시도해도 같으므로 바로 실패
+                    raise SttBackendError(f"OpenAI STT 요청 실패 ({response.status_code}): {truncate_graphemes(response.text, 500)}")
+                return response.json()
+            except (requests.RequestException, ValueError) as exc:
+                last_error = exc
+                logging.warning("OpenAI STT 요청 실패 (시도 %d/%d): %s", attempt, OPENAI_STT_MAX_RETRIES, exc)
+                if attempt < OPENAI_STT_MAX_RETRIES:
+                    time.sleep(2 ** attempt)
+        raise SttBackendError(f"OpenAI STT 요청이 모두 실패했습니다: {last_error}")
+
+    def transcribe(self, path: Path, output_dir: Path, options: TranscriptionOptions) -> Transcription:
+        options.check_cancelled()
+        output_dir.mkdir(parents=True, exist_ok=True)
+        prompt, provenance = options.resolve_prompt(scope="chunk")
+
+        total = get_audio_duration(path)
+        window = OPENAI_STT_CHUNK_MINUTES * 60
+        if total:
+            starts = [i * window for i in range(int(total // window) + (1 if total % window else 0))] or [0.0]
+        else:
+            # 길이를 모르면 한 번에 업로드 (업로드 한도를 넘으면 API가 거부함)
+            starts = [0.0]
+
+        segments: List[Dict[str, Any]] = []
+        texts: List[str] = []
+        language = options.language
+        with tempfile.TemporaryDirectory(prefix="recordroute_openai_stt_") as tmp_dir:
+            for index, start in enumerate(starts, 1):
+                options.check_cancelled()
+                options.report(f"'{path.name}' OpenAI STT 청크 {index}/{len(starts)} 업로드 중...")
+                duration = window if total else None
+                chunk_path = self._encode_chunk(path, Path(tmp_dir) / f"chunk_{index:03d}.mp3", start, duration)
+
+                # 이전 청크의 끝부분을 프롬프트로 넘겨 문장이 끊기지 않도록 함
+                context = " ".join(texts)[-OPENAI_STT_CONTEXT_CHARS:]
+                chunk_prompt = f"{prompt} {context}".strip() if prompt else context
+                result = normalize_remote_result(self._transcribe_chunk(chunk_path, options, chunk_prompt))
+                chunk_path.unlink(missing_ok=True)
+
+                for segment in result["segments"]:
+                    segments.append(dict(segment, start=segment["start"] + start, end=segment["end"] + start))
+                if result["text"]:
+                    texts.append(result["text"].strip())
+                language = language or result.get("language")
+
+        options.check_cancelled()
+        merged = {"text": " ".join(texts), "language": language, "segments": segments,
+                  "decoding": {"strategy": "api"}}
+        output_path = write_transcription_outputs(
+            path, output_dir, merged, options.language, options.filter_fillers,
+            options.min_seg_length, options.normalize_punct, self.model, options.progress_callback,
+            export_to_obsidian=options.export_to_obsidian, prompt_provenance=provenance,
+        )
+        return Transcription.from_output(output_path, self.name, self.model, language)
+
+
+# 백엔드 이름 → 구현 클래스. 새 백엔드는 여기에 등록한다.
+STT_ENGINES: Dict[str, Type[SttEngine]] = {
+    WhisperEngine.name: WhisperEngine,
+    HttpSttEngine.name: HttpSttEngine,
+}
+
+# 클라우드 전송은 명시적으로 켠 경우에만 사용 가능
+if OPENAI_STT_ENABLED:
+    STT_ENGINES[OpenAISttEngine.name] = OpenAISttEngine
+
+
+def get_stt_engine(name: Optional[str] = None) -> SttEngine:
+    """Return the configured (or named) backend instance."""
+    backend = (name or STT_BACKEND or "whisper").strip().lower()
+    engine_cls = STT_ENGINES.get(backend)
+    if engine_cls is None and backend == OpenAISttEngine.name:
+        raise SttBackendError("OpenAI STT 백엔드가 비활성화되어 있습니다. OPENAI_STT_ENABLED=true로 설정하세요.")
+    if engine_cls is None:
+        raise SttBackendError(
+            f"알 수 없는 STT 백엔드: {backend} (사용 가능: {', '.join(sorted(STT_ENGINES))})"
+        )
+    return engine_cls()
@@ -0,0 +1,226 @@
+"""Whisper initial-prompt biasing from the glossary and record metadata.
+
+Whisper spells names and jargon it has seen in the prompt far more reliably,
+so the STT step builds ``initial_prompt`` from, in priority order:
+
+* ``user`` — the prompt typed for this run (``model_settings.initial_prompt``);
+* ``attendees`` — attendee names given with the request or stored on the
+  record (``attendees``) plus speaker names already assigned to it;
+* ``tags`` — the record's tags (project codenames, customers, ...);
+* ``vocabulary`` — the curated custom vocabulary of this DB folder
+  (``STT_VOCABULARY_FILE``, default ``DB/stt_vocabulary.txt``): company and
+  product names, jargon, one term per line, ``#`` starts a comment. Edit it
+  by hand or through ``GET``/``POST /stt/vocabulary``;
+* ``people`` — names and aliases of attendees found in the people directory,
+  then the other names of the directory (``people_directory.py``);
+* ``glossary`` — the top ``vocab.json`` keywords (``STT_PROMPT_GLOSSARY_TERMS``).
+
+Terms are de-duplicated and added until ``STT_PROMPT_MAX_TOKENS`` is
+reached; Whisper itself keeps only the last 223 prompt tokens, so anything
+beyond the budget would silently cut off the highest-priority terms.
+Tokens are counted with Whisper's multilingual tokenizer when it is
+importable and estimated from the UTF-8 length otherwise.
+
+:func:`build_initial_prompt` returns the prompt together with its
+provenance, which the STT step stores in the segments file (``prompt``) and
+the ``stt_completed`` event so a transcript shows whether and how it was
+biased::
+
+    {"applied": true, "text": "...", "sources": {"attendees": 3, "glossary": 12},
+     "tokens": 58, "tokens_estimated": false, "dropped_terms": 0,
+     "scope": "all_windows"}
+
+``scope`` is filled in by the backend: ``all_windows`` when local Whisper
+repeats the prompt for every 30 s decoding window (``carry_initial_prompt``,
+openai-whisper 20240930+, ``STT_PROMPT_CARRY``), ``first_window`` when the
+installed Whisper only conditions the first window, ``request``/``chunk``
+for remote backends that receive it once per request or upload chunk.
+"""
+
+from __future__ import annotations
+
+import logging
+import os
+from functools import lru_cache
+from pathlib import Path
+from typing import Any, Dict, Iterable, List, Optional, Tuple
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value, get_db_base_path
+    from .people_directory import people_prompt_terms
+    from .vocabulary_manager import VocabularyManager
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value, get_db_base_path  # type: ignore
+    from people_directory import people_prompt_terms  # type: ignore
+    from vocabulary_manager import VocabularyManager  # type: ignore
+
+STT_PROMPT_ENABLED = get_config_value("STT_PROMPT_ENABLED", True, bool)
+# Whisper는 프롬프트의 마지막 223토큰만 사용하므로 그보다 작게 유지
+STT_PROMPT_MAX_TOKENS = max(16, min(get_config_value("STT_PROMPT_MAX_TOKENS", 200, int), 223))
+STT_PROMPT_GLOSSARY_TERMS = max(0, get_config_value("STT_PROMPT_GLOSSARY_TERMS", 20, int))
+# 회사명·제품명·전문 용어를 한 줄에 하나씩 적은 파일 (비우면 DB/stt_vocabulary.txt)
+STT_VOCABULARY_FILE = get_config_value("STT_VOCABULARY_FILE", "", str)
+
+PROMPT_SOURCES = ("user", "attendees", "tags", "vocabulary", "people", "glossary")
+VOCABULARY_HEADER = "# Whisper 초기 프롬프트에 넣을 용어 (한 줄에 하나, #으로 시작하면 주석)\n"
+MAX_VOCABULARY_TERM_CHARS = 100
+SEPARATOR = ", "
+
+
+@lru_cache(maxsize=1)
+def _tokenizer():
+    try:
+        from whisper.tokenizer import get_tokenizer
+        return get_tokenizer(multilingual=True)
+    except Exception:  # whisper 미설치 또는 tiktoken 자원 없음
+        return None
+
+
+def count_prompt_tokens(text: str) -> Tuple[int, bool]:
+    """``(token count, estimated)`` of ``text`` for Whisper's multilingual tokenizer."""
+    tokenizer = _tokenizer()
+    if tokenizer is not None:
+        return len(tokenizer.encode(" " + text)), False
+    # 한글은 대부분 글자당 2~3토큰, 영문은 단어당 1~2토큰
+    return (len(text.encode("utf-8")) + 1) // 2, True
+
+
+def glossary_terms(limit: int = None) -> List[str]:
+    """Top glossary keywords by weight (empty when the glossary is unreadable)."""
+    limit = STT_PROMPT_GLOSSARY_TERMS if limit is None else limit
+    if limit <= 0:
+        return []
+    try:
+        manager = VocabularyManager(vocab_path=str(get_db_base_path() / "vocab.json"))
+        keywords = manager.get_top_keywords(limit=limit, max_length=10_000)
+    except Exception as e:
+        logging.warning("용어집을 읽지 못해 프롬프트에서 제외합니다: %s", e)
+        return []
+    return [term for term in keywords.split(SEPARATOR) if term]
+
+
+def vocabulary_path() -> Path:
+    return Path(STT_VOCABULARY_FILE).expanduser() if STT_VOCABULARY_FILE else get_db_base_path() / "stt_vocabulary.txt"
+
+
+def custom_vocabulary() -> List[str]:
+    """Terms of the custom vocabulary file in file order (empty when there is none)."""
+    try:
+        with open(vocabulary_path(), "r", encoding="utf-8") as f:
+            lines = f.read().splitlines()
+    except FileNotFoundError:
+        return []
+    except OSError as e:
+        logging.warning("사용자 용어 파일을 읽지 못해 프롬프트에서 제외합니다: %s", e)
+        return []
+    terms: List[str] = []
+    seen = set()
+    for line in lines:
+        term = line.split("#", 1)[0].strip()
+        if term and term.casefold() not in seen:
+            seen.add(term.casefold())
+            terms.append(term)
+    return terms
+
+
+def update_custom_vocabulary(add: Iterable[str] = (), remove: Iterable[str] = (),
+                             replace: Optional[Iterable[str]] = None) -> List[str]:
+    """Add/remove terms (or ``replace`` the whole list) and rewrite the file; returns the new terms.
+
+    Comparison ignores case. Raises :class:`ValueError` for empty or
+    over-long terms and terms containing ``#`` or line breaks.
+    """
+    def clean(values: Iterable[str]) -> List[str]:
+        terms = []
+        for value in values:
+            term = str(value).strip()
+            if not term or len(term) > MAX_VOCABULARY_TERM_CHARS or "#" in term or "\n" in term:
+                raise ValueError(f"잘못된 용어입니다 (1~{MAX_VOCABULARY_TERM_CHARS}자, # 및 줄바꿈 불가): {value!r}")
+            terms.append(term)
+        return terms
+
+    terms = clean(replace) if replace is not None else custom_vocabulary()
+    removed = {term.casefold() for term in clean(remove)}
+    terms = [term for term in terms if term.casefold() not in removed]
+    seen = {term.casefold() for term in terms}
+    for term in clean(add):
+        if term.casefold() not in seen:
+            seen.add(term.casefold())
+            terms.append(term)
+    path = vocabulary_path()
+    path.parent.mkdir(parents=True, exist_ok=True)
+    tmp_path = path.with_name(f"{path.name}.tmp")
+    with open(tmp_path, "w", encoding="utf-8") as f:
+        f.write(VOCABULARY_HEADER + "".join(f"{term}\n" for term in terms))
+    os.replace(tmp_path, path)
+    return custom_vocabulary()
+
+
+def record_prompt_terms(record: Optional[Dict[str, Any]], attendees: Iterable[str] = ()) -> Dict[str, List[str]]:
+    """Attendee names and tags of ``record`` (plus request-supplied ``attendees``)."""
+    record = record or {}
+    names = list(attendees or []) + list(record.get("attendees") or [])
+    names += list((record.get("speaker_names") or {}).values())
+    return {"attendees": names, "tags": list(record.get("tags") or [])}
+
+
+def build_initial_prompt(user_prompt: str = "", terms: Optional[Dict[str, Iterable[str]]] = None,
+                         max_tokens: int = None) -> Tuple[str, Dict[str, Any]]:
+    """Prompt text within the token budget and its provenance.
+
+    ``terms`` maps a source in :data:`PROMPT_SOURCES` to its terms; the user
+    prompt is kept whole (and wins the budget), other terms are added one by
+    one in source order until the budget is used up.
+    """
+    max_tokens = STT_PROMPT_MAX_TOKENS if max_tokens is None else max_tokens
+    terms = terms or {}
+    parts: List[str] = []
+    sources: Dict[str, int] = {}
+    seen = set()
+    dropped = 0
+
+    user_prompt = (user_prompt or "").strip()
+    if user_prompt:
+        parts.append(user_prompt)
+        sources["user"] = 1
+        seen.update(term.strip().casefold() for term in user_prompt.split(","))
+
+    for source in PROMPT_SOURCES[1:]:
+        for term in terms.get(source) or []:
+            term = str(term).strip()
+            if not term or term.casefold() in seen:
+                continue
+            seen.add(term.casefold())
+            if count_prompt_tokens(SEPARATOR.join(parts + [term]))[0] > max_tokens:
+                dropped += 1
+                continue
+            parts.append(term)
+            sources[source] = sources.get(source, 0) + 1
+
+    prompt = SEPARATOR.join(parts)
+    tokens, estimated = count_prompt_tokens(prompt) if prompt else (0, False)
+    provenance = {
+        "applied": bool(prompt),
+        "text": prompt,
+        "sources": sources,
+        "chars": len(prompt),
+        "tokens": tokens,
+        "tokens_estimated": estimated,
+        "dropped_terms": dropped,
+    }
+    if tokens > max_tokens:
+        # 사용자 프롬프트만으로 예산을 넘으면 Whisper가 앞부분을 잘라냄
+        provenance["truncated"] = True
+    return prompt, provenance
+
+
+def prompt_for_record(user_prompt: str = "", record: Optional[Dict[str, Any]] = None,
+                      attendees: Iterable[str] = ()) -> Tuple[str, Dict[str, Any]]:
+    """Initial prompt for transcribing ``record`` (only the user prompt when ``STT_PROMPT_ENABLED`` is off)."""
+    if not STT_PROMPT_ENABLED:
+        return build_initial_prompt(user_prompt)
+    terms = record_prompt_terms(record, attendees)
+    terms["vocabulary"] = custom_vocabulary()
+    terms["people"] = people_prompt_terms(terms["attendees"])
+    terms["glossary"] = glossary_terms()
+    return build_initial_prompt(user_prompt, terms)
@@ -0,0 +1,72 @@
+"""Subtitle and plain transcript rendering from transcript segments.
+
+Cue times keep the millisecond precision of the stored segments
+(``HH:MM:SS,mmm`` for SRT, ``HH:MM:SS.mmm`` for WebVTT).
+"""
+
+from __future__ import annotations
+
+from typing import Any, Dict, List, Optional
+
+SUBTITLE_FORMATS = ("srt", "vtt")
+
+
+def format_timestamp(seconds: float, separator: str = ",") -> str:
+    """Format seconds as ``HH:MM:SS,mmm`` (SRT) or with ``.`` (WebVTT)."""
+    milliseconds = max(0, int(round(float(seconds) * 1000)))
+    hours, milliseconds = divmod(milliseconds, 3_600_000)
+    minutes, milliseconds = divmod(milliseconds, 60_000)
+    secs, milliseconds = divmod(milliseconds, 1000)
+    return f"{hours:02d}:{minutes:02d}:{secs:02d}{separator}{milliseconds:03d}"
+
+
+def _cue_text(segment: Dict[str, Any], speaker_names: Dict[str, str]) -> str:
+    text = " ".join(str(segment.get("text", "")).split())
+    label = segment.get("speaker")
+    if label and text:
+        return f"{speaker_names.get(label, label)}: {text}"
+    return text
+
+
+def _cues(segments: List[Dict[str, Any]], speaker_names: Optional[Dict[str, str]]):
+    """``(start, end, text)`` per non-empty segment; a cue lasts at least 1 ms."""
+    speaker_names = speaker_names or {}
+    for segment in segments:
+        text = _cue_text(segment, speaker_names)
+        if not text:
+            continue
+        start = float(segment.get("start", 0.0))
+        end = max(float(segment.get("end", start)), start + 0.001)
+        yield start, end, text
+
+
+def segments_to_srt(segments: List[Dict[str, Any]], speaker_names: Optional[Dict[str, str]] = None) -> str:
+    """Render segments as SRT cues, prefixing speaker names when labelled."""
+    cues = []
+    for start, end, text in _cues(segments, speaker_names):
+        cues.append(
+            f"{len(cues) + 1}\n{format_timestamp(start)} --> {format_timestamp(end)}\n{text}\n"
+        )
+    return "\n".join(cues)
+
+
+def segments_to_vtt(segments: List[Dict[str, Any]], speaker_names: Optional[Dict[str, str]] = None) -> str:
+    """Render segments as a WebVTT file, prefixing speaker names when labelled."""
+    cues = [
+        f"{format_timestamp(start, '.')} --> {format_timestamp(end, '.')}\n{text}\n"
+        for start, end, text in _cues(segments, speaker_names)
+    ]
+    return "\n".join(["WEBVTT\n", *cues])
+
+
+def segments_to_text(segments: List[Dict[str, Any]], speaker_names: Optional[Dict[str, str]] = None) -> str:
+    """One line per segment without timestamps (speaker names prefixed when labelled)."""
+    return "".join(f"{text}\n" for _, _, text in _cues(segments, speaker_names))
+
+
+def render_subtitles(segments: List[Dict[str, Any]], subtitle_format: str,
+                     speaker_names: Optional[Dict[str, str]] = None) -> str:
+    if subtitle_format not in SUBTITLE_FORMATS:
+        raise ValueError(f"지원하지 않는 자막 형식입니다: {subtitle_format}")
+    render = segments_to_srt if subtitle_format == "srt" else segments_to_vtt
+    return render(segments, speaker_names)
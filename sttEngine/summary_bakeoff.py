@@ -0,0 +1,188 @@
+"""Summary "bake-off": one transcript summarized by several models side by side.
+
+Choosing between gemma, llama and qwen is easiest on one's own meetings.
+``POST /record/{id}/summary/bakeoff`` summarizes the record's transcript
+with 2–``MAX_MODELS`` models in parallel (``SUMMARY_BAKEOFF_MODELS`` when
+the request names none) and keeps every output next to the transcript::
+
+    summary_bakeoff/{bakeoff_id}/
+        manifest.json            # 모델별 상태, 소요 시간, 프롬프트 버전 등 출처 정보
+        gemma3_4b.summary.md
+        llama3.2.summary.md
+
+The record's own summary is not touched. :func:`compare` turns a manifest
+into the comparison returned by ``GET /record/{id}/summary/bakeoff/{bakeoff_id}``:
+per-model length, duration and items per summary section, plus which
+sections each model left empty.
+"""
+
+from __future__ import annotations
+
+import copy
+import json
+import re
+import threading
+import time
+import uuid
+from concurrent.futures import ThreadPoolExecutor
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Callable, Dict, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+SUMMARY_BAKEOFF_MODELS = get_config_value("SUMMARY_BAKEOFF_MODELS", "", str)
+
+BAKEOFF_DIRNAME = "summary_bakeoff"
+MANIFEST_NAME = "manifest.json"
+MIN_MODELS = 2
+MAX_MODELS = 3
+
+_manifest_lock = threading.Lock()
+
+
+class BakeoffError(ValueError):
+    """Raised for invalid model lists and unknown bake-offs."""
+
+
+def resolve_models(models: Any) -> List[str]:
+    """Validated, de-duplicated model list (falls back to ``SUMMARY_BAKEOFF_MODELS``)."""
+    if models is None:
+        models = [model.strip() for model in SUMMARY_BAKEOFF_MODELS.split(",") if model.strip()]
+    if not isinstance(models, list) or not all(isinstance(model, str) and model.strip() for model in models):
+        raise BakeoffError("models는 모델 이름 문자열 배열이어야 합니다.")
+    models = list(dict.fromkeys(model.strip() for model in models))
+    if not MIN_MODELS <= len(models) <= MAX_MODELS:
+        raise BakeoffError(
+            f"비교할 모델을 {MIN_MODELS}~{MAX_MODELS}개 지정하세요 (요청의 models 또는 SUMMARY_BAKEOFF_MODELS)."
+        )
+    return models
+
+
+def bakeoff_root(output_dir: Path) -> Path:
+    return output_dir / BAKEOFF_DIRNAME
+
+
+def _model_filename(model: str) -> str:
+    return re.sub(r"[^\w.-]+", "_", model) + ".summary.md"
+
+
+def _write_manifest(directory: Path, manifest: Dict[str, Any]) -> None:
+    tmp_path = directory / f"{MANIFEST_NAME}.tmp"
+    with open(tmp_path, "w", encoding="utf-8") as f:
+        json.dump(manifest, f, ensure_ascii=False, indent=2)
+    tmp_path.replace(directory / MANIFEST_NAME)
+
+
+def load_manifest(output_dir: Path, bakeoff_id: str) -> Optional[Dict[str, Any]]:
+    if not re.fullmatch(r"[0-9a-f]{32}", bakeoff_id):
+        return None
+    manifest_path = bakeoff_root(output_dir) / bakeoff_id / MANIFEST_NAME
+    if not manifest_path.exists():
+        return None
+    with open(manifest_path, "r", encoding="utf-8") as f:
+        return json.load(f)
+
+
+def list_bakeoffs(output_dir: Path) -> List[Dict[str, Any]]:
+    """Saved bake-offs of a record, newest first (without summary texts)."""
+    root = bakeoff_root(output_dir)
+    if not root.exists():
+        return []
+    bakeoffs = []
+    for directory in root.iterdir():
+        manifest = load_manifest(output_dir, directory.name)
+        if manifest:
+            bakeoffs.append({
+                key: manifest.get(key) for key in ("id", "status", "models", "started_at", "finished_at")
+            })
+    return sorted(bakeoffs, key=lambda item: item.get("started_at") or "", reverse=True)
+
+
+def start_bakeoff(output_dir: Path, text: str, models: List[str],
+                  summarize: Callable[[str, str], str], provenance: Dict[str, Any]) -> Dict[str, Any]:
+    """Summarize ``text`` with every model in a background thread; returns the initial manifest.
+
+    ``summarize(text, model)`` produces one summary; ``provenance`` (record
+    id, source file, prompt version, chunk size...) is stored in the manifest.
+    """
+    bakeoff_id = uuid.uuid4().hex
+    directory = bakeoff_root(output_dir) / bakeoff_id
+    directory.mkdir(parents=True)
+    manifest = {
+        "id": bakeoff_id,
+        **provenance,
+        "status": "running",
+        "models": models,
+        "source_chars": len(text),
+        "started_at": datetime.now().isoformat(),
+        "finished_at": None,
+        "results": {
+            model: {"model": model, "status": "running", "file": _model_filename(model), "chars": None,
+                    "duration_seconds": None, "error": None}
+            for model in models
+        },
+    }
+    _write_manifest(directory, manifest)
+
+    def run_model(model: str) -> None:
+        started = time.monotonic()
+        entry: Dict[str, Any] = {}
+        try:
+            summary = summarize(text, model)
+            (directory / _model_filename(model)).write_text(summary, encoding="utf-8")
+            entry.update(status="completed", chars=len(summary))
+        except Exception as exc:
+            print(f"요약 비교 실패 ({model}): {exc}")
+            entry.update(status="failed", error=str(exc))
+        entry.update(duration_seconds=round(time.monotonic() - started, 1), finished_at=datetime.now().isoformat())
+        with _manifest_lock:
+            manifest["results"][model].update(entry)
+            _write_manifest(directory, manifest)
+
+    def run() -> None:
+        with ThreadPoolExecutor(max_workers=len(models), thread_name_prefix="bakeoff") as pool:
+            list(pool.map(run_model, models))
+        with _manifest_lock:
+            statuses = {result["status"] for result in manifest["results"].values()}
+            manifest["status"] = "failed" if statuses == {"failed"} else "completed"
+            manifest["finished_at"] = datetime.now().isoformat()
+            _write_manifest(directory, manifest)
+        print(f"요약 비교 {bakeoff_id} 완료: {', '.join(models)}")
+
+    snapshot = copy.deepcopy(manifest)
+    threading.Thread(target=run, daemon=True).start()
+    return snapshot
+
+
+def compare(output_dir: Path, manifest: Dict[str, Any],
+            parse_sections: Callable[[str], Dict[str, List[str]]]) -> Dict[str, Any]:
+    """Manifest plus each model's summary text and per-section item counts."""
+    directory = bakeoff_root(output_dir) / manifest["id"]
+    results = []
+    for model in manifest["models"]:
+        result = dict(manifest["results"][model])
+        summary_path = directory / result["file"]
+        result["summary"] = summary_path.read_text(encoding="utf-8") if summary_path.exists() else None
+        if result["summary"] is not None:
+            sections = parse_sections(result["summary"])
+            result["section_items"] = {key: len(items) for key, items in sections.items()}
+            result["empty_sections"] = [key for key, items in sections.items() if not items]
+        results.append(result)
+
+    completed = [result for result in results if result["status"] == "completed"]
+    comparison = {}
+    if completed:
+        comparison = {
+            "fastest": min(completed, key=lambda result: result["duration_seconds"])["model"],
+            "shortest": min(completed, key=lambda result: result["chars"])["model"],
+            "longest": max(completed, key=lambda result: result["chars"])["model"],
+            "most_sections": max(
+                completed, key=lambda result: len(result["section_items"]) - len(result["empty_sections"])
+            )["model"],
+        }
+    return {**{key: value for key, value in manifest.items() if key != "results"},
+            "results": results, "comparison": comparison}
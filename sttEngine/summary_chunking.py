@@ -0,0 +1,186 @@
+"""Chunk sizes for the map-reduce summary, planned from the model's context.
+
+The summary step used to cut transcripts by a fixed byte count
+(``DEFAULT_CHUNK_SIZE``) and call Ollama with a fixed ``num_ctx``, so a
+long Korean transcript could silently overflow the context while a short
+English one was split for nothing. With ``SUMMARY_CHUNKING=adaptive``
+(default) :func:`plan_chunks` decides per run:
+
+1. the context available: the model's ``context_length`` (``ollama show``),
+   capped by ``SUMMARY_NUM_CTX_MAX`` because a larger ``num_ctx`` costs
+   memory — or the ``num_ctx`` given in the model options;
+2. the input budget of one call: that context minus the prompt template
+   (plus people/series context), ``SUMMARY_OUTPUT_RESERVE_TOKENS`` for the
+   answer and a 10% margin for the token estimate;
+3. the tokens of the transcript, estimated from its density — Hangul, kana
+   and CJK characters count about a token each, other text about 3.5
+   characters per token;
+4. the fewest chunks that fit the budget with ``SUMMARY_CHUNK_OVERLAP_TOKENS``
+   of overlap, sized evenly (``SUMMARY_CHUNK_TOKENS`` caps a chunk), and
+   how many chunk summaries one reduce call can take.
+
+A transcript that fits is summarized in one call with ``num_ctx`` raised
+just enough to hold it. The plan (:meth:`ChunkPlan.to_dict`) is recorded in
+the ``summary_generated`` event and the summary debug manifest.
+``SUMMARY_CHUNKING=fixed`` keeps the old byte-based behaviour.
+"""
+
+from __future__ import annotations
+
+import math
+import re
+from dataclasses import asdict, dataclass
+from typing import Any, Dict, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+CHUNKING_MODES = ("adaptive", "fixed")
+SUMMARY_CHUNKING = get_config_value("SUMMARY_CHUNKING", "adaptive", str).strip().lower()
+# 0이면 예산에 맞춰 자동
+SUMMARY_CHUNK_TOKENS = max(0, get_config_value("SUMMARY_CHUNK_TOKENS", 0, int))
+SUMMARY_CHUNK_OVERLAP_TOKENS = max(0, get_config_value("SUMMARY_CHUNK_OVERLAP_TOKENS", 200, int))
+SUMMARY_NUM_CTX_MAX = max(2048, get_config_value("SUMMARY_NUM_CTX_MAX", 32768, int))
+SUMMARY_OUTPUT_RESERVE_TOKENS = max(256, get_config_value("SUMMARY_OUTPUT_RESERVE_TOKENS", 1024, int))
+
+# 토큰 수 추정값의 오차를 감안한 여유
+SAFETY_MARGIN = 0.1
+WIDE_CHAR_TOKENS = 1.0
+CHARS_PER_TOKEN = 3.5
+# 한 번의 호출에 넣을 최소 입력 토큰 (이보다 작으면 컨텍스트 설정이 잘못된 것)
+MIN_INPUT_TOKENS = 512
+NUM_CTX_STEP = 1024
+
+_WIDE_CHARS = re.compile(r"[\u1100-\u11ff\u3040-\u30ff\u3130-\u318f\u3400-\u4dbf\u4e00-\u9fff\uac00-\ud7af\uf900-\ufaff]")
+
+
+def estimate_tokens(text: str) -> int:
+    """Rough token count of ``text`` for tokenizers of the usual Ollama models."""
+    if not text:
+        return 0
+    wide = len(_WIDE_CHARS.findall(text))
+    return math.ceil(wide * WIDE_CHAR_TOKENS + (len(text) - wide) / CHARS_PER_TOKEN)
+
+
+@dataclass
+class ChunkPlan:
+    """How one summary run splits its input and which ``num_ctx`` it calls the model with."""
+
+    mode: str
+    model_context: Optional[int]
+    num_ctx: int
+    input_tokens: int
+    prompt_tokens: int
+    output_tokens: int
+    budget_tokens: int
+    chunk_tokens: int
+    overlap_tokens: int
+    chunks: int
+    reduce_batch: int
+    estimated_calls: int
+
+    def to_dict(self) -> Dict[str, Any]:
+        return asdict(self)
+
+
+def _reduce_calls(chunks: int, reduce_batch: int) -> int:
+    if chunks <= 1:
+        return 0
+    calls = 1
+    while chunks > reduce_batch:
+        chunks = math.ceil(chunks / reduce_batch)
+        calls += chunks
+    return calls
+
+
+def plan_chunks(input_tokens: int, prompt_tokens: int, model_context: Optional[int], default_num_ctx: int,
+                num_ctx: Optional[int] = None, output_tokens: Optional[int] = None) -> ChunkPlan:
+    """Fewest evenly sized chunks of ``input_tokens`` that fit one call each.
+
+    ``num_ctx`` fixes the context (model options); otherwise it is chosen
+    between ``default_num_ctx`` and the model's context capped by
+    ``SUMMARY_NUM_CTX_MAX``.
+    """
+    output_tokens = output_tokens or SUMMARY_OUTPUT_RESERVE_TOKENS
+    ceiling = num_ctx or min(model_context or default_num_ctx, SUMMARY_NUM_CTX_MAX)
+
+    def budget_for(context: int) -> int:
+        return int(context * (1 - SAFETY_MARGIN)) - prompt_tokens - output_tokens
+
+    chunk_limit = SUMMARY_CHUNK_TOKENS or None
+    fits = input_tokens <= budget_for(ceiling) and (chunk_limit is None or input_tokens <= chunk_limit)
+    if fits and num_ctx is None:
+        # 한 번에 들어가면 필요한 만큼만 num_ctx를 키움
+        needed = math.ceil((input_tokens + prompt_tokens + output_tokens) / (1 - SAFETY_MARGIN))
+        context = min(ceiling, max(default_num_ctx, math.ceil(needed / NUM_CTX_STEP) * NUM_CTX_STEP))
+    else:
+        context = ceiling
+    budget = budget_for(context)
+    if budget < MIN_INPUT_TOKENS:
+        raise ValueError(
+            f"num_ctx {context}에서 프롬프트({prompt_tokens})와 출력 예약({output_tokens})을 빼면 "
+            f"입력에 쓸 토큰이 {budget}개뿐입니다. num_ctx 또는 SUMMARY_NUM_CTX_MAX를 늘리세요."
+        )
+    chunk_budget = min(budget, chunk_limit) if chunk_limit else budget
+    if fits:
+        chunks, chunk_tokens, overlap = 1, input_tokens, 0
+    else:
+        overlap = min(SUMMARY_CHUNK_OVERLAP_TOKENS, chunk_budget // 4)
+        chunks = max(2, math.ceil((input_tokens - overlap) / (chunk_budget - overlap)))
+        chunk_tokens = min(chunk_budget, math.ceil((input_tokens + (chunks - 1) * overlap) / chunks))
+    reduce_batch = max(2, budget // output_tokens)
+    return ChunkPlan(
+        mode="adaptive",
+        model_context=model_context,
+        num_ctx=context,
+        input_tokens=input_tokens,
+        prompt_tokens=prompt_tokens,
+        output_tokens=output_tokens,
+        budget_tokens=budget,
+        chunk_tokens=chunk_tokens,
+        overlap_tokens=overlap,
+        chunks=chunks,
+        reduce_batch=reduce_batch,
+        estimated_calls=chunks + _reduce_calls(chunks, reduce_batch),
+    )
+
+
+def _split_long_line(line: str, max_tokens: int) -> List[str]:
+    """Cut a line longer than ``max_tokens`` into pieces at the line's own density."""
+    tokens = estimate_tokens(line)
+    pieces = math.ceil(tokens / max_tokens)
+    size = math.ceil(len(line) / pieces)
+    return [line[i:i + size] for i in range(0, len(line), size)]
+
+
+def split_chunks(text: str, chunk_tokens: int, overlap_tokens: int = 0) -> List[str]:
+    """Line-aligned chunks of about ``chunk_tokens``; each repeats the last ``overlap_tokens`` of the previous one."""
+    lines: List[str] = []
+    for line in text.splitlines():
+        lines.extend(_split_long_line(line, chunk_tokens) if estimate_tokens(line) > chunk_tokens else [line])
+    chunks: List[str] = []
+    current: List[str] = []
+    size = 0
+    fresh = False
+    for line in lines:
+        line_tokens = estimate_tokens(line) + 1
+        if fresh and size + line_tokens > chunk_tokens:
+            chunks.append("\n".join(current))
+            # 다음 청크는 앞 청크의 끝 몇 줄을 겹쳐서 시작
+            carried: List[str] = []
+            carried_size = 0
+            for previous in reversed(current):
+                previous_tokens = estimate_tokens(previous) + 1
+                if carried_size + previous_tokens > overlap_tokens:
+                    break
+                carried.insert(0, previous)
+                carried_size += previous_tokens
+            current, size, fresh = carried, carried_size, False
+        current.append(line)
+        size += line_tokens
+        fresh = fresh or bool(line.strip())
+    if fresh:
+        chunks.append("\n".join(current))
+    return [chunk.strip() for chunk in chunks if chunk.strip()]
@@ -0,0 +1,196 @@
+"""Optional capture of map-reduce summary prompts and model responses.
+
+When ``SUMMARY_DEBUG_ENABLED`` is set, every summary run writes the chunk
+summaries, reduce intermediates and the exact prompts sent to Ollama to
+``{output folder}/summary_debug/{task id}/``::
+
+    summary_debug/
+        3f2c.../
+            manifest.json      # 실행 정보 + 단계 목록 (프롬프트/응답 포함)
+            01_chunk_1.prompt.txt
+            01_chunk_1.output.txt
+            ...
+            05_final_reduce.output.txt
+
+Captured text is redacted (e-mail addresses, phone, resident registration
+and card numbers, ``SUMMARY_DEBUG_REDACT``) and cut to
+``SUMMARY_DEBUG_MAX_CHARS`` per prompt/response before it is stored, and
+only the last ``SUMMARY_DEBUG_MAX_RUNS`` runs of a record are kept.
+``GET /record/{id}/summary_debug`` serves the latest manifest (or
+``?task_id=``'s) and ``GET /tasks/{id}/log`` the one of that task.
+Folders written before per-task capture (manifest directly under
+``summary_debug/``) are still served as the latest run.
+"""
+
+from __future__ import annotations
+
+import json
+import re
+import shutil
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+SUMMARY_DEBUG_ENABLED = get_config_value("SUMMARY_DEBUG_ENABLED", False, bool)
+SUMMARY_DEBUG_REDACT = get_config_value("SUMMARY_DEBUG_REDACT", True, bool)
+SUMMARY_DEBUG_MAX_CHARS = max(1000, get_config_value("SUMMARY_DEBUG_MAX_CHARS", 20000, int))
+SUMMARY_DEBUG_MAX_RUNS = max(1, get_config_value("SUMMARY_DEBUG_MAX_RUNS", 5, int))
+SUMMARY_DEBUG_DIRNAME = "summary_debug"
+MANIFEST_NAME = "manifest.json"
+
+# (치환 문자열, 패턴) — 카드 번호를 전화번호보다 먼저 검사
+REDACTION_PATTERNS = (
+    ("[EMAIL]", re.compile(r"[\w.+-]+@[\w-]+(?:\.[\w-]+)+")),
+    ("[RRN]", re.compile(r"(?<!\d)\d{6}[- ]?[1-4]\d{6}(?!\d)")),
+    ("[CARD]", re.compile(r"(?<!\d)\d{4}(?:[- ]?\d{4}){3}(?!\d)")),
+    ("[PHONE]", re.compile(r"(?<![\d-])(?:\+\d{1,3}[- ]?\d{1,2}|0\d{1,2})[- ]?\d{3,4}[- ]?\d{4}(?![\d-])")),
+)
+
+
+def redact(text: str) -> tuple:
+    """``(text with personal identifiers masked, number of replacements)``."""
+    count = 0
+    for replacement, pattern in REDACTION_PATTERNS:
+        text, replaced = pattern.subn(replacement, text)
+        count += replaced
+    return text, count
+
+
+def _limit(text: str) -> tuple:
+    if len(text) <= SUMMARY_DEBUG_MAX_CHARS:
+        return text, False
+    omitted = len(text) - SUMMARY_DEBUG_MAX_CHARS
+    return text[:SUMMARY_DEBUG_MAX_CHARS] + f"\n… ({omitted}자 생략)", True
+
+
+class SummaryTrace:
+    """Collects the prompt/response pairs of one summarize_text_mapreduce run.
+
+    Text is redacted and size-limited as it is recorded, so nothing beyond
+    what ends up on disk is held in memory.
+    """
+
+    def __init__(self, **metadata: Any):
+        self.metadata = metadata
+        self.started_at = datetime.now().isoformat()
+        self.steps: List[Dict[str, Any]] = []
+        self.redact = SUMMARY_DEBUG_REDACT
+
+    def record(self, stage: str, prompt: str, output: str, **details: Any) -> None:
+        step: Dict[str, Any] = {"stage": stage, **details}
+        redactions = 0
+        for key, text in (("prompt", prompt), ("output", output)):
+            step[f"{key}_chars"] = len(text)
+            if self.redact:
+                text, replaced = redact(text)
+                redactions += replaced
+            step[key], truncated = _limit(text)
+            if truncated:
+                step[f"{key}_truncated"] = True
+        if self.redact:
+            step["redactions"] = redactions
+        step["recorded_at"] = datetime.now().isoformat()
+        self.steps.append(step)
+
+
+def summary_debug_dir(output_dir: Path) -> Path:
+    return output_dir / SUMMARY_DEBUG_DIRNAME
+
+
+def _step_basename(position: int, step: Dict[str, Any]) -> str:
+    name = f"{position:02d}_{step['stage']}"
+    if step.get("index") is not None:
+        name += f"_{step['index']}"
+    return name
+
+
+def _run_dirs(debug_dir: Path) -> List[Path]:
+    """Per-task run folders of a record, oldest first."""
+    if not debug_dir.is_dir():
+        return []
+    runs = [path for path in debug_dir.iterdir() if (path / MANIFEST_NAME).is_file()]
+    return sorted(runs, key=lambda path: (path / MANIFEST_NAME).stat().st_mtime)
+
+
+def write_summary_debug(output_dir: Path, trace: SummaryTrace) -> Path:
+    """Write the steps of ``trace`` to ``output_dir/summary_debug/{task id}`` and prune old runs."""
+    debug_dir = summary_debug_dir(output_dir)
+    run_name = str(trace.metadata.get("task_id") or datetime.now().strftime("%Y%m%d%H%M%S"))
+    target = debug_dir / run_name
+    tmp_dir = debug_dir / f"{run_name}.tmp"
+    if tmp_dir.exists():
+        shutil.rmtree(tmp_dir)
+    tmp_dir.mkdir(parents=True)
+
+    steps = []
+    for position, step in enumerate(trace.steps, 1):
+        basename = _step_basename(position, step)
+        (tmp_dir / f"{basename}.prompt.txt").write_text(step["prompt"], encoding="utf-8")
+        (tmp_dir / f"{basename}.output.txt").write_text(step["output"], encoding="utf-8")
+        steps.append({**step, "files": {
+            "prompt": f"{basename}.prompt.txt",
+            "output": f"{basename}.output.txt",
+        }})
+
+    manifest = {
+        **trace.metadata,
+        "started_at": trace.started_at,
+        "finished_at": datetime.now().isoformat(),
+        "redacted": trace.redact,
+        "max_chars": SUMMARY_DEBUG_MAX_CHARS,
+        "step_count": len(steps),
+        "steps": steps,
+    }
+    with open(tmp_dir / MANIFEST_NAME, "w", encoding="utf-8") as f:
+        json.dump(manifest, f, ensure_ascii=False, indent=2)
+
+    if target.exists():
+        shutil.rmtree(target)
+    tmp_dir.replace(target)
+
+    # 단계별 캡처 이전 형식(최상위 manifest)은 새 실행이 생기면 정리
+    legacy_manifest = debug_dir / MANIFEST_NAME
+    if legacy_manifest.exists():
+        for path in debug_dir.iterdir():
+            if path.is_file():
+                path.unlink()
+    for old_run in _run_dirs(debug_dir)[:-SUMMARY_DEBUG_MAX_RUNS]:
+        shutil.rmtree(old_run, ignore_errors=True)
+    return target
+
+
+def _read_manifest(path: Path) -> Dict[str, Any]:
+    with open(path, "r", encoding="utf-8") as f:
+        return json.load(f)
+
+
+def list_summary_debug_runs(output_dir: Path) -> List[Dict[str, Any]]:
+    """Retained runs of a record (``task_id``, times, step count), newest first."""
+    runs = []
+    for run_dir in reversed(_run_dirs(summary_debug_dir(output_dir))):
+        manifest = _read_manifest(run_dir / MANIFEST_NAME)
+        runs.append({
+            "task_id": manifest.get("task_id") or run_dir.name,
+            "started_at": manifest.get("started_at"),
+            "finished_at": manifest.get("finished_at"),
+            "step_count": manifest.get("step_count"),
+        })
+    return runs
+
+
+def load_summary_debug(output_dir: Path, task_id: Optional[str] = None) -> Optional[Dict[str, Any]]:
+    """Return the manifest of ``task_id``'s run (latest run when omitted) or ``None`` if absent."""
+    debug_dir = summary_debug_dir(output_dir)
+    if task_id:
+        manifest_path = debug_dir / Path(task_id).name / MANIFEST_NAME
+        return _read_manifest(manifest_path) if manifest_path.is_file() else None
+    runs = _run_dirs(debug_dir)
+    if runs:
+        return _read_manifest(runs[-1] / MANIFEST_NAME)
+    legacy_manifest = debug_dir / MANIFEST_NAME
+    return _read_manifest(legacy_manifest) if legacy_manifest.is_file() else None
@@ -0,0 +1,195 @@
+"""Re-generate summaries produced with older prompt versions.
+
+Every summary stores the prompt version (a hash of the chunk/reduce prompts,
+see ``workflow.summarize.get_prompt_version``) on its history record as
+``summary_prompt_version``. After prompts change via ``/admin/reload``,
+records whose version differs — or that predate version tracking, or whose
+transcript was partly re-transcribed (``stale_artifacts``) — are
+"stale" and can be re-summarized in batches by a single background job,
+either on request (``POST /summaries/regenerate``) or by the scheduler
+(``SUMMARY_REGEN_INTERVAL_HOURS``).
+"""
+
+from __future__ import annotations
+
+import threading
+import time
+import uuid
+from datetime import datetime
+from typing import Any, Callable, Dict, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+SUMMARY_REGEN_BATCH_SIZE = max(1, get_config_value("SUMMARY_REGEN_BATCH_SIZE", 5, int))
+# 배치 사이 대기 시간 (다른 작업이 Ollama를 쓸 틈을 줌)
+SUMMARY_REGEN_BATCH_PAUSE_SECONDS = get_config_value("SUMMARY_REGEN_BATCH_PAUSE_SECONDS", 5, float)
+SUMMARY_REGEN_INTERVAL_HOURS = get_config_value("SUMMARY_REGEN_INTERVAL_HOURS", 0, float)
+
+MAX_JOB_ERRORS = 50
+
+
+class RegenerationBusy(RuntimeError):
+    """Raised when a regeneration job is already running."""
+
+
+def find_stale_summaries(history: List[Dict[str, Any]], current_version: str) -> List[Dict[str, Any]]:
+    """Return active records whose summary is outdated (prompt version or transcript)."""
+    stale = []
+    for record in history:
+        if record.get("deleted") or not (record.get("completed_tasks") or {}).get("summary"):
+            continue
+        if (record.get("summary_prompt_version") != current_version
+                or "summary" in (record.get("stale_artifacts") or [])):
+            stale.append(record)
+    return stale
+
+
+class SummaryRegenerationJob:
+    """Background job re-summarizing records batch by batch."""
+
+    def __init__(self, record_ids: List[str], runner: Callable[[str], Dict[str, Any]],
+                 prompt_version: str, batch_size: int = SUMMARY_REGEN_BATCH_SIZE,
+                 trigger: str = "manual"):
+        self.id = str(uuid.uuid4())
+        self.record_ids = list(record_ids)
+        self.runner = runner
+        self.prompt_version = prompt_version
+        self.batch_size = max(1, batch_size)
+        self.trigger = trigger
+        self.status = "pending"
+        self.processed = 0
+        self.succeeded = 0
+        self.failed = 0
+        self.current_record: Optional[str] = None
+        self.errors: List[Dict[str, str]] = []
+        self.started_at: Optional[str] = None
+        self.finished_at: Optional[str] = None
+        self._cancel = threading.Event()
+        self._lock = threading.Lock()
+
+    @property
+    def running(self) -> bool:
+        return self.status in ("pending", "running")
+
+    def cancel(self) -> None:
+        self._cancel.set()
+
+    def snapshot(self) -> Dict[str, Any]:
+        with self._lock:
+            total = len(self.record_ids)
+            batches = (total + self.batch_size - 1) // self.batch_size
+            return {
+                "id": self.id,
+                "status": self.status,
+                "trigger": self.trigger,
+                "prompt_version": self.prompt_version,
+                "total": total,
+                "processed": self.processed,
+                "succeeded": self.succeeded,
+                "failed": self.failed,
+                "batch_size": self.batch_size,
+                "batch": min(batches, self.processed // self.batch_size + 1) if total else 0,
+                "batches": batches,
+                "percent": round(self.processed / total * 100, 1) if total else 100.0,
+                "current_record": self.current_record,
+                "errors": list(self.errors),
+                "started_at": self.started_at,
+                "finished_at": self.finished_at,
+            }
+
+    def run(self) -> None:
+        self.status = "running"
+        self.started_at = datetime.now().isoformat()
+        try:
+            for start in range(0, len(self.record_ids), self.batch_size):
+                if start and SUMMARY_REGEN_BATCH_PAUSE_SECONDS > 0:
+                    self._cancel.wait(SUMMARY_REGEN_BATCH_PAUSE_SECONDS)
+                for record_id in self.record_ids[start:start + self.batch_size]:
+                    if self._cancel.is_set():
+                        self.status = "cancelled"
+                        return
+                    self._run_one(record_id)
+            self.status = "completed"
+        except Exception as exc:  # pragma: no cover - defensive
+            self.status = "failed"
+            self._add_error(self.current_record or "", str(exc))
+        finally:
+            self.current_record = None
+            self.finished_at = datetime.now().isoformat()
+            print(
+                f"요약 재생성 {self.status}: {self.succeeded}개 성공, "
+                f"{self.failed}개 실패 / 전체 {len(self.record_ids)}개"
+            )
+
+    def _run_one(self, record_id: str) -> None:
+        self.current_record = record_id
+        try:
+            result = self.runner(record_id) or {}
+            error = result.get("error")
+        except Exception as exc:
+            error = str(exc)
+        with self._lock:
+            self.processed += 1
+            if error:
+                self.failed += 1
+            else:
+                self.succeeded += 1
+        if error:
+            self._add_error(record_id, error)
+
+    def _add_error(self, record_id: str, message: str) -> None:
+        with self._lock:
+            if len(self.errors) < MAX_JOB_ERRORS:
+                self.errors.append({"record_id": record_id, "error": message})
+
+
+_job_lock = threading.Lock()
+_current_job: Optional[SummaryRegenerationJob] = None
+
+
+def get_current_job() -> Optional[SummaryRegenerationJob]:
+    return _current_job
+
+
+def start_regeneration(record_ids: List[str], runner: Callable[[str], Dict[str, Any]],
+                       prompt_version: str, batch_size: Optional[int] = None,
+                       trigger: str = "manual") -> SummaryRegenerationJob:
+    """Start a background job; raises :class:`RegenerationBusy` if one is running."""
+    global _current_job
+    with _job_lock:
+        if _current_job is not None and _current_job.running:
+            raise RegenerationBusy("요약 재생성 작업이 이미 진행 중입니다.")
+        job = SummaryRegenerationJob(record_ids, runner, prompt_version,
+                                     batch_size or SUMMARY_REGEN_BATCH_SIZE, trigger)
+        _current_job = job
+    threading.Thread(target=job.run, daemon=True).start()
+    return job
+
+
+def start_regeneration_scheduler(find_stale: Callable[[], List[str]],
+                                 runner: Callable[[str], Dict[str, Any]],
+                                 current_version: Callable[[], str],
+                                 interval_hours: float = SUMMARY_REGEN_INTERVAL_HOURS):
+    """Periodically re-summarize stale records in a daemon thread."""
+    if interval_hours <= 0:
+        return None
+
+    def run():
+        while True:
+            time.sleep(interval_hours * 3600)
+            try:
+                record_ids = find_stale()
+                if record_ids:
+                    print(f"이전 프롬프트로 생성된 요약 {len(record_ids)}개 재생성 시작")
+                    start_regeneration(record_ids, runner, current_version(), trigger="scheduler")
+            except RegenerationBusy:
+                pass
+            except Exception as exc:
+                print(f"요약 자동 재생성 실패: {exc}")
+
+    thread = threading.Thread(target=run, daemon=True)
+    thread.start()
+    return thread
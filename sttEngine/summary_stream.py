@@ -0,0 +1,136 @@
+"""Live summary output over Server-Sent Events.
+
+A summary normally appears only when the whole answer is in: the final
+Ollama call (single chunk or final reduce) waits for the complete response.
+``POST /record/{id}/summary/stream`` starts the record's summary step (it
+overwrites the stored summary, so it needs a writing token and is refused in
+maintenance mode) and answers with its ``task_id``.
+``GET /record/{id}/summary/stream?task_id=`` then attaches to that run and
+keeps the connection open as an SSE stream (``text/event-stream``); a GET
+never starts anything. Events sent before the client attaches are kept, one
+client can attach to a run, and a run nobody attaches to within
+``SUMMARY_STREAM_ATTACH_SECONDS`` stops collecting events:
+
+* ``started`` — ``{"task_id", "record_id"}``;
+* ``progress`` — the task's progress messages (chunk summaries, saving, ...);
+* ``token`` — ``{"text"}``, a piece of the final summary as Ollama
+  generates it (the final call runs with ``stream=True``);
+* ``reset`` — the final call is retried; discard the text received so far;
+* ``done`` — ``{"results"}`` once the summary is saved (same links as
+  ``/process``); ``error`` — the error payload when the step failed.
+
+Only the final call is streamed; the chunk summaries before it are reported
+as progress. A structured summary (``summary_format: structured``) is not
+streamed since its JSON is converted before it is shown. The summary is
+saved like any other run, so closing the connection does not stop it. A
+comment line is sent every ``SUMMARY_STREAM_HEARTBEAT_SECONDS`` while
+nothing happens so proxies keep the connection.
+"""
+
+from __future__ import annotations
+
+import json
+import queue
+import threading
+from typing import Any, Callable, Dict, Iterator, Optional, Tuple
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+SUMMARY_STREAM_HEARTBEAT_SECONDS = max(1.0, get_config_value("SUMMARY_STREAM_HEARTBEAT_SECONDS", 15, float))
+SUMMARY_STREAM_ATTACH_SECONDS = max(1.0, get_config_value("SUMMARY_STREAM_ATTACH_SECONDS", 60, float))
+END_EVENTS = ("done", "error")
+HEARTBEAT = b": keep-alive\n\n"
+
+Event = Tuple[str, Optional[Dict[str, Any]]]
+
+_streams_lock = threading.Lock()
+_streams: Dict[str, "SummaryStream"] = {}
+
+
+class SummaryStream:
+    """Events of one streamed summary run, handed from the task thread to the HTTP handler."""
+
+    def __init__(self, task_id: str, record_id: Optional[str] = None):
+        self.task_id = task_id
+        self.record_id = record_id
+        self.attached = False
+        self._events: "queue.Queue[Event]" = queue.Queue()
+
+    def send(self, event: str, data: Optional[Dict[str, Any]] = None) -> None:
+        self._events.put((event, data))
+
+    def token(self, text: Optional[str]) -> None:
+        """``on_token`` callback of the summary call (``None`` means the call is retried)."""
+        if text is None:
+            self.send("reset", {})
+        else:
+            self.send("token", {"text": text})
+
+    def events(self, heartbeat: float = None) -> Iterator[Optional[Event]]:
+        """Events until ``done``/``error``; ``None`` after ``heartbeat`` seconds without one."""
+        heartbeat = heartbeat or SUMMARY_STREAM_HEARTBEAT_SECONDS
+        while True:
+            try:
+                event = self._events.get(timeout=heartbeat)
+            except queue.Empty:
+                yield None
+                continue
+            yield event
+            if event[0] in END_EVENTS:
+                return
+
+
+def open_stream(task_id: str, record_id: Optional[str] = None) -> SummaryStream:
+    stream = SummaryStream(task_id, record_id)
+    with _streams_lock:
+        _streams[task_id] = stream
+    # 아무도 붙지 않은 스트림은 이벤트가 계속 쌓이지 않도록 일정 시간 뒤 정리
+    timer = threading.Timer(SUMMARY_STREAM_ATTACH_SECONDS, _drop_unattached, (task_id, stream))
+    timer.daemon = True
+    timer.start()
+    return stream
+
+
+def attach_stream(task_id: Optional[str], record_id: str) -> Optional[SummaryStream]:
+    """The open stream of ``task_id`` for ``record_id`` if no client reads it yet (``None`` otherwise)."""
+    with _streams_lock:
+        stream = _streams.get(task_id) if task_id else None
+        if stream is None or stream.record_id != record_id or stream.attached:
+            return None
+        stream.attached = True
+        return stream
+
+
+def _drop_unattached(task_id: str, stream: SummaryStream) -> None:
+    with _streams_lock:
+        if not stream.attached and _streams.get(task_id) is stream:
+            del _streams[task_id]
+
+
+def close_stream(task_id: str) -> None:
+    with _streams_lock:
+        _streams.pop(task_id, None)
+
+
+def token_sink(task_id: Optional[str]) -> Optional[Callable[[Optional[str]], None]]:
+    """``on_token`` for the summary of ``task_id`` when a client streams it (``None`` otherwise)."""
+    with _streams_lock:
+        stream = _streams.get(task_id) if task_id else None
+    return stream.token if stream else None
+
+
+def forward_progress(event: Dict[str, Any]) -> None:
+    """Progress bus subscriber: pass a streamed task's progress messages to its stream."""
+    with _streams_lock:
+        stream = _streams.get(event.get("task_id"))
+    if stream is not None and event.get("message") and not event.get("done"):
+        stream.send("progress", {"message": event["message"], "seq": event.get("seq")})
+
+
+def format_sse(event: str, data: Optional[Dict[str, Any]]) -> bytes:
+    """One SSE message (``event:`` + one-line JSON ``data:``)."""
+    return f"event: {event}\ndata: {json.dumps(data or {}, ensure_ascii=False)}\n\n".encode("utf-8")
+
@@ -0,0 +1,166 @@
+"""Journal of in-flight workflow tasks and the clients that started them.
+
+Every task started through ``run_workflow`` is written to
+``DB/task_journal.json`` and removed when it ends, so tasks interrupted by a
+killed server are found on the next start and flagged as ``interrupted``.
+
+Frontend clients identify themselves on the progress WebSocket and send
+periodic heartbeats (``{"type": "heartbeat", "client_id": "..."}``). When a
+client is silent for ``CLIENT_HEARTBEAT_TIMEOUT_SECONDS`` its running tasks
+are flagged ``detached``; they keep running, and once finished are kept as
+``finished_unattended`` so the user sees them on the next launch
+(``GET /tasks/orphaned``) until dismissed.
+"""
+
+from __future__ import annotations
+
+import json
+import threading
+import time
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+CLIENT_HEARTBEAT_TIMEOUT_SECONDS = get_config_value("CLIENT_HEARTBEAT_TIMEOUT_SECONDS", 90, float)
+# 업로드 본문이 이 시간 동안 한 바이트도 오지 않으면 업로드를 중단
+UPLOAD_IDLE_TIMEOUT_SECONDS = get_config_value("UPLOAD_IDLE_TIMEOUT_SECONDS", 60, float)
+JOURNAL_FILENAME = "task_journal.json"
+
+
+class TaskJournal:
+    def __init__(self, path: Path, heartbeat_timeout: float = CLIENT_HEARTBEAT_TIMEOUT_SECONDS):
+        self.path = path
+        self.heartbeat_timeout = heartbeat_timeout
+        self._lock = threading.Lock()
+        self._clients: Dict[str, float] = {}
+        self._data = self._load()
+
+    def _load(self) -> Dict[str, Dict[str, Any]]:
+        try:
+            with open(self.path, "r", encoding="utf-8") as f:
+                data = json.load(f)
+        except FileNotFoundError:
+            data = {}
+        except (OSError, json.JSONDecodeError) as exc:
+            print(f"작업 저널을 읽을 수 없어 새로 시작합니다: {exc}")
+            data = {}
+        return {"running": data.get("running") or {}, "orphaned": data.get("orphaned") or {}}
+
+    def _save(self) -> None:
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        tmp_path = self.path.with_name(f"{self.path.name}.tmp")
+        with open(tmp_path, "w", encoding="utf-8") as f:
+            json.dump(self._data, f, ensure_ascii=False, indent=2)
+        tmp_path.replace(self.path)
+
+    def start(self, task_id: str, record_id: Optional[str], steps: List[str],
+              client_id: Optional[str] = None) -> None:
+        with self._lock:
+            self._data["running"][task_id] = {
+                "task_id": task_id,
+                "record_id": record_id,
+                "steps": list(steps or []),
+                "client_id": client_id,
+                "started_at": datetime.now().isoformat(),
+            }
+            self._save()
+
+    def finish(self, task_id: str) -> None:
+        with self._lock:
+            entry = self._data["running"].pop(task_id, None)
+            if entry is None:
+                return
+            if entry.get("status") == "detached":
+                # 클라이언트가 사라진 동안 끝난 작업은 다음 실행 때 알려줌
+                entry["status"] = "finished_unattended"
+                entry["finished_at"] = datetime.now().isoformat()
+                self._data["orphaned"][task_id] = entry
+            self._save()
+
+    def recover_interrupted(self) -> List[Dict[str, Any]]:
+        """Move tasks left running by a previous server process to the orphan list."""
+        with self._lock:
+            interrupted = list(self._data["running"].values())
+            if not interrupted:
+                return []
+            now = datetime.now().isoformat()
+            for entry in interrupted:
+                entry["status"] = "interrupted"
+                entry["detected_at"] = now
+                self._data["orphaned"][entry["task_id"]] = entry
+            self._data["running"] = {}
+            self._save()
+            return interrupted
+
+    def heartbeat(self, client_id: str) -> None:
+        with self._lock:
+            self._clients[client_id] = time.time()
+            # 다시 나타난 클라이언트의 작업은 더 이상 분리된 상태가 아님
+            changed = False
+            for entry in self._data["running"].values():
+                if entry.get("client_id") == client_id and entry.get("status") == "detached":
+                    entry.pop("status", None)
+                    entry.pop("detached_at", None)
+                    changed = True
+            if changed:
+                self._save()
+
+    def detach_lost_clients(self) -> List[Dict[str, Any]]:
+        """Flag running tasks whose client stopped sending heartbeats; return those entries."""
+        now = time.time()
+        with self._lock:
+            lost = {client_id for client_id, seen in self._clients.items()
+                    if now - seen > self.heartbeat_timeout}
+            detached = []
+            for entry in self._data["running"].values():
+                if entry.get("client_id") in lost and entry.get("status") != "detached":
+                    entry["status"] = "detached"
+                    entry["detached_at"] = datetime.now().isoformat()
+                    detached.append(dict(entry))
+            for client_id in lost:
+                del self._clients[client_id]
+            if detached:
+                self._save()
+            return detached
+
+    def orphaned(self) -> List[Dict[str, Any]]:
+        with self._lock:
+            detached = [dict(e) for e in self._data["running"].values() if e.get("status") == "detached"]
+            flagged = [dict(e) for e in self._data["orphaned"].values()]
+        return sorted(detached + flagged, key=lambda e: e.get("started_at") or "", reverse=True)
+
+    def dismiss(self, task_id: str) -> bool:
+        with self._lock:
+            if self._data["orphaned"].pop(task_id, None) is None:
+                return False
+            self._save()
+            return True
+
+    def active_clients(self) -> int:
+        with self._lock:
+            return len(self._clients)
+
+
+def start_client_watchdog(journal: TaskJournal, on_detached=None, interval: float = 30) -> threading.Thread:
+    """Periodically flag tasks of clients whose heartbeats stopped."""
+
+    def run():
+        while True:
+            time.sleep(interval)
+            try:
+                detached = journal.detach_lost_clients()
+                if detached:
+                    print(f"클라이언트 연결이 끊긴 작업 {len(detached)}개를 분리 상태로 표시")
+                    if on_detached:
+                        on_detached(detached)
+            except Exception as exc:
+                print(f"클라이언트 감시 실패: {exc}")
+
+    thread = threading.Thread(target=run, daemon=True)
+    thread.start()
+    return thread
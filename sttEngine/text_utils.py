@@ -0,0 +1,171 @@
+"""UTF-8-safe text truncation shared by chunking, summaries and log output.
+
+Python slices by code point, which never raises, but can still cut a
+user-visible character in half: decomposed (NFD) Hangul syllables from
+macOS file names are two or three jamo code points, and emoji may be a
+ZWJ sequence with skin-tone and variation modifiers. Byte-limited output
+(request bodies, log lines) can also end in the middle of a multi-byte
+sequence. The helpers here always cut on grapheme cluster boundaries:
+
+* :func:`truncate_bytes` — longest prefix that fits in ``max_bytes`` of UTF-8.
+* :func:`grapheme_len` / :func:`truncate_graphemes` — length and cut by
+  user-perceived characters.
+* :func:`display_width` / :func:`clip_display` — terminal columns, where
+  Hangul, CJK and emoji take two columns.
+
+Grapheme clusters follow the common cases of Unicode UAX #29 (combining
+marks, ZWJ, variation selectors, emoji modifiers, Hangul jamo, regional
+indicator pairs) without needing the third-party ``regex`` module.
+"""
+
+from __future__ import annotations
+
+import unicodedata
+from typing import Iterator, List
+
+ELLIPSIS = "…"
+
+_ZWJ = "\u200d"
+
+
+def _is_extend(char: str) -> bool:
+    code = ord(char)
+    return (
+        unicodedata.category(char) in ("Mn", "Me", "Mc")
+        or 0xFE00 <= code <= 0xFE0F       # variation selectors
+        or 0x1F3FB <= code <= 0x1F3FF     # emoji skin-tone modifiers
+        or 0xE0020 <= code <= 0xE007F     # emoji tag sequences
+        or char == _ZWJ
+    )
+
+
+def _jamo_kind(char: str) -> str:
+    code = ord(char)
+    if 0x1100 <= code <= 0x115F or 0xA960 <= code <= 0xA97F:
+        return "L"
+    if 0x1160 <= code <= 0x11A7 or 0xD7B0 <= code <= 0xD7C6:
+        return "V"
+    if 0x11A8 <= code <= 0x11FF or 0xD7CB <= code <= 0xD7FB:
+        return "T"
+    if 0xAC00 <= code <= 0xD7A3:
+        # 완성형 음절: 받침이 없으면 LV (뒤에 V/T가 올 수 있음), 있으면 LVT (뒤에 T만)
+        return "LV" if (code - 0xAC00) % 28 == 0 else "LVT"
+    return ""
+
+
+def _is_regional_indicator(char: str) -> bool:
+    return 0x1F1E6 <= ord(char) <= 0x1F1FF
+
+
+def _joins(previous: str, current: str, regional_run: int) -> bool:
+    """Whether ``current`` continues the grapheme cluster ending in ``previous``."""
+    if previous == "\r" and current == "\n":
+        return True
+    if previous in "\r\n" or current in "\r\n":
+        return False
+    if _is_extend(current):
+        return True
+    if previous == _ZWJ:
+        return True  # ZWJ 이모지 시퀀스
+    prev_kind, cur_kind = _jamo_kind(previous), _jamo_kind(current)
+    if prev_kind == "L" and cur_kind in ("L", "V", "LV", "LVT"):
+        return True
+    if prev_kind in ("V", "LV") and cur_kind in ("V", "T"):
+        return True
+    if prev_kind in ("T", "LVT") and cur_kind == "T":
+        return True
+    if _is_regional_indicator(previous) and _is_regional_indicator(current):
+        return regional_run % 2 == 1  # 국기는 두 글자씩
+    return False
+
+
+def iter_graphemes(text: str) -> Iterator[str]:
+    """Yield the user-perceived characters of ``text``."""
+    if not text:
+        return
+    start = 0
+    regional_run = 1 if _is_regional_indicator(text[0]) else 0
+    for index in range(1, len(text)):
+        previous, current = text[index - 1], text[index]
+        if not _joins(previous, current, regional_run):
+            yield text[start:index]
+            start = index
+            regional_run = 0
+        if _is_regional_indicator(current):
+            regional_run += 1
+    yield text[start:]
+
+
+def graphemes(text: str) -> List[str]:
+    return list(iter_graphemes(text))
+
+
+def grapheme_len(text: str) -> int:
+    return sum(1 for _ in iter_graphemes(text))
+
+
+def grapheme_boundary(text: str, index: int) -> int:
+    """Largest grapheme boundary ``<= index`` (``index`` itself if it already is one)."""
+    if index <= 0 or index >= len(text):
+        return max(0, min(index, len(text)))
+    position = 0
+    for cluster in iter_graphemes(text):
+        if position + len(cluster) > index:
+            return position
+        position += len(cluster)
+    return len(text)
+
+
+def truncate_graphemes(text: str, max_graphemes: int, ellipsis: str = ELLIPSIS) -> str:
+    """Cut ``text`` to at most ``max_graphemes`` characters, ellipsis included."""
+    clusters = graphemes(text)
+    if len(clusters) <= max_graphemes:
+        return text
+    keep = max(0, max_graphemes - grapheme_len(ellipsis))
+    return "".join(clusters[:keep]) + ellipsis
+
+
+def truncate_bytes(text: str, max_bytes: int, ellipsis: str = "") -> str:
+    """Longest grapheme-aligned prefix whose UTF-8 encoding (with ``ellipsis``) fits in ``max_bytes``."""
+    if len(text.encode("utf-8")) <= max_bytes:
+        return text
+    budget = max_bytes - len(ellipsis.encode("utf-8"))
+    parts, used = [], 0
+    for cluster in iter_graphemes(text):
+        size = len(cluster.encode("utf-8"))
+        if used + size > budget:
+            break
+        parts.append(cluster)
+        used += size
+    return "".join(parts) + ellipsis if budget >= 0 else ""
+
+
+def _cluster_width(cluster: str) -> int:
+    base = cluster[0]
+    if unicodedata.category(base) in ("Cc", "Cf") or _is_extend(base):
+        return 0
+    if unicodedata.east_asian_width(base) in ("W", "F") or _jamo_kind(base):
+        return 2
+    if len(cluster) > 1 and ("\ufe0f" in cluster or _ZWJ in cluster):
+        return 2  # 이모지 표현 선택자/ZWJ 시퀀스
+    return 1
+
+
+def display_width(text: str) -> int:
+    """Terminal column width (Hangul/CJK/emoji count as two columns)."""
+    return sum(_cluster_width(cluster) for cluster in iter_graphemes(text))
+
+
+def clip_display(text: str, max_width: int, ellipsis: str = ELLIPSIS) -> str:
+    """Cut ``text`` to at most ``max_width`` terminal columns, ellipsis included."""
+    if display_width(text) <= max_width:
+        return text
+    budget = max_width - display_width(ellipsis)
+    parts, used = [], 0
+    for cluster in iter_graphemes(text):
+        width = _cluster_width(cluster)
+        if used + width > budget:
+            break
+        parts.append(cluster)
+        used += width
+    return "".join(parts) + ellipsis
@@ -0,0 +1,78 @@
+"""Clean and verbatim views of a transcript.
+
+Postprocessing (discard phrases, fillers, minimum length, repetition and
+hallucination checks, repeated-word normalization) used to throw the
+removed text away for good. The STT step now keeps every raw segment in
+the ``verbatim`` list of the segments document, the ones the filter
+removed marked with why::
+
+    "verbatim": [
+        {"start": 0.0, "end": 0.8, "text": "음...", "dropped": "filler"},
+        {"start": 0.8, "end": 4.2, "text": "오늘 회의 시작하겠습니다"}
+    ]
+
+so the filter is a view over the raw segments rather than a destructive
+step:
+
+* ``clean`` (default) — the transcript markdown and ``segments`` as before,
+  including manual edits;
+* ``verbatim`` — every raw segment with its original text, fillers kept.
+
+``GET /record/{id}/transcript?view=...`` and ``/download/{id}?view=...`` on
+STT files pick the view. Transcripts made before ``verbatim`` existed only
+have the clean view.
+"""
+
+from __future__ import annotations
+
+from collections import Counter
+from typing import Any, Dict, Iterable, List, Optional
+
+VIEWS = ("clean", "verbatim")
+DEFAULT_VIEW = "clean"
+
+
+class TranscriptViewError(ValueError):
+    """Raised for unknown view names."""
+
+
+def parse_view(value: Optional[str]) -> str:
+    view = (value or DEFAULT_VIEW).strip().lower()
+    if view not in VIEWS:
+        raise TranscriptViewError(f"view는 {', '.join(VIEWS)} 중 하나여야 합니다.")
+    return view
+
+
+def available_views(document: Optional[Dict[str, Any]]) -> List[str]:
+    return list(VIEWS) if (document or {}).get("verbatim") is not None else [DEFAULT_VIEW]
+
+
+def verbatim_segments(document: Optional[Dict[str, Any]]) -> Optional[List[Dict[str, Any]]]:
+    """Raw segments of a segments document, ``None`` when it predates the verbatim view."""
+    return (document or {}).get("verbatim")
+
+
+def dropped_counts(segments: Iterable[Dict[str, Any]]) -> Dict[str, int]:
+    """Number of segments the clean view leaves out, per reason."""
+    return dict(Counter(segment["dropped"] for segment in segments if segment.get("dropped")))
+
+
+def _timestamp(seconds: float) -> str:
+    h, rem = divmod(int(seconds), 3600)
+    m, s = divmod(rem, 60)
+    return f"{h:02d}:{m:02d}:{s:02d}"
+
+
+def render_markdown(title: str, segments: Iterable[Dict[str, Any]]) -> str:
+    """Transcript markdown in the STT output format (``[HH:MM:SS - HH:MM:SS] 텍스트`` lines)."""
+    body = "\n".join(
+        f"[{_timestamp(segment['start'])} - {_timestamp(segment['end'])}] {segment['text']}"
+        for segment in segments
+    )
+    return f"{title}\n\n{body}"
+
+
+def title_line(markdown: str, fallback: str) -> str:
+    """First ``# `` heading of a transcript markdown, or ``# {fallback}``."""
+    first = markdown.splitlines()[0] if markdown else ""
+    return first if first.startswith("# ") else f"# {fallback}"
@@ -0,0 +1,491 @@
+"""Server-side download of remote recordings for ``POST /upload_url``.
+
+Webinars and talks are usually published as links, not files. The server
+fetches them itself so the user does not have to download and re-upload a
+multi-GB video:
+
+* A direct media link (``Content-Type: audio/*``, ``video/*`` or a known
+  extension) is streamed with ``requests`` into the upload folder.
+* Anything else — YouTube, Vimeo, conference pages — is handed to
+  `yt-dlp <https://github.com/yt-dlp/yt-dlp>`_ when it is installed (the
+  ``yt-dlp`` executable on PATH / ``YTDLP_PATH``, or the ``yt_dlp`` Python
+  package), which extracts the best audio stream.
+
+Both paths report progress through a callback, honour a cancellation event
+and stop at ``URL_UPLOAD_MAX_MB``. The server must not become a proxy into
+its own network (Ollama on ``127.0.0.1:11434``, LAN hosts, the cloud
+metadata address ``169.254.169.254``): the host of the URL and of every
+redirect (followed by hand, at most ``URL_UPLOAD_MAX_REDIRECTS``) is
+resolved and refused when any address is loopback, private, link-local or
+reserved, and yt-dlp is never started for such a host. yt-dlp follows
+redirects and extractor-supplied media URLs on its own, and a hostname can
+resolve differently by the time a connection is made, so both paths also
+connect only through a loopback proxy (:func:`egress_proxy_url`) that
+resolves every host itself, refuses internal addresses and connects to the
+address it checked. Set ``URL_UPLOAD_ALLOW_PRIVATE=true`` to download from
+the local network anyway. Files are written with the ``.part`` suffix until
+complete, so interrupted downloads are cleaned up on the next start like
+interrupted uploads.
+
+Jobs are kept in memory (:func:`create_job` / :func:`get_job`) so clients
+can poll ``GET /upload_url/{task_id}`` for the resulting record id.
+"""
+
+from __future__ import annotations
+
+import importlib.util
+import ipaddress
+import mimetypes
+import os
+import re
+import shutil
+import socket
+import socketserver
+import subprocess
+import sys
+import threading
+import time
+from collections import OrderedDict
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Callable, Dict, List, Optional
+from urllib.parse import unquote, urljoin, urlparse
+
+import requests
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+    from .disk_guard import ensure_free_space
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+    from disk_guard import ensure_free_space  # type: ignore
+
+URL_UPLOAD_MAX_MB = get_config_value("URL_UPLOAD_MAX_MB", 4096, int)
+URL_UPLOAD_TIMEOUT_SECONDS = get_config_value("URL_UPLOAD_TIMEOUT_SECONDS", 30, float)
+YTDLP_PATH = get_config_value("YTDLP_PATH", "yt-dlp", str)
+URL_UPLOAD_ALLOW_PRIVATE = get_config_value("URL_UPLOAD_ALLOW_PRIVATE", False, bool)
+URL_UPLOAD_MAX_REDIRECTS = max(0, get_config_value("URL_UPLOAD_MAX_REDIRECTS", 5, int))
+
+DOWNLOAD_CHUNK_SIZE = 1024 * 1024
+PARTIAL_SUFFIX = ".part"
+PROGRESS_INTERVAL_SECONDS = 1.0
+JOB_RETENTION = 50
+REDIRECT_STATUSES = (301, 302, 303, 307, 308)
+PROXY_MAX_HEADER_BYTES = 64 * 1024
+# 프록시가 다음 홉으로 넘기지 않는 헤더 (연결마다 Connection: close로 대상 하나만 허용)
+_HOP_HEADERS = {"connection", "keep-alive", "proxy-connection", "proxy-authorization", "te", "upgrade"}
+
+MEDIA_EXTENSIONS = {'.flac', '.m4a', '.mp3', '.mp4', '.mpeg', '.mpga', '.oga', '.ogg', '.qta', '.wav', '.webm'}
+# yt-dlp --newline 진행 줄: "[download]  42.3% of ~ 81.20MiB at 2.31MiB/s ETA 00:21"
+_YTDLP_PROGRESS = re.compile(r"^\[download\]\s+(\d+(?:\.\d+)?)%\s+of\s+~?\s*([\d.]+\w+)")
+
+_jobs: "OrderedDict[str, Dict[str, Any]]" = OrderedDict()
+_jobs_lock = threading.Lock()
+
+
+class UrlIngestError(ValueError):
+    """Raised for invalid URLs, non-media responses and failed downloads."""
+
+
+class UrlIngestCancelled(UrlIngestError):
+    """Raised when the download was cancelled through ``/cancel``."""
+
+
+def _is_internal_address(address: str) -> bool:
+    ip = ipaddress.ip_address(address.split("%", 1)[0])  # IPv6 zone id 제거
+    if isinstance(ip, ipaddress.IPv6Address) and ip.ipv4_mapped:
+        ip = ip.ipv4_mapped
+    return (ip.is_loopback or ip.is_private or ip.is_link_local or ip.is_reserved
+            or ip.is_multicast or ip.is_unspecified or not ip.is_global)
+
+
+def _public_addresses(host: str, port: int) -> List[str]:
+    """Addresses of ``host``; refused when any of them is internal."""
+    try:
+        addresses = list(dict.fromkeys(
+            info[4][0] for info in socket.getaddrinfo(host, port, type=socket.SOCK_STREAM)))
+    except (socket.gaierror, UnicodeError):
+        raise UrlIngestError(f"호스트를 찾을 수 없습니다: {host}") from None
+    # 주소가 여러 개면 하나라도 내부 주소일 때 거부 (요청 시 어느 주소로 연결될지 모름)
+    internal = sorted(address for address in addresses if _is_internal_address(address))
+    if internal:
+        raise UrlIngestError(f"내부 네트워크 주소는 받을 수 없습니다: {host} ({internal[0]})")
+    return addresses
+
+
+def check_public_host(url: str) -> None:
+    """Refuse ``url`` when its host resolves to an internal address (see the module docstring)."""
+    if URL_UPLOAD_ALLOW_PRIVATE:
+        return
+    parsed = urlparse(url)
+    host = parsed.hostname
+    try:
+        port = parsed.port or (443 if parsed.scheme == "https" else 80)
+    except ValueError:
+        raise UrlIngestError("url의 포트가 올바르지 않습니다.") from None
+    if not host:
+        raise UrlIngestError("url에 호스트가 없습니다.")
+    _public_addresses(host, port)
+
+
+def _connect_public(host: str, port: int) -> socket.socket:
+    """Connect to ``host`` using only the addresses that were checked (no second DNS lookup)."""
+    error: Optional[OSError] = None
+    for address in _public_addresses(host, port):
+        try:
+            return socket.create_connection((address, port), timeout=URL_UPLOAD_TIMEOUT_SECONDS)
+        except OSError as exc:
+            error = exc
+    raise error or OSError(f"연결할 주소가 없습니다: {host}")
+
+
+def _relay(source: socket.socket, target: socket.socket) -> None:
+    try:
+        while True:
+            chunk = source.recv(DOWNLOAD_CHUNK_SIZE)
+            if not chunk:
+                break
+            target.sendall(chunk)
+    except OSError:
+        pass
+    finally:
+        try:
+            target.shutdown(socket.SHUT_WR)
+        except OSError:
+            pass
+
+
+class _EgressProxyHandler(socketserver.StreamRequestHandler):
+    """``CONNECT host:port`` tunnels and absolute-form ``http://`` requests to public hosts only."""
+
+    def _reply(self, status: str) -> None:
+        self.wfile.write(f"HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".encode("ascii"))
+
+    def handle(self) -> None:
+        self.connection.settimeout(URL_UPLOAD_TIMEOUT_SECONDS)
+        try:
+            request_line = self.rfile.readline(PROXY_MAX_HEADER_BYTES).decode("latin-1")
+            headers: List[str] = []
+            size = len(request_line)
+            while True:
+                line = self.rfile.readline(PROXY_MAX_HEADER_BYTES).decode("latin-1")
+                size += len(line)
+                if line in ("\r\n", "\n", "") or size > PROXY_MAX_HEADER_BYTES:
+                    break
+                headers.append(line.rstrip("\r\n"))
+        except OSError:
+            return
+        parts = request_line.split()
+        if len(parts) != 3 or size > PROXY_MAX_HEADER_BYTES:
+            self._reply("400 Bad Request")
+            return
+        method, target, version = parts
+        if method.upper() == "CONNECT":
+            host, _, port_text = target.rpartition(":")
+            host = host.strip("[]")
+        else:
+            parsed = urlparse(target)
+            try:
+                host, port_text = parsed.hostname, str(parsed.port or 80)
+            except ValueError:
+                host, port_text = None, ""
+            if parsed.scheme != "http":
+                host = None
+        if not host or not port_text.isdigit() or not 0 < int(port_text) < 65536:
+            self._reply("400 Bad Request")
+            return
+        if any(line.split(":", 1)[0].strip().lower() == "transfer-encoding" for line in headers):
+            self._reply("411 Length Required")  # 청크 본문은 전달하지 않음
+            return
+        try:
+            upstream = _connect_public(host, int(port_text))
+        except UrlIngestError:
+            self._reply("403 Internal Address Refused")
+            return
+        except OSError:
+            self._reply("502 Bad Gateway")
+            return
+        with upstream:
+            upstream.settimeout(None)
+            self.connection.settimeout(None)
+            if method.upper() == "CONNECT":
+                self.wfile.write(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+                sender = threading.Thread(target=_relay, args=(self.connection, upstream), daemon=True)
+                sender.start()
+                _relay(upstream, self.connection)
+                sender.join(URL_UPLOAD_TIMEOUT_SECONDS)
+                return
+            kept = [line for line in headers if line.split(":", 1)[0].strip().lower() not in _HOP_HEADERS]
+            # 원본 서버에는 origin-form 요청 줄로 전달
+            path = (parsed.path or "/") + (f"?{parsed.query}" if parsed.query else "")
+            head = "\r\n".join([f"{method} {path} {version}", *kept, "Connection: close", "", ""])
+            upstream.sendall(head.encode("latin-1"))
+            length = next((line.split(":", 1)[1].strip() for line in kept
+                           if line.split(":", 1)[0].strip().lower() == "content-length"), "0")
+            if length.isdigit() and int(length):
+                upstream.sendall(self.rfile.read(int(length)))
+            _relay(upstream, self.connection)
+
+
+class _EgressProxy(socketserver.ThreadingTCPServer):
+    daemon_threads = True
+    allow_reuse_address = True
+
+
+_egress_proxy: Optional[_EgressProxy] = None
+_egress_proxy_lock = threading.Lock()
+
+
+def egress_proxy_url() -> Optional[str]:
+    """URL of the loopback proxy every download goes through (``None`` with ``URL_UPLOAD_ALLOW_PRIVATE``).
+
+    The proxy is started on first use on a free ``127.0.0.1`` port.
+    """
+    global _egress_proxy
+    if URL_UPLOAD_ALLOW_PRIVATE:
+        return None
+    with _egress_proxy_lock:
+        if _egress_proxy is None:
+            _egress_proxy = _EgressProxy(("127.0.0.1", 0), _EgressProxyHandler)
+            threading.Thread(target=_egress_proxy.serve_forever, name="url-ingest-proxy", daemon=True).start()
+        return f"http://127.0.0.1:{_egress_proxy.server_address[1]}"
+
+
+def validate_url(url: Any) -> str:
+    """``url`` stripped, when it is an http(s) URL of a public host."""
+    parsed = urlparse(url.strip() if isinstance(url, str) else "")
+    if parsed.scheme not in ("http", "https") or not parsed.netloc:
+        raise UrlIngestError("url은 http(s) 주소여야 합니다.")
+    check_public_host(url.strip())
+    return url.strip()
+
+
+def max_bytes() -> int:
+    return URL_UPLOAD_MAX_MB * 1024 * 1024
+
+
+def ytdlp_command() -> Optional[List[str]]:
+    """Command prefix that runs yt-dlp, or ``None`` when it is not installed."""
+    executable = shutil.which(YTDLP_PATH)
+    if executable:
+        return [executable]
+    if importlib.util.find_spec("yt_dlp") is not None:
+        return [sys.executable, "-m", "yt_dlp"]
+    return None
+
+
+def _format_size(size: float) -> str:
+    for unit in ("B", "KB", "MB"):
+        if size < 1024:
+            return f"{size:.0f}{unit}" if unit == "B" else f"{size:.1f}{unit}"
+        size /= 1024
+    return f"{size:.2f}GB"
+
+
+def _safe_filename(name: str) -> str:
+    name = os.path.basename(unquote(name or "")).strip()
+    name = re.sub(r'[\\/:*?"<>|\x00-\x1f]', "_", name)
+    return name[:150] or "download"
+
+
+def _filename_from_response(url: str, response: requests.Response) -> str:
+    disposition = response.headers.get("Content-Disposition") or ""
+    match = re.search(r"filename\*=(?:UTF-8'')?([^;]+)", disposition, re.IGNORECASE) \
+        or re.search(r'filename="?([^";]+)"?', disposition, re.IGNORECASE)
+    name = _safe_filename(match.group(1) if match else urlparse(response.url or url).path)
+    if not Path(name).suffix:
+        content_type = (response.headers.get("Content-Type") or "").split(";")[0].strip()
+        name += mimetypes.guess_extension(content_type) or ""
+    return name
+
+
+def _is_media_response(url: str, response: requests.Response) -> bool:
+    content_type = (response.headers.get("Content-Type") or "").split(";")[0].strip().lower()
+    if content_type.startswith(("audio/", "video/")):
+        return True
+    # 일부 CDN은 application/octet-stream으로 보냄 → 확장자로 판단
+    suffix = Path(urlparse(response.url or url).path).suffix.lower()
+    return content_type != "text/html" and suffix in MEDIA_EXTENSIONS
+
+
+def _check_cancel(cancel_event: Optional[threading.Event]) -> None:
+    if cancel_event is not None and cancel_event.is_set():
+        raise UrlIngestCancelled("다운로드가 취소되었습니다.")
+
+
+def _stream_download(response: requests.Response, target: Path, progress: Callable[[str], None],
+                     cancel_event: Optional[threading.Event]) -> Path:
+    total = int(response.headers.get("Content-Length") or 0)
+    if total > max_bytes():
+        raise UrlIngestError(f"파일이 너무 큽니다 ({_format_size(total)}, 최대 {URL_UPLOAD_MAX_MB}MB).")
+    if total:
+        ensure_free_space(target.parent, total, "원격 파일 다운로드")
+    partial = target.with_name(target.name + PARTIAL_SUFFIX)
+    received = 0
+    last_report = 0.0
+    try:
+        with open(partial, "wb") as output:
+            for chunk in response.iter_content(DOWNLOAD_CHUNK_SIZE):
+                _check_cancel(cancel_event)
+                output.write(chunk)
+                received += len(chunk)
+                if received > max_bytes():
+                    raise UrlIngestError(f"파일이 최대 크기 {URL_UPLOAD_MAX_MB}MB를 넘습니다.")
+                now = time.monotonic()
+                if now - last_report >= PROGRESS_INTERVAL_SECONDS:
+                    last_report = now
+                    if total:
+                        progress(f"'{target.name}' 다운로드 중... {received / total * 100:.0f}% "
+                                 f"({_format_size(received)} / {_format_size(total)})")
+                    else:
+                        progress(f"'{target.name}' 다운로드 중... {_format_size(received)}")
+        partial.replace(target)
+    except BaseException:
+        partial.unlink(missing_ok=True)
+        raise
+    progress(f"'{target.name}' 다운로드 완료 ({_format_size(received)})")
+    return target
+
+
+def _open_url(url: str) -> requests.Response:
+    """GET ``url`` (already validated), following redirects by hand so every hop is checked."""
+    proxy = egress_proxy_url()
+    proxies = {"http": proxy, "https": proxy} if proxy else None
+    for _ in range(URL_UPLOAD_MAX_REDIRECTS + 1):
+        try:
+            response = requests.get(url, stream=True, timeout=URL_UPLOAD_TIMEOUT_SECONDS,
+                                    headers={"User-Agent": "RecordRoute"}, allow_redirects=False,
+                                    proxies=proxies)
+        except requests.RequestException as exc:
+            raise UrlIngestError(f"주소에 연결할 수 없습니다: {exc}") from None
+        location = response.headers.get("Location")
+        if response.status_code not in REDIRECT_STATUSES or not location:
+            return response
+        response.close()
+        url = validate_url(urljoin(url, location))
+    raise UrlIngestError(f"리디렉션이 너무 많습니다 (최대 {URL_UPLOAD_MAX_REDIRECTS}회).")
+
+
+def _ytdlp_download(url: str, dest_dir: Path, progress: Callable[[str], None],
+                    cancel_event: Optional[threading.Event]) -> Path:
+    check_public_host(url)
+    command = ytdlp_command()
+    if command is None:
+        raise UrlIngestError(
+            "미디어 파일 주소가 아닙니다. YouTube 등 동영상 페이지를 받으려면 yt-dlp를 설치하세요 (pip install yt-dlp)."
+        )
+    command += [
+        "--no-playlist", "--newline", "--progress", "--no-mtime",
+        "-f", "bestaudio/best",
+        "--max-filesize", f"{URL_UPLOAD_MAX_MB}M",
+        "-o", str(dest_dir / "%(title).100B [%(id)s].%(ext)s"),
+        "--print", "after_move:filepath",
+    ]
+    env = None
+    proxy = egress_proxy_url()
+    if proxy:
+        # yt-dlp가 따라가는 리디렉션과 추출기가 찾은 미디어 주소도 모두 프록시에서 검사
+        command += ["--proxy", proxy]
+        env = {key: value for key, value in os.environ.items() if key.lower() != "no_proxy"}
+    command.append(url)
+    progress("yt-dlp로 미디어 정보를 확인하는 중...")
+    process = subprocess.Popen(command, stdout=subprocess.PIPE, stderr=subprocess.STDOUT,
+                               text=True, encoding="utf-8", errors="replace", env=env)
+    output_lines: List[str] = []
+    last_report = 0.0
+
+    def kill_on_cancel():
+        if cancel_event is None:
+            return
+        while process.poll() is None:
+            if cancel_event.wait(0.5):
+                process.terminate()
+                return
+
+    threading.Thread(target=kill_on_cancel, daemon=True).start()
+    for line in process.stdout:
+        line = line.strip()
+        if not line:
+            continue
+        match = _YTDLP_PROGRESS.match(line)
+        if match:
+            now = time.monotonic()
+            if now - last_report >= PROGRESS_INTERVAL_SECONDS:
+                last_report = now
+                progress(f"yt-dlp 다운로드 중... {float(match.group(1)):.0f}% (약 {match.group(2)})")
+            continue
+        output_lines.append(line)
+    process.wait()
+    _check_cancel(cancel_event)
+
+    downloaded = [Path(line) for line in output_lines if Path(line).is_file()]
+    if process.returncode != 0 or not downloaded:
+        errors = [line for line in output_lines if line.startswith("ERROR")]
+        detail = (errors or output_lines or ["알 수 없는 오류"])[-1]
+        raise UrlIngestError(f"yt-dlp 다운로드 실패: {detail}")
+    progress(f"'{downloaded[-1].name}' 다운로드 완료 ({_format_size(downloaded[-1].stat().st_size)})")
+    return downloaded[-1]
+
+
+def download_media(url: str, dest_dir: Path, progress: Callable[[str], None] = lambda message: None,
+                   cancel_event: Optional[threading.Event] = None, use_ytdlp: Optional[bool] = None) -> Path:
+    """Download ``url`` into ``dest_dir`` and return the saved file.
+
+    ``use_ytdlp`` forces (``True``) or forbids (``False``) yt-dlp; by default
+    it is used only when the URL does not answer with a media file.
+    """
+    url = validate_url(url)
+    dest_dir.mkdir(parents=True, exist_ok=True)
+    if use_ytdlp:
+        return _ytdlp_download(url, dest_dir, progress, cancel_event)
+
+    progress("원격 파일에 연결하는 중...")
+    response = _open_url(url)
+    with response:
+        if response.status_code >= 400:
+            raise UrlIngestError(f"원격 서버가 HTTP {response.status_code}로 응답했습니다.")
+        if _is_media_response(url, response):
+            return _stream_download(response, dest_dir / _filename_from_response(url, response), progress,
+                                    cancel_event)
+    if use_ytdlp is False:
+        raise UrlIngestError("미디어 파일 주소가 아닙니다 (Content-Type이 audio/* 또는 video/*가 아님).")
+    return _ytdlp_download(url, dest_dir, progress, cancel_event)
+
+
+def create_job(task_id: str, url: str, steps: List[str]) -> Dict[str, Any]:
+    job = {
+        "task_id": task_id,
+        "url": url,
+        "steps": list(steps),
+        "status": "downloading",
+        "record_id": None,
+        "file_path": None,
+        "filename": None,
+        "duplicate_of": None,
+        "results": None,
+        "error": None,
+        "created_at": datetime.now().isoformat(),
+        "finished_at": None,
+    }
+    with _jobs_lock:
+        _jobs.pop(task_id, None)
+        _jobs[task_id] = job
+        while len(_jobs) > JOB_RETENTION:
+            _jobs.popitem(last=False)
+        return dict(job)
+
+
+def update_job(task_id: str, **fields: Any) -> None:
+    with _jobs_lock:
+        job = _jobs.get(task_id)
+        if job is None:
+            return
+        job.update(fields)
+        if fields.get("status") in ("completed", "duplicate", "failed", "cancelled"):
+            job["finished_at"] = datetime.now().isoformat()
+
+
+def get_job(task_id: str) -> Optional[Dict[str, Any]]:
+    with _jobs_lock:
+        job = _jobs.get(task_id)
+        return dict(job) if job else None
@@ -0,0 +1,173 @@
+"""In-memory embedding vectors for vector search.
+
+Every search used to ``np.load`` the ``.npy`` file of every index entry,
+so a query cost one file read per recording. :data:`VECTOR_CACHE` keeps the
+vectors in memory instead, as float32 arrays whose rows are already
+normalized (one row per chunk), so scoring an entry is a single matrix
+product:
+
+- the vectors of the index are loaded in the background at startup
+  (:func:`preload`) and every embedding saved through :func:`save_vector`
+  is added right away;
+- each entry remembers the file's ``mtime``/size. Vector files are only
+  rewritten together with the index, so while the index generation stays
+  the same a cached entry is used without touching the disk; after it
+  changes, an entry checks its file once on next use and reloads it if it
+  was rewritten or drops it if it is gone;
+- ``VECTOR_CACHE_MAX_MB`` caps the memory used (``0`` turns the cache off).
+  Beyond it the least recently used vectors are dropped and read from disk
+  (memory-mapped) when a search needs them.
+
+``GET /cache/stats`` reports the cache under ``vector_cache``.
+"""
+
+from __future__ import annotations
+
+import threading
+from collections import OrderedDict
+from dataclasses import dataclass
+from pathlib import Path
+from typing import Any, Dict, Iterable, Optional, Tuple
+
+import numpy as np
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+VECTOR_CACHE_MAX_MB = max(0, get_config_value("VECTOR_CACHE_MAX_MB", 1024, int))
+VECTOR_CACHE_PRELOAD = get_config_value("VECTOR_CACHE_PRELOAD", True, bool)
+
+MB = 1024 * 1024
+
+
+def normalize_rows(vector: np.ndarray) -> np.ndarray:
+    """Unit-length float32 rows of a document or chunk-matrix vector; zero rows are dropped."""
+    rows = np.asarray(vector, dtype=np.float32)
+    if rows.ndim == 1:
+        rows = rows.reshape(1, -1)
+    norms = np.linalg.norm(rows, axis=1)
+    keep = norms > 0
+    return np.ascontiguousarray(rows[keep] / norms[keep, None])
+
+
+def best_score(query_unit: np.ndarray, rows: np.ndarray) -> Optional[float]:
+    """Cosine similarity of the best matching row (``None`` when no row can be compared)."""
+    if rows.size == 0 or rows.shape[1] != query_unit.shape[0]:
+        return None
+    return float(np.max(rows @ query_unit))
+
+
+@dataclass
+class _Entry:
+    rows: np.ndarray
+    stamp: Tuple[int, int]
+    generation: Optional[str]
+
+
+class VectorCache:
+    """Normalized vectors keyed by file path, bounded by ``limit_bytes`` (least recently used dropped first)."""
+
+    def __init__(self, limit_bytes: int):
+        self.limit_bytes = limit_bytes
+        self._entries: "OrderedDict[str, _Entry]" = OrderedDict()
+        self._bytes = 0
+        self._lock = threading.Lock()
+        self._counters = {"hits": 0, "disk_reads": 0, "evictions": 0}
+
+    def get(self, path: Path, generation: Optional[str] = None) -> Optional[np.ndarray]:
+        """Normalized rows of the vector file ``path`` (``None`` if the file is missing)."""
+        key = str(path)
+        with self._lock:
+            entry = self._entries.get(key)
+            if entry is not None and generation is not None and entry.generation == generation:
+                self._entries.move_to_end(key)
+                self._counters["hits"] += 1
+                return entry.rows
+        try:
+            stat = path.stat()
+        except OSError:
+            self.discard(path)
+            return None
+        stamp = (stat.st_mtime_ns, stat.st_size)
+        if entry is not None and entry.stamp == stamp:
+            with self._lock:
+                entry.generation = generation
+                self._counters["hits"] += 1
+            return entry.rows
+        try:
+            rows = normalize_rows(np.load(path, mmap_mode="r"))
+        except (OSError, ValueError):
+            self.discard(path)
+            return None
+        with self._lock:
+            self._counters["disk_reads"] += 1
+            self._store(key, _Entry(rows, stamp, generation))
+        return rows
+
+    def put(self, path: Path, vector: np.ndarray) -> None:
+        """Add a vector that was just written to ``path``."""
+        try:
+            stat = path.stat()
+        except OSError:
+            return
+        with self._lock:
+            self._store(str(path), _Entry(normalize_rows(vector), (stat.st_mtime_ns, stat.st_size), None))
+
+    def discard(self, path: Path) -> None:
+        with self._lock:
+            entry = self._entries.pop(str(path), None)
+            if entry is not None:
+                self._bytes -= entry.rows.nbytes
+
+    def clear(self) -> None:
+        with self._lock:
+            self._entries.clear()
+            self._bytes = 0
+
+    def full(self) -> bool:
+        return self._bytes >= self.limit_bytes
+
+    def _store(self, key: str, entry: _Entry) -> None:
+        """Insert ``entry`` and evict down to the limit (lock held)."""
+        previous = self._entries.pop(key, None)
+        if previous is not None:
+            self._bytes -= previous.rows.nbytes
+        if entry.rows.nbytes > self.limit_bytes:
+            return
+        self._entries[key] = entry
+        self._bytes += entry.rows.nbytes
+        while self._bytes > self.limit_bytes:
+            _, evicted = self._entries.popitem(last=False)
+            self._bytes -= evicted.rows.nbytes
+            self._counters["evictions"] += 1
+
+    def stats(self) -> Dict[str, Any]:
+        with self._lock:
+            return {
+                "entries": len(self._entries),
+                "bytes": self._bytes,
+                "limit_bytes": self.limit_bytes,
+                **self._counters,
+            }
+
+
+VECTOR_CACHE = VectorCache(VECTOR_CACHE_MAX_MB * MB)
+
+
+def save_vector(path: Path, vector: np.ndarray) -> None:
+    """``np.save`` an embedding and keep it in :data:`VECTOR_CACHE`."""
+    np.save(path, vector)
+    VECTOR_CACHE.put(path, vector)
+
+
+def preload(paths: Iterable[Path], generation: Optional[str] = None) -> int:
+    """Load vector files into the cache until it is full; returns how many are cached."""
+    loaded = 0
+    for path in paths:
+        if VECTOR_CACHE.full():
+            break
+        if VECTOR_CACHE.get(path, generation) is not None:
+            loaded += 1
+    return loaded
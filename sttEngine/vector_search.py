@@ -3,20 +3,44 @@ from __future__ import annotations
 import os
 import sys
 from pathlib import Path
-from typing import List, Dict, Any, Optional
+from typing import List, Dict, Any, Iterable, Optional
 
 import numpy as np
 import json
 from datetime import datetime
 
-from embedding_pipeline import (
-    INDEX_FILE,
-    VECTOR_DIR,
-    embed_text_ollama,
-    load_index,
-    resolve_index_path,
-)
-from search_cache import get_cached_search_result, cache_search_result
+try:  # 서버와 같은 모듈을 써야 캐시 적중 통계와 벡터 캐시가 공유됨
+    from .embedding_pipeline import (
+        INDEX_FILE,
+        SEARCH_TARGETS,
+        VECTOR_DIR,
+        embed_text_ollama,
+        entry_kind,
+        entry_namespace,
+        get_index_generation,
+        get_index_snapshot,
+        plan_index_shards,
+        resolve_index_path,
+    )
+    from .search_cache import get_cached_search_result, cache_search_result
+    from .cache_manager import cached_query_embedding
+    from .vector_cache import VECTOR_CACHE, best_score
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from embedding_pipeline import (
+        INDEX_FILE,
+        SEARCH_TARGETS,
+        VECTOR_DIR,
+        embed_text_ollama,
+        entry_kind,
+        entry_namespace,
+        get_index_generation,
+        get_index_snapshot,
+        plan_index_shards,
+        resolve_index_path,
+    )
+    from search_cache import get_cached_search_result, cache_search_result
+    from cache_manager import cached_query_embedding
+    from vector_cache import VECTOR_CACHE, best_score
 
 # 설정 모듈 임포트
 sys.path.append(str(Path(__file__).parent / "sttEngine"))
@@ -25,14 +49,25 @@ from config import get_default_model, get_model_for_task, normalize_db_record_pa
 
 def search(query: str, base_dir: Path, top_k: int = 10,
            start_date: Optional[str] = None,
-           end_date: Optional[str] = None) -> List[Dict[str, Any]]:
+           end_date: Optional[str] = None,
+           target: str = "both",
+           namespaces: Optional[Iterable[str]] = None) -> List[Dict[str, Any]]:
     """Return top_k most similar documents for the given query.
 
     날짜/시간 필터링을 위해 ISO 형식의 ``start_date``와 ``end_date``를
-    선택적으로 받을 수 있다.
+    선택적으로 받을 수 있다. ``target``으로 전사(``transcript``), 요약
+    (``summary``) 또는 둘 다(``both``)를 검색 대상으로 지정한다. 요약 항목은
+    본문 벡터와 한 줄 요약 벡터 중 높은 점수를 사용한다. ``namespaces``가
+    주어지면 그 네임스페이스의 항목만 검색한다 (``None``이면 전체).
     """
-    # 캐시된 결과 확인
-    cached_results = get_cached_search_result(query, top_k, start_date, end_date)
+    if target not in SEARCH_TARGETS:
+        raise ValueError(f"지원하지 않는 검색 대상입니다: {target}")
+    allowed = frozenset(namespaces) if namespaces else None
+
+    # 캐시된 결과 확인 (색인이 바뀌면 이전 결과는 사용하지 않음)
+    generation = get_index_generation()
+    cached_results = get_cached_search_result(query, top_k, start_date, end_date, target, generation,
+                                              namespaces=allowed)
     if cached_results is not None:
         print(f"캐시에서 검색 결과 반환: {len(cached_results)}개 항목")
         return cached_results
@@ -44,15 +79,26 @@ def search(query: str, base_dir: Path, top_k: int = 10,
         model_name = os.environ.get("EMBEDDING_MODEL", "bge-m3:latest")
     
     try:
-        query_vec = embed_text_ollama(query, model_name)
-        index = load_index()
+        query_vec = np.asarray(cached_query_embedding(query, model_name, embed_text_ollama), dtype=np.float32)
+        query_norm = float(np.linalg.norm(query_vec))
+        if query_norm == 0:
+            return []
+        query_unit = query_vec / query_norm
+        # 불변 스냅샷을 사용하므로 아래 파일 IO 동안 색인 쓰기를 막지 않는다
+        # 색인을 기간별 샤드로 나눈 경우 날짜 필터와 겹치는 샤드만 읽는다
+        snapshot = get_index_snapshot(plan_index_shards(start_date, end_date))
         results: List[Dict[str, Any]] = []
 
         start_dt = datetime.fromisoformat(start_date) if start_date else None
         end_dt = datetime.fromisoformat(end_date) if end_date else None
 
-        for path_str, meta in index.items():
-            if isinstance(meta, dict) and meta.get("deleted"):
+        for path_str, meta in snapshot:
+            if meta.get("deleted"):
+                continue
+            if allowed is not None and entry_namespace(meta) not in allowed:
+                continue
+            kind = entry_kind(path_str, meta)
+            if target != "both" and kind != target:
                 continue
             timestamp_str = meta.get("timestamp")
             if start_dt or end_dt:
@@ -67,17 +113,24 @@ def search(query: str, base_dir: Path, top_k: int = 10,
                 if end_dt and doc_time > end_dt:
                     continue
 
-            vec_file = VECTOR_DIR / meta.get("vector", "")
-            if not vec_file.exists():
-                continue
-            doc_vec = np.load(vec_file)
-            # cosine similarity
-            denom = (np.linalg.norm(query_vec) * np.linalg.norm(doc_vec))
-            if denom == 0:
+            scores = []
+            for vector_key in ("vector", "title_vector"):
+                vector_name = meta.get(vector_key)
+                if not vector_name:
+                    continue
+                # 메모리의 정규화된 벡터 사용 (색인이 바뀐 뒤 처음 쓸 때만 파일 확인)
+                rows = VECTOR_CACHE.get(VECTOR_DIR / vector_name, generation)
+                if rows is None:
+                    continue
+                # 조각별 벡터로 저장된 긴 문서는 가장 잘 맞는 조각의 코사인 유사도를 사용
+                vector_score = best_score(query_unit, rows)
+                if vector_score is not None:
+                    scores.append(vector_score)
+            if not scores:
                 continue
-            score = float(np.dot(query_vec, doc_vec) / denom)
+            score = max(scores)
             try:
-                resolved_path = resolve_index_path(path_str, meta if isinstance(meta, dict) else None)
+                resolved_path = resolve_index_path(path_str, dict(meta))
             except Exception:
                 resolved_path = Path(path_str).resolve()
             try:
@@ -86,14 +139,15 @@ def search(query: str, base_dir: Path, top_k: int = 10,
                 rel_path = resolved_path.as_posix()
 
             rel_path = normalize_db_record_path(rel_path, base_dir)
-            results.append({"file": rel_path, "score": score})
+            results.append({"file": rel_path, "score": score, "kind": kind, "namespace": entry_namespace(meta)})
         
         results.sort(key=lambda x: x["score"], reverse=True)
         final_results = results[:top_k]
         
         # 결과를 캐시에 저장
         cache_search_result(query, top_k, final_results,
-                            start_date=start_date, end_date=end_date)
+                            start_date=start_date, end_date=end_date,
+                            target=target, generation=generation, namespaces=allowed)
         print(f"새로운 검색 결과를 캐시에 저장: {len(final_results)}개 항목")
         
         return final_results
@@ -0,0 +1,120 @@
+"""Time-period shards of the vector index.
+
+With years of recordings a single ``vector_store/index.json`` has to be
+read, rewritten and scanned as a whole for every search and every new
+embedding. ``VECTOR_INDEX_SHARDING`` splits the index by the entry
+``timestamp``:
+
+    none     one ``index.json`` (default)
+    month    ``vector_store/shards/2026-10.json``, ...
+    quarter  ``vector_store/shards/2026-Q4.json``, ...
+
+Entries without a timestamp go to ``undated.json``. ``shards/manifest.json``
+lists every shard with its entry count and the first/last entry time, so a
+search with a date filter only reads the shards whose period overlaps it
+(:func:`plan_shards`); undated entries never match a date filter and are
+skipped as well. Saving the index rewrites only the shards whose entries
+changed.
+
+Switching the setting migrates on the next index load: ``index.json`` is
+split into shards (and kept as ``index.json.migrated``), shards are
+regrouped for a new period, or merged back into ``index.json`` for
+``none`` (the shard folder is kept as ``shards.migrated``).
+"""
+
+from __future__ import annotations
+
+from datetime import datetime
+from typing import Any, Dict, Iterable, List, Optional, Tuple
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+SHARD_PERIODS = ("none", "month", "quarter")
+VECTOR_INDEX_SHARDING = get_config_value("VECTOR_INDEX_SHARDING", "none", str).strip().lower()
+if VECTOR_INDEX_SHARDING not in SHARD_PERIODS:
+    print(f"알 수 없는 VECTOR_INDEX_SHARDING '{VECTOR_INDEX_SHARDING}', none을 사용합니다.")
+    VECTOR_INDEX_SHARDING = "none"
+SHARD_DIR_NAME = "shards"
+MANIFEST_NAME = "manifest.json"
+UNDATED_SHARD = "undated"
+
+
+def _parse(timestamp: Any) -> Optional[datetime]:
+    if not timestamp:
+        return None
+    try:
+        parsed = datetime.fromisoformat(str(timestamp))
+    except ValueError:
+        return None
+    # 시간대가 있는 값은 비교할 수 있게 시간대 정보를 떼어냄 (색인 시각은 로컬 시각)
+    return parsed.replace(tzinfo=None)
+
+
+def shard_for(timestamp: Any, period: str) -> str:
+    """Shard name of an entry timestamp: ``2026-10`` (month), ``2026-Q4`` (quarter) or ``undated``."""
+    moment = _parse(timestamp)
+    if moment is None:
+        return UNDATED_SHARD
+    if period == "quarter":
+        return f"{moment.year}-Q{(moment.month - 1) // 3 + 1}"
+    return f"{moment.year}-{moment.month:02d}"
+
+
+def shard_range(name: str) -> Optional[Tuple[datetime, datetime]]:
+    """``[start, end)`` of a month or quarter shard name; ``None`` for ``undated`` or unknown names."""
+    try:
+        year, part = name.split("-", 1)
+        if part.startswith("Q"):
+            first_month = (int(part[1:]) - 1) * 3 + 1
+            months = 3
+        else:
+            first_month, months = int(part), 1
+        start = datetime(int(year), first_month, 1)
+    except ValueError:
+        return None
+    next_month = first_month - 1 + months
+    return start, datetime(start.year + next_month // 12, next_month % 12 + 1, 1)
+
+
+def split_index(index: Dict[str, Dict[str, Any]], period: str) -> Dict[str, Dict[str, Dict[str, Any]]]:
+    """``{shard: {key: meta}}`` for the entries of a full index."""
+    shards: Dict[str, Dict[str, Dict[str, Any]]] = {}
+    for key, meta in index.items():
+        timestamp = meta.get("timestamp") if isinstance(meta, dict) else None
+        shards.setdefault(shard_for(timestamp, period), {})[key] = meta
+    return shards
+
+
+def describe_shard(entries: Dict[str, Dict[str, Any]]) -> Dict[str, Any]:
+    """Manifest entry of a shard: entry count and first/last entry time."""
+    times = sorted(str(meta["timestamp"]) for meta in entries.values()
+                   if isinstance(meta, dict) and _parse(meta.get("timestamp")))
+    return {"entries": len(entries), "first": times[0] if times else None, "last": times[-1] if times else None}
+
+
+def plan_shards(shards: Iterable[str], start_date: Optional[str] = None,
+                end_date: Optional[str] = None) -> List[str]:
+    """Shards that can hold entries between ``start_date`` and ``end_date`` (ISO strings).
+
+    Without a filter every shard is returned; with one, ``undated`` and
+    shards whose period ends before ``start_date`` or starts after
+    ``end_date`` are left out.
+    """
+    start_dt, end_dt = _parse(start_date), _parse(end_date)
+    if start_dt is None and end_dt is None:
+        return sorted(shards)
+    planned = []
+    for name in shards:
+        period_range = shard_range(name)
+        if period_range is None:
+            continue
+        period_start, period_end = period_range
+        if start_dt and period_end <= start_dt:
+            continue
+        if end_dt and period_start > end_dt:
+            continue
+        planned.append(name)
+    return sorted(planned)
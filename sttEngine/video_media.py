@@ -0,0 +1,218 @@
+"""Video upload helpers: probing, poster thumbnails, audio extraction and subtitle muxing.
+
+Video uploads (mp4/webm) are treated as audio for STT, but right after the
+upload a background worker probes the file with ffprobe and, when it really
+contains a video stream, writes into ``{output folder}/media/``::
+
+    {stem}.thumb.jpg   # 기록 목록 미리보기용 포스터 이미지
+    {stem}.mp3         # 16kHz 모노 오디오 트랙 (STT 입력으로 사용)
+    {stem}.srt         # 자막 내보내기 시 생성
+    {stem}.subtitled.mp4|webm  # 자막 트랙을 합친 영상
+
+Work is queued and processed by ``VIDEO_PREP_WORKERS`` threads (default 1)
+so a burst of uploads does not start many ffmpeg processes at once.
+"""
+
+from __future__ import annotations
+
+import json
+import queue
+import subprocess
+import threading
+from pathlib import Path
+from typing import Any, Callable, Dict, Optional, Tuple
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+    from .resampler import resample_args
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+    from resampler import resample_args  # type: ignore
+
+VIDEO_EXTENSIONS = {".mp4", ".webm"}
+MEDIA_SUBDIR = "media"
+
+VIDEO_PREP_ENABLED = get_config_value("VIDEO_PREP_ENABLED", True, bool)
+VIDEO_PREP_WORKERS = max(1, get_config_value("VIDEO_PREP_WORKERS", 1, int))
+VIDEO_THUMBNAIL_WIDTH = get_config_value("VIDEO_THUMBNAIL_WIDTH", 480, int)
+# 첫 프레임은 검은 화면인 경우가 많아 약간 뒤의 프레임을 사용
+VIDEO_THUMBNAIL_OFFSET_SECONDS = 5.0
+FFMPEG_TIMEOUT_SECONDS = 600
+
+
+class VideoProcessingError(RuntimeError):
+    """Raised when ffprobe/ffmpeg fails for a video file."""
+
+
+def is_video_candidate(path: Path) -> bool:
+    return path.suffix.lower() in VIDEO_EXTENSIONS
+
+
+def media_dir(output_dir: Path) -> Path:
+    return output_dir / MEDIA_SUBDIR
+
+
+def media_paths(output_dir: Path, stem: str) -> Tuple[Path, Path]:
+    """Return ``(thumbnail, audio_track)`` paths for a record's output folder."""
+    directory = media_dir(output_dir)
+    return directory / f"{stem}.thumb.jpg", directory / f"{stem}.mp3"
+
+
+def _run(command: list, action: str) -> subprocess.CompletedProcess:
+    try:
+        result = subprocess.run(command, capture_output=True, check=False, timeout=FFMPEG_TIMEOUT_SECONDS)
+    except FileNotFoundError as exc:
+        raise VideoProcessingError(f"{command[0]}을(를) 찾을 수 없습니다. ffmpeg를 설치하세요.") from exc
+    except subprocess.TimeoutExpired as exc:
+        raise VideoProcessingError(f"{action} 시간 초과") from exc
+    if result.returncode != 0:
+        stderr = result.stderr.decode("utf-8", "replace").strip()
+        raise VideoProcessingError(f"{action} 실패: {stderr}")
+    return result
+
+
+def probe_media(path: Path) -> Dict[str, Any]:
+    """Return ``{"duration", "has_video", "has_audio", "audio_format"}`` for a media file.
+
+    ``audio_format`` describes the container and first audio stream
+    (``{"container", "codec", "sample_rate", "channels", "bit_rate"}``) or is
+    ``None`` without an audio stream.
+    """
+    result = _run([
+        "ffprobe", "-v", "error", "-show_entries",
+        "format=duration,format_name,bit_rate:stream=codec_type,codec_name,sample_rate,channels,bit_rate,disposition",
+        "-of", "json", str(path),
+    ], "ffprobe 분석")
+    try:
+        info = json.loads(result.stdout.decode("utf-8", "replace") or "{}")
+    except json.JSONDecodeError as exc:
+        raise VideoProcessingError("ffprobe 출력을 해석할 수 없습니다.") from exc
+
+    streams = info.get("streams") or []
+    # 앨범 아트(attached_pic)는 영상 스트림으로 보지 않음
+    has_video = any(
+        s.get("codec_type") == "video" and not (s.get("disposition") or {}).get("attached_pic")
+        for s in streams
+    )
+    try:
+        duration = float((info.get("format") or {}).get("duration"))
+    except (TypeError, ValueError):
+        duration = None
+    audio = next((s for s in streams if s.get("codec_type") == "audio"), None)
+    return {
+        "duration": duration,
+        "has_video": has_video,
+        "has_audio": audio is not None,
+        "audio_format": _audio_format(info.get("format") or {}, audio) if audio else None,
+    }
+
+
+def _int_or_none(value: Any) -> Optional[int]:
+    try:
+        return int(value)
+    except (TypeError, ValueError):
+        return None
+
+
+def _audio_format(container: Dict[str, Any], stream: Dict[str, Any]) -> Dict[str, Any]:
+    return {
+        # "mov,mp4,m4a,3gp,3g2,mj2"처럼 여러 이름이 오면 첫 번째
+        "container": (container.get("format_name") or "").split(",")[0] or None,
+        "codec": stream.get("codec_name"),
+        "sample_rate": _int_or_none(stream.get("sample_rate")),
+        "channels": _int_or_none(stream.get("channels")),
+        "bit_rate": _int_or_none(stream.get("bit_rate")) or _int_or_none(container.get("bit_rate")),
+    }
+
+
+def generate_thumbnail(video_path: Path, output_path: Path, duration: Optional[float] = None) -> Path:
+    """Write a JPEG poster frame of ``video_path``."""
+    position = VIDEO_THUMBNAIL_OFFSET_SECONDS
+    if duration:
+        position = min(position, duration / 2)
+    output_path.parent.mkdir(parents=True, exist_ok=True)
+    _run([
+        "ffmpeg", "-nostdin", "-v", "error", "-y", "-ss", f"{position:.3f}", "-i", str(video_path),
+        "-frames:v", "1", "-vf", f"scale={VIDEO_THUMBNAIL_WIDTH}:-2", "-q:v", "4", str(output_path),
+    ], "썸네일 생성")
+    return output_path
+
+
+def extract_audio_track(video_path: Path, output_path: Path) -> Path:
+    """Extract the audio track as 16kHz mono MP3 (enough for STT, small on disk)."""
+    output_path.parent.mkdir(parents=True, exist_ok=True)
+    tmp_path = output_path.with_name(f"{output_path.stem}.tmp{output_path.suffix}")
+    _run([
+        "ffmpeg", "-nostdin", "-v", "error", "-y", "-i", str(video_path),
+        "-vn", *resample_args(), "-c:a", "libmp3lame", "-b:a", "64k", str(tmp_path),
+    ], "오디오 추출")
+    tmp_path.replace(output_path)
+    return output_path
+
+
+# 컨테이너별 자막 코덱 (MP4는 mov_text, WebM은 WebVTT만 허용)
+SUBTITLE_CODECS = {".mp4": "mov_text", ".webm": "webvtt"}
+# Whisper 언어 코드 → 컨테이너 메타데이터용 ISO 639-2 코드
+ISO639_2 = {"ko": "kor", "en": "eng", "ja": "jpn", "zh": "zho", "de": "deu", "fr": "fra", "es": "spa"}
+
+
+def subtitled_video_path(output_dir: Path, video_path: Path) -> Path:
+    return media_dir(output_dir) / f"{video_path.stem}.subtitled{video_path.suffix.lower()}"
+
+
+def mux_subtitles(video_path: Path, subtitle_path: Path, output_path: Path,
+                  language: Optional[str] = None) -> Path:
+    """Copy the video/audio streams and add ``subtitle_path`` as a soft subtitle track."""
+    codec = SUBTITLE_CODECS.get(video_path.suffix.lower())
+    if not codec:
+        raise VideoProcessingError(f"자막을 넣을 수 없는 형식입니다: {video_path.suffix}")
+    command = [
+        "ffmpeg", "-nostdin", "-v", "error", "-y", "-i", str(video_path), "-i", str(subtitle_path),
+        "-map", "0:v", "-map", "0:a?", "-map", "1:0", "-c:v", "copy", "-c:a", "copy", "-c:s", codec,
+    ]
+    if language in ISO639_2:
+        command += ["-metadata:s:s:0", f"language={ISO639_2[language]}"]
+    output_path.parent.mkdir(parents=True, exist_ok=True)
+    tmp_path = output_path.with_name(f"{output_path.stem}.tmp{output_path.suffix}")
+    _run(command + [str(tmp_path)], "자막 합치기")
+    tmp_path.replace(output_path)
+    return output_path
+
+
+class BackgroundWorkQueue:
+    """FIFO queue processed by a fixed number of daemon worker threads."""
+
+    def __init__(self, name: str, workers: int):
+        self.name = name
+        self.workers = workers
+        self._queue: "queue.Queue[Tuple[Callable[..., Any], tuple]]" = queue.Queue()
+        self._started = False
+        self._start_lock = threading.Lock()
+
+    def submit(self, func: Callable[..., Any], *args: Any) -> None:
+        self._ensure_started()
+        self._queue.put((func, args))
+
+    def pending(self) -> int:
+        return self._queue.qsize()
+
+    def _ensure_started(self) -> None:
+        with self._start_lock:
+            if self._started:
+                return
+            for index in range(self.workers):
+                threading.Thread(target=self._work, name=f"{self.name}-{index}", daemon=True).start()
+            self._started = True
+
+    def _work(self) -> None:
+        while True:
+            func, args = self._queue.get()
+            try:
+                func(*args)
+            except Exception as exc:  # pragma: no cover - keep the worker alive
+                print(f"{self.name} 작업 실패: {exc}")
+            finally:
+                self._queue.task_done()
+
+
+VIDEO_PREP_QUEUE = BackgroundWorkQueue("video-prep", VIDEO_PREP_WORKERS)
@@ -191,6 +191,40 @@ class VocabularyManager:
         self._save_vocab(vocab)
         logging.info("vocab.json 업데이트 완료: 총 %d개 키워드", len(vocab))
 
+    def export_entries(self) -> Dict[str, Dict[str, any]]:
+        """Return all vocabulary entries (for configuration bundles)."""
+        return self._load_vocab()
+
+    def import_entries(self, entries: Dict[str, Dict[str, any]], replace: bool = False) -> int:
+        """Import vocabulary entries from a configuration bundle.
+
+        Existing keywords keep the higher of the two weights unless
+        ``replace`` is set, in which case the vocabulary is overwritten.
+
+        Args:
+            entries: Keyword entries in the vocab.json format
+            replace: Replace the whole vocabulary instead of merging
+
+        Returns:
+            Number of keywords in the vocabulary after the import
+        """
+        def apply() -> int:
+            vocab = {} if replace else self._load_vocab()
+            for keyword, entry in entries.items():
+                current = vocab.get(keyword)
+                if current is None or entry.get("weight", 0) > current.get("weight", 0):
+                    vocab[keyword] = dict(entry)
+            self._save_vocab(vocab)
+            return len(vocab)
+
+        if FILELOCK_AVAILABLE:
+            try:
+                with FileLock(self.lock_path, timeout=5):
+                    return apply()
+            except Exception as e:
+                logging.warning("vocab.json 파일 잠금 타임아웃: %s. 경고만 출력하고 계속 진행합니다.", e)
+        return apply()
+
     def get_top_keywords(self, limit: int = 20, max_length: int = 200) -> str:
         """Get top keywords as a comma-separated string.
 
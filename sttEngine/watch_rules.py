@@ -0,0 +1,232 @@
+"""Watch rules: notify when a new recording mentions a topic.
+
+A rule is stored as ``DB/watch_rules/{id}.json``::
+
+    {"id": "...", "name": "계약 갱신", "keywords": ["계약 갱신", "renewal"],
+     "query": "계약 연장 협상", "threshold": 0.6,
+     "channels": ["websocket", "webhook"], "enabled": true}
+
+After a record has been processed and indexed, every enabled rule is
+evaluated once against it:
+
+* ``keywords`` — case-insensitive match in the transcript, summary or
+  one-line summary (whitespace differences ignored);
+* ``query`` — cosine similarity between the query embedding and the
+  record's index vectors (best chunk of the transcript/summary, or the
+  one-line summary vector) of at least ``threshold``
+  (``WATCH_SEMANTIC_THRESHOLD`` by default).
+
+A rule matches when any keyword or the query matches. Matches are announced
+on the rule's ``channels``: ``websocket`` sends a ``watch_matched`` message
+to connected browsers, ``webhook`` delivers the ``watch_matched`` event to
+the webhooks subscribed to it. People who chose channels for
+``watch_matched`` (``notifications``) are notified either way. Each rule
+fires at most once per record.
+"""
+
+from __future__ import annotations
+
+import json
+import re
+import threading
+import uuid
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Callable, Dict, Iterable, List, Optional
+
+import numpy as np
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value, get_db_base_path
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value, get_db_base_path  # type: ignore
+
+WATCH_RULES_DIR = get_db_base_path() / "watch_rules"
+WATCH_SEMANTIC_THRESHOLD = get_config_value("WATCH_SEMANTIC_THRESHOLD", 0.6, float)
+
+CHANNELS = ("websocket", "webhook")
+SNIPPET_CHARS = 60
+_rules_lock = threading.Lock()
+
+
+class WatchRuleError(ValueError):
+    """Raised for invalid rule definitions or unknown rule ids."""
+
+
+def _rule_path(rule_id: str) -> Path:
+    if not re.fullmatch(r"[\w-]+", rule_id or ""):
+        raise WatchRuleError("잘못된 감시 규칙 ID입니다.")
+    return WATCH_RULES_DIR / f"{rule_id}.json"
+
+
+def _apply_fields(rule: Dict[str, Any], payload: Dict[str, Any]) -> None:
+    if "name" in payload:
+        if not isinstance(payload["name"], str) or not payload["name"].strip():
+            raise WatchRuleError("규칙 이름(name)이 필요합니다.")
+        rule["name"] = payload["name"].strip()
+    if "keywords" in payload:
+        keywords = payload["keywords"] or []
+        if not isinstance(keywords, list) or not all(isinstance(k, str) for k in keywords):
+            raise WatchRuleError("keywords는 문자열 배열이어야 합니다.")
+        rule["keywords"] = list(dict.fromkeys(k.strip() for k in keywords if k.strip()))
+    if "query" in payload:
+        if payload["query"] is not None and not isinstance(payload["query"], str):
+            raise WatchRuleError("query는 문자열이어야 합니다.")
+        rule["query"] = (payload["query"] or "").strip() or None
+    if "threshold" in payload:
+        threshold = payload["threshold"]
+        if threshold is not None and (isinstance(threshold, bool) or not isinstance(threshold, (int, float))
+                                      or not 0 < threshold <= 1):
+            raise WatchRuleError("threshold는 0보다 크고 1 이하인 숫자여야 합니다.")
+        rule["threshold"] = threshold
+    if "channels" in payload:
+        channels = payload["channels"]
+        if not isinstance(channels, list) or not channels or any(c not in CHANNELS for c in channels):
+            raise WatchRuleError(f"channels는 {', '.join(CHANNELS)} 중 하나 이상이어야 합니다.")
+        rule["channels"] = list(dict.fromkeys(channels))
+    if "enabled" in payload:
+        rule["enabled"] = bool(payload["enabled"])
+    if not rule.get("keywords") and not rule.get("query"):
+        raise WatchRuleError("keywords 또는 query 중 하나 이상이 필요합니다.")
+
+
+def _write_rule(rule: Dict[str, Any]) -> None:
+    WATCH_RULES_DIR.mkdir(parents=True, exist_ok=True)
+    path = _rule_path(rule["id"])
+    tmp_path = path.with_suffix(".json.tmp")
+    with open(tmp_path, "w", encoding="utf-8") as f:
+        json.dump(rule, f, ensure_ascii=False, indent=2)
+    tmp_path.replace(path)
+
+
+def list_rules() -> List[Dict[str, Any]]:
+    rules = []
+    if WATCH_RULES_DIR.exists():
+        for path in sorted(WATCH_RULES_DIR.glob("*.json")):
+            try:
+                with open(path, "r", encoding="utf-8") as f:
+                    rules.append(json.load(f))
+            except (OSError, json.JSONDecodeError):
+                continue
+    return rules
+
+
+def get_rule(rule_id: str) -> Dict[str, Any]:
+    path = _rule_path(rule_id)
+    if not path.exists():
+        raise WatchRuleError(f"감시 규칙을 찾을 수 없습니다: {rule_id}")
+    with open(path, "r", encoding="utf-8") as f:
+        return json.load(f)
+
+
+def create_rule(payload: Dict[str, Any]) -> Dict[str, Any]:
+    if "name" not in payload:
+        raise WatchRuleError("규칙 이름(name)이 필요합니다.")
+    now = datetime.now().isoformat()
+    rule = {"id": str(uuid.uuid4()), "name": None, "keywords": [], "query": None, "threshold": None,
+            "channels": list(CHANNELS), "enabled": True, "match_count": 0, "last_match": None,
+            "created_at": now, "updated_at": now}
+    _apply_fields(rule, payload)
+    with _rules_lock:
+        _write_rule(rule)
+    return rule
+
+
+def update_rule(rule_id: str, payload: Dict[str, Any]) -> Dict[str, Any]:
+    with _rules_lock:
+        rule = get_rule(rule_id)
+        _apply_fields(rule, payload)
+        rule["updated_at"] = datetime.now().isoformat()
+        _write_rule(rule)
+    return rule
+
+
+def delete_rule(rule_id: str) -> bool:
+    path = _rule_path(rule_id)
+    with _rules_lock:
+        if not path.exists():
+            return False
+        path.unlink()
+    return True
+
+
+def record_match(rule_id: str, record_id: str) -> None:
+    """Count a match on the rule (missing rules are ignored)."""
+    with _rules_lock:
+        try:
+            rule = get_rule(rule_id)
+        except WatchRuleError:
+            return
+        rule["match_count"] = rule.get("match_count", 0) + 1
+        rule["last_match"] = {"record_id": record_id, "at": datetime.now().isoformat()}
+        _write_rule(rule)
+
+
+def _normalize(text: str) -> str:
+    return re.sub(r"\s+", " ", text).casefold()
+
+
+def keyword_hits(keywords: Iterable[str], texts: Dict[str, str]) -> List[Dict[str, Any]]:
+    """``[{"keyword", "source", "snippet"}]`` for each keyword found in one of ``texts``."""
+    hits = []
+    normalized = {source: _normalize(text) for source, text in texts.items() if text}
+    for keyword in keywords:
+        needle = _normalize(keyword.strip())
+        for source, haystack in normalized.items():
+            position = haystack.find(needle)
+            if position < 0:
+                continue
+            start = max(0, position - SNIPPET_CHARS)
+            end = position + len(needle) + SNIPPET_CHARS
+            snippet = haystack[start:end].strip()
+            hits.append({
+                "keyword": keyword,
+                "source": source,
+                "snippet": ("…" if start else "") + snippet + ("…" if end < len(haystack) else ""),
+            })
+            break
+    return hits
+
+
+def best_similarity(query_vector: np.ndarray, vectors: Iterable[np.ndarray]) -> Optional[float]:
+    """Highest cosine similarity of ``query_vector`` to any vector (rows of 2-D chunk arrays included)."""
+    best = None
+    query_norm = np.linalg.norm(query_vector)
+    for vector in vectors:
+        rows = vector if vector.ndim == 2 else vector[np.newaxis, :]
+        for row in rows:
+            denom = query_norm * np.linalg.norm(row)
+            if denom:
+                score = float(np.dot(query_vector, row) / denom)
+                best = score if best is None else max(best, score)
+    return best
+
+
+def evaluate_rule(rule: Dict[str, Any], texts: Dict[str, str],
+                  vectors: Dict[str, List[np.ndarray]],
+                  embed_query: Callable[[str, str], np.ndarray]) -> Optional[Dict[str, Any]]:
+    """Match report of ``rule`` for one record, or ``None``.
+
+    ``texts`` maps a source (transcript, summary, title) to its text,
+    ``vectors`` maps an embedding model to the record's vectors made with it,
+    and ``embed_query(text, model)`` embeds the rule query.
+    """
+    hits = keyword_hits(rule.get("keywords") or [], texts)
+    score = None
+    threshold = rule.get("threshold") or WATCH_SEMANTIC_THRESHOLD
+    if rule.get("query"):
+        for model, model_vectors in vectors.items():
+            similarity = best_similarity(embed_query(rule["query"], model), model_vectors)
+            if similarity is not None and (score is None or similarity > score):
+                score = similarity
+    semantic = score is not None and score >= threshold
+    if not hits and not semantic:
+        return None
+    return {
+        "rule_id": rule["id"],
+        "rule_name": rule.get("name"),
+        "keywords": hits,
+        "score": round(score, 4) if score is not None else None,
+        "threshold": threshold if rule.get("query") else None,
+        "semantic": semantic,
+    }
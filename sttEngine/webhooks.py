@@ -0,0 +1,271 @@
+"""Outgoing webhooks that deliver record artifacts when processing completes.
+
+A webhook is stored as ``DB/webhooks/{id}.json``::
+
+    {"id": "...", "name": "Notion 회의록 DB", "url": "https://api.notion.com/v1/pages",
+     "events": ["summary_completed"], "headers": {"Authorization": "Bearer ..."},
+     "body": {...} | "...", "enabled": true}
+
+When a step finishes, every enabled webhook subscribed to the event gets a
+POST in a background thread. Without ``body`` the request is the artifact
+context itself as JSON (see :data:`CONTEXT_FIELDS`), so simple receivers
+need no template. ``body`` reshapes it for systems like Notion:
+
+* a JSON object/array — every string inside is rendered with the minutes
+  template syntax (``{{title}}``, ``{{#decisions}}...{{/decisions}}``); a
+  string that is exactly one ``{{name}}`` tag is replaced by the raw value,
+  so lists and objects (``{{structured}}``) keep their JSON type;
+* a string — rendered as a text body (``content_type`` defaults to
+  ``text/plain``).
+
+Failed deliveries are retried ``WEBHOOK_MAX_ATTEMPTS`` times with backoff.
+"""
+
+from __future__ import annotations
+
+import json
+import re
+import threading
+import time
+import uuid
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Callable, Dict, List, Optional, Tuple
+from urllib.parse import urlparse
+
+import requests
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value, get_db_base_path
+    from .minutes_templates import TemplateError, render_template, validate_template
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value, get_db_base_path  # type: ignore
+    from minutes_templates import TemplateError, render_template, validate_template  # type: ignore
+
+WEBHOOKS_DIR = get_db_base_path() / "webhooks"
+WEBHOOK_TIMEOUT_SECONDS = get_config_value("WEBHOOK_TIMEOUT_SECONDS", 10, float)
+WEBHOOK_MAX_ATTEMPTS = max(1, get_config_value("WEBHOOK_MAX_ATTEMPTS", 3, int))
+# 전사/요약 링크를 외부에서 열 수 있도록 앞에 붙일 주소 (예: Cloudflare Tunnel 도메인)
+WEBHOOK_PUBLIC_BASE_URL = get_config_value("WEBHOOK_PUBLIC_BASE_URL", "", str).rstrip("/")
+
+EVENTS = ("stt_completed", "summary_completed", "watch_matched")
+CONTEXT_FIELDS = {
+    "event": "이벤트 이름 (stt_completed | summary_completed | watch_matched)",
+    "record_id": "기록 ID",
+    "title": "기록 제목 (파일명)",
+    "filename": "원본 파일명",
+    "created_at": "업로드 시각",
+    "completed_at": "처리 완료 시각",
+    "one_line_summary": "한 줄 요약",
+    "summary": "요약 전문 (마크다운)",
+    "summary_url": "요약 다운로드 주소",
+    "transcript_url": "전사 다운로드 주소",
+    "structured": "구조화 요약 {language, sections: [{key, heading, items}]}",
+    "topics": "주요 주제 목록 (decisions, action_items, key_points, risks, next_steps도 같음)",
+    "watch": "일치한 감시 규칙 {rule_id, rule_name, keywords: [{keyword, source, snippet}], score} (watch_matched만)",
+}
+_SINGLE_TAG = re.compile(r"^\s*{{\s*([\w.]+)\s*}}\s*$")
+_MASK = "********"
+_webhooks_lock = threading.Lock()
+
+
+class WebhookError(ValueError):
+    """Raised for invalid webhook definitions or unknown webhook ids."""
+
+
+def _webhook_path(webhook_id: str) -> Path:
+    if not re.fullmatch(r"[\w-]+", webhook_id or ""):
+        raise WebhookError("잘못된 웹훅 ID입니다.")
+    return WEBHOOKS_DIR / f"{webhook_id}.json"
+
+
+def _validate_body(body: Any) -> None:
+    """Check the template syntax of every string in ``body``."""
+    if isinstance(body, str):
+        validate_template(body)
+    elif isinstance(body, dict):
+        for value in body.values():
+            _validate_body(value)
+    elif isinstance(body, list):
+        for value in body:
+            _validate_body(value)
+
+
+def _apply_fields(webhook: Dict[str, Any], payload: Dict[str, Any]) -> None:
+    if "name" in payload:
+        if not isinstance(payload["name"], str) or not payload["name"].strip():
+            raise WebhookError("웹훅 이름(name)이 필요합니다.")
+        webhook["name"] = payload["name"].strip()
+    if "url" in payload:
+        parsed = urlparse(payload["url"] if isinstance(payload["url"], str) else "")
+        if parsed.scheme not in ("http", "https") or not parsed.netloc:
+            raise WebhookError("url은 http(s) 주소여야 합니다.")
+        webhook["url"] = payload["url"]
+    if "events" in payload:
+        events = payload["events"]
+        if not isinstance(events, list) or not events or any(event not in EVENTS for event in events):
+            raise WebhookError(f"events는 {', '.join(EVENTS)} 중 하나 이상이어야 합니다.")
+        webhook["events"] = list(dict.fromkeys(events))
+    if "headers" in payload:
+        headers = payload["headers"] or {}
+        if not isinstance(headers, dict) or not all(
+            isinstance(k, str) and isinstance(v, str) for k, v in headers.items()
+        ):
+            raise WebhookError("headers는 문자열 값을 가진 객체여야 합니다.")
+        # 목록 응답에서 가려진 값이 그대로 돌아오면 기존 값 유지
+        previous = webhook.get("headers") or {}
+        webhook["headers"] = {k: previous.get(k, v) if v == _MASK else v for k, v in headers.items()}
+    if "body" in payload:
+        body = payload["body"]
+        if body is not None and not isinstance(body, (str, dict, list)):
+            raise WebhookError("body는 문자열, 객체, 배열 중 하나여야 합니다.")
+        try:
+            _validate_body(body)
+        except TemplateError as exc:
+            raise WebhookError(f"body 템플릿 오류: {exc}") from None
+        webhook["body"] = body
+    if "content_type" in payload:
+        if payload["content_type"] is not None and not isinstance(payload["content_type"], str):
+            raise WebhookError("content_type은 문자열이어야 합니다.")
+        webhook["content_type"] = payload["content_type"]
+    if "enabled" in payload:
+        webhook["enabled"] = bool(payload["enabled"])
+
+
+def _write_webhook(webhook: Dict[str, Any]) -> None:
+    WEBHOOKS_DIR.mkdir(parents=True, exist_ok=True)
+    path = _webhook_path(webhook["id"])
+    tmp_path = path.with_suffix(".json.tmp")
+    with open(tmp_path, "w", encoding="utf-8") as f:
+        json.dump(webhook, f, ensure_ascii=False, indent=2)
+    tmp_path.replace(path)
+
+
+def masked(webhook: Dict[str, Any]) -> Dict[str, Any]:
+    """Copy of ``webhook`` with header values hidden (they usually hold API tokens)."""
+    return {**webhook, "headers": {key: _MASK for key in webhook.get("headers") or {}}}
+
+
+def list_webhooks() -> List[Dict[str, Any]]:
+    webhooks = []
+    if WEBHOOKS_DIR.exists():
+        for path in sorted(WEBHOOKS_DIR.glob("*.json")):
+            try:
+                with open(path, "r", encoding="utf-8") as f:
+                    webhooks.append(json.load(f))
+            except (OSError, json.JSONDecodeError):
+                continue
+    return webhooks
+
+
+def get_webhook(webhook_id: str) -> Dict[str, Any]:
+    path = _webhook_path(webhook_id)
+    if not path.exists():
+        raise WebhookError(f"웹훅을 찾을 수 없습니다: {webhook_id}")
+    with open(path, "r", encoding="utf-8") as f:
+        return json.load(f)
+
+
+def create_webhook(payload: Dict[str, Any]) -> Dict[str, Any]:
+    if "name" not in payload or "url" not in payload:
+        raise WebhookError("웹훅 이름(name)과 url이 필요합니다.")
+    now = datetime.now().isoformat()
+    webhook = {"id": str(uuid.uuid4()), "name": None, "url": None, "events": ["summary_completed"],
+               "headers": {}, "body": None,
+               "content_type": None, "enabled": True, "created_at": now, "updated_at": now}
+    _apply_fields(webhook, payload)
+    with _webhooks_lock:
+        _write_webhook(webhook)
+    return webhook
+
+
+def update_webhook(webhook_id: str, payload: Dict[str, Any]) -> Dict[str, Any]:
+    with _webhooks_lock:
+        webhook = get_webhook(webhook_id)
+        _apply_fields(webhook, payload)
+        webhook["updated_at"] = datetime.now().isoformat()
+        _write_webhook(webhook)
+    return webhook
+
+
+def delete_webhook(webhook_id: str) -> bool:
+    path = _webhook_path(webhook_id)
+    with _webhooks_lock:
+        if not path.exists():
+            return False
+        path.unlink()
+    return True
+
+
+def public_url(path: Optional[str]) -> Optional[str]:
+    if not path:
+        return None
+    return f"{WEBHOOK_PUBLIC_BASE_URL}{path}" if path.startswith("/") else path
+
+
+def _render_value(value: Any, context: Dict[str, Any]) -> Any:
+    if isinstance(value, str):
+        single = _SINGLE_TAG.match(value)
+        if single and single.group(1) in context:
+            return context[single.group(1)]
+        return render_template(value, context)
+    if isinstance(value, dict):
+        return {key: _render_value(item, context) for key, item in value.items()}
+    if isinstance(value, list):
+        return [_render_value(item, context) for item in value]
+    return value
+
+
+def render_body(webhook: Dict[str, Any], context: Dict[str, Any]) -> Tuple[bytes, str]:
+    """Request body and content type for ``webhook`` filled from ``context``."""
+    body = webhook.get("body")
+    if body is None:
+        return json.dumps(context, ensure_ascii=False).encode("utf-8"), "application/json"
+    if isinstance(body, str):
+        return render_template(body, context).encode("utf-8"), webhook.get("content_type") or "text/plain; charset=utf-8"
+    rendered = _render_value(body, context)
+    return json.dumps(rendered, ensure_ascii=False).encode("utf-8"), webhook.get("content_type") or "application/json"
+
+
+def deliver(webhook: Dict[str, Any], context: Dict[str, Any]) -> Dict[str, Any]:
+    """POST the rendered body, retrying failures; returns a delivery report."""
+    data, content_type = render_body(webhook, context)
+    headers = {"Content-Type": content_type, **(webhook.get("headers") or {})}
+    error = None
+    for attempt in range(1, WEBHOOK_MAX_ATTEMPTS + 1):
+        try:
+            response = requests.post(webhook["url"], data=data, headers=headers, timeout=WEBHOOK_TIMEOUT_SECONDS)
+            if response.status_code < 400:
+                return {"webhook_id": webhook["id"], "ok": True, "status": response.status_code, "attempts": attempt}
+            error = f"HTTP {response.status_code}: {response.text[:200]}"
+            if 400 <= response.status_code < 500 and response.status_code not in (408, 429):
+                break  # 요청 자체가 잘못된 경우 재시도해도 같음
+        except requests.RequestException as exc:
+            error = str(exc)
+        if attempt < WEBHOOK_MAX_ATTEMPTS:
+            time.sleep(2 ** (attempt - 1))
+    return {"webhook_id": webhook["id"], "ok": False, "error": error, "attempts": attempt}
+
+
+def dispatch(event: str, context: Dict[str, Any],
+             on_result: Optional[Callable[[Dict[str, Any]], None]] = None) -> int:
+    """Deliver ``event`` to every enabled subscribed webhook in the background.
+
+    Returns the number of webhooks notified; ``on_result`` receives each
+    delivery report.
+    """
+    targets = [hook for hook in list_webhooks() if hook.get("enabled", True) and event in hook.get("events", [])]
+
+    def run(webhook: Dict[str, Any]) -> None:
+        try:
+            result = deliver(webhook, {**context, "event": event})
+        except Exception as exc:  # pragma: no cover - defensive
+            result = {"webhook_id": webhook.get("id"), "ok": False, "error": str(exc), "attempts": 0}
+        if not result["ok"]:
+            print(f"웹훅 전송 실패 ({webhook.get('name')}): {result['error']}")
+        if on_result:
+            on_result(result)
+
+    for webhook in targets:
+        threading.Thread(target=run, args=(webhook,), daemon=True).start()
+    return len(targets)
@@ -1,12 +1,15 @@
 # summarize.py
 import argparse
+import hashlib
 import json
 import logging
+import math
 import platform
 import re
 import sys
+import threading
 from pathlib import Path
-from typing import Dict, List, Optional
+from typing import Callable, Dict, Iterator, List, Optional, Union
 import time
 from concurrent.futures import ThreadPoolExecutor, TimeoutError as FutureTimeoutError
 
@@ -20,10 +23,18 @@ except ImportError:
 sys.path.append(str(Path(__file__).parent.parent))
 from config import get_model_for_task, get_default_model, get_config_value
 from logger import setup_logging
+from artifact_store import detach as detach_artifact
 from obsidian_mcp import send_summary_to_obsidian_sync
 
 setup_logging()
-from ollama_utils import ensure_ollama_server, check_ollama_model_available, safe_ollama_call
+from ollama_utils import ensure_ollama_server, check_ollama_model_available, get_model_context_length, safe_ollama_call
+from text_utils import clip_display, truncate_graphemes
+from overlap_dedup import dedup_chunk_texts
+from deadline import check_deadline, deadline_timeout
+from incremental_summary import SUMMARY_INCREMENTAL_CHUNK_BYTES, split_stable_chunks
+import summary_chunking
+from summary_chunking import ChunkPlan, estimate_tokens, plan_chunks, split_chunks
+from structured_summary import StructuredSummary, StructuredSummaryError, parse_structured_summary
 
 # 설정 상수 - .env 파일에서 로드
 try:
@@ -38,23 +49,36 @@ except:
 DEFAULT_CHUNK_SIZE = get_config_value("DEFAULT_CHUNK_SIZE", 32000, int)  # 청킹 크기 증가로 불필요한 분할 방지
 DEFAULT_TEMPERATURE = get_config_value("DEFAULT_TEMPERATURE_SUMMARY", 0.2, float)
 DEFAULT_NUM_CTX = get_config_value("DEFAULT_NUM_CTX", 8192, int)
+# --target-chunks로 청크를 더 잘게 나눌 때의 최소 청크 크기 (토큰)
+MIN_TARGET_CHUNK_TOKENS = 1500
 MAX_RETRIES = get_config_value("MAX_RETRIES", 3, int)
 RETRY_DELAY = get_config_value("RETRY_DELAY", 2, int)
 OLLAMA_TIMEOUT = get_config_value("OLLAMA_TIMEOUT", 300, int)  # 5분 타임아웃
+# 요약 출력 언어 (auto = 원문 문자로 판단, 판단이 어려우면 ko)
+SUMMARY_LANGUAGE = get_config_value("SUMMARY_LANGUAGE", "auto", str).strip().lower()
+# 전사 언어별 요약 모델 ("ko=eeve-korean:10.8b,en=llama3.2", 요청에 모델이 없을 때만 적용)
+SUMMARY_MODEL_BY_LANGUAGE = get_config_value("SUMMARY_MODEL_BY_LANGUAGE", "", str)
 
-# 프롬프트 템플릿
-BASE_PROMPT = """당신은 전문 요약가입니다. 다음 텍스트를 간결하고 구조화된 한국어 요약으로 작성합니다.
+# 요약 섹션: 언어와 무관한 고정 키 (JSON 출력/회의록 템플릿 변수) 순서대로
+SUMMARY_SECTIONS = ("topics", "key_points", "decisions", "action_items", "risks", "next_steps")
+
+# 언어별 섹션 제목 (SUMMARY_SECTIONS 순서)
+SECTION_HEADINGS: Dict[str, List[str]] = {
+    "ko": ["주요 주제", "핵심 내용", "결정 사항", "실행 항목", "리스크/이슈", "차기 일정"],
+    "en": ["Main Topics", "Key Points", "Decisions", "Action Items", "Risks/Issues", "Next Steps"],
+    "ja": ["主なテーマ", "要点", "決定事項", "アクションアイテム", "リスク/課題", "今後の予定"],
+    "zh": ["主要议题", "核心内容", "决定事项", "行动项", "风险/问题", "后续安排"],
+}
+LANGUAGE_NAMES = {"ko": "한국어", "en": "영어(English)", "ja": "일본어(日本語)", "zh": "중국어(中文)"}
+
+# 프롬프트 템플릿 ({language}, {sections}는 요약 언어에 맞게 채워짐)
+BASE_PROMPT = """당신은 전문 요약가입니다. 다음 텍스트를 간결하고 구조화된 {language} 요약으로 작성합니다.
 
 지침:
 - 불렛 포인트를 사용합니다.
 - 사실에만 근거합니다. 해석/추정/의견 금지.
 - 섹션 제목은 다음 순서를 고정합니다:
-  1) 주요 주제
-  2) 핵심 내용
-  3) 결정 사항
-  4) 실행 항목
-  5) 리스크/이슈
-  6) 차기 일정
+{sections}
 
 출력은 반드시 위 6개 섹션만 포함합니다."""
 
@@ -72,6 +96,115 @@ REDUCE_PROMPT = BASE_PROMPT + """
 {summaries}
 ---"""
 
+# 같은 시리즈의 이전 회의 요약 (meeting_series.py) — 단일/최종 리듀스 프롬프트 뒤에 붙임
+SERIES_CONTEXT_PROMPT = """
+
+참고: 아래는 같은 회의 시리즈의 이전 회의 요약입니다(오래된 순). 요약 대상이 아니라 비교용입니다.
+- 이전 회의와 달라진 내용(새 결정, 바뀐 일정/수치)은 항목 앞에 "(변경)"을 붙입니다.
+- 이전 회의의 실행 항목 중 이번에도 언급되거나 완료되지 않은 것은 실행 항목에 "(이월)"을 붙여 포함합니다.
+- 이번 회의에서 언급되지 않은 이전 내용은 추가하지 않습니다.
+---
+{series_context}
+---"""
+
+def with_series_context(prompt: str, series_context: Optional[str]) -> str:
+    """이전 회의 요약이 있으면 비교 지침과 함께 프롬프트 뒤에 붙인다."""
+    if not series_context:
+        return prompt
+    return prompt + SERIES_CONTEXT_PROMPT.format(series_context=series_context)
+
+PEOPLE_CONTEXT_PROMPT = """
+
+참고: 아래는 이 회의의 참석자이거나 언급된 인물입니다. 별칭이나 다른 표기로 나오더라도 요약에서는 이 이름으로 씁니다.
+{people_context}"""
+
+# SUMMARY_FORMAT=structured: 마지막 요약 호출에만 붙여 JSON으로 답하게 함 (청크 요약은 그대로 마크다운)
+STRUCTURED_OUTPUT_PROMPT = """
+
+출력 형식: 위 섹션 제목을 쓰지 말고 아래 키를 가진 JSON 객체 하나만 출력합니다 (코드 블록, 설명 금지).
+값은 {language}로 쓰고, 해당 내용이 없으면 빈 배열을 씁니다.
+{{
+  "topics": ["주요 주제"],
+  "key_points": ["핵심 내용"],
+  "decisions": ["결정 사항"],
+  "action_items": [{{"task": "할 일", "owner": "담당자 (없으면 null)", "due": "기한 (없으면 null)"}}],
+  "risks": ["리스크/이슈"],
+  "next_steps": [{{"item": "차기 일정", "date": "날짜/시각 (없으면 null)"}}]
+}}"""
+
+def with_structured_output(prompt: str, language: str) -> str:
+    """구조화 요약이면 JSON 출력 지침을 프롬프트 뒤에 붙인다."""
+    return prompt + STRUCTURED_OUTPUT_PROMPT.format(language=LANGUAGE_NAMES[language])
+
+def with_people_context(prompt: str, people_context: Optional[str]) -> str:
+    """인물 목록이 있으면 이름 표기 지침과 함께 프롬프트 뒤에 붙인다."""
+    if not people_context:
+        return prompt
+    return prompt + PEOPLE_CONTEXT_PROMPT.format(people_context=people_context)
+
+def get_prompt_version() -> str:
+    """현재 요약 프롬프트(CHUNK/REDUCE)의 버전 해시
+
+    /admin/reload로 프롬프트가 바뀌면 값이 달라지므로, 요약마다 저장해 두고
+    이전 프롬프트로 만든 요약을 찾아 재생성하는 데 사용한다.
+    """
+    digest = hashlib.sha256(f"{CHUNK_PROMPT}\0{REDUCE_PROMPT}".encode("utf-8")).hexdigest()
+    return digest[:12]
+
+def detect_summary_language(text: str) -> str:
+    """원문에 가장 많이 쓰인 문자 체계로 요약 언어를 고른다 (판단이 어려우면 ko)."""
+    sample = text[:20000]
+    counts = {
+        "ko": len(re.findall(r"[\uac00-\ud7a3]", sample)),
+        "ja": len(re.findall(r"[\u3040-\u30ff]", sample)),
+        "zh": len(re.findall(r"[\u4e00-\u9fff]", sample)),
+        "en": len(re.findall(r"[A-Za-z]", sample)) // 4,  # 알파벳은 글자 수가 많으므로 보정
+    }
+    # 일본어 문장에도 한자가 섞이므로 가나가 있으면 일본어로 본다
+    if counts["ja"] and counts["ja"] * 3 >= counts["zh"]:
+        counts["ja"] += counts["zh"]
+    language, count = max(counts.items(), key=lambda item: item[1])
+    return language if count else "ko"
+
+
+def resolve_summary_language(language: Optional[str], text: str = "") -> str:
+    """요청/설정 언어를 지원 언어로 정리 (auto면 원문으로 판단)."""
+    language = (language or SUMMARY_LANGUAGE or "auto").strip().lower()
+    if language == "auto":
+        return detect_summary_language(text)
+    return language if language in SECTION_HEADINGS else "ko"
+
+
+def parse_language_models(value: Optional[str]) -> Dict[str, str]:
+    """``"ko=모델,en=모델"`` 형식을 {언어 코드: 모델}로 바꾼다 (잘못된 항목은 경고 후 무시)."""
+    mapping: Dict[str, str] = {}
+    for entry in (value or "").split(","):
+        if not entry.strip():
+            continue
+        language, sep, model = entry.partition("=")
+        language, model = language.strip().lower(), model.strip()
+        if not sep or not language or not model:
+            logging.warning(f"SUMMARY_MODEL_BY_LANGUAGE 항목을 무시합니다: {entry.strip()!r} (형식: 언어=모델)")
+            continue
+        mapping[language] = model
+    return mapping
+
+
+def summary_model_for_language(language: Optional[str]) -> Optional[str]:
+    """전사 언어에 지정된 요약 모델 (없으면 None, ``*`` 항목은 나머지 언어용)."""
+    mapping = parse_language_models(SUMMARY_MODEL_BY_LANGUAGE)
+    language = (language or "").strip().lower()
+    # "en-US"처럼 지역이 붙은 코드는 기본 언어로도 찾음
+    return mapping.get(language) or mapping.get(language.split("-")[0]) or mapping.get("*")
+
+
+def build_prompt(template: str, language: str, **values: str) -> str:
+    """프롬프트 템플릿에 요약 언어와 섹션 제목을 채운다."""
+    headings = SECTION_HEADINGS[language]
+    sections = "\n".join(f"  {index}) {heading}" for index, heading in enumerate(headings, 1))
+    return template.format(language=LANGUAGE_NAMES[language], sections=sections, **values)
+
+
 class SummarizationError(Exception):
     """요약 처리 중 발생하는 예외"""
     pass
@@ -156,7 +289,7 @@ def remove_timestamps(text: str) -> str:
             # 반복 텍스트 필터링 추가
             if is_repetitive_text(cleaned_line):
                 repetitive_filtered += 1
-                logging.debug(f"반복 텍스트 필터링: '{cleaned_line[:50]}...'")
+                logging.debug(f"반복 텍스트 필터링: '{clip_display(cleaned_line, 50)}'")
                 continue
             
             meaningful_lines.append(cleaned_line)
@@ -275,20 +408,56 @@ def chunk_text(text: str, max_bytes: int, target_chunks: Optional[int] = None) -
     
     return final_chunks
 
+def stream_ollama_chat(model: str, prompt: str, options: dict) -> Iterator[str]:
+    """Ollama chat 응답을 생성되는 대로 조각(str) 단위로 반환 (stream=True)"""
+    parts = safe_ollama_call(
+        ollama.chat,
+        model=model,
+        messages=[{"role": "user", "content": prompt}],
+        options=options,
+        stream=True,
+    )
+    for part in parts:
+        try:
+            content = part["message"]["content"]
+        except (TypeError, KeyError):
+            raise SummarizationError(f"스트리밍 응답 조각에 'message.content' 키가 없습니다: {type(part)}")
+        if content:
+            yield content
+
+
 def call_ollama_with_timeout(
     model: str,
     prompt: str,
     options: dict,
-    timeout: int = OLLAMA_TIMEOUT
+    timeout: int = OLLAMA_TIMEOUT,
+    response_format: Optional[str] = None,
+    on_token: Optional[Callable[[Optional[str]], None]] = None
 ) -> str:
-    """타임아웃을 적용한 Ollama 호출"""
+    """타임아웃을 적용한 Ollama 호출 (response_format="json"이면 JSON만 출력하게 함)
+
+    on_token이 주어지면 응답을 스트리밍으로 받아 조각마다 on_token(조각)을 호출한다.
+    """
+    stopped = threading.Event()
+
     def _call_ollama():
+        if on_token is not None:
+            pieces = []
+            for piece in stream_ollama_chat(model, prompt, options):
+                # 타임아웃 뒤에도 도는 스트림이 재시도의 조각과 섞이지 않게 멈춤
+                if stopped.is_set():
+                    break
+                pieces.append(piece)
+                on_token(piece)
+            return {"message": {"role": "assistant", "content": "".join(pieces)}}
+        extra = {"format": response_format} if response_format else {}
         return safe_ollama_call(
             ollama.chat,
             model=model,
             messages=[{"role": "user", "content": prompt}],
             options=options,
             stream=False,
+            **extra,
         )
     
     with ThreadPoolExecutor(max_workers=1) as executor:
@@ -298,6 +467,7 @@ def call_ollama_with_timeout(
             return response
         except FutureTimeoutError:
             logging.error(f"Ollama 호출 타임아웃 ({timeout}초)")
+            stopped.set()
             future.cancel()
             raise SummarizationError(f"Ollama 호출이 {timeout}초 내에 완료되지 않음")
 
@@ -305,23 +475,39 @@ def call_ollama_with_retry(
     model: str, 
     prompt: str, 
     temperature: float = DEFAULT_TEMPERATURE,
-    num_ctx: int = DEFAULT_NUM_CTX,
-    max_tokens: Optional[int] = None
+    num_ctx: Optional[int] = None,
+    max_tokens: Optional[int] = None,
+    extra_options: Optional[dict] = None,
+    response_format: Optional[str] = None,
+    on_token: Optional[Callable[[Optional[str]], None]] = None
 ) -> str:
-    """재시도 로직과 타임아웃을 포함한 Ollama 호출"""
+    """재시도 로직과 타임아웃을 포함한 Ollama 호출
+
+    extra_options는 검증된 Ollama 옵션(model_options.validate_model_options)으로,
+    위의 기본 옵션보다 우선한다. on_token이 주어지면 응답 조각을 생성되는 대로 넘기고,
+    재시도할 때는 그때까지 보낸 조각을 버리라는 뜻으로 on_token(None)을 먼저 호출한다.
+    """
     options = {
         "temperature": temperature,
-        "num_ctx": num_ctx,
+        "num_ctx": num_ctx or DEFAULT_NUM_CTX,
     }
     
     if max_tokens:
         options["num_predict"] = max_tokens
+    if extra_options:
+        options.update(extra_options)
     
     for attempt in range(MAX_RETRIES):
+        # 요청 제한 시간(deadline.py)이 지났으면 재시도하지 않고 중단
+        check_deadline("요약")
         try:
             logging.debug(f"모델 호출 시도 {attempt + 1}/{MAX_RETRIES}")
-            
-            response = call_ollama_with_timeout(model, prompt, options, OLLAMA_TIMEOUT)
+            if on_token is not None and attempt:
+                on_token(None)
+
+            response = call_ollama_with_timeout(
+                model, prompt, options, deadline_timeout(OLLAMA_TIMEOUT), response_format, on_token
+            )
 
             # 응답 형식 처리
             try:
@@ -341,10 +527,76 @@ def call_ollama_with_retry(
             logging.warning(f"모델 호출 실패 (시도 {attempt + 1}): {e}")
             if attempt < MAX_RETRIES - 1:
                 logging.info(f"{RETRY_DELAY}초 후 재시도...")
-                time.sleep(RETRY_DELAY)
+                time.sleep(deadline_timeout(RETRY_DELAY))
             else:
+                check_deadline("요약")
                 raise SummarizationError(f"모든 재시도 실패: {e}")
 
+def plan_summary(
+    text: str,
+    model: str,
+    chunk_size: int = DEFAULT_CHUNK_SIZE,
+    max_tokens: Optional[int] = None,
+    model_options: Optional[dict] = None,
+    language: Optional[str] = None,
+    series_context: Optional[str] = None,
+    people_context: Optional[str] = None
+) -> ChunkPlan:
+    """요약 한 번의 청크 분할 계획 (summary_chunking 참고)
+
+    프롬프트 템플릿과 시리즈/인물 컨텍스트를 뺀 입력 예산으로 청크 수와 크기,
+    num_ctx를 정한다. model_options의 num_ctx가 있으면 그 값을 그대로 쓴다.
+    """
+    language = resolve_summary_language(language, text)
+    cleaned_text = strip_prefix_before_bracket(text)
+    prompt_tokens = max(
+        estimate_tokens(with_people_context(with_series_context(
+            build_prompt(CHUNK_PROMPT, language, chunk=""), series_context), people_context)),
+        estimate_tokens(with_people_context(with_series_context(
+            build_prompt(REDUCE_PROMPT, language, summaries=""), series_context), people_context)),
+    )
+    input_tokens = estimate_tokens(cleaned_text)
+    num_ctx = (model_options or {}).get("num_ctx")
+    if summary_chunking.SUMMARY_CHUNKING == "fixed":
+        # 기존 방식: 바이트 단위 분할, 고정 num_ctx, 10개씩 배치 리듀스
+        num_ctx = num_ctx or DEFAULT_NUM_CTX
+        chunks = max(1, len(chunk_text(cleaned_text, chunk_size)))
+        return ChunkPlan(
+            mode="fixed", model_context=None, num_ctx=num_ctx, input_tokens=input_tokens,
+            prompt_tokens=prompt_tokens, output_tokens=max_tokens or 0,
+            budget_tokens=num_ctx - prompt_tokens - (max_tokens or 0), chunk_tokens=math.ceil(input_tokens / chunks),
+            overlap_tokens=0, chunks=chunks, reduce_batch=10, estimated_calls=chunks + (1 if chunks > 1 else 0),
+        )
+    try:
+        return plan_chunks(input_tokens, prompt_tokens, get_model_context_length(model), DEFAULT_NUM_CTX,
+                           num_ctx=num_ctx, output_tokens=max_tokens)
+    except ValueError as e:
+        raise SummarizationError(str(e)) from None
+
+def call_structured_summary(
+    model: str,
+    prompt: str,
+    language: str,
+    temperature: float,
+    num_ctx: Optional[int],
+    max_tokens: Optional[int],
+    model_options: Optional[dict]
+) -> StructuredSummary:
+    """JSON 형식으로 마지막 요약을 받아 검증 (형식이 틀리면 MAX_RETRIES까지 다시 요청)"""
+    prompt = with_structured_output(prompt, language)
+    error = None
+    for attempt in range(MAX_RETRIES):
+        answer = call_ollama_with_retry(
+            model, prompt, temperature, num_ctx=num_ctx, max_tokens=max_tokens,
+            extra_options=model_options, response_format="json"
+        )
+        try:
+            return parse_structured_summary(answer, language)
+        except StructuredSummaryError as e:
+            error = e
+            logging.warning(f"구조화 요약 형식 오류 (시도 {attempt + 1}/{MAX_RETRIES}): {e}")
+    raise SummarizationError(f"구조화 요약을 해석하지 못했습니다: {error}")
+
 def summarize_text_mapreduce(
     text: str,
     model: str,
@@ -352,18 +604,48 @@ def summarize_text_mapreduce(
     max_tokens: Optional[int],
     temperature: float = DEFAULT_TEMPERATURE,
     progress_callback=None,
-    target_chunks: Optional[int] = None
-) -> str:
-    """맵-리듀스 패턴으로 텍스트 요약"""
+    target_chunks: Optional[int] = None,
+    trace=None,
+    model_options: Optional[dict] = None,
+    language: Optional[str] = None,
+    series_context: Optional[str] = None,
+    people_context: Optional[str] = None,
+    chunk_cache=None,
+    chunk_plan: Optional[ChunkPlan] = None,
+    structured: bool = False,
+    on_token: Optional[Callable[[Optional[str]], None]] = None
+) -> Union[str, StructuredSummary]:
+    """맵-리듀스 패턴으로 텍스트 요약
+
+    trace가 주어지면 각 단계의 프롬프트와 응답을 ``trace.record(stage, prompt, output, **details)``로 넘긴다.
+    language는 요약 출력 언어(ko/en/ja/zh/auto)로, 섹션 제목도 그 언어로 쓴다.
+    series_context(같은 시리즈의 이전 회의 요약)는 마지막 단계 프롬프트에만 넣어
+    변경 사항과 이월된 실행 항목을 표시하게 한다.
+    people_context(인물 디렉터리의 참석자/언급 인물 목록)는 청크와 마지막 단계 프롬프트에 넣어
+    별칭으로 불린 사람도 같은 이름으로 쓰게 한다.
+    chunk_cache(incremental_summary.ChunkSummaryCache)가 주어지면 내용 기준으로 청크를 나누고
+    캐시에 있는 청크 요약은 다시 만들지 않는다 (전사 일부 수정 후 재요약용).
+    chunk_plan(plan_summary 결과)이 없으면 여기서 계산한다. 청크 크기, num_ctx, 배치 리듀스 크기를
+    그 계획대로 쓰고 trace.metadata["chunk_plan"]에 남긴다.
+    structured이면 마지막 호출(단일 청크 또는 최종 리듀스)만 JSON으로 받아
+    문자열 대신 StructuredSummary를 반환한다 (structured_summary.py).
+    on_token이 주어지면 마지막 호출을 스트리밍으로 받아 조각마다 넘긴다 (call_ollama_with_retry 참고,
+    structured일 때는 스트리밍하지 않음).
+    """
+    def record_step(stage: str, prompt: str, output: str, **details) -> None:
+        if trace is not None:
+            trace.record(stage, prompt, output, **details)
+
+    language = resolve_summary_language(language, text)
     if not text.strip():
-        return "요약할 내용이 없습니다."
+        return StructuredSummary(language=language) if structured else "요약할 내용이 없습니다."
     
     # 디버깅: 입력 텍스트 크기 확인
     original_bytes = len(text.encode('utf-8'))
     original_chars = len(text)
     original_lines = len(text.splitlines())
     print(f"[DEBUG] 입력 텍스트 크기: {original_chars:,} 문자, {original_bytes:,} bytes, {original_lines:,} 줄")
-    print(f"[DEBUG] 텍스트 첫 200자: {repr(text[:200])}")
+    print(f"[DEBUG] 텍스트 첫 200자: {repr(truncate_graphemes(text, 200, ellipsis=''))}")
     
     # 요약 프롬프트 토큰 절감을 위해 각 줄의 접두사 제거 (원본 파일은 수정하지 않음)
     cleaned_text = strip_prefix_before_bracket(text)
@@ -372,17 +654,53 @@ def summarize_text_mapreduce(
         f"접두사 제거 완료: {original_bytes:,} bytes → {cleaned_bytes:,} bytes ({original_bytes - cleaned_bytes:,} bytes 감소)"
     )
     
-    chunks = chunk_text(cleaned_text, chunk_size, target_chunks)
-    logging.info(f"텍스트 분할 완료: {len(chunks)}개 청크 (청크당 최대 {chunk_size:,} bytes, 전체 {cleaned_bytes:,} bytes)")
+    plan = chunk_plan or plan_summary(
+        text, model, chunk_size, max_tokens, model_options, language, series_context, people_context
+    )
+    adaptive = plan.mode == "adaptive"
+    num_ctx = plan.num_ctx
+    logging.info(
+        f"청크 계획({plan.mode}): 입력 약 {plan.input_tokens:,} 토큰, num_ctx {num_ctx:,}, "
+        f"청크 {plan.chunks}개 × 약 {plan.chunk_tokens:,} 토큰 (겹침 {plan.overlap_tokens}), 예상 호출 {plan.estimated_calls}회"
+    )
+    if trace is not None:
+        trace.metadata["chunk_plan"] = plan.to_dict()
+
+    if chunk_cache is not None:
+        # 캐시 재사용을 위해 내용 기준 경계를 유지하되 입력 예산을 넘지 않게 함
+        max_bytes = SUMMARY_INCREMENTAL_CHUNK_BYTES
+        if adaptive:
+            # 토큰당 3바이트 이상(한글 1글자 ≈ 3바이트 ≈ 1토큰)이라 바이트 한도로 보수적으로 환산
+            max_bytes = min(max_bytes, plan.budget_tokens * 3)
+        chunks = split_stable_chunks(cleaned_text, max_bytes)
+    elif adaptive:
+        chunk_tokens = plan.chunk_tokens
+        if target_chunks and target_chunks > plan.chunks:
+            chunk_tokens = max(MIN_TARGET_CHUNK_TOKENS, math.ceil(plan.input_tokens / target_chunks))
+        if chunk_tokens < plan.input_tokens:
+            chunks = split_chunks(cleaned_text, chunk_tokens, plan.overlap_tokens)
+        else:
+            chunks = [cleaned_text]
+    else:
+        chunks = chunk_text(cleaned_text, chunk_size, target_chunks)
+    logging.info(f"텍스트 분할 완료: {len(chunks)}개 청크 (전체 {cleaned_bytes:,} bytes)")
     
     if len(chunks) == 0:
-        return "분할된 청크가 없습니다."
+        return StructuredSummary(language=language) if structured else "분할된 청크가 없습니다."
     
     # 단일 청크인 경우 직접 요약
     if len(chunks) == 1:
         logging.info("단일 청크 요약 수행")
-        prompt = CHUNK_PROMPT.format(chunk=chunks[0])
-        return call_ollama_with_retry(model, prompt, temperature, max_tokens=max_tokens)
+        prompt = build_prompt(CHUNK_PROMPT, language, chunk=chunks[0])
+        prompt = with_people_context(with_series_context(prompt, series_context), people_context)
+        if structured:
+            result = call_structured_summary(model, prompt, language, temperature, num_ctx, max_tokens, model_options)
+            record_step("single", prompt, json.dumps(result.to_dict(), ensure_ascii=False, indent=2), structured=True)
+            return result
+        summary = call_ollama_with_retry(model, prompt, temperature, num_ctx=num_ctx, max_tokens=max_tokens,
+                                         extra_options=model_options, on_token=on_token)
+        record_step("single", prompt, summary)
+        return summary
     
     # 다중 청크 처리 시작 알림
     logging.info("텍스트가 길어 분할 처리중...")
@@ -403,13 +721,23 @@ def summarize_text_mapreduce(
         logging.debug(f"청크 크기: {chunk_bytes:,} bytes")
         
         try:
-            prompt = CHUNK_PROMPT.format(chunk=chunk)
+            prompt = with_people_context(build_prompt(CHUNK_PROMPT, language, chunk=chunk), people_context)
+            cache_key = chunk_cache.key(model, prompt, temperature, model_options) if chunk_cache is not None else None
+            cached = chunk_cache.get(cache_key) if cache_key else None
+            if cached is not None:
+                # 바뀌지 않은 청크는 이전 요약 재사용
+                chunk_summaries.append(cached)
+                record_step("chunk", prompt, cached, index=i, total=len(chunks), cached=True)
+                continue
             prompt_bytes = len(prompt.encode('utf-8'))
             prompt_chars = len(prompt)
             print(f"[DEBUG] 청크 {i} 프롬프트 크기: {prompt_chars:,} 문자, {prompt_bytes:,} bytes")
-            print(f"[DEBUG] 청크 {i} 내용 첫 200자: {repr(chunk[:200])}")
-            summary = call_ollama_with_retry(model, prompt, temperature, max_tokens=max_tokens)
+            print(f"[DEBUG] 청크 {i} 내용 첫 200자: {repr(truncate_graphemes(chunk, 200, ellipsis=''))}")
+            summary = call_ollama_with_retry(model, prompt, temperature, num_ctx=num_ctx, max_tokens=max_tokens, extra_options=model_options)
             chunk_summaries.append(summary)
+            if cache_key:
+                chunk_cache.put(cache_key, summary)
+            record_step("chunk", prompt, summary, index=i, total=len(chunks))
             
             summary_bytes = len(summary.encode('utf-8'))
             logging.debug(f"청크 {i} 요약 완료 (크기: {summary_bytes:,} bytes)")
@@ -419,11 +747,15 @@ def summarize_text_mapreduce(
     
     # 2단계: 청크 요약들을 통합 요약 (Reduce)
     logging.info("2단계: 통합 요약 시작")
+    # 청크 경계에서 이웃 요약이 되풀이한 문장 제거
+    chunk_summaries, duplicated = dedup_chunk_texts(chunk_summaries)
+    if duplicated:
+        logging.info(f"청크 경계 중복 문장 {duplicated}개 제거")
     combined_summaries = '\n\n---청크 요약 구분선---\n\n'.join(chunk_summaries)
     
     # 배치 리듀스: 청크 요약이 많을 때 계층적 처리
     num_chunks = len(chunk_summaries)
-    batch_size = 10  # K=10개씩 1차 리듀스
+    batch_size = plan.reduce_batch  # K개씩 1차 리듀스 (고정 모드는 10)
     
     if num_chunks > batch_size:
         logging.info(f"청크 요약 {num_chunks}개가 많아 배치 리듀스 적용 (배치 크기: {batch_size})")
@@ -445,9 +777,10 @@ def summarize_text_mapreduce(
                 progress_callback(progress_msg)
             
             batch_combined = '\n\n---청크 요약 구분선---\n\n'.join(batch_chunk_summaries)
-            batch_prompt = REDUCE_PROMPT.format(summaries=batch_combined)
-            batch_summary = call_ollama_with_retry(model, batch_prompt, temperature, max_tokens=max_tokens)
+            batch_prompt = build_prompt(REDUCE_PROMPT, language, summaries=batch_combined)
+            batch_summary = call_ollama_with_retry(model, batch_prompt, temperature, num_ctx=num_ctx, max_tokens=max_tokens, extra_options=model_options)
             batch_summaries.append(batch_summary)
+            record_step("batch_reduce", batch_prompt, batch_summary, index=batch_idx + 1, total=num_batches)
         
         # 2차 파이널 리듀스: 1차 리듀스 결과들을 최종 통합
         logging.info(f"2차 파이널 리듀스: {len(batch_summaries)}개 배치 요약 통합")
@@ -455,16 +788,23 @@ def summarize_text_mapreduce(
             progress_callback("최종 통합 요약 생성중...")
         
         final_combined = '\n\n---배치 요약 구분선---\n\n'.join(batch_summaries)
-        reduce_prompt = REDUCE_PROMPT.format(summaries=final_combined)
+        reduce_prompt = build_prompt(REDUCE_PROMPT, language, summaries=final_combined)
     else:
         # 청크 수가 적으면 기존 방식 사용
         combined_summaries = '\n\n---청크 요약 구분선---\n\n'.join(chunk_summaries)
         combined_bytes = len(combined_summaries.encode('utf-8'))
         logging.debug(f"통합 요약 크기: {combined_bytes:,} bytes")
         
-        if combined_bytes > chunk_size * 2:  # 안전 마진 적용
+        if adaptive:
+            oversized = estimate_tokens(combined_summaries) > plan.budget_tokens
+        else:
+            oversized = combined_bytes > chunk_size * 2  # 안전 마진 적용
+        if oversized:
             logging.info(f"통합 요약이 클 수 있음 ({combined_bytes:,} bytes). 재귀적 요약 적용")
-            summary_chunks = chunk_text(combined_summaries, chunk_size)
+            if adaptive:
+                summary_chunks = split_chunks(combined_summaries, plan.budget_tokens)
+            else:
+                summary_chunks = chunk_text(combined_summaries, chunk_size)
             if len(summary_chunks) > 1:
                 logging.info(f"청크 요약을 {len(summary_chunks)}개 그룹으로 재분할")
                 final_summaries = []
@@ -473,32 +813,54 @@ def summarize_text_mapreduce(
                     logging.info(progress_msg)
                     if progress_callback:
                         progress_callback(progress_msg)
-                    group_prompt = REDUCE_PROMPT.format(summaries=summary_chunk)
-                    group_summary = call_ollama_with_retry(model, group_prompt, temperature, max_tokens=max_tokens)
+                    group_prompt = build_prompt(REDUCE_PROMPT, language, summaries=summary_chunk)
+                    group_summary = call_ollama_with_retry(model, group_prompt, temperature, num_ctx=num_ctx, max_tokens=max_tokens, extra_options=model_options)
                     final_summaries.append(group_summary)
+                    record_step("group_reduce", group_prompt, group_summary, index=i, total=len(summary_chunks))
                 
                 final_combined = '\n\n---최종 통합 구분선---\n\n'.join(final_summaries)
-                reduce_prompt = REDUCE_PROMPT.format(summaries=final_combined)
+                reduce_prompt = build_prompt(REDUCE_PROMPT, language, summaries=final_combined)
             else:
-                reduce_prompt = REDUCE_PROMPT.format(summaries=combined_summaries)
+                reduce_prompt = build_prompt(REDUCE_PROMPT, language, summaries=combined_summaries)
         else:
-            reduce_prompt = REDUCE_PROMPT.format(summaries=combined_summaries)
+            reduce_prompt = build_prompt(REDUCE_PROMPT, language, summaries=combined_summaries)
     
-    final_summary = call_ollama_with_retry(model, reduce_prompt, temperature, max_tokens=max_tokens)
+    reduce_prompt = with_people_context(with_series_context(reduce_prompt, series_context), people_context)
+    if structured:
+        result = call_structured_summary(model, reduce_prompt, language, temperature, num_ctx, max_tokens, model_options)
+        record_step("final_reduce", reduce_prompt, json.dumps(result.to_dict(), ensure_ascii=False, indent=2),
+                    structured=True)
+        logging.info("맵-리듀스 요약 완료 (구조화)")
+        return result
+    final_summary = call_ollama_with_retry(model, reduce_prompt, temperature, num_ctx=num_ctx, max_tokens=max_tokens,
+                                           extra_options=model_options, on_token=on_token)
+    record_step("final_reduce", reduce_prompt, final_summary)
     
     logging.info("맵-리듀스 요약 완료")
     return final_summary
 
+def _heading_key(heading: str) -> str:
+    return re.sub(r"\s*/\s*", "/", heading).casefold()
+
+
+# 모든 언어의 섹션 제목 → 고정 키 (다른 언어로 쓰인 요약도 파싱)
+_HEADING_LOOKUP = {
+    _heading_key(heading): key
+    for headings in SECTION_HEADINGS.values()
+    for key, heading in zip(SUMMARY_SECTIONS, headings)
+}
+
+
+def match_section_heading(line: str) -> Optional[str]:
+    """``## 3) 결정 사항`` / ``**Decisions:**`` 같은 줄이면 섹션 키를 반환."""
+    stripped = line.strip().strip("#*_ ").strip()
+    stripped = re.sub(r"^\d+\s*[).]\s*", "", stripped).strip("*_: ").strip()
+    return _HEADING_LOOKUP.get(_heading_key(stripped))
+
+
 def parse_summary_to_sections(summary: str) -> Dict[str, List[str]]:
-    """요약 텍스트를 섹션별로 파싱"""
-    sections = {
-        "주요 주제": [],
-        "핵심 내용": [],
-        "결정 사항": [],
-        "실행 항목": [],
-        "리스크/이슈": [],
-        "차기 일정": []
-    }
+    """요약 텍스트를 섹션별로 파싱 (언어와 무관한 SUMMARY_SECTIONS 키 사용)"""
+    sections: Dict[str, List[str]] = {key: [] for key in SUMMARY_SECTIONS}
     
     current_section = None
     lines = summary.split('\n')
@@ -509,18 +871,9 @@ def parse_summary_to_sections(summary: str) -> Dict[str, List[str]]:
             continue
         
         # 섹션 헤더 감지
-        if '1)' in line and '주요 주제' in line:
-            current_section = "주요 주제"
-        elif '2)' in line and '핵심 내용' in line:
-            current_section = "핵심 내용"
-        elif '3)' in line and '결정' in line:
-            current_section = "결정 사항"
-        elif '4)' in line and '실행' in line:
-            current_section = "실행 항목"
-        elif '5)' in line and ('리스크' in line or '이슈' in line):
-            current_section = "리스크/이슈"
-        elif '6)' in line and '차기 일정' in line:
-            current_section = "차기 일정"
+        heading = match_section_heading(line)
+        if heading:
+            current_section = heading
         elif current_section and (line.startswith('- ') or line.startswith('• ') or line.startswith('* ')):
             # 불릿 포인트 내용 추가
             content = line.lstrip('- •* ').strip()
@@ -529,17 +882,31 @@ def parse_summary_to_sections(summary: str) -> Dict[str, List[str]]:
     
     return sections
 
-def save_output(content: str, output_path: Path, as_json: bool = False) -> None:
+def structured_summary(content: str, language: Optional[str] = None) -> Dict[str, object]:
+    """JSON 출력용 구조: 섹션마다 고정 ``key``와 요약 언어의 ``heading``."""
+    language = resolve_summary_language(language, content)
+    sections = parse_summary_to_sections(content)
+    return {
+        "language": language,
+        "sections": [
+            {"key": key, "heading": heading, "items": sections[key]}
+            for key, heading in zip(SUMMARY_SECTIONS, SECTION_HEADINGS[language])
+        ],
+    }
+
+
+def save_output(content: str, output_path: Path, as_json: bool = False, language: Optional[str] = None) -> None:
     """출력 파일 저장"""
     try:
         output_path.parent.mkdir(parents=True, exist_ok=True)
         
         if as_json:
-            sections = parse_summary_to_sections(content)
-            json_content = json.dumps(sections, ensure_ascii=False, indent=2)
+            json_content = json.dumps(structured_summary(content, language), ensure_ascii=False, indent=2)
             output_path = output_path.with_suffix('.summary.json')
             output_path.write_text(json_content, encoding='utf-8')
         else:
+            # 다른 기록과 공유 중인 요약 파일이면 덮어쓰기 전에 분리
+            detach_artifact(output_path)
             output_path.write_text(content, encoding='utf-8')
         
         logging.info(f"요약 결과 저장: {output_path}")
@@ -605,6 +972,11 @@ def main() -> None:
         action="store_true",
         help="JSON 형식으로 섹션별 구조화된 출력"
     )
+    parser.add_argument(
+        "--language",
+        choices=["auto", *SECTION_HEADINGS],
+        help=f"요약 출력 언어 (기본값: {SUMMARY_LANGUAGE})"
+    )
     parser.add_argument(
         "--verbose", "-v",
         action="store_true",
@@ -672,11 +1044,12 @@ def main() -> None:
             chunk_size=args.chunk_size,
             max_tokens=args.max_tokens,
             temperature=args.temperature,
-            target_chunks=args.target_chunks
+            target_chunks=args.target_chunks,
+            language=args.language
         )
         
         # 결과 저장
-        save_output(summary, output_path, args.json)
+        save_output(summary, output_path, args.json, language=args.language)
 
         # Obsidian MCP 자동 전송
         try:
@@ -3,14 +3,24 @@
 import whisper
 import os
 import argparse
+import copy
+import inspect
+import json
 import logging
+import math
+import threading
+import time
 import traceback
 import platform
 import subprocess
+from collections import OrderedDict, deque
+from contextlib import contextmanager
+from datetime import datetime
 from pathlib import Path
 from concurrent.futures import ThreadPoolExecutor, as_completed
-from typing import Tuple
+from typing import Any, Dict, List, Tuple
 
+import numpy as np
 import torch
 
 # .env 파일의 환경변수 자동 로드
@@ -24,10 +34,19 @@ except ImportError:
 # 설정 모듈 임포트
 import sys
 sys.path.append(str(Path(__file__).parent.parent))
-from config import get_db_base_path, get_default_model, get_model_for_task
+from config import get_config_value, get_db_base_path, get_default_model, get_model_for_task
 from logger import setup_logging
 from vocabulary_manager import VocabularyManager
+from segment_store import segments_path_for, write_segments
+from postprocess_rules import discard_phrase_list, get_rule_pack
+from hallucination import HallucinationFilter, HallucinationThresholds
+from decoding import describe as describe_decoding, resolve_strategy, summarize_decoding
+from decoding import whisper_options as decoding_options
+from overlap_dedup import splice_window_segments
+from resampler import needs_resample, resample_args, resampler_name
 from obsidian_mcp import send_stt_to_obsidian_sync
+from disk_guard import ensure_model_download_space
+from inference_priority import background_inference
 
 setup_logging()
 
@@ -37,13 +56,316 @@ DEFAULT_OUTPUT_DIR = DB_BASE_PATH / "whisper_output"
 # Whisper가 지원하는 파일 확장자 목록
 SUPPORTED_EXTS = {'.flac', '.m4a', '.mp3', '.mp4', '.mpeg', '.mpga', '.oga', '.ogg', '.qta', '.wav', '.webm'}
 
-# Whisper가 잘못 인식하는 불필요 문구 목록
-DISCARD_PHRASES = {
-    "이 영상은 자막을 사용하였습니다.",
-    "자막을 사용하였습니다.",
-    "이 영상은 자막을 사용합니다.",
-    "자막을 사용합니다."
-}
+# 모델당 동시에 추론할 수 있는 Whisper 상태(state) 수
+WHISPER_POOL_SIZE = max(1, get_config_value("WHISPER_POOL_SIZE", 1, int))
+# 요청마다 다른 모델을 쓸 때 메모리에 함께 둘 모델 수 (넘으면 가장 오래 안 쓴 유휴 모델을 내림, 0이면 제한 없음)
+WHISPER_MAX_LOADED_MODELS = max(0, get_config_value("WHISPER_MAX_LOADED_MODELS", 2, int))
+
+# 긴 파일 체크포인트: 이 길이(분) 이상인 파일은 구간 단위로 변환하고 구간마다 저장 (0이면 비활성화)
+CHECKPOINT_MIN_SECONDS = get_config_value("STT_CHECKPOINT_MIN_MINUTES", 60, float) * 60
+CHECKPOINT_WINDOW_SECONDS = max(60.0, get_config_value("STT_CHECKPOINT_WINDOW_MINUTES", 12, float) * 60)
+# 구간 경계에서 말이 잘리지 않도록 다음 구간을 이만큼 앞에서 시작하고 겹친 문장은 정렬해 한 번만 남김
+CHECKPOINT_OVERLAP_SECONDS = min(60.0, max(0.0, get_config_value("STT_CHECKPOINT_OVERLAP_SECONDS", 5, float)))
+CHECKPOINT_VERSION = 1
+# openai-whisper 20240930+의 carry_initial_prompt로 initial_prompt를 모든 30초 창에 반복
+STT_PROMPT_CARRY = get_config_value("STT_PROMPT_CARRY", True, bool)
+# 언어를 지정하지 않으면 파일 곳곳의 30초 창 몇 개로 언어를 감지해 확률을 평균 (첫 창이 무음이어도 감지)
+STT_LANGUAGE_DETECT_WINDOWS = max(1, get_config_value("STT_LANGUAGE_DETECT_WINDOWS", 3, int))
+# 감지 확률이 이보다 낮으면 STT_LANGUAGE_FALLBACK으로 변환 (비어 있으면 감지 결과를 그대로 사용)
+STT_LANGUAGE_MIN_CONFIDENCE = get_config_value("STT_LANGUAGE_MIN_CONFIDENCE", 0.5, float)
+STT_LANGUAGE_FALLBACK = get_config_value("STT_LANGUAGE_FALLBACK", "", str).strip()
+LANGUAGE_DETECT_CANDIDATES = 3
+
+class TranscriptionCancelled(Exception):
+    """Raised from inside Whisper inference when the task was cancelled."""
+
+
+@contextmanager
+def abort_on_cancel(model, cancel_event):
+    """추론 도중 취소 신호를 확인하는 훅을 모델에 설치한다.
+
+    openai-whisper에는 중단 콜백이 없으므로 인코더(30초 창마다)와
+    디코더(토큰마다) 실행 직전에 신호를 확인해 예외로 추론을 끊는다.
+    풀에서 빌린 상태마다 모듈 객체가 다르므로 다른 작업에는 영향이 없다.
+    """
+    if cancel_event is None:
+        yield
+        return
+
+    def check_cancelled(module, inputs):
+        if cancel_event.is_set():
+            raise TranscriptionCancelled("작업이 취소되었습니다.")
+
+    handles = [
+        model.encoder.register_forward_pre_hook(check_cancelled),
+        model.decoder.register_forward_pre_hook(check_cancelled),
+    ]
+    try:
+        yield
+    finally:
+        for handle in handles:
+            handle.remove()
+
+
+class _ThreadStdout:
+    """sys.stdout 대체 객체. 스레드별로 출력 대상을 바꿀 수 있다.
+
+    Whisper의 verbose 출력(타임스탬프)을 파일별 진행률 계산에 사용하는데,
+    여러 파일을 병렬로 변환할 때 전역 sys.stdout을 교체하면 서로의 출력을
+    덮어쓰게 되므로 현재 스레드의 출력만 임시 파일로 돌린다.
+    """
+
+    def __init__(self, default):
+        self._default = default
+        self._local = threading.local()
+
+    def _target(self):
+        return getattr(self._local, "stream", None) or self._default
+
+    def write(self, data):
+        return self._target().write(data)
+
+    def flush(self):
+        return self._target().flush()
+
+    def __getattr__(self, name):
+        return getattr(self._target(), name)
+
+    @contextmanager
+    def redirect(self, stream):
+        previous = getattr(self._local, "stream", None)
+        self._local.stream = stream
+        try:
+            yield stream
+        finally:
+            self._local.stream = previous
+
+
+_stdout_router_lock = threading.Lock()
+
+
+def _thread_stdout() -> _ThreadStdout:
+    """Install (once) and return the per-thread stdout router."""
+    with _stdout_router_lock:
+        if not isinstance(sys.stdout, _ThreadStdout):
+            sys.stdout = _ThreadStdout(sys.stdout)
+        return sys.stdout
+
+
+def _clone_whisper_state(model):
+    """가중치를 공유하는 모델 사본을 만든다.
+
+    Whisper의 디코딩은 KV 캐시 훅을 모듈에 직접 등록하므로 같은 모듈
+    객체로 두 파일을 동시에 디코딩할 수 없다. 파라미터와 버퍼는 그대로
+    공유하고 모듈 객체만 새로 만들어 모델을 두 번 로드하지 않고도 독립적인
+    추론 상태를 얻는다.
+    """
+    shared = {id(tensor): tensor for tensor in model.parameters()}
+    shared.update({id(tensor): tensor for tensor in model.buffers()})
+    # alignment_heads 등 일반 속성으로 보관된 텐서도 공유
+    for value in vars(model).values():
+        if isinstance(value, torch.Tensor):
+            shared[id(value)] = value
+    return copy.deepcopy(model, memo=shared)
+
+
+class WhisperStatePool:
+    """하나의 로드된 모델에 대한 추론 상태 풀.
+
+    상태가 모두 사용 중이면 요청 순서(FIFO)대로 대기하며, 대기 중인 작업에는
+    ``on_queued(순번, 예상 시작 시각)``으로 대기열 위치를 알려준다. 예상 시각은
+    최근 변환 소요 시간의 평균으로 추정하므로 기록이 없으면 ``None``이다.
+    """
+
+    def __init__(self, model, size: int):
+        self.model = model
+        self.size = size
+        self._condition = threading.Condition()
+        self._free = [model] + [_clone_whisper_state(model) for _ in range(size - 1)]
+        self._waiting = deque()
+        self._leased_at: Dict[int, float] = {}
+        self._durations = deque(maxlen=20)
+        # get_pool(reserve=True)로 예약한 작업 수 (예약 중에는 모델을 내리지 않음)
+        self._reserved = 0
+
+    def _estimate_start(self, position: int):
+        """Soft estimate (epoch seconds) of when the ``position``-th waiter gets a state."""
+        if not self._durations:
+            return None
+        average = sum(self._durations) / len(self._durations)
+        now = time.time()
+        # 사용 중인 상태가 끝나는 예상 시각들 중 position번째 (이후는 평균 소요 시간마다 하나씩)
+        finish_times = sorted(max(now, started + average) for started in self._leased_at.values())
+        rounds, index = divmod(position - 1, max(len(finish_times), 1))
+        base = finish_times[index] if finish_times else now
+        return base + rounds * average
+
+    @contextmanager
+    def lease(self, on_queued=None, cancel_event=None):
+        """사용 가능한 상태 하나를 빌려주고, 모두 사용 중이면 반납될 때까지 순서대로 대기한다."""
+        ticket = object()
+        reported = None
+        with self._condition:
+            self._waiting.append(ticket)
+        try:
+            while True:
+                with self._condition:
+                    if self._free and self._waiting[0] is ticket:
+                        self._waiting.popleft()
+                        state = self._free.pop()
+                        self._leased_at[id(state)] = time.time()
+                        self._condition.notify_all()  # 뒤 대기자들의 순번이 바뀜
+                        break
+                    position = self._waiting.index(ticket) + 1
+                    if position == reported:
+                        self._condition.wait(timeout=1.0)
+                        if cancel_event is not None and cancel_event.is_set():
+                            raise TranscriptionCancelled("작업이 취소되었습니다.")
+                        continue
+                    estimated_start = self._estimate_start(position)
+                reported = position
+                if on_queued:
+                    on_queued(position, estimated_start)
+        except BaseException:
+            with self._condition:
+                if ticket in self._waiting:
+                    self._waiting.remove(ticket)
+                    self._condition.notify_all()
+            raise
+
+        if reported is not None and on_queued:
+            on_queued(0, None)
+        try:
+            yield state
+        finally:
+            with self._condition:
+                started = self._leased_at.pop(id(state), None)
+                if started is not None:
+                    self._durations.append(time.time() - started)
+                self._free.append(state)
+                self._condition.notify_all()
+
+    def queue_length(self) -> int:
+        with self._condition:
+            return len(self._waiting)
+
+    def reserve(self) -> None:
+        """Keep the pool loaded until :meth:`release` (taken by ``WhisperEngineManager.get_pool``)."""
+        with self._condition:
+            self._reserved += 1
+
+    def release(self) -> None:
+        with self._condition:
+            self._reserved -= 1
+
+    def busy(self) -> bool:
+        """True while a state is leased, a job is waiting for one or the pool is reserved."""
+        with self._condition:
+            return bool(self._leased_at or self._waiting or self._reserved)
+
+
+class WhisperEngineManager:
+    """로드된 Whisper 모델과 상태 풀을 (모델, 장치) 단위로 관리한다.
+
+    요청마다 ``model_settings.whisper``로 다른 모델을 고를 수 있으며, 처음 쓰는
+    모델은 그때 내려받아 로드한다. 로드된 모델이 ``max_models``를 넘으면 가장
+    오래 쓰지 않은 유휴 모델부터 내린다 (변환 중이거나 대기자가 있는 모델,
+    ``get_pool(reserve=True)``/``use_pool``로 예약된 모델은 끝날 때까지 유지).
+    """
+
+    def __init__(self, pool_size: int = WHISPER_POOL_SIZE, max_models: int = WHISPER_MAX_LOADED_MODELS):
+        self.pool_size = pool_size
+        self.max_models = max_models
+        self._pools: "OrderedDict[Tuple[str, str], WhisperStatePool]" = OrderedDict()
+        self._last_used: Dict[Tuple[str, str], float] = {}
+        self._lock = threading.Lock()
+
+    def get_pool(self, model_identifier: str, device: str, reserve: bool = False) -> WhisperStatePool:
+        """Return the state pool for a model, loading the model on first use.
+
+        With ``reserve`` the pool is reserved before the lock is released, so
+        it cannot be evicted before the caller leases a state; the caller
+        must call ``pool.release()`` when done (see :meth:`use_pool`).
+        """
+        key = (model_identifier, device)
+        with self._lock:
+            pool = self._pools.get(key)
+            if pool is None:
+                logging.info("'%s' 모델을 로드하는 중...", os.path.basename(model_identifier))
+                ensure_model_download_space(model_identifier)
+                model = whisper.load_model(model_identifier, device=device)
+                pool = WhisperStatePool(model, self.pool_size)
+                self._pools[key] = pool
+                logging.info("모델 로드 완료 (동시 처리 상태 %d개).", pool.size)
+            self._pools.move_to_end(key)
+            self._last_used[key] = time.time()
+            if reserve:
+                pool.reserve()
+            evicted = self._evict_idle(keep=key)
+        if evicted and torch.cuda.is_available():
+            torch.cuda.empty_cache()
+        return pool
+
+    @contextmanager
+    def use_pool(self, model_identifier: str, device: str):
+        """The state pool for a model, kept loaded until the block exits."""
+        pool = self.get_pool(model_identifier, device, reserve=True)
+        try:
+            yield pool
+        finally:
+            pool.release()
+
+    def _evict_idle(self, keep: Tuple[str, str]) -> List[Tuple[str, str]]:
+        """Unload least recently used idle models beyond ``max_models`` (lock held)."""
+        evicted = []
+        for key in list(self._pools):
+            if not self.max_models or len(self._pools) <= self.max_models:
+                break
+            if key == keep or self._pools[key].busy():
+                continue
+            del self._pools[key]
+            self._last_used.pop(key, None)
+            evicted.append(key)
+            logging.info("사용하지 않는 '%s' 모델을 메모리에서 내림 (최대 %d개 유지).",
+                         os.path.basename(key[0]), self.max_models)
+        return evicted
+
+    def is_loaded(self, model_identifier: str, device: str) -> bool:
+        with self._lock:
+            return (model_identifier, device) in self._pools
+
+    def loaded_models(self) -> List[Dict[str, Any]]:
+        """Loaded models from least to most recently used."""
+        with self._lock:
+            return [
+                {
+                    "model": os.path.basename(model_identifier),
+                    "device": device,
+                    "states": pool.size,
+                    "busy": pool.busy(),
+                    "queued": pool.queue_length(),
+                    "last_used": datetime.fromtimestamp(self._last_used[(model_identifier, device)]).isoformat(),
+                }
+                for (model_identifier, device), pool in self._pools.items()
+            ]
+
+    def release_all(self) -> None:
+        """Drop every cached model so the next job reloads it."""
+        with self._lock:
+            self._pools.clear()
+            self._last_used.clear()
+        if torch.cuda.is_available():
+            torch.cuda.empty_cache()
+
+
+engine_manager = WhisperEngineManager()
+
+
+def available_whisper_models() -> List[str]:
+    """Model names openai-whisper can download (a local checkpoint path also works)."""
+    available = getattr(whisper, "available_models", None)
+    return list(available()) if available else []
+
 
 def list_media_files(root: Path, recursive: bool):
     """지원하는 미디어 파일 목록을 반환합니다."""
@@ -131,6 +453,12 @@ def format_timestamp(seconds: float) -> str:
     m, s = divmod(rem, 60)
     return f"{h:02d}:{m:02d}:{s:02d}"
 
+def format_transcript_lines(segments) -> str:
+    """(start, end, text) 목록을 ``[HH:MM:SS - HH:MM:SS] 텍스트`` 줄로 만듭니다."""
+    return "\n".join(
+        f"[{format_timestamp(start)} - {format_timestamp(end)}] {text}" for start, end, text in segments
+    )
+
 def merge_segments(segments, max_gap: float = 0.2):
     """
     연속 세그먼트가 동일한 텍스트이고 시간 간격이 max_gap 이내이면 병합
@@ -165,30 +493,33 @@ def merge_segments(segments, max_gap: float = 0.2):
     
     return merged
 
-def should_keep_segment(text: str, enable_filter: bool, min_length: int):
-    """세그먼트 유지 여부를 판단합니다."""
+def segment_drop_reason(text: str, enable_filter: bool, min_length: int, rules=None):
+    """세그먼트를 정리본(clean)에서 뺄 이유를 반환합니다. 유지하면 ``None``.
+
+    rules는 언어별 후처리 규칙 팩(postprocess_rules.RulePack)이며, 없으면 기본 언어 규칙을 사용합니다.
+    """
     text = text.strip()
+    rules = rules or get_rule_pack()
     
     # 빈 텍스트 제거
     if not text:
-        return False
+        return "empty"
 
     # 최소 길이 검사
     if len(text) < min_length:
-        return False
+        return "min_length"
 
-    # 사전 정의된 불필요 문구 제거
-    if text in DISCARD_PHRASES:
-        return False
+    # 언어별 규칙 팩의 불필요 문구 제거
+    if rules.is_discard_phrase(text):
+        return "discard_phrase"
     
     # 필터링이 비활성화되면 유지
     if not enable_filter:
-        return True
+        return None
     
     # 보수적 필러 필터: 단독으로 나타나는 필러만 제거
-    filler_words = {"아", "으", "음", "어", "저", "그", "뭐", "얍", "흠", "네", "예"}
-    if text in filler_words:
-        return False
+    if rules.is_filler(text):
+        return "filler"
     
     # 반복적인 패턴 감지 및 제거
     import re
@@ -198,13 +529,17 @@ def should_keep_segment(text: str, enable_filter: bool, min_length: int):
     if len(words) >= 10:
         unique_words = set(words)
         if len(unique_words) <= 2:  # 1-2개 고유 단어만 있는 경우
-            return False
+            return "repetition"
     
     # 숫자만 나열된 경우 제거 (예: "1. 2. 3. 4...")
     if re.match(r'^[\d\.\s]+$', text) and len(text.split()) >= 10:
-        return False
+        return "repetition"
     
-    return True
+    return None
+
+def should_keep_segment(text: str, enable_filter: bool, min_length: int, rules=None):
+    """세그먼트 유지 여부를 판단합니다 (:func:`segment_drop_reason` 참고)."""
+    return segment_drop_reason(text, enable_filter, min_length, rules) is None
 
 
 def resolve_inference_device(requested_device: str) -> Tuple[str, str]:
@@ -237,6 +572,216 @@ def resolve_inference_device(requested_device: str) -> Tuple[str, str]:
 
     return "cpu", "CUDA/MPS 장치를 찾을 수 없어 CPU로 실행합니다."
 
+def supports_carry_initial_prompt(model) -> bool:
+    """설치된 Whisper의 transcribe가 carry_initial_prompt를 받는지 확인한다."""
+    try:
+        return "carry_initial_prompt" in inspect.signature(model.transcribe).parameters
+    except (TypeError, ValueError, AttributeError):
+        return False
+
+
+def get_audio_duration(audio_file):
+    """ffprobe를 사용하여 오디오 파일의 길이(초) 반환"""
+    try:
+        result = subprocess.run([
+            'ffprobe', '-v', 'quiet', '-show_entries', 'format=duration', 
+            '-of', 'csv=p=0', str(audio_file)
+        ], capture_output=True, text=True, check=True)
+        duration = float(result.stdout.strip())
+        return duration
+    except (subprocess.CalledProcessError, ValueError, FileNotFoundError):
+        print(f"오디오 길이 측정 실패: {audio_file}")
+        return None
+
+
+def load_audio_window(audio_file: Path, start: float, duration: float) -> np.ndarray:
+    """ffmpeg로 [start, start + duration) 구간만 16kHz 모노 float 배열로 읽는다."""
+    command = [
+        "ffmpeg", "-nostdin", "-ss", f"{start:.3f}", "-t", f"{duration:.3f}",
+        "-i", str(audio_file), *resample_args(whisper.audio.SAMPLE_RATE),
+        "-f", "s16le", "-acodec", "pcm_s16le", "-"
+    ]
+    result = subprocess.run(command, capture_output=True, check=False)
+    if result.returncode != 0:
+        raise RuntimeError(f"오디오 구간 읽기 실패: {result.stderr.decode(errors='ignore')}")
+    return np.frombuffer(result.stdout, np.int16).flatten().astype(np.float32) / 32768.0
+
+
+def _language_probabilities(model, audio: np.ndarray) -> Dict[str, float]:
+    """Whisper ``detect_language`` 확률 (30초 창 하나)."""
+    audio = whisper.pad_or_trim(audio)
+    n_mels = getattr(getattr(model, "dims", None), "n_mels", 80)
+    try:
+        mel = whisper.log_mel_spectrogram(audio, n_mels=n_mels)
+    except TypeError:  # n_mels 인자가 없는 이전 openai-whisper
+        mel = whisper.log_mel_spectrogram(audio)
+    _, probs = model.detect_language(mel.to(model.device))
+    return probs[0] if isinstance(probs, list) else probs
+
+
+def detect_spoken_language(model, audio_file: Path, total_duration: float = None) -> Dict[str, object]:
+    """변환 전에 음성 언어를 감지한다: ``{"language", "confidence", "candidates", "windows"}``.
+
+    파일 길이에 고르게 퍼진 ``STT_LANGUAGE_DETECT_WINDOWS``개의 30초 창의
+    확률을 평균한다. 영어 전용 모델은 감지하지 않고 ``en``을 돌려준다.
+    """
+    if not getattr(model, "is_multilingual", True):
+        return {"language": "en", "confidence": 1.0, "candidates": {"en": 1.0}, "windows": 0}
+    window = whisper.audio.CHUNK_LENGTH
+    windows = STT_LANGUAGE_DETECT_WINDOWS if total_duration and total_duration > window else 1
+    span = max(0.0, (total_duration or 0) - window)
+    offsets = [span * (i + 1) / (windows + 1) if windows > 1 else 0.0 for i in range(windows)]
+    totals: Dict[str, float] = {}
+    for offset in offsets:
+        if windows > 1:
+            audio = load_audio_window(audio_file, offset, window)
+        else:
+            audio = whisper.load_audio(str(audio_file))
+        for code, probability in _language_probabilities(model, audio).items():
+            totals[code] = totals.get(code, 0.0) + float(probability) / len(offsets)
+    ranked = sorted(totals.items(), key=lambda item: item[1], reverse=True)
+    language, confidence = ranked[0]
+    return {
+        "language": language,
+        "confidence": round(confidence, 4),
+        "candidates": {code: round(probability, 4) for code, probability in ranked[:LANGUAGE_DETECT_CANDIDATES]},
+        "windows": len(offsets),
+    }
+
+
+def extract_audio_slice(audio_file: Path, wav_path: Path, start: float, duration: float) -> Path:
+    """ffmpeg로 [start, start + duration) 구간만 16kHz 모노 WAV 파일로 잘라낸다."""
+    command = [
+        "ffmpeg", "-nostdin", "-y", "-ss", f"{start:.3f}", "-t", f"{duration:.3f}",
+        "-i", str(audio_file), "-vn", *resample_args(), "-c:a", "pcm_s16le", str(wav_path)
+    ]
+    result = subprocess.run(command, capture_output=True, text=True, encoding='utf-8', check=False)
+    if result.returncode != 0:
+        raise RuntimeError(f"오디오 구간 추출 실패: {result.stderr}")
+    return wav_path
+
+
+def checkpoint_path_for(output_dir: Path, source_file: Path) -> Path:
+    return output_dir / f"{source_file.stem}.checkpoint.json"
+
+
+def _checkpoint_signature(source_file: Path, transcribe_params: dict, model_name: str = None) -> dict:
+    """체크포인트가 같은 입력/모델/변환 설정에서 만들어졌는지 판단하는 값.
+
+    모델, 언어, task, 프롬프트, 디코딩/임계값 등 ``model.transcribe``에 넘기는
+    옵션이 하나라도 다르면 이전 구간 결과와 섞이지 않도록 새로 변환한다.
+    """
+    params = {key: value for key, value in transcribe_params.items() if key != "verbose"}
+    params.setdefault("task", "transcribe")
+    return {
+        "version": CHECKPOINT_VERSION,
+        "source": source_file.name,
+        "size": source_file.stat().st_size,
+        "window_seconds": CHECKPOINT_WINDOW_SECONDS,
+        "overlap_seconds": CHECKPOINT_OVERLAP_SECONDS,
+        "model": model_name,
+        # 저장된 JSON과 비교하므로 튜플(온도 스케줄 등)은 JSON 값으로 맞춤
+        "params": json.loads(json.dumps(params, sort_keys=True, ensure_ascii=False, default=str)),
+    }
+
+
+def load_checkpoint(checkpoint_path: Path, signature: dict):
+    """저장된 체크포인트를 읽는다. 입력이나 설정이 달라졌으면 None."""
+    if not checkpoint_path.exists():
+        return None
+    try:
+        with open(checkpoint_path, "r", encoding="utf-8") as f:
+            state = json.load(f)
+    except (OSError, json.JSONDecodeError):
+        logging.warning("체크포인트를 읽을 수 없어 처음부터 변환합니다: %s", checkpoint_path.name)
+        return None
+    if state.get("signature") != signature:
+        logging.info("입력 또는 설정이 바뀌어 기존 체크포인트를 무시합니다: %s", checkpoint_path.name)
+        return None
+    return state
+
+
+def clear_checkpoint(output_dir: Path, source_file: Path) -> None:
+    checkpoint_path = checkpoint_path_for(output_dir, source_file)
+    try:
+        checkpoint_path.unlink()
+    except FileNotFoundError:
+        pass
+    except OSError as e:
+        logging.warning("체크포인트 삭제 실패: %s", e)
+
+
+def transcribe_with_checkpoints(model, source_file: Path, audio_file: Path, output_dir: Path,
+                                total_duration: float, transcribe_params: dict,
+                                progress_callback=None, model_name: str = None) -> dict:
+    """아주 긴 오디오를 구간 단위로 변환하며 구간마다 결과를 체크포인트로 저장한다.
+
+    충돌이나 취소로 중단되어도 완료된 구간은 보존되고, 같은 파일을 다시
+    변환하면 마지막 체크포인트 다음 구간부터 이어서 진행한다. 반환값은
+    ``model.transcribe``와 같은 형태(``segments``, ``text``, ``language``)다.
+    """
+    window_seconds = CHECKPOINT_WINDOW_SECONDS
+    total_windows = max(1, math.ceil(total_duration / window_seconds))
+    checkpoint_path = checkpoint_path_for(output_dir, source_file)
+    signature = _checkpoint_signature(source_file, transcribe_params, model_name)
+
+    state = load_checkpoint(checkpoint_path, signature)
+    segments = list(state.get("segments", [])) if state else []
+    completed = int(state.get("completed_windows", 0)) if state else 0
+    language = (state.get("language") if state else None) or transcribe_params.get("language")
+
+    if completed:
+        logging.info("체크포인트에서 이어서 변환: %s (%d/%d 구간 완료)", source_file.name, completed, total_windows)
+        if progress_callback:
+            progress_callback(f"'{source_file.name}' 체크포인트에서 재개 ({completed}/{total_windows} 구간 완료)")
+
+    # 구간별 변환에서는 stdout 기반 진행률 대신 구간 단위 진행률을 사용
+    params = dict(transcribe_params, verbose=None)
+
+    for index in range(completed, total_windows):
+        offset = index * window_seconds
+        if progress_callback:
+            percent = int(index / total_windows * 100)
+            progress_callback(f"'{source_file.name}' 구간 {index + 1}/{total_windows} 변환 중... {percent}%")
+
+        if language:
+            params["language"] = language
+        overlap = min(CHECKPOINT_OVERLAP_SECONDS, offset)
+        audio = load_audio_window(audio_file, offset - overlap, window_seconds + overlap)
+        window_result = model.transcribe(audio, **params)
+        # 자동 감지된 언어는 첫 구간 결과로 고정하여 구간마다 달라지지 않게 함
+        language = language or window_result.get("language")
+
+        window_segments = [
+            {
+                "start": segment.get("start", 0.0) + offset - overlap,
+                "end": segment.get("end", 0.0) + offset - overlap,
+                "text": segment.get("text", ""),
+                "temperature": segment.get("temperature", 0.0),
+            }
+            for segment in window_result.get("segments", []) or []
+        ]
+        segments, duplicated = splice_window_segments(segments, window_segments, offset - overlap, offset)
+        if duplicated:
+            logging.info("구간 %d 경계의 중복 세그먼트 %d개 제거", index + 1, duplicated)
+
+        write_atomic(checkpoint_path, json.dumps({
+            "signature": signature,
+            "completed_windows": index + 1,
+            "total_windows": total_windows,
+            "language": language,
+            "segments": segments,
+            "updated_at": datetime.now().isoformat(),
+        }, ensure_ascii=False))
+        logging.info("체크포인트 저장: %s (%d/%d 구간)", source_file.name, index + 1, total_windows)
+
+    return {
+        "segments": segments,
+        "text": "".join(segment["text"] for segment in segments),
+        "language": language,
+    }
+
+
 def get_unique_output_path(base_path: Path) -> Path:
     """파일명 충돌 시 접미사를 붙여 고유한 경로를 반환"""
     if not base_path.exists():
@@ -255,41 +800,203 @@ def get_unique_output_path(base_path: Path) -> Path:
 
 
 
+def convert_to_wav(file_path: Path, wav_path: Path) -> Path:
+    """ffmpeg로 16kHz 모노 PCM WAV 파일을 만듭니다 (resampler.py의 고품질 리샘플러 사용)."""
+    command = [
+        "ffmpeg", "-i", str(file_path), *resample_args(),
+        "-c:a", "pcm_s16le", "-y", str(wav_path)
+    ]
+
+    result = subprocess.run(command, capture_output=True, text=True, encoding='utf-8', check=False)
+
+    if result.returncode != 0:
+        logging.error(f"ffmpeg 변환 실패: {file_path.name}\n{result.stderr}")
+        raise RuntimeError(f"ffmpeg 변환 실패: {result.stderr}")
+
+    logging.info(f"성공적으로 '{wav_path.name}' 파일로 변환했습니다.")
+    return wav_path
+
+
+def write_transcription_outputs(file_path: Path, output_dir: Path, result: dict,
+                                language: str, filter_fillers: bool, min_seg_length: int,
+                                normalize_punct: bool, model_name: str = None,
+                                progress_callback=None, export_to_obsidian: bool = True,
+                                prompt_provenance: dict = None) -> Path:
+    """Whisper 형식 결과(text/segments/language)를 마크다운과 세그먼트 파일로 저장합니다.
+
+    로컬 Whisper와 원격 STT 백엔드가 같은 후처리(병합, 필터링, 정규화)와
+    출력 형식을 쓰도록 공통으로 사용합니다. 구간 재변환처럼 중간 결과만
+    필요한 경우 ``export_to_obsidian=False``로 Obsidian 전송을 건너뜁니다.
+    ``prompt_provenance``(stt_prompt.build_initial_prompt)는 세그먼트 파일의 ``prompt``로,
+    결과의 ``decoding``(decoding.describe)은 구간별 온도 통계와 함께 ``decoding``으로 남깁니다.
+    """
+    # 출력 파일 경로 결정 (원본 파일명 기준)
+    base_output_path = output_dir / f"{file_path.stem}.md"
+    output_file_path = get_unique_output_path(base_output_path)
+
+    # 세그먼트 처리
+    if progress_callback:
+        progress_callback(f"'{file_path.name}' 결과 처리 중...")
+    
+    segments = result.get("segments", []) or []
+    decoding = summarize_decoding(result["decoding"], segments) if result.get("decoding") else None
+    segments = merge_segments(segments, max_gap=0.2)
+
+    # 감지된 언어에 맞는 후처리 규칙 팩 선택
+    detected_language = result.get("language") or language
+    rules = get_rule_pack(detected_language)
+    # 압축률/무음 확률/로그 확률 기반 환각 검사
+    hallucination_filter = HallucinationFilter(HallucinationThresholds.from_config())
+    rule_filtered = 0
+
+    # 필터링 및 정규화 (걸러낸 세그먼트도 원문 보기용으로 verbatim에 이유와 함께 남김)
+    processed_segments = []
+    segment_records = []  # 세그먼트 파일용 (화자 라벨이 있으면 함께 저장)
+    verbatim_records = []
+    for segment in segments:
+        text = segment.get("text", "").strip()
+        verbatim = {"start": segment.get("start", 0.0), "end": segment.get("end", 0.0), "text": text}
+        if segment.get("speaker"):
+            verbatim["speaker"] = str(segment["speaker"])
+        if text:
+            verbatim_records.append(verbatim)
+        drop_reason = segment_drop_reason(text, filter_fillers, min_seg_length, rules)
+        if drop_reason:
+            verbatim["dropped"] = drop_reason
+            rule_filtered += 1
+            continue
+        hallucination_reasons = hallucination_filter.apply(segment)
+        if hallucination_reasons is None:
+            verbatim["dropped"] = "hallucination"
+            continue
+        text = normalize_text(text, normalize_punct or True)  # 반복 단어 제거는 항상 활성화
+        processed_segments.append(
+            (
+                segment.get("start", 0.0),
+                segment.get("end", 0.0),
+                text,
+            )
+        )
+        record = {"start": segment.get("start", 0.0), "end": segment.get("end", 0.0), "text": text}
+        if segment.get("speaker"):
+            record["speaker"] = str(segment["speaker"])
+        if hallucination_reasons:
+            record["hallucination"] = hallucination_reasons
+        segment_records.append(record)
+
+    filtering = {
+        "input_segments": len(segments),
+        "kept_segments": len(segment_records),
+        "rule_filtered": rule_filtered,
+        "hallucination": hallucination_filter.summary(),
+    }
+    if rule_filtered or hallucination_filter.dropped or hallucination_filter.flagged:
+        logging.info(
+            f"세그먼트 필터링: 규칙 {rule_filtered}개 제거, 환각 의심 "
+            f"{hallucination_filter.dropped}개 제거 / {hallucination_filter.flagged}개 표시"
+        )
+
+    # 마크다운 생성 (원본 파일명 기준)
+    markdown_content = f"# {file_path.stem}\n\n"
+    if processed_segments:
+        markdown_content += format_transcript_lines(processed_segments)
+    else:
+        original_text = result.get("text", "").strip()
+        for phrase in discard_phrase_list(rules.language):
+            original_text = original_text.replace(phrase, "").strip()
+        # 원본 텍스트도 반복 패턴인지 확인
+        if not should_keep_segment(original_text, True, 10, rules):
+            markdown_content += "## 변환 결과\n\n음성 내용을 인식할 수 없거나 주로 무음/반복 패턴으로 구성되어 있습니다.\n\n**참고사항:**\n- 녹음 품질이 낮거나 배경소음이 많은 경우\n- 실제 음성 내용이 없는 경우\n- 매우 조용한 음성이나 중얼거림인 경우\n\n다른 Whisper 모델(large, base 등)을 시도하거나 녹음 파일을 확인해 보세요."
+        else:
+            markdown_content += normalize_text(original_text, normalize_punct or True)  # 반복 단어 제거는 항상 활성화
+
+    # 원자적 저장
+    if progress_callback:
+        progress_callback(f"'{file_path.name}' 파일 저장 중...")
+
+    write_atomic(output_file_path, markdown_content)
+    try:
+        write_segments(
+            segments_path_for(output_file_path),
+            segment_records,
+            language=detected_language,
+            model=model_name,
+            speaker_embeddings=result.get("speaker_embeddings"),
+            filtering=filtering,
+            verbatim=verbatim_records,
+            prompt=prompt_provenance,
+            decoding=decoding,
+            language_detection=result.get("language_detection"),
+        )
+    except OSError as e:
+        logging.warning(f"세그먼트 파일 저장 실패 (처리는 계속): {e}")
+
+    # Obsidian MCP 자동 전송 (구간 재변환 같은 중간 결과는 제외)
+    if export_to_obsidian:
+        try:
+            # UUID 추출 (output_file_path의 부모 디렉토리명 = UUID 폴더)
+            file_uuid = output_file_path.parent.name
+
+            # 파일 생성 시각 (현재 시각)
+            created_at = datetime.now()
+
+            # STT 텍스트 추출 (타임스탬프 제거한 순수 텍스트)
+            stt_text_only = "\n".join([text for _, _, text in processed_segments]) if processed_segments else result.get("text", "").strip()
+
+            if progress_callback:
+                progress_callback(f"'{file_path.name}' Obsidian 전송 중...")
+
+            # Obsidian에 전송 (동기 버전)
+            mcp_result = send_stt_to_obsidian_sync(
+                uuid=file_uuid,
+                stt_text=stt_text_only,
+                original_filename=file_path.name,
+                created_at=created_at
+            )
+
+            if mcp_result["success"]:
+                logging.info(f"Obsidian MCP 전송 성공: {mcp_result['message']}")
+                if progress_callback:
+                    progress_callback(f"'{file_path.name}' Obsidian 전송 완료")
+            else:
+                logging.warning(f"Obsidian MCP 전송 실패 (처리는 계속): {mcp_result['message']}")
+
+        except Exception as e:
+            # Obsidian 전송 실패해도 전체 프로세스는 계속 진행
+            logging.warning(f"Obsidian MCP 전송 중 오류 (처리는 계속): {e}")
+
+    if progress_callback:
+        progress_callback(f"'{file_path.name}' 변환 완료!")
+
+    return output_file_path
+
+
 def transcribe_single_file(file_path: Path, output_dir: Path, model,
                           language: str, initial_prompt: str,
                           filter_fillers: bool, min_seg_length: int,
                           normalize_punct: bool, use_fp16: bool,
-                          progress_callback=None):
-    """단일 파일을 변환하고 결과를 저장합니다. m4a 파일은 wav로 자동 변환합니다."""
+                          progress_callback=None, model_name: str = None,
+                          export_to_obsidian: bool = True, prompt_provenance: dict = None,
+                          decoding_strategy: str = None):
+    """단일 파일을 변환하고 결과를 저장합니다. 16kHz 모노 WAV가 아니면 먼저 고품질 리샘플링합니다.
+
+    마크다운과 함께 타임스탬프 세그먼트를 ``{파일명}.segments.json``(버전 포함)으로 저장합니다.
+    ``decoding_strategy``(greedy/fallback/beam, 기본값 STT_DECODING_STRATEGY)는 decoding.py 참고.
+    """
+    decoding_strategy = resolve_strategy(decoding_strategy)
     
     temp_wav_path = None
     file_to_process = file_path
 
     try:
-        # M4A 파일을 WAV로 변환
-        if file_path.suffix.lower() == '.m4a':
+        # Whisper 내부의 기본 리샘플링 대신 16kHz 모노 WAV로 미리 변환 (sinc 리샘플러)
+        if needs_resample(file_path):
             if progress_callback:
-                progress_callback(f"'{file_path.name}' m4a → wav 변환 중...")
-            logging.info(f"'{file_path.name}'은(는) m4a 파일이므로, 처리를 위해 wav로 변환합니다.")
-            temp_wav_path = file_path.with_suffix('.wav')
-            
-            command = [
-                "ffmpeg", "-i", str(file_path), "-ar", "16000", "-ac", "1", 
-                "-c:a", "pcm_s16le", "-y", str(temp_wav_path)
-            ]
-            
-            result = subprocess.run(command, capture_output=True, text=True, encoding='utf-8', check=False)
-            
-            if result.returncode != 0:
-                logging.error(f"ffmpeg 변환 실패: {file_path.name}\n{result.stderr}")
-                raise RuntimeError(f"ffmpeg 변환 실패: {result.stderr}")
-            
+                progress_callback(f"'{file_path.name}' 16kHz wav 변환 중...")
+            logging.info(f"'{file_path.name}'을(를) 16kHz 모노 wav로 리샘플링합니다 ({resampler_name()}).")
+            temp_wav_path = file_path.with_name(f"{file_path.stem}.16k.wav")
+            convert_to_wav(file_path, temp_wav_path)
             file_to_process = temp_wav_path
-            logging.info(f"성공적으로 '{temp_wav_path.name}' 파일로 변환했습니다.")
-
-        # 출력 파일 경로 결정 (원본 파일명 기준)
-        base_output_path = output_dir / f"{file_path.stem}.md"
-        output_file_path = get_unique_output_path(base_output_path)
 
         # Whisper 변환 실행
         if progress_callback:
@@ -298,41 +1005,61 @@ def transcribe_single_file(file_path: Path, output_dir: Path, model,
         transcribe_params = {
             "fp16": use_fp16,
             "verbose": True,  # 항상 verbose 활성화하여 진행률 출력 확인
-            "temperature": 0.0,
-            "no_speech_threshold": 0.6,  # 무음 구간 감지 임계값 상향
-            "logprob_threshold": -1.0,   # 낮은 확신도 구간 필터링
-            "compression_ratio_threshold": 2.4,  # 반복적인 텍스트 감지
+            # 온도 스케줄/빔 크기. 아래 임계값을 넘는 창은 다음 온도로 다시 디코딩
+            **decoding_options(decoding_strategy),
+            # 무음/저확신/반복 텍스트 임계값 (변환 후 세그먼트별 환각 검사와 같은 값)
+            **HallucinationThresholds.from_config().whisper_options(),
             "condition_on_previous_text": False  # 이전 텍스트 의존성 제거
         }
-        if language:
-            transcribe_params["language"] = language
+        # 아주 긴 파일은 구간 단위로 변환하며 체크포인트를 남김 (언어 감지 창 배치에도 사용)
+        audio_duration = get_audio_duration(file_to_process) if CHECKPOINT_MIN_SECONDS > 0 or not language else None
+        language_detection = None
+        if not language:
+            if progress_callback:
+                progress_callback(f"'{file_path.name}' 언어 감지 중...")
+            language_detection = detect_spoken_language(model, file_to_process, audio_duration)
+            language = language_detection["language"]
+            if language_detection["confidence"] < STT_LANGUAGE_MIN_CONFIDENCE and STT_LANGUAGE_FALLBACK:
+                language = STT_LANGUAGE_FALLBACK
+                language_detection["fallback"] = language
+            logging.info(
+                f"감지된 언어: {language_detection['language']} ({language_detection['confidence']:.0%}), "
+                f"변환 언어: {language}"
+            )
+            if progress_callback:
+                progress_callback(
+                    f"'{file_path.name}' 감지된 언어: {language_detection['language']} "
+                    f"({language_detection['confidence']:.0%})"
+                )
+        # 감지한 언어도 고정해 30초 창/체크포인트 구간마다 언어가 바뀌지 않게 함
+        transcribe_params["language"] = language
+        prompt_scope = None
         if initial_prompt:
             transcribe_params["initial_prompt"] = initial_prompt
+            # condition_on_previous_text=False이면 프롬프트가 첫 30초 창에만 적용되므로 가능하면 매 창에 반복
+            prompt_scope = "first_window"
+            if STT_PROMPT_CARRY and supports_carry_initial_prompt(model):
+                transcribe_params["carry_initial_prompt"] = True
+                prompt_scope = "all_windows"
+        if prompt_provenance is not None:
+            prompt_provenance = dict(prompt_provenance, scope=prompt_scope)
+
+        if CHECKPOINT_MIN_SECONDS > 0 and audio_duration and audio_duration >= CHECKPOINT_MIN_SECONDS:
+            result = transcribe_with_checkpoints(
+                model, file_path, file_to_process, output_dir,
+                audio_duration, transcribe_params, progress_callback, model_name=model_name
+            )
 
         # 오디오 길이 기반 진행률 처리
-        if progress_callback:
+        elif progress_callback:
             import threading
             import time
             import sys
             import io
             import re
             
-            # 오디오 파일 길이 가져오기
-            def get_audio_duration(audio_file):
-                """ffprobe를 사용하여 오디오 파일의 길이(초) 반환"""
-                try:
-                    result = subprocess.run([
-                        'ffprobe', '-v', 'quiet', '-show_entries', 'format=duration', 
-                        '-of', 'csv=p=0', str(audio_file)
-                    ], capture_output=True, text=True, check=True)
-                    duration = float(result.stdout.strip())
-                    return duration
-                except (subprocess.CalledProcessError, ValueError, FileNotFoundError):
-                    print(f"오디오 길이 측정 실패: {audio_file}")
-                    return None
-            
             # 오디오 총 길이 가져오기
-            total_duration = get_audio_duration(file_to_process)
+            total_duration = audio_duration or get_audio_duration(file_to_process)
             
             if total_duration:
                 print(f"오디오 총 길이: {total_duration:.2f}초")
@@ -350,71 +1077,70 @@ def transcribe_single_file(file_path: Path, output_dir: Path, model,
                         with tempfile.NamedTemporaryFile(mode='w+', delete=False) as temp_file:
                             temp_filename = temp_file.name
                         
-                        old_stdout = sys.stdout
+                        capture_file = open(temp_filename, 'w', buffering=1)
                         
                         try:
-                            # stdout을 임시 파일로 리다이렉션
-                            sys.stdout = open(temp_filename, 'w', buffering=1)
+                            # 현재 스레드의 stdout만 임시 파일로 리다이렉션
+                            with _thread_stdout().redirect(capture_file):
                             
-                            def read_timestamps():
-                                """타임스탬프를 읽어서 진행률 계산"""
-                                last_size = 0
-                                while not transcription_complete.is_set():
-                                    try:
-                                        with open(temp_filename, 'r') as f:
-                                            f.seek(last_size)
-                                            new_content = f.read()
-                                            if new_content:
-                                                last_size = f.tell()
+                                def read_timestamps():
+                                    """타임스탬프를 읽어서 진행률 계산"""
+                                    last_size = 0
+                                    while not transcription_complete.is_set():
+                                        try:
+                                            with open(temp_filename, 'r') as f:
+                                                f.seek(last_size)
+                                                new_content = f.read()
+                                                if new_content:
+                                                    last_size = f.tell()
                                                 
-                                                # 타임스탬프 패턴 매칭: [00:01.234 --> 00:02.567]
-                                                timestamp_pattern = r'\[(\d{2}):(\d{2})\.(\d{3}) --> (\d{2}):(\d{2})\.(\d{3})\]'
-                                                matches = re.findall(timestamp_pattern, new_content)
+                                                    # 타임스탬프 패턴 매칭: [00:01.234 --> 00:02.567]
+                                                    timestamp_pattern = r'\[(\d{2}):(\d{2})\.(\d{3}) --> (\d{2}):(\d{2})\.(\d{3})\]'
+                                                    matches = re.findall(timestamp_pattern, new_content)
                                                 
-                                                for match in matches:
-                                                    # 끝 시간 계산 (분:초.밀리초 -> 초)
-                                                    end_minutes = int(match[3])
-                                                    end_seconds = int(match[4])
-                                                    end_milliseconds = int(match[5])
-                                                    current_time = end_minutes * 60 + end_seconds + end_milliseconds / 1000
+                                                    for match in matches:
+                                                        # 끝 시간 계산 (분:초.밀리초 -> 초)
+                                                        end_minutes = int(match[3])
+                                                        end_seconds = int(match[4])
+                                                        end_milliseconds = int(match[5])
+                                                        current_time = end_minutes * 60 + end_seconds + end_milliseconds / 1000
                                                     
-                                                    # 진행률 계산
-                                                    percent = min(int((current_time / total_duration) * 100), 99)
+                                                        # 진행률 계산
+                                                        percent = min(int((current_time / total_duration) * 100), 99)
                                                     
-                                                    if percent > captured_progress['last_percent']:
-                                                        captured_progress['last_percent'] = percent
-                                                        captured_progress['last_timestamp'] = current_time
+                                                        if percent > captured_progress['last_percent']:
+                                                            captured_progress['last_percent'] = percent
+                                                            captured_progress['last_timestamp'] = current_time
                                                         
-                                                        # 남은 시간 추정
-                                                        if current_time > 0:
-                                                            elapsed_real = time.time() - start_time
-                                                            estimated_total_time = elapsed_real * (total_duration / current_time)
-                                                            remaining_time = max(0, estimated_total_time - elapsed_real)
+                                                            # 남은 시간 추정
+                                                            if current_time > 0:
+                                                                elapsed_real = time.time() - start_time
+                                                                estimated_total_time = elapsed_real * (total_duration / current_time)
+                                                                remaining_time = max(0, estimated_total_time - elapsed_real)
                                                             
-                                                            progress_msg = f"'{file_path.name}' 처리 중... {percent}% ({current_time:.1f}/{total_duration:.1f}초) [약 {remaining_time:.0f}초 남음]"
-                                                        else:
-                                                            progress_msg = f"'{file_path.name}' 처리 중... {percent}% ({current_time:.1f}/{total_duration:.1f}초)"
+                                                                progress_msg = f"'{file_path.name}' 처리 중... {percent}% ({current_time:.1f}/{total_duration:.1f}초) [약 {remaining_time:.0f}초 남음]"
+                                                            else:
+                                                                progress_msg = f"'{file_path.name}' 처리 중... {percent}% ({current_time:.1f}/{total_duration:.1f}초)"
                                                         
-                                                        progress_callback(progress_msg)
+                                                            progress_callback(progress_msg)
                                         
-                                        time.sleep(0.5)
-                                    except Exception as e:
-                                        print(f"타임스탬프 모니터링 오류: {e}")
-                                        time.sleep(1)
+                                            time.sleep(0.5)
+                                        except Exception as e:
+                                            print(f"타임스탬프 모니터링 오류: {e}")
+                                            time.sleep(1)
                             
-                            # 모니터링 스레드 시작
-                            start_time = time.time()
-                            monitor_thread = threading.Thread(target=read_timestamps, daemon=True)
-                            monitor_thread.start()
+                                # 모니터링 스레드 시작
+                                start_time = time.time()
+                                monitor_thread = threading.Thread(target=read_timestamps, daemon=True)
+                                monitor_thread.start()
                             
-                            # Whisper 실행
-                            result = model.transcribe(str(file_to_process), **transcribe_params)
+                                # Whisper 실행
+                                result = model.transcribe(str(file_to_process), **transcribe_params)
                             
-                            return result
+                                return result
                             
                         finally:
-                            sys.stdout.close()
-                            sys.stdout = old_stdout
+                            capture_file.close()
                             transcription_complete.set()
                             
                             # 임시 파일 정리
@@ -424,6 +1150,8 @@ def transcribe_single_file(file_path: Path, output_dir: Path, model,
                             except:
                                 pass
                     
+                    except TranscriptionCancelled:
+                        raise
                     except Exception as e:
                         print(f"타임스탬프 기반 진행률 실패: {e}")
                         # 폴백: 기본 Whisper 실행
@@ -470,107 +1198,107 @@ def transcribe_single_file(file_path: Path, output_dir: Path, model,
             # 진행률 콜백이 없으면 일반적으로 실행
             result = model.transcribe(str(file_to_process), **transcribe_params)
 
-        # 세그먼트 처리
-        if progress_callback:
-            progress_callback(f"'{file_path.name}' 결과 처리 중...")
-        
-        segments = result.get("segments", []) or []
-        segments = merge_segments(segments, max_gap=0.2)
-
-        # 필터링 및 정규화
-        processed_segments = []
-        for segment in segments:
-            text = segment.get("text", "").strip()
-            if not should_keep_segment(text, filter_fillers, min_seg_length):
-                continue
-            text = normalize_text(text, normalize_punct or True)  # 반복 단어 제거는 항상 활성화
-            processed_segments.append(
-                (
-                    segment.get("start", 0.0),
-                    segment.get("end", 0.0),
-                    text,
-                )
-            )
+        result["decoding"] = describe_decoding(decoding_strategy)
+        if language_detection:
+            result["language_detection"] = language_detection
+        output_file_path = write_transcription_outputs(
+            file_path, output_dir, result, language, filter_fillers,
+            min_seg_length, normalize_punct, model_name, progress_callback,
+            export_to_obsidian=export_to_obsidian, prompt_provenance=prompt_provenance
+        )
+        clear_checkpoint(output_dir, file_path)
 
-        # 마크다운 생성 (원본 파일명 기준)
-        markdown_content = f"# {file_path.stem}\n\n"
-        if processed_segments:
-            lines = []
-            for start, end, text in processed_segments:
-                ts = f"{format_timestamp(start)} - {format_timestamp(end)}"
-                lines.append(f"[{ts}] {text}")
-            markdown_content += "\n".join(lines)
-        else:
-            original_text = result.get("text", "").strip()
-            for phrase in DISCARD_PHRASES:
-                original_text = original_text.replace(phrase, "").strip()
-            # 원본 텍스트도 반복 패턴인지 확인
-            if not should_keep_segment(original_text, True, 10):
-                markdown_content += "## 변환 결과\n\n음성 내용을 인식할 수 없거나 주로 무음/반복 패턴으로 구성되어 있습니다.\n\n**참고사항:**\n- 녹음 품질이 낮거나 배경소음이 많은 경우\n- 실제 음성 내용이 없는 경우\n- 매우 조용한 음성이나 중얼거림인 경우\n\n다른 Whisper 모델(large, base 등)을 시도하거나 녹음 파일을 확인해 보세요."
-            else:
-                markdown_content += normalize_text(original_text, normalize_punct or True)  # 반복 단어 제거는 항상 활성화
+        return output_file_path
 
-        # 원자적 저장
-        if progress_callback:
-            progress_callback(f"'{file_path.name}' 파일 저장 중...")
+    finally:
+        # 임시 WAV 파일 삭제
+        if temp_wav_path and temp_wav_path.exists():
+            try:
+                temp_wav_path.unlink()
+                logging.info(f"임시 wav 파일 '{temp_wav_path.name}'을(를) 삭제했습니다.")
+            except OSError as e:
+                logging.error(f"임시 wav 파일 삭제 실패: {e}")
 
-        write_atomic(output_file_path, markdown_content)
 
-        # Obsidian MCP 자동 전송
-        try:
-            from datetime import datetime
+def merge_vocab_prompt(initial_prompt: str, progress_callback=None) -> str:
+    """vocab.json 상위 키워드를 사용자 initial_prompt 뒤에 덧붙입니다."""
+    # Load vocabulary keywords for improved STT accuracy
+    try:
+        vocab_manager = VocabularyManager(vocab_path=str(DB_BASE_PATH / "vocab.json"))
+        vocab_keywords = vocab_manager.get_top_keywords(limit=20, max_length=200)
 
-            # UUID 추출 (output_file_path의 부모 디렉토리명 = UUID 폴더)
-            file_uuid = output_file_path.parent.name
+        # Merge user-provided initial_prompt with vocabulary keywords
+        if vocab_keywords:
+            if initial_prompt:
+                # Combine user prompt and vocab keywords
+                combined_prompt = f"{initial_prompt}, {vocab_keywords}"
+            else:
+                combined_prompt = vocab_keywords
 
-            # 파일 생성 시각 (현재 시각)
-            created_at = datetime.now()
+            logging.info("Initial Prompt (vocab 포함): %s", combined_prompt)
+            if progress_callback:
+                progress_callback(f"vocab 키워드 로드: {len(vocab_keywords)}자")
 
-            # STT 텍스트 추출 (타임스탬프 제거한 순수 텍스트)
-            stt_text_only = "\n".join([text for _, _, text in processed_segments]) if processed_segments else result.get("text", "").strip()
+            return combined_prompt
+        logging.info("vocab.json이 비어있습니다. 사용자 제공 prompt만 사용합니다.")
+    except Exception as e:
+        logging.warning("vocab 키워드 로드 실패: %s. 사용자 제공 prompt만 사용합니다.", e)
+    return initial_prompt
 
-            if progress_callback:
-                progress_callback(f"'{file_path.name}' Obsidian 전송 중...")
 
-            # Obsidian에 전송 (동기 버전)
-            mcp_result = send_stt_to_obsidian_sync(
-                uuid=file_uuid,
-                stt_text=stt_text_only,
-                original_filename=file_path.name,
-                created_at=created_at
-            )
+def transcribe_file(file_path: Path, output_dir: Path, model_identifier: str,
+                    language: str, initial_prompt: str, filter_fillers: bool,
+                    min_seg_length: int, normalize_punct: bool, requested_device: str,
+                    progress_callback=None, cancel_event=None, export_to_obsidian: bool = True,
+                    queue_callback=None, prompt_provenance: dict = None,
+                    decoding_strategy: str = None) -> Path:
+    """단일 파일을 로컬 Whisper로 변환하고 마크다운 경로를 반환합니다.
 
-            if mcp_result["success"]:
-                logging.info(f"Obsidian MCP 전송 성공: {mcp_result['message']}")
-                if progress_callback:
-                    progress_callback(f"'{file_path.name}' Obsidian 전송 완료")
-            else:
-                logging.warning(f"Obsidian MCP 전송 실패 (처리는 계속): {mcp_result['message']}")
+    transcribe_audio_files와 달리 실패를 삼키지 않고 예외로 전달합니다.
+    추론 상태를 기다리는 동안 ``queue_callback(순번, 예상 시작 시각)``이 호출되며,
+    대기가 끝나면 순번 0으로 한 번 더 호출됩니다. ``prompt_provenance``가 있으면
+    ``initial_prompt``는 용어집까지 합친 최종 프롬프트로 보고 그대로 사용합니다.
+    """
+    if prompt_provenance is None:
+        initial_prompt = merge_vocab_prompt(initial_prompt, progress_callback)
+    output_dir.mkdir(parents=True, exist_ok=True)
 
-        except Exception as e:
-            # Obsidian 전송 실패해도 전체 프로세스는 계속 진행
-            logging.warning(f"Obsidian MCP 전송 중 오류 (처리는 계속): {e}")
+    device, device_message = resolve_inference_device(requested_device)
+    logging.info("선택된 장치: %s", device.upper())
+    if progress_callback:
+        progress_callback(f"실행 장치: {device.upper()}")
+        progress_callback(f"Whisper 모델 ({os.path.basename(model_identifier)}) 로드 중...")
+    if device_message:
+        logging.info(device_message)
 
+    # 상태를 빌리기 전에 다른 모델 로드로 이 모델이 내려가지 않도록 예약한 채로 사용
+    with engine_manager.use_pool(model_identifier, device) as pool:
         if progress_callback:
-            progress_callback(f"'{file_path.name}' 변환 완료!")
-
-        return output_file_path
+            progress_callback("모델 로드 완료")
 
-    finally:
-        # 임시 WAV 파일 삭제
-        if temp_wav_path and temp_wav_path.exists():
-            try:
-                temp_wav_path.unlink()
-                logging.info(f"임시 wav 파일 '{temp_wav_path.name}'을(를) 삭제했습니다.")
-            except OSError as e:
-                logging.error(f"임시 wav 파일 삭제 실패: {e}")
+        if cancel_event is not None and cancel_event.is_set():
+            raise TranscriptionCancelled("작업이 취소되었습니다.")
+
+        with pool.lease(queue_callback, cancel_event) as model, abort_on_cancel(model, cancel_event), \
+                background_inference() as applied:
+            if applied["priority"] != "normal" or applied["threads"]:
+                logging.info("추론 우선순위: %s (%s), CPU 스레드: %s",
+                             applied["priority"], applied["scope"], applied["threads"] or "기본값")
+            return transcribe_single_file(
+                file_path, output_dir, model, language, initial_prompt,
+                filter_fillers, min_seg_length, normalize_punct, device != "cpu",
+                progress_callback, model_name=os.path.basename(model_identifier),
+                export_to_obsidian=export_to_obsidian, prompt_provenance=prompt_provenance,
+                decoding_strategy=decoding_strategy
+            )
 
 
 def transcribe_audio_files(input_dir: str, output_dir: str, model_identifier: str,
                           language: str, initial_prompt: str, workers: int,
                           recursive: bool, filter_fillers: bool,
                           min_seg_length: int, normalize_punct: bool,
-                          requested_device: str, progress_callback=None):
+                          requested_device: str, progress_callback=None,
+                          cancel_event=None):
     """
     지정된 입력 디렉토리 내의 모든 오디오/비디오 파일을 Whisper를 사용하여
     텍스트로 변환하고, 변환된 텍스트를 마크다운(.md) 파일로 저장합니다.
@@ -587,30 +1315,11 @@ def transcribe_audio_files(input_dir: str, output_dir: str, model_identifier: st
         min_seg_length (int): 세그먼트 최소 길이
         normalize_punct (bool): 연속 마침표 정규화 여부
         requested_device (str): "auto", "cuda", "cpu", "mps" 중 하나로 지정된 장치
+        cancel_event (threading.Event): 설정되면 진행 중인 추론을 중단하고
+            TranscriptionCancelled를 발생시킴
     """
 
-    # Load vocabulary keywords for improved STT accuracy
-    try:
-        vocab_manager = VocabularyManager(vocab_path=str(DB_BASE_PATH / "vocab.json"))
-        vocab_keywords = vocab_manager.get_top_keywords(limit=20, max_length=200)
-
-        # Merge user-provided initial_prompt with vocabulary keywords
-        if vocab_keywords:
-            if initial_prompt:
-                # Combine user prompt and vocab keywords
-                combined_prompt = f"{initial_prompt}, {vocab_keywords}"
-            else:
-                combined_prompt = vocab_keywords
-
-            logging.info("Initial Prompt (vocab 포함): %s", combined_prompt)
-            if progress_callback:
-                progress_callback(f"vocab 키워드 로드: {len(vocab_keywords)}자")
-
-            initial_prompt = combined_prompt
-        else:
-            logging.info("vocab.json이 비어있습니다. 사용자 제공 prompt만 사용합니다.")
-    except Exception as e:
-        logging.warning("vocab 키워드 로드 실패: %s. 사용자 제공 prompt만 사용합니다.", e)
+    initial_prompt = merge_vocab_prompt(initial_prompt, progress_callback)
 
     input_path_obj = Path(input_dir)
     output_path_obj = Path(output_dir)
@@ -645,10 +1354,9 @@ def transcribe_audio_files(input_dir: str, output_dir: str, model_identifier: st
     if progress_callback:
         progress_callback(f"Whisper 모델 ({os.path.basename(model_identifier)}) 로드 중...")
 
-    logging.info("'%s' 모델을 로드하는 중...", os.path.basename(model_identifier))
     try:
-        model = whisper.load_model(model_identifier, device=device)
-        logging.info("모델 로드 완료.")
+        # 이미 로드된 모델이 있으면 재사용하고, 상태 풀에서 추론 상태를 빌려 쓴다
+        pool = engine_manager.get_pool(model_identifier, device, reserve=True)
         if progress_callback:
             progress_callback("모델 로드 완료")
     except Exception as e:
@@ -660,47 +1368,77 @@ def transcribe_audio_files(input_dir: str, output_dir: str, model_identifier: st
 
     # 변환 실행
     failures = []
-    
-    if workers <= 1:
-        # 순차 처리
-        for i, file_path in enumerate(files_to_process, 1):
-            if progress_callback:
-                progress_callback(f"파일 {i}/{len(files_to_process)} 처리 시작: {file_path.name}")
-            
-            logging.info("'%s' 파일 변환 시작", file_path.name)
-            try:
-                output_path = transcribe_single_file(
-                    file_path, output_path_obj, model, language, initial_prompt,
-                    filter_fillers, min_seg_length, normalize_punct, use_fp16, progress_callback
-                )
-                logging.info("변환 완료: %s → %s", file_path.name, output_path.name)
-            except Exception as e:
-                failures.append((file_path, str(e)))
-                logging.error("변환 실패: %s", file_path.name, exc_info=True)
+    try:
+        if workers <= 1:
+            # 순차 처리
+            for i, file_path in enumerate(files_to_process, 1):
                 if progress_callback:
-                    progress_callback(f"파일 {file_path.name} 변환 실패: {e}")
-    else:
-        # 병렬 처리 (주의: 단일 GPU/MPS/CPU에서는 비권장)
-        logging.warning("병렬 처리 모드 활성화 (workers=%d). 단일 GPU/MPS/CPU에서는 성능 향상이 제한적일 수 있습니다.", workers)
-        with ThreadPoolExecutor(max_workers=workers) as executor:
-            # 작업 제출
-            futures = {
-                executor.submit(
-                    transcribe_single_file, file_path, output_path_obj, model,
-                    language, initial_prompt, filter_fillers, min_seg_length,
-                    normalize_punct, use_fp16, progress_callback
-                ): file_path for file_path in files_to_process
-            }
-            
-            # 결과 수집
-            for future in as_completed(futures):
-                file_path = futures[future]
+                    progress_callback(f"파일 {i}/{len(files_to_process)} 처리 시작: {file_path.name}")
+
+                if cancel_event is not None and cancel_event.is_set():
+                    raise TranscriptionCancelled("작업이 취소되었습니다.")
+
+                logging.info("'%s' 파일 변환 시작", file_path.name)
                 try:
-                    output_path = future.result()
+                    with pool.lease() as model, abort_on_cancel(model, cancel_event), background_inference():
+                        output_path = transcribe_single_file(
+                            file_path, output_path_obj, model, language, initial_prompt,
+                            filter_fillers, min_seg_length, normalize_punct, use_fp16, progress_callback,
+                            model_name=os.path.basename(model_identifier)
+                        )
                     logging.info("변환 완료: %s → %s", file_path.name, output_path.name)
+                except TranscriptionCancelled:
+                    logging.info("변환 취소됨: %s", file_path.name)
+                    if progress_callback:
+                        progress_callback(f"파일 {file_path.name} 변환 취소됨")
+                    raise
                 except Exception as e:
                     failures.append((file_path, str(e)))
                     logging.error("변환 실패: %s", file_path.name, exc_info=True)
+                    if progress_callback:
+                        progress_callback(f"파일 {file_path.name} 변환 실패: {e}")
+        else:
+            # 병렬 처리 (주의: 단일 GPU/MPS/CPU에서는 비권장)
+            logging.warning("병렬 처리 모드 활성화 (workers=%d). 단일 GPU/MPS/CPU에서는 성능 향상이 제한적일 수 있습니다.", workers)
+            if workers > pool.size:
+                logging.warning("Whisper 상태 풀 크기(%d)보다 workers가 많아 일부 파일은 대기합니다. WHISPER_POOL_SIZE를 늘려보세요.", pool.size)
+
+            def transcribe_with_lease(file_path: Path) -> Path:
+                if cancel_event is not None and cancel_event.is_set():
+                    raise TranscriptionCancelled("작업이 취소되었습니다.")
+                with pool.lease() as model, abort_on_cancel(model, cancel_event), background_inference():
+                    return transcribe_single_file(
+                        file_path, output_path_obj, model,
+                        language, initial_prompt, filter_fillers, min_seg_length,
+                        normalize_punct, use_fp16, progress_callback,
+                        model_name=os.path.basename(model_identifier)
+                    )
+
+            cancelled = False
+            with ThreadPoolExecutor(max_workers=workers) as executor:
+                # 작업 제출
+                futures = {
+                    executor.submit(transcribe_with_lease, file_path): file_path
+                    for file_path in files_to_process
+                }
+
+                # 결과 수집
+                for future in as_completed(futures):
+                    file_path = futures[future]
+                    try:
+                        output_path = future.result()
+                        logging.info("변환 완료: %s → %s", file_path.name, output_path.name)
+                    except TranscriptionCancelled:
+                        cancelled = True
+                    except Exception as e:
+                        failures.append((file_path, str(e)))
+                        logging.error("변환 실패: %s", file_path.name, exc_info=True)
+
+            if cancelled:
+                logging.info("병렬 변환이 취소되었습니다.")
+                raise TranscriptionCancelled("작업이 취소되었습니다.")
+    finally:
+        pool.release()
 
     # 최종 결과 요약
     logging.info("="*50)
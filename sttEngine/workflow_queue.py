@@ -0,0 +1,139 @@
+"""Step-level queue for workflow tasks.
+
+``/process`` (and every other entry point of ``run_workflow``) runs in its
+own request thread, so concurrent uploads used to run STT, embedding and
+summary all at once and fight over the GPU/CPU. Each workflow step now
+takes a slot from :data:`WORKFLOW_QUEUE` first; at most
+``TASK_QUEUE_CONCURRENCY`` steps run at a time (0 disables the queue).
+
+Waiting steps are grouped by category — every queued ``stt`` step before
+any ``summary`` step before any ``embedding`` step, FIFO within a category —
+so the Whisper model finishes the pending transcriptions before the LLM is
+loaded for summaries. A task releases its slot between steps and queues
+again for the next one.
+
+The Whisper state pool (``WHISPER_POOL_SIZE``) still queues inference
+requests that bypass the workflow (range re-transcription).
+"""
+
+from __future__ import annotations
+
+import threading
+import time
+from collections import deque
+from typing import Any, Callable, Dict, List, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+TASK_QUEUE_CONCURRENCY = max(0, get_config_value("TASK_QUEUE_CONCURRENCY", 1, int))
+# 대기열 정렬 순서 (앞 카테고리가 모두 끝나야 다음 카테고리 시작)
+CATEGORIES = ("stt", "summary", "embedding")
+
+
+class WorkflowQueue:
+    """Slots for workflow steps, granted by category priority then arrival order."""
+
+    def __init__(self, concurrency: int = TASK_QUEUE_CONCURRENCY):
+        self.concurrency = concurrency
+        self._condition = threading.Condition()
+        self._waiting: List[Dict[str, Any]] = []
+        self._running: Dict[str, Dict[str, Any]] = {}
+        self._seq = 0
+        self._durations = deque(maxlen=20)
+
+    @property
+    def enabled(self) -> bool:
+        return self.concurrency > 0
+
+    def _ordered(self) -> List[Dict[str, Any]]:
+        return sorted(self._waiting, key=lambda entry: (CATEGORIES.index(entry["category"]), entry["seq"]))
+
+    def _estimate_start(self, position: int) -> Optional[float]:
+        if not self._durations:
+            return None
+        average = sum(self._durations) / len(self._durations)
+        now = time.time()
+        finish_times = sorted(max(now, entry["started"] + average) for entry in self._running.values())
+        rounds, index = divmod(position - 1, max(len(finish_times), 1))
+        base = finish_times[index] if finish_times else now
+        return base + rounds * average
+
+    def _release_locked(self, key: str) -> None:
+        entry = self._running.pop(key, None)
+        if entry is not None:
+            self._durations.append(time.time() - entry["started"])
+            self._condition.notify_all()
+
+    def acquire(self, key: str, category: str,
+                on_queued: Optional[Callable[[int, Optional[float]], None]] = None,
+                cancel_event=None) -> bool:
+        """Wait for a slot for ``key``'s ``category`` step; ``False`` when cancelled while waiting.
+
+        A slot ``key`` still holds from its previous step is released first.
+        ``on_queued(position, estimated_start)`` is called whenever the
+        position changes and with ``(0, None)`` once a queued step starts.
+        """
+        if category not in CATEGORIES:
+            raise ValueError(f"알 수 없는 작업 카테고리: {category}")
+        with self._condition:
+            self._release_locked(key)
+            if not self.enabled:
+                return True
+            self._seq += 1
+            entry = {"key": key, "category": category, "seq": self._seq, "queued_at": time.time()}
+            self._waiting.append(entry)
+        reported = None
+        try:
+            while True:
+                with self._condition:
+                    ordered = self._ordered()
+                    if len(self._running) < self.concurrency and ordered[0] is entry:
+                        self._waiting.remove(entry)
+                        self._running[key] = {"category": category, "started": time.time()}
+                        self._condition.notify_all()
+                        break
+                    position = ordered.index(entry) + 1
+                    if position == reported:
+                        self._condition.wait(timeout=1.0)
+                        if cancel_event is not None and cancel_event.is_set():
+                            self._waiting.remove(entry)
+                            self._condition.notify_all()
+                            return False
+                        continue
+                    estimated_start = self._estimate_start(position)
+                reported = position
+                if on_queued:
+                    on_queued(position, estimated_start)
+        except BaseException:
+            with self._condition:
+                if entry in self._waiting:
+                    self._waiting.remove(entry)
+                    self._condition.notify_all()
+            raise
+        if reported is not None and on_queued:
+            on_queued(0, None)
+        return True
+
+    def release(self, key: str) -> None:
+        """Give back the slot ``key`` holds (no-op when it holds none)."""
+        with self._condition:
+            self._release_locked(key)
+
+    def snapshot(self) -> Dict[str, Any]:
+        """Running and waiting steps (waiting in grant order)."""
+        with self._condition:
+            return {
+                "concurrency": self.concurrency,
+                "running": [{"task_id": key, **entry} for key, entry in self._running.items()],
+                "waiting": [
+                    {"task_id": entry["key"], "category": entry["category"], "queue_position": position,
+                     "queued_at": entry["queued_at"]}
+                    for position, entry in enumerate(self._ordered(), start=1)
+                ],
+            }
+
+
+WORKFLOW_QUEUE = WorkflowQueue()
@@ -0,0 +1,122 @@
+"""Per-connection outbound queues for the progress WebSocket.
+
+Broadcasting used to ``await client.send`` for every client at once, so a
+client on a slow link piled up one pending coroutine per progress event and
+held frames in memory without limit. Each connection now has a
+:class:`ClientOutbox` drained by its own sender task:
+
+* messages with a coalescing key replace the pending message with the same
+  key instead of queueing behind it (latest wins): progress per task, the
+  Whisper queue snapshot, updates per record. A client that falls behind
+  gets the newest state of each task, not every intermediate percentage;
+* at most ``WS_OUTBOX_MAX_MESSAGES`` distinct messages wait per connection.
+  Beyond that ``WS_OVERFLOW_POLICY`` decides: ``drop_oldest`` (default)
+  discards the oldest pending message (one-off messages before coalesced
+  state), ``disconnect`` closes the connection so the client reconnects and
+  resyncs over HTTP;
+* a single send that takes longer than ``WS_SEND_TIMEOUT_SECONDS`` closes the
+  connection with :data:`CLOSE_SLOW_CLIENT`.
+
+Enqueueing never waits, so publishers (and the progress bus) are never held
+up by a client. All methods except :meth:`ClientOutbox.stats` must run on the
+WebSocket event loop.
+"""
+
+from __future__ import annotations
+
+import asyncio
+import itertools
+import time
+from collections import OrderedDict
+from typing import Any, Dict, Hashable, Optional
+
+try:  # pragma: no cover - import resolution for both package/script execution
+    from .config import get_config_value
+except ImportError:  # pragma: no cover - fallback when imported as a script
+    from config import get_config_value  # type: ignore
+
+WS_OUTBOX_MAX_MESSAGES = max(1, get_config_value("WS_OUTBOX_MAX_MESSAGES", 200, int))
+WS_OVERFLOW_POLICY = get_config_value("WS_OVERFLOW_POLICY", "drop_oldest", str).strip().lower()
+WS_SEND_TIMEOUT_SECONDS = get_config_value("WS_SEND_TIMEOUT_SECONDS", 10, float)
+OVERFLOW_POLICIES = ("drop_oldest", "disconnect")
+
+CLOSE_SLOW_CLIENT = 4008
+CLOSE_OVERFLOW = 4009
+_UNIQUE = object()
+
+
+class ClientOutbox:
+    def __init__(self, websocket, max_messages: int = WS_OUTBOX_MAX_MESSAGES,
+                 policy: str = WS_OVERFLOW_POLICY, send_timeout: float = WS_SEND_TIMEOUT_SECONDS):
+        if policy not in OVERFLOW_POLICIES:
+            print(f"알 수 없는 WS_OVERFLOW_POLICY 값: {policy} (지원: {', '.join(OVERFLOW_POLICIES)}), drop_oldest 사용")
+            policy = "drop_oldest"
+        self.websocket = websocket
+        self.max_messages = max_messages
+        self.policy = policy
+        self.send_timeout = send_timeout
+        self.connected_at = time.time()
+        self.sent = 0
+        self.coalesced = 0
+        self.dropped = 0
+        self.closed_reason: Optional[str] = None
+        self._pending: "OrderedDict[Hashable, str]" = OrderedDict()
+        self._unique = itertools.count()
+        self._wakeup = asyncio.Event()
+
+    def put(self, data: str, key: Optional[Hashable] = None) -> bool:
+        """Queue ``data``; a pending message with the same ``key`` is replaced. ``False`` once closed."""
+        if self.closed_reason:
+            return False
+        if key is not None and key in self._pending:
+            # 자리는 유지하고 내용만 최신으로 (한 작업의 폭주가 다른 작업을 밀어내지 않도록)
+            self._pending[key] = data
+            self.coalesced += 1
+            return True
+        if len(self._pending) >= self.max_messages:
+            if self.policy == "disconnect":
+                self._close(CLOSE_OVERFLOW, "outbound queue overflow")
+                return False
+            # 최신 상태(합쳐지는 메시지)보다 일회성 메시지를 먼저 버림
+            victim = next((k for k in self._pending if isinstance(k, tuple) and k[0] is _UNIQUE),
+                          next(iter(self._pending)))
+            del self._pending[victim]
+            self.dropped += 1
+        self._pending[key if key is not None else (_UNIQUE, next(self._unique))] = data
+        self._wakeup.set()
+        return True
+
+    def _close(self, code: int, reason: str) -> None:
+        self.closed_reason = reason
+        self._pending.clear()
+        self._wakeup.set()
+        asyncio.ensure_future(self.websocket.close(code, reason))
+
+    async def run(self) -> None:
+        """Send pending messages until the connection closes."""
+        while not self.closed_reason:
+            await self._wakeup.wait()
+            self._wakeup.clear()
+            while self._pending and not self.closed_reason:
+                _, data = self._pending.popitem(last=False)
+                try:
+                    await asyncio.wait_for(self.websocket.send(data), self.send_timeout)
+                except asyncio.TimeoutError:
+                    self._close(CLOSE_SLOW_CLIENT, "client too slow")
+                    return
+                except Exception:
+                    self.closed_reason = "connection closed"
+                    return
+                self.sent += 1
+
+    def stats(self) -> Dict[str, Any]:
+        return {
+            "remote": str(getattr(self.websocket, "remote_address", "") or ""),
+            "connected_at": self.connected_at,
+            "pending": len(self._pending),
+            "sent": self.sent,
+            "coalesced": self.coalesced,
+            "dropped": self.dropped,
+            "policy": self.policy,
+            "closed_reason": self.closed_reason,
+        }
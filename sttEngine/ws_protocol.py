@@ -0,0 +1,282 @@
+"""Versioned message schema of the progress WebSocket (``ws://host:8765``).
+
+Every server message is a JSON object tagged with the protocol version and
+its type, the remaining keys being the fields of the message dataclass::
+
+    {"v": 1, "type": "task_progress", "task_id": "...", "message": "...", "seq": 42, ...}
+
+Server → client: :class:`Hello` (handshake reply), :class:`TaskProgress`,
+:class:`RecordUpdated`, :class:`QueueChanged`, :class:`WatchMatched`,
+:class:`Notification` and :class:`Error`.
+Client → server: ``{"type": "hello", "protocol_version": 1, "client_id": "..."}``
+once after connecting, then ``{"type": "heartbeat", "client_id": "..."}``.
+
+A client that asks for a version the server does not speak gets an
+``Error`` with ``code: "unsupported_protocol_version"`` and the supported
+versions, and the connection is closed with :data:`CLOSE_UNSUPPORTED_VERSION`.
+Clients that never say hello are treated as version 1, which keeps
+``task_id``/``message`` at the top level like the untyped messages before.
+
+:func:`asyncapi_document` renders the schema as an AsyncAPI 2.6 document
+(``GET /ws/asyncapi.json``).
+"""
+
+from __future__ import annotations
+
+import json
+import time
+from dataclasses import asdict, dataclass, field, fields
+from enum import Enum
+from typing import Any, ClassVar, Dict, List, Optional, Tuple
+
+PROTOCOL_VERSION = 1
+SUPPORTED_VERSIONS = (1,)
+WEBSOCKET_PORT = 8765
+# 4000-4999: 애플리케이션 정의 종료 코드
+# AUTH_ENABLED일 때 자격 증명 없이 연결한 경우
+CLOSE_UNAUTHORIZED = 4001
+CLOSE_UNSUPPORTED_VERSION = 4002
+
+
+class MessageType(str, Enum):
+    HELLO = "hello"
+    TASK_PROGRESS = "task_progress"
+    RECORD_UPDATED = "record_updated"
+    QUEUE_CHANGED = "queue_changed"
+    WATCH_MATCHED = "watch_matched"
+    NOTIFICATION = "notification"
+    ERROR = "error"
+
+
+class ClientMessageType(str, Enum):
+    HELLO = "hello"
+    HEARTBEAT = "heartbeat"
+
+
+class ProtocolError(ValueError):
+    """Raised for client messages that break the protocol; ``code`` goes into the ``Error`` reply."""
+
+    def __init__(self, code: str, message: str, **details: Any):
+        self.code = code
+        self.details = details
+        super().__init__(message)
+
+
+@dataclass
+class Hello:
+    """Handshake reply with the negotiated version."""
+
+    TYPE: ClassVar[MessageType] = MessageType.HELLO
+    protocol_version: int
+    supported_versions: List[int]
+    server_time: float = field(default_factory=time.time)
+
+
+@dataclass
+class TaskProgress:
+    """Progress message of a task; ``done`` on its last event, ``queue`` while it waits for Whisper."""
+
+    TYPE: ClassVar[MessageType] = MessageType.TASK_PROGRESS
+    task_id: str
+    message: Optional[str]
+    seq: int
+    timestamp: float
+    done: bool = False
+    queue: Optional[Dict[str, Any]] = None
+
+
+@dataclass
+class RecordUpdated:
+    """A record was added, changed or removed; fetch ``GET /history/changes`` for the details."""
+
+    TYPE: ClassVar[MessageType] = MessageType.RECORD_UPDATED
+    record_id: str
+    change: str  # updated | removed
+    change_seq: Optional[int] = None
+
+
+@dataclass
+class QueueChanged:
+    """Tasks waiting for a workflow slot or a Whisper state, in order."""
+
+    TYPE: ClassVar[MessageType] = MessageType.QUEUE_CHANGED
+    tasks: List[Dict[str, Any]]
+
+
+@dataclass
+class WatchMatched:
+    """A processed record matched a watch rule (``keywords`` found, ``score`` of the semantic query)."""
+
+    TYPE: ClassVar[MessageType] = MessageType.WATCH_MATCHED
+    rule_id: str
+    rule_name: str
+    record_id: str
+    title: Optional[str] = None
+    keywords: List[str] = field(default_factory=list)
+    score: Optional[float] = None
+
+
+@dataclass
+class Notification:
+    """A ``desktop`` notification (``notifications``) to show as a browser notification."""
+
+    TYPE: ClassVar[MessageType] = MessageType.NOTIFICATION
+    event: str
+    title: str
+    text: str
+    record_id: Optional[str] = None
+    url: Optional[str] = None
+
+
+@dataclass
+class Error:
+    """Protocol error; ``details`` depends on ``code``."""
+
+    TYPE: ClassVar[MessageType] = MessageType.ERROR
+    code: str
+    message: str
+    details: Dict[str, Any] = field(default_factory=dict)
+
+
+SERVER_MESSAGES = (Hello, TaskProgress, RecordUpdated, QueueChanged, WatchMatched, Notification, Error)
+
+
+def encode(message: Any, version: int = PROTOCOL_VERSION) -> str:
+    return json.dumps({"v": version, "type": message.TYPE.value, **asdict(message)}, ensure_ascii=False)
+
+
+def coalesce_key(message: Any) -> Optional[Tuple[str, ...]]:
+    """Messages with the same key supersede each other in a slow client's queue (``None``: never)."""
+    if isinstance(message, TaskProgress):
+        return (message.TYPE.value, message.task_id)
+    if isinstance(message, RecordUpdated):
+        return (message.TYPE.value, message.record_id)
+    if isinstance(message, QueueChanged):
+        return (message.TYPE.value,)
+    return None
+
+
+def error_from(exc: ProtocolError) -> Error:
+    return Error(code=exc.code, message=str(exc), details=exc.details)
+
+
+def negotiate_version(requested: Any) -> int:
+    """Version to speak with a client asking for ``requested`` (``None`` → current)."""
+    if requested is None:
+        return PROTOCOL_VERSION
+    if isinstance(requested, bool) or not isinstance(requested, int) or requested not in SUPPORTED_VERSIONS:
+        raise ProtocolError(
+            "unsupported_protocol_version",
+            f"지원하지 않는 WebSocket 프로토콜 버전입니다: {requested}",
+            supported_versions=list(SUPPORTED_VERSIONS),
+        )
+    return requested
+
+
+def parse_client_message(raw: Any) -> Tuple[ClientMessageType, Dict[str, Any]]:
+    """Validate one client frame; returns its type and the decoded object."""
+    try:
+        message = json.loads(raw)
+    except (TypeError, ValueError):
+        raise ProtocolError("invalid_message", "메시지는 JSON 객체여야 합니다.")
+    if not isinstance(message, dict):
+        raise ProtocolError("invalid_message", "메시지는 JSON 객체여야 합니다.")
+    try:
+        message_type = ClientMessageType(message.get("type"))
+    except ValueError:
+        raise ProtocolError(
+            "unknown_message_type",
+            f"알 수 없는 메시지 유형입니다: {message.get('type')}",
+            supported_types=[t.value for t in ClientMessageType],
+        )
+    return message_type, message
+
+
+_JSON_TYPES = {
+    "str": {"type": "string"},
+    "int": {"type": "integer"},
+    "float": {"type": "number"},
+    "bool": {"type": "boolean"},
+    "List[int]": {"type": "array", "items": {"type": "integer"}},
+    "List[str]": {"type": "array", "items": {"type": "string"}},
+    "List[Dict[str, Any]]": {"type": "array", "items": {"type": "object"}},
+    "Dict[str, Any]": {"type": "object"},
+}
+
+
+def _field_schema(annotation: str) -> Dict[str, Any]:
+    if annotation.startswith("Optional[") and annotation.endswith("]"):
+        schema = dict(_field_schema(annotation[len("Optional["):-1]))
+        schema["type"] = [schema["type"], "null"]
+        return schema
+    return _JSON_TYPES[annotation]
+
+
+def message_schema(message_class: type) -> Dict[str, Any]:
+    """JSON schema of one server message, derived from its dataclass fields."""
+    properties: Dict[str, Any] = {
+        "v": {"type": "integer", "enum": list(SUPPORTED_VERSIONS)},
+        "type": {"type": "string", "const": message_class.TYPE.value},
+    }
+    required = ["v", "type"]
+    for item in fields(message_class):
+        properties[item.name] = _field_schema(str(item.type))
+        if not str(item.type).startswith("Optional["):
+            required.append(item.name)
+    return {
+        "name": message_class.__name__,
+        "summary": (message_class.__doc__ or "").strip(),
+        "payload": {"type": "object", "properties": properties, "required": required},
+    }
+
+
+def asyncapi_document() -> Dict[str, Any]:
+    client_messages = {
+        "ClientHello": {
+            "name": "ClientHello",
+            "summary": "Sent once after connecting; protocol_version defaults to the current version.",
+            "payload": {
+                "type": "object",
+                "properties": {
+                    "type": {"type": "string", "const": ClientMessageType.HELLO.value},
+                    "protocol_version": {"type": "integer"},
+                    "client_id": {"type": "string", "maxLength": 100},
+                },
+                "required": ["type"],
+            },
+        },
+        "Heartbeat": {
+            "name": "Heartbeat",
+            "summary": "Keeps the client's tasks from being treated as orphaned.",
+            "payload": {
+                "type": "object",
+                "properties": {
+                    "type": {"type": "string", "const": ClientMessageType.HEARTBEAT.value},
+                    "client_id": {"type": "string", "maxLength": 100},
+                },
+                "required": ["type", "client_id"],
+            },
+        },
+    }
+    server_messages = {cls.__name__: message_schema(cls) for cls in SERVER_MESSAGES}
+    return {
+        "asyncapi": "2.6.0",
+        "info": {
+            "title": "RecordRoute progress WebSocket",
+            "version": str(PROTOCOL_VERSION),
+            "description": (
+                f"Unsupported protocol versions receive an Error with code unsupported_protocol_version "
+                f"and the connection is closed with code {CLOSE_UNSUPPORTED_VERSION}. "
+                f"With AUTH_ENABLED, connections without the auth cookie or an API key header "
+                f"are closed with code {CLOSE_UNAUTHORIZED}."
+            ),
+        },
+        "servers": {"local": {"url": f"localhost:{WEBSOCKET_PORT}", "protocol": "ws"}},
+        "channels": {
+            "/": {
+                "publish": {"message": {"oneOf": [{"$ref": f"#/components/messages/{n}"} for n in client_messages]}},
+                "subscribe": {"message": {"oneOf": [{"$ref": f"#/components/messages/{n}"} for n in server_messages]}},
+            }
+        },
+        "components": {"messages": {**client_messages, **server_messages}},
+    }
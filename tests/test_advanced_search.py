@@ -0,0 +1,68 @@
+"""Regression tests for the advanced search ``date`` and ``speaker`` filters."""
+
+import sys
+import unittest
+from datetime import datetime, timezone
+from pathlib import Path
+
+sys.path.insert(0, str(Path(__file__).resolve().parent.parent))
+
+from sttEngine.advanced_search import FilterContext, evaluate_filter, validate_filter  # noqa: E402
+
+
+def matches(date_filter, timestamp):
+    node = {"date": date_filter}
+    validate_filter(node)
+    return evaluate_filter(node, {"id": "r1", "timestamp": timestamp}, FilterContext(lambda record, source: ""))
+
+
+class DateFilterTest(unittest.TestCase):
+    def test_date_only_to_includes_the_whole_day(self):
+        self.assertTrue(matches({"to": "2026-01-05"}, "2026-01-05T00:00:00"))
+        self.assertTrue(matches({"to": "2026-01-05"}, "2026-01-05T23:59:59.999999"))
+        self.assertFalse(matches({"to": "2026-01-05"}, "2026-01-06T00:00:00"))
+
+    def test_datetime_to_is_exact(self):
+        self.assertTrue(matches({"to": "2026-01-05T12:00:00"}, "2026-01-05T12:00:00"))
+        self.assertFalse(matches({"to": "2026-01-05T12:00:00"}, "2026-01-05T12:00:01"))
+
+    def test_from_and_to_range(self):
+        window = {"from": "2026-01-01", "to": "2026-01-31"}
+        self.assertTrue(matches(window, "2026-01-31T18:30:00"))
+        self.assertFalse(matches(window, "2025-12-31T23:59:59"))
+        self.assertFalse(matches(window, "2026-02-01T00:00:00"))
+
+    def test_aware_bound_against_naive_timestamp(self):
+        moment = datetime(2026, 1, 5, 12, 0, tzinfo=timezone.utc)
+        local = moment.astimezone().replace(tzinfo=None).isoformat()
+        self.assertTrue(matches({"from": moment.isoformat()}, local))
+        self.assertTrue(matches({"to": moment.isoformat()}, local))
+        self.assertFalse(matches({"to": "2026-01-05T12:00:00+00:00"}, "2027-01-01T00:00:00"))
+
+    def test_aware_timestamp_against_naive_bound(self):
+        self.assertTrue(matches({"from": "2026-01-01", "to": "2026-12-31"}, "2026-06-01T10:00:00+09:00"))
+        self.assertFalse(matches({"to": "2025-12-31"}, "2026-06-01T10:00:00+09:00"))
+
+
+class SpeakerFilterTest(unittest.TestCase):
+    RECORD = {"id": "r1", "speakers": ["SPEAKER_00", "SPEAKER_01"], "speaker_names": {"SPEAKER_01": "김민지"}}
+
+    def matches(self, name, record=None):
+        node = {"speaker": name}
+        validate_filter(node)
+        # 전사 본문에 이름이 나와도 화자로 보지 않음
+        context = FilterContext(lambda record, source: "박서준 님이 말했듯이 김민지 님 안건은 보류")
+        return evaluate_filter(node, record or self.RECORD, context)
+
+    def test_matches_speaker_labels_and_assigned_names(self):
+        self.assertTrue(self.matches("speaker_00"))
+        self.assertTrue(self.matches("김민지"))
+        self.assertTrue(self.matches("민지"))
+
+    def test_ignores_names_only_mentioned_in_the_transcript(self):
+        self.assertFalse(self.matches("박서준"))
+        self.assertFalse(self.matches("김민지", {"id": "r2", "speakers": ["SPEAKER_00"]}))
+
+
+if __name__ == "__main__":
+    unittest.main()
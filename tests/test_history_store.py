@@ -0,0 +1,111 @@
+"""Regression tests for upload history persistence."""
+
+import os
+import sys
+import tempfile
+import unittest
+from pathlib import Path
+
+sys.path.insert(0, str(Path(__file__).resolve().parent.parent))
+
+from sttEngine.history_store import JsonHistoryStore, SqliteHistoryStore  # noqa: E402
+
+
+def make_records(count):
+    return [
+        {"id": f"record-{index:04d}", "timestamp": f"2026-01-01T00:{index // 60:02d}:{index % 60:02d}",
+         "filename": f"audio-{index}.m4a", "deleted": False}
+        for index in reversed(range(count))
+    ]
+
+
+class HistoryStoreTest(unittest.TestCase):
+    def setUp(self):
+        self._tmp = tempfile.TemporaryDirectory()
+        self.db_base = Path(self._tmp.name)
+
+    def tearDown(self):
+        self._tmp.cleanup()
+
+    def test_sqlite_keeps_more_than_100_records(self):
+        store = SqliteHistoryStore(self.db_base)
+        store.save(make_records(150))
+        history = SqliteHistoryStore(self.db_base).load()
+        self.assertEqual(len(history), 150)
+        self.assertEqual(history[0]["id"], "record-0149")
+        self.assertEqual(history[-1]["id"], "record-0000")
+
+    def test_sqlite_keeps_records_added_one_at_a_time(self):
+        store = SqliteHistoryStore(self.db_base)
+        for record in reversed(make_records(150)):
+            history = store.load()
+            history.insert(0, record)
+            store.save(history)
+        self.assertEqual(len(store.load()), 150)
+        self.assertEqual(store.page(0, 200)[1], 150)
+
+    def test_nested_load_and_save_keep_the_outer_baseline(self):
+        store = SqliteHistoryStore(self.db_base)
+        store.save(make_records(3))
+        outer = store.load()
+        # 같은 스레드에서 다른 저장이 끼어들어 기록을 추가하고 하나를 고침
+        inner = store.load()
+        inner.insert(0, {"id": "record-new", "timestamp": "2026-01-02T00:00:00", "deleted": False})
+        next(r for r in inner if r["id"] == "record-0000")["filename"] = "renamed.m4a"
+        store.save(inner)
+        outer[0]["deleted"] = True
+        store.save(outer)
+        history = {record["id"]: record for record in store.load()}
+        self.assertIn("record-new", history)
+        self.assertEqual(history["record-0000"]["filename"], "renamed.m4a")
+        self.assertTrue(history["record-0002"]["deleted"])
+
+    def test_saving_the_same_list_twice_uses_the_written_rows(self):
+        store = SqliteHistoryStore(self.db_base)
+        store.save(make_records(2))
+        history = store.load()
+        history[0]["filename"] = "first.m4a"
+        store.save(history)
+        other = store.load()
+        other[1]["filename"] = "other.m4a"
+        store.save(other)
+        history.pop()
+        store.save(history)
+        self.assertEqual([(r["id"], r["filename"]) for r in store.load()], [("record-0001", "first.m4a")])
+
+    def test_json_keeps_more_than_100_records(self):
+        store = JsonHistoryStore(self.db_base)
+        store.save(make_records(150))
+        self.assertEqual(len(JsonHistoryStore(self.db_base).load()), 150)
+
+
+class AddUploadRecordTest(unittest.TestCase):
+    """``add_upload_record`` must not trim the history (the SQLite store deletes dropped rows)."""
+
+    @classmethod
+    def setUpClass(cls):
+        cls._tmp = tempfile.TemporaryDirectory()
+        os.environ["DB_FOLDER_PATH"] = cls._tmp.name
+        try:
+            from sttEngine import server
+        except ImportError as exc:  # Whisper/Ollama 등 서버 의존성이 없는 환경
+            cls._tmp.cleanup()
+            raise unittest.SkipTest(f"server를 불러올 수 없습니다: {exc}")
+        cls.server = server
+
+    @classmethod
+    def tearDownClass(cls):
+        cls._tmp.cleanup()
+
+    def test_add_upload_record_keeps_150_records(self):
+        upload_dir = Path(self._tmp.name) / "uploads"
+        upload_dir.mkdir(exist_ok=True)
+        for index in range(150):
+            path = upload_dir / f"audio-{index}.m4a"
+            path.write_bytes(b"")
+            self.server.add_upload_record(path, "audio")
+        self.assertEqual(len(self.server.load_upload_history()), 150)
+
+
+if __name__ == "__main__":
+    unittest.main()
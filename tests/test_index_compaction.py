@@ -0,0 +1,58 @@
+"""Regression tests for vector index compaction running next to new embeddings."""
+
+import sys
+import tempfile
+import unittest
+from pathlib import Path
+from unittest import mock
+
+ROOT = Path(__file__).resolve().parent.parent
+sys.path.insert(0, str(ROOT))
+sys.path.insert(1, str(ROOT / "sttEngine"))  # embedding_pipeline은 config 등을 바로 import
+
+try:
+    from sttEngine import embedding_pipeline
+except ImportError as exc:  # numpy/requests 등 임베딩 의존성이 없는 환경
+    raise unittest.SkipTest(f"embedding_pipeline을 불러올 수 없습니다: {exc}")
+
+
+class CompactIndexTest(unittest.TestCase):
+    def setUp(self):
+        self._tmp = tempfile.TemporaryDirectory()
+        base = Path(self._tmp.name)
+        self.vector_dir = base / "vector_store"
+        self.vector_dir.mkdir()
+        for name in ("kept.npy", "orphan.npy"):
+            (self.vector_dir / name).write_bytes(b"vector")
+        index = {"/records/kept.md": {"vector": "kept.npy", "deleted": False}}
+        self._patches = [
+            mock.patch.object(embedding_pipeline, "VECTOR_DIR", self.vector_dir),
+            mock.patch.object(embedding_pipeline, "DELETED_VECTOR_DIR", base / "deleted"),
+            mock.patch.object(embedding_pipeline, "load_index", return_value=index),
+            mock.patch.object(embedding_pipeline, "save_index"),
+        ]
+        for patch in self._patches:
+            patch.start()
+
+    def tearDown(self):
+        for patch in self._patches:
+            patch.stop()
+        self._tmp.cleanup()
+
+    def test_fresh_unreferenced_vector_is_kept(self):
+        # 임베딩이 벡터를 저장하고 아직 색인에 추가하기 전
+        report = embedding_pipeline.compact_index()
+        self.assertTrue((self.vector_dir / "orphan.npy").exists())
+        self.assertEqual(report["removed_files"], [])
+        self.assertEqual(report["skipped_recent_files"], ["orphan.npy"])
+
+    def test_old_unreferenced_vector_is_removed(self):
+        with mock.patch.object(embedding_pipeline, "ORPHAN_GRACE_SECONDS", -60):
+            report = embedding_pipeline.compact_index()
+        self.assertFalse((self.vector_dir / "orphan.npy").exists())
+        self.assertTrue((self.vector_dir / "kept.npy").exists())
+        self.assertEqual(report["removed_files"], ["orphan.npy"])
+
+
+if __name__ == "__main__":
+    unittest.main()
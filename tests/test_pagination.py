@@ -0,0 +1,170 @@
+"""Regression tests for cursor pagination round-trips."""
+
+import os
+import sys
+import tempfile
+import unittest
+from pathlib import Path
+from unittest import mock
+
+sys.path.insert(0, str(Path(__file__).resolve().parent.parent))
+
+from sttEngine.pagination import (  # noqa: E402
+    CursorError,
+    cursor_page,
+    decode_cursor,
+    encode_cursor,
+    parse_page_params,
+    sort_key,
+)
+
+
+def make_records(count):
+    # 같은 시각이 여러 개, 시각 없는 기록도 섞음
+    records = [{"id": f"r{index:03d}", "timestamp": f"2026-01-{index // 10 + 1:02d}T09:00:00"}
+               for index in range(count)]
+    records[7]["timestamp"] = None
+    records[42]["timestamp"] = ""
+    return records
+
+
+def history_key(descending):
+    return lambda record: sort_key(record.get("timestamp"), record.get("id"), descending=descending)
+
+
+def read_all_pages(items, limit, descending, list_name="history", sort="timestamp"):
+    pages, cursor = [], ""
+    while True:
+        page, cursor = cursor_page(items, history_key(descending), cursor, limit, list_name, sort, descending)
+        pages.append(page)
+        if cursor is None:
+            return pages
+
+
+class CursorRoundTripTest(unittest.TestCase):
+    def test_cursor_encodes_and_decodes_the_key(self):
+        key = sort_key("2026-01-05T09:00:00", "회의-기록", descending=True)
+        cursor = encode_cursor("history", "timestamp:desc", key)
+        self.assertNotIn("=", cursor)
+        self.assertEqual(decode_cursor(cursor, "history", "timestamp:desc"), key)
+        self.assertIsNone(decode_cursor("", "history", "timestamp:desc"))
+
+    def test_pages_cover_every_item_once_in_order(self):
+        records = make_records(125)
+        for descending in (False, True):
+            for limit in (1, 7, 50, 125, 500):
+                with self.subTest(descending=descending, limit=limit):
+                    pages = read_all_pages(records, limit, descending)
+                    served = [record["id"] for page in pages for record in page]
+                    expected = [record["id"] for record in
+                                sorted(records, key=history_key(descending), reverse=descending)]
+                    self.assertEqual(served, expected)
+                    self.assertTrue(all(0 < len(page) <= limit for page in pages))
+
+    def test_missing_values_come_last_in_both_directions(self):
+        records = make_records(60)
+        for descending in (False, True):
+            served = [r["id"] for page in read_all_pages(records, 10, descending) for r in page]
+            self.assertEqual(sorted(served[-2:]), ["r007", "r042"])
+
+    def test_inserted_and_removed_items_do_not_shift_later_pages(self):
+        records = make_records(60)
+        key = history_key(True)
+        first, cursor = cursor_page(records, key, "", 10, "history", "timestamp", True)
+        # 첫 페이지를 받은 뒤 가장 새 기록 추가, 이미 받은 기록 삭제
+        changed = [{"id": "new", "timestamp": "2027-01-01T00:00:00"}] + \
+                  [record for record in records if record["id"] != first[0]["id"]]
+        second, _ = cursor_page(changed, key, cursor, 10, "history", "timestamp", True)
+        expected, _ = cursor_page(records, key, cursor, 10, "history", "timestamp", True)
+        self.assertEqual(second, expected)
+        self.assertFalse({r["id"] for r in first} & {r["id"] for r in second})
+
+    def test_rejects_foreign_and_malformed_cursors(self):
+        records = make_records(60)
+        _, cursor = cursor_page(records, history_key(False), "", 5, "history", "timestamp")
+        with self.assertRaises(CursorError):
+            decode_cursor(cursor, "tasks", "timestamp")
+        with self.assertRaises(CursorError):
+            decode_cursor(cursor, "history", "updated_at")
+        for bad in ("not-base64!", "e30", encode_cursor("history", "timestamp", ()).upper()):
+            with self.subTest(cursor=bad), self.assertRaises(CursorError):
+                cursor_page(records, history_key(False), bad, 5, "history", "timestamp")
+
+    def test_parse_page_params(self):
+        self.assertIsNone(parse_page_params({}))
+        self.assertEqual(parse_page_params({"cursor": [""]}), ("", 50))
+        self.assertEqual(parse_page_params({"cursor": ["abc"], "limit": ["20"]}), ("abc", 20))
+        for limit in ("0", "501", "x"):
+            with self.subTest(limit=limit), self.assertRaises(CursorError):
+                parse_page_params({"cursor": [""], "limit": [limit]})
+
+
+class ListRoutePaginationTest(unittest.TestCase):
+    """Every paginated list route accepts ``?cursor=``/``?limit=`` and keeps its side fields."""
+
+    ROUTES = {
+        "/speakers/profiles": ("profiles", "list_profiles", lambda i: {"id": f"p{i}", "name": f"화자{i}"}),
+        "/minutes/templates": ("templates", "list_templates", lambda i: {"id": f"t{i}", "builtin": i == 0}),
+        "/admin/tokens": ("tokens", "list_tokens", lambda i: {"id": f"k{i}", "created_at": f"2026-01-0{i + 1}"}),
+        "/webhooks": ("webhooks", "list_webhooks", lambda i: {"id": f"w{i}", "url": "https://example.com/hook"}),
+        "/series": ("series", "list_series", lambda i: {"id": f"s{i}", "name": f"주간{i}", "record_ids": ["r"]}),
+        "/watch/rules": ("rules", "list_watch_rules", lambda i: {"id": f"rule{i}"}),
+    }
+
+    @classmethod
+    def setUpClass(cls):
+        cls._tmp = tempfile.TemporaryDirectory()
+        os.environ["DB_FOLDER_PATH"] = cls._tmp.name
+        try:
+            from sttEngine import server
+        except ImportError as exc:  # Whisper/Ollama 등 서버 의존성이 없는 환경
+            cls._tmp.cleanup()
+            raise unittest.SkipTest(f"server를 불러올 수 없습니다: {exc}")
+        cls.server = server
+
+    @classmethod
+    def tearDownClass(cls):
+        cls._tmp.cleanup()
+
+    def get(self, path):
+        handler = self.server.UploadHandler.__new__(self.server.UploadHandler)
+        handler.path = path
+        sent = []
+        with mock.patch.object(handler, "_authorize", return_value=True), \
+                mock.patch.object(handler, "_send_json", side_effect=lambda status, payload: sent.append(
+                    (status, payload))):
+            handler.do_GET()
+        self.assertEqual(len(sent), 1, path)
+        return sent[0]
+
+    def test_list_routes_page_with_cursor(self):
+        for route, (field, loader, make) in self.ROUTES.items():
+            items = [make(index) for index in range(5)]
+            with self.subTest(route=route), mock.patch.object(self.server, loader, return_value=items):
+                status, full = self.get(route)
+                self.assertEqual(status, 200)
+                self.assertNotIn("next_cursor", full)
+                served, cursor = [], ""
+                while cursor is not None:
+                    status, page = self.get(f"{route}?cursor={cursor}&limit=2")
+                    self.assertEqual(status, 200)
+                    self.assertLessEqual(len(page[field]), 2)
+                    self.assertEqual(set(page) - {field, "next_cursor", "has_more", "limit"},
+                                     set(full) - {field})
+                    served.extend(page[field])
+                    cursor = page["next_cursor"]
+                self.assertEqual(served, full[field])
+                self.assertEqual(self.get(f"{route}?cursor=bad!&limit=2")[0], 400)
+
+    def test_running_tasks_page_with_cursor(self):
+        tasks = {f"task-{index}": {"start_time": 100.0 + index, "status": "running"} for index in range(3)}
+        with mock.patch.object(self.server, "get_running_tasks", return_value=tasks):
+            first = self.get("/tasks?cursor=&limit=2")[1]
+            second = self.get(f"/tasks?cursor={first['next_cursor']}&limit=2")[1]
+        self.assertEqual([task["task_id"] for task in first["tasks"] + second["tasks"]],
+                         ["task-0", "task-1", "task-2"])
+        self.assertFalse(second["has_more"])
+
+
+if __name__ == "__main__":
+    unittest.main()
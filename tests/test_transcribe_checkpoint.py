@@ -0,0 +1,57 @@
+"""Regression tests for the long-file transcription checkpoint signature."""
+
+import json
+import sys
+import tempfile
+import unittest
+from pathlib import Path
+
+sys.path.insert(0, str(Path(__file__).resolve().parent.parent))
+
+try:
+    from sttEngine.workflow import transcribe
+except ImportError as exc:  # whisper/torch 등 STT 의존성이 없는 환경
+    raise unittest.SkipTest(f"transcribe를 불러올 수 없습니다: {exc}")
+
+PARAMS = {
+    "fp16": False,
+    "verbose": True,
+    "temperature": (0.0, 0.2, 0.4),
+    "beam_size": None,
+    "condition_on_previous_text": False,
+    "language": "ko",
+    "initial_prompt": "회의록",
+}
+
+
+class CheckpointSignatureTest(unittest.TestCase):
+    def setUp(self):
+        self._tmp = tempfile.TemporaryDirectory()
+        base = Path(self._tmp.name)
+        self.source = base / "meeting.m4a"
+        self.source.write_bytes(b"audio")
+        self.checkpoint = base / "meeting.checkpoint.json"
+        signature = transcribe._checkpoint_signature(self.source, PARAMS, "large-v3")
+        self.checkpoint.write_text(json.dumps({"signature": signature, "completed_windows": 2}), encoding="utf-8")
+
+    def tearDown(self):
+        self._tmp.cleanup()
+
+    def load(self, params, model_name="large-v3"):
+        signature = transcribe._checkpoint_signature(self.source, params, model_name)
+        return transcribe.load_checkpoint(self.checkpoint, signature)
+
+    def test_same_settings_resume(self):
+        self.assertIsNotNone(self.load(dict(PARAMS)))
+        self.assertIsNotNone(self.load(dict(PARAMS, verbose=None)))
+
+    def test_changed_model_or_options_restart(self):
+        self.assertIsNone(self.load(dict(PARAMS), model_name="medium"))
+        for change in ({"language": "en"}, {"task": "translate"}, {"temperature": (0.0,)},
+                       {"beam_size": 5}, {"initial_prompt": None}, {"carry_initial_prompt": True}):
+            with self.subTest(change=change):
+                self.assertIsNone(self.load(dict(PARAMS, **change)))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,183 @@
+"""Regression tests for ``/upload_url`` URL validation (no requests to internal hosts)."""
+
+import socket
+import sys
+import tempfile
+import threading
+import unittest
+from http.server import BaseHTTPRequestHandler, HTTPServer
+from pathlib import Path
+from unittest import mock
+
+sys.path.insert(0, str(Path(__file__).resolve().parent.parent))
+
+from sttEngine import url_ingest  # noqa: E402
+from sttEngine.url_ingest import UrlIngestError, download_media, validate_url  # noqa: E402
+
+PUBLIC_URL = "http://93.184.216.34/talk.mp3"
+
+
+def fake_response(status, headers=None):
+    response = mock.MagicMock()
+    response.status_code = status
+    response.headers = headers or {}
+    response.__enter__.return_value = response
+    return response
+
+
+class ValidateUrlTest(unittest.TestCase):
+    def test_rejects_non_http_urls(self):
+        for url in ("ftp://example.com/a.mp3", "file:///etc/passwd", "example.com/a.mp3", "", None, 42):
+            with self.subTest(url=url), self.assertRaises(UrlIngestError):
+                validate_url(url)
+
+    def test_rejects_internal_hosts(self):
+        for url in (
+            "http://127.0.0.1:11434/api/tags",
+            "http://localhost:8080/",
+            "http://10.0.0.5/a.mp3",
+            "http://192.168.1.20/a.mp3",
+            "http://172.16.0.1/a.mp3",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://0.0.0.0/",
+            "http://[::1]/",
+            "http://[fe80::1]/",
+            "http://[::ffff:127.0.0.1]/",
+            "http://240.0.0.1/",
+        ):
+            with self.subTest(url=url), self.assertRaises(UrlIngestError):
+                validate_url(url)
+
+    def test_accepts_public_host(self):
+        self.assertEqual(validate_url(f"  {PUBLIC_URL} "), PUBLIC_URL)
+
+    def test_rejects_hostname_resolving_to_internal_address(self):
+        with mock.patch.object(url_ingest.socket, "getaddrinfo",
+                               return_value=[(2, 1, 6, "", ("93.184.216.34", 80)), (2, 1, 6, "", ("10.1.2.3", 80))]):
+            with self.assertRaises(UrlIngestError):
+                validate_url("http://media.example.com/a.mp3")
+
+    def test_allow_private_setting(self):
+        with mock.patch.object(url_ingest, "URL_UPLOAD_ALLOW_PRIVATE", True):
+            self.assertEqual(validate_url("http://192.168.1.20/a.mp3"), "http://192.168.1.20/a.mp3")
+
+
+class DownloadRedirectTest(unittest.TestCase):
+    def setUp(self):
+        self._tmp = tempfile.TemporaryDirectory()
+        self.dest = Path(self._tmp.name)
+
+    def tearDown(self):
+        self._tmp.cleanup()
+
+    def test_redirect_to_internal_host_is_refused(self):
+        redirect = fake_response(302, {"Location": "http://169.254.169.254/latest/meta-data/"})
+        with mock.patch.object(url_ingest.requests, "get", return_value=redirect) as get:
+            with self.assertRaises(UrlIngestError):
+                download_media(PUBLIC_URL, self.dest, use_ytdlp=False)
+        get.assert_called_once()
+        self.assertIs(get.call_args.kwargs["allow_redirects"], False)
+
+    def test_relative_redirect_is_followed(self):
+        redirect = fake_response(301, {"Location": "/media/talk.mp3"})
+        media = fake_response(200, {"Content-Type": "audio/mpeg", "Content-Length": "4"})
+        media.url = "http://93.184.216.34/media/talk.mp3"
+        media.iter_content.return_value = [b"data"]
+        with mock.patch.object(url_ingest.requests, "get", side_effect=[redirect, media]) as get:
+            saved = download_media(PUBLIC_URL, self.dest, use_ytdlp=False)
+        self.assertEqual(get.call_args_list[1].args[0], "http://93.184.216.34/media/talk.mp3")
+        self.assertEqual(saved.read_bytes(), b"data")
+
+    def test_too_many_redirects(self):
+        loop = fake_response(302, {"Location": PUBLIC_URL})
+        with mock.patch.object(url_ingest.requests, "get", return_value=loop) as get:
+            with self.assertRaises(UrlIngestError):
+                download_media(PUBLIC_URL, self.dest, use_ytdlp=False)
+        self.assertEqual(get.call_count, url_ingest.URL_UPLOAD_MAX_REDIRECTS + 1)
+
+    def test_ytdlp_is_not_started_for_internal_host(self):
+        with mock.patch.object(url_ingest.subprocess, "Popen") as popen:
+            with self.assertRaises(UrlIngestError):
+                download_media("http://127.0.0.1:11434/", self.dest, use_ytdlp=True)
+            with self.assertRaises(UrlIngestError):
+                url_ingest._ytdlp_download("http://10.0.0.5/watch", self.dest, lambda message: None, None)
+        popen.assert_not_called()
+
+    def test_ytdlp_goes_through_the_proxy(self):
+        with mock.patch.object(url_ingest, "ytdlp_command", return_value=["yt-dlp"]), \
+                mock.patch.object(url_ingest.subprocess, "Popen") as popen:
+            popen.return_value.stdout = []
+            popen.return_value.returncode = 1
+            with self.assertRaises(UrlIngestError):
+                url_ingest._ytdlp_download(PUBLIC_URL, self.dest, lambda message: None, None)
+        command = popen.call_args.args[0]
+        self.assertEqual(command[command.index("--proxy") + 1], url_ingest.egress_proxy_url())
+        self.assertEqual(command[-1], PUBLIC_URL)
+
+    def test_requests_go_through_the_proxy(self):
+        media = fake_response(200, {"Content-Type": "audio/mpeg"})
+        media.url = PUBLIC_URL
+        media.iter_content.return_value = [b"data"]
+        with mock.patch.object(url_ingest.requests, "get", return_value=media) as get:
+            download_media(PUBLIC_URL, self.dest, use_ytdlp=False)
+        proxy = url_ingest.egress_proxy_url()
+        self.assertEqual(get.call_args.kwargs["proxies"], {"http": proxy, "https": proxy})
+
+
+class EgressProxyTest(unittest.TestCase):
+    def send(self, request):
+        port = int(url_ingest.egress_proxy_url().rsplit(":", 1)[1])
+        with socket.socket() as client:  # create_connection은 테스트에서 가로챌 수 있어 직접 연결
+            client.settimeout(5)
+            client.connect(("127.0.0.1", port))
+            client.sendall(request.encode("latin-1"))
+            chunks = []
+            while True:
+                chunk = client.recv(65536)
+                if not chunk:
+                    return b"".join(chunks).decode("latin-1")
+                chunks.append(chunk)
+
+    def test_refuses_internal_addresses(self):
+        for request in ("CONNECT 127.0.0.1:11434 HTTP/1.1\r\nHost: 127.0.0.1:11434\r\n\r\n",
+                        "CONNECT [::1]:443 HTTP/1.1\r\n\r\n",
+                        "GET http://169.254.169.254/latest/meta-data/ HTTP/1.1\r\nHost: 169.254.169.254\r\n\r\n"):
+            with self.subTest(request=request):
+                self.assertTrue(self.send(request).startswith("HTTP/1.1 403"))
+
+    def test_connects_to_the_checked_address(self):
+        # 검사 뒤 DNS 답이 바뀌어도 검사한 주소로만 연결
+        answers = [[(2, 1, 6, "", ("93.184.216.34", 443))], [(2, 1, 6, "", ("127.0.0.1", 443))]]
+        with mock.patch.object(url_ingest.socket, "getaddrinfo", side_effect=answers) as getaddrinfo, \
+                mock.patch.object(url_ingest.socket, "create_connection", side_effect=OSError) as connect:
+            reply = self.send("CONNECT media.example.com:443 HTTP/1.1\r\n\r\n")
+        self.assertTrue(reply.startswith("HTTP/1.1 502"))
+        getaddrinfo.assert_called_once()
+        self.assertEqual(connect.call_args.args[0], ("93.184.216.34", 443))
+
+    def test_forwards_public_http_requests(self):
+        class Handler(BaseHTTPRequestHandler):
+            def do_GET(self):
+                body = f"{self.path}|{self.headers.get('Connection')}".encode()
+                self.send_response(200)
+                self.send_header("Content-Length", str(len(body)))
+                self.end_headers()
+                self.wfile.write(body)
+
+            def log_message(self, *args):
+                pass
+
+        origin = HTTPServer(("127.0.0.1", 0), Handler)
+        threading.Thread(target=origin.serve_forever, daemon=True).start()
+        self.addCleanup(origin.server_close)
+        self.addCleanup(origin.shutdown)
+        port = origin.server_address[1]
+        with mock.patch.object(url_ingest, "_is_internal_address", return_value=False):
+            reply = self.send(f"GET http://127.0.0.1:{port}/talk.mp3?t=1 HTTP/1.1\r\nHost: 127.0.0.1\r\n"
+                              "Proxy-Connection: keep-alive\r\n\r\n")
+        self.assertTrue(reply.startswith("HTTP/1.0 200"))
+        self.assertTrue(reply.endswith("/talk.mp3?t=1|close"))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,50 @@
+"""Regression tests for Whisper model eviction while a job holds the pool."""
+
+import sys
+import unittest
+from pathlib import Path
+from unittest import mock
+
+sys.path.insert(0, str(Path(__file__).resolve().parent.parent))
+
+try:
+    from sttEngine.workflow import transcribe
+except ImportError as exc:  # whisper/torch 등 STT 의존성이 없는 환경
+    raise unittest.SkipTest(f"transcribe를 불러올 수 없습니다: {exc}")
+
+
+class EngineManagerEvictionTest(unittest.TestCase):
+    def setUp(self):
+        patches = [
+            mock.patch.object(transcribe.whisper, "load_model", side_effect=lambda name, device: object(),
+                              create=True),
+            mock.patch.object(transcribe, "ensure_model_download_space"),
+        ]
+        for patch in patches:
+            patch.start()
+            self.addCleanup(patch.stop)
+        self.manager = transcribe.WhisperEngineManager(pool_size=1, max_models=1)
+
+    def test_reserved_pool_is_not_evicted_before_the_lease(self):
+        pool = self.manager.get_pool("small", "cpu", reserve=True)
+        # 예약한 작업이 상태를 빌리기 전에 다른 모델이 로드됨
+        self.manager.get_pool("medium", "cpu")
+        self.assertTrue(self.manager.is_loaded("small", "cpu"))
+        with pool.lease():
+            pass
+        pool.release()
+        self.manager.get_pool("large", "cpu")
+        self.assertFalse(self.manager.is_loaded("small", "cpu"))
+
+    def test_use_pool_keeps_the_model_for_the_block(self):
+        with self.manager.use_pool("small", "cpu") as pool:
+            self.manager.get_pool("medium", "cpu")
+            self.assertTrue(self.manager.is_loaded("small", "cpu"))
+            self.assertTrue(pool.busy())
+        self.assertFalse(pool.busy())
+        self.manager.get_pool("large", "cpu")
+        self.assertFalse(self.manager.is_loaded("small", "cpu"))
+
+
+if __name__ == "__main__":
+    unittest.main()